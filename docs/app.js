@@ -258,6 +258,65 @@ const exampleEducation = {
             <h3>Network Architecture</h3>
             <p><span class="network-type">Feed-Forward Neural Network</span> [9, 6, 4] - 9 inputs (one per pixel), 4 outputs (one per pattern).</p>
         `
+    },
+    sine: {
+        title: "Sine Wave - Regression",
+        content: `
+            <h3>What is Regression?</h3>
+            <p>Every other example sorts inputs into classes. Here the network learns a continuous function: y = sin(x) over one full period.</p>
+
+            <h3>The Data</h3>
+            <p>32 evenly spaced points with x from -&pi; to &pi;, scaled to [-1, 1]. The sigmoid output lies between 0 and 1, so the targets are scaled the same way: 0.5 + 0.5 &middot; sin(x).</p>
+
+            <div class="highlight">
+                <strong>Universal Approximation:</strong> A single hidden layer of sigmoids can approximate any smooth curve. Each hidden neuron contributes one "step", and the output neuron blends the steps into the wave.
+            </div>
+
+            <h3>Network Architecture</h3>
+            <p><span class="network-type">Feed-Forward Neural Network</span> [1, 8, 1] - one input, eight hidden neurons, one continuous output. Accuracy is not meaningful here; watch the loss instead.</p>
+        `
+    },
+    spirals: {
+        title: "Two Spirals - Deep Nonlinearity",
+        content: `
+            <h3>The Data</h3>
+            <p>Two interleaved spiral arms winding out from the center, 50 points each. Points of the two classes sit right next to each other on neighbouring turns.</p>
+
+            <div class="highlight">
+                <strong>Why Two Hidden Layers?</strong> The boundary has to wind between the arms. The first hidden layer cuts the plane into pieces and the second combines them into the spiral-shaped regions.
+            </div>
+
+            <h3>Network Architecture</h3>
+            <p><span class="network-type">Feed-Forward Neural Network</span> [2, 16, 16, 1] - two inputs (x, y), two hidden layers, one output (which arm).</p>
+        `
+    },
+    moons: {
+        title: "Two Moons - Curved Boundary",
+        content: `
+            <h3>The Data</h3>
+            <p>Two interleaving half circles with a little random noise, 50 points each. The tip of each moon reaches into the other.</p>
+
+            <div class="highlight">
+                <strong>Watch the Decision Boundary:</strong> No straight line separates the moons, so the boundary the network draws has to bend around both tips.
+            </div>
+
+            <h3>Network Architecture</h3>
+            <p><span class="network-type">Feed-Forward Neural Network</span> [2, 8, 1] - two inputs (x, y), one output (which moon).</p>
+        `
+    },
+    circles: {
+        title: "Concentric Circles - Closed Boundary",
+        content: `
+            <h3>The Data</h3>
+            <p>A small circle of points inside a larger one, 50 points each, with a little random noise.</p>
+
+            <div class="highlight">
+                <strong>Enclosing a Region:</strong> One class surrounds the other, so the boundary must be a closed loop. Each hidden neuron adds one straight edge, and together they approximate the ring.
+            </div>
+
+            <h3>Network Architecture</h3>
+            <p><span class="network-type">Feed-Forward Neural Network</span> [2, 8, 1] - two inputs (x, y), one output (inner or outer circle).</p>
+        `
     }
 };
 
@@ -0,0 +1,41 @@
+// Naive triple loop vs the cache-blocked kernel behind `dot_multiply`
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use matrix::matrix::Matrix;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+fn naive(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut data = vec![0.0; a.rows * b.cols];
+    for i in 0..a.rows {
+        for j in 0..b.cols {
+            let mut sum = 0.0;
+            for k in 0..a.cols {
+                sum += a.data[i * a.cols + k] * b.data[k * b.cols + j];
+            }
+            data[i * b.cols + j] = sum;
+        }
+    }
+    Matrix { rows: a.rows, cols: b.cols, data }
+}
+
+fn bench_matmul(c: &mut Criterion) {
+    let mut group = c.benchmark_group("matmul");
+    group.sample_size(10);
+
+    for size in [128, 512, 1024] {
+        let mut rng = StdRng::seed_from_u64(42);
+        let a = Matrix::random_uniform(size, size, 1.0, &mut rng);
+        let b = Matrix::random_uniform(size, size, 1.0, &mut rng);
+
+        group.bench_with_input(BenchmarkId::new("naive", size), &size, |bench, _| {
+            bench.iter(|| naive(black_box(&a), black_box(&b)))
+        });
+        group.bench_with_input(BenchmarkId::new("blocked", size), &size, |bench, _| {
+            bench.iter(|| black_box(&a).dot_multiply(black_box(&b)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_matmul);
+criterion_main!(benches);
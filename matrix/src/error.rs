@@ -0,0 +1,34 @@
+use std::fmt;
+
+/// Errors from the checked `Matrix` constructors and shape checks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatrixError {
+    /// `data.len()` does not equal `rows * cols`
+    DataLength { rows: usize, cols: usize, len: usize },
+
+    /// A row passed to `Matrix::from_rows` has a different length than the first
+    RaggedRows { row: usize, expected: usize, found: usize },
+
+    /// A matrix does not have the shape an operation requires
+    DimensionMismatch { expected: (usize, usize), found: (usize, usize) },
+}
+
+impl fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            MatrixError::DataLength { rows, cols, len } => {
+                write!(f, "Matrix data has {} values but shape is {}x{}", len, rows, cols)
+            }
+            MatrixError::RaggedRows { row, expected, found } => {
+                write!(f, "Row {} has {} values, expected {}", row, found, expected)
+            }
+            MatrixError::DimensionMismatch { expected, found } => write!(
+                f,
+                "Expected a {}x{} matrix, got {}x{}",
+                expected.0, expected.1, found.0, found.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MatrixError {}
@@ -0,0 +1,267 @@
+/// Experimental wgpu compute backend for matrix products
+///
+/// With the `gpu` feature enabled, `Matrix::dot_multiply` offloads products of
+/// at least `GPU_THRESHOLD` multiply-adds to a compute shader. The device is
+/// opened lazily on first use; if no adapter is available (headless machines,
+/// CI, missing drivers) or the matrices exceed the device limits, the product
+/// silently falls back to the CPU kernel.
+///
+/// The shader computes in `f32`, so GPU results agree with the CPU path to
+/// roughly single precision (about 1e-6 relative error) rather than exactly.
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use wgpu::util::DeviceExt;
+
+use crate::matrix::Matrix;
+
+/// Minimum number of multiply-adds before a product is sent to the GPU
+///
+/// Below this the cost of uploading and reading back buffers outweighs the
+/// speedup, so every network in the bundled examples stays on the CPU.
+pub const GPU_THRESHOLD: usize = 1 << 24;
+
+/// Edge length of the square workgroup tile, must match the shader
+const TILE: u32 = 16;
+
+const SHADER: &str = r#"
+struct Dims {
+    m: u32,
+    k: u32,
+    n: u32,
+    _pad: u32,
+};
+
+@group(0) @binding(0) var<uniform> dims: Dims;
+@group(0) @binding(1) var<storage, read> lhs: array<f32>;
+@group(0) @binding(2) var<storage, read> rhs: array<f32>;
+@group(0) @binding(3) var<storage, read_write> out: array<f32>;
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let row = id.y;
+    let col = id.x;
+    if (row >= dims.m || col >= dims.n) {
+        return;
+    }
+
+    var sum = 0.0;
+    for (var p = 0u; p < dims.k; p = p + 1u) {
+        sum = sum + lhs[row * dims.k + p] * rhs[p * dims.n + col];
+    }
+    out[row * dims.n + col] = sum;
+}
+"#;
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+/// Number of live `CpuOnlyGuard`s
+static CPU_ONLY: AtomicUsize = AtomicUsize::new(0);
+static CONTEXT: OnceLock<Option<GpuContext>> = OnceLock::new();
+
+struct GpuContext {
+    adapter_name: String,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+}
+
+/// Enable or disable GPU offloading at runtime (enabled by default)
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Keeps offloading disabled until dropped, see `cpu_only`
+#[must_use = "offloading is re-enabled as soon as the guard is dropped"]
+#[derive(Debug)]
+pub struct CpuOnlyGuard(());
+
+impl Drop for CpuOnlyGuard {
+    fn drop(&mut self) {
+        CPU_ONLY.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Disable GPU offloading while the returned guard is alive
+///
+/// Unlike `set_enabled(false)` this does not outlive the caller: offloading
+/// resumes once every guard has been dropped, so overlapping users (e.g.
+/// concurrent training runs in one process) do not re-enable it for each other.
+pub fn cpu_only() -> CpuOnlyGuard {
+    CPU_ONLY.fetch_add(1, Ordering::Relaxed);
+    CpuOnlyGuard(())
+}
+
+/// Whether offloading is neither switched off nor held off by a guard
+fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed) && CPU_ONLY.load(Ordering::Relaxed) == 0
+}
+
+/// Whether a GPU adapter was found and offloading is enabled
+///
+/// The first call opens the device, which can take a moment.
+pub fn is_available() -> bool {
+    enabled() && context().is_some()
+}
+
+/// Name of the GPU adapter in use, if any
+pub fn adapter_name() -> Option<String> {
+    context().map(|ctx| ctx.adapter_name.clone())
+}
+
+/// Multiply `lhs * rhs` on the GPU
+///
+/// Returns `None` when offloading is disabled, no adapter is available or the
+/// matrices are too large for the device, so the caller can fall back to the
+/// CPU. Dimensions are assumed to have been checked already.
+pub fn matmul(lhs: &Matrix, rhs: &Matrix) -> Option<Matrix> {
+    if !enabled() {
+        return None;
+    }
+    let ctx = context()?;
+    let (m, k, n) = (lhs.rows, lhs.cols, rhs.cols);
+    if m == 0 || k == 0 || n == 0 {
+        return None;
+    }
+
+    let limits = ctx.device.limits();
+    let max_binding = limits.max_storage_buffer_binding_size as usize;
+    let largest = (m * k).max(k * n).max(m * n) * size_of::<f32>();
+    let groups_x = (n as u32).div_ceil(TILE);
+    let groups_y = (m as u32).div_ceil(TILE);
+    if largest > max_binding
+        || groups_x > limits.max_compute_workgroups_per_dimension
+        || groups_y > limits.max_compute_workgroups_per_dimension
+    {
+        return None;
+    }
+
+    let to_f32 = |data: &[f64]| data.iter().map(|&v| v as f32).collect::<Vec<f32>>();
+    let dims = [m as u32, k as u32, n as u32, 0];
+    let out_size = (m * n * size_of::<f32>()) as u64;
+
+    let device = &ctx.device;
+    let dims_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("matmul dims"),
+        contents: bytemuck::cast_slice(&dims),
+        usage: wgpu::BufferUsages::UNIFORM,
+    });
+    let lhs_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("matmul lhs"),
+        contents: bytemuck::cast_slice(&to_f32(&lhs.data)),
+        usage: wgpu::BufferUsages::STORAGE,
+    });
+    let rhs_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("matmul rhs"),
+        contents: bytemuck::cast_slice(&to_f32(&rhs.data)),
+        usage: wgpu::BufferUsages::STORAGE,
+    });
+    let out_buf = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("matmul out"),
+        size: out_size,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+    let read_buf = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("matmul readback"),
+        size: out_size,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("matmul bindings"),
+        layout: &ctx.pipeline.get_bind_group_layout(0),
+        entries: &[
+            wgpu::BindGroupEntry { binding: 0, resource: dims_buf.as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 1, resource: lhs_buf.as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 2, resource: rhs_buf.as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 3, resource: out_buf.as_entire_binding() },
+        ],
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    {
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("matmul"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&ctx.pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch_workgroups(groups_x, groups_y, 1);
+    }
+    encoder.copy_buffer_to_buffer(&out_buf, 0, &read_buf, 0, out_size);
+    ctx.queue.submit(Some(encoder.finish()));
+
+    let slice = read_buf.slice(..);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    device.poll(wgpu::PollType::Wait).ok()?;
+    receiver.recv().ok()?.ok()?;
+
+    let data = bytemuck::cast_slice::<u8, f32>(&slice.get_mapped_range())
+        .iter()
+        .map(|&v| f64::from(v))
+        .collect();
+    read_buf.unmap();
+
+    Some(Matrix { rows: m, cols: n, data })
+}
+
+fn context() -> Option<&'static GpuContext> {
+    CONTEXT.get_or_init(|| pollster::block_on(GpuContext::new())).as_ref()
+}
+
+impl GpuContext {
+    async fn new() -> Option<GpuContext> {
+        // GL has limited compute support, so only the native APIs are tried
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::PRIMARY,
+            ..Default::default()
+        });
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                force_fallback_adapter: false,
+                compatible_surface: None,
+            })
+            .await
+            .ok()?;
+
+        // Software rasterizers are slower than the CPU kernel
+        let info = adapter.get_info();
+        if info.device_type == wgpu::DeviceType::Cpu {
+            return None;
+        }
+
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor {
+                label: Some("matrix"),
+                required_limits: adapter.limits(),
+                ..Default::default()
+            })
+            .await
+            .ok()?;
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("matmul"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("matmul"),
+            layout: None,
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        Some(GpuContext {
+            adapter_name: info.name,
+            device,
+            queue,
+            pipeline,
+        })
+    }
+}
@@ -1,3 +1,12 @@
 pub mod macros;
 
-pub mod matrix;
\ No newline at end of file
+pub mod error;
+pub mod matrix;
+pub mod parallel;
+pub mod random;
+pub mod tensor;
+
+#[cfg(feature = "gpu")]
+pub mod gpu;
+#[cfg(feature = "ndarray")]
+pub mod ndarray;
\ No newline at end of file
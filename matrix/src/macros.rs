@@ -27,6 +27,41 @@ macro_rules! matrix {
     };
 }
 
+/// Assert that two matrices have the same shape and approximately equal elements
+///
+/// Uses `Matrix::approx_eq` with the given tolerance, or `DEFAULT_TOLERANCE`
+/// when omitted. On failure the message includes the largest difference and
+/// both matrices.
+///
+/// ```
+/// use matrix::{assert_matrix_eq, matrix::Matrix};
+///
+/// let a = Matrix::new(1, 2, vec![1.0, 2.0]).unwrap();
+/// let b = Matrix::new(1, 2, vec![1.0, 2.0 + 1e-12]).unwrap();
+/// assert_matrix_eq!(a, b);
+/// assert_matrix_eq!(a, Matrix::new(1, 2, vec![1.1, 2.0]).unwrap(), 0.2);
+/// ```
+#[macro_export]
+macro_rules! assert_matrix_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_matrix_eq!($left, $right, $crate::matrix::DEFAULT_TOLERANCE)
+    };
+    ($left:expr, $right:expr, $tolerance:expr $(,)?) => {{
+        let (left, right, tolerance) = (&$left, &$right, $tolerance);
+        match left.max_abs_diff(right) {
+            None => panic!(
+                "assertion failed: matrix shapes differ ({}x{} vs {}x{})",
+                left.rows, left.cols, right.rows, right.cols
+            ),
+            Some(diff) if diff.is_nan() || diff > tolerance => panic!(
+                "assertion failed: matrices differ by {} (tolerance {})\n  left: {:?}\n right: {:?}",
+                diff, tolerance, left, right
+            ),
+            Some(_) => {}
+        }
+    }};
+}
+
 #[cfg(test)]
 mod tests {
     use crate::matrix::Matrix;
@@ -52,4 +87,32 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_assert_matrix_eq_within_tolerance() {
+        let a = matrix![1.0, 2.0; 3.0, 4.0];
+        let b = matrix![1.0, 2.0 + 1e-12; 3.0, 4.0];
+        assert_matrix_eq!(a, b);
+        assert_matrix_eq!(a, matrix![1.05, 2.0; 3.0, 3.95], 0.1);
+    }
+
+    #[test]
+    #[should_panic(expected = "matrices differ by")]
+    fn test_assert_matrix_eq_reports_difference() {
+        assert_matrix_eq!(matrix![1.0, 2.0], matrix![1.0, 2.5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "shapes differ (1x2 vs 2x1)")]
+    fn test_assert_matrix_eq_shape_mismatch() {
+        assert_matrix_eq!(matrix![1.0, 2.0], matrix![1.0; 2.0]);
+    }
+
+    #[test]
+    fn test_approx_eq_rejects_nan() {
+        let a = matrix![1.0, f64::NAN];
+        assert!(!a.approx_eq(&a, 1.0));
+        assert!(matrix![1.0].approx_eq(&matrix![1.5], 0.5));
+        assert_eq!(matrix![1.0].max_abs_diff(&matrix![1.0, 2.0]), None);
+    }
 }
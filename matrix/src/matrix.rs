@@ -4,7 +4,12 @@ use rand::SeedableRng;
 use rand::rngs::StdRng;
 use serde::{Serialize, Deserialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::error::MatrixError;
+use crate::parallel::{MaybeSync, for_each_row};
+use crate::random::Init;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedMatrix")]
 pub struct Matrix {
     pub rows: usize,
     pub cols: usize,
@@ -23,17 +28,88 @@ impl Matrix {
 			panic!("Attempted to multiply by matrix of incorrect dimensions");
 		}
 
-        let mut result_data = vec![0.0; self.cols * self.rows];
-        for (i, &val) in self.data.iter().enumerate() {
-            result_data[i] = val * other.data[i]
+        self.zip_with(other, |a, b| a * b)
+    }
+    /// Element-wise quotient `self / other`
+    ///
+    /// Division by zero follows IEEE 754 (infinity or NaN); add an epsilon to
+    /// the denominator when that matters, as Adam and RMSprop do.
+    pub fn elementwise_divide(&self, other: &Matrix) -> Matrix {
+        if self.rows != other.rows || self.cols != other.cols {
+            panic!("Attempted to divide by matrix of incorrect dimensions");
         }
 
+        self.zip_with(other, |a, b| a / b)
+    }
+
+    /// Outer product of two vectors: `result[i][j] = a[i] * b[j]`
+    ///
+    /// Both arguments are treated as flat vectors regardless of their shape,
+    /// so row and column vectors can be mixed.
+    pub fn outer(a: &Matrix, b: &Matrix) -> Matrix {
+        let cols = b.data.len();
+        let mut data = vec![0.0; a.data.len() * cols];
+        for_each_row(&mut data, cols, a.data.len() * cols, |i, row| {
+            for (out, value) in row.iter_mut().zip(&b.data) {
+                *out = a.data[i] * value;
+            }
+        });
+
         Matrix {
-            rows: self.rows,
-            cols: self.cols,
-            data: result_data,
+            rows: a.data.len(),
+            cols,
+            data,
         }
     }
+
+    /// Concatenate matrices side by side
+    ///
+    /// Stacking column vectors this way builds a `(features x batch)` matrix
+    /// with one sample per column. An empty slice gives a `0x0` matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrices do not all have the same number of rows
+    pub fn hstack(matrices: &[Matrix]) -> Matrix {
+        let rows = matrices.first().map_or(0, |m| m.rows);
+        assert!(
+            matrices.iter().all(|m| m.rows == rows),
+            "Cannot hstack matrices with different numbers of rows"
+        );
+
+        let cols = matrices.iter().map(|m| m.cols).sum();
+        let mut data = Vec::with_capacity(rows * cols);
+        for i in 0..rows {
+            for m in matrices {
+                data.extend_from_slice(m.row(i));
+            }
+        }
+
+        Matrix { rows, cols, data }
+    }
+
+    /// Concatenate matrices top to bottom
+    ///
+    /// Stacking row vectors this way builds a `(batch x features)` matrix with
+    /// one sample per row. An empty slice gives a `0x0` matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrices do not all have the same number of columns
+    pub fn vstack(matrices: &[Matrix]) -> Matrix {
+        let cols = matrices.first().map_or(0, |m| m.cols);
+        assert!(
+            matrices.iter().all(|m| m.cols == cols),
+            "Cannot vstack matrices with different numbers of columns"
+        );
+
+        Matrix {
+            rows: matrices.iter().map(|m| m.rows).sum(),
+            cols,
+            data: matrices.iter().flat_map(|m| m.data.iter().copied()).collect(),
+        }
+    }
+
     pub fn random(rows: usize, cols: usize) -> Matrix {
         let mut buffer = Vec::<f64>::with_capacity(rows * cols);
 
@@ -59,16 +135,79 @@ impl Matrix {
         Matrix{rows,cols,data:buffer}
     }
 
+    /// Create a random matrix drawing each element from `init`
+    ///
+    /// Pass an RNG from `create_rng` for reproducible results.
+    pub fn random_with(rows: usize, cols: usize, init: Init, rng: &mut impl Rng) -> Matrix {
+        let data = (0..rows * cols).map(|_| init.sample(rng)).collect();
+        Matrix { rows, cols, data }
+    }
+
+    /// Create a random matrix uniform in `[-limit, limit)`
+    pub fn random_uniform(rows: usize, cols: usize, limit: f64, rng: &mut impl Rng) -> Matrix {
+        Self::random_with(rows, cols, Init::Uniform { limit }, rng)
+    }
+
+    /// Create a random matrix with normally distributed elements
+    pub fn random_normal(rows: usize, cols: usize, mean: f64, std: f64, rng: &mut impl Rng) -> Matrix {
+        Self::random_with(rows, cols, Init::Normal { mean, std }, rng)
+    }
+
+    /// Create a random matrix from a normal distribution truncated at two standard deviations
+    pub fn random_truncated_normal(rows: usize, cols: usize, mean: f64, std: f64, rng: &mut impl Rng) -> Matrix {
+        Self::random_with(rows, cols, Init::TruncatedNormal { mean, std }, rng)
+    }
+
+    /// Encode as compact binary (bincode): rows, cols, then the row-major data
+    ///
+    /// Each element takes exactly 8 bytes, compared to roughly 20 bytes per
+    /// float as JSON text.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bincode::serialize(self).expect("Matrix serialization cannot fail")
+    }
+
+    /// Decode a matrix written by `to_bytes`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are truncated or malformed, or the data
+    /// length does not match the encoded shape
+    pub fn from_bytes(bytes: &[u8]) -> bincode::Result<Matrix> {
+        bincode::deserialize(bytes)
+    }
+
     /// Create a seeded RNG from a u64 seed
     pub fn create_rng(seed: u64) -> StdRng {
         StdRng::seed_from_u64(seed)
     }
 
-    pub fn new(rows: usize, cols: usize, data: Vec<f64>) -> Matrix {
+    /// Create a matrix from row-major data
+    ///
+    /// # Errors
+    ///
+    /// Returns `MatrixError::DataLength` if `data.len() != rows * cols`
+    pub fn new(rows: usize, cols: usize, data: Vec<f64>) -> Result<Matrix, MatrixError> {
+        if data.len() != rows * cols {
+            return Err(MatrixError::DataLength { rows, cols, len: data.len() });
+        }
+        Ok(Matrix { rows, cols, data })
+    }
 
-        assert!(data.len()-1 != rows * cols, "Invalid Size");
-       Matrix { rows, cols, data }  
-        
+    /// Create a matrix with one row per inner vector
+    ///
+    /// # Errors
+    ///
+    /// Returns `MatrixError::RaggedRows` if the rows differ in length
+    pub fn from_rows(rows: &[Vec<f64>]) -> Result<Matrix, MatrixError> {
+        let cols = rows.first().map_or(0, Vec::len);
+        let mut data = Vec::with_capacity(rows.len() * cols);
+        for (i, row) in rows.iter().enumerate() {
+            if row.len() != cols {
+                return Err(MatrixError::RaggedRows { row: i, expected: cols, found: row.len() });
+            }
+            data.extend_from_slice(row);
+        }
+        Ok(Matrix { rows: rows.len(), cols, data })
     }
    
     pub fn zeros(rows:usize, cols:usize) -> Matrix {
@@ -76,28 +215,41 @@ impl Matrix {
         Matrix { rows, cols, data: vec![0.0; cols * rows] }
     }
 
+    /// Element-wise sum
+    ///
+    /// `other` may also be a column vector with the same number of rows, in
+    /// which case it is broadcast across every column of `self`. This applies
+    /// a bias vector to a batch of activations stored as (neurons x batch_size).
     pub fn add(&self, other: &Matrix) -> Matrix {
+        if self.rows == other.rows && other.cols == 1 && self.cols != 1 {
+            return self.add_column(other);
+        }
+
         if self.rows != other.rows || self.cols != other.cols {
 			panic!("Attempted to add matrix of incorrect dimensions");
 		}
 
-      let mut buffer = Vec::<f64>::with_capacity(self.rows * self.cols);
-
-      for i in 0..self.data.len() { 
-
-              let result = self.data[i] + other.data[i];
+      self.zip_with(other, |a, b| a + b)
 
-              buffer.push(result);
-
-      }
+  }
 
-      Matrix { 
-          rows:self.rows,
-          cols: self.cols,
-          data: buffer
-      }
+    /// Add a column vector to every column
+    fn add_column(&self, column: &Matrix) -> Matrix {
+        let mut data = vec![0.0; self.data.len()];
+        let cols = self.cols;
+        for_each_row(&mut data, cols, self.data.len(), |i, row| {
+            let bias = column.data[i];
+            for (out, value) in row.iter_mut().zip(&self.data[i * cols..(i + 1) * cols]) {
+                *out = value + bias;
+            }
+        });
 
-  }
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data,
+        }
+    }
   
     pub fn subtract(&self, other: &Matrix) -> Matrix {
 
@@ -106,60 +258,117 @@ impl Matrix {
           "Cannot subtract matrices with different dimensions"
       );
 
-      let mut buffer = Vec::<f64>::with_capacity(self.rows * self.cols);
-
-      for i in 0..self.data.len() { 
-
-              let result = self.data[i] - other.data[i];
-
-              buffer.push(result);
-
-      }
-
-      Matrix { 
-          rows:self.rows,
-          cols: self.cols,
-          data: buffer
-      }
+      self.zip_with(other, |a, b| a - b)
 
   }
     
     
     pub fn dot_multiply(&self, other: &Matrix) -> Matrix {
-       
+        let mut result = Matrix::zeros(self.rows, other.cols);
+        self.dot_multiply_into(other, &mut result);
+        result
+    }
 
+    /// Compute `self * other` into a preallocated `out`
+    ///
+    /// Same result as `dot_multiply` without allocating, for hot loops that
+    /// reuse their buffers (see the network crate's `Workspace`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the inner dimensions differ or `out` is not `self.rows x other.cols`
+    pub fn dot_multiply_into(&self, other: &Matrix, out: &mut Matrix) {
         if self.cols != other.rows {
-			panic!("Attempted to multiply by matrix of incorrect dimensions");
-		}
+            panic!("Attempted to multiply by matrix of incorrect dimensions");
+        }
+        check_output_shape(out, self.rows, other.cols);
+
+        let work = self.rows * self.cols * other.cols;
 
+        #[cfg(feature = "gpu")]
+        if work >= crate::gpu::GPU_THRESHOLD
+            && let Some(result) = crate::gpu::matmul(self, other)
+        {
+            out.data.copy_from_slice(&result.data);
+            return;
+        }
+
+        // Output row blocks only read their own rows of self, so they are independent
+        let block_len = MATMUL_BLOCK * other.cols;
+        for_each_row(&mut out.data, block_len, work, |b, out| {
+            matmul_rows(&self.data, &other.data, out, b * MATMUL_BLOCK, self.cols, other.cols);
+        });
+    }
 
-        let mut result_data = vec![0.0; self.rows * other.cols];
+    /// Compute `self^T * other` without materializing the transpose
+    ///
+    /// Equivalent to `self.transpose().dot_multiply(other)`.
+    pub fn dot_multiply_transposed_lhs(&self, other: &Matrix) -> Matrix {
+        let mut result = Matrix::zeros(self.cols, other.cols);
+        self.dot_multiply_transposed_lhs_into(other, &mut result);
+        result
+    }
 
-        for i in 0..self.rows {
-            for j in 0..other.cols {
+    /// Compute `self^T * other` into a preallocated `out`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row counts differ or `out` is not `self.cols x other.cols`
+    pub fn dot_multiply_transposed_lhs_into(&self, other: &Matrix, out: &mut Matrix) {
+        if self.rows != other.rows {
+            panic!("Attempted to multiply by matrix of incorrect dimensions");
+        }
+        check_output_shape(out, self.cols, other.cols);
+
+        // Row i of the result is column i of self combined with other
+        let work = self.cols * self.rows * other.cols;
+        for_each_row(&mut out.data, other.cols, work, |i, row| {
+            for (j, out) in row.iter_mut().enumerate() {
                 let mut sum = 0.0;
-                for k in 0..self.cols {
-                    sum += self.data[i * self.cols + k] * other.data[k * other.cols + j];
+                for k in 0..self.rows {
+                    sum += self.data[k * self.cols + i] * other.data[k * other.cols + j];
                 }
-                result_data[i * other.cols + j] = sum;
+                *out = sum;
             }
-        }
+        });
+    }
 
-        Matrix {
-            rows: self.rows,
-            cols: other.cols,
-            data: result_data,
+    /// Compute `self * other^T` without materializing the transpose
+    ///
+    /// Equivalent to `self.dot_multiply(&other.transpose())`. Both operands are
+    /// read along their rows, which keeps the inner loop contiguous.
+    pub fn dot_multiply_transposed_rhs(&self, other: &Matrix) -> Matrix {
+        let mut result = Matrix::zeros(self.rows, other.rows);
+        self.dot_multiply_transposed_rhs_into(other, &mut result);
+        result
+    }
+
+    /// Compute `self * other^T` into a preallocated `out`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the column counts differ or `out` is not `self.rows x other.rows`
+    pub fn dot_multiply_transposed_rhs_into(&self, other: &Matrix, out: &mut Matrix) {
+        if self.cols != other.cols {
+            panic!("Attempted to multiply by matrix of incorrect dimensions");
         }
-    
+        check_output_shape(out, self.rows, other.rows);
+
+        let work = self.rows * self.cols * other.rows;
+        for_each_row(&mut out.data, other.rows, work, |i, row| {
+            let lhs = &self.data[i * self.cols..(i + 1) * self.cols];
+            for (j, out) in row.iter_mut().enumerate() {
+                let rhs = &other.data[j * other.cols..(j + 1) * other.cols];
+                *out = lhs.iter().zip(rhs).map(|(a, b)| a * b).sum();
+            }
+        });
     }
 
     pub fn transpose(&self) -> Matrix {
         let mut buffer = vec![0.0; self.cols * self.rows];
 
-        for i in 0..self.rows {
-            for j in 0..self.cols {
-                buffer[j * self.rows + i] = self.data[i * self.cols + j];
-            }
+        for (i, j, value) in self.iter_indexed() {
+            buffer[j * self.rows + i] = value;
         }
 
         Matrix {
@@ -169,23 +378,297 @@ impl Matrix {
         }
     }
 
+    /// Apply `func` to every element
+    ///
+    /// With the `parallel` feature, `func` must be `Sync` so large matrices
+    /// can be mapped across threads.
     pub fn map<F>(&mut self, func: F) -> Matrix
     where
-        F: Fn(&f64) -> f64,
+        F: Fn(&f64) -> f64 + MaybeSync,
     {
-        let mut result = Matrix {
+        let mut data = vec![0.0; self.data.len()];
+        let cols = self.cols;
+        for_each_row(&mut data, cols, self.data.len(), |i, row| {
+            let source = &self.data[i * cols..(i + 1) * cols];
+            for (out, value) in row.iter_mut().zip(source) {
+                *out = func(value);
+            }
+        });
+
+        Matrix {
             rows: self.rows,
             cols: self.cols,
-            data: Vec::with_capacity(self.data.len()),
-        };
+            data,
+        }
+    }
 
-        result.data.extend(self.data.iter().map(func));
+    /// Sum of all elements
+    pub fn sum(&self) -> f64 {
+        self.data.iter().sum()
+    }
 
-        result
+    /// Mean of all elements (NaN for an empty matrix)
+    pub fn mean(&self) -> f64 {
+        self.sum() / self.data.len() as f64
+    }
+
+    /// Largest element
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is empty
+    pub fn max(&self) -> f64 {
+        self.data[self.argmax()]
     }
 
+    /// Smallest element
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is empty
+    pub fn min(&self) -> f64 {
+        self.data[argmin(&self.data).expect("Cannot reduce an empty matrix")]
+    }
 
+    /// Row-major index of the largest element
+    ///
+    /// For a column vector (such as a network output) this is the index of the
+    /// winning neuron. Ties resolve to the first occurrence.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is empty
+    pub fn argmax(&self) -> usize {
+        argmax(&self.data).expect("Cannot reduce an empty matrix")
+    }
+
+    /// Sum of each row, as a `rows x 1` column vector
+    pub fn row_sums(&self) -> Matrix {
+        Matrix {
+            rows: self.rows,
+            cols: 1,
+            data: self.rows().map(|row| row.iter().sum()).collect(),
+        }
+    }
+
+    /// Sum of each column, as a `1 x cols` row vector
+    pub fn col_sums(&self) -> Matrix {
+        let mut data = vec![0.0; self.cols];
+        for row in self.rows() {
+            for (total, value) in data.iter_mut().zip(row) {
+                *total += value;
+            }
+        }
+        Matrix { rows: 1, cols: self.cols, data }
+    }
+
+    /// Mean of each row, as a `rows x 1` column vector
+    pub fn row_means(&self) -> Matrix {
+        let cols = self.cols as f64;
+        let mut sums = self.row_sums();
+        sums.data.iter_mut().for_each(|v| *v /= cols);
+        sums
+    }
+
+    /// Mean of each column, as a `1 x cols` row vector
+    ///
+    /// With activations stored as (neurons x batch_size), use `row_means` for
+    /// per-neuron batch statistics and `col_argmax` for per-sample predictions.
+    pub fn col_means(&self) -> Matrix {
+        let rows = self.rows as f64;
+        let mut sums = self.col_sums();
+        sums.data.iter_mut().for_each(|v| *v /= rows);
+        sums
+    }
+
+    /// Column index of the largest element in each row
+    pub fn row_argmax(&self) -> Vec<usize> {
+        self.rows()
+            .map(|row| argmax(row).expect("Cannot reduce an empty row"))
+            .collect()
+    }
+
+    /// Row index of the largest element in each column
+    pub fn col_argmax(&self) -> Vec<usize> {
+        self.transpose().row_argmax()
+    }
+
+    /// Whether both matrices have the same shape and every pair of elements
+    /// differs by at most `tolerance`
+    ///
+    /// NaN never compares equal, so a matrix containing NaN is not
+    /// approximately equal to anything.
+    pub fn approx_eq(&self, other: &Matrix, tolerance: f64) -> bool {
+        self.max_abs_diff(other).is_some_and(|diff| diff <= tolerance)
+    }
+
+    /// Largest absolute element-wise difference, or `None` if the shapes differ
+    ///
+    /// Returns NaN if either matrix contains NaN.
+    pub fn max_abs_diff(&self, other: &Matrix) -> Option<f64> {
+        if self.rows != other.rows || self.cols != other.cols {
+            return None;
+        }
+        let diff = self.data.iter().zip(&other.data).fold(0.0_f64, |worst, (a, b)| {
+            let d = (a - b).abs();
+            if d.is_nan() || worst.is_nan() { f64::NAN } else { worst.max(d) }
+        });
+        Some(diff)
+    }
+
+    /// Element at `(row, col)`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` or `col` is out of range
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        assert!(row < self.rows && col < self.cols, "Matrix index ({}, {}) out of range", row, col);
+        self.data[row * self.cols + col]
+    }
+
+    /// The elements of row `row` as a slice
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` is out of range
+    pub fn row(&self, row: usize) -> &[f64] {
+        assert!(row < self.rows, "Matrix row {} out of range", row);
+        &self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// Iterate over the rows, each as a slice
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = &[f64]> + '_ {
+        (0..self.rows).map(move |i| &self.data[i * self.cols..(i + 1) * self.cols])
+    }
+
+    /// Iterate over the columns, each as an iterator over its elements
+    ///
+    /// Columns are strided in the row-major layout, so each one is yielded
+    /// lazily rather than as a slice.
+    pub fn cols(&self) -> impl ExactSizeIterator<Item = impl Iterator<Item = &f64> + '_> + '_ {
+        (0..self.cols).map(move |j| self.data.iter().skip(j).step_by(self.cols))
+    }
+
+    /// Iterate over `(row, col, value)` for every element in row-major order
+    pub fn iter_indexed(&self) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        self.rows()
+            .enumerate()
+            .flat_map(|(i, row)| row.iter().enumerate().map(move |(j, &value)| (i, j, value)))
+    }
+
+    /// Combine two equally sized matrices element by element
+    fn zip_with(&self, other: &Matrix, op: fn(f64, f64) -> f64) -> Matrix {
+        let mut data = vec![0.0; self.data.len()];
+        let cols = self.cols;
+        for_each_row(&mut data, cols, self.data.len(), |i, row| {
+            let range = i * cols..(i + 1) * cols;
+            for ((out, a), b) in row.iter_mut().zip(&self.data[range.clone()]).zip(&other.data[range]) {
+                *out = op(*a, *b);
+            }
+        });
+
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data,
+        }
+    }
+}
+
+/// Panic unless `out` has the shape an `_into` operation writes
+fn check_output_shape(out: &Matrix, rows: usize, cols: usize) {
+    assert!(
+        out.rows == rows && out.cols == cols,
+        "Output matrix is {}x{}, expected {}x{}",
+        out.rows,
+        out.cols,
+        rows,
+        cols
+    );
+}
+
+/// Tolerance used by `assert_matrix_eq!` when none is given
+pub const DEFAULT_TOLERANCE: f64 = 1e-9;
+
+/// Side length of the square tiles used by the blocked matmul kernel
+///
+/// A 64x64 tile of `f64` is 32 KiB, small enough to stay in L1/L2 cache while
+/// it is reused for every row of the current row block.
+pub const MATMUL_BLOCK: usize = 64;
+
+/// Cache-blocked product of row-major `lhs` (`? x k`) and `rhs` (`k x n`)
+///
+/// Fills `out` with rows `row_start..row_start + out.len() / n` of the result.
+/// The loops are tiled over rows, the shared dimension and output columns so a
+/// `MATMUL_BLOCK`-square tile of `rhs` is reused across a block of rows instead
+/// of streaming the whole of `rhs` once per output row. Every output element
+/// still accumulates its terms in increasing `k` order, so results are
+/// bit-identical to the naive triple loop.
+pub(crate) fn matmul_rows(lhs: &[f64], rhs: &[f64], out: &mut [f64], row_start: usize, k: usize, n: usize) {
+    if n == 0 {
+        return;
+    }
+    out.fill(0.0);
+    let rows = out.len() / n;
+
+    for row_block in (0..rows).step_by(MATMUL_BLOCK) {
+        let row_end = (row_block + MATMUL_BLOCK).min(rows);
+        for k_block in (0..k).step_by(MATMUL_BLOCK) {
+            let k_end = (k_block + MATMUL_BLOCK).min(k);
+            for col_block in (0..n).step_by(MATMUL_BLOCK) {
+                let col_end = (col_block + MATMUL_BLOCK).min(n);
+
+                for i in row_block..row_end {
+                    let lhs_row = &lhs[(row_start + i) * k..(row_start + i + 1) * k];
+                    let out_row = &mut out[i * n + col_block..i * n + col_end];
+                    for p in k_block..k_end {
+                        let a = lhs_row[p];
+                        let rhs_row = &rhs[p * n + col_block..p * n + col_end];
+                        for (o, r) in out_row.iter_mut().zip(rhs_row) {
+                            *o += a * r;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Index of the largest value, first occurrence on ties
+fn argmax(values: &[f64]) -> Option<usize> {
+    values
+        .iter()
+        .enumerate()
+        .reduce(|best, current| if current.1.total_cmp(best.1).is_gt() { current } else { best })
+        .map(|(i, _)| i)
 }
+
+/// Index of the smallest value, first occurrence on ties
+fn argmin(values: &[f64]) -> Option<usize> {
+    values
+        .iter()
+        .enumerate()
+        .reduce(|best, current| if current.1.total_cmp(best.1).is_lt() { current } else { best })
+        .map(|(i, _)| i)
+}
+
+/// Serialized form of `Matrix`, validated by `TryFrom` on deserialization so
+/// checkpoints with inconsistent shapes are rejected instead of loaded
+#[derive(Deserialize)]
+struct UncheckedMatrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<f64>,
+}
+
+impl TryFrom<UncheckedMatrix> for Matrix {
+    type Error = MatrixError;
+
+    fn try_from(m: UncheckedMatrix) -> Result<Self, Self::Error> {
+        Matrix::new(m.rows, m.cols, m.data)
+    }
+}
+
 impl From<Vec<f64>> for Matrix {
     fn from(vec: Vec<f64>) -> Self {
         let rows = vec.len();
@@ -204,21 +687,101 @@ impl PartialEq for Matrix {
     }
 }
 
+/// Rows shown at each end when a matrix is truncated for display
+const DISPLAY_EDGE_ROWS: usize = 4;
+
+/// Columns shown at each end when a matrix is truncated for display
+const DISPLAY_EDGE_COLS: usize = 3;
+
+/// Matrices with more elements than this get a summarized `Debug` output
+const DEBUG_FULL_LIMIT: usize = 64;
+
+/// Indices to display along one axis; `None` marks the elided middle
+fn display_indices(len: usize, edge: usize) -> Vec<Option<usize>> {
+    if len <= 2 * edge + 1 {
+        (0..len).map(Some).collect()
+    } else {
+        (0..edge)
+            .map(Some)
+            .chain(std::iter::once(None))
+            .chain((len - edge..len).map(Some))
+            .collect()
+    }
+}
+
+/// Shape header followed by right-aligned rows
+///
+/// Large matrices show only their first and last rows and columns. The
+/// formatter precision is honored (`{:.2}`), defaulting to 4 decimals.
 impl fmt::Display for Matrix {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for row in 0..self.rows {
-            for col in 0..self.cols {
-                write!(f, "{}", self.data[row * self.cols + col])?;
-                if col < self.cols - 1 {
-                    write!(f, "\t")?; // Separate columns with a tab
+        let precision = f.precision().unwrap_or(4);
+        writeln!(f, "Matrix {}x{}", self.rows, self.cols)?;
+
+        let rows = display_indices(self.rows, DISPLAY_EDGE_ROWS);
+        let cols = display_indices(self.cols, DISPLAY_EDGE_COLS);
+        let cell = |row: usize, col: Option<usize>| match col {
+            Some(col) => format!("{:.*}", precision, self.get(row, col)),
+            None => "...".to_string(),
+        };
+
+        let width = rows
+            .iter()
+            .flatten()
+            .flat_map(|&row| cols.iter().map(move |&col| (row, col)))
+            .map(|(row, col)| cell(row, col).len())
+            .max()
+            .unwrap_or(0);
+
+        for row in rows {
+            match row {
+                Some(row) => {
+                    let cells: Vec<String> = cols
+                        .iter()
+                        .map(|&col| format!("{:>width$}", cell(row, col), width = width))
+                        .collect();
+                    writeln!(f, "[ {} ]", cells.join("  "))?;
                 }
+                None => writeln!(f, "  ...")?,
             }
-            writeln!(f)?; // Move to the next line after each row
         }
         Ok(())
     }
 }
 
+/// Full contents for small matrices; shape, edge values and statistics otherwise
+impl fmt::Debug for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.data.len() <= DEBUG_FULL_LIMIT {
+            return f
+                .debug_struct("Matrix")
+                .field("rows", &self.rows)
+                .field("cols", &self.cols)
+                .field("data", &self.data)
+                .finish();
+        }
+
+        let edge = DISPLAY_EDGE_COLS;
+        let head: Vec<String> = self.data[..edge].iter().map(|v| format!("{:?}", v)).collect();
+        let tail: Vec<String> = self.data[self.data.len() - edge..]
+            .iter()
+            .map(|v| format!("{:?}", v))
+            .collect();
+        write!(
+            f,
+            "Matrix {{ rows: {}, cols: {}, data: [{}, ..., {}] ({} values, min {:.4}, max {:.4}, mean {:.4}) }}",
+            self.rows,
+            self.cols,
+            head.join(", "),
+            tail.join(", "),
+            self.data.len(),
+            self.min(),
+            self.max(),
+            self.mean()
+        )
+    }
+}
+
 
 
 
@@ -245,14 +808,14 @@ mod tests {
     #[test]
     fn test_elementwise_multiply() {
         // Create two matrices for testing
-        let matrix1 = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
-        let matrix2 = Matrix::new(2, 2, vec![5.0, 6.0, 7.0, 8.0]);
+        let matrix1 = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+        let matrix2 = Matrix::new(2, 2, vec![5.0, 6.0, 7.0, 8.0]).unwrap();
 
         // Perform element-wise multiplication
         let result = matrix1.elementwise_multiply(&matrix2);
 
         // Define the expected result
-        let expected_result = Matrix::new(2, 2, vec![5.0, 12.0, 21.0, 32.0]);
+        let expected_result = Matrix::new(2, 2, vec![5.0, 12.0, 21.0, 32.0]).unwrap();
 
         // Check if the actual result matches the expected result
         assert_eq!(result, expected_result);
@@ -303,6 +866,67 @@ mod tests {
         assert_eq!(result, expected_result);
     }
 
+    #[test]
+    fn test_blocked_dot_multiply_matches_naive() {
+        // Sizes straddle MATMUL_BLOCK so partial tiles are exercised
+        let mut rng = StdRng::seed_from_u64(3);
+        let a = Matrix::random_uniform(MATMUL_BLOCK + 6, 2 * MATMUL_BLOCK + 2, 1.0, &mut rng);
+        let b = Matrix::random_uniform(2 * MATMUL_BLOCK + 2, MATMUL_BLOCK + 3, 1.0, &mut rng);
+
+        let mut naive = Matrix::zeros(a.rows, b.cols);
+        for i in 0..a.rows {
+            for j in 0..b.cols {
+                let mut sum = 0.0;
+                for k in 0..a.cols {
+                    sum += a.data[i * a.cols + k] * b.data[k * b.cols + j];
+                }
+                naive.data[i * b.cols + j] = sum;
+            }
+        }
+
+        assert_eq!(a.dot_multiply(&b).data, naive.data);
+    }
+
+    #[test]
+    fn test_dot_multiply_transposed_lhs() {
+        let a = matrix![
+            1.0, 2.0, 3.0;
+            4.0, 5.0, 6.0
+        ];
+        let b = matrix![
+            7.0, 8.0;
+            9.0, 10.0
+        ];
+
+        assert_eq!(a.dot_multiply_transposed_lhs(&b), a.transpose().dot_multiply(&b));
+    }
+
+    #[test]
+    fn test_dot_multiply_transposed_rhs() {
+        let a = matrix![
+            1.0, 2.0, 3.0;
+            4.0, 5.0, 6.0
+        ];
+        let b = matrix![
+            7.0, 8.0, 9.0;
+            10.0, 11.0, 12.0;
+            13.0, 14.0, 15.0;
+            16.0, 17.0, 18.0
+        ];
+
+        let result = a.dot_multiply_transposed_rhs(&b);
+        assert_eq!((result.rows, result.cols), (2, 4));
+        assert_eq!(result, a.dot_multiply(&b.transpose()));
+    }
+
+    #[test]
+    #[should_panic(expected = "incorrect dimensions")]
+    fn test_dot_multiply_transposed_lhs_dimension_mismatch() {
+        let a = Matrix::zeros(2, 3);
+        let b = Matrix::zeros(3, 2);
+        let _ = a.dot_multiply_transposed_lhs(&b);
+    }
+
     #[test]
     #[should_panic(expected = "Cannot subtract matrices with different dimensions")]
     fn test_subtract_different_dimensions() {
@@ -344,6 +968,250 @@ mod tests {
         assert_eq!(result, expected_result);
     }
 
+    #[test]
+    fn test_add_broadcasts_column_vector() {
+        let batch = matrix![
+            1.0, 2.0, 3.0;
+            4.0, 5.0, 6.0
+        ];
+        let bias = Matrix::from(vec![10.0, 20.0]);
+
+        let expected = matrix![
+            11.0, 12.0, 13.0;
+            24.0, 25.0, 26.0
+        ];
+        assert_eq!(batch.add(&bias), expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "Attempted to add matrix of incorrect dimensions")]
+    fn test_add_broadcast_row_mismatch() {
+        let batch = Matrix::zeros(2, 3);
+        let bias = Matrix::from(vec![1.0, 2.0, 3.0]);
+        let _ = batch.add(&bias);
+    }
+
+    #[test]
+    #[should_panic(expected = "Attempted to add matrix of incorrect dimensions")]
+    fn test_add_does_not_broadcast_lhs_column() {
+        let column = Matrix::from(vec![1.0, 2.0]);
+        let batch = Matrix::zeros(2, 3);
+        let _ = column.add(&batch);
+    }
+
+    #[test]
+    fn test_elementwise_divide() {
+        let a = matrix![
+            2.0, 9.0;
+            1.0, -8.0
+        ];
+        let b = matrix![
+            4.0, 3.0;
+            0.0, 2.0
+        ];
+
+        let result = a.elementwise_divide(&b);
+        assert_eq!(result.data[0], 0.5);
+        assert_eq!(result.data[1], 3.0);
+        assert_eq!(result.data[2], f64::INFINITY);
+        assert_eq!(result.data[3], -4.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Attempted to divide by matrix of incorrect dimensions")]
+    fn test_elementwise_divide_dimension_mismatch() {
+        let _ = Matrix::zeros(2, 2).elementwise_divide(&Matrix::zeros(2, 3));
+    }
+
+    #[test]
+    fn test_outer_product() {
+        let a = Matrix::from(vec![1.0, 2.0, 3.0]);
+        let b = matrix![4.0, 5.0];
+
+        let expected = matrix![
+            4.0, 5.0;
+            8.0, 10.0;
+            12.0, 15.0
+        ];
+        assert_eq!(Matrix::outer(&a, &b), expected);
+        assert_eq!(Matrix::outer(&a, &b), a.dot_multiply(&b));
+    }
+
+    #[test]
+    fn test_display_small_matrix() {
+        let m = matrix![
+            1.0, -22.5;
+            3.25, 4.0
+        ];
+
+        assert_eq!(
+            format!("{:.2}", m),
+            "Matrix 2x2\n[   1.00  -22.50 ]\n[   3.25    4.00 ]\n"
+        );
+    }
+
+    #[test]
+    fn test_display_truncates_large_matrix() {
+        let m = Matrix::zeros(100, 50);
+        let text = m.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines[0], "Matrix 100x50");
+        assert_eq!(lines.len(), 1 + 2 * DISPLAY_EDGE_ROWS + 1, "Header, edge rows and ellipsis");
+        assert_eq!(lines[1 + DISPLAY_EDGE_ROWS], "  ...");
+        assert_eq!(lines[1].matches("0.0000").count(), 2 * DISPLAY_EDGE_COLS);
+        assert!(lines[1].contains("..."));
+    }
+
+    #[test]
+    fn test_debug_summarizes_large_matrix() {
+        let small = Matrix::from(vec![1.0, 2.0]);
+        assert_eq!(format!("{:?}", small), "Matrix { rows: 2, cols: 1, data: [1.0, 2.0] }");
+
+        let large = Matrix::from((0..1000).map(|i| i as f64).collect::<Vec<_>>());
+        let debug = format!("{:?}", large);
+        assert!(debug.contains("data: [0.0, 1.0, 2.0, ..., 997.0, 998.0, 999.0]"));
+        assert!(debug.contains("1000 values"));
+        assert!(debug.len() < 200);
+    }
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let m = matrix![
+            1.0, -2.5, 3.0e-12;
+            f64::MAX, 0.1, 7.0
+        ];
+
+        let bytes = m.to_bytes();
+        assert_eq!(bytes.len(), 8 * 3 + 8 * 6, "Header plus 8 bytes per element");
+        assert_eq!(Matrix::from_bytes(&bytes).unwrap(), m);
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_bad_input() {
+        let bytes = Matrix::zeros(2, 2).to_bytes();
+        assert!(Matrix::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        let inconsistent = Matrix { rows: 3, cols: 3, data: vec![0.0; 4] };
+        let err = Matrix::from_bytes(&inconsistent.to_bytes()).unwrap_err();
+        assert!(err.to_string().contains("shape is 3x3"));
+    }
+
+    #[test]
+    fn test_reductions() {
+        let m = matrix![
+            1.0, 5.0, 3.0;
+            4.0, 2.0, 6.0
+        ];
+
+        assert_eq!(m.sum(), 21.0);
+        assert_eq!(m.mean(), 3.5);
+        assert_eq!(m.max(), 6.0);
+        assert_eq!(m.min(), 1.0);
+        assert_eq!(m.argmax(), 5);
+        assert_eq!(m.row_sums(), Matrix::from(vec![9.0, 12.0]));
+        assert_eq!(m.col_sums(), matrix![5.0, 7.0, 9.0]);
+        assert_eq!(m.row_means(), Matrix::from(vec![3.0, 4.0]));
+        assert_eq!(m.col_means(), matrix![2.5, 3.5, 4.5]);
+        assert_eq!(m.row_argmax(), vec![1, 2]);
+        assert_eq!(m.col_argmax(), vec![1, 0, 1]);
+    }
+
+    #[test]
+    fn test_argmax_ties_pick_first() {
+        let m = Matrix::from(vec![0.2, 0.7, 0.7, 0.1]);
+        assert_eq!(m.argmax(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "empty matrix")]
+    fn test_argmax_empty_panics() {
+        let _ = Matrix::zeros(0, 0).argmax();
+    }
+
+    #[test]
+    fn test_checked_constructors() {
+        let m = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+        assert_eq!(m, matrix![1.0, 2.0; 3.0, 4.0]);
+        assert_eq!(
+            Matrix::new(2, 2, vec![1.0, 2.0, 3.0]).unwrap_err(),
+            MatrixError::DataLength { rows: 2, cols: 2, len: 3 }
+        );
+        assert!(Matrix::new(1, 1, vec![]).is_err());
+
+        let rows = vec![vec![1.0, 2.0], vec![3.0, 4.0]];
+        assert_eq!(Matrix::from_rows(&rows).unwrap(), m);
+        assert_eq!(
+            Matrix::from_rows(&[vec![1.0, 2.0], vec![3.0]]).unwrap_err(),
+            MatrixError::RaggedRows { row: 1, expected: 2, found: 1 }
+        );
+    }
+
+    #[test]
+    fn test_hstack() {
+        let a = matrix![1.0; 2.0];
+        let b = matrix![3.0, 4.0; 5.0, 6.0];
+        assert_eq!(Matrix::hstack(&[a, b]), matrix![1.0, 3.0, 4.0; 2.0, 5.0, 6.0]);
+
+        let samples = vec![Matrix::from(vec![1.0, 2.0]), Matrix::from(vec![3.0, 4.0])];
+        assert_eq!(Matrix::hstack(&samples), matrix![1.0, 3.0; 2.0, 4.0]);
+    }
+
+    #[test]
+    fn test_vstack() {
+        let a = matrix![1.0, 2.0];
+        let b = matrix![3.0, 4.0; 5.0, 6.0];
+        assert_eq!(Matrix::vstack(&[a, b]), matrix![1.0, 2.0; 3.0, 4.0; 5.0, 6.0]);
+        assert_eq!(Matrix::vstack(&[]), Matrix::zeros(0, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "different numbers of rows")]
+    fn test_hstack_row_mismatch() {
+        let _ = Matrix::hstack(&[Matrix::zeros(2, 1), Matrix::zeros(3, 1)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "different numbers of columns")]
+    fn test_vstack_column_mismatch() {
+        let _ = Matrix::vstack(&[Matrix::zeros(1, 2), Matrix::zeros(1, 3)]);
+    }
+
+    #[test]
+    fn test_row_and_column_iterators() {
+        let m = matrix![1.0, 2.0, 3.0; 4.0, 5.0, 6.0];
+
+        let rows: Vec<&[f64]> = m.rows().collect();
+        assert_eq!(rows, vec![&[1.0, 2.0, 3.0][..], &[4.0, 5.0, 6.0][..]]);
+        assert_eq!(m.row(1), &[4.0, 5.0, 6.0]);
+
+        let cols: Vec<Vec<f64>> = m.cols().map(|col| col.copied().collect()).collect();
+        assert_eq!(cols, vec![vec![1.0, 4.0], vec![2.0, 5.0], vec![3.0, 6.0]]);
+        assert_eq!(m.cols().len(), 3);
+
+        assert_eq!(m.get(1, 2), 6.0);
+    }
+
+    #[test]
+    fn test_iter_indexed() {
+        let m = matrix![1.0, 2.0; 3.0, 4.0];
+        let indexed: Vec<_> = m.iter_indexed().collect();
+        assert_eq!(indexed, vec![(0, 0, 1.0), (0, 1, 2.0), (1, 0, 3.0), (1, 1, 4.0)]);
+    }
+
+    #[test]
+    fn test_iterators_on_empty_dimensions() {
+        assert_eq!(Matrix::zeros(3, 0).rows().count(), 3);
+        assert_eq!(Matrix::zeros(0, 3).cols().map(Iterator::count).collect::<Vec<_>>(), vec![0, 0, 0]);
+        assert_eq!(Matrix::zeros(0, 3).iter_indexed().count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_get_out_of_range() {
+        let _ = Matrix::zeros(2, 2).get(0, 2);
+    }
+
     #[test]
     fn test_transpose_2x2() {
         let matrix = matrix![
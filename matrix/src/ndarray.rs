@@ -0,0 +1,34 @@
+/// Conversions between `Matrix` and `ndarray::Array2<f64>`
+///
+/// Enabled with the `ndarray` feature. Both types are row-major, so
+/// standard-layout arrays convert without reordering; other layouts (for
+/// example a transposed view) are copied into row-major order first.
+use ndarray::Array2;
+
+use crate::matrix::Matrix;
+
+impl From<Array2<f64>> for Matrix {
+    fn from(array: Array2<f64>) -> Self {
+        let (rows, cols) = array.dim();
+        let data = if array.is_standard_layout() {
+            array.into_raw_vec_and_offset().0
+        } else {
+            array.iter().copied().collect()
+        };
+        Matrix { rows, cols, data }
+    }
+}
+
+impl From<Matrix> for Array2<f64> {
+    fn from(matrix: Matrix) -> Self {
+        Array2::from_shape_vec((matrix.rows, matrix.cols), matrix.data)
+            .expect("Matrix data length matches its shape")
+    }
+}
+
+/// Split a `(samples x features)` array into per-sample vectors
+///
+/// This is the layout expected by `Network::train` and `TrainingController::train`.
+pub fn to_samples(array: &Array2<f64>) -> Vec<Vec<f64>> {
+    array.rows().into_iter().map(|row| row.to_vec()).collect()
+}
@@ -0,0 +1,61 @@
+/// Optional rayon parallelism for matrix operations
+///
+/// With the `parallel` feature enabled, row-wise kernels are distributed over
+/// the rayon thread pool once the amount of work reaches `PARALLEL_THRESHOLD`
+/// multiply-adds. Smaller matrices (like the XOR examples) always run serially
+/// because the cost of scheduling would outweigh the speedup. Without the
+/// feature every kernel runs serially and no extra bounds are required.
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Minimum number of scalar operations before work is split across threads
+pub const PARALLEL_THRESHOLD: usize = 32 * 1024;
+
+/// `Sync` when the `parallel` feature is enabled, no requirement otherwise
+///
+/// Used as a bound on closures passed to `Matrix::map` so that serial builds
+/// keep accepting closures that capture non-thread-safe state.
+#[cfg(feature = "parallel")]
+pub trait MaybeSync: Sync {}
+#[cfg(feature = "parallel")]
+impl<T: Sync + ?Sized> MaybeSync for T {}
+
+/// `Sync` when the `parallel` feature is enabled, no requirement otherwise
+///
+/// Used as a bound on closures passed to `Matrix::map` so that serial builds
+/// keep accepting closures that capture non-thread-safe state.
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSync {}
+#[cfg(not(feature = "parallel"))]
+impl<T: ?Sized> MaybeSync for T {}
+
+/// Run `kernel(row_index, row)` over every `row_len`-sized row of `data`
+///
+/// `work` is the total number of scalar operations, compared against
+/// `PARALLEL_THRESHOLD` to decide whether to use the thread pool.
+pub(crate) fn for_each_row<K>(data: &mut [f64], row_len: usize, work: usize, kernel: K)
+where
+    K: Fn(usize, &mut [f64]) + MaybeSync,
+{
+    if row_len == 0 {
+        return;
+    }
+
+    #[cfg(feature = "parallel")]
+    if work >= PARALLEL_THRESHOLD {
+        data.par_chunks_mut(row_len)
+            .enumerate()
+            .for_each(|(i, row)| kernel(i, row));
+        return;
+    }
+
+    let _ = work;
+    data.chunks_mut(row_len)
+        .enumerate()
+        .for_each(|(i, row)| kernel(i, row));
+}
+
+/// Whether an operation of the given size would run on the thread pool
+pub fn is_parallel(work: usize) -> bool {
+    cfg!(feature = "parallel") && work >= PARALLEL_THRESHOLD
+}
@@ -0,0 +1,54 @@
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+
+/// Distributions for random matrix initialization
+///
+/// These are the primitives behind network-level schemes such as Xavier
+/// (uniform or normal with a fan-dependent scale) and He initialization.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Init {
+    /// Uniform in `[-limit, limit)`
+    Uniform { limit: f64 },
+
+    /// Normal with the given mean and standard deviation
+    Normal { mean: f64, std: f64 },
+
+    /// Normal with values further than two standard deviations from the mean
+    /// redrawn, so no single weight starts out extreme
+    TruncatedNormal { mean: f64, std: f64 },
+}
+
+impl Init {
+    /// Draw one value from the distribution
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` or `std` is negative or not finite
+    pub fn sample(&self, rng: &mut impl Rng) -> f64 {
+        match *self {
+            Init::Uniform { limit } => {
+                assert!(limit.is_finite() && limit >= 0.0, "Uniform limit must be a non-negative number");
+                if limit == 0.0 {
+                    0.0
+                } else {
+                    rng.gen_range(-limit..limit)
+                }
+            }
+            Init::Normal { mean, std } => normal(mean, std).sample(rng),
+            Init::TruncatedNormal { mean, std } => {
+                let normal = normal(mean, std);
+                loop {
+                    let value = normal.sample(rng);
+                    if (value - mean).abs() <= 2.0 * std {
+                        return value;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn normal(mean: f64, std: f64) -> Normal<f64> {
+    assert!(std.is_finite() && std >= 0.0, "Standard deviation must be a non-negative number");
+    Normal::new(mean, std).expect("Standard deviation must be a non-negative number")
+}
@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+
+use crate::matrix::{Matrix, matmul_rows};
+use crate::parallel::{MaybeSync, for_each_row};
+
+/// A batch of equally sized matrices stored contiguously
+///
+/// Item `b` occupies `data[b * rows * cols..(b + 1) * rows * cols]` in the
+/// same row-major layout as `Matrix`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tensor3 {
+    pub batch: usize,
+    pub rows: usize,
+    pub cols: usize,
+    pub data: Vec<f64>,
+}
+
+impl Tensor3 {
+    /// Create a tensor filled with zeros
+    pub fn zeros(batch: usize, rows: usize, cols: usize) -> Tensor3 {
+        Tensor3 {
+            batch,
+            rows,
+            cols,
+            data: vec![0.0; batch * rows * cols],
+        }
+    }
+
+    /// Stack matrices of identical shape into a tensor
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrices do not all have the same shape
+    pub fn from_matrices(matrices: &[Matrix]) -> Tensor3 {
+        let (rows, cols) = matrices.first().map_or((0, 0), |m| (m.rows, m.cols));
+        let mut data = Vec::with_capacity(matrices.len() * rows * cols);
+
+        for m in matrices {
+            assert!(
+                m.rows == rows && m.cols == cols,
+                "All matrices in a Tensor3 must have the same shape"
+            );
+            data.extend_from_slice(&m.data);
+        }
+
+        Tensor3 {
+            batch: matrices.len(),
+            rows,
+            cols,
+            data,
+        }
+    }
+
+    /// Copy out the matrix at `index`
+    pub fn matrix(&self, index: usize) -> Matrix {
+        assert!(index < self.batch, "Batch index out of range");
+        let size = self.rows * self.cols;
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data[index * size..(index + 1) * size].to_vec(),
+        }
+    }
+
+    /// Split the tensor back into its matrices
+    pub fn to_matrices(&self) -> Vec<Matrix> {
+        (0..self.batch).map(|i| self.matrix(i)).collect()
+    }
+
+    /// Multiply matching items: `result[b] = self[b] * other[b]`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the batch sizes differ or the inner dimensions do not match
+    pub fn batched_matmul(&self, other: &Tensor3) -> Tensor3 {
+        if self.batch != other.batch || self.cols != other.rows {
+            panic!("Attempted to multiply by tensor of incorrect dimensions");
+        }
+
+        let lhs_size = self.rows * self.cols;
+        let rhs_size = other.rows * other.cols;
+        self.batched(self.rows, other.cols, self.cols, |b, out| {
+            let lhs = &self.data[b * lhs_size..(b + 1) * lhs_size];
+            let rhs = &other.data[b * rhs_size..(b + 1) * rhs_size];
+            matmul_rows(lhs, rhs, out, 0, self.cols, other.cols);
+        })
+    }
+
+    /// Multiply every item by a shared matrix on the left: `result[b] = lhs * self[b]`
+    ///
+    /// This is the batched feed-forward step: one weight matrix applied to a
+    /// batch of activation matrices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lhs.cols` does not match `self.rows`
+    pub fn left_multiply(&self, lhs: &Matrix) -> Tensor3 {
+        if lhs.cols != self.rows {
+            panic!("Attempted to multiply by tensor of incorrect dimensions");
+        }
+
+        let size = self.rows * self.cols;
+        self.batched(lhs.rows, self.cols, lhs.cols, |b, out| {
+            let rhs = &self.data[b * size..(b + 1) * size];
+            matmul_rows(&lhs.data, rhs, out, 0, lhs.cols, self.cols);
+        })
+    }
+
+    /// Apply `func` to every element
+    pub fn map<F>(&self, func: F) -> Tensor3
+    where
+        F: Fn(&f64) -> f64,
+    {
+        Tensor3 {
+            batch: self.batch,
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(func).collect(),
+        }
+    }
+
+    /// Allocate a `(batch, rows, cols)` result and fill each item with `kernel`
+    ///
+    /// `inner` is the shared dimension of the products, used to estimate work.
+    fn batched<K>(&self, rows: usize, cols: usize, inner: usize, kernel: K) -> Tensor3
+    where
+        K: Fn(usize, &mut [f64]) + MaybeSync,
+    {
+        let mut data = vec![0.0; self.batch * rows * cols];
+        let work = self.batch * rows * cols * inner;
+        for_each_row(&mut data, rows * cols, work, kernel);
+
+        Tensor3 {
+            batch: self.batch,
+            rows,
+            cols,
+            data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix;
+
+    #[test]
+    fn test_batched_matmul_matches_per_item_dot() {
+        let a = vec![
+            matrix![1.0, 2.0; 3.0, 4.0],
+            matrix![0.5, -1.0; 2.0, 0.0],
+        ];
+        let b = vec![
+            matrix![1.0, 0.0, 2.0; 0.0, 1.0, 3.0],
+            matrix![2.0, 1.0, 0.0; 1.0, 1.0, 1.0],
+        ];
+
+        let result = Tensor3::from_matrices(&a).batched_matmul(&Tensor3::from_matrices(&b));
+
+        assert_eq!((result.batch, result.rows, result.cols), (2, 2, 3));
+        for i in 0..2 {
+            assert_eq!(result.matrix(i), a[i].dot_multiply(&b[i]));
+        }
+    }
+
+    #[test]
+    fn test_left_multiply_shared_weights() {
+        let weights = matrix![1.0, 2.0; 3.0, 4.0; 5.0, 6.0];
+        let inputs = vec![Matrix::from(vec![1.0, 0.0]), Matrix::from(vec![0.0, 1.0])];
+
+        let result = Tensor3::from_matrices(&inputs).left_multiply(&weights);
+
+        assert_eq!(result.to_matrices(), vec![
+            Matrix::from(vec![1.0, 3.0, 5.0]),
+            Matrix::from(vec![2.0, 4.0, 6.0]),
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "same shape")]
+    fn test_from_matrices_shape_mismatch() {
+        let _ = Tensor3::from_matrices(&[Matrix::zeros(2, 2), Matrix::zeros(2, 3)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "incorrect dimensions")]
+    fn test_batched_matmul_batch_mismatch() {
+        let a = Tensor3::zeros(2, 2, 2);
+        let b = Tensor3::zeros(3, 2, 2);
+        let _ = a.batched_matmul(&b);
+    }
+}
@@ -0,0 +1,61 @@
+// Tests for the wgpu backend (run with `--features gpu`)
+//
+// Machines without a GPU adapter only exercise the CPU fallback.
+#![cfg(feature = "gpu")]
+
+use matrix::gpu;
+use matrix::matrix::Matrix;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+fn cpu_product(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut data = vec![0.0; a.rows * b.cols];
+    for i in 0..a.rows {
+        for j in 0..b.cols {
+            data[i * b.cols + j] = (0..a.cols).map(|k| a.data[i * a.cols + k] * b.data[k * b.cols + j]).sum();
+        }
+    }
+    Matrix { rows: a.rows, cols: b.cols, data }
+}
+
+#[test]
+fn test_gpu_matmul_matches_cpu_or_falls_back() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let a = Matrix::random_uniform(130, 70, 1.0, &mut rng);
+    let b = Matrix::random_uniform(70, 90, 1.0, &mut rng);
+    let expected = cpu_product(&a, &b);
+
+    match gpu::matmul(&a, &b) {
+        Some(result) => {
+            assert!(gpu::is_available());
+            assert_eq!((result.rows, result.cols), (130, 90));
+            for (x, y) in result.data.iter().zip(&expected.data) {
+                assert!((x - y).abs() < 1e-4, "{} vs {}", x, y);
+            }
+        }
+        None => assert!(!gpu::is_available()),
+    }
+
+    gpu::set_enabled(false);
+    assert!(gpu::matmul(&a, &b).is_none());
+    gpu::set_enabled(true);
+
+    let guard = gpu::cpu_only();
+    assert!(gpu::matmul(&a, &b).is_none());
+    drop(guard);
+    assert_eq!(gpu::matmul(&a, &b).is_some(), gpu::is_available());
+}
+
+#[test]
+fn test_large_dot_multiply_uses_backend_transparently() {
+    let mut rng = StdRng::seed_from_u64(11);
+    let a = Matrix::random_uniform(256, 256, 1.0, &mut rng);
+    let b = Matrix::random_uniform(256, 256, 1.0, &mut rng);
+    const { assert!(256 * 256 * 256 >= gpu::GPU_THRESHOLD) };
+
+    let result = a.dot_multiply(&b);
+    let expected = cpu_product(&a, &b);
+    for (x, y) in result.data.iter().zip(&expected.data) {
+        assert!((x - y).abs() < 1e-3, "{} vs {}", x, y);
+    }
+}
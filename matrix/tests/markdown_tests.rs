@@ -4,22 +4,23 @@ use std::path::Path;
 
 /// Recursively finds all .md files in a directory
 fn find_markdown_files(dir: &Path, files: &mut Vec<std::path::PathBuf>) {
-    if dir.is_dir() {
-        if let Ok(entries) = fs::read_dir(dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_dir() {
-                    // Skip target and .git directories
-                    if let Some(name) = path.file_name() {
-                        if name != "target" && name != ".git" {
-                            find_markdown_files(&path, files);
-                        }
-                    }
-                } else if let Some(ext) = path.extension() {
-                    if ext == "md" {
-                        files.push(path);
-                    }
+    if dir.is_dir()
+        && let Ok(entries) = fs::read_dir(dir)
+    {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                // Skip target and .git directories
+                if let Some(name) = path.file_name()
+                    && name != "target"
+                    && name != ".git"
+                {
+                    find_markdown_files(&path, files);
                 }
+            } else if let Some(ext) = path.extension()
+                && ext == "md"
+            {
+                files.push(path);
             }
         }
     }
@@ -0,0 +1,39 @@
+// Tests for ndarray interop (run with `--features ndarray`)
+#![cfg(feature = "ndarray")]
+
+use matrix::matrix::Matrix;
+use matrix::ndarray::to_samples;
+use ndarray::{Array2, array};
+
+#[test]
+fn test_array_to_matrix() {
+    let array = array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+    let m = Matrix::from(array);
+
+    assert_eq!((m.rows, m.cols), (2, 3));
+    assert_eq!(m.data, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+}
+
+#[test]
+fn test_transposed_array_is_copied_row_major() {
+    let array = array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]].reversed_axes();
+    let m = Matrix::from(array);
+
+    assert_eq!((m.rows, m.cols), (3, 2));
+    assert_eq!(m.data, vec![1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+}
+
+#[test]
+fn test_matrix_to_array_roundtrip() {
+    let original = Matrix::new(2, 2, vec![0.5, -1.0, 2.0, 3.5]).unwrap();
+    let array: Array2<f64> = original.clone().into();
+
+    assert_eq!(array[[1, 0]], 2.0);
+    assert_eq!(Matrix::from(array), original);
+}
+
+#[test]
+fn test_to_samples() {
+    let data = array![[0.0, 1.0], [1.0, 0.0], [1.0, 1.0]];
+    assert_eq!(to_samples(&data), vec![vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]]);
+}
@@ -0,0 +1,79 @@
+// Tests that large matrix operations (parallel with the `parallel` feature)
+// match a straightforward serial computation
+use matrix::assert_matrix_eq;
+use matrix::matrix::Matrix;
+use matrix::parallel::{PARALLEL_THRESHOLD, is_parallel};
+
+fn seeded(rows: usize, cols: usize, seed: u64) -> Matrix {
+    let mut rng = Matrix::create_rng(seed);
+    Matrix::random_seeded(rows, cols, &mut rng)
+}
+
+#[test]
+fn test_large_dot_multiply_matches_reference() {
+    let a = seeded(64, 48, 1);
+    let b = seeded(48, 40, 2);
+    assert!(a.rows * a.cols * b.cols >= PARALLEL_THRESHOLD, "Should exceed the threshold");
+
+    let result = a.dot_multiply(&b);
+    for i in 0..a.rows {
+        for j in 0..b.cols {
+            let expected: f64 = (0..a.cols)
+                .map(|k| a.data[i * a.cols + k] * b.data[k * b.cols + j])
+                .sum();
+            assert_eq!(result.data[i * b.cols + j], expected);
+        }
+    }
+}
+
+#[test]
+fn test_large_elementwise_ops_match_reference() {
+    let mut a = seeded(256, 160, 3);
+    let b = seeded(256, 160, 4);
+
+    let sum = a.add(&b);
+    let difference = a.subtract(&b);
+    let product = a.elementwise_multiply(&b);
+    let mapped = a.map(|x| x * 2.0 + 1.0);
+
+    for i in 0..a.data.len() {
+        assert_eq!(sum.data[i], a.data[i] + b.data[i]);
+        assert_eq!(difference.data[i], a.data[i] - b.data[i]);
+        assert_eq!(product.data[i], a.data[i] * b.data[i]);
+        assert_eq!(mapped.data[i], a.data[i] * 2.0 + 1.0);
+    }
+}
+
+#[test]
+fn test_small_matrices_stay_serial() {
+    assert!(!is_parallel(3 * 2), "XOR-sized work never uses the thread pool");
+    assert_eq!(is_parallel(PARALLEL_THRESHOLD), cfg!(feature = "parallel"));
+}
+
+#[test]
+fn test_map_with_non_sync_closure_without_feature() {
+    // Closures must be Sync only when the parallel feature is enabled
+    let mut m = seeded(2, 2, 5);
+    let offset = std::rc::Rc::new(1.0);
+    #[cfg(not(feature = "parallel"))]
+    let mapped = m.map(|x| x + *offset);
+    #[cfg(feature = "parallel")]
+    let mapped = {
+        let offset = *offset;
+        m.map(move |x| x + offset)
+    };
+    assert_eq!(mapped.data[0], m.data[0] + 1.0);
+}
+
+#[test]
+fn test_large_transposed_multiplies_match_explicit_transpose() {
+    let a = seeded(48, 64, 6);
+    let b = seeded(48, 40, 7);
+    let c = seeded(30, 64, 8);
+
+    assert_eq!(a.dot_multiply_transposed_lhs(&b), a.transpose().dot_multiply(&b));
+
+    let fused = a.dot_multiply_transposed_rhs(&c);
+    let explicit = a.dot_multiply(&c.transpose());
+    assert_matrix_eq!(fused, explicit, 1e-12);
+}
@@ -0,0 +1,72 @@
+// Tests for random initialization distributions
+use matrix::matrix::Matrix;
+use matrix::random::Init;
+
+fn mean_and_std(data: &[f64]) -> (f64, f64) {
+    let mean = data.iter().sum::<f64>() / data.len() as f64;
+    let variance = data.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / data.len() as f64;
+    (mean, variance.sqrt())
+}
+
+#[test]
+fn test_random_uniform_range() {
+    let mut rng = Matrix::create_rng(1);
+    let m = Matrix::random_uniform(50, 40, 0.3, &mut rng);
+
+    assert_eq!((m.rows, m.cols), (50, 40));
+    assert!(m.data.iter().all(|&x| (-0.3..0.3).contains(&x)));
+    assert!(m.data.iter().any(|&x| x < 0.0), "Should produce negative values");
+
+    let (mean, _) = mean_and_std(&m.data);
+    assert!(mean.abs() < 0.02);
+}
+
+#[test]
+fn test_random_normal_statistics() {
+    let mut rng = Matrix::create_rng(2);
+    let m = Matrix::random_normal(100, 100, 1.5, 0.5, &mut rng);
+
+    let (mean, std) = mean_and_std(&m.data);
+    assert!((mean - 1.5).abs() < 0.02, "mean was {}", mean);
+    assert!((std - 0.5).abs() < 0.02, "std was {}", std);
+}
+
+#[test]
+fn test_random_truncated_normal_bounds() {
+    let mut rng = Matrix::create_rng(3);
+    let m = Matrix::random_truncated_normal(100, 100, 0.0, 1.0, &mut rng);
+
+    assert!(m.data.iter().all(|x| x.abs() <= 2.0));
+    let (_, std) = mean_and_std(&m.data);
+    // Truncating at 2 sigma shrinks the spread to about 0.88
+    assert!(std > 0.8 && std < 0.95, "std was {}", std);
+}
+
+#[test]
+fn test_random_distributions_are_seedable() {
+    for init in [
+        Init::Uniform { limit: 1.0 },
+        Init::Normal { mean: 0.0, std: 1.0 },
+        Init::TruncatedNormal { mean: 0.0, std: 1.0 },
+    ] {
+        let a = Matrix::random_with(4, 4, init, &mut Matrix::create_rng(42));
+        let b = Matrix::random_with(4, 4, init, &mut Matrix::create_rng(42));
+        let c = Matrix::random_with(4, 4, init, &mut Matrix::create_rng(43));
+        assert_eq!(a, b, "{:?} should be reproducible", init);
+        assert_ne!(a, c, "{:?} should depend on the seed", init);
+    }
+}
+
+#[test]
+fn test_zero_width_distributions() {
+    let mut rng = Matrix::create_rng(4);
+    assert!(Matrix::random_uniform(2, 2, 0.0, &mut rng).data.iter().all(|&x| x == 0.0));
+    assert!(Matrix::random_normal(2, 2, 3.0, 0.0, &mut rng).data.iter().all(|&x| x == 3.0));
+}
+
+#[test]
+#[should_panic(expected = "Standard deviation")]
+fn test_negative_std_panics() {
+    let mut rng = Matrix::create_rng(5);
+    let _ = Matrix::random_normal(2, 2, 0.0, -1.0, &mut rng);
+}
@@ -2,15 +2,48 @@
 // Generates SVG visualization of neural network weights and architecture from checkpoint files
 
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use neural_network::histogram::{Histogram, LayerHistogram};
+use neural_network::boundary::{DecisionGrid, input_ranges};
+use neural_network::checkpoint::Checkpoint;
+use neural_network::history::TrainingHistory;
+use neural_network::inference::InferenceModel;
+use neural_network::plot::{ChartOptions, SERIES_COLORS, Series, line_chart_svg, loss_chart_svg};
 use std::fs;
+use std::path::Path;
+
+/// What to render from the checkpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Mode {
+    /// Network architecture with weights and biases
+    Network,
+    /// Per-layer weight norms over the recorded training history
+    WeightNorms,
+    /// Training and validation loss over the recorded training history
+    Loss,
+    /// Per-layer histograms of weight and bias values
+    Histograms,
+    /// Predicted class over a grid of inputs of a two-input network
+    Boundary,
+}
+
+/// File format written to the output path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Vector image, zoomable in a browser
+    Svg,
+    /// Raster image, for networks whose SVG is too large to view
+    Png,
+    /// Graphviz graph of the network, for layout with `dot` and friends
+    Dot,
+}
 
 /// Visualize neural network architecture and weights from a checkpoint file
 #[derive(Parser, Debug)]
 #[command(name = "visualize")]
 #[command(about = "Generate SVG visualization of neural network weights")]
 struct Args {
-    /// Path to checkpoint file
+    /// Path to checkpoint file (a directory of checkpoints with --animate)
     #[arg(short, long)]
     checkpoint: String,
 
@@ -29,25 +62,80 @@ struct Args {
     /// Show weight values as text
     #[arg(long, default_value = "false")]
     show_values: bool,
+
+    /// Visualization mode
+    #[arg(long, value_enum, default_value = "network")]
+    mode: Mode,
+
+    /// Output format; an output path ending in .png selects png, and `dot` is available for the network mode
+    #[arg(long, value_enum, default_value = "svg")]
+    format: Format,
+
+    /// Number of bins for histogram mode
+    #[arg(long, default_value = "20", value_parser = clap::value_parser!(u32).range(1..))]
+    bins: u32,
+
+    /// Plot the loss and weight-norm modes on a logarithmic value axis
+    #[arg(long)]
+    log_scale: bool,
+
+    /// Grid points along each axis in boundary mode
+    #[arg(long, default_value = "100", value_parser = clap::value_parser!(u32).range(2..))]
+    resolution: u32,
+
+    /// Example drawn over the boundary, which also sets the plotted input range (default: the checkpoint's example)
+    #[arg(long)]
+    example: Option<String>,
+
+    /// Animate the network's weights over the checkpoints in the --checkpoint directory, in epoch order
+    #[arg(long)]
+    animate: bool,
+
+    /// Seconds each checkpoint is shown in an animation
+    #[arg(long, default_value = "0.5")]
+    frame_secs: f64,
+}
+
+impl Args {
+    /// The format to write: `--format`, or png for a `.png` output
+    fn output_format(&self) -> Format {
+        if self.format == Format::Svg && self.output.to_lowercase().ends_with(".png") {
+            Format::Png
+        } else {
+            self.format
+        }
+    }
+
+    /// Canvas size and value axis of the line chart modes
+    fn chart_options(&self) -> ChartOptions {
+        ChartOptions {
+            width: self.width,
+            height: self.height,
+            log_scale: self.log_scale,
+        }
+    }
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
 
     println!("Neural Network Visualizer");
+    if args.animate {
+        return animate(&args);
+    }
     println!("Reading checkpoint: {}", args.checkpoint);
 
-    // Load checkpoint
-    let checkpoint_data = fs::read_to_string(&args.checkpoint)
-        .with_context(|| format!("Failed to read checkpoint file: {}", args.checkpoint))?;
-
-    let checkpoint = serde_json::from_str::<neural_network::checkpoint::Checkpoint>(&checkpoint_data)
-        .with_context(|| "Failed to parse checkpoint JSON")?;
+    // Load checkpoint (JSON or binary, optionally compressed)
+    let checkpoint = neural_network::checkpoint::Checkpoint::load(Path::new(&args.checkpoint))
+        .with_context(|| format!("Failed to load checkpoint file: {}", args.checkpoint))?;
 
     println!("Checkpoint metadata:");
     println!("  Example: {}", checkpoint.metadata.example);
     println!("  Epochs: {}/{}", checkpoint.metadata.epoch, checkpoint.metadata.total_epochs);
     println!("  Learning rate: {}", checkpoint.metadata.learning_rate);
+    for (label, value) in checkpoint.metadata.details() {
+        println!("  {}: {}", label, value);
+    }
     println!();
 
     let network = &checkpoint.network;
@@ -66,8 +154,68 @@ fn main() -> Result<()> {
              total_weights + total_biases, total_weights, total_biases);
     println!();
 
+    let format = args.output_format();
+    if format == Format::Dot {
+        if args.mode != Mode::Network {
+            anyhow::bail!("--format dot draws the network; it can't be combined with --mode {:?}", args.mode);
+        }
+        fs::write(&args.output, generate_dot(network, &args))
+            .with_context(|| format!("Failed to write DOT file: {}", args.output))?;
+        println!("Graphviz DOT file saved to: {}", args.output);
+        println!("Render it with: dot -Tsvg {} -o network.svg", args.output);
+        return Ok(());
+    }
+
     // Generate SVG
-    let svg = generate_svg(network, &args)?;
+    let svg = match args.mode {
+        Mode::Network => generate_svg(network, &args)?,
+        Mode::WeightNorms => {
+            if checkpoint.history.is_empty() {
+                anyhow::bail!(
+                    "Checkpoint has no training history. Re-train with the current CLI to record weight norms."
+                );
+            }
+            println!("Plotting weight norms over {} recorded epochs", checkpoint.history.len());
+            generate_weight_norms_svg(&checkpoint.history, &args)
+        }
+        Mode::Loss => {
+            if checkpoint.history.is_empty() {
+                anyhow::bail!(
+                    "Checkpoint has no training history. Re-train with the current CLI to record losses."
+                );
+            }
+            println!(
+                "Plotting loss over {} recorded epochs ({:.2}s of training)",
+                checkpoint.history.len(),
+                checkpoint.history.elapsed_secs()
+            );
+            loss_chart_svg(&checkpoint.history, &args.chart_options())
+        }
+        Mode::Histograms => {
+            let histograms = network.weight_histograms(args.bins as usize);
+            generate_histograms_svg(&histograms, &args)
+        }
+        Mode::Boundary => {
+            let (grid, points) = sample_boundary(&checkpoint, &args)?;
+            if format == Format::Png {
+                // Drawn directly, which is sharper and faster than rasterizing one rect per cell
+                boundary_image(&grid, &points, &args)
+                    .save(&args.output)
+                    .with_context(|| format!("Failed to write PNG file: {}", args.output))?;
+                println!("Decision boundary saved to: {}", args.output);
+                return Ok(());
+            }
+            generate_boundary_svg(&grid, &points, &args)
+        }
+    };
+
+    if format == Format::Png {
+        rasterize(&svg)?
+            .save_png(&args.output)
+            .with_context(|| format!("Failed to write PNG file: {}", args.output))?;
+        println!("PNG visualization saved to: {}", args.output);
+        return Ok(());
+    }
 
     // Write SVG file
     fs::write(&args.output, svg)
@@ -79,17 +227,48 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+/// Radius of a neuron in the network drawings
+const NEURON_SIZE: u32 = 20;
+
+/// Top-left corner of every neuron's box, layer by layer
+///
+/// Layers are spread evenly across the canvas and their neurons centered
+/// vertically.
+fn neuron_positions(layers: &[usize], args: &Args) -> Vec<Vec<(u32, u32)>> {
+    let margin = 80;
+    let layer_spacing = (args.width - 2 * margin) / (layers.len() as u32 - 1).max(1);
+    layers
+        .iter()
+        .enumerate()
+        .map(|(layer_idx, &num_neurons)| {
+            let x = margin + layer_idx as u32 * layer_spacing;
+            let layer_height = num_neurons as u32 * (NEURON_SIZE * 3);
+            let start_y = args.height.saturating_sub(layer_height) / 2;
+            (0..num_neurons).map(|neuron_idx| (x, start_y + neuron_idx as u32 * (NEURON_SIZE * 3))).collect()
+        })
+        .collect()
+}
+
+/// Stroke color and width of a weight whose magnitude is at `normalized`
+/// between the smallest (0) and largest (1) of its layer
+fn weight_style(weight: f64, normalized: f64) -> (String, f64) {
+    let intensity = (normalized * 200.0) as u8 + 55;
+    let color = if weight >= 0.0 {
+        // Positive weights: blue gradient
+        format!("rgb(55, {}, {})", intensity, 255)
+    } else {
+        // Negative weights: red gradient
+        format!("rgb(255, {}, {})", intensity, intensity)
+    };
+    (color, 0.5 + normalized * 3.0)
+}
+
 fn generate_svg(network: &neural_network::network::Network, args: &Args) -> Result<String> {
     let width = args.width;
     let height = args.height;
     let margin = 80;
 
-    let num_layers = network.layers.len();
-    let _max_neurons = *network.layers.iter().max().unwrap();
-
-    // Calculate spacing
-    let layer_spacing = (width - 2 * margin) / (num_layers as u32 - 1);
-    let neuron_size = 20;
+    let neuron_size = NEURON_SIZE;
 
     let mut svg = String::new();
 
@@ -123,20 +302,7 @@ fn generate_svg(network: &neural_network::network::Network, args: &Args) -> Resu
         width / 2, width / 2, network.layers
     ));
 
-    // Calculate neuron positions for each layer
-    let mut neuron_positions = Vec::new();
-    for (layer_idx, &num_neurons) in network.layers.iter().enumerate() {
-        let x = margin + layer_idx as u32 * layer_spacing;
-        let layer_height = num_neurons as u32 * (neuron_size * 3);
-        let start_y = (height - layer_height) / 2;
-
-        let mut layer_neurons = Vec::new();
-        for neuron_idx in 0..num_neurons {
-            let y = start_y + neuron_idx as u32 * (neuron_size * 3);
-            layer_neurons.push((x, y));
-        }
-        neuron_positions.push(layer_neurons);
-    }
+    let neuron_positions = neuron_positions(&network.layers, args);
 
     // Draw connections (weights) first so they appear behind neurons
     svg.push_str("<!-- Weight connections -->\n");
@@ -148,21 +314,18 @@ fn generate_svg(network: &neural_network::network::Network, args: &Args) -> Resu
         // Calculate weight statistics for this layer
         let mut min_weight = f64::INFINITY;
         let mut max_weight = f64::NEG_INFINITY;
-        for row in 0..weights.rows {
-            for col in 0..weights.cols {
-                let w = weights.data[row * weights.cols + col];
+        for row in weights.rows() {
+            for w in row {
                 min_weight = min_weight.min(w.abs());
                 max_weight = max_weight.max(w.abs());
             }
         }
 
-        for from_idx in 0..from_layer.len() {
-            for to_idx in 0..to_layer.len() {
-                let (x1, y1) = from_layer[from_idx];
-                let (x2, y2) = to_layer[to_idx];
+        for (from_idx, &(x1, y1)) in from_layer.iter().enumerate() {
+            for (to_idx, &(x2, y2)) in to_layer.iter().enumerate() {
 
                 // Get weight value (weights are stored as [to_neurons x from_neurons])
-                let weight = weights.data[to_idx * weights.cols + from_idx];
+                let weight = weights.get(to_idx, from_idx);
                 let weight_abs = weight.abs();
 
                 // Map weight to color and thickness
@@ -171,18 +334,7 @@ fn generate_svg(network: &neural_network::network::Network, args: &Args) -> Resu
                 } else {
                     0.5
                 };
-
-                let color = if weight >= 0.0 {
-                    // Positive weights: blue gradient
-                    let intensity = (normalized * 200.0) as u8 + 55;
-                    format!("rgb(55, {}, {})", intensity, 255)
-                } else {
-                    // Negative weights: red gradient
-                    let intensity = (normalized * 200.0) as u8 + 55;
-                    format!("rgb(255, {}, {})", intensity, intensity)
-                };
-
-                let thickness = 0.5 + normalized * 3.0;
+                let (color, thickness) = weight_style(weight, normalized);
 
                 svg.push_str(&format!(
                     r##"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="{:.2}" class="weight-line">
@@ -210,7 +362,6 @@ fn generate_svg(network: &neural_network::network::Network, args: &Args) -> Resu
 
     // Draw neurons
     svg.push_str("<!-- Neurons -->\n");
-    let _layer_names = vec!["Input", "Hidden", "Hidden", "Hidden", "Hidden", "Output"];
     for (layer_idx, layer_neurons) in neuron_positions.iter().enumerate() {
         let layer_name = if layer_idx == 0 {
             "Input"
@@ -277,3 +428,619 @@ fn generate_svg(network: &neural_network::network::Network, args: &Args) -> Resu
 
     Ok(svg)
 }
+
+/// Write an animated SVG of the weights in a directory of checkpoints
+fn animate(args: &Args) -> Result<()> {
+    if args.mode != Mode::Network || args.output_format() != Format::Svg {
+        anyhow::bail!("--animate draws the network as an animated SVG; it can't be combined with other modes or formats");
+    }
+    if args.frame_secs.is_nan() || args.frame_secs <= 0.0 {
+        anyhow::bail!("--frame-secs must be positive, got {}", args.frame_secs);
+    }
+    println!("Reading checkpoints in: {}", args.checkpoint);
+    let frames = load_frames(Path::new(&args.checkpoint))?;
+    let (first, last) = (&frames[0].metadata, &frames[frames.len() - 1].metadata);
+    println!(
+        "Animating {} checkpoints from epoch {} to {} ({:.1}s per loop)",
+        frames.len(),
+        first.epoch,
+        last.epoch,
+        frames.len() as f64 * args.frame_secs
+    );
+
+    fs::write(&args.output, generate_animation_svg(&frames, args))
+        .with_context(|| format!("Failed to write SVG file: {}", args.output))?;
+    println!("Animated SVG saved to: {}", args.output);
+    println!("Open in a browser to play it");
+    Ok(())
+}
+
+/// Every checkpoint in `dir`, sorted by epoch
+///
+/// Files that are not checkpoints are skipped; all checkpoints must share an
+/// architecture.
+fn load_frames(dir: &Path) -> Result<Vec<Checkpoint>> {
+    let mut frames = Vec::new();
+    let entries = fs::read_dir(dir).with_context(|| format!("Failed to read checkpoint directory: {}", dir.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        match Checkpoint::load(&path) {
+            Ok(checkpoint) => frames.push(checkpoint),
+            Err(e) => println!("Skipping {}: {}", path.display(), e),
+        }
+    }
+    if frames.len() < 2 {
+        anyhow::bail!(
+            "Found {} checkpoint(s) in {}; an animation needs at least 2 (train --snapshots saves them)",
+            frames.len(),
+            dir.display()
+        );
+    }
+    frames.sort_by_key(|checkpoint| checkpoint.metadata.epoch);
+    let layers = &frames[0].network.layers;
+    if let Some(other) = frames.iter().find(|checkpoint| &checkpoint.network.layers != layers) {
+        anyhow::bail!(
+            "Checkpoints have different architectures: {:?} at epoch {} and {:?} at epoch {}",
+            layers,
+            frames[0].metadata.epoch,
+            other.network.layers,
+            other.metadata.epoch
+        );
+    }
+    Ok(frames)
+}
+
+/// Draw the network once and animate each weight's color and thickness
+/// through the checkpoints
+///
+/// Weights are scaled by the largest magnitude their layer reaches in any
+/// checkpoint, so growth over training shows as lines thickening. An epoch
+/// counter and the loss of each checkpoint play along.
+fn generate_animation_svg(frames: &[Checkpoint], args: &Args) -> String {
+    let network = &frames[0].network;
+    let positions = neuron_positions(&network.layers, args);
+    let duration = frames.len() as f64 * args.frame_secs;
+    let animation = |attribute: &str, values: &[String]| {
+        format!(
+            r##"  <animate attributeName="{}" values="{}" dur="{:.3}s" calcMode="discrete" repeatCount="indefinite"/>
+"##,
+            attribute,
+            values.join(";"),
+            duration
+        )
+    };
+
+    let mut svg = String::new();
+    svg.push_str(&format!(
+        r##"<?xml version="1.0" encoding="UTF-8"?>
+<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}">
+<defs>
+  <style>
+    .neuron {{ fill: #4a90e2; stroke: #2c5aa0; stroke-width: 2; }}
+    .weight-line {{ stroke-opacity: 0.6; }}
+    .neuron-label {{ font-family: Arial, sans-serif; font-size: 10px; fill: #666; }}
+    .title {{ font-family: Arial, sans-serif; font-size: 20px; font-weight: bold; fill: #333; }}
+    .subtitle {{ font-family: Arial, sans-serif; font-size: 14px; fill: #666; }}
+  </style>
+</defs>
+
+<!-- Background -->
+<rect width="{w}" height="{h}" fill="#f5f7fa"/>
+
+<!-- Title -->
+<text x="{cx}" y="30" class="title" text-anchor="middle">Training Evolution</text>
+<text x="{cx}" y="50" class="subtitle" text-anchor="middle">{layers:?}</text>
+
+<!-- Frame captions -->
+"##,
+        w = args.width,
+        h = args.height,
+        cx = args.width / 2,
+        layers = network.layers,
+    ));
+
+    // One caption per frame, shown only during its frame
+    for (i, frame) in frames.iter().enumerate() {
+        let visibility: Vec<String> =
+            (0..frames.len()).map(|j| if i == j { "visible" } else { "hidden" }.to_string()).collect();
+        let loss = frame.metadata.final_loss.or_else(|| frame.history.last().map(|record| record.loss));
+        let caption = match loss {
+            Some(loss) => format!("Epoch {} - loss {:.6}", frame.metadata.epoch, loss),
+            None => format!("Epoch {}", frame.metadata.epoch),
+        };
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"75\" class=\"subtitle\" text-anchor=\"middle\" visibility=\"{}\">{}\n{}</text>\n",
+            args.width / 2,
+            visibility[0],
+            caption,
+            animation("visibility", &visibility)
+        ));
+    }
+
+    svg.push_str("\n<!-- Weight connections -->\n");
+    for (layer, pair) in positions.windows(2).enumerate() {
+        let largest = frames
+            .iter()
+            .flat_map(|frame| frame.network.weights[layer].data.iter())
+            .fold(0.0_f64, |m, w| m.max(w.abs()))
+            .max(f64::EPSILON);
+        for (from, &(x1, y1)) in pair[0].iter().enumerate() {
+            for (to, &(x2, y2)) in pair[1].iter().enumerate() {
+                // Weights are stored as [to_neurons x from_neurons]
+                let styles: Vec<(String, f64)> = frames
+                    .iter()
+                    .map(|frame| {
+                        let weight = frame.network.weights[layer].get(to, from);
+                        weight_style(weight, weight.abs() / largest)
+                    })
+                    .collect();
+                let colors: Vec<String> = styles.iter().map(|(color, _)| color.clone()).collect();
+                let widths: Vec<String> = styles.iter().map(|(_, width)| format!("{:.2}", width)).collect();
+                svg.push_str(&format!(
+                    "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"{}\" class=\"weight-line\">\n{}{}</line>\n",
+                    x1,
+                    y1 + NEURON_SIZE / 2,
+                    x2,
+                    y2 + NEURON_SIZE / 2,
+                    colors[0],
+                    widths[0],
+                    animation("stroke", &colors),
+                    animation("stroke-width", &widths)
+                ));
+            }
+        }
+    }
+
+    svg.push_str("\n<!-- Neurons -->\n");
+    for neurons in &positions {
+        for (neuron, &(x, y)) in neurons.iter().enumerate() {
+            svg.push_str(&format!(
+                r##"<circle cx="{}" cy="{}" r="{}" class="neuron"/>
+<text x="{}" y="{}" class="neuron-label" text-anchor="middle">{}</text>
+"##,
+                x,
+                y + NEURON_SIZE / 2,
+                NEURON_SIZE,
+                x,
+                y + NEURON_SIZE / 2 + 5,
+                neuron
+            ));
+        }
+    }
+
+    svg.push_str("\n</svg>");
+    svg
+}
+
+/// Fonts tried, in order, for the generic sans-serif family of the SVGs
+const SANS_SERIF_FONTS: [&str; 5] = ["Arial", "Helvetica", "Liberation Sans", "DejaVu Sans", "Noto Sans"];
+
+/// Render an SVG document to pixels at its own size
+///
+/// Text is drawn with the system fonts; without Arial, the first installed
+/// font of `SANS_SERIF_FONTS` stands in for it.
+fn rasterize(svg: &str) -> Result<resvg::tiny_skia::Pixmap> {
+    let mut options = resvg::usvg::Options::default();
+    let fonts = options.fontdb_mut();
+    fonts.load_system_fonts();
+    let installed = SANS_SERIF_FONTS
+        .into_iter()
+        .find(|family| fonts.faces().any(|face| face.families.iter().any(|(name, _)| name == family)));
+    if let Some(family) = installed {
+        fonts.set_sans_serif_family(family);
+    }
+    let tree = resvg::usvg::Tree::from_str(svg, &options).context("Failed to parse the generated SVG")?;
+    let size = tree.size().to_int_size();
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(size.width(), size.height())
+        .context("The image must be at least one pixel wide and high")?;
+    resvg::render(&tree, resvg::tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    Ok(pixmap)
+}
+
+/// Describe the network as a Graphviz digraph
+///
+/// Each layer is a cluster of neuron nodes, ranked left to right. Hidden and
+/// output neurons are labelled with their bias; every weight is an edge
+/// labelled with its value, blue when positive and red when negative, and
+/// thicker the larger it is within its layer. With `--show-values` off the
+/// labels are rounded to two decimals.
+fn generate_dot(network: &neural_network::network::Network, args: &Args) -> String {
+    let last = network.layers.len() - 1;
+    let layer_name = |layer: usize| match layer {
+        0 => "Input".to_string(),
+        l if l == last => "Output".to_string(),
+        l => format!("Hidden {}", l),
+    };
+    let precision = if args.show_values { 4 } else { 2 };
+
+    let mut dot = String::from(
+        "digraph network {\n  rankdir=LR;\n  splines=line;\n  nodesep=0.3;\n  ranksep=2.0;\n  node [shape=circle, style=filled, fillcolor=\"#4a90e2\", color=\"#2c5aa0\", fontcolor=white, fontname=Arial, fontsize=10];\n  edge [fontname=Arial, fontsize=8, arrowsize=0.5];\n",
+    );
+
+    for (layer, &neurons) in network.layers.iter().enumerate() {
+        dot.push_str(&format!(
+            "\n  subgraph cluster_{} {{\n    label=\"{} ({})\";\n    color=\"#dddddd\";\n    fontname=Arial;\n",
+            layer,
+            layer_name(layer),
+            neurons
+        ));
+        for neuron in 0..neurons {
+            let label = match layer {
+                0 => format!("N{}", neuron),
+                _ => format!("N{}\\nb={:.*}", neuron, precision, network.biases[layer - 1].get(neuron, 0)),
+            };
+            dot.push_str(&format!("    l{}n{} [label=\"{}\"];\n", layer, neuron, label));
+        }
+        dot.push_str("  }\n");
+    }
+
+    for (layer, weights) in network.weights.iter().enumerate() {
+        let largest = weights.data.iter().fold(0.0_f64, |m, w| m.max(w.abs())).max(f64::EPSILON);
+        dot.push('\n');
+        for to in 0..weights.rows {
+            for from in 0..weights.cols {
+                // Weights are stored as [to_neurons x from_neurons]
+                let weight = weights.get(to, from);
+                let color = if weight >= 0.0 { "#3776d8" } else { "#d83737" };
+                dot.push_str(&format!(
+                    "  l{}n{} -> l{}n{} [label=\"{:.*}\", color=\"{}\", penwidth={:.2}];\n",
+                    layer,
+                    from,
+                    layer + 1,
+                    to,
+                    precision,
+                    weight,
+                    color,
+                    0.5 + weight.abs() / largest * 3.0
+                ));
+            }
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// Render per-layer weight norms over epochs as an SVG line chart
+///
+/// Flat lines near zero indicate dead layers; steadily rising lines indicate
+/// runaway weight growth.
+fn generate_weight_norms_svg(history: &TrainingHistory, args: &Args) -> String {
+    let series: Vec<Series> = (0..history.num_layers())
+        .map(|layer| (format!("Layer {} -> {}", layer, layer + 1), history.weight_norm_series(layer)))
+        .collect();
+    line_chart_svg("Weight Norms per Layer", "L2 norm", &series, &args.chart_options())
+}
+
+/// Render weight and bias histograms, one row per layer
+///
+/// Weights are drawn on the left and biases on the right; each panel uses
+/// the value range of its own layer.
+fn generate_histograms_svg(histograms: &[LayerHistogram], args: &Args) -> String {
+    let width = args.width as f64;
+    let height = args.height as f64;
+    let margin = 60.0;
+    let gap = 40.0;
+    let rows = histograms.len().max(1) as f64;
+    let panel_width = (width - 2.0 * margin - gap) / 2.0;
+    let panel_height = (height - 2.0 * margin - gap * (rows - 1.0)) / rows;
+
+    let mut svg = String::new();
+    svg.push_str(&format!(
+        r##"<?xml version="1.0" encoding="UTF-8"?>
+<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}">
+<defs>
+  <style>
+    .axis {{ stroke: #333; stroke-width: 1; }}
+    .bar {{ stroke: #fff; stroke-width: 0.5; }}
+    .bar:hover {{ fill-opacity: 0.7; }}
+    .tick-label {{ font-family: Arial, sans-serif; font-size: 11px; fill: #666; }}
+    .panel-title {{ font-family: Arial, sans-serif; font-size: 13px; fill: #333; }}
+    .title {{ font-family: Arial, sans-serif; font-size: 20px; font-weight: bold; fill: #333; }}
+  </style>
+</defs>
+
+<!-- Background -->
+<rect width="{w}" height="{h}" fill="#f5f7fa"/>
+
+<!-- Title -->
+<text x="{cx}" y="30" class="title" text-anchor="middle">Weight and Bias Histograms</text>
+
+"##,
+        w = args.width,
+        h = args.height,
+        cx = args.width / 2,
+    ));
+
+    for (row, layer) in histograms.iter().enumerate() {
+        let y = margin + row as f64 * (panel_height + gap);
+        let panels = [
+            (&layer.weights, "Weights", SERIES_COLORS[0], margin),
+            (&layer.biases, "Biases", SERIES_COLORS[1], margin + panel_width + gap),
+        ];
+        svg.push_str(&format!("<!-- Layer {} -> {} -->\n", layer.layer, layer.layer + 1));
+        for (histogram, label, color, x) in panels {
+            let title = format!("Layer {} -> {} {}", layer.layer, layer.layer + 1, label);
+            svg.push_str(&histogram_panel(histogram, &title, color, x, y, panel_width, panel_height));
+        }
+    }
+
+    svg.push_str("\n</svg>");
+    svg
+}
+
+/// One bar chart panel with its title and min/max tick labels
+fn histogram_panel(
+    histogram: &Histogram,
+    title: &str,
+    color: &str,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+) -> String {
+    let title_height = 18.0;
+    let label_height = 16.0;
+    let plot_top = y + title_height;
+    let plot_height = (height - title_height - label_height).max(1.0);
+    let bottom = plot_top + plot_height;
+    let max_count = histogram.counts.iter().copied().max().unwrap_or(0).max(1) as f64;
+    let bar_width = width / histogram.counts.len() as f64;
+
+    let mut svg = format!(
+        r##"<text x="{x:.1}" y="{ty:.1}" class="panel-title">{title} (max count {max_count})</text>
+"##,
+        x = x,
+        ty = y + 12.0,
+        title = title,
+        max_count = max_count,
+    );
+
+    for (i, (&count, (lo, hi))) in histogram
+        .counts
+        .iter()
+        .zip(histogram.bin_edges())
+        .enumerate()
+    {
+        let bar_height = count as f64 / max_count * plot_height;
+        svg.push_str(&format!(
+            r##"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}" class="bar">
+  <title>[{:.4}, {:.4}): {}</title>
+</rect>
+"##,
+            x + i as f64 * bar_width,
+            bottom - bar_height,
+            bar_width,
+            bar_height,
+            color,
+            lo,
+            hi,
+            count
+        ));
+    }
+
+    svg.push_str(&format!(
+        r##"<line x1="{x:.1}" y1="{b:.1}" x2="{r:.1}" y2="{b:.1}" class="axis"/>
+<text x="{x:.1}" y="{ly:.1}" class="tick-label">{min:.3}</text>
+<text x="{r:.1}" y="{ly:.1}" class="tick-label" text-anchor="end">{max:.3}</text>
+"##,
+        x = x,
+        r = x + width,
+        b = bottom,
+        ly = bottom + 13.0,
+        min = histogram.min,
+        max = histogram.max,
+    ));
+
+    svg
+}
+
+/// A sample drawn over a decision boundary: its two inputs and its class
+type Point = (f64, f64, usize);
+
+/// Predict the classes of a grid over the example's input range
+///
+/// Without an example the range is the unit square and no samples are drawn.
+fn sample_boundary(checkpoint: &Checkpoint, args: &Args) -> Result<(DecisionGrid, Vec<Point>)> {
+    let name = args.example.as_deref().unwrap_or(&checkpoint.metadata.example);
+    let example = neural_network::examples::get_example(name);
+    if example.is_none() && args.example.is_some() {
+        anyhow::bail!("Example '{}' not found", name);
+    }
+
+    let (x_range, y_range) = match &example {
+        Some(example) => input_ranges(&example.inputs, 0.1)?,
+        None => {
+            println!("No example '{}' to take the input range from, plotting [0, 1] x [0, 1]", name);
+            ((0.0, 1.0), (0.0, 1.0))
+        }
+    };
+    let points = example
+        .iter()
+        .flat_map(|example| example.inputs.iter().zip(&example.targets))
+        .map(|(input, target)| (input[0], input[1], neural_network::training::class_of(target)))
+        .collect();
+
+    println!(
+        "Sampling the decision boundary on a {r}x{r} grid over [{:.2}, {:.2}] x [{:.2}, {:.2}]",
+        x_range.0,
+        x_range.1,
+        y_range.0,
+        y_range.1,
+        r = args.resolution
+    );
+    let mut model = InferenceModel::from(checkpoint.clone());
+    let grid = DecisionGrid::sample(&mut model, x_range, y_range, args.resolution as usize)?;
+    Ok((grid, points))
+}
+
+/// Red, green and blue of a `#rrggbb` color
+fn rgb(hex: &str) -> [u8; 3] {
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or(0);
+    [channel(1), channel(3), channel(5)]
+}
+
+/// Color of a grid point: its class color faded toward white as the model
+/// grows less confident
+fn cell_color(class: usize, confidence: f64) -> [u8; 3] {
+    let alpha = 0.15 + 0.6 * confidence;
+    rgb(SERIES_COLORS[class % SERIES_COLORS.len()]).map(|c| (255.0 - (255.0 - c as f64) * alpha).round() as u8)
+}
+
+/// Render the predicted class of every grid point as colored cells, with the
+/// example's samples on top
+///
+/// Saturated cells are confident predictions; the pale bands between classes
+/// show where the boundary lies.
+fn generate_boundary_svg(grid: &DecisionGrid, points: &[Point], args: &Args) -> String {
+    let width = args.width as f64;
+    let height = args.height as f64;
+    let margin = 60.0;
+    let side = (width.min(height) - 2.0 * margin).max(1.0);
+    let left = (width - side) / 2.0;
+    let top = margin;
+    let cell = side / grid.resolution as f64;
+    let (x_range, y_range) = (grid.x_range, grid.y_range);
+    let to_x = |x: f64| left + (x - x_range.0) / (x_range.1 - x_range.0) * side;
+    let to_y = |y: f64| top + (y_range.1 - y) / (y_range.1 - y_range.0) * side;
+
+    let mut svg = String::new();
+    svg.push_str(&format!(
+        r##"<?xml version="1.0" encoding="UTF-8"?>
+<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}">
+<defs>
+  <style>
+    .axis {{ stroke: #333; stroke-width: 1; fill: none; }}
+    .sample {{ stroke: #333; stroke-width: 1; }}
+    .tick-label {{ font-family: Arial, sans-serif; font-size: 11px; fill: #666; }}
+    .title {{ font-family: Arial, sans-serif; font-size: 20px; font-weight: bold; fill: #333; }}
+  </style>
+</defs>
+
+<!-- Background -->
+<rect width="{w}" height="{h}" fill="#f5f7fa"/>
+
+<!-- Title -->
+<text x="{cx}" y="30" class="title" text-anchor="middle">Decision Boundary</text>
+
+<!-- Grid -->
+"##,
+        w = args.width,
+        h = args.height,
+        cx = args.width / 2,
+    ));
+
+    for row in 0..grid.resolution {
+        for column in 0..grid.resolution {
+            let i = grid.index(row, column);
+            let [r, g, b] = cell_color(grid.classes[i], grid.confidence[i]);
+            // Cells overlap by a fraction of a pixel to avoid seams between them
+            svg.push_str(&format!(
+                "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"rgb({},{},{})\"/>\n",
+                left + column as f64 * cell,
+                top + row as f64 * cell,
+                cell + 0.5,
+                cell + 0.5,
+                r,
+                g,
+                b
+            ));
+        }
+    }
+
+    svg.push_str(&format!(
+        r##"
+<!-- Axes -->
+<rect x="{l:.1}" y="{t:.1}" width="{s:.1}" height="{s:.1}" class="axis"/>
+<text x="{l:.1}" y="{xl:.1}" class="tick-label" text-anchor="start">{x0:.2}</text>
+<text x="{r:.1}" y="{xl:.1}" class="tick-label" text-anchor="end">{x1:.2}</text>
+<text x="{yl:.1}" y="{b:.1}" class="tick-label" text-anchor="end">{y0:.2}</text>
+<text x="{yl:.1}" y="{yt:.1}" class="tick-label" text-anchor="end">{y1:.2}</text>
+
+<!-- Samples -->
+"##,
+        l = left,
+        t = top,
+        s = side,
+        r = left + side,
+        b = top + side,
+        xl = top + side + 16.0,
+        yl = left - 6.0,
+        yt = top + 10.0,
+        x0 = x_range.0,
+        x1 = x_range.1,
+        y0 = y_range.0,
+        y1 = y_range.1,
+    ));
+    for &(x, y, class) in points {
+        svg.push_str(&format!(
+            r##"<circle cx="{:.1}" cy="{:.1}" r="4" fill="{}" class="sample">
+  <title>({:.3}, {:.3}): class {}</title>
+</circle>
+"##,
+            to_x(x),
+            to_y(y),
+            SERIES_COLORS[class % SERIES_COLORS.len()],
+            x,
+            y,
+            class
+        ));
+    }
+
+    // Legend
+    svg.push_str(&format!(
+        "\n<!-- Legend -->\n<g transform=\"translate({:.1}, {:.1})\">\n",
+        left + side + 20.0,
+        top + 10.0
+    ));
+    for class in 0..grid.num_classes {
+        let y = class as f64 * 18.0;
+        svg.push_str(&format!(
+            r##"  <circle cx="6" cy="{y:.1}" r="5" fill="{color}" class="sample"/>
+  <text x="18" y="{ty:.1}" class="tick-label">Class {class}</text>
+"##,
+            y = y,
+            ty = y + 4.0,
+            color = SERIES_COLORS[class % SERIES_COLORS.len()],
+            class = class
+        ));
+    }
+    svg.push_str("</g>\n\n</svg>");
+
+    svg
+}
+
+/// Render the decision boundary as an image filling the whole canvas, with
+/// the example's samples as outlined dots
+fn boundary_image(grid: &DecisionGrid, points: &[Point], args: &Args) -> image::RgbImage {
+    let (width, height) = (args.width, args.height);
+    let resolution = grid.resolution;
+    let mut image = image::RgbImage::from_fn(width, height, |x, y| {
+        let column = (x as usize * resolution / width as usize).min(resolution - 1);
+        let row = (y as usize * resolution / height as usize).min(resolution - 1);
+        let i = grid.index(row, column);
+        image::Rgb(cell_color(grid.classes[i], grid.confidence[i]))
+    });
+
+    let (x_range, y_range) = (grid.x_range, grid.y_range);
+    for &(x, y, class) in points {
+        let cx = (x - x_range.0) / (x_range.1 - x_range.0) * width as f64;
+        let cy = (y_range.1 - y) / (y_range.1 - y_range.0) * height as f64;
+        let fill = rgb(SERIES_COLORS[class % SERIES_COLORS.len()]);
+        for dy in -4..=4 {
+            for dx in -4..=4 {
+                let (px, py) = (cx as i64 + dx, cy as i64 + dy);
+                let distance = dx * dx + dy * dy;
+                if distance > 16 || px < 0 || py < 0 || px >= width as i64 || py >= height as i64 {
+                    continue;
+                }
+                let color = if distance > 9 { [51, 51, 51] } else { fill };
+                image.put_pixel(px as u32, py as u32, image::Rgb(color));
+            }
+        }
+    }
+    image
+}
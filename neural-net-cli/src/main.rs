@@ -2,6 +2,7 @@
 ///
 /// Command-line interface for training and evaluating neural networks
 /// on classic logic gate problems (AND, OR, XOR).
+use anyhow::Context;
 use clap::{Parser, Subcommand};
 
 #[derive(Parser)]
@@ -9,360 +10,3086 @@ use clap::{Parser, Subcommand};
 #[command(about = "Neural Network Demonstration Platform", long_about = None)]
 #[command(version)]
 struct Cli {
+    /// Print a readable report, or JSON on stdout for scripts (train, eval, info, compare and checkpoints list; the report then goes to stderr)
+    #[arg(long, global = true, value_enum, default_value = "text")]
+    format: OutputFormat,
+
+    /// Show more training progress: -v adds the loss every 100 epochs, -vv everything
+    #[arg(short, long, global = true, action = clap::ArgAction::Count, conflicts_with = "quiet")]
+    verbose: u8,
+
+    /// Only show training warnings and errors
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// Level of the training progress shown and logged (overrides -v and -q)
+    #[arg(long, global = true, value_enum, value_name = "LEVEL")]
+    log_level: Option<LogLevel>,
+
+    /// Also append the training progress with timestamps to this file (debug level unless --log-level is given)
+    #[arg(long, global = true, value_name = "FILE")]
+    log_file: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+/// Set by `--format json`: stdout then carries only the JSON result
+static JSON_OUTPUT: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Whether the command prints JSON, see `--format`
+fn json_output() -> bool {
+    JSON_OUTPUT.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+/// Set when the terminal shows less than the info level, which hides the
+/// progress bar too
+static QUIET: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Heap allocation counting for `bench`
+///
+/// Behind the `count-allocations` feature, since the counter is shared by
+/// every thread and every command pays for it on each allocation.
+#[cfg(feature = "count-allocations")]
+mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// Heap allocations and reallocations made so far on any thread
+    static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+    /// The system allocator, counting every allocation in `ALLOCATIONS`
+    struct CountingAllocator;
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            unsafe { System.alloc(layout) }
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            unsafe { System.alloc_zeroed(layout) }
+        }
+
+        // The default would allocate, copy and free, so growing a buffer
+        // could never happen in place while benchmarking
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            unsafe { System.realloc(ptr, layout, new_size) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) }
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    pub fn allocations() -> u64 {
+        ALLOCATIONS.load(Ordering::Relaxed)
+    }
+}
+
+/// Allocation counter for `bench`, if built with `count-allocations`
+fn allocation_counter() -> Option<fn() -> u64> {
+    #[cfg(feature = "count-allocations")]
+    {
+        Some(counting::allocations)
+    }
+    #[cfg(not(feature = "count-allocations"))]
+    {
+        None
+    }
+}
+
+/// Send training progress to the terminal and, with `--log-file`, to a file
+///
+/// The terminal shows bare messages on stdout (stderr under `--format json`)
+/// at the level chosen with `-v`, `-q` or `--log-level`; the file gets every
+/// event at `--log-level`, or debug, with a timestamp and level.
+fn init_logging(cli: &Cli) -> anyhow::Result<()> {
+    use tracing_subscriber::{filter::LevelFilter, fmt, fmt::writer::BoxMakeWriter, prelude::*};
+
+    let terminal_level = match (cli.log_level, cli.quiet, cli.verbose) {
+        (Some(level), _, _) => LevelFilter::from(level),
+        (None, true, _) => LevelFilter::WARN,
+        (None, false, 0) => LevelFilter::INFO,
+        (None, false, 1) => LevelFilter::DEBUG,
+        (None, false, _) => LevelFilter::TRACE,
+    };
+    QUIET.store(terminal_level < LevelFilter::INFO, std::sync::atomic::Ordering::Relaxed);
+    let writer = if json_output() { BoxMakeWriter::new(std::io::stderr) } else { BoxMakeWriter::new(std::io::stdout) };
+    let terminal = fmt::layer()
+        .without_time()
+        .with_level(false)
+        .with_target(false)
+        .with_ansi(false)
+        .with_writer(writer)
+        .with_filter(terminal_level);
+
+    let file = match &cli.log_file {
+        Some(path) => {
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Cannot open log file {}", path))?;
+            let level = cli.log_level.map_or(LevelFilter::DEBUG, LevelFilter::from);
+            Some(fmt::layer().with_ansi(false).with_target(false).with_writer(std::sync::Mutex::new(file)).with_filter(level))
+        }
+        None => None,
+    };
+
+    tracing_subscriber::registry().with(terminal).with(file).init();
+    Ok(())
+}
+
+/// `println!` for the readable report of a command that can print JSON; it
+/// moves to stderr under `--format json`
+macro_rules! report {
+    ($($arg:tt)*) => {
+        if json_output() {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+
+/// Failure category of a command, reported as the process exit code so
+/// scripts can branch on it instead of parsing stderr
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ErrorKind {
+    /// Any other failure
+    Failure,
+
+    /// Invalid arguments, from clap or found after parsing, e.g. an unknown example
+    Usage,
+
+    /// A file or directory that doesn't exist
+    NotFound,
+
+    /// Training produced a NaN or infinite loss
+    Diverged,
+
+    /// A checkpoint that is damaged, encrypted with another secret, from
+    /// another format version, or for an example this build doesn't have
+    IncompatibleCheckpoint,
+}
+
+impl ErrorKind {
+    /// Category of an error, from the first typed error in its chain
+    fn of(error: &anyhow::Error) -> Self {
+        use neural_network::{checkpoint::CheckpointError, training::DivergenceError};
+
+        if let Some(error) = error.downcast_ref::<CliError>() {
+            return error.kind;
+        }
+        if error.downcast_ref::<DivergenceError>().is_some() {
+            return ErrorKind::Diverged;
+        }
+        if error.downcast_ref::<CheckpointError>().is_some() {
+            return ErrorKind::IncompatibleCheckpoint;
+        }
+        let not_found = error
+            .chain()
+            .any(|cause| cause.downcast_ref::<std::io::Error>().is_some_and(|e| e.kind() == std::io::ErrorKind::NotFound));
+        if not_found { ErrorKind::NotFound } else { ErrorKind::Failure }
+    }
+
+    /// Exit code of the category; 2 matches clap's code for parse errors
+    fn exit_code(self) -> u8 {
+        match self {
+            ErrorKind::Failure => 1,
+            ErrorKind::Usage => 2,
+            ErrorKind::NotFound => 3,
+            ErrorKind::Diverged => 4,
+            ErrorKind::IncompatibleCheckpoint => 5,
+        }
+    }
+}
+
+/// An error whose category its type doesn't tell, such as an argument out
+/// of range
+#[derive(Debug)]
+struct CliError {
+    kind: ErrorKind,
+    message: String,
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// An error for invalid arguments, exiting with code 2
+fn usage_error(message: impl Into<String>) -> anyhow::Error {
+    CliError { kind: ErrorKind::Usage, message: message.into() }.into()
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// List available training examples
     List,
 
     /// Train a neural network on an example
-    Train {
-        /// Example to train on (and, or, xor)
+    Train(Box<TrainArgs>),
+
+    /// Resume training from a checkpoint
+    Resume(ResumeArgs),
+
+    /// Evaluate a trained model
+    Eval {
+        /// Path to trained model file
+        #[arg(short, long, visible_alias = "checkpoint")]
+        model: String,
+
+        /// Input values (comma-separated, e.g., "0.5,1.0" or "-1.0,0.5")
+        #[arg(short, long, allow_hyphen_values = true)]
+        input: Option<String>,
+
+        /// Also print the activations of every layer, from the input to the output
+        #[arg(long, requires = "input")]
+        activations: bool,
+
+        #[command(flatten)]
+        encryption: EncryptionArgs,
+    },
+
+    /// Predict every row of a CSV file of inputs and write the outputs as CSV
+    Predict {
+        /// Path to trained model file
+        #[arg(short, long, visible_alias = "checkpoint")]
+        model: String,
+
+        /// CSV file with one row of input values per sample, optionally after a header line
         #[arg(short, long)]
-        example: String,
+        data: String,
+
+        /// Output CSV file [default: print to stdout]
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Add a class column: the index of the largest output, or 0/1 for a single output
+        #[arg(long)]
+        argmax: bool,
+
+        #[command(flatten)]
+        encryption: EncryptionArgs,
+    },
+
+    /// Score a model on labeled data with a confusion matrix and per-class accuracy
+    Test {
+        /// Path to trained model file
+        #[arg(short, long, visible_alias = "checkpoint")]
+        model: String,
+
+        /// Built-in or registered example to test on
+        #[arg(short, long, conflicts_with = "data", required_unless_present = "data")]
+        example: Option<String>,
+
+        /// CSV or Parquet file with inputs followed by targets to test on
+        #[arg(short, long)]
+        data: Option<String>,
+
+        /// Number of trailing CSV or Parquet columns that are targets
+        #[arg(long, value_name = "N", default_value = "1", requires = "data", visible_alias = "target-cols")]
+        target_columns: usize,
+
+        /// Decision threshold of a single output
+        #[arg(long, default_value = "0.5")]
+        threshold: f64,
+
+        /// Also draw the confusion matrix as an SVG heatmap to this file
+        #[arg(long, value_name = "FILE")]
+        plot: Option<String>,
+
+        #[command(flatten)]
+        encryption: EncryptionArgs,
+    },
+
+    /// Display detailed model information
+    Info {
+        /// Path to model file
+        #[arg(short, long, visible_alias = "checkpoint")]
+        model: String,
+    },
+
+    /// Plot the training history of a model as an SVG loss curve
+    PlotLoss {
+        /// Path to model file
+        #[arg(short, long, visible_alias = "model")]
+        checkpoint: String,
+
+        /// Output SVG file path
+        #[arg(short, long)]
+        output: String,
+
+        /// Plot the loss on a logarithmic axis
+        #[arg(long)]
+        log_scale: bool,
+
+        /// Width of the chart in pixels
+        #[arg(long, default_value = "1200")]
+        width: u32,
+
+        /// Height of the chart in pixels
+        #[arg(long, default_value = "800")]
+        height: u32,
+    },
+
+    /// Quantize a trained model to int8 weights for inference
+    Quantize {
+        /// Path to trained model file
+        #[arg(short, long)]
+        model: String,
+
+        /// Output file path for the quantized model
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Bits per weight, from 2 to 8
+        #[arg(long, default_value = "8", value_parser = clap::value_parser!(u8).range(2..=8))]
+        bits: u8,
+    },
 
-        /// Number of training epochs
-        #[arg(short = 'n', long, default_value = "10000")]
-        epochs: u32,
+    /// Zero the smallest weights of a trained model
+    Prune {
+        /// Path to trained model file
+        #[arg(short, long)]
+        model: String,
 
-        /// Learning rate
+        /// Fraction of weights to set to zero, from 0 to 1
         #[arg(short, long, default_value = "0.5")]
-        learning_rate: f64,
+        sparsity: f64,
+
+        /// Save the pruned model as a checkpoint
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Import a sigmoid MLP from an ONNX or safetensors file as a checkpoint
+    Import {
+        /// Path to the ONNX (.onnx) or safetensors (.safetensors) model
+        #[arg(short, long)]
+        model: String,
+
+        /// Output file path for the checkpoint
+        #[arg(short, long)]
+        output: String,
+
+        /// Learning rate used when the imported model is fine-tuned
+        /// [default: the safetensors metadata, otherwise 0.5]
+        #[arg(short, long)]
+        learning_rate: Option<f64>,
+
+        /// Example the model was trained on, so `resume` can fine-tune it
+        #[arg(short, long)]
+        example: Option<String>,
+    },
+
+    /// Export the parameters of a trained model as a safetensors file
+    Export {
+        /// Path to trained model file
+        #[arg(short, long)]
+        model: String,
+
+        /// Output file path for the safetensors file
+        #[arg(short, long)]
+        output: String,
+
+        /// Write an inference-only checkpoint instead: the network and
+        /// normalizers without history, optimizer state or lineage
+        #[arg(long)]
+        strip: bool,
+    },
+
+    /// Convert a model between checkpoint, safetensors, ONNX and npz files
+    Convert {
+        /// Model to convert: a checkpoint (.json or .bin, optionally .gz or
+        /// .zst) or a .safetensors, .onnx or .npz file
+        #[arg(short, long)]
+        input: String,
+
+        /// Output file; its extension picks the format as for the input
+        #[arg(short, long)]
+        output: String,
+    },
+
+    /// Compare two checkpoints of the same architecture
+    Compare {
+        /// Earlier checkpoint, e.g. the one a run was resumed from
+        before: String,
+
+        /// Later checkpoint
+        after: String,
 
-        /// Random seed for reproducible training
+        /// Also evaluate both models on this built-in or registered example
+        #[arg(short, long, conflicts_with = "data")]
+        example: Option<String>,
+
+        /// Also evaluate both models on this CSV or Parquet file
+        #[arg(short, long)]
+        data: Option<String>,
+
+        /// Number of trailing CSV or Parquet columns that are targets
+        #[arg(long, value_name = "N", default_value = "1", requires = "data")]
+        target_columns: usize,
+    },
+
+    /// Combine several trained models into an ensemble prediction
+    Ensemble {
+        /// Paths to trained model files (repeat or comma-separate)
+        #[arg(short, long, required = true, value_delimiter = ',')]
+        models: Vec<String>,
+
+        /// How member outputs are combined
+        #[arg(short, long, value_enum, default_value = "average")]
+        strategy: EnsembleStrategy,
+
+        /// Input values (comma-separated); defaults to the example's training inputs
+        #[arg(short, long, allow_hyphen_values = true)]
+        input: Option<String>,
+    },
+
+    /// Train every combination of hyperparameters in a TOML spec and rank them
+    Sweep {
+        /// Path to the TOML sweep spec
+        #[arg(short, long, visible_alias = "config")]
+        spec: String,
+
+        /// Write the ranked results to this file: markdown if it ends in .md, JSON otherwise
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Save the final checkpoint of the best trial to this file
+        #[arg(short, long)]
+        best: Option<String>,
+
+        /// Number of trials to train at once
+        #[arg(short = 'j', long, value_name = "N", default_value = "1")]
+        parallel: usize,
+    },
+
+    /// Sweep the learning rate over a short run to find a good starting value
+    LrFind {
+        /// Example to run the range test on (and, or, xor)
         #[arg(short, long)]
+        example: String,
+
+        /// Learning rate of the first step
+        #[arg(long, default_value = "0.0001")]
+        min_lr: f64,
+
+        /// Learning rate of the last step
+        #[arg(long, default_value = "100")]
+        max_lr: f64,
+
+        /// Number of steps (one epoch each) between the two rates
+        #[arg(long, default_value = "100")]
+        steps: usize,
+
+        /// Random seed for the initial weights
+        #[arg(long)]
         seed: Option<u64>,
 
-        /// Output file path for trained model
+        /// Write every recorded point as CSV to this file
         #[arg(short, long)]
         output: Option<String>,
     },
 
-    /// Resume training from a checkpoint
-    Resume {
-        /// Path to checkpoint file
-        #[arg(short, long)]
-        checkpoint: String,
+    /// Compare back-propagated gradients with finite differences on a random network
+    Gradcheck {
+        /// All layer sizes from input to output (comma-separated, e.g. 2,3,1)
+        #[arg(long, value_delimiter = ',', value_name = "SIZES", default_value = "2,3,1")]
+        layers: Vec<usize>,
+
+        /// Number of random samples the gradients are summed over
+        #[arg(short, long, default_value = "10")]
+        samples: usize,
+
+        /// Activation function to check
+        #[arg(short, long, value_enum, default_value = "sigmoid")]
+        activation: ActivationKind,
+
+        /// Random seed for the weights and samples
+        #[arg(long, default_value = "42")]
+        seed: u64,
+
+        /// Step added to and subtracted from each parameter
+        #[arg(long, default_value = "1e-5")]
+        epsilon: f64,
+
+        /// Largest relative error a layer may have
+        #[arg(long, default_value = "1e-6")]
+        tolerance: f64,
+    },
+
+    /// Measure feed-forward and backprop throughput and allocations
+    Bench {
+        /// All layer sizes from input to output (comma-separated)
+        #[arg(long, value_delimiter = ',', value_name = "SIZES", default_value = "784,128,10")]
+        layers: Vec<usize>,
+
+        /// Samples per mini-batch in the batch phase
+        #[arg(short, long, default_value = "32")]
+        batch: usize,
+
+        /// Activation function of the network
+        #[arg(short, long, value_enum, default_value = "sigmoid")]
+        activation: ActivationKind,
+
+        /// Seconds each phase runs for
+        #[arg(long, default_value = "1")]
+        seconds: f64,
+
+        /// Random seed for the weights and samples
+        #[arg(long, default_value = "42")]
+        seed: u64,
+    },
+
+    /// Start the REST API server with trained models already loaded
+    Serve {
+        /// Checkpoints to serve (repeat or comma-separate); each is served under its file name without extensions
+        #[arg(short, long, visible_alias = "model", required = true, value_delimiter = ',')]
+        checkpoint: Vec<String>,
+
+        /// Host address to bind to (0.0.0.0 for all interfaces)
+        #[arg(short = 'H', long, default_value = "127.0.0.1")]
+        host: String,
+
+        /// Port number to listen on
+        #[arg(short, long, default_value = "2421")]
+        port: u16,
+
+        #[command(flatten)]
+        encryption: EncryptionArgs,
+    },
+
+    /// Work with training data files
+    Data {
+        #[command(subcommand)]
+        command: DataCommand,
+    },
+
+    /// List or prune the checkpoints in a directory
+    Checkpoints {
+        #[command(subcommand)]
+        command: CheckpointsCommand,
+    },
+}
+
+/// Subcommands of `data`
+#[derive(Subcommand)]
+enum DataCommand {
+    /// Print per-column statistics and the class distribution of a dataset
+    Inspect {
+        /// CSV or Parquet file, or IDX images with --labels
+        #[arg(required_unless_present = "example", conflicts_with = "example")]
+        file: Option<String>,
+
+        /// Inspect a built-in or registered example instead of a file
+        #[arg(short, long)]
+        example: Option<String>,
+
+        /// IDX label file for IDX images
+        #[arg(long, value_name = "FILE", requires = "file")]
+        labels: Option<String>,
+
+        /// Number of trailing CSV or Parquet columns that are targets
+        #[arg(long, value_name = "N", default_value = "1")]
+        target_columns: usize,
+    },
+}
+
+/// Subcommands of `checkpoints`
+#[derive(Subcommand)]
+enum CheckpointsCommand {
+    /// List the checkpoints of a directory with their epoch, loss and save time
+    List {
+        /// Directory holding the checkpoints, e.g. the --snapshots directory of train
+        #[arg(short, long, default_value = ".")]
+        dir: String,
+
+        #[command(flatten)]
+        encryption: EncryptionArgs,
+    },
+
+    /// Delete the checkpoints of a directory that the retention flags do not keep
+    Clean {
+        /// Directory holding the checkpoints, e.g. the --snapshots directory of train
+        #[arg(short, long, default_value = ".")]
+        dir: String,
+
+        /// Keep this many of the most recently saved checkpoints
+        #[arg(long, value_name = "N", required_unless_present = "keep_best")]
+        keep_last: Option<usize>,
+
+        /// Keep this many checkpoints with the lowest final loss
+        #[arg(long, value_name = "N")]
+        keep_best: Option<usize>,
+
+        /// Only print what would be deleted
+        #[arg(long)]
+        dry_run: bool,
+
+        #[command(flatten)]
+        encryption: EncryptionArgs,
+    },
+}
+
+/// Options for the `resume` command
+///
+/// Hyperparameters given here override the checkpoint's and are recorded in
+/// the metadata of the saved model.
+#[derive(clap::Args)]
+struct ResumeArgs {
+    /// Path to checkpoint file
+    #[arg(short, long)]
+    checkpoint: String,
+
+    /// Number of additional training epochs
+    #[arg(short = 'n', long)]
+    epochs: u32,
+
+    /// Output file path for updated model
+    #[arg(short, long)]
+    output: Option<String>,
+
+    /// Continue with this learning rate, e.g. to decay it [default: the checkpoint's]
+    #[arg(short, long, value_name = "RATE")]
+    learning_rate: Option<f64>,
+
+    /// Continue averaging gradients over this many samples per update [default: the checkpoint's]
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    batch_size: Option<u64>,
+
+    /// Switch the activation function [default: the checkpoint's]
+    #[arg(long, value_enum)]
+    activation: Option<ActivationKind>,
+
+    /// Switch the update rule, starting its statistics from zero [default: the checkpoint's]
+    #[arg(long, value_enum)]
+    optimizer: Option<OptimizerKind>,
+
+    #[command(flatten)]
+    encryption: EncryptionArgs,
+}
+
+/// Options for the `train` command
+#[derive(Clone, clap::Args)]
+struct TrainArgs {
+    /// Example to train on (and, or, xor)
+    #[arg(short, long, required_unless_present = "data")]
+    example: Option<String>,
+
+    /// Train on this file instead: CSV, Parquet, or IDX images with --labels; streamed
+    /// unless --validation-split, --balance or --sample-weights need it in memory
+    #[arg(long, value_name = "FILE", conflicts_with = "example")]
+    data: Option<String>,
+
+    /// IDX label file for IDX images given with --data
+    #[arg(long, value_name = "FILE", requires = "data")]
+    labels: Option<String>,
+
+    /// Number of trailing CSV or Parquet columns that are targets
+    #[arg(long, visible_alias = "target-cols", value_name = "N", default_value = "1")]
+    target_columns: usize,
+
+    /// Samples read from --data at a time
+    #[arg(long, value_name = "N", default_value = "10000")]
+    chunk_size: usize,
+
+    /// Hidden layer sizes (comma-separated; default: the example's architecture, or inputs + outputs with --data)
+    #[arg(long, value_delimiter = ',', value_name = "SIZES")]
+    hidden: Option<Vec<usize>>,
+
+    /// All layer sizes from input to output (comma-separated, e.g. 2,8,4,1); the
+    /// first and last must match the data's inputs and outputs
+    #[arg(long, value_delimiter = ',', value_name = "SIZES", conflicts_with = "hidden")]
+    layers: Option<Vec<usize>>,
+
+    /// Number of training epochs
+    #[arg(short = 'n', long, default_value = "10000")]
+    epochs: u32,
+
+    /// Learning rate
+    #[arg(short, long, default_value = "0.5")]
+    learning_rate: f64,
+
+    /// Activation function of every layer
+    #[arg(long, value_enum, default_value = "sigmoid")]
+    activation: ActivationKind,
+
+    /// Update rule; momentum and Adam keep running statistics in the checkpoint
+    #[arg(long, value_enum, default_value = "sgd")]
+    optimizer: OptimizerKind,
+
+    /// Random seed for the initial weights and shuffling (drawn at random and saved with the model if not given)
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Shuffle the training samples every epoch (seeded by --seed)
+    #[arg(long)]
+    shuffle: bool,
+
+    /// Make the run bit-for-bit reproducible (uses a fixed seed if --seed is not given)
+    #[arg(long)]
+    deterministic: bool,
+
+    /// Stop as soon as the loss falls below this value
+    #[arg(long, value_name = "LOSS")]
+    target_loss: Option<f64>,
+
+    /// Average gradients over this many samples per update (default: update after every sample)
+    #[arg(long, value_name = "N")]
+    batch_size: Option<usize>,
+
+    /// Add Gaussian noise of initial variance ETA to the gradients, annealed over epochs
+    #[arg(long, value_name = "ETA")]
+    gradient_noise: Option<f64>,
+
+    /// Add fresh Gaussian noise of this standard deviation to the inputs every epoch
+    #[arg(long, value_name = "STD")]
+    input_noise: Option<f64>,
+
+    /// Loss weight of each class (comma-separated, indexed by target class)
+    #[arg(long, value_delimiter = ',', value_name = "WEIGHTS", conflicts_with = "sample_weights")]
+    class_weights: Option<Vec<f64>>,
+
+    /// Loss weight of each training sample (comma-separated, in example order)
+    #[arg(long, value_delimiter = ',', value_name = "WEIGHTS")]
+    sample_weights: Option<Vec<f64>>,
+
+    /// Hold out this fraction of every class for validation (seeded by --seed, 42 if unset)
+    #[arg(long, value_name = "FRACTION", conflicts_with = "sample_weights")]
+    validation_split: Option<f64>,
+
+    /// Even out the class counts of the training data before training
+    #[arg(long, value_enum, value_name = "METHOD", conflicts_with = "sample_weights")]
+    balance: Option<BalanceKind>,
+
+    /// Scale the inputs with a normalizer fit on the training data (saved with the model)
+    #[arg(long, value_enum, value_name = "KIND")]
+    normalize: Option<NormalizeKind>,
+
+    /// Scale regression targets with a normalizer fit on the training data; eval maps predictions back
+    #[arg(long, value_enum, value_name = "KIND")]
+    normalize_targets: Option<NormalizeKind>,
+
+    /// Decision threshold for the reported accuracy, precision, recall and F1
+    #[arg(long, default_value = "0.5")]
+    threshold: f64,
+
+    /// Output file path for trained model
+    #[arg(short, long)]
+    output: Option<String>,
+
+    /// Also save the model to --output every N seconds while training
+    #[arg(long, value_name = "N", requires = "output")]
+    checkpoint_secs: Option<u64>,
+
+    /// Keep a snapshot of the model every --snapshot-every epochs in this directory, e.g. for visualize --animate
+    #[arg(long, value_name = "DIR")]
+    snapshots: Option<String>,
+
+    /// Epochs between snapshots
+    #[arg(long, value_name = "N", default_value = "100", requires = "snapshots", value_parser = clap::value_parser!(u32).range(1..))]
+    snapshot_every: u32,
+
+    /// Append per-epoch loss, metrics and learning rate to this CSV file
+    #[arg(long, value_name = "PATH")]
+    log_csv: Option<String>,
+
+    #[command(flatten)]
+    encryption: EncryptionArgs,
+
+    /// Write TensorBoard event files (loss, metrics, weight histograms) to this directory
+    #[arg(long, value_name = "DIR")]
+    tensorboard: Option<String>,
+
+    /// Keep running and retrain, rewriting --output, whenever the --data file changes
+    #[arg(long, requires = "data")]
+    watch: bool,
+}
+
+/// Combination strategy for the `ensemble` command
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum EnsembleStrategy {
+    /// Average member outputs
+    Average,
+    /// Majority vote over member predictions
+    Vote,
+}
+
+/// Activation function for the `train` and `resume` commands
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ActivationKind {
+    /// Logistic sigmoid, outputs in (0, 1)
+    Sigmoid,
+    /// Rectified linear unit, max(0, x)
+    Relu,
+    /// Hyperbolic tangent, outputs in (-1, 1)
+    Tanh,
+    /// Gaussian error linear unit
+    Gelu,
+}
+
+impl From<ActivationKind> for neural_network::activations::Activation {
+    fn from(kind: ActivationKind) -> Self {
+        use neural_network::activations::{GELU, RELU, SIGMOID, TANH};
+
+        match kind {
+            ActivationKind::Sigmoid => SIGMOID,
+            ActivationKind::Relu => RELU,
+            ActivationKind::Tanh => TANH,
+            ActivationKind::Gelu => GELU,
+        }
+    }
+}
+
+/// Update rule for the `train` and `resume` commands
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum OptimizerKind {
+    /// Plain gradient descent
+    Sgd,
+    /// Gradient descent with momentum 0.9
+    Momentum,
+    /// Adam with the usual betas
+    Adam,
+}
+
+impl From<OptimizerKind> for neural_network::optimizer::Optimizer {
+    fn from(kind: OptimizerKind) -> Self {
+        match kind {
+            OptimizerKind::Sgd => Self::Sgd,
+            OptimizerKind::Momentum => Self::momentum(),
+            OptimizerKind::Adam => Self::adam(),
+        }
+    }
+}
+
+/// Level of the `--log-level` option
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<LogLevel> for tracing_subscriber::filter::LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => Self::ERROR,
+            LogLevel::Warn => Self::WARN,
+            LogLevel::Info => Self::INFO,
+            LogLevel::Debug => Self::DEBUG,
+            LogLevel::Trace => Self::TRACE,
+        }
+    }
+}
+
+/// Output format of the commands, see `--format`
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum OutputFormat {
+    /// Readable sections and tables
+    Text,
+    /// One JSON document with the results
+    Json,
+}
+
+/// Input normalization for the `train` command
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum NormalizeKind {
+    /// Map each feature's training range to [0, 1]
+    #[value(name = "minmax")]
+    MinMax,
+    /// Standardize each feature to mean 0 and standard deviation 1
+    #[value(name = "zscore")]
+    ZScore,
+}
+
+impl From<NormalizeKind> for neural_network::normalize::Normalization {
+    fn from(kind: NormalizeKind) -> Self {
+        match kind {
+            NormalizeKind::MinMax => Self::MinMax,
+            NormalizeKind::ZScore => Self::ZScore,
+        }
+    }
+}
+
+/// Secret for encrypted checkpoints, shared by `train`, `resume`, `eval`, `predict`, `test` and `serve`
+#[derive(Clone, clap::Args)]
+struct EncryptionArgs {
+    /// Encrypt saved and decrypt loaded checkpoints with the 256-bit key in this file (raw or hex)
+    #[arg(long, value_name = "FILE")]
+    key_file: Option<String>,
+
+    /// Encrypt saved and decrypt loaded checkpoints with the password in this environment variable
+    #[arg(long, value_name = "VAR", conflicts_with = "key_file")]
+    password_env: Option<String>,
+}
+
+impl EncryptionArgs {
+    /// The secret given on the command line, if any
+    fn secret(&self) -> anyhow::Result<Option<neural_network::encryption::Secret>> {
+        use neural_network::encryption::Secret;
+
+        if let Some(path) = &self.key_file {
+            return Ok(Some(Secret::from_key_file(std::path::Path::new(path))?));
+        }
+        match &self.password_env {
+            Some(var) => {
+                let password = std::env::var(var)
+                    .map_err(|_| usage_error(format!("Environment variable {} with the checkpoint password is not set", var)))?;
+                if password.is_empty() {
+                    return Err(usage_error(format!("Environment variable {} holds an empty password", var)));
+                }
+                Ok(Some(Secret::Password(password)))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Class balancing for the `train` command
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum BalanceKind {
+    /// Repeat samples of smaller classes
+    Oversample,
+    /// Drop samples of larger classes
+    Undersample,
+}
+
+impl From<BalanceKind> for neural_network::sampling::Balance {
+    fn from(kind: BalanceKind) -> Self {
+        match kind {
+            BalanceKind::Oversample => Self::Oversample,
+            BalanceKind::Undersample => Self::Undersample,
+        }
+    }
+}
+
+fn main() -> std::process::ExitCode {
+    match run() {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {:?}", error);
+            std::process::ExitCode::from(ErrorKind::of(&error).exit_code())
+        }
+    }
+}
+
+fn run() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    if let OutputFormat::Json = cli.format {
+        if !matches!(
+            cli.command,
+            Commands::Train(_)
+                | Commands::Eval { .. }
+                | Commands::Info { .. }
+                | Commands::Compare { .. }
+                | Commands::Checkpoints { command: CheckpointsCommand::List { .. } }
+        ) {
+            return Err(usage_error("--format json is supported by train, eval, info, compare and checkpoints list"));
+        }
+        JSON_OUTPUT.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+    init_logging(&cli)?;
+
+    match cli.command {
+        Commands::List => {
+            cmd_list()?;
+        }
+        Commands::Train(args) if args.watch => {
+            cmd_watch(*args)?;
+        }
+        Commands::Train(args) => {
+            cmd_train(*args)?;
+        }
+        Commands::Resume(args) => {
+            cmd_resume(args)?;
+        }
+        Commands::Eval {
+            model,
+            input,
+            activations,
+            encryption,
+        } => {
+            cmd_eval(&model, input, activations, encryption.secret()?)?;
+        }
+        Commands::Predict {
+            model,
+            data,
+            output,
+            argmax,
+            encryption,
+        } => {
+            cmd_predict(&model, &data, output, argmax, encryption.secret()?)?;
+        }
+        Commands::Test {
+            model,
+            example,
+            data,
+            target_columns,
+            threshold,
+            plot,
+            encryption,
+        } => {
+            let (dataset, name) = evaluation_data(example.as_deref(), data.as_deref(), target_columns)?
+                .expect("clap requires --example or --data");
+            cmd_test(&model, dataset.as_ref(), &name, threshold, plot, encryption.secret()?)?;
+        }
+        Commands::Info { model } => {
+            cmd_info(&model)?;
+        }
+        Commands::PlotLoss {
+            checkpoint,
+            output,
+            log_scale,
+            width,
+            height,
+        } => {
+            let options = neural_network::plot::ChartOptions { width, height, log_scale };
+            cmd_plot_loss(&checkpoint, &output, &options)?;
+        }
+        Commands::Quantize { model, output, bits } => {
+            cmd_quantize(&model, output, bits)?;
+        }
+        Commands::Prune { model, sparsity, output } => {
+            cmd_prune(&model, sparsity, output)?;
+        }
+        Commands::Import {
+            model,
+            output,
+            learning_rate,
+            example,
+        } => {
+            cmd_import(&model, &output, learning_rate, example)?;
+        }
+        Commands::Export { model, output, strip } => {
+            cmd_export(&model, &output, strip)?;
+        }
+        Commands::Convert { input, output } => {
+            cmd_convert(&input, &output)?;
+        }
+        Commands::Compare {
+            before,
+            after,
+            example,
+            data,
+            target_columns,
+        } => {
+            cmd_compare(&before, &after, example.as_deref(), data.as_deref(), target_columns)?;
+        }
+        Commands::Ensemble {
+            models,
+            strategy,
+            input,
+        } => {
+            cmd_ensemble(&models, strategy, input)?;
+        }
+        Commands::Sweep {
+            spec,
+            output,
+            best,
+            parallel,
+        } => {
+            cmd_sweep(&spec, output, best, parallel)?;
+        }
+        Commands::LrFind {
+            example,
+            min_lr,
+            max_lr,
+            steps,
+            seed,
+            output,
+        } => {
+            cmd_lr_find(&example, min_lr, max_lr, steps, seed, output)?;
+        }
+        Commands::Bench {
+            layers,
+            batch,
+            activation,
+            seconds,
+            seed,
+        } => {
+            cmd_bench(layers, batch, activation, seconds, seed)?;
+        }
+        Commands::Gradcheck {
+            layers,
+            samples,
+            activation,
+            seed,
+            epsilon,
+            tolerance,
+        } => {
+            let checker = neural_network::gradcheck::GradCheck { epsilon, tolerance };
+            cmd_gradcheck(&layers, samples, activation, seed, checker)?;
+        }
+        Commands::Serve {
+            checkpoint,
+            host,
+            port,
+            encryption,
+        } => {
+            cmd_serve(&checkpoint, &host, port, encryption.secret()?)?;
+        }
+        Commands::Data {
+            command:
+                DataCommand::Inspect {
+                    file,
+                    example,
+                    labels,
+                    target_columns,
+                },
+        } => {
+            cmd_data_inspect(file.as_deref(), example.as_deref(), labels.as_deref(), target_columns)?;
+        }
+        Commands::Checkpoints {
+            command: CheckpointsCommand::List { dir, encryption },
+        } => {
+            cmd_checkpoints_list(&dir, encryption.secret()?)?;
+        }
+        Commands::Checkpoints {
+            command:
+                CheckpointsCommand::Clean {
+                    dir,
+                    keep_last,
+                    keep_best,
+                    dry_run,
+                    encryption,
+                },
+        } => {
+            let retention = neural_network::checkpoint_dir::Retention { keep_last, keep_best };
+            cmd_checkpoints_clean(&dir, retention, dry_run, encryption.secret()?)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// List available training examples
+fn cmd_list() -> anyhow::Result<()> {
+    use neural_network::examples;
+
+    println!("Available Examples:");
+    println!();
+
+    for name in examples::list_examples() {
+        let example = examples::get_example(name).unwrap();
+        println!("  {} - {}", name, example.description);
+    }
+
+    Ok(())
+}
+
+/// Train a neural network
+fn cmd_train(args: TrainArgs) -> anyhow::Result<()> {
+    use neural_network::{activations::Activation, callbacks::Checkpointer, examples, loggers::CsvLogger, network::Network, normalize::Normalization, optimizer::Optimizer, sampling::{Balance, class_counts, stratified_split}, tensorboard::TensorBoardWriter, training::{DETERMINISTIC_SEED, GradientNoise, SampleWeights, TrainingConfig, TrainingController, Validation}};
+    use neural_network::dataset::{Dataset, VecDataset};
+    use std::path::Path;
+
+    let TrainArgs {
+        example,
+        data: data_path,
+        labels,
+        target_columns,
+        chunk_size,
+        hidden,
+        layers,
+        epochs,
+        learning_rate,
+        activation,
+        optimizer,
+        seed,
+        shuffle,
+        deterministic,
+        target_loss,
+        batch_size,
+        gradient_noise,
+        input_noise,
+        class_weights,
+        sample_weights,
+        validation_split,
+        balance,
+        normalize,
+        normalize_targets,
+        threshold,
+        output,
+        checkpoint_secs,
+        snapshots,
+        snapshot_every,
+        log_csv,
+        encryption,
+        tensorboard,
+        watch: _,
+    } = args;
+    let encryption = encryption.secret()?;
+    // The split keeps its fixed default seed, so only the weights and shuffle vary between unseeded runs
+    let sampling_seed = seed.unwrap_or(DETERMINISTIC_SEED);
+    // Every run gets a seed, so any saved model can be reproduced with --seed
+    let seed = seed.unwrap_or_else(|| if deterministic { DETERMINISTIC_SEED } else { rand::random() });
+
+    // Load example, or open the file to stream
+    let (ex, stream, name) = match &data_path {
+        Some(path) => {
+            let (dataset, _) = open_data(path, labels.as_deref(), target_columns)?;
+            let name = Path::new(path).file_name().map_or(path.clone(), |n| n.to_string_lossy().into_owned());
+            (None, Some(dataset), name)
+        }
+        None => {
+            let example = example.expect("clap requires --example without --data");
+            let ex = examples::get_example(&example)
+                .ok_or_else(|| usage_error(format!("Unknown example: {}. Use 'list' to see available examples.", example)))?;
+            let name = ex.name.to_string();
+            (Some(ex), None, name)
+        }
+    };
+    let (inputs, outputs) = match (&ex, &stream) {
+        (Some(ex), _) => (ex.input_dim(), ex.target_dim()),
+        (None, Some(dataset)) => (dataset.input_dim(), dataset.target_dim()),
+        (None, None) => unreachable!("either an example or a data file is loaded"),
+    };
+    // Splitting, balancing and per-sample weights need every sample of a file in memory
+    let (stream, loaded) = match stream {
+        Some(dataset) if validation_split.is_some() || balance.is_some() || sample_weights.is_some() => {
+            let (inputs, targets) = dataset.to_vecs();
+            (None, Some(VecDataset::new(inputs, targets)?))
+        }
+        stream => (stream, None),
+    };
+    let arch = match (&layers, &hidden, &ex) {
+        (Some(layers), _, _) => {
+            if layers.len() < 2 || layers[0] != inputs || layers[layers.len() - 1] != outputs {
+                return Err(usage_error(format!(
+                    "--layers {:?} does not fit {}: the first size must be its {} inputs and the last its {} outputs",
+                    layers, name, inputs, outputs
+                )));
+            }
+            layers.clone()
+        }
+        (None, Some(hidden), _) => [vec![inputs], hidden.clone(), vec![outputs]].concat(),
+        (None, None, Some(ex)) => ex.recommended_arch.clone(),
+        (None, None, None) => vec![inputs, inputs + outputs, outputs],
+    };
+    if arch.contains(&0) {
+        return Err(usage_error(format!("Layer sizes must be positive, got {:?}", arch)));
+    }
+
+    tracing::info!("Training {} network", name);
+    if let Some(dataset) = &stream {
+        tracing::info!(
+            "Data: {} samples with {} inputs and {} outputs, streamed in chunks of {}",
+            dataset.len(),
+            inputs,
+            outputs,
+            chunk_size
+        );
+    }
+    if let Some(dataset) = &loaded {
+        tracing::info!("Data: {} samples with {} inputs and {} outputs, loaded into memory", dataset.len(), inputs, outputs);
+    }
+    tracing::info!("Architecture: {:?}", arch);
+    tracing::info!("Epochs: {}", epochs);
+    tracing::info!("Learning rate: {}", learning_rate);
+    let activation = Activation::from(activation);
+    tracing::info!("Activation: {}", activation.name);
+    let optimizer = Optimizer::from(optimizer);
+    tracing::info!("Optimizer: {}", optimizer.name());
+    tracing::info!("Seed: {}", seed);
+    if shuffle {
+        tracing::info!("Shuffling samples every epoch");
+    }
+    if deterministic {
+        tracing::info!("Deterministic mode");
+    }
+    if let Some(target) = target_loss {
+        tracing::info!("Target loss: {}", target);
+    }
+    if let Some(n) = batch_size {
+        tracing::info!("Batch size: {}", n);
+    }
+    let gradient_noise = gradient_noise.map(GradientNoise::new);
+    if let Some(noise) = &gradient_noise {
+        tracing::info!("Gradient noise: eta = {}, gamma = {}", noise.eta, noise.gamma);
+    }
+    if let Some(std_dev) = input_noise {
+        tracing::info!("Input noise: std dev {}", std_dev);
+    }
+    let sample_weights = match (class_weights, sample_weights) {
+        (Some(weights), _) => {
+            tracing::info!("Class weights: {:?}", weights);
+            SampleWeights::PerClass(weights)
+        }
+        (None, Some(weights)) => {
+            tracing::info!("Sample weights: {:?}", weights);
+            SampleWeights::PerSample(weights)
+        }
+        (None, None) => SampleWeights::None,
+    };
+
+    // Split and balance before anything looks at the training samples
+    let mut data = VecDataset::default();
+    let mut validation = Validation::None;
+    let samples: Option<&dyn Dataset> = match (&ex, &loaded) {
+        (Some(ex), _) => Some(ex),
+        (None, loaded) => loaded.as_ref().map(|dataset| dataset as &dyn Dataset),
+    };
+    if let Some(samples) = samples {
+        (data, validation) = match validation_split {
+            Some(fraction) => {
+                let (train, held_out) = stratified_split(samples, fraction, sampling_seed)?;
+                tracing::info!("Validation: {} of {} samples held out, stratified by class", held_out.len(), samples.len());
+                let (inputs, targets) = held_out.into_vecs();
+                (train, Validation::Set { inputs, targets })
+            }
+            None => {
+                let (inputs, targets) = samples.to_vecs();
+                (VecDataset::new(inputs, targets)?, Validation::None)
+            }
+        };
+    }
+    if let Some(kind) = balance {
+        let balance = Balance::from(kind);
+        let before = class_counts(&data);
+        data = balance.apply(&data, sampling_seed);
+        tracing::info!("Class balance ({}): {:?} -> {:?} samples", balance.name(), before, class_counts(&data));
+    }
+
+    let normalization = normalize.map(Normalization::from);
+    if let Some(kind) = normalization {
+        tracing::info!("Input normalization: {}", kind.name());
+    }
+    let target_normalization = normalize_targets.map(Normalization::from);
+    if let Some(kind) = target_normalization {
+        tracing::info!("Target scaling: {}", kind.name());
+    }
+    #[cfg(feature = "gpu")]
+    match neural_network::matrix::gpu::adapter_name() {
+        Some(name) => tracing::info!("GPU: {}", name),
+        None => tracing::info!("GPU: no adapter found, using CPU"),
+    }
+    tracing::info!("");
+
+    // Create network with recommended architecture
+    let network = Network::new_seeded(arch, activation, learning_rate, seed);
+
+    // Create training config
+    let config = TrainingConfig {
+        epochs,
+        checkpoint_every: checkpoint_secs.map(std::time::Duration::from_secs),
+        checkpoint_path: output.as_ref().map(|p| Path::new(p).to_path_buf()),
+        verbose: false,
+        example_name: Some(name.clone()),
+        dataset_path: data_path.clone(),
+        shuffle,
+        seed: Some(seed),
+        deterministic,
+        target_loss,
+        batch_size,
+        sample_weights,
+        gradient_noise,
+        input_noise,
+        normalization,
+        target_normalization,
+        validation,
+        classification_threshold: Some(threshold),
+        optimizer,
+        encryption,
+        ..Default::default()
+    };
+
+    // Create training controller
+    let mut controller = TrainingController::new(network, config);
+
+    if let Some(path) = &log_csv {
+        controller.add_callback(CsvLogger::create(Path::new(path))?);
+    }
+    if let Some(dir) = &snapshots {
+        std::fs::create_dir_all(dir)?;
+        tracing::info!("Snapshots: every {} epochs in {}", snapshot_every, dir);
+        let snapshotter = Checkpointer::new(Path::new(dir).join("snapshot.json"), name.clone());
+        controller.add_callback(snapshotter.every_epochs(snapshot_every).numbered());
+    }
+    if let Some(dir) = &tensorboard {
+        let writer = TensorBoardWriter::create(Path::new(dir))?;
+        tracing::info!("TensorBoard events: {}", writer.path().display());
+        controller.add_callback(writer);
+    }
+
+    cancel_on_ctrl_c(&controller)?;
+
+    let pb = progress_bar(&mut controller, epochs);
+
+    // Train network
+    let history = match stream {
+        Some(dataset) => controller.train_streaming(dataset, chunk_size)?,
+        None => controller.train_dataset(&data)?,
+    };
+    if controller.is_cancelled() {
+        pb.abandon_with_message("Training interrupted");
+        finish_interrupted(&controller, output.as_deref())?;
+    }
+    if history.len() < epochs as usize {
+        // Leave the bar at the last epoch trained
+        pb.abandon_with_message("Training complete!");
+        if let Some(target) = target_loss {
+            tracing::info!("");
+            tracing::info!("Reached target loss {} after {} of {} epochs", target, history.len(), epochs);
+        }
+    } else {
+        pb.finish_with_message("Training complete!");
+    }
+    if let Some(last) = history.last() {
+        tracing::info!(
+            "Training time: {} ({:.2}ms per epoch)",
+            format_secs(last.elapsed_secs),
+            last.elapsed_secs * 1000.0 / history.len() as f64
+        );
+    }
+
+    if let Some(metrics) = history.last().and_then(|r| r.metrics) {
+        tracing::info!("");
+        tracing::info!("Final metrics (threshold {}): {}", threshold, metrics);
+    }
+    if let Some(last) = history.last()
+        && let Some(loss) = last.validation_loss
+    {
+        tracing::info!("Final validation loss: {:.6}", loss);
+        if let Some(metrics) = last.validation_metrics {
+            tracing::info!("Validation metrics: {}", metrics);
+        }
+    }
+
+    // Show which outputs are lagging for multi-output examples
+    if let Some(last) = history.last()
+        && last.output_losses.len() > 1
+    {
+        tracing::info!("");
+        tracing::info!("Final loss per output:");
+        for (i, loss) in last.output_losses.iter().enumerate() {
+            tracing::info!("  Output {}: {:.6}", i, loss);
+        }
+    }
+
+    // Save model if output path specified
+    if let Some(output_path) = &output {
+        tracing::info!("");
+        tracing::info!("Saving model to: {}", output_path);
+        controller.save_checkpoint(Path::new(output_path))?;
+        tracing::info!("Model saved successfully!");
+    }
+
+    if json_output() {
+        let result = serde_json::json!({
+            "name": name,
+            "data": data_path,
+            "architecture": controller.network().layers,
+            "activation": activation.name,
+            "optimizer": optimizer.name(),
+            "seed": seed,
+            "epochs": history.len(),
+            "final_loss": history.last().map(|r| r.loss),
+            "final_epoch": history.last(),
+            "output": output,
+        });
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    }
+
+    Ok(())
+}
+
+/// A data file opened for streaming, with its column names if it has them
+type DataFile = (Box<dyn neural_network::dataset::Dataset + Send>, Option<Vec<String>>);
+
+/// Open a data file given on the command line: IDX images with labels, a
+/// Parquet file by its `.parquet` extension, CSV otherwise
+fn open_data(path: &str, labels: Option<&str>, target_columns: usize) -> anyhow::Result<DataFile> {
+    use neural_network::streaming::{CsvDataset, IdxDataset};
+    use std::path::Path;
+
+    let path = Path::new(path);
+    if let Some(labels) = labels {
+        return Ok((Box::new(IdxDataset::open(path, Path::new(labels))?), None));
+    }
+    if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("parquet")) {
+        #[cfg(feature = "parquet")]
+        {
+            let data = neural_network::streaming::ParquetDataset::open(path, target_columns)?;
+            let names = data.column_names().to_vec();
+            return Ok((Box::new(data), Some(names)));
+        }
+        #[cfg(not(feature = "parquet"))]
+        anyhow::bail!("Reading {} needs a build with --features parquet", path.display());
+    }
+    let data = CsvDataset::open(path, target_columns)?;
+    let header = data.header().map(<[String]>::to_vec);
+    Ok((Box::new(data), header))
+}
+
+/// Print statistics of a data file or example
+fn cmd_data_inspect(
+    file: Option<&str>,
+    example: Option<&str>,
+    labels: Option<&str>,
+    target_columns: usize,
+) -> anyhow::Result<()> {
+    use neural_network::examples;
+
+    let (data, header): DataFile = match (file, example) {
+        (Some(path), _) => open_data(path, labels, target_columns)?,
+        (None, Some(name)) => {
+            let ex = examples::get_example(name)
+                .ok_or_else(|| usage_error(format!("Unknown example: {}. Use 'list' to see available examples.", name)))?;
+            (Box::new(ex), None)
+        }
+        (None, None) => return Err(usage_error("Pass a data file or --example")),
+    };
+    let summary = data.describe();
+
+    println!("Dataset: {}", file.or(example).unwrap_or_default());
+    println!("Samples: {}", summary.samples);
+    let columns = summary.inputs.len() + summary.targets.len();
+    let names: Vec<String> = match header {
+        Some(names) if names.len() == columns => names,
+        _ => (0..summary.inputs.len())
+            .map(|j| format!("input {}", j))
+            .chain((0..summary.targets.len()).map(|j| format!("target {}", j)))
+            .collect(),
+    };
+    let width = names.iter().map(String::len).max().unwrap_or(0).max(6);
+    for (title, stats, names) in [
+        ("Inputs", &summary.inputs, &names[..summary.inputs.len()]),
+        ("Targets", &summary.targets, &names[summary.inputs.len()..]),
+    ] {
+        println!();
+        println!("{} ({}):", title, stats.len());
+        println!("  {:<width$} {:>12} {:>12} {:>12} {:>12}", "Column", "Mean", "Std", "Min", "Max");
+        for (name, s) in names.iter().zip(stats.iter()) {
+            println!(
+                "  {:<width$} {:>12.6} {:>12.6} {:>12.6} {:>12.6}",
+                name, s.mean, s.std_dev, s.min, s.max
+            );
+        }
+    }
+
+    println!();
+    println!("Class distribution:");
+    for (class, &count) in summary.class_counts.iter().enumerate() {
+        println!(
+            "  Class {}: {} ({:.1}%)",
+            class,
+            count,
+            count as f64 * 100.0 / summary.samples as f64
+        );
+    }
+
+    // Point out columns that would break or stall training
+    let mut warnings = Vec::new();
+    for (name, s) in names.iter().zip(summary.inputs.iter().chain(&summary.targets)) {
+        if s.non_finite > 0 {
+            warnings.push(format!("{} has {} NaN or infinite values", name, s.non_finite));
+        }
+        if s.std_dev == 0.0 {
+            warnings.push(format!("{} is constant", name));
+        }
+    }
+    for (class, &count) in summary.class_counts.iter().enumerate() {
+        if count == 0 {
+            warnings.push(format!("class {} has no samples", class));
+        }
+    }
+    if !warnings.is_empty() {
+        println!();
+        println!("Warnings:");
+        for warning in warnings {
+            println!("  {}", warning);
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the checkpoints of a directory, oldest file name first
+fn cmd_checkpoints_list(dir: &str, secret: Option<neural_network::encryption::Secret>) -> anyhow::Result<()> {
+    use neural_network::checkpoint_dir;
+    use std::path::Path;
+
+    let entries = checkpoint_dir::scan(Path::new(dir), secret.as_ref())?;
+    if json_output() {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    println!("Checkpoints in {}: {}", dir, entries.len());
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let names: Vec<String> = entries
+        .iter()
+        .map(|e| e.path.file_name().unwrap_or_default().to_string_lossy().into_owned())
+        .collect();
+    let width = names.iter().map(String::len).max().unwrap_or(0).max(4);
+    println!();
+    println!("  {:<width$} {:>8} {:>12} {:>10}  Saved", "File", "Epochs", "Loss", "Bytes");
+    for (name, entry) in names.iter().zip(&entries) {
+        match &entry.metadata {
+            Some(metadata) => {
+                let loss = metadata.final_loss.map_or("-".to_string(), |loss| format!("{:.6}", loss));
+                println!(
+                    "  {:<width$} {:>8} {:>12} {:>10}  {}",
+                    name, metadata.epoch, loss, entry.size_bytes, metadata.timestamp
+                );
+            }
+            None => println!("  {:<width$} unreadable: {}", name, entry.error.as_deref().unwrap_or_default()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Delete the checkpoints of a directory that `retention` does not keep
+fn cmd_checkpoints_clean(
+    dir: &str,
+    retention: neural_network::checkpoint_dir::Retention,
+    dry_run: bool,
+    secret: Option<neural_network::encryption::Secret>,
+) -> anyhow::Result<()> {
+    use neural_network::checkpoint_dir;
+    use std::path::Path;
+
+    let entries = checkpoint_dir::scan(Path::new(dir), secret.as_ref())?;
+    let removals = retention.removals(&entries);
+    let readable = entries.iter().filter(|e| e.metadata.is_some()).count();
+    let skipped = entries.len() - readable;
+
+    let verb = if dry_run { "Would delete" } else { "Deleted" };
+    let mut freed = 0;
+    for entry in &removals {
+        if !dry_run {
+            std::fs::remove_file(&entry.path)
+                .with_context(|| format!("Failed to delete {}", entry.path.display()))?;
+        }
+        freed += entry.size_bytes;
+        println!("{} {}", verb, entry.path.display());
+    }
+    println!(
+        "{} {} of {} checkpoints ({} bytes), kept {}",
+        verb,
+        removals.len(),
+        readable,
+        freed,
+        readable - removals.len()
+    );
+    if skipped > 0 {
+        println!("Skipped {} unreadable files; see 'checkpoints list'", skipped);
+    }
+
+    Ok(())
+}
+
+/// Resume training from a checkpoint
+fn cmd_resume(args: ResumeArgs) -> anyhow::Result<()> {
+    use neural_network::{activations::Activation, checkpoint::Checkpoint, network::Network, optimizer::Optimizer, training::{TrainingConfig, TrainingController}};
+    use std::path::Path;
+
+    let ResumeArgs {
+        checkpoint,
+        epochs,
+        output,
+        learning_rate,
+        batch_size,
+        activation,
+        optimizer,
+        encryption,
+    } = args;
+    let encryption = encryption.secret()?;
+    let checkpoint = checkpoint.as_str();
+    let checkpoint_path = Path::new(checkpoint);
+
+    tracing::info!("Resuming training from checkpoint: {}", checkpoint);
+    tracing::info!("Additional epochs: {}", epochs);
+    tracing::info!("");
+
+    // Load checkpoint to get training data info
+    let checkpoint = Checkpoint::load_with_secret(checkpoint_path, encryption.as_ref())?;
+    let metadata = checkpoint.metadata.clone();
+    let state = checkpoint.training_state.clone().unwrap_or_default();
+    let recorded = checkpoint.history.len();
+    let network = Network::from_checkpoint(checkpoint)?;
+
+    tracing::info!("Loaded checkpoint:");
+    tracing::info!("  Architecture: {:?}", network.layers);
+    tracing::info!("  Previous epochs: {}", metadata.epoch);
+    tracing::info!("  Example: {}", metadata.example);
+    tracing::info!("  Learning rate: {}", metadata.learning_rate);
+    tracing::info!("  Activation: {}", network.activation.name);
+    tracing::info!("  Optimizer: {}", state.optimizer.name());
+    if state.shuffle {
+        tracing::info!("  Shuffling samples every epoch");
+    }
+    if recorded > 0 {
+        tracing::info!("  Recorded history: {} epochs (continued in the saved model)", recorded);
+    }
+    tracing::info!("");
+
+    // Hyperparameters that differ from the checkpoint's, as (name, before, after)
+    let mut overrides = Vec::new();
+    if let Some(rate) = learning_rate
+        && rate != network.learning_rate
+    {
+        overrides.push(("learning_rate", network.learning_rate.to_string(), rate.to_string()));
+    }
+    let batch_size = batch_size.map_or(state.batch_size, |n| Some(n as usize));
+    if batch_size != state.batch_size {
+        let show = |size: Option<usize>| size.unwrap_or(1).to_string();
+        overrides.push(("batch_size", show(state.batch_size), show(batch_size)));
+    }
+    let activation = activation.map(Activation::from);
+    if let Some(activation) = activation
+        && activation.name != network.activation.name
+    {
+        overrides.push(("activation", network.activation.name.to_string(), activation.name.to_string()));
+    }
+    let optimizer = optimizer.map_or(state.optimizer, Optimizer::from);
+    if optimizer != state.optimizer {
+        overrides.push(("optimizer", state.optimizer.name().to_string(), optimizer.name().to_string()));
+    }
+    for (name, before, after) in &overrides {
+        tracing::info!("Switching {}: {} -> {}", name, before, after);
+    }
+
+    // Get training data from example
+    use neural_network::examples;
+    let ex = examples::get_example(&metadata.example)
+        .ok_or_else(|| CliError {
+            kind: ErrorKind::IncompatibleCheckpoint,
+            message: format!("Example '{}' not found", metadata.example),
+        })?;
+
+    // Create training config
+    let config = TrainingConfig {
+        epochs,
+        checkpoint_interval: if output.is_some() { Some(epochs) } else { None },
+        checkpoint_path: output.as_ref().map(|p| Path::new(p).to_path_buf()),
+        verbose: false,
+        example_name: Some(metadata.example.clone()),
+        // Continue with the settings the optimizer and shuffle state belong to
+        shuffle: state.shuffle,
+        batch_size,
+        optimizer,
+        // Decrypt the checkpoint and encrypt the updated model with the same secret
+        encryption,
+        ..Default::default()
+    };
+
+    // Resume training
+    let mut controller = TrainingController::from_checkpoint(checkpoint_path, config)?;
+    if let Some(activation) = activation {
+        controller.network_mut().activation = activation;
+    }
+    if let Some(rate) = learning_rate {
+        controller.network_mut().learning_rate = rate;
+    }
+    for (name, before, after) in overrides {
+        controller.record_override(name, before, after);
+    }
+
+    cancel_on_ctrl_c(&controller)?;
+
+    let pb = progress_bar(&mut controller, epochs);
+    let history = controller.train_dataset(&ex)?;
+    if controller.is_cancelled() {
+        pb.abandon_with_message("Training interrupted");
+        finish_interrupted(&controller, output.as_deref())?;
+    }
+    pb.finish_with_message("Training complete!");
+    if let Some(last) = history.last() {
+        tracing::info!(
+            "Training time: {} ({:.2}ms per epoch), final loss: {:.6}",
+            format_secs(last.elapsed_secs),
+            last.elapsed_secs * 1000.0 / history.len() as f64,
+            last.loss
+        );
+    }
+
+    // Save if output specified
+    if let Some(output_path) = output {
+        tracing::info!("");
+        tracing::info!("Model saved to: {}", output_path);
+    }
+
+    Ok(())
+}
+
+/// Show the progress of a run of `epochs` epochs on a progress bar
+///
+/// The bar shows the epoch, the latest loss and accuracy and the estimated
+/// time left, and within epochs longer than a second the batch and running
+/// loss. It is hidden under `-q`. The loss is also logged every 100 epochs at
+/// the debug level and every epoch at the trace level.
+fn progress_bar(controller: &mut neural_network::training::TrainingController, epochs: u32) -> indicatif::ProgressBar {
+    use indicatif::{ProgressBar, ProgressStyle};
+    use neural_network::callbacks::{from_batch_fn, from_fn};
+
+    let pb = if QUIET.load(std::sync::atomic::Ordering::Relaxed) { ProgressBar::hidden() } else { ProgressBar::new(epochs as u64) };
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{msg} [{bar:40.cyan/blue}] {pos}/{len} ({prefix})")
+            .unwrap()
+            .progress_chars("#>-"),
+    );
+    pb.set_message("Training");
+    pb.set_prefix("ETA -");
+
+    // Add progress callback (clone pb for the closure)
+    let pb_clone = pb.clone();
+    controller.add_callback(from_fn(move |record, _network| {
+        pb_clone.set_position(record.epoch as u64);
+        pb_clone.set_prefix(format!("ETA {}", format_secs(record.eta_secs(epochs))));
+        if record.epoch % 100 == 0 || record.epoch == 1 {
+            let accuracy = record.metrics.map_or(0.0, |m| m.accuracy);
+            pb_clone.set_message(format!(
+                "Training (loss: {:.6}, accuracy: {:.1}%)",
+                record.loss,
+                accuracy * 100.0
+            ));
+            if tracing::enabled!(tracing::Level::DEBUG) {
+                pb_clone.suspend(|| tracing::debug!("Epoch {}: loss {:.6}, accuracy {:.1}%", record.epoch, record.loss, accuracy * 100.0));
+            }
+        }
+        if tracing::enabled!(tracing::Level::TRACE) {
+            pb_clone.suspend(|| tracing::trace!("Epoch {}: loss {:.6}, gradient norm {:.6}", record.epoch, record.loss, record.gradient_norm));
+        }
+    }));
+
+    // Show progress within epochs that take longer than a second
+    let pb_clone = pb.clone();
+    controller.add_callback(from_batch_fn(std::time::Duration::from_secs(1), move |ctx| {
+        pb_clone.set_message(format!(
+            "Training (epoch {}: batch {}/{}, running loss: {:.6})",
+            ctx.epoch,
+            ctx.batch + 1,
+            ctx.batches,
+            ctx.running_loss
+        ));
+    }));
+    pb
+}
+
+/// Format a duration in seconds as `1h02m`, `3m05s` or `4.2s`
+fn format_secs(secs: f64) -> String {
+    let whole = secs.round() as u64;
+    if whole >= 3600 {
+        format!("{}h{:02}m", whole / 3600, whole % 3600 / 60)
+    } else if whole >= 60 {
+        format!("{}m{:02}s", whole / 60, whole % 60)
+    } else {
+        format!("{:.1}s", secs)
+    }
+}
+
+/// Token of the run that Ctrl-C stops, if one is training
+static CTRL_C_TOKEN: std::sync::Mutex<Option<neural_network::training::CancellationToken>> = std::sync::Mutex::new(None);
+
+/// Stop `controller`'s training at the next weight update on Ctrl-C
+///
+/// A second Ctrl-C, or one while nothing is training, exits immediately
+/// without saving. The handler is installed once, so repeated runs under
+/// `train --watch` hand it their own token.
+fn cancel_on_ctrl_c(controller: &neural_network::training::TrainingController) -> anyhow::Result<()> {
+    static INSTALLED: std::sync::OnceLock<()> = std::sync::OnceLock::new();
+
+    *CTRL_C_TOKEN.lock().unwrap() = Some(controller.cancellation_token());
+    if INSTALLED.get().is_none() {
+        ctrlc::set_handler(|| match CTRL_C_TOKEN.lock().unwrap().as_ref() {
+            Some(token) if !token.is_cancelled() => token.cancel(),
+            _ => std::process::exit(130),
+        })?;
+        let _ = INSTALLED.set(());
+    }
+    Ok(())
+}
+
+/// Train once, then again every time the `--data` file changes
+///
+/// The file's directory is watched rather than the file itself, since many
+/// editors save by replacing the file. Events that arrive within
+/// `WATCH_SETTLE` of each other count as one change. A run that fails, e.g.
+/// on a half-written file, is reported and the watch goes on.
+fn cmd_watch(args: TrainArgs) -> anyhow::Result<()> {
+    use notify::{RecursiveMode, Watcher};
+    use std::path::Path;
+    use std::sync::mpsc;
+
+    const WATCH_SETTLE: std::time::Duration = std::time::Duration::from_millis(300);
+
+    let data = args.data.clone().expect("clap requires --data with --watch");
+    let path = std::fs::canonicalize(&data).with_context(|| format!("Cannot watch {}", data))?;
+    let (dir, file_name) = (path.parent().unwrap_or(Path::new("/")).to_path_buf(), path.file_name().map(|n| n.to_owned()));
+
+    let (sender, changes) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event
+            && (event.kind.is_create() || event.kind.is_modify())
+            && event.paths.iter().any(|p| p.file_name() == file_name.as_deref())
+        {
+            let _ = sender.send(());
+        }
+    })?;
+    watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+
+    for run in 1.. {
+        if run > 1 {
+            tracing::info!("");
+            tracing::info!("{} changed, retraining (run {})", data, run);
+        }
+        if let Err(e) = cmd_train(args.clone()) {
+            tracing::error!("Training failed: {:#}", e);
+        }
+        // Ctrl-C between runs exits instead of cancelling the finished run
+        CTRL_C_TOKEN.lock().unwrap().take();
+
+        tracing::info!("");
+        tracing::info!("Watching {} for changes (Ctrl-C to stop)", data);
+        changes.recv()?;
+        while changes.recv_timeout(WATCH_SETTLE).is_ok() {}
+    }
+    Ok(())
+}
+
+/// Save a final checkpoint of an interrupted run to `output` (if given) and
+/// exit with the conventional Ctrl-C status
+fn finish_interrupted(
+    controller: &neural_network::training::TrainingController,
+    output: Option<&str>,
+) -> anyhow::Result<()> {
+    let epoch = controller.history().last().map_or(0, |r| r.epoch);
+    tracing::info!("");
+    tracing::info!("Interrupted after epoch {}", epoch);
+    if let Some(path) = output {
+        controller.save_checkpoint(std::path::Path::new(path))?;
+        tracing::info!("Checkpoint saved to: {}", path);
+        tracing::info!("Continue with: neural-net-cli resume --checkpoint {} --epochs <N>", path);
+    }
+    std::process::exit(130);
+}
+
+/// Evaluate a trained model
+fn cmd_eval(
+    model: &str,
+    input: Option<String>,
+    activations: bool,
+    encryption: Option<neural_network::encryption::Secret>,
+) -> anyhow::Result<()> {
+    use neural_network::{checkpoint::Checkpoint, network::Network};
+    use std::path::Path;
+
+    let model_path = Path::new(model);
+
+    // Load model
+    let checkpoint = Checkpoint::load_with_secret(model_path, encryption.as_ref())?;
+    let normalizer = checkpoint.normalizer.clone();
+    let target_normalizer = checkpoint.target_normalizer.clone();
+    let metadata = checkpoint.metadata.clone();
+    let mut network = Network::from_checkpoint(checkpoint)?;
+
+    // Display model info
+    report!("Loaded model: {}", model);
+    report!("  Example: {}", metadata.example);
+    report!("  Architecture: {:?}", network.layers);
+    report!("  Training epochs: {}", metadata.epoch);
+    report!("  Learning rate: {}", metadata.learning_rate);
+    if let Some(normalizer) = &normalizer {
+        report!("  Input normalization: {}", normalizer.kind().name());
+    }
+    if let Some(normalizer) = &target_normalizer {
+        report!("  Target scaling: {}", normalizer.kind().name());
+    }
+    report!();
+    let mut result = serde_json::json!({
+        "model": model,
+        "example": metadata.example,
+        "architecture": network.layers,
+        "epochs": metadata.epoch,
+    });
+
+    // Parse input if provided
+    if let Some(input_str) = input {
+        let inputs: Result<Vec<f64>, _> = input_str
+            .split(',')
+            .map(|s| s.trim().parse::<f64>())
+            .collect();
+
+        let inputs = inputs.map_err(|e| {
+            usage_error(format!("Invalid input format: {}. Expected comma-separated numbers (e.g., '0.0,1.0')", e))
+        })?;
+
+        // Run prediction on the inputs as scaled for training
+        let scaled = normalizer.as_ref().map_or_else(|| inputs.clone(), |n| n.transform(&inputs));
+        let input_matrix = neural_network::matrix::Matrix::from(scaled);
+        let output = network
+            .try_feed_forward(input_matrix)
+            .map_err(|e| usage_error(format!("Invalid input dimensions: {}", e)))?;
+
+        // Display results in the units of the training targets
+        let output = match &target_normalizer {
+            Some(normalizer) => normalizer.inverse_transform(&output.data),
+            None => output.data,
+        };
+        report!("Input: {:?}", inputs);
+        report!("Output: {:?}", output);
+        result["input"] = serde_json::json!(inputs);
+        result["output"] = serde_json::json!(output);
+
+        // Activations are those the network computed, on the normalized scale
+        if activations {
+            let layers = network.get_activations();
+            report!();
+            report!("Activations:");
+            for (i, values) in layers.iter().enumerate() {
+                let role = match i {
+                    0 => " (input)",
+                    _ if i + 1 == layers.len() => " (output)",
+                    _ => "",
+                };
+                report!("  Layer {}{}: {:?}", i, role, values);
+            }
+            result["activations"] = serde_json::json!(layers);
+        }
+    } else {
+        report!("No input provided. Use --input <values> to make a prediction.");
+        report!("Example: --input 0.0,1.0");
+    }
+
+    if json_output() {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    }
+    Ok(())
+}
+
+/// Predict every row of a CSV file of inputs
+fn cmd_predict(
+    model: &str,
+    data: &str,
+    output: Option<String>,
+    argmax: bool,
+    encryption: Option<neural_network::encryption::Secret>,
+) -> anyhow::Result<()> {
+    use neural_network::{inference::InferenceModel, training::class_of};
+    use std::fmt::Write;
+    use std::path::Path;
+
+    let mut inference = InferenceModel::load_with_secret(Path::new(model), encryption.as_ref())?;
+    let expected_inputs = inference.network.layers[0];
+    let output_count = *inference.network.layers.last().unwrap_or(&0);
+
+    let contents = std::fs::read_to_string(data).with_context(|| format!("Failed to read {}", data))?;
+    let mut csv = (0..output_count).map(|i| format!("output_{}", i)).collect::<Vec<_>>().join(",");
+    if argmax {
+        csv.push_str(",class");
+    }
+    csv.push('\n');
+
+    let mut rows = 0;
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let values = match line.split(',').map(|s| s.trim().parse::<f64>()).collect::<Result<Vec<f64>, _>>() {
+            Ok(values) => values,
+            // A first line that is not numbers is a header
+            Err(_) if number == 0 => continue,
+            Err(e) => anyhow::bail!("{}:{}: invalid input: {}", data, number + 1, e),
+        };
+        if values.len() != expected_inputs {
+            anyhow::bail!(
+                "{}:{}: expected {} inputs, got {}",
+                data,
+                number + 1,
+                expected_inputs,
+                values.len()
+            );
+        }
+
+        let prediction = inference.predict(&values)?;
+        let fields = prediction.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(",");
+        if argmax {
+            writeln!(csv, "{},{}", fields, class_of(&prediction))?;
+        } else {
+            writeln!(csv, "{}", fields)?;
+        }
+        rows += 1;
+    }
+    if rows == 0 {
+        anyhow::bail!("{} has no input rows", data);
+    }
+
+    match output {
+        Some(path) => {
+            std::fs::write(&path, csv).with_context(|| format!("Failed to write {}", path))?;
+            println!("Loaded model: {}", model);
+            println!("  Architecture: {:?}", inference.network.layers);
+            println!("Predicted {} rows from {}", rows, data);
+            println!("Predictions saved to: {}", path);
+        }
+        None => print!("{}", csv),
+    }
+
+    Ok(())
+}
+
+/// Plot the loss history of a checkpoint as an SVG line chart
+fn cmd_plot_loss(checkpoint: &str, output: &str, options: &neural_network::plot::ChartOptions) -> anyhow::Result<()> {
+    use neural_network::{checkpoint::Checkpoint, plot::loss_chart_svg};
+    use std::path::Path;
+
+    let checkpoint = Checkpoint::load(Path::new(checkpoint))?;
+    let history = &checkpoint.history;
+    if history.is_empty() {
+        anyhow::bail!("Checkpoint has no training history. Re-train with the current CLI to record losses.");
+    }
+
+    std::fs::write(output, loss_chart_svg(history, options))?;
+    println!(
+        "Plotted the loss over {} recorded epochs{}",
+        history.len(),
+        if options.log_scale { " on a log scale" } else { "" }
+    );
+    println!("Loss curve saved to: {}", output);
+
+    Ok(())
+}
+
+/// Display detailed model information
+fn cmd_info(model: &str) -> anyhow::Result<()> {
+    use neural_network::{checkpoint::Checkpoint, network::Network, summary::CheckpointSummary};
+    use std::path::Path;
+
+    let model_path = Path::new(model);
+
+    // Load model
+    let checkpoint = Checkpoint::load(model_path)?;
+    let summary = CheckpointSummary::of(&checkpoint);
+    Network::from_checkpoint(checkpoint)?;
+
+    if json_output() {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+        return Ok(());
+    }
+
+    // Display header
+    println!("Model Information");
+    println!("================");
+    println!();
+
+    // Display metadata
+    let metadata = &summary.metadata;
+    println!("Metadata:");
+    println!("  Version: {}", metadata.version);
+    println!("  Example: {}", metadata.example);
+    println!("  Training Epochs: {}", metadata.epoch);
+    println!("  Total Epochs: {}", metadata.total_epochs);
+    println!("  Learning Rate: {}", metadata.learning_rate);
+    println!("  Timestamp: {}", metadata.timestamp);
+    for (label, value) in metadata.details() {
+        println!("  {}: {}", label, value);
+    }
+    println!();
+
+    // Display the checkpoints this one was resumed from
+    if !summary.lineage.is_empty() {
+        println!("Lineage:");
+        for run in &summary.lineage {
+            println!(
+                "  Resumed from {} at epoch {} (network sha256 {}) on {}",
+                run.parent_path,
+                run.parent_epoch,
+                &run.parent_checksum[..run.parent_checksum.len().min(12)],
+                run.resumed_at
+            );
+        }
+        println!();
+    }
+
+    // Display architecture
+    let layers = &summary.architecture;
+    println!("Architecture:");
+    println!("  Layers: {:?}", layers);
+    println!("  Activation: {}", summary.activation);
+    println!("  Input neurons: {}", layers[0]);
+    println!("  Output neurons: {}", layers[layers.len() - 1]);
+    if layers.len() > 2 {
+        println!("  Hidden layers: {}", layers.len() - 2);
+    }
+    if let Some(kind) = &summary.normalizer {
+        println!("  Input normalization: {}", kind);
+    }
+    if let Some(kind) = &summary.target_normalizer {
+        println!("  Target scaling: {}", kind);
+    }
+    println!();
+
+    // Display the parameters and weight statistics of every layer
+    println!("Parameters:");
+    println!(
+        "  {:<7} {:>9} {:>8} {:>7} {:>11} {:>11} {:>11} {:>11}",
+        "Layer", "Shape", "Weights", "Biases", "Mean", "Std", "Min", "Max"
+    );
+    for layer in &summary.layers {
+        let stats = &layer.weight_stats;
+        println!(
+            "  {:<7} {:>9} {:>8} {:>7} {:>11.6} {:>11.6} {:>11.6} {:>11.6}",
+            format!("{} -> {}", layer.layer, layer.layer + 1),
+            format!("{}x{}", layer.outputs, layer.inputs),
+            layer.weights,
+            layer.biases,
+            stats.mean,
+            stats.std_dev,
+            stats.min,
+            stats.max
+        );
+    }
+    println!("  (Mean, Std, Min and Max are of the weights)");
+    println!();
+
+    // Display total parameters
+    println!("Total Parameters: {}", summary.total_parameters);
+
+    // Display how training went
+    if let Some(history) = &summary.history {
+        println!();
+        println!("History:");
+        println!("  Epochs recorded: {}", history.epochs);
+        println!("  Loss: {:.6} -> {:.6}", history.first_loss, history.final_loss);
+        println!("  Best loss: {:.6} (epoch {})", history.best_loss, history.best_epoch);
+        if let Some(loss) = history.final_validation_loss {
+            println!("  Validation loss: {:.6}", loss);
+        }
+        if let Some(accuracy) = history.final_accuracy {
+            println!("  Accuracy: {:.1}%", accuracy * 100.0);
+        }
+        println!("  Training time: {}", format_secs(history.elapsed_secs));
+    }
+
+    Ok(())
+}
+
+/// Quantize a trained model and report the size, loss, accuracy and speed difference
+fn cmd_quantize(model: &str, output: Option<String>, bits: u8) -> anyhow::Result<()> {
+    use neural_network::{checkpoint::Checkpoint, matrix::Matrix, network::Network, quantize::QuantizedNetwork};
+    use std::path::Path;
+    use std::time::Instant;
+
+    let checkpoint = Checkpoint::load(Path::new(model))?;
+    let example = checkpoint_example(&checkpoint);
+    let mut network = Network::from_checkpoint(checkpoint)?;
+    let quantized = QuantizedNetwork::with_bits(&network, bits);
+    let int = format!("int{}", bits);
+
+    println!("Quantized model: {}", model);
+    println!("  Architecture: {:?}", network.layers);
+    println!("  Parameters: {}", parameter_count(&network));
+    println!();
+
+    // Parameter storage
+    let float_bytes = QuantizedNetwork::float_size_bytes(&network);
+    let int_bytes = quantized.size_bytes();
+    println!("Size:");
+    println!("  f64:  {} bytes", float_bytes);
+    println!("  {}: {} bytes ({:.1}x smaller)", int, int_bytes, float_bytes as f64 / int_bytes as f64);
+    println!();
+
+    println!("Quantization parameters:");
+    for (i, layer) in quantized.quantized_layers.iter().enumerate() {
+        println!("  Layer {} -> {}: scale {:.6}, zero-point {}",
+            i, i + 1, layer.params.scale, layer.params.zero_point);
+    }
+    println!();
+
+    // Compare loss, outputs and timing on the training samples of the example
+    if let Some((name, inputs, targets)) = example {
+        let mut max_error: f64 = 0.0;
+        for input in &inputs {
+            let expected = network.feed_forward(Matrix::from(input.clone()));
+            let actual = quantized.predict(input);
+            for (e, a) in expected.data.iter().zip(&actual) {
+                max_error = max_error.max((e - a).abs());
+            }
+        }
+        let float_loss = mean_loss(&inputs, &targets, |input| network.feed_forward(Matrix::from(input.to_vec())).data);
+        let int_loss = mean_loss(&inputs, &targets, |input| quantized.predict(input));
+        println!("Accuracy on '{}' inputs:", name);
+        println!("  Loss (f64):  {:.6}", float_loss);
+        println!("  Loss ({}): {:.6}", int, int_loss);
+        println!("  Max output difference: {:.6}", max_error);
+        println!();
+
+        const ROUNDS: u32 = 1000;
+        let start = Instant::now();
+        for _ in 0..ROUNDS {
+            for input in &inputs {
+                std::hint::black_box(network.feed_forward(Matrix::from(input.clone())));
+            }
+        }
+        let float_time = start.elapsed();
+
+        let start = Instant::now();
+        for _ in 0..ROUNDS {
+            for input in &inputs {
+                std::hint::black_box(quantized.predict(input));
+            }
+        }
+        let int_time = start.elapsed();
+
+        let samples = (ROUNDS as usize * inputs.len()) as f64;
+        println!("Inference speed ({} predictions):", samples);
+        println!("  f64:  {:.3} us/prediction", float_time.as_secs_f64() * 1e6 / samples);
+        println!("  {}: {:.3} us/prediction", int, int_time.as_secs_f64() * 1e6 / samples);
+        println!();
+    }
+
+    if let Some(output_path) = output {
+        quantized.save(Path::new(&output_path))?;
+        println!("Quantized model saved to: {}", output_path);
+    }
+
+    Ok(())
+}
+
+/// Prune the smallest weights of a trained model and report the size,
+/// parameter count and loss before and after
+fn cmd_prune(model: &str, sparsity: f64, output: Option<String>) -> anyhow::Result<()> {
+    use neural_network::{checkpoint::Checkpoint, matrix::Matrix, network::Network, prune, quantize::QuantizedNetwork};
+    use std::path::Path;
+
+    if !(0.0..=1.0).contains(&sparsity) {
+        return Err(usage_error(format!("Sparsity must be between 0 and 1, got {}", sparsity)));
+    }
+
+    let mut checkpoint = Checkpoint::load(Path::new(model))?;
+    let example = checkpoint_example(&checkpoint);
+    let mut original = Network::from_checkpoint(checkpoint.clone())?;
+    let pruned = prune::prune(&mut checkpoint.network, sparsity);
+    let network = &mut checkpoint.network;
+
+    println!("Pruned model: {}", model);
+    println!("  Architecture: {:?}", network.layers);
+    println!("  Pruned weights: {} ({:.1}% of weights are now zero)", pruned, prune::sparsity(network) * 100.0);
+    println!();
+
+    let dense_bytes = QuantizedNetwork::float_size_bytes(&original);
+    let sparse_bytes = prune::sparse_size_bytes(network);
+    println!("Size:");
+    println!("  dense:  {} bytes", dense_bytes);
+    println!("  sparse: {} bytes ({:.1}x smaller)", sparse_bytes, dense_bytes as f64 / sparse_bytes as f64);
+    println!();
+
+    let biases: usize = network.biases.iter().map(|b| b.data.len()).sum();
+    println!("Parameters:");
+    println!("  before: {} nonzero of {}", prune::nonzero_weights(&original) + biases, parameter_count(&original));
+    println!("  after:  {} nonzero of {}", prune::nonzero_weights(network) + biases, parameter_count(network));
+    println!();
+
+    if let Some((name, inputs, targets)) = example {
+        let before = mean_loss(&inputs, &targets, |input| original.feed_forward(Matrix::from(input.to_vec())).data);
+        let after = mean_loss(&inputs, &targets, |input| network.feed_forward(Matrix::from(input.to_vec())).data);
+        println!("Loss on '{}':", name);
+        println!("  before: {:.6}", before);
+        println!("  after:  {:.6}", after);
+        println!();
+    }
+
+    if let Some(output_path) = output {
+        checkpoint.save(Path::new(&output_path))?;
+        println!("Pruned model saved to: {}", output_path);
+    }
+
+    Ok(())
+}
+
+/// Number of weights and biases of a network
+fn parameter_count(network: &neural_network::network::Network) -> usize {
+    network.weights.iter().chain(&network.biases).map(|m| m.data.len()).sum()
+}
+
+/// Name, inputs and targets of an example
+type ExampleSamples = (String, Vec<Vec<f64>>, Vec<Vec<f64>>);
+
+/// The training samples of the example a checkpoint was trained on, scaled
+/// with its normalizers, if the example exists and fits the network
+fn checkpoint_example(checkpoint: &neural_network::checkpoint::Checkpoint) -> Option<ExampleSamples> {
+    let ex = neural_network::examples::get_example(&checkpoint.metadata.example)?;
+    let layers = &checkpoint.network.layers;
+    if ex.inputs[0].len() != layers[0] || ex.targets[0].len() != layers[layers.len() - 1] {
+        return None;
+    }
+    let scale = |rows: &[Vec<f64>], normalizer: &Option<neural_network::normalize::Normalizer>| match normalizer {
+        Some(normalizer) => rows.iter().map(|row| normalizer.transform(row)).collect(),
+        None => rows.to_vec(),
+    };
+    let inputs = scale(&ex.inputs, &checkpoint.normalizer);
+    let targets = scale(&ex.targets, &checkpoint.target_normalizer);
+    Some((ex.name.to_string(), inputs, targets))
+}
+
+/// Squared error summed over the outputs and averaged over the samples, as
+/// the training loss is reported
+fn mean_loss(inputs: &[Vec<f64>], targets: &[Vec<f64>], mut predict: impl FnMut(&[f64]) -> Vec<f64>) -> f64 {
+    let total: f64 = inputs
+        .iter()
+        .zip(targets)
+        .map(|(input, target)| predict(input).iter().zip(target).map(|(o, t)| (t - o).powi(2)).sum::<f64>())
+        .sum();
+    total / inputs.len() as f64
+}
+
+/// Convert an ONNX or safetensors model into a checkpoint
+fn cmd_import(model: &str, output: &str, learning_rate: Option<f64>, example: Option<String>) -> anyhow::Result<()> {
+    use neural_network::{checkpoint::CheckpointMetadata, onnx, safetensors};
+    use std::path::Path;
+
+    let path = Path::new(model);
+    let (format, network) = if path.extension().is_some_and(|ext| ext == "safetensors") {
+        let mut network = safetensors::load(path)?;
+        if let Some(rate) = learning_rate {
+            network.learning_rate = rate;
+        }
+        ("safetensors", network)
+    } else {
+        ("ONNX", onnx::load(path, learning_rate.unwrap_or(0.5))?)
+    };
+    let parameters: usize = network.weights.iter().chain(&network.biases).map(|m| m.data.len()).sum();
+    println!("Imported {} model: {}", format, model);
+    println!("  Architecture: {:?}", network.layers);
+    println!("  Parameters: {}", parameters);
 
-        /// Number of additional training epochs
-        #[arg(short = 'n', long)]
-        epochs: u32,
+    let metadata = CheckpointMetadata {
+        version: "1.0".to_string(),
+        example: example.unwrap_or_else(|| "imported".to_string()),
+        epoch: 0,
+        total_epochs: 0,
+        learning_rate: network.learning_rate,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        ..Default::default()
+    };
+    network.save_checkpoint(Path::new(output), metadata)?;
+    println!("Checkpoint saved to: {}", output);
 
-        /// Output file path for updated model
-        #[arg(short, long)]
-        output: Option<String>,
-    },
+    Ok(())
+}
 
-    /// Evaluate a trained model
-    Eval {
-        /// Path to trained model file
-        #[arg(short, long)]
-        model: String,
+/// Write the parameters of a checkpoint as a safetensors file, or with
+/// `strip` as an inference-only checkpoint
+fn cmd_export(model: &str, output: &str, strip: bool) -> anyhow::Result<()> {
+    use neural_network::{checkpoint::Checkpoint, network::Network, safetensors};
+    use std::path::Path;
 
-        /// Input values (comma-separated, e.g., "0.5,1.0" or "-1.0,0.5")
-        #[arg(short, long, allow_hyphen_values = true)]
-        input: Option<String>,
-    },
+    if strip {
+        let checkpoint = Checkpoint::load(Path::new(model))?;
+        checkpoint.stripped().save(Path::new(output))?;
+        println!("Exported model: {}", model);
+        println!("  Architecture: {:?}", checkpoint.network.layers);
+        println!("  Size: {} -> {} bytes", std::fs::metadata(model)?.len(), std::fs::metadata(output)?.len());
+        println!("Inference-only checkpoint saved to: {}", output);
+        return Ok(());
+    }
 
-    /// Display detailed model information
-    Info {
-        /// Path to model file
-        #[arg(short, long)]
-        model: String,
-    },
+    let (network, _) = Network::load_checkpoint(Path::new(model))?;
+    safetensors::save(&network, Path::new(output))?;
+    println!("Exported model: {}", model);
+    println!("  Architecture: {:?}", network.layers);
+    println!("Safetensors file saved to: {}", output);
+
+    Ok(())
 }
 
-fn main() -> anyhow::Result<()> {
-    let cli = Cli::parse();
+/// File format of a model, chosen from its extension
+#[derive(Clone, Copy, PartialEq)]
+enum ModelFormat {
+    Checkpoint,
+    Safetensors,
+    Onnx,
+    Npz,
+}
 
-    match cli.command {
-        Commands::List => {
-            cmd_list()?;
-        }
-        Commands::Train {
-            example,
-            epochs,
-            learning_rate,
-            seed,
-            output,
-        } => {
-            cmd_train(&example, epochs, learning_rate, seed, output)?;
-        }
-        Commands::Resume {
-            checkpoint,
-            epochs,
-            output,
-        } => {
-            cmd_resume(&checkpoint, epochs, output)?;
+impl ModelFormat {
+    /// `.safetensors`, `.onnx` and `.npz` files; anything else is a checkpoint
+    fn of(path: &str) -> Self {
+        match std::path::Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            Some("safetensors") => Self::Safetensors,
+            Some("onnx") => Self::Onnx,
+            Some("npz") => Self::Npz,
+            _ => Self::Checkpoint,
         }
-        Commands::Eval { model, input } => {
-            cmd_eval(&model, input)?;
-        }
-        Commands::Info { model } => {
-            cmd_info(&model)?;
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Checkpoint => "checkpoint",
+            Self::Safetensors => "safetensors",
+            Self::Onnx => "ONNX",
+            Self::Npz => "npz",
         }
     }
 
-    Ok(())
+    /// What a file of this format is called in "... saved to" lines
+    fn file(self) -> &'static str {
+        match self {
+            Self::Checkpoint => "Checkpoint",
+            Self::Safetensors => "Safetensors file",
+            Self::Onnx => "ONNX model",
+            Self::Npz => "Npz file",
+        }
+    }
 }
 
-/// List available training examples
-fn cmd_list() -> anyhow::Result<()> {
-    use neural_network::examples;
+/// Rewrite a model in the format picked by the output extension
+///
+/// A checkpoint converted to another checkpoint keeps everything it holds;
+/// the other formats only store the network parameters, so converting to
+/// them drops the history, optimizer state and normalizers.
+fn cmd_convert(input: &str, output: &str) -> anyhow::Result<()> {
+    use neural_network::checkpoint::{Checkpoint, CheckpointMetadata};
+    use neural_network::{network::Network, npz, onnx, safetensors};
+    use std::path::Path;
 
-    println!("Available Examples:");
-    println!();
+    let (from, to) = (ModelFormat::of(input), ModelFormat::of(output));
+    let (input_path, output_path) = (Path::new(input), Path::new(output));
+    let network = if from == ModelFormat::Checkpoint && to == ModelFormat::Checkpoint {
+        let checkpoint = Checkpoint::load(input_path)?;
+        checkpoint.save(output_path)?;
+        checkpoint.network
+    } else {
+        let network = match from {
+            ModelFormat::Checkpoint => Network::load_checkpoint(input_path)?.0,
+            ModelFormat::Safetensors => safetensors::load(input_path)?,
+            ModelFormat::Onnx => onnx::load(input_path, 0.5)?,
+            ModelFormat::Npz => npz::load(input_path)?,
+        };
+        match to {
+            ModelFormat::Checkpoint => {
+                let metadata = CheckpointMetadata {
+                    version: "1.0".to_string(),
+                    example: "imported".to_string(),
+                    learning_rate: network.learning_rate,
+                    timestamp: chrono::Utc::now().to_rfc3339(),
+                    ..Default::default()
+                };
+                network.save_checkpoint(output_path, metadata)?;
+            }
+            ModelFormat::Safetensors => safetensors::save(&network, output_path)?,
+            ModelFormat::Onnx => onnx::save(&network, output_path)?,
+            ModelFormat::Npz => npz::save(&network, output_path)?,
+        }
+        network
+    };
 
-    for name in examples::list_examples() {
-        let example = examples::get_example(name).unwrap();
-        println!("  {} - {}", name, example.description);
+    println!("Converted {} model: {}", from.name(), input);
+    println!("  Architecture: {:?}", network.layers);
+    println!("  Size: {} -> {} bytes", std::fs::metadata(input)?.len(), std::fs::metadata(output)?.len());
+    if from == ModelFormat::Checkpoint && to != ModelFormat::Checkpoint {
+        println!("  Only the network is kept: history, optimizer state and normalizers are dropped");
     }
+    println!("{} saved to: {}", to.file(), output);
 
     Ok(())
 }
 
-/// Train a neural network
-fn cmd_train(
-    example: &str,
-    epochs: u32,
-    learning_rate: f64,
-    seed: Option<u64>,
-    output: Option<String>,
+/// Report the parameter and metadata differences of two checkpoints, and
+/// their loss and accuracy on an example or data file if one is given
+fn cmd_compare(
+    before: &str,
+    after: &str,
+    example: Option<&str>,
+    data: Option<&str>,
+    target_columns: usize,
 ) -> anyhow::Result<()> {
-    use indicatif::{ProgressBar, ProgressStyle};
-    use neural_network::{activations::SIGMOID, examples, network::Network, training::{TrainingConfig, TrainingController}};
+    use neural_network::{compare::{Evaluation, compare}, inference::InferenceModel};
     use std::path::Path;
 
-    // Load example
-    let ex = examples::get_example(example)
-        .ok_or_else(|| anyhow::anyhow!("Unknown example: {}. Use 'list' to see available examples.", example))?;
+    let diff = compare(Path::new(before), Path::new(after))?;
 
-    println!("Training {} network", ex.name);
-    println!("Architecture: {:?}", ex.recommended_arch);
-    println!("Epochs: {}", epochs);
-    println!("Learning rate: {}", learning_rate);
-    if let Some(s) = seed {
-        println!("Seed: {}", s);
+    report!("Comparing checkpoints:");
+    report!("  Before: {}", before);
+    report!("  After:  {}", after);
+    report!("  Architecture: {:?}", diff.layers);
+    report!();
+
+    report!("Metadata:");
+    if diff.metadata.is_empty() {
+        report!("  No differences");
     }
-    println!();
+    for change in &diff.metadata {
+        report!("  {}: {} -> {}", change.field, change.before, change.after);
+    }
+    report!();
 
-    // Create network with recommended architecture
-    let network = if let Some(s) = seed {
-        Network::new_seeded(ex.recommended_arch.clone(), SIGMOID, learning_rate, s)
+    report!("Parameter changes:");
+    report!("  {:<7} {:>12} {:>12} {:>12} {:>12}", "Layer", "Weights L2", "Max |dW|", "Biases L2", "Max |db|");
+    for d in &diff.layer_diffs {
+        report!(
+            "  {:<7} {:>12.6} {:>12.6} {:>12.6} {:>12.6}",
+            d.layer + 1,
+            d.weight_l2,
+            d.weight_max_abs,
+            d.bias_l2,
+            d.bias_max_abs
+        );
+    }
+    report!();
+    if diff.is_unchanged() {
+        report!("The networks are identical");
     } else {
-        Network::new(ex.recommended_arch.clone(), SIGMOID, learning_rate)
-    };
+        report!("Total L2 distance: {:.6}", diff.total_l2());
+    }
 
-    // Create training config
-    let config = TrainingConfig {
-        epochs,
-        checkpoint_interval: if output.is_some() { Some(epochs) } else { None },
-        checkpoint_path: output.as_ref().map(|p| Path::new(p).to_path_buf()),
-        verbose: false,
-        example_name: Some(ex.name.to_string()),
+    let mut result = serde_json::json!({
+        "before": before,
+        "after": after,
+        "total_l2": diff.total_l2(),
+        "diff": diff,
+    });
+    let Some((dataset, name)) = evaluation_data(example, data, target_columns)? else {
+        if json_output() {
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        return Ok(());
     };
+    let a = Evaluation::of(&mut InferenceModel::load(Path::new(before))?, dataset.as_ref())?;
+    let b = Evaluation::of(&mut InferenceModel::load(Path::new(after))?, dataset.as_ref())?;
 
-    // Create training controller
-    let mut controller = TrainingController::new(network, config);
+    report!();
+    report!("Evaluation on {} ({} samples):", name, a.samples);
+    report!("  {:<9} {:>12} {:>12} {:>12}", "Metric", "Before", "After", "Change");
+    report!("  {:<9} {:>12.6} {:>12.6} {:>+12.6}", "Loss", a.loss, b.loss, b.loss - a.loss);
+    let (acc_a, acc_b) = (a.metrics.accuracy * 100.0, b.metrics.accuracy * 100.0);
+    report!(
+        "  {:<9} {:>11.1}% {:>11.1}% {:>+11.1}%",
+        "Accuracy", acc_a, acc_b, acc_b - acc_a
+    );
+    report!();
+    if b.loss < a.loss {
+        report!("The after model has the lower loss");
+    } else if b.loss > a.loss {
+        report!("The before model has the lower loss");
+    } else {
+        report!("Both models have the same loss");
+    }
 
-    // Setup progress bar
-    let pb = ProgressBar::new(epochs as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{msg} [{bar:40.cyan/blue}] {pos}/{len} ({eta})")
-            .unwrap()
-            .progress_chars("#>-"),
+    if json_output() {
+        result["evaluation"] = serde_json::json!({ "data": name, "before": a, "after": b });
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    }
+    Ok(())
+}
+
+/// Data to evaluate models on, with the name to report it by
+type NamedData = (Box<dyn neural_network::dataset::Dataset + Send>, String);
+
+/// The example or data file given to an evaluating command, if either
+fn evaluation_data(example: Option<&str>, data: Option<&str>, target_columns: usize) -> anyhow::Result<Option<NamedData>> {
+    Ok(match (example, data) {
+        (Some(name), _) => {
+            let ex = neural_network::examples::get_example(name)
+                .ok_or_else(|| usage_error(format!("Unknown example: {}. Use 'list' to see available examples.", name)))?;
+            Some((Box::new(ex), name.to_string()))
+        }
+        (None, Some(path)) => Some((open_data(path, None, target_columns)?.0, path.to_string())),
+        (None, None) => None,
+    })
+}
+
+/// Evaluate a model on labeled data and break its predictions down by class
+fn cmd_test(
+    model: &str,
+    data: &dyn neural_network::dataset::Dataset,
+    name: &str,
+    threshold: f64,
+    plot: Option<String>,
+    encryption: Option<neural_network::encryption::Secret>,
+) -> anyhow::Result<()> {
+    use neural_network::{compare::Evaluation, inference::InferenceModel, plot::{ChartOptions, confusion_matrix_svg}};
+    use std::path::Path;
+
+    let mut inference = InferenceModel::load_with_secret(Path::new(model), encryption.as_ref())?;
+    let evaluation = Evaluation::at_threshold(&mut inference, data, threshold)?;
+    let confusion = &evaluation.confusion;
+
+    println!("Testing {} on {} ({} samples)", model, name, evaluation.samples);
+    println!("  Loss: {:.6}", evaluation.loss);
+    println!(
+        "  Accuracy: {:.2}% ({} of {} correct)",
+        confusion.accuracy() * 100.0,
+        confusion.correct(),
+        confusion.total()
     );
-    pb.set_message("Training");
+    println!();
 
-    // Add progress callback (clone pb for the closure)
-    let pb_clone = pb.clone();
-    controller.add_callback(Box::new(move |epoch, loss, _network| {
-        pb_clone.set_position(epoch as u64);
-        if epoch % 100 == 0 || epoch == 1 {
-            pb_clone.set_message(format!("Training (loss: {:.6})", loss));
+    // Wide enough for the largest count and the class labels
+    let width = confusion.counts.iter().flatten().max().map_or(1, |n| n.to_string().len()).max(confusion.num_classes().to_string().len()).max(5);
+    println!("Confusion matrix (rows: actual class, columns: predicted class):");
+    print!("  {:>8}", "");
+    for class in 0..confusion.num_classes() {
+        print!(" {:>width$}", class, width = width);
+    }
+    println!();
+    for (actual, row) in confusion.counts.iter().enumerate() {
+        print!("  {:>8}", actual);
+        for count in row {
+            print!(" {:>width$}", count, width = width);
         }
-    }));
+        println!();
+    }
+    println!();
 
-    // Train network
-    controller.train(ex.inputs.clone(), ex.targets.clone())?;
-    pb.finish_with_message("Training complete!");
+    let percent = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{:.2}%", v * 100.0));
+    println!("Per-class results:");
+    println!("  {:>5} {:>8} {:>9} {:>9}", "Class", "Support", "Accuracy", "Precision");
+    for class in 0..confusion.num_classes() {
+        println!(
+            "  {:>5} {:>8} {:>9} {:>9}",
+            class,
+            confusion.support(class),
+            percent(confusion.class_accuracy(class)),
+            percent(confusion.class_precision(class))
+        );
+    }
 
-    // Save model if output path specified
-    if let Some(output_path) = output {
+    if let Some(path) = plot {
+        let options = ChartOptions { width: 800, height: 800, ..Default::default() };
+        std::fs::write(&path, confusion_matrix_svg(confusion, &options))?;
         println!();
-        println!("Saving model to: {}", output_path);
-        println!("Model saved successfully!");
+        println!("Confusion matrix plot saved to: {}", path);
     }
 
     Ok(())
 }
 
-/// Resume training from a checkpoint
-fn cmd_resume(checkpoint: &str, epochs: u32, output: Option<String>) -> anyhow::Result<()> {
-    use neural_network::{network::Network, training::{TrainingConfig, TrainingController}};
+/// Serve checkpoints over the REST API of `neural-net-server`
+///
+/// Each checkpoint is loaded up front, so a bad path or key fails before the
+/// server binds, and is served under its file name up to the first dot, e.g.
+/// `xor` for `models/xor.json.gz`.
+fn cmd_serve(
+    checkpoints: &[String],
+    host: &str,
+    port: u16,
+    encryption: Option<neural_network::encryption::Secret>,
+) -> anyhow::Result<()> {
+    use neural_network::inference::InferenceModel;
     use std::path::Path;
 
-    let checkpoint_path = Path::new(checkpoint);
+    let mut models: Vec<(String, InferenceModel)> = Vec::new();
+    for path in checkpoints {
+        let file_name = Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or(path);
+        let model_id = file_name.split('.').next().unwrap_or(file_name).to_string();
+        if models.iter().any(|(id, _)| *id == model_id) {
+            return Err(usage_error(format!("Two checkpoints would be served as model '{}', rename one of them", model_id)));
+        }
+        let model = InferenceModel::load_with_secret(Path::new(path), encryption.as_ref())?;
+        models.push((model_id, model));
+    }
 
-    println!("Resuming training from checkpoint: {}", checkpoint);
-    println!("Additional epochs: {}", epochs);
+    let addr = format!("{}:{}", host, port);
+    println!("Serving {} model(s) on http://{}", models.len(), addr);
+    for (model_id, model) in &models {
+        println!("  {}: {:?} ({} epochs)", model_id, model.network.layers, model.metadata.epoch);
+        println!("    GET  http://{}/api/models/{}", addr, model_id);
+    }
+    println!("Evaluate with: POST http://{}/api/eval {{\"model_id\": \"<id>\", \"input\": [...]}}", addr);
     println!();
 
-    // Load checkpoint to get training data info
-    let (network, metadata) = Network::load_checkpoint(checkpoint_path)?;
-
-    println!("Loaded checkpoint:");
-    println!("  Architecture: {:?}", network.layers);
-    println!("  Previous epochs: {}", metadata.epoch);
-    println!("  Example: {}", metadata.example);
-    println!("  Learning rate: {}", metadata.learning_rate);
-    println!();
+    let options = neural_net_server::ServerOptions {
+        models,
+        ..Default::default()
+    };
+    tokio::runtime::Runtime::new()?.block_on(neural_net_server::run_server_with_options(&addr, options))
+}
 
-    // Get training data from example
-    use neural_network::examples;
-    let ex = examples::get_example(&metadata.example)
-        .ok_or_else(|| anyhow::anyhow!("Example '{}' not found", metadata.example))?;
+/// Predict with an ensemble of trained models
+fn cmd_ensemble(models: &[String], strategy: EnsembleStrategy, input: Option<String>) -> anyhow::Result<()> {
+    use neural_network::{ensemble::{Combine, Ensemble}, examples, network::Network};
+    use std::path::Path;
 
-    // Create training config
-    let config = TrainingConfig {
-        epochs,
-        checkpoint_interval: if output.is_some() { Some(epochs) } else { None },
-        checkpoint_path: output.as_ref().map(|p| Path::new(p).to_path_buf()),
-        verbose: false,
-        example_name: Some(metadata.example.clone()),
+    let combine = match strategy {
+        EnsembleStrategy::Average => Combine::Average,
+        EnsembleStrategy::Vote => Combine::Vote,
     };
+    let mut ensemble = Ensemble::from_checkpoints(models, combine)?;
 
-    // Resume training
-    let mut controller = TrainingController::from_checkpoint(checkpoint_path, config)?;
+    println!("Ensemble of {} models ({:?})", ensemble.len(), combine);
+    for model in models {
+        println!("  {}", model);
+    }
+    println!();
+
+    let inputs = if let Some(input_str) = input {
+        let values = input_str
+            .split(',')
+            .map(|s| s.trim().parse::<f64>())
+            .collect::<Result<Vec<f64>, _>>()
+            .map_err(|e| {
+                usage_error(format!("Invalid input format: {}. Expected comma-separated numbers (e.g., '0.0,1.0')", e))
+            })?;
+        vec![values]
+    } else {
+        let (_, metadata) = Network::load_checkpoint(Path::new(&models[0]))?;
+        let ex = examples::get_example(&metadata.example).ok_or_else(|| {
+            anyhow::anyhow!("Example '{}' not found. Use --input to provide values.", metadata.example)
+        })?;
+        ex.inputs
+    };
 
-    println!("Resuming training...");
-    controller.train(ex.inputs.clone(), ex.targets.clone())?;
-    println!("Training complete!");
+    let expected_inputs = ensemble.networks[0].layers[0];
+    for values in &inputs {
+        if values.len() != expected_inputs {
+            return Err(usage_error(format!(
+                "Invalid input dimensions: expected {} inputs, got {}",
+                expected_inputs,
+                values.len()
+            )));
+        }
 
-    // Save if output specified
-    if let Some(output_path) = output {
-        println!();
-        println!("Model saved to: {}", output_path);
+        let members = ensemble.member_outputs(values);
+        println!("Input: {:?}", values);
+        for (i, output) in members.iter().enumerate() {
+            println!("  Model {}: {:?}", i, output);
+        }
+        println!("  Ensemble: {:?}", ensemble.predict(values));
     }
 
     Ok(())
 }
 
-/// Evaluate a trained model
-fn cmd_eval(model: &str, input: Option<String>) -> anyhow::Result<()> {
-    use neural_network::network::Network;
+/// Run a grid or random sweep from a TOML spec
+fn cmd_sweep(spec_path: &str, output: Option<String>, best: Option<String>, parallel: usize) -> anyhow::Result<()> {
+    use neural_network::sweep::SweepSpec;
     use std::path::Path;
 
-    let model_path = Path::new(model);
+    if parallel == 0 {
+        return Err(usage_error("--parallel must be at least 1"));
+    }
+    let spec = SweepSpec::load(Path::new(spec_path))?;
+    let planned = spec.planned_trials();
 
-    // Load model
-    let (mut network, metadata) = Network::load_checkpoint(model_path)?;
+    if let Some(search) = &spec.random {
+        let trials = planned.map_or("unlimited".to_string(), |n| n.to_string());
+        let secs = search.max_secs.map_or("none".to_string(), |s| format!("{}s", s));
+        println!("Random search on {} (max trials: {}, time limit: {})", spec.example, trials, secs);
+    } else {
+        println!("Sweeping {} trials on {}", planned.unwrap_or_default(), spec.example);
+    }
+    if parallel > 1 {
+        println!("Training {} trials at once", parallel);
+    }
+    println!();
+
+    let report = spec.run_parallel(parallel, |i, result| {
+        let score = result.score().map_or("failed".to_string(), |s| format!("{:.6}", s));
+        let progress = planned.map_or(format!("{}", i + 1), |n| format!("{}/{}", i + 1, n));
+        println!(
+            "[{}] {:?} lr={:.4} epochs={}: {}",
+            progress, result.architecture, result.trial.learning_rate, result.trial.epochs, score
+        );
+    })?;
 
-    // Display model info
-    println!("Loaded model: {}", model);
-    println!("  Example: {}", metadata.example);
-    println!("  Architecture: {:?}", network.layers);
-    println!("  Training epochs: {}", metadata.epoch);
-    println!("  Learning rate: {}", metadata.learning_rate);
     println!();
+    print!("{}", report.summary());
 
-    // Parse input if provided
-    if let Some(input_str) = input {
-        let inputs: Result<Vec<f64>, _> = input_str
-            .split(',')
-            .map(|s| s.trim().parse::<f64>())
-            .collect();
+    if output.is_some() || best.is_some() {
+        println!();
+    }
+    if let Some(path) = output {
+        report.save(Path::new(&path))?;
+        println!("Report saved to: {}", path);
+    }
+    if let Some(path) = best {
+        let checkpoint = report
+            .best_checkpoint
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No trial succeeded, so there is no best checkpoint to save"))?;
+        checkpoint.save(Path::new(&path))?;
+        println!("Best checkpoint saved to: {}", path);
+    }
 
-        let inputs = inputs.map_err(|e| {
-            anyhow::anyhow!("Invalid input format: {}. Expected comma-separated numbers (e.g., '0.0,1.0')", e)
-        })?;
+    Ok(())
+}
 
-        // Validate input dimensions
-        if inputs.len() != network.layers[0] {
-            anyhow::bail!(
-                "Invalid input dimensions: expected {} inputs, got {}",
-                network.layers[0],
-                inputs.len()
+fn cmd_lr_find(
+    example: &str,
+    min_lr: f64,
+    max_lr: f64,
+    steps: usize,
+    seed: Option<u64>,
+    output: Option<String>,
+) -> anyhow::Result<()> {
+    use neural_network::{activations::SIGMOID, examples, lr_finder::LrFinder, network::Network};
+    use std::fmt::Write as _;
+
+    let ex = examples::get_example(example)
+        .ok_or_else(|| usage_error(format!("Unknown example: {}. Use 'list' to see available examples.", example)))?;
+    if !(min_lr > 0.0 && min_lr < max_lr) {
+        return Err(usage_error("Learning rate range must satisfy 0 < min-lr < max-lr"));
+    }
+
+    println!("Learning rate range test on {}", ex.name);
+    println!("Rates: {} to {} over {} steps", min_lr, max_lr, steps);
+    println!();
+
+    let network = match seed {
+        Some(s) => Network::new_seeded(ex.recommended_arch.clone(), SIGMOID, min_lr, s),
+        None => Network::new(ex.recommended_arch.clone(), SIGMOID, min_lr),
+    };
+    let finder = LrFinder {
+        min_lr,
+        max_lr,
+        steps,
+        ..Default::default()
+    };
+    let result = finder.run(&network, &ex.inputs, &ex.targets);
+
+    // Print about ten evenly spaced points plus the last one
+    println!("{:>14}  {:>10}  {:>10}", "learning rate", "loss", "smoothed");
+    let stride = result.points.len().div_ceil(10).max(1);
+    for (i, point) in result.points.iter().enumerate() {
+        if i % stride == 0 || i + 1 == result.points.len() {
+            println!(
+                "{:>14.6}  {:>10.6}  {:>10.6}",
+                point.learning_rate, point.loss, point.smoothed_loss
             );
         }
+    }
+    println!();
 
-        // Run prediction
-        let input_matrix = neural_network::matrix::Matrix::from(inputs.clone());
-        let output = network.feed_forward(input_matrix);
+    if result.diverged {
+        println!("Loss diverged after {} steps", result.points.len());
+    }
+    if let Some(rate) = result.min_loss_rate() {
+        println!("Lowest loss at learning rate: {:.6}", rate);
+    }
+    match result.suggestion() {
+        Some(rate) => println!("Suggested learning rate: {:.6}", rate),
+        None => println!("No suggestion: the loss never decreased; try a wider range"),
+    }
 
-        // Display results
-        println!("Input: {:?}", inputs);
-        println!("Output: {:?}", output.data);
-    } else {
-        println!("No input provided. Use --input <values> to make a prediction.");
-        println!("Example: --input 0.0,1.0");
+    if let Some(path) = output {
+        let mut csv = String::from("learning_rate,loss,smoothed_loss\n");
+        for point in &result.points {
+            writeln!(csv, "{},{},{}", point.learning_rate, point.loss, point.smoothed_loss)?;
+        }
+        std::fs::write(&path, csv)?;
+        println!();
+        println!("Points saved to: {}", path);
     }
 
     Ok(())
 }
 
-/// Display detailed model information
-fn cmd_info(model: &str) -> anyhow::Result<()> {
-    use neural_network::network::Network;
-    use std::path::Path;
-
-    let model_path = Path::new(model);
-
-    // Load model
-    let (network, metadata) = Network::load_checkpoint(model_path)?;
+/// Time the forward and backward passes of a random network and, with the
+/// `count-allocations` feature, count the heap allocations per sample
+fn cmd_bench(layers: Vec<usize>, batch: usize, activation: ActivationKind, seconds: f64, seed: u64) -> anyhow::Result<()> {
+    use neural_network::benchmark::Benchmark;
 
-    // Display header
-    println!("Model Information");
-    println!("================");
-    println!();
+    if layers.len() < 2 || layers.contains(&0) {
+        return Err(usage_error("--layers needs at least an input and an output size, all above zero"));
+    }
+    if batch == 0 {
+        return Err(usage_error("--batch must be at least 1"));
+    }
+    let min_time = std::time::Duration::try_from_secs_f64(seconds)
+        .map_err(|_| usage_error("--seconds must be a non-negative number"))?;
+    let bench = Benchmark {
+        layers,
+        batch,
+        activation: activation.into(),
+        min_time,
+        seed,
+    };
 
-    // Display metadata
-    println!("Metadata:");
-    println!("  Version: {}", metadata.version);
-    println!("  Example: {}", metadata.example);
-    println!("  Training Epochs: {}", metadata.epoch);
-    println!("  Total Epochs: {}", metadata.total_epochs);
-    println!("  Learning Rate: {}", metadata.learning_rate);
-    println!("  Timestamp: {}", metadata.timestamp);
+    println!(
+        "Benchmark of {:?} with {}, batch size {}, {}s per phase",
+        bench.layers, bench.activation.name, bench.batch, seconds
+    );
+    if cfg!(debug_assertions) {
+        println!("Debug build: run with cargo run --release for representative numbers");
+    }
     println!();
 
-    // Display architecture
-    println!("Architecture:");
-    println!("  Layers: {:?}", network.layers);
-    println!("  Input neurons: {}", network.layers[0]);
-    println!("  Output neurons: {}", network.layers[network.layers.len() - 1]);
-    if network.layers.len() > 2 {
-        println!("  Hidden layers: {}", network.layers.len() - 2);
+    let report = bench.run(allocation_counter());
+    println!("  {:<24} {:>14} {:>14} {:>14}", "Phase", "Samples/sec", "us/sample", "Allocs/sample");
+    for phase in &report.phases {
+        println!(
+            "  {:<24} {:>14.1} {:>14.2} {:>14}",
+            phase.name,
+            phase.samples_per_second(),
+            phase.seconds * 1e6 / phase.samples as f64,
+            phase.allocations_per_sample().map_or("-".to_string(), |allocs| format!("{:.2}", allocs))
+        );
     }
-    println!();
+    if allocation_counter().is_none() {
+        println!();
+        println!("Allocations are counted in builds with --features count-allocations");
+    }
+    Ok(())
+}
 
-    // Display weight matrices
-    println!("Weights:");
-    let mut total_params = 0;
-    for (i, weight) in network.weights.iter().enumerate() {
-        let params = weight.rows * weight.cols;
-        total_params += params;
-        println!("  Layer {} -> {}: {}x{} ({} parameters)",
-            i, i + 1, weight.rows, weight.cols, params);
+/// Check back-propagation against finite differences on a seeded random
+/// network and samples, failing if any layer exceeds the tolerance
+fn cmd_gradcheck(
+    layers: &[usize],
+    samples: usize,
+    activation: ActivationKind,
+    seed: u64,
+    checker: neural_network::gradcheck::GradCheck,
+) -> anyhow::Result<()> {
+    let activation: neural_network::activations::Activation = activation.into();
+    if layers.len() < 2 || layers.contains(&0) {
+        return Err(usage_error("--layers needs at least an input and an output size, all above zero"));
+    }
+    if samples == 0 {
+        return Err(usage_error("--samples must be at least 1"));
+    }
+    if checker.epsilon.is_nan() || checker.epsilon <= 0.0 {
+        return Err(usage_error("--epsilon must be positive"));
     }
+
+    println!("Gradient check of {:?} with {} on {} random samples", layers, activation.name, samples);
+    println!("Epsilon: {:e}, tolerance: {:e}, seed: {}", checker.epsilon, checker.tolerance, seed);
     println!();
 
-    // Display bias vectors
-    println!("Biases:");
-    for (i, bias) in network.biases.iter().enumerate() {
-        let params = bias.rows;
-        total_params += params;
-        println!("  Layer {}: {}x{} ({} parameters)",
-            i + 1, bias.rows, bias.cols, params);
+    let report = checker.run_random(layers, activation, samples, seed);
+    println!("  {:<7} {:>14} {:>14}  Result", "Layer", "Weights error", "Biases error");
+    for layer in &report.layers {
+        let result = if layer.max() <= report.tolerance { "ok" } else { "FAIL" };
+        println!(
+            "  {:<7} {:>14.3e} {:>14.3e}  {}",
+            format!("{}->{}", layers[layer.layer], layers[layer.layer + 1]),
+            layer.weights,
+            layer.biases,
+            result
+        );
     }
     println!();
+    println!("Max relative error: {:.3e}", report.max_error());
 
-    // Display total parameters
-    println!("Total Parameters: {}", total_params);
-
+    if !report.passed() {
+        anyhow::bail!(
+            "Gradient check failed: relative error {:.3e} exceeds the tolerance {:e}",
+            report.max_error(),
+            report.tolerance
+        );
+    }
+    println!("Back-propagation matches the numerical gradients");
     Ok(())
 }
@@ -0,0 +1,37 @@
+// Integration tests for the bench command
+mod common;
+
+use common::cli;
+
+#[test]
+fn test_bench_reports_throughput() {
+    let output = cli(&["bench", "--layers", "4,8,2", "--batch", "8", "--seconds", "0.05"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "Bench should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("Benchmark of [4, 8, 2] with sigmoid, batch size 8"), "{}", stdout);
+
+    for name in ["feed-forward ", "feed-forward (workspace)", "backprop (per sample)", "backprop (batch of 8)"] {
+        let values = row(&stdout, name);
+        assert_eq!(values.len(), 3, "{}", stdout);
+        assert!(values[0].parse::<f64>().unwrap() > 0.0, "{}", stdout);
+        assert_eq!(values[2], "-", "Allocations are only counted with count-allocations: {}", stdout);
+    }
+    assert!(stdout.contains("--features count-allocations"), "{}", stdout);
+}
+
+#[test]
+fn test_bench_rejects_invalid_settings() {
+    let output = cli(&["bench", "--layers", "4", "--seconds", "0"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("at least an input and an output"));
+
+    let output = cli(&["bench", "--batch", "0", "--seconds", "0"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--batch must be at least 1"));
+}
+
+/// The values after `name` in the bench table row that starts with it
+fn row<'a>(stdout: &'a str, name: &str) -> Vec<&'a str> {
+    let line = stdout.lines().find(|line| line.trim_start().starts_with(name)).unwrap();
+    line[line.find(name).unwrap() + name.len()..].split_whitespace().collect()
+}
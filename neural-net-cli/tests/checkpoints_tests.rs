@@ -0,0 +1,82 @@
+// Integration tests for checkpoints command
+use std::process::Command;
+use tempfile::TempDir;
+
+/// Train XOR for 300 epochs with a snapshot every 100 epochs in `dir`
+fn train_with_snapshots(dir: &str) {
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "train",
+            "--example",
+            "xor",
+            "--epochs",
+            "300",
+            "--snapshots",
+            dir,
+            "--snapshot-every",
+            "100",
+        ])
+        .output()
+        .expect("Failed to train");
+    assert!(output.status.success(), "Training should succeed");
+}
+
+fn checkpoints(args: &[&str]) -> std::process::Output {
+    Command::new("cargo")
+        .args(["run", "--bin", "neural-net-cli", "--", "checkpoints"])
+        .args(args)
+        .output()
+        .expect("Failed to run checkpoints")
+}
+
+#[test]
+fn test_checkpoints_list_shows_metadata() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let dir = temp_dir.path().to_str().unwrap();
+    train_with_snapshots(dir);
+
+    let output = checkpoints(&["list", "--dir", dir]);
+    assert!(output.status.success(), "List should succeed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(": 3"), "Should count the snapshots: {}", stdout);
+    assert!(stdout.contains("snapshot_epoch000200.json"), "Should list the snapshot files");
+    assert!(stdout.contains("Epochs") && stdout.contains("Loss") && stdout.contains("Saved"));
+
+    let output = Command::new("cargo")
+        .args(["run", "--bin", "neural-net-cli", "--", "--format", "json", "checkpoints", "list", "--dir", dir])
+        .output()
+        .expect("Failed to run checkpoints");
+    let entries: serde_json::Value = serde_json::from_slice(&output.stdout).expect("List should print JSON");
+    assert_eq!(entries.as_array().unwrap().len(), 3);
+    assert_eq!(entries[2]["metadata"]["epoch"], 300);
+}
+
+#[test]
+fn test_checkpoints_clean_keeps_last() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let dir = temp_dir.path().to_str().unwrap();
+    train_with_snapshots(dir);
+
+    let output = checkpoints(&["clean", "--dir", dir, "--keep-last", "1", "--dry-run"]);
+    assert!(output.status.success(), "Dry run should succeed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Would delete 2 of 3"));
+    assert!(temp_dir.path().join("snapshot_epoch000100.json").exists(), "Dry run should not delete");
+
+    let output = checkpoints(&["clean", "--dir", dir, "--keep-last", "1"]);
+    assert!(output.status.success(), "Clean should succeed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Deleted 2 of 3"));
+    assert!(!temp_dir.path().join("snapshot_epoch000100.json").exists());
+    assert!(!temp_dir.path().join("snapshot_epoch000200.json").exists());
+    assert!(temp_dir.path().join("snapshot_epoch000300.json").exists());
+}
+
+#[test]
+fn test_checkpoints_clean_requires_retention() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let output = checkpoints(&["clean", "--dir", temp_dir.path().to_str().unwrap()]);
+    assert!(!output.status.success(), "Clean without a retention flag should fail");
+}
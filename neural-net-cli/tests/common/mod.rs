@@ -0,0 +1,11 @@
+// Helpers shared by the CLI integration tests
+use std::process::{Command, Output};
+
+/// Run `neural-net-cli` with `args` through `cargo run`
+pub fn cli(args: &[&str]) -> Output {
+    Command::new("cargo")
+        .args(["run", "--bin", "neural-net-cli", "--"])
+        .args(args)
+        .output()
+        .expect("Failed to run neural-net-cli")
+}
@@ -0,0 +1,76 @@
+// Integration tests for compare command
+mod common;
+
+use common::cli;
+use tempfile::TempDir;
+
+#[test]
+fn test_compare_resumed_checkpoint() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let first_path = temp_dir.path().join("first.json");
+    let resumed_path = temp_dir.path().join("resumed.json");
+    let (first, resumed) = (first_path.to_str().unwrap(), resumed_path.to_str().unwrap());
+
+    assert!(cli(&["train", "--example", "xor", "--epochs", "100", "--output", first]).status.success());
+    assert!(cli(&["resume", "--checkpoint", first, "--epochs", "50", "--output", resumed]).status.success());
+
+    let output = cli(&["compare", first, resumed]);
+    assert!(output.status.success(), "Compare should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Architecture: [2, 3, 1]"), "{}", stdout);
+    assert!(stdout.contains("epoch: 100 -> 150"), "{}", stdout);
+    assert!(stdout.contains("Weights L2"), "{}", stdout);
+    assert!(stdout.contains("Total L2 distance"), "{}", stdout);
+
+    let output = cli(&["compare", first, first]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("No differences") && stdout.contains("The networks are identical"), "{}", stdout);
+}
+
+#[test]
+fn test_compare_incompatible_architectures() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let xor_path = temp_dir.path().join("xor.json");
+    let iris_path = temp_dir.path().join("iris.json");
+    let (xor, iris) = (xor_path.to_str().unwrap(), iris_path.to_str().unwrap());
+
+    assert!(cli(&["train", "--example", "xor", "--epochs", "10", "--output", xor]).status.success());
+    assert!(cli(&["train", "--example", "iris", "--epochs", "1", "--output", iris]).status.success());
+
+    let output = cli(&["compare", xor, iris]);
+    assert!(!output.status.success(), "Compare should fail for different architectures");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Incompatible architectures"));
+}
+
+#[test]
+fn test_compare_evaluates_both_models() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let first_path = temp_dir.path().join("first.json");
+    let resumed_path = temp_dir.path().join("resumed.json");
+    let data_path = temp_dir.path().join("xor.csv");
+    let (first, resumed, data) = (
+        first_path.to_str().unwrap(),
+        resumed_path.to_str().unwrap(),
+        data_path.to_str().unwrap(),
+    );
+
+    assert!(cli(&["train", "--example", "xor", "--epochs", "100", "--output", first]).status.success());
+    assert!(cli(&["resume", "--checkpoint", first, "--epochs", "50", "--output", resumed]).status.success());
+
+    let output = cli(&["compare", first, resumed, "--example", "xor"]);
+    assert!(output.status.success(), "Compare should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Evaluation on xor (4 samples):"), "{}", stdout);
+    assert!(stdout.contains("Loss") && stdout.contains("Accuracy"), "{}", stdout);
+    assert!(stdout.contains("model has the lower loss") || stdout.contains("same loss"), "{}", stdout);
+
+    std::fs::write(&data_path, "a,b,xor\n0,1,1\n1,1,0\n").unwrap();
+    let output = cli(&["compare", first, resumed, "--data", data]);
+    assert!(output.status.success(), "Compare should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("(2 samples)"));
+
+    let output = cli(&["compare", first, resumed, "--example", "iris"]);
+    assert!(!output.status.success(), "Evaluating on data of another shape should fail");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("does not fit"));
+}
@@ -0,0 +1,77 @@
+// Integration tests for the convert command
+mod common;
+
+use common::cli;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn convert(input: &Path, output: &Path) -> String {
+    let result = cli(&["convert", "--input", input.to_str().unwrap(), "--output", output.to_str().unwrap()]);
+    assert!(result.status.success(), "Convert should succeed: {}", String::from_utf8_lossy(&result.stderr));
+    assert!(output.exists(), "{} should be written", output.display());
+    String::from_utf8_lossy(&result.stdout).to_string()
+}
+
+fn network(checkpoint: &Path) -> serde_json::Value {
+    let checkpoint: serde_json::Value = serde_json::from_str(&fs::read_to_string(checkpoint).unwrap()).unwrap();
+    checkpoint["network"].clone()
+}
+
+#[test]
+fn test_convert_through_every_format_keeps_the_parameters() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let path = |name: &str| temp_dir.path().join(name);
+
+    let output = cli(&[
+        "train", "--example", "xor", "--epochs", "200", "--learning-rate", "0.3", "--output",
+        path("model.json").to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "Training should succeed");
+
+    let stdout = convert(&path("model.json"), &path("model.bin.zst"));
+    assert!(stdout.contains("Converted checkpoint model"), "{}", stdout);
+    assert!(stdout.contains("Checkpoint saved to"), "{}", stdout);
+
+    let stdout = convert(&path("model.bin.zst"), &path("model.npz"));
+    assert!(stdout.contains("Only the network is kept"), "{}", stdout);
+    assert!(stdout.contains("Npz file saved to"), "{}", stdout);
+    convert(&path("model.npz"), &path("model.safetensors"));
+    let stdout = convert(&path("model.safetensors"), &path("model.onnx"));
+    assert!(stdout.contains("ONNX model saved to"), "{}", stdout);
+    convert(&path("model.onnx"), &path("roundtrip.json"));
+
+    let (original, roundtrip) = (network(&path("model.json")), network(&path("roundtrip.json")));
+    assert_eq!(roundtrip["layers"], original["layers"]);
+    assert_eq!(roundtrip["weights"], original["weights"]);
+    assert_eq!(roundtrip["biases"], original["biases"]);
+
+    // Checkpoint to checkpoint keeps the metadata and history
+    let read = |name: &str| -> serde_json::Value {
+        serde_json::from_str(&fs::read_to_string(path(name)).unwrap()).unwrap()
+    };
+    convert(&path("model.bin.zst"), &path("copy.json"));
+    let (original, copy) = (read("model.json"), read("copy.json"));
+    assert_eq!(copy["metadata"], original["metadata"]);
+    assert_eq!(copy["history"], original["history"]);
+}
+
+#[test]
+fn test_convert_errors() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let output_path = temp_dir.path().join("model.npz");
+    let output = cli(&["convert", "--input", "/nonexistent/model.json", "--output", output_path.to_str().unwrap()]);
+    assert!(!output.status.success(), "Convert should fail for a missing model");
+    assert!(!output_path.exists());
+
+    let model_path = temp_dir.path().join("relu.json");
+    let onnx_path = temp_dir.path().join("relu.onnx");
+    let output = cli(&[
+        "train", "--example", "xor", "--epochs", "10", "--activation", "relu", "--output",
+        model_path.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "Training should succeed");
+    let output = cli(&["convert", "--input", model_path.to_str().unwrap(), "--output", onnx_path.to_str().unwrap()]);
+    assert!(!output.status.success(), "ONNX export of a relu network should fail");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Only sigmoid networks"));
+}
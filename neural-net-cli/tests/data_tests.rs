@@ -0,0 +1,63 @@
+// Integration tests for the data command
+use std::fs;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn inspect(args: &[&str]) -> String {
+    let output = Command::new("cargo")
+        .args(["run", "--bin", "neural-net-cli", "--", "data", "inspect"])
+        .args(args)
+        .output()
+        .expect("Failed to run CLI");
+    assert!(
+        output.status.success(),
+        "Inspect should succeed. stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn test_data_inspect_csv_names_columns_and_warns() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("data.csv");
+    fs::write(&path, "width,depth,label\n1,7,0\n3,7,1\n2,nan,1\n").unwrap();
+
+    let stdout = inspect(&[path.to_str().unwrap()]);
+    assert!(stdout.contains("Samples: 3"), "stdout: {}", stdout);
+    assert!(stdout.contains("Inputs (2):"), "stdout: {}", stdout);
+    assert!(
+        stdout.lines().any(|l| l.split_whitespace().eq(["width", "2.000000", "0.816497", "1.000000", "3.000000"])),
+        "stdout: {}",
+        stdout
+    );
+    assert!(stdout.contains("Class 1: 2 (66.7%)"), "stdout: {}", stdout);
+    assert!(stdout.contains("depth has 1 NaN or infinite values"), "stdout: {}", stdout);
+    assert!(stdout.contains("depth is constant"), "stdout: {}", stdout);
+}
+
+#[test]
+fn test_data_inspect_example() {
+    let stdout = inspect(&["--example", "iris"]);
+    assert!(stdout.contains("Samples: 60"), "stdout: {}", stdout);
+    assert!(stdout.contains("Targets (3):"), "stdout: {}", stdout);
+    assert!(stdout.contains("Class 2: 20 (33.3%)"), "stdout: {}", stdout);
+    assert!(!stdout.contains("Warnings:"), "stdout: {}", stdout);
+}
+
+#[test]
+#[cfg(not(feature = "parquet"))]
+fn test_data_inspect_parquet_needs_feature() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("data.parquet");
+    fs::write(&path, "PAR1").unwrap();
+
+    let output = Command::new("cargo")
+        .args(["run", "--bin", "neural-net-cli", "--", "data", "inspect"])
+        .arg(&path)
+        .output()
+        .expect("Failed to run CLI");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("needs a build with --features parquet"), "stderr: {}", stderr);
+}
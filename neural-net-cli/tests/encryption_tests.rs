@@ -0,0 +1,57 @@
+// Integration tests for encrypted checkpoints on the command line
+mod common;
+
+use common::cli;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_train_eval_and_resume_with_key_file() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let key_path = temp_dir.path().join("model.key");
+    fs::write(&key_path, [42u8; 32]).unwrap();
+    let model_path = temp_dir.path().join("model.json");
+    let resumed_path = temp_dir.path().join("resumed.json");
+    let (key, model, resumed) = (
+        key_path.to_str().unwrap(),
+        model_path.to_str().unwrap(),
+        resumed_path.to_str().unwrap(),
+    );
+
+    let output = cli(&["train", "--example", "xor", "--epochs", "100", "--output", model, "--key-file", key]);
+    assert!(output.status.success(), "Training should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(fs::read(&model_path).unwrap().starts_with(b"NNCE"), "The model is saved encrypted");
+
+    let output = cli(&["eval", "--model", model, "--input", "1,0"]);
+    assert!(!output.status.success(), "Eval without the key should fail");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("encrypted"), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let output = cli(&["eval", "--model", model, "--input", "1,0", "--key-file", key]);
+    assert!(output.status.success(), "Eval should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Output: ["));
+
+    let output = cli(&["resume", "--checkpoint", model, "--epochs", "20", "--output", resumed, "--key-file", key]);
+    assert!(output.status.success(), "Resume should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(fs::read(&resumed_path).unwrap().starts_with(b"NNCE"), "The resumed model stays encrypted");
+}
+
+#[test]
+fn test_password_env_must_be_set() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let model_path = temp_dir.path().join("model.json");
+
+    let output = cli(&[
+        "train",
+        "--example",
+        "and",
+        "--epochs",
+        "10",
+        "--output",
+        model_path.to_str().unwrap(),
+        "--password-env",
+        "NEURAL_NET_TEST_UNSET_PASSWORD",
+    ]);
+    assert!(!output.status.success(), "Training without the password should fail");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("NEURAL_NET_TEST_UNSET_PASSWORD"));
+    assert!(!model_path.exists());
+}
@@ -0,0 +1,80 @@
+// Integration tests for ensemble command
+use std::process::Command;
+use tempfile::TempDir;
+
+fn train_model(path: &std::path::Path, seed: &str) {
+    Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "train",
+            "--example",
+            "and",
+            "--epochs",
+            "2000",
+            "--seed",
+            seed,
+            "--output",
+            path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to train");
+}
+
+#[test]
+fn test_ensemble_combines_checkpoints() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let first = temp_dir.path().join("first.json");
+    let second = temp_dir.path().join("second.json");
+    train_model(&first, "1");
+    train_model(&second, "2");
+
+    let models = format!("{},{}", first.display(), second.display());
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "ensemble",
+            "--models",
+            &models,
+            "--strategy",
+            "vote",
+        ])
+        .output()
+        .expect("Failed to run ensemble");
+
+    assert!(output.status.success(), "Ensemble should succeed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Ensemble of 2 models"));
+    assert_eq!(stdout.matches("Ensemble:").count(), 4, "One prediction per AND input");
+}
+
+#[test]
+fn test_ensemble_with_input() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let model = temp_dir.path().join("model.json");
+    train_model(&model, "3");
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "ensemble",
+            "--models",
+            model.to_str().unwrap(),
+            "--input",
+            "1.0,1.0",
+        ])
+        .output()
+        .expect("Failed to run ensemble");
+
+    assert!(output.status.success(), "Ensemble should succeed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Input: [1.0, 1.0]"));
+}
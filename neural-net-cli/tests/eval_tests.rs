@@ -1,4 +1,5 @@
 // Integration tests for eval command
+use std::fs;
 use std::process::Command;
 use tempfile::TempDir;
 
@@ -359,3 +360,120 @@ fn test_eval_shows_model_info() {
 
     // TempDir automatically cleans up when dropped
 }
+
+#[test]
+fn test_eval_applies_saved_normalization() {
+    let temp_dir = create_temp_dir();
+    let model_path = temp_dir.path().join("iris.json");
+
+    let train = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "train",
+            "--example",
+            "iris",
+            "--epochs",
+            "300",
+            "--normalize",
+            "zscore",
+            "--output",
+            model_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to train");
+    assert!(train.status.success());
+    assert!(String::from_utf8_lossy(&train.stdout).contains("Input normalization: zscore"));
+
+    // Raw measurements of a setosa flower, in cm
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "eval",
+            "--model",
+            model_path.to_str().unwrap(),
+            "--input",
+            "5.1,3.5,1.4,0.2",
+        ])
+        .output()
+        .expect("Failed to eval");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Input normalization: zscore"));
+
+    let line = stdout.lines().find(|l| l.starts_with("Output:")).expect("Should show output");
+    let scores: Vec<f64> = line
+        .trim_start_matches("Output: [")
+        .trim_end_matches(']')
+        .split(", ")
+        .map(|v| v.parse().unwrap())
+        .collect();
+    assert!(scores[0] > scores[1] && scores[0] > scores[2], "Should predict setosa: {:?}", scores);
+}
+
+#[test]
+fn test_eval_inverts_target_scaling() {
+    let temp_dir = create_temp_dir();
+    let data_path = temp_dir.path().join("line.csv");
+    let rows: String = (0..=10).map(|i| format!("{},{}\n", i as f64 / 10.0, 100 * i + 500)).collect();
+    fs::write(&data_path, rows).unwrap();
+    let model_path = temp_dir.path().join("line.json");
+
+    let train = Command::new("cargo")
+        .args(["run", "--bin", "neural-net-cli", "--", "train", "--data"])
+        .arg(&data_path)
+        .args(["--hidden", "4", "--epochs", "3000", "--seed", "3", "--normalize-targets", "minmax", "--output"])
+        .arg(&model_path)
+        .output()
+        .expect("Failed to train");
+    assert!(train.status.success(), "stderr: {}", String::from_utf8_lossy(&train.stderr));
+    assert!(String::from_utf8_lossy(&train.stdout).contains("Target scaling: minmax"));
+
+    let output = Command::new("cargo")
+        .args(["run", "--bin", "neural-net-cli", "--", "eval", "--model"])
+        .arg(&model_path)
+        .args(["--input", "0.5"])
+        .output()
+        .expect("Failed to eval");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Target scaling: minmax"), "stdout: {}", stdout);
+
+    let line = stdout.lines().find(|l| l.starts_with("Output:")).expect("Should show output");
+    let value: f64 = line.trim_start_matches("Output: [").trim_end_matches(']').parse().unwrap();
+    assert!((value - 1000.0).abs() < 20.0, "Output in target units: {}", value);
+}
+
+#[test]
+fn test_eval_checkpoint_alias_with_activations() {
+    let temp_dir = create_temp_dir();
+    let model_path = temp_dir.path().join("xor.json");
+
+    let output = Command::new("cargo")
+        .args(["run", "--bin", "neural-net-cli", "--", "train", "--example", "xor", "--epochs", "100"])
+        .args(["--output", model_path.to_str().unwrap()])
+        .output()
+        .expect("Failed to train");
+    assert!(output.status.success(), "Training should succeed");
+
+    let output = Command::new("cargo")
+        .args(["run", "--bin", "neural-net-cli", "--", "eval", "--checkpoint", model_path.to_str().unwrap()])
+        .args(["--input", "1,0", "--activations"])
+        .output()
+        .expect("Failed to run eval");
+    assert!(output.status.success(), "Eval should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let output_line = stdout.lines().find(|l| l.starts_with("Output: ")).expect("Output line");
+    let layers: Vec<&str> = stdout.lines().filter(|l| l.starts_with("  Layer ")).collect();
+    assert_eq!(layers.len(), 3, "{}", stdout);
+    assert_eq!(layers[0], "  Layer 0 (input): [1.0, 0.0]");
+    assert!(layers[1].starts_with("  Layer 1: ["), "{}", stdout);
+    assert_eq!(layers[2].trim_start_matches("  Layer 2 (output): "), output_line.trim_start_matches("Output: "));
+}
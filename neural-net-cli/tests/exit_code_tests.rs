@@ -0,0 +1,52 @@
+// Integration tests for the exit codes of failure categories
+use std::process::Command;
+use tempfile::TempDir;
+
+fn exit_code(args: &[&str]) -> Option<i32> {
+    Command::new("cargo")
+        .args(["run", "--bin", "neural-net-cli", "--"])
+        .args(args)
+        .output()
+        .expect("Failed to run neural-net-cli")
+        .status
+        .code()
+}
+
+#[test]
+fn test_success_exits_with_zero() {
+    assert_eq!(exit_code(&["list"]), Some(0));
+}
+
+#[test]
+fn test_bad_arguments_exit_with_two() {
+    assert_eq!(exit_code(&["train", "--epochs", "many"]), Some(2), "clap parse error");
+    assert_eq!(exit_code(&["train", "--example", "nonexistent"]), Some(2), "unknown example");
+    assert_eq!(exit_code(&["bench", "--batch", "0"]), Some(2), "out-of-range value");
+}
+
+#[test]
+fn test_missing_file_exits_with_three() {
+    assert_eq!(exit_code(&["eval", "--model", "/nonexistent/model.json"]), Some(3));
+    assert_eq!(exit_code(&["checkpoints", "list", "--dir", "/nonexistent/runs"]), Some(3));
+}
+
+#[test]
+fn test_divergence_exits_with_four() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let data_path = temp_dir.path().join("nan.csv");
+    std::fs::write(&data_path, "1,NaN,1\n0,1,0\n").unwrap();
+
+    assert_eq!(exit_code(&["train", "--data", data_path.to_str().unwrap(), "--epochs", "5"]), Some(4));
+}
+
+#[test]
+fn test_incompatible_checkpoint_exits_with_five() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let not_a_checkpoint = temp_dir.path().join("notes.json");
+    std::fs::write(&not_a_checkpoint, r#"{"notes": true}"#).unwrap();
+    let truncated = temp_dir.path().join("truncated.json");
+    std::fs::write(&truncated, r#"{"metadata": {"version": "1.0""#).unwrap();
+
+    assert_eq!(exit_code(&["eval", "--model", not_a_checkpoint.to_str().unwrap()]), Some(5));
+    assert_eq!(exit_code(&["info", "--checkpoint", truncated.to_str().unwrap()]), Some(5));
+}
@@ -0,0 +1,77 @@
+// Integration tests for export command
+mod common;
+
+use common::cli;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn network(checkpoint: &Path) -> serde_json::Value {
+    let checkpoint: serde_json::Value = serde_json::from_str(&fs::read_to_string(checkpoint).unwrap()).unwrap();
+    checkpoint["network"].clone()
+}
+
+#[test]
+fn test_export_safetensors_and_import_back() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let model_path = temp_dir.path().join("model.json");
+    let tensors_path = temp_dir.path().join("model.safetensors");
+    let imported_path = temp_dir.path().join("imported.json");
+    let (model, tensors, imported) = (
+        model_path.to_str().unwrap(),
+        tensors_path.to_str().unwrap(),
+        imported_path.to_str().unwrap(),
+    );
+
+    let output = cli(&["train", "--example", "xor", "--epochs", "200", "--learning-rate", "0.3", "--output", model]);
+    assert!(output.status.success(), "Training should succeed");
+
+    let output = cli(&["export", "--model", model, "--output", tensors]);
+    assert!(output.status.success(), "Export should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Safetensors file saved to"));
+    assert!(tensors_path.exists(), "Safetensors file should be written");
+
+    let output = cli(&["import", "--model", tensors, "--output", imported, "--example", "xor"]);
+    assert!(output.status.success(), "Import should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Imported safetensors model"));
+
+    let (original, roundtrip) = (network(&model_path), network(&imported_path));
+    assert_eq!(roundtrip["weights"], original["weights"]);
+    assert_eq!(roundtrip["biases"], original["biases"]);
+    assert_eq!(roundtrip["learning_rate"], 0.3, "The learning rate is kept in the metadata");
+}
+
+#[test]
+fn test_export_nonexistent_model() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let output_path = temp_dir.path().join("model.safetensors");
+    let output = cli(&["export", "--model", "/nonexistent/model.json", "--output", output_path.to_str().unwrap()]);
+
+    assert!(!output.status.success(), "Export should fail for a missing model");
+    assert!(!output_path.exists());
+}
+
+#[test]
+fn test_export_stripped_checkpoint() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let model_path = temp_dir.path().join("model.json");
+    let stripped_path = temp_dir.path().join("stripped.json");
+    let (model, stripped) = (model_path.to_str().unwrap(), stripped_path.to_str().unwrap());
+
+    let output = cli(&["train", "--example", "xor", "--epochs", "200", "--normalize", "minmax", "--output", model]);
+    assert!(output.status.success(), "Training should succeed");
+
+    let output = cli(&["export", "--model", model, "--output", stripped, "--strip"]);
+    assert!(output.status.success(), "Export should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Inference-only checkpoint saved to"));
+    assert!(fs::metadata(&stripped_path).unwrap().len() < fs::metadata(&model_path).unwrap().len());
+
+    let checkpoint: serde_json::Value = serde_json::from_str(&fs::read_to_string(&stripped_path).unwrap()).unwrap();
+    assert_eq!(checkpoint["network"], network(&model_path));
+    assert!(checkpoint["normalizer"].is_object(), "Normalizers are kept");
+    assert!(checkpoint["training_state"].is_null());
+    assert_eq!(checkpoint["history"]["epochs"].as_array().map(Vec::len), Some(0));
+
+    let output = cli(&["eval", "--model", stripped, "--input", "1,0"]);
+    assert!(output.status.success(), "Stripped checkpoints evaluate: {}", String::from_utf8_lossy(&output.stderr));
+}
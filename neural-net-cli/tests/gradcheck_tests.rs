@@ -0,0 +1,27 @@
+// Integration tests for the gradcheck command
+mod common;
+
+use common::cli;
+
+#[test]
+fn test_gradcheck_prints_per_layer_errors() {
+    let output = cli(&["gradcheck", "--layers", "2,3,1", "--samples", "10"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "Gradcheck should pass: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("Gradient check of [2, 3, 1] with sigmoid on 10 random samples"), "{}", stdout);
+    assert!(stdout.contains("2->3"), "{}", stdout);
+    assert!(stdout.contains("3->1"), "{}", stdout);
+    assert!(stdout.contains("Back-propagation matches the numerical gradients"), "{}", stdout);
+}
+
+#[test]
+fn test_gradcheck_fails_above_tolerance() {
+    let output = cli(&["gradcheck", "--layers", "3,4,2", "--activation", "tanh", "--tolerance", "0"]);
+    assert!(!output.status.success(), "A zero tolerance should fail");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("FAIL"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Gradient check failed"));
+
+    let output = cli(&["gradcheck", "--layers", "3"]);
+    assert!(!output.status.success(), "A single layer should be rejected");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("at least an input and an output"));
+}
@@ -0,0 +1,150 @@
+// Integration tests for import command
+use std::fs;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn bytes_field(out: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    varint(out, field << 3 | 2);
+    varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+/// A dense layer as `(name, rows, cols, weights, biases)`
+type Layer<'a> = (&'a str, u64, u64, &'a [f32], &'a [f32]);
+
+/// An ONNX graph of Gemm + Sigmoid layers
+fn onnx_mlp(layers: &[Layer]) -> Vec<u8> {
+    let mut graph = Vec::new();
+    let mut input = "input".to_string();
+    for (i, (name, rows, cols, weights, biases)) in layers.iter().enumerate() {
+        let output = if i + 1 == layers.len() { "output".to_string() } else { format!("a{}", i) };
+        let (weight, bias, z) = (format!("{}.weight", name), format!("{}.bias", name), format!("z{}", i));
+
+        let mut gemm = Vec::new();
+        for name in [&input, &weight, &bias] {
+            bytes_field(&mut gemm, 1, name.as_bytes());
+        }
+        bytes_field(&mut gemm, 2, z.as_bytes());
+        bytes_field(&mut gemm, 4, b"Gemm");
+        let mut trans_b = Vec::new();
+        bytes_field(&mut trans_b, 1, b"transB");
+        trans_b.extend_from_slice(&[3 << 3, 1]);
+        bytes_field(&mut gemm, 5, &trans_b);
+        bytes_field(&mut graph, 1, &gemm);
+
+        let mut sigmoid = Vec::new();
+        bytes_field(&mut sigmoid, 1, z.as_bytes());
+        bytes_field(&mut sigmoid, 2, output.as_bytes());
+        bytes_field(&mut sigmoid, 4, b"Sigmoid");
+        bytes_field(&mut graph, 1, &sigmoid);
+
+        for (tensor_name, dims, values) in [(&weight, vec![*rows, *cols], *weights), (&bias, vec![*rows], *biases)] {
+            let mut tensor = Vec::new();
+            for dim in dims {
+                tensor.push(1 << 3);
+                varint(&mut tensor, dim);
+            }
+            tensor.extend_from_slice(&[2 << 3, 1]);
+            bytes_field(&mut tensor, 8, tensor_name.as_bytes());
+            let raw: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+            bytes_field(&mut tensor, 9, &raw);
+            bytes_field(&mut graph, 5, &tensor);
+        }
+        input = output;
+    }
+    for (field, name) in [(11, "input"), (12, "output")] {
+        let mut value_info = Vec::new();
+        bytes_field(&mut value_info, 1, name.as_bytes());
+        bytes_field(&mut graph, field, &value_info);
+    }
+
+    let mut model = Vec::new();
+    bytes_field(&mut model, 7, &graph);
+    model
+}
+
+#[test]
+fn test_import_onnx_and_resume_training() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let onnx_path = temp_dir.path().join("xor.onnx");
+    let checkpoint_path = temp_dir.path().join("xor.json");
+    let model = onnx_mlp(&[
+        ("fc1", 2, 2, &[0.5, -0.5, 0.25, 0.75], &[0.1, -0.1]),
+        ("fc2", 1, 2, &[1.0, -1.0], &[0.0]),
+    ]);
+    fs::write(&onnx_path, model).unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "import",
+            "--model",
+            onnx_path.to_str().unwrap(),
+            "--output",
+            checkpoint_path.to_str().unwrap(),
+            "--example",
+            "xor",
+        ])
+        .output()
+        .expect("Failed to run import");
+
+    assert!(output.status.success(), "Import should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Architecture: [2, 2, 1]"), "{}", stdout);
+    assert!(stdout.contains("Parameters: 9"), "{}", stdout);
+    let checkpoint: serde_json::Value = serde_json::from_str(&fs::read_to_string(&checkpoint_path).unwrap()).unwrap();
+    assert_eq!(checkpoint["metadata"]["example"], "xor");
+    assert_eq!(checkpoint["metadata"]["epoch"], 0);
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "resume",
+            "--checkpoint",
+            checkpoint_path.to_str().unwrap(),
+            "--epochs",
+            "10",
+        ])
+        .output()
+        .expect("Failed to run resume");
+    assert!(output.status.success(), "Imported model should fine-tune: {}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn test_import_rejects_invalid_model() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let onnx_path = temp_dir.path().join("bad.onnx");
+    fs::write(&onnx_path, b"not an onnx model").unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "import",
+            "--model",
+            onnx_path.to_str().unwrap(),
+            "--output",
+            temp_dir.path().join("out.json").to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to run import");
+
+    assert!(!output.status.success(), "Import should fail for an invalid model");
+    assert!(!temp_dir.path().join("out.json").exists());
+}
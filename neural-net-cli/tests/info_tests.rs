@@ -333,3 +333,107 @@ fn test_info_with_corrupted_file() {
 
     // TempDir automatically cleans up when dropped
 }
+
+#[test]
+fn test_info_displays_resume_lineage() {
+    let temp_dir = create_temp_dir();
+    let first = temp_dir.path().join("first.json");
+    let second = temp_dir.path().join("second.json");
+
+    let run = |args: &[&str]| {
+        let output = Command::new("cargo")
+            .args(["run", "--bin", "neural-net-cli", "--"])
+            .args(args)
+            .output()
+            .expect("Failed to run CLI");
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+    run(&["train", "--example", "and", "--epochs", "40", "--output", first.to_str().unwrap()]);
+    run(&["resume", "--checkpoint", first.to_str().unwrap(), "--epochs", "20", "--output", second.to_str().unwrap()]);
+
+    let stdout = run(&["info", "--model", second.to_str().unwrap()]);
+    assert!(stdout.contains("Training Epochs: 60"), "{}", stdout);
+    assert!(stdout.contains("Total Epochs: 60"), "{}", stdout);
+    assert!(stdout.contains("Lineage:"), "{}", stdout);
+    assert!(stdout.contains(&format!("Resumed from {} at epoch 40", first.display())), "{}", stdout);
+
+    let stdout = run(&["info", "--model", first.to_str().unwrap()]);
+    assert!(!stdout.contains("Lineage:"), "{}", stdout);
+}
+
+#[test]
+fn test_info_displays_training_details() {
+    let temp_dir = create_temp_dir();
+    let model_path = temp_dir.path().join("model.json");
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "train",
+            "--example",
+            "xor",
+            "--epochs",
+            "50",
+            "--seed",
+            "7",
+            "--output",
+            model_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to train");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let output = Command::new("cargo")
+        .args(["run", "--bin", "neural-net-cli", "--", "info", "--model", model_path.to_str().unwrap()])
+        .output()
+        .expect("Failed to run info");
+    assert!(output.status.success(), "Info should succeed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("Created: "), "{}", stdout);
+    assert!(stdout.contains("Crate version: "), "{}", stdout);
+    assert!(stdout.contains("Training time: "), "{}", stdout);
+    assert!(stdout.contains("Final loss: "), "{}", stdout);
+    assert!(stdout.contains("Seed: 7"), "{}", stdout);
+    assert!(stdout.contains("Optimizer: sgd"), "{}", stdout);
+    assert!(stdout.contains("Dataset fingerprint: "), "{}", stdout);
+}
+
+#[test]
+fn test_info_checkpoint_tables_and_json() {
+    let temp_dir = create_temp_dir();
+    let model_path = temp_dir.path().join("model.json");
+    let model = model_path.to_str().unwrap();
+    let cli = |args: &[&str]| {
+        Command::new("cargo")
+            .args(["run", "--bin", "neural-net-cli", "--"])
+            .args(args)
+            .output()
+            .expect("Failed to run neural-net-cli")
+    };
+
+    let output = cli(&["train", "--example", "xor", "--epochs", "50", "--output", model]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let output = cli(&["info", "--checkpoint", model]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Parameters:"), "{}", stdout);
+    assert!(stdout.contains("Total Parameters: 13"), "{}", stdout);
+    assert!(stdout.contains("History:"), "{}", stdout);
+    assert!(stdout.contains("Epochs recorded: 50"), "{}", stdout);
+
+    let output = cli(&["info", "--checkpoint", model, "--format", "json"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("Info should print JSON");
+    assert_eq!(json["architecture"], serde_json::json!([2, 3, 1]));
+    assert_eq!(json["total_parameters"], 13);
+    assert_eq!(json["layers"][0]["weights"], 6);
+    assert!(json["layers"][0]["weight_stats"]["std_dev"].is_number());
+    assert_eq!(json["history"]["epochs"], 50);
+    assert_eq!(json["metadata"]["example"], "xor");
+}
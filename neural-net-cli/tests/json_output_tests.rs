@@ -0,0 +1,48 @@
+// Integration tests for --format json
+mod common;
+
+use common::cli;
+use serde_json::Value;
+use std::process::Output;
+use tempfile::TempDir;
+
+/// The JSON document a successful command printed on stdout
+fn json(output: &Output) -> Value {
+    assert!(output.status.success(), "Command should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout)
+        .unwrap_or_else(|e| panic!("stdout should be JSON ({}): {}", e, String::from_utf8_lossy(&output.stdout)))
+}
+
+#[test]
+fn test_json_output_of_train_eval_info_and_compare() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let model_path = temp_dir.path().join("and_model.json");
+    let model = model_path.to_str().unwrap();
+
+    let output = cli(&["train", "--example", "and", "--epochs", "300", "--seed", "5", "--output", model, "--format", "json"]);
+    let train = json(&output);
+    assert_eq!(train["name"], "and");
+    assert_eq!(train["architecture"], serde_json::json!([2, 2, 1]));
+    assert_eq!((train["epochs"].as_u64(), train["seed"].as_u64()), (Some(300), Some(5)));
+    assert_eq!(train["final_loss"], train["final_epoch"]["loss"]);
+    assert_eq!(train["output"], model);
+    // The readable report moves to stderr
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Model saved successfully!"));
+
+    let eval = json(&cli(&["--format", "json", "eval", "--model", model, "--input", "1,1", "--activations"]));
+    assert_eq!(eval["input"], serde_json::json!([1.0, 1.0]));
+    assert_eq!(eval["output"].as_array().unwrap().len(), 1);
+    assert_eq!(eval["activations"].as_array().unwrap().len(), 3);
+
+    let info = json(&cli(&["info", "--model", model, "--format", "json"]));
+    assert_eq!(info["metadata"]["epoch"], 300);
+
+    let compare = json(&cli(&["compare", model, model, "--example", "and", "--format", "json"]));
+    assert_eq!(compare["total_l2"], 0.0);
+    assert_eq!(compare["evaluation"]["before"]["samples"], 4);
+    assert_eq!(compare["evaluation"]["before"], compare["evaluation"]["after"]);
+
+    let output = cli(&["list", "--format", "json"]);
+    assert!(!output.status.success(), "Commands without JSON output should refuse it");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--format json is supported by train, eval, info, compare and checkpoints list"));
+}
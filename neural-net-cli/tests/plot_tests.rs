@@ -0,0 +1,31 @@
+// Integration tests for the plot-loss command
+mod common;
+
+use common::cli;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_plot_loss_writes_svg() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let model_path = temp_dir.path().join("xor_model.json");
+    let svg_path = temp_dir.path().join("loss.svg");
+    let (model, svg) = (model_path.to_str().unwrap(), svg_path.to_str().unwrap());
+
+    let output = cli(&["train", "--example", "xor", "--epochs", "200", "--output", model]);
+    assert!(output.status.success(), "Training should succeed: {}", String::from_utf8_lossy(&output.stderr));
+
+    let output = cli(&["plot-loss", "--checkpoint", model, "--output", svg, "--log-scale", "--width", "640"]);
+    assert!(output.status.success(), "Plotting should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Plotted the loss over 200 recorded epochs on a log scale"), "{}", stdout);
+
+    let chart = fs::read_to_string(&svg_path).unwrap();
+    assert!(chart.starts_with("<?xml"));
+    assert!(chart.contains("width=\"640\""));
+    assert!(chart.contains("Training loss"));
+    assert!(chart.contains("MSE (log scale)"));
+
+    let output = cli(&["plot-loss", "--model", temp_dir.path().join("missing.json").to_str().unwrap(), "--output", svg]);
+    assert!(!output.status.success(), "A missing model should fail");
+}
@@ -0,0 +1,60 @@
+// Integration tests for the predict command
+mod common;
+
+use common::cli;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_predict_csv_with_argmax() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let model_path = temp_dir.path().join("and_model.json");
+    let data_path = temp_dir.path().join("inputs.csv");
+    let preds_path = temp_dir.path().join("preds.csv");
+    let (model, data, preds) = (
+        model_path.to_str().unwrap(),
+        data_path.to_str().unwrap(),
+        preds_path.to_str().unwrap(),
+    );
+
+    let output = cli(&["train", "--example", "and", "--epochs", "5000", "--output", model]);
+    assert!(output.status.success(), "Training should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    fs::write(&data_path, "a,b\n0,0\n0,1\n\n1,0\n1,1\n").unwrap();
+
+    let output = cli(&["predict", "--checkpoint", model, "--data", data, "--output", preds, "--argmax"]);
+    assert!(output.status.success(), "Predict should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Predicted 4 rows"));
+
+    let written = fs::read_to_string(&preds_path).unwrap();
+    let lines: Vec<&str> = written.lines().collect();
+    assert_eq!(lines[0], "output_0,class");
+    assert_eq!(lines.len(), 5, "One line per input row after the header: {}", written);
+    let classes: Vec<&str> = lines[1..].iter().map(|line| line.rsplit(',').next().unwrap()).collect();
+    assert_eq!(classes, ["0", "0", "0", "1"], "AND gate classes: {}", written);
+}
+
+#[test]
+fn test_predict_to_stdout_and_bad_rows() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let model_path = temp_dir.path().join("xor_model.json");
+    let data_path = temp_dir.path().join("inputs.csv");
+    let (model, data) = (model_path.to_str().unwrap(), data_path.to_str().unwrap());
+
+    let output = cli(&["train", "--example", "xor", "--epochs", "100", "--output", model]);
+    assert!(output.status.success(), "Training should succeed: {}", String::from_utf8_lossy(&output.stderr));
+
+    fs::write(&data_path, "0,1\n1,1\n").unwrap();
+    let output = cli(&["predict", "--model", model, "--data", data]);
+    assert!(output.status.success(), "Predict should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 3, "Header and two predictions: {}", stdout);
+    assert_eq!(lines[0], "output_0");
+    assert!(lines[1].parse::<f64>().is_ok(), "{}", stdout);
+
+    fs::write(&data_path, "0,1\n1,1,0\n").unwrap();
+    let output = cli(&["predict", "--model", model, "--data", data]);
+    assert!(!output.status.success(), "A row of the wrong width should fail");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(":2: expected 2 inputs, got 3"), "{}", stderr);
+}
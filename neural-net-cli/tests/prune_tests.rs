@@ -0,0 +1,98 @@
+// Integration tests for prune command
+use std::process::Command;
+use tempfile::TempDir;
+
+fn train_xor(model_path: &str) {
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "train",
+            "--example",
+            "xor",
+            "--epochs",
+            "1000",
+            "--output",
+            model_path,
+        ])
+        .output()
+        .expect("Failed to train");
+    assert!(output.status.success(), "Training should succeed");
+}
+
+#[test]
+fn test_prune_reports_and_saves() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let model_path = temp_dir.path().join("model.json");
+    let pruned_path = temp_dir.path().join("pruned.json");
+    train_xor(model_path.to_str().unwrap());
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "prune",
+            "--model",
+            model_path.to_str().unwrap(),
+            "--sparsity",
+            "0.8",
+            "--output",
+            pruned_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to run prune");
+
+    assert!(output.status.success(), "Prune should succeed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("77.8% of weights are now zero"), "Should report the sparsity: {}", stdout);
+    assert!(stdout.contains("dense:  104 bytes"), "Should report the dense size");
+    assert!(stdout.contains("after:  6 nonzero of 13"), "Should report the parameter count");
+    assert!(stdout.contains("Loss on 'xor'"), "Should report the loss");
+    assert!(pruned_path.exists(), "Pruned model should be written");
+
+    // The pruned checkpoint loads like any other
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "eval",
+            "--model",
+            pruned_path.to_str().unwrap(),
+            "--input",
+            "1,0",
+        ])
+        .output()
+        .expect("Failed to run eval");
+    assert!(output.status.success(), "Pruned model should load");
+}
+
+#[test]
+fn test_prune_rejects_invalid_sparsity() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let model_path = temp_dir.path().join("model.json");
+    train_xor(model_path.to_str().unwrap());
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "prune",
+            "--model",
+            model_path.to_str().unwrap(),
+            "--sparsity",
+            "1.5",
+        ])
+        .output()
+        .expect("Failed to run prune");
+
+    assert!(!output.status.success(), "Sparsity above 1 should be rejected");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Sparsity must be between 0 and 1"));
+}
@@ -0,0 +1,127 @@
+// Integration tests for quantize command
+use std::process::Command;
+use tempfile::TempDir;
+
+#[test]
+fn test_quantize_reports_size_and_saves() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let model_path = temp_dir.path().join("model.json");
+    let quantized_path = temp_dir.path().join("model.q8.json");
+
+    Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "train",
+            "--example",
+            "xor",
+            "--epochs",
+            "1000",
+            "--output",
+            model_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to train");
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "quantize",
+            "--model",
+            model_path.to_str().unwrap(),
+            "--output",
+            quantized_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to run quantize");
+
+    assert!(output.status.success(), "Quantize should succeed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("int8"), "Should report int8 size");
+    assert!(stdout.contains("Max output difference"), "Should compare outputs");
+    assert!(stdout.contains("us/prediction"), "Should report inference speed");
+    assert!(quantized_path.exists(), "Quantized model should be written");
+}
+
+#[test]
+fn test_quantize_nonexistent_model() {
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "quantize",
+            "--model",
+            "/nonexistent/model.json",
+        ])
+        .output()
+        .expect("Failed to run quantize");
+
+    assert!(!output.status.success(), "Quantize should fail for a missing model");
+}
+
+#[test]
+fn test_quantize_bits_reports_loss() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let model_path = temp_dir.path().join("model.json");
+
+    Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "train",
+            "--example",
+            "xor",
+            "--epochs",
+            "1000",
+            "--output",
+            model_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to train");
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "quantize",
+            "--model",
+            model_path.to_str().unwrap(),
+            "--bits",
+            "4",
+        ])
+        .output()
+        .expect("Failed to run quantize");
+
+    assert!(output.status.success(), "Quantize should succeed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("int4:"), "Should report int4 size");
+    assert!(stdout.contains("Parameters: 13"), "Should report the parameter count");
+    assert!(stdout.contains("Loss (f64)") && stdout.contains("Loss (int4)"), "Should compare losses");
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "quantize",
+            "--model",
+            model_path.to_str().unwrap(),
+            "--bits",
+            "16",
+        ])
+        .output()
+        .expect("Failed to run quantize");
+    assert!(!output.status.success(), "More than 8 bits should be rejected");
+}
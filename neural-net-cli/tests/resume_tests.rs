@@ -372,8 +372,173 @@ fn test_resume_metadata_updated() {
     // Check second checkpoint metadata shows continued training
     let contents2 = fs::read_to_string(&checkpoint2).unwrap();
     let json2: serde_json::Value = serde_json::from_str(&contents2).unwrap();
-    assert_eq!(json2["metadata"]["epoch"], 50);
-    assert_eq!(json2["metadata"]["total_epochs"], 50);
+    assert_eq!(json2["metadata"]["epoch"], 150);
+    assert_eq!(json2["metadata"]["total_epochs"], 150);
+
+    // The resume is recorded with the parent checkpoint it started from
+    let lineage = json2["lineage"].as_array().unwrap();
+    assert_eq!(lineage.len(), 1);
+    assert_eq!(lineage[0]["parent_path"], checkpoint1.to_str().unwrap());
+    assert_eq!(lineage[0]["parent_epoch"], 100);
+    assert_eq!(lineage[0]["parent_checksum"], json1["checksum"]);
+
+    // The loss curve continues across the resume
+    let history = json2["history"]["epochs"].as_array().unwrap();
+    assert_eq!(history.len(), 150);
+    assert_eq!(history[0]["epoch"], 1);
+    assert_eq!(history[149]["epoch"], 150);
 
     // TempDir automatically cleans up when dropped
 }
+
+#[test]
+fn test_resume_keeps_shuffle_and_optimizer_state() {
+    let temp_dir = create_temp_dir();
+    let checkpoint_path = temp_dir.path().join("shuffled.json");
+
+    let train_output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "train",
+            "--example",
+            "xor",
+            "--epochs",
+            "30",
+            "--shuffle",
+            "--seed",
+            "3",
+            "--output",
+            checkpoint_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to run train");
+    assert!(train_output.status.success(), "Training should succeed");
+
+    let checkpoint: serde_json::Value = serde_json::from_str(&fs::read_to_string(&checkpoint_path).unwrap()).unwrap();
+    let state = &checkpoint["training_state"];
+    assert_eq!(state["epochs_trained"], 30);
+    assert_eq!(state["seed"], 3);
+    assert_eq!(state["shuffle"], true);
+
+    let resume_output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "resume",
+            "--checkpoint",
+            checkpoint_path.to_str().unwrap(),
+            "--epochs",
+            "10",
+        ])
+        .output()
+        .expect("Failed to run resume");
+    assert!(resume_output.status.success(), "Resume should succeed");
+
+    let stdout = String::from_utf8_lossy(&resume_output.stdout);
+    assert!(stdout.contains("Optimizer: sgd"));
+    assert!(stdout.contains("Shuffling samples every epoch"), "Shuffling continues after resume");
+}
+
+#[test]
+fn test_resume_with_activation_and_optimizer() {
+    let temp_dir = create_temp_dir();
+    let checkpoint_path = temp_dir.path().join("sigmoid.json");
+    let resumed_path = temp_dir.path().join("relu_momentum.json");
+
+    let train_output = Command::new("cargo")
+        .args(["run", "--bin", "neural-net-cli", "--", "train", "--example", "and", "--epochs", "20", "--output"])
+        .arg(&checkpoint_path)
+        .output()
+        .expect("Failed to run train");
+    assert!(train_output.status.success(), "Training should succeed");
+
+    let resume_output = Command::new("cargo")
+        .args(["run", "--bin", "neural-net-cli", "--", "resume", "--checkpoint"])
+        .arg(&checkpoint_path)
+        .args(["--epochs", "10", "--activation", "relu", "--optimizer", "momentum", "--output"])
+        .arg(&resumed_path)
+        .output()
+        .expect("Failed to run resume");
+    assert!(
+        resume_output.status.success(),
+        "Resume should succeed: {}",
+        String::from_utf8_lossy(&resume_output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&resume_output.stdout);
+    assert!(stdout.contains("Switching activation: sigmoid -> relu"), "stdout: {}", stdout);
+    assert!(stdout.contains("Switching optimizer: sgd -> momentum"), "stdout: {}", stdout);
+
+    let checkpoint: serde_json::Value = serde_json::from_str(&fs::read_to_string(&resumed_path).unwrap()).unwrap();
+    assert_eq!(checkpoint["network"]["activation"], "relu");
+    assert_eq!(checkpoint["metadata"]["optimizer"], "momentum");
+    assert!(checkpoint["training_state"]["optimizer"]["Momentum"].is_object(), "The optimizer state is rebuilt");
+}
+
+#[test]
+fn test_resume_reports_progress() {
+    let temp_dir = create_temp_dir();
+    let checkpoint_path = temp_dir.path().join("checkpoint.json");
+    let checkpoint = checkpoint_path.to_str().unwrap();
+    let run = |args: &[&str]| {
+        let output = Command::new("cargo")
+            .args(["run", "--bin", "neural-net-cli", "--"])
+            .args(args)
+            .output()
+            .expect("Failed to run neural-net-cli");
+        assert!(output.status.success(), "Command should succeed: {}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    run(&["train", "--example", "and", "--epochs", "50", "--output", checkpoint]);
+    let stdout = run(&["resume", "--checkpoint", checkpoint, "--epochs", "200", "-v"]);
+    // Epochs of the resumed run count from 1, so the loss shows at 100 and 200
+    assert!(stdout.contains("Epoch 100: loss") && stdout.contains("Epoch 200: loss"), "{}", stdout);
+    assert!(stdout.contains("ms per epoch), final loss: "), "{}", stdout);
+}
+
+#[test]
+fn test_resume_overrides_are_recorded() {
+    let temp_dir = create_temp_dir();
+    let first = temp_dir.path().join("first.json");
+    let second = temp_dir.path().join("second.json");
+    let third = temp_dir.path().join("third.json");
+    let run = |args: &[&str]| {
+        let output = Command::new("cargo")
+            .args(["run", "--bin", "neural-net-cli", "--"])
+            .args(args)
+            .output()
+            .expect("Failed to run neural-net-cli");
+        assert!(output.status.success(), "Command should succeed: {}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+    let (first, second, third) = (first.to_str().unwrap(), second.to_str().unwrap(), third.to_str().unwrap());
+
+    run(&["train", "--example", "and", "--epochs", "30", "--output", first]);
+    let stdout = run(&["resume", "--checkpoint", first, "--epochs", "20", "--learning-rate", "0.1", "--batch-size", "2", "--output", second]);
+    assert!(stdout.contains("Switching learning_rate: 0.5 -> 0.1"), "{}", stdout);
+    assert!(stdout.contains("Switching batch_size: 1 -> 2"), "{}", stdout);
+
+    let checkpoint: serde_json::Value = serde_json::from_str(&fs::read_to_string(second).unwrap()).unwrap();
+    assert_eq!(checkpoint["metadata"]["learning_rate"], 0.1);
+    assert_eq!(checkpoint["training_state"]["batch_size"], 2);
+    let overrides = &checkpoint["metadata"]["overrides"];
+    assert_eq!(overrides[0], serde_json::json!({"name": "learning_rate", "before": "0.5", "after": "0.1", "epoch": 30}));
+    assert_eq!(overrides[1]["name"], "batch_size");
+
+    // Later resumes keep the earlier overrides and add their own
+    run(&["resume", "--checkpoint", second, "--epochs", "10", "--learning-rate", "0.05", "--output", third]);
+    let checkpoint: serde_json::Value = serde_json::from_str(&fs::read_to_string(third).unwrap()).unwrap();
+    let overrides = checkpoint["metadata"]["overrides"].as_array().unwrap();
+    assert_eq!(overrides.len(), 3);
+    assert_eq!(overrides[2], serde_json::json!({"name": "learning_rate", "before": "0.1", "after": "0.05", "epoch": 50}));
+    assert_eq!(checkpoint["training_state"]["batch_size"], 2, "The batch size carries over");
+
+    let info = run(&["info", "--model", third]);
+    assert!(info.contains("learning_rate 0.1 -> 0.05 after epoch 50"), "{}", info);
+}
@@ -0,0 +1,56 @@
+// Integration tests for the serve command
+mod common;
+
+use common::cli;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+/// GET `path` from the server on `port`, retrying until it is up
+fn get(port: u16, path: &str) -> String {
+    let start = Instant::now();
+    loop {
+        if let Ok(mut stream) = TcpStream::connect(("127.0.0.1", port)) {
+            write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            return response;
+        }
+        assert!(start.elapsed() < Duration::from_secs(30), "The server did not start");
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+#[test]
+fn test_serve_preloads_checkpoints() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let xor_path = temp_dir.path().join("xor.json");
+    let and_path = temp_dir.path().join("and.json.gz");
+    let (xor, and) = (xor_path.to_str().unwrap(), and_path.to_str().unwrap());
+    for (example, path) in [("xor", xor), ("and", and)] {
+        let output = cli(&["train", "--example", example, "--epochs", "20", "--output", path]);
+        assert!(output.status.success(), "Training should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    let mut server = Command::new(env!("CARGO_BIN_EXE_neural-net-cli"))
+        .args(["serve", "--checkpoint", xor, "--checkpoint", and, "--port", "3471"])
+        .stdout(Stdio::null())
+        .spawn()
+        .expect("Failed to run neural-net-cli");
+    let xor_info = get(3471, "/api/models/xor");
+    let and_info = get(3471, "/api/models/and");
+    let missing = get(3471, "/api/models/or");
+    server.kill().unwrap();
+    server.wait().unwrap();
+
+    assert!(xor_info.starts_with("HTTP/1.1 200"), "{}", xor_info);
+    assert!(xor_info.contains("\"architecture\":[2,3,1]"), "{}", xor_info);
+    assert!(and_info.contains("\"example\":\"and\""), "{}", and_info);
+    assert!(missing.starts_with("HTTP/1.1 404"), "{}", missing);
+
+    let output = cli(&["serve", "--checkpoint", &format!("{},{}", xor, xor)]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Two checkpoints would be served as model 'xor'"));
+}
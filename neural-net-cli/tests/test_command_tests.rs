@@ -0,0 +1,42 @@
+// Integration tests for the test command
+mod common;
+
+use common::cli;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_confusion_matrix_on_example_and_data() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let model_path = temp_dir.path().join("and_model.json");
+    let data_path = temp_dir.path().join("and.csv");
+    let plot_path = temp_dir.path().join("confusion.svg");
+    let (model, data, plot) = (
+        model_path.to_str().unwrap(),
+        data_path.to_str().unwrap(),
+        plot_path.to_str().unwrap(),
+    );
+
+    let output = cli(&["train", "--example", "and", "--epochs", "5000", "--output", model]);
+    assert!(output.status.success(), "Training should succeed: {}", String::from_utf8_lossy(&output.stderr));
+
+    let output = cli(&["test", "--checkpoint", model, "--example", "and", "--plot", plot]);
+    assert!(output.status.success(), "Test should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Accuracy: 100.00% (4 of 4 correct)"), "{}", stdout);
+    assert!(stdout.contains("Confusion matrix (rows: actual class, columns: predicted class):"), "{}", stdout);
+    assert!(stdout.contains("Per-class results:"), "{}", stdout);
+    assert!(stdout.contains("Confusion matrix plot saved to:"), "{}", stdout);
+    assert!(fs::read_to_string(&plot_path).unwrap().contains("<svg"));
+
+    // Every sample labeled 1 puts the three negatives in the wrong class
+    fs::write(&data_path, "0,0,1\n0,1,1\n1,0,1\n1,1,1\n").unwrap();
+    let output = cli(&["test", "--model", model, "--data", data]);
+    assert!(output.status.success(), "Test should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Accuracy: 25.00% (1 of 4 correct)"), "{}", stdout);
+    assert!(stdout.contains("1        4    25.00%   100.00%"), "{}", stdout);
+
+    let output = cli(&["test", "--model", model]);
+    assert!(!output.status.success(), "Test needs an example or data");
+}
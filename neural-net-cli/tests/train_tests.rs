@@ -252,3 +252,505 @@ fn test_train_creates_valid_checkpoint() {
 
     // TempDir automatically cleans up when dropped
 }
+
+#[test]
+fn test_train_multi_output_reports_per_output_loss() {
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "train",
+            "--example",
+            "adder2",
+            "--epochs",
+            "200",
+        ])
+        .output()
+        .expect("Failed to run CLI");
+
+    assert!(output.status.success(), "Training should succeed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Final loss per output"), "Should report per-output loss");
+    assert!(stdout.contains("Output 2:"), "adder2 has three outputs");
+}
+
+#[test]
+fn test_train_log_csv() {
+    let temp_dir = create_temp_dir();
+    let log_path = temp_dir.path().join("run.csv");
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "train",
+            "--example",
+            "xor",
+            "--epochs",
+            "25",
+            "--log-csv",
+            log_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to run CLI");
+
+    assert!(
+        output.status.success(),
+        "Training should succeed. stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let contents = fs::read_to_string(&log_path).expect("CSV log should be created");
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines.len(), 26, "Header plus one row per epoch");
+    assert!(lines[0].starts_with("epoch,loss,"));
+    let last: Vec<&str> = lines[25].split(',').collect();
+    assert_eq!(last[0], "25");
+    assert!(!last[3].is_empty(), "Accuracy column is filled in");
+    assert_eq!(last[7], "0.5", "Learning rate column");
+}
+
+#[test]
+fn test_sweep_command_ranks_grid() {
+    let temp_dir = create_temp_dir();
+    let spec_path = temp_dir.path().join("sweep.toml");
+    let report_path = temp_dir.path().join("report.json");
+    fs::write(
+        &spec_path,
+        "example = \"and\"\nlearning_rates = [0.5, 1.0]\nhidden_layers = [[2]]\nepochs = [50, 100]\nseed = 1\n",
+    )
+    .unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "sweep",
+            "--spec",
+            spec_path.to_str().unwrap(),
+            "--output",
+            report_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to run CLI");
+
+    assert!(
+        output.status.success(),
+        "Sweep should succeed. stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[4/4]"), "Progress for every trial");
+
+    let report: serde_json::Value = serde_json::from_str(&fs::read_to_string(&report_path).unwrap()).unwrap();
+    assert_eq!(report["results"].as_array().unwrap().len(), 4);
+}
+
+#[test]
+fn test_sweep_command_parallel_markdown_and_best_checkpoint() {
+    let temp_dir = create_temp_dir();
+    let spec_path = temp_dir.path().join("sweep.toml");
+    let report_path = temp_dir.path().join("report.md");
+    let best_path = temp_dir.path().join("best.json");
+    fs::write(
+        &spec_path,
+        "example = \"and\"\nlearning_rates = [0.0, 1.0]\nhidden_layers = [[2], [3]]\nepochs = [100]\nseed = 1\n",
+    )
+    .unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "sweep",
+            "--config",
+            spec_path.to_str().unwrap(),
+            "--parallel",
+            "2",
+            "--output",
+            report_path.to_str().unwrap(),
+            "--best",
+            best_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to run CLI");
+
+    assert!(
+        output.status.success(),
+        "Sweep should succeed. stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Training 2 trials at once"), "{}", stdout);
+    assert!(stdout.contains("[4/4]"), "Progress for every trial: {}", stdout);
+    assert!(stdout.contains("Best checkpoint saved to"), "{}", stdout);
+
+    let markdown = fs::read_to_string(&report_path).unwrap();
+    assert!(markdown.starts_with("# Sweep results for and"), "{}", markdown);
+    let best_row = markdown.lines().find(|line| line.starts_with("| 1 |")).unwrap();
+    assert!(best_row.contains("| 1 | 100 |"), "A zero learning rate never wins: {}", best_row);
+
+    let checkpoint: serde_json::Value = serde_json::from_str(&fs::read_to_string(&best_path).unwrap()).unwrap();
+    assert_eq!(checkpoint["metadata"]["learning_rate"], 1.0);
+    assert_eq!(checkpoint["metadata"]["epoch"], 100);
+}
+
+#[test]
+fn test_train_with_class_and_sample_weights() {
+    let run = |flag: &str, weights: &str| {
+        Command::new("cargo")
+            .args([
+                "run",
+                "--bin",
+                "neural-net-cli",
+                "--",
+                "train",
+                "--example",
+                "and",
+                "--epochs",
+                "50",
+                flag,
+                weights,
+            ])
+            .output()
+            .expect("Failed to run CLI")
+    };
+
+    let output = run("--class-weights", "1,3");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Class weights: [1.0, 3.0]"));
+
+    let output = run("--sample-weights", "1,2");
+    assert!(!output.status.success(), "AND has four samples");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("2 sample weights for 4 samples"));
+}
+
+#[test]
+fn test_lr_find_command_suggests_rate() {
+    let temp_dir = create_temp_dir();
+    let csv_path = temp_dir.path().join("lr.csv");
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "lr-find",
+            "--example",
+            "xor",
+            "--seed",
+            "7",
+            "--output",
+            csv_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to run CLI");
+
+    assert!(
+        output.status.success(),
+        "lr-find should succeed. stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Suggested learning rate"), "stdout: {}", stdout);
+
+    let csv = fs::read_to_string(&csv_path).unwrap();
+    assert!(csv.starts_with("learning_rate,loss,smoothed_loss"));
+    assert!(csv.lines().count() > 2, "One row per recorded step");
+}
+
+#[test]
+fn test_train_deterministic_is_reproducible_without_seed() {
+    let temp_dir = create_temp_dir();
+    let train = |name: &str| {
+        let output_path = temp_dir.path().join(name);
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--bin",
+                "neural-net-cli",
+                "--",
+                "train",
+                "--example",
+                "xor",
+                "--epochs",
+                "200",
+                "--shuffle",
+                "--deterministic",
+                "--output",
+                output_path.to_str().unwrap(),
+            ])
+            .output()
+            .expect("Failed to run CLI");
+        assert!(
+            output.status.success(),
+            "Training should succeed. stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&output_path).unwrap()).unwrap();
+        json["network"].clone()
+    };
+
+    assert_eq!(train("first.json"), train("second.json"), "Both runs should produce the same network");
+}
+
+#[test]
+fn test_train_stops_at_target_loss_and_saves() {
+    let temp_dir = create_temp_dir();
+    let output_path = temp_dir.path().join("and_target.json");
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "neural-net-cli",
+            "--",
+            "train",
+            "--example",
+            "and",
+            "--epochs",
+            "100000",
+            "--seed",
+            "3",
+            "--target-loss",
+            "0.05",
+            "--output",
+            output_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to run CLI");
+
+    assert!(
+        output.status.success(),
+        "Training should succeed. stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Reached target loss 0.05 after"), "stdout: {}", stdout);
+
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&output_path).unwrap()).unwrap();
+    let epoch = json["metadata"]["epoch"].as_u64().unwrap();
+    assert!(epoch < 100000, "Checkpoint records the epochs actually used");
+}
+
+#[test]
+fn test_train_with_stratified_validation_and_balance() {
+    let run = |args: &[&str]| {
+        let output = Command::new("cargo")
+            .args(["run", "--bin", "neural-net-cli", "--", "train", "--epochs", "20"])
+            .args(args)
+            .output()
+            .expect("Failed to run CLI");
+        assert!(
+            output.status.success(),
+            "Training should succeed. stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+
+    let stdout = run(&["--example", "iris", "--validation-split", "0.25"]);
+    assert!(stdout.contains("Validation: 15 of 60 samples held out"), "stdout: {}", stdout);
+    assert!(stdout.contains("Final validation loss:"), "stdout: {}", stdout);
+
+    // AND has three negative samples and one positive
+    let stdout = run(&["--example", "and", "--balance", "oversample"]);
+    assert!(stdout.contains("Class balance (oversample): [3, 1] -> [3, 3] samples"), "stdout: {}", stdout);
+}
+
+#[test]
+fn test_train_streams_csv_data() {
+    let temp_dir = create_temp_dir();
+    let data_path = temp_dir.path().join("xor.csv");
+    let rows: String = (0..40).map(|i| format!("{},{},{}\n", i % 2, i / 2 % 2, (i % 2) ^ (i / 2 % 2))).collect();
+    fs::write(&data_path, format!("a,b,xor\n{}", rows)).unwrap();
+    let output_path = temp_dir.path().join("model.json");
+
+    let output = Command::new("cargo")
+        .args(["run", "--bin", "neural-net-cli", "--", "train", "--data"])
+        .arg(&data_path)
+        .args(["--chunk-size", "16", "--hidden", "4", "--epochs", "20", "--seed", "1", "--output"])
+        .arg(&output_path)
+        .output()
+        .expect("Failed to run CLI");
+
+    assert!(
+        output.status.success(),
+        "Training should succeed. stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Training xor.csv network"), "stdout: {}", stdout);
+    assert!(stdout.contains("Data: 40 samples with 2 inputs and 1 outputs, streamed in chunks of 16"), "stdout: {}", stdout);
+    assert!(stdout.contains("Architecture: [2, 4, 1]"), "stdout: {}", stdout);
+
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&output_path).unwrap()).unwrap();
+    assert_eq!(json["metadata"]["example"], "xor.csv");
+}
+
+#[test]
+fn test_train_custom_layers() {
+    let temp_dir = create_temp_dir();
+    let output_path = temp_dir.path().join("model.json");
+    let train = |layers: &str| {
+        Command::new("cargo")
+            .args(["run", "--bin", "neural-net-cli", "--", "train", "--example", "xor", "--epochs", "20"])
+            .args(["--layers", layers, "--output"])
+            .arg(&output_path)
+            .output()
+            .expect("Failed to run CLI")
+    };
+
+    let output = train("2,8,4,1");
+    assert!(output.status.success(), "Training should succeed. stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Architecture: [2, 8, 4, 1]"), "stdout: {}", stdout);
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&output_path).unwrap()).unwrap();
+    assert_eq!(json["network"]["layers"], serde_json::json!([2, 8, 4, 1]));
+
+    for layers in ["3,4,1", "2,4,2", "2", "2,0,1"] {
+        let output = train(layers);
+        assert!(!output.status.success(), "--layers {} should be rejected for xor", layers);
+    }
+    let stderr = String::from_utf8_lossy(&train("3,4,1").stderr).into_owned();
+    assert!(stderr.contains("the first size must be its 2 inputs and the last its 1 outputs"), "stderr: {}", stderr);
+}
+
+#[test]
+fn test_train_data_file_with_split_and_normalization() {
+    let temp_dir = create_temp_dir();
+    let data_path = temp_dir.path().join("quadrants.csv");
+    // Two inputs in [-10, 10] and two one-hot targets: right half, upper half
+    let rows: String = (0..60)
+        .map(|i| {
+            let (x, y) = ((i % 7) as f64 * 3.0 - 9.0, (i % 5) as f64 * 4.0 - 8.5);
+            format!("{},{},{},{}\n", x, y, u8::from(x > 0.0), u8::from(y > 0.0))
+        })
+        .collect();
+    fs::write(&data_path, format!("x,y,right,up\n{}", rows)).unwrap();
+    let output_path = temp_dir.path().join("model.json");
+
+    let output = Command::new("cargo")
+        .args(["run", "--bin", "neural-net-cli", "--", "train", "--data"])
+        .arg(&data_path)
+        .args(["--target-cols", "2", "--layers", "2,6,2", "--validation-split", "0.2", "--normalize", "zscore"])
+        .args(["--epochs", "30", "--seed", "1", "--output"])
+        .arg(&output_path)
+        .output()
+        .expect("Failed to run CLI");
+
+    assert!(
+        output.status.success(),
+        "Training should succeed. stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Data: 60 samples with 2 inputs and 2 outputs, loaded into memory"), "stdout: {}", stdout);
+    assert!(stdout.contains("Architecture: [2, 6, 2]"), "stdout: {}", stdout);
+    assert!(stdout.contains("samples held out"), "stdout: {}", stdout);
+    assert!(stdout.contains("Final validation loss"), "stdout: {}", stdout);
+
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&output_path).unwrap()).unwrap();
+    assert_eq!(json["metadata"]["example"], "quadrants.csv");
+    assert_eq!(json["metadata"]["dataset_path"], data_path.to_str().unwrap());
+    assert!(json["normalizer"].is_object(), "The input normalizer is saved");
+}
+
+#[test]
+fn test_train_with_activation_and_optimizer() {
+    let temp_dir = create_temp_dir();
+    let output_path = temp_dir.path().join("tanh_adam.json");
+
+    let output = Command::new("cargo")
+        .args(["run", "--bin", "neural-net-cli", "--", "train", "--example", "xor"])
+        .args(["--activation", "tanh", "--optimizer", "adam", "--epochs", "50", "--output"])
+        .arg(&output_path)
+        .output()
+        .expect("Failed to run CLI");
+
+    assert!(
+        output.status.success(),
+        "Training should succeed. stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Activation: tanh"), "stdout: {}", stdout);
+    assert!(stdout.contains("Optimizer: adam"), "stdout: {}", stdout);
+
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&output_path).unwrap()).unwrap();
+    assert_eq!(json["network"]["activation"], "tanh");
+    assert_eq!(json["metadata"]["optimizer"], "adam");
+
+    let output = Command::new("cargo")
+        .args(["run", "--bin", "neural-net-cli", "--", "train", "--example", "xor", "--activation", "swish"])
+        .output()
+        .expect("Failed to run CLI");
+    assert!(!output.status.success(), "Unknown activations are rejected");
+}
+
+#[test]
+fn test_train_records_drawn_seed_for_reproduction() {
+    let temp_dir = create_temp_dir();
+    let train = |name: &str, seed: Option<&str>| {
+        let output_path = temp_dir.path().join(name);
+        let mut command = Command::new("cargo");
+        command.args(["run", "--bin", "neural-net-cli", "--", "train", "--example", "xor", "--epochs", "100", "--shuffle"]);
+        if let Some(seed) = seed {
+            command.args(["--seed", seed]);
+        }
+        let output = command.arg("--output").arg(&output_path).output().expect("Failed to run CLI");
+        assert!(
+            output.status.success(),
+            "Training should succeed. stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&output_path).unwrap()).unwrap();
+        (String::from_utf8_lossy(&output.stdout).to_string(), json)
+    };
+
+    let (stdout, first) = train("unseeded.json", None);
+    let seed = first["metadata"]["seed"].as_u64().expect("An unseeded run records the seed it drew");
+    assert!(stdout.contains(&format!("Seed: {}", seed)), "stdout: {}", stdout);
+
+    let (_, second) = train("reseeded.json", Some(&seed.to_string()));
+    assert_eq!(first["network"], second["network"], "The recorded seed reproduces the run");
+}
+
+#[test]
+fn test_train_verbosity_and_log_file() {
+    let temp_dir = create_temp_dir();
+    let log_path = temp_dir.path().join("train.log");
+    let run = |args: &[&str]| {
+        let output = Command::new("cargo")
+            .args(["run", "--bin", "neural-net-cli", "--", "train", "--example", "and", "--epochs", "200"])
+            .args(args)
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success(), "Training should succeed: {}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    let quiet = run(&["-q", "--log-file", log_path.to_str().unwrap()]);
+    assert!(quiet.trim().is_empty(), "-q should hide the progress: {}", quiet);
+    // The log file still gets everything down to debug, with timestamps
+    let log = fs::read_to_string(&log_path).unwrap();
+    assert!(log.contains(" INFO Training and network"), "{}", log);
+    assert!(log.contains(" DEBUG Epoch 100: loss"), "{}", log);
+    assert!(log.lines().all(|line| line.starts_with("20")), "Every line should start with a timestamp: {}", log);
+
+    let normal = run(&[]);
+    assert!(normal.contains("Training and network") && !normal.contains("Epoch 100:"), "{}", normal);
+    let verbose = run(&["-v"]);
+    assert!(verbose.contains("Epoch 100: loss"), "{}", verbose);
+    let errors_only = run(&["-v", "--log-level", "error"]);
+    assert!(errors_only.trim().is_empty(), "--log-level overrides -v: {}", errors_only);
+}
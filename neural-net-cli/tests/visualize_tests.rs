@@ -0,0 +1,136 @@
+// Integration tests for the visualize tool
+use std::fs;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+fn run(bin: &str, args: &[&str]) -> Output {
+    Command::new("cargo")
+        .args(["run", "--bin", bin, "--"])
+        .args(args)
+        .output()
+        .unwrap_or_else(|_| panic!("Failed to run {}", bin))
+}
+
+fn train(temp_dir: &TempDir, example: &str) -> String {
+    let path = temp_dir.path().join(format!("{}.json", example));
+    let path = path.to_str().unwrap().to_string();
+    let output = run("neural-net-cli", &["train", "--example", example, "--epochs", "50", "--seed", "1", "--output", &path]);
+    assert!(output.status.success(), "Training should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    path
+}
+
+#[test]
+fn test_visualize_dot_format() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let model = train(&temp_dir, "xor");
+    let dot_path = temp_dir.path().join("network.dot");
+    let dot = dot_path.to_str().unwrap();
+
+    let output = run("visualize", &["--checkpoint", &model, "--output", dot, "--format", "dot"]);
+    assert!(output.status.success(), "DOT export should succeed: {}", String::from_utf8_lossy(&output.stderr));
+
+    let graph = fs::read_to_string(&dot_path).unwrap();
+    assert!(graph.starts_with("digraph network {"));
+    assert!(graph.contains("label=\"Input (2)\""));
+    assert!(graph.contains("label=\"Output (1)\""));
+    // XOR's recommended [2, 3, 1] network has 2 * 3 + 3 * 1 weights
+    assert_eq!(graph.matches(" -> ").count(), 9, "{}", graph);
+    assert!(graph.contains("l0n1 -> l1n2 [label="));
+
+    let output = run("visualize", &["--checkpoint", &model, "--output", dot, "--format", "dot", "--mode", "loss"]);
+    assert!(!output.status.success(), "DOT is only available for the network mode");
+}
+
+#[test]
+fn test_visualize_boundary_svg_and_png() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let model = train(&temp_dir, "moons");
+    let svg_path = temp_dir.path().join("boundary.svg");
+    let png_path = temp_dir.path().join("boundary.png");
+
+    let output = run(
+        "visualize",
+        &["--checkpoint", &model, "--output", svg_path.to_str().unwrap(), "--mode", "boundary", "--resolution", "20"],
+    );
+    assert!(output.status.success(), "Boundary SVG should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("on a 20x20 grid"));
+    let svg = fs::read_to_string(&svg_path).unwrap();
+    assert!(svg.contains("Decision Boundary"));
+    assert_eq!(svg.matches("<rect x=").count(), 20 * 20 + 1, "One cell per grid point plus the frame");
+    assert!(svg.contains("class=\"sample\""), "The moons samples are drawn");
+
+    let output = run(
+        "visualize",
+        &["--checkpoint", &model, "--output", png_path.to_str().unwrap(), "--mode", "boundary", "--width", "64", "--height", "48"],
+    );
+    assert!(output.status.success(), "Boundary PNG should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    let png = fs::read(&png_path).unwrap();
+    assert_eq!(&png[1..4], b"PNG");
+    assert_eq!(u32::from_be_bytes(png[16..20].try_into().unwrap()), 64, "Width in the IHDR chunk");
+    assert_eq!(u32::from_be_bytes(png[20..24].try_into().unwrap()), 48, "Height in the IHDR chunk");
+
+    let output = run("visualize", &["--checkpoint", &model, "--output", svg_path.to_str().unwrap(), "--mode", "boundary", "--example", "iris"]);
+    assert!(!output.status.success(), "Iris has four inputs");
+}
+
+#[test]
+fn test_visualize_png_output() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let model = train(&temp_dir, "xor");
+    let png_size = |path: &std::path::Path| {
+        let png = fs::read(path).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+        (u32::from_be_bytes(png[16..20].try_into().unwrap()), u32::from_be_bytes(png[20..24].try_into().unwrap()))
+    };
+
+    let by_extension = temp_dir.path().join("network.png");
+    let output = run("visualize", &["--checkpoint", &model, "--output", by_extension.to_str().unwrap(), "--width", "400", "--height", "300"]);
+    assert!(output.status.success(), "PNG output should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("PNG visualization saved to"));
+    assert_eq!(png_size(&by_extension), (400, 300));
+
+    let by_flag = temp_dir.path().join("loss.image");
+    let output = run(
+        "visualize",
+        &["--checkpoint", &model, "--output", by_flag.to_str().unwrap(), "--mode", "loss", "--format", "png", "--width", "320", "--height", "240"],
+    );
+    assert!(output.status.success(), "PNG output should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(png_size(&by_flag), (320, 240));
+}
+
+#[test]
+fn test_visualize_animates_training_snapshots() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let snapshots = temp_dir.path().join("snapshots");
+    let animation_path = temp_dir.path().join("evolution.svg");
+    let (dir, animation) = (snapshots.to_str().unwrap(), animation_path.to_str().unwrap());
+
+    let output = run(
+        "neural-net-cli",
+        &["train", "--example", "xor", "--epochs", "40", "--seed", "1", "--snapshots", dir, "--snapshot-every", "10"],
+    );
+    assert!(output.status.success(), "Training should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    let mut names: Vec<String> =
+        fs::read_dir(&snapshots).unwrap().map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned()).collect();
+    names.sort();
+    assert_eq!(names.len(), 4, "{:?}", names);
+    assert_eq!(names[0], "snapshot_epoch000010.json");
+
+    let output = run("visualize", &["--checkpoint", dir, "--output", animation, "--animate", "--frame-secs", "0.25"]);
+    assert!(output.status.success(), "Animation should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Animating 4 checkpoints from epoch 10 to 40 (1.0s per loop)"));
+
+    let svg = fs::read_to_string(&animation_path).unwrap();
+    assert!(svg.contains("Epoch 10 - loss"));
+    assert!(svg.contains("Epoch 40 - loss"));
+    // Color and width of each of XOR's 9 weights, plus a caption per frame
+    assert_eq!(svg.matches("<animate ").count(), 9 * 2 + 4);
+    assert!(svg.contains("dur=\"1.000s\""));
+
+    fs::remove_file(snapshots.join("snapshot_epoch000010.json")).unwrap();
+    fs::remove_file(snapshots.join("snapshot_epoch000020.json")).unwrap();
+    fs::remove_file(snapshots.join("snapshot_epoch000030.json")).unwrap();
+    let output = run("visualize", &["--checkpoint", dir, "--output", animation, "--animate"]);
+    assert!(!output.status.success(), "One checkpoint is not an animation");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("needs at least 2"));
+}
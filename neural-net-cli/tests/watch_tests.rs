@@ -0,0 +1,59 @@
+// Integration tests for train --watch
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+/// Wait up to a minute for `path` to hold something other than `previous`
+fn wait_for_change(path: &Path, previous: Option<&str>) -> String {
+    let start = Instant::now();
+    loop {
+        if let Ok(contents) = fs::read_to_string(path)
+            && Some(contents.as_str()) != previous
+            && serde_json::from_str::<serde_json::Value>(&contents).is_ok()
+        {
+            return contents;
+        }
+        assert!(start.elapsed() < Duration::from_secs(60), "{} was not (re)written", path.display());
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+#[test]
+fn test_watch_retrains_when_data_changes() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let data_path = temp_dir.path().join("and.csv");
+    let model_path = temp_dir.path().join("model.json");
+    fs::write(&data_path, "0,0,0\n0,1,0\n1,0,0\n1,1,1\n").unwrap();
+
+    // Run the binary directly so killing it does not leave a child behind
+    let mut child = Command::new(env!("CARGO_BIN_EXE_neural-net-cli"))
+        .args(["train", "--data", data_path.to_str().unwrap(), "--epochs", "50", "--seed", "3", "--watch"])
+        .args(["--output", model_path.to_str().unwrap()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to run neural-net-cli");
+
+    let first = wait_for_change(&model_path, None);
+    // Let the watcher settle before the edit
+    std::thread::sleep(Duration::from_millis(500));
+    fs::write(&data_path, "0,0,0\n0,1,1\n1,0,1\n1,1,1\n").unwrap();
+    let second = wait_for_change(&model_path, Some(&first));
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+    let (first, second): (serde_json::Value, serde_json::Value) =
+        (serde_json::from_str(&first).unwrap(), serde_json::from_str(&second).unwrap());
+    assert_ne!(first["network"]["weights"], second["network"]["weights"], "OR data should train other weights");
+}
+
+#[test]
+fn test_watch_requires_data() {
+    let output = Command::new(env!("CARGO_BIN_EXE_neural-net-cli"))
+        .args(["train", "--example", "and", "--watch"])
+        .output()
+        .expect("Failed to run neural-net-cli");
+    assert!(!output.status.success(), "--watch without --data should be rejected");
+}
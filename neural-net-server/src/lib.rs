@@ -2,9 +2,11 @@
 // REST API server for neural network training and evaluation
 
 use axum::{
+    body::Bytes,
     extract::{Path, State},
-    http::StatusCode,
-    response::{Json, sse::{Event, Sse}},
+    http::{HeaderMap, StatusCode},
+    extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    response::{Json, Response, sse::{Event, Sse}},
     routing::{get, post},
     Router,
 };
@@ -12,38 +14,222 @@ use futures::stream::{self, Stream};
 use std::convert::Infallible;
 use neural_network::{
     activations::SIGMOID,
-    examples,
+    callbacks::{from_batch_fn, from_fn},
+    encryption::Secret,
+    examples::{self, Example},
+    history::EpochRecord,
+    inference::InferenceModel,
+    metrics::DEFAULT_THRESHOLD,
     network::Network,
-    training::{TrainingConfig, TrainingController},
+    normalize::{Normalization, Normalizer},
+    tensorboard::TensorBoardWriter,
+    training::{CancellationToken, SampleWeights, TrainingConfig, TrainingController},
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::mpsc::RecvTimeoutError;
 use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
 use uuid::Uuid;
 
+/// Server-wide settings chosen at startup
+#[derive(Debug, Clone, Default)]
+pub struct ServerOptions {
+    /// Write TensorBoard event files for every training run to
+    /// `<dir>/<model_id>/`
+    pub tensorboard_dir: Option<PathBuf>,
+    /// Key that decrypts checkpoints uploaded to `POST /api/models`
+    pub checkpoint_key: Option<Secret>,
+    /// Models to serve from startup, by model id, e.g. the checkpoints given
+    /// to `neural-net-cli serve`
+    pub models: Vec<(String, InferenceModel)>,
+    /// Background jobs (`POST /api/jobs`) that train at once; further jobs
+    /// stay `queued` until one finishes. Defaults to the number of CPU cores.
+    pub max_jobs: Option<usize>,
+}
+
 /// Application state shared across handlers
 #[derive(Clone)]
 pub struct AppState {
     models: Arc<Mutex<HashMap<String, StoredModel>>>,
+    /// Cancellation tokens of streamed and background training runs still in progress
+    runs: Arc<Mutex<HashMap<String, CancellationToken>>>,
+    /// Background training jobs, see `JobTable`
+    jobs: Arc<Mutex<JobTable>>,
+    /// One permit per job allowed to train at once, see `ServerOptions::max_jobs`
+    job_slots: Arc<Semaphore>,
+    options: Arc<ServerOptions>,
 }
 
 impl AppState {
-    fn new() -> Self {
+    fn new(mut options: ServerOptions) -> Self {
+        let models = std::mem::take(&mut options.models)
+            .into_iter()
+            .map(|(model_id, model)| (model_id, StoredModel::from(model)))
+            .collect();
+        let max_jobs = options
+            .max_jobs
+            .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()));
         Self {
-            models: Arc::new(Mutex::new(HashMap::new())),
+            models: Arc::new(Mutex::new(models)),
+            runs: Arc::new(Mutex::new(HashMap::new())),
+            jobs: Arc::new(Mutex::new(JobTable::default())),
+            job_slots: Arc::new(Semaphore::new(max_jobs.max(1))),
+            options: Arc::new(options),
+        }
+    }
+
+    /// Attach a TensorBoard writer for the run `model_id` if a log directory is configured
+    fn attach_tensorboard(&self, controller: &mut TrainingController, model_id: &str) -> anyhow::Result<()> {
+        if let Some(dir) = &self.options.tensorboard_dir {
+            let writer = TensorBoardWriter::create(&dir.join(model_id))?;
+            controller.add_callback(writer);
+        }
+        Ok(())
+    }
+
+    /// Controller for the run `model_id` of `req`, stopped by `token`
+    ///
+    /// TensorBoard logging is skipped with a warning if its writer cannot be
+    /// created, since the request has already been answered.
+    fn run_controller(
+        &self,
+        req: &TrainRequest,
+        example: &Example,
+        sample_weights: SampleWeights,
+        token: CancellationToken,
+        model_id: &str,
+    ) -> TrainingController {
+        // Create network (seeded if seed provided, random otherwise)
+        let arch = example.recommended_arch.clone();
+        let network = match req.seed {
+            Some(s) => Network::new_seeded(arch, SIGMOID, req.learning_rate, s),
+            None => Network::new(arch, SIGMOID, req.learning_rate),
+        };
+
+        // Create training config
+        let config = TrainingConfig {
+            epochs: req.epochs,
+            checkpoint_interval: None,
+            checkpoint_path: None,
+            verbose: false,
+            example_name: Some(req.example.clone()),
+            classification_threshold: Some(req.threshold.unwrap_or(DEFAULT_THRESHOLD)),
+            target_loss: req.target_loss,
+            sample_weights,
+            normalization: req.normalize,
+            target_normalization: req.normalize_targets,
+            ..Default::default()
+        };
+
+        let mut controller = TrainingController::new(network, config);
+        controller.set_cancellation_token(token);
+        if let Err(e) = self.attach_tensorboard(&mut controller, model_id) {
+            eprintln!("TensorBoard logging disabled for {}: {}", model_id, e);
+        }
+        controller
+    }
+
+    /// Apply `update` to the job `job_id`
+    fn update_job(&self, job_id: &str, update: impl FnOnce(&mut JobResponse)) {
+        if let Some(job) = self.jobs.lock().unwrap().by_id.get_mut(job_id) {
+            update(job);
+        }
+    }
+
+    /// Apply the final `update` to the job `job_id` and evict the oldest
+    /// finished jobs beyond `MAX_FINISHED_JOBS`
+    fn finish_job(&self, job_id: &str, update: impl FnOnce(&mut JobResponse)) {
+        let mut jobs = self.jobs.lock().unwrap();
+        if let Some(job) = jobs.by_id.get_mut(job_id) {
+            update(job);
+            jobs.finished.push_back(job_id.to_string());
+        }
+        while jobs.finished.len() > MAX_FINISHED_JOBS {
+            if let Some(oldest) = jobs.finished.pop_front() {
+                jobs.by_id.remove(&oldest);
+            }
         }
     }
+
+    /// End the run `model_id` that trained with `result`
+    ///
+    /// A completed run's model is stored before the returned `Done` progress
+    /// reports it; cancelled and failed runs are discarded.
+    fn end_run(
+        &self,
+        controller: TrainingController,
+        result: anyhow::Result<()>,
+        model_id: String,
+        example: String,
+    ) -> Progress {
+        self.runs.lock().unwrap().remove(&model_id);
+        let epochs_trained = controller.history().len() as u32;
+        let (status, error) = match result {
+            Err(e) => ("failed", Some(e.to_string())),
+            Ok(()) if controller.is_cancelled() => ("cancelled", None),
+            Ok(()) => {
+                let stored_model = StoredModel {
+                    normalizer: controller.normalizer().cloned(),
+                    target_normalizer: controller.target_normalizer().cloned(),
+                    learning_rate: controller.network().learning_rate,
+                    network: controller.into_network(),
+                    example,
+                    epochs: epochs_trained,
+                };
+                self.models.lock().unwrap().insert(model_id, stored_model);
+                ("completed", None)
+            }
+        };
+        Progress::Done { status, epochs_trained, error }
+    }
+}
+
+/// Report every epoch of `controller` and, during long epochs, its batch
+/// progress through `send`
+fn report_progress(controller: &mut TrainingController, send: impl Fn(Progress) + Clone + 'static) {
+    let send_epoch = send.clone();
+    controller.add_callback(from_fn(move |record, _network| {
+        send_epoch(Progress::Epoch(record.clone()));
+    }));
+    let interval = std::time::Duration::from_secs_f64(BATCH_PROGRESS_SECS);
+    controller.add_callback(from_batch_fn(interval, move |ctx| {
+        send(Progress::Batch {
+            epoch: ctx.epoch,
+            batch: ctx.batch,
+            batches: ctx.batches,
+            running_loss: ctx.running_loss,
+        });
+    }));
 }
 
 /// Stored model with metadata
 #[derive(Clone)]
 struct StoredModel {
     network: Network,
+    /// Applied to inputs before evaluation
+    normalizer: Option<Normalizer>,
+    /// Inverted on outputs after evaluation
+    target_normalizer: Option<Normalizer>,
     example: String,
     epochs: u32,
     learning_rate: f64,
 }
 
+impl From<InferenceModel> for StoredModel {
+    fn from(model: InferenceModel) -> Self {
+        StoredModel {
+            example: model.metadata.example,
+            epochs: model.metadata.epoch,
+            learning_rate: model.network.learning_rate,
+            network: model.network,
+            normalizer: model.normalizer,
+            target_normalizer: model.target_normalizer,
+        }
+    }
+}
+
 /// Health check response
 #[derive(Serialize)]
 struct HealthResponse {
@@ -58,6 +244,18 @@ struct ExampleInfo {
     architecture: Vec<usize>,
 }
 
+/// Request to register a custom example
+#[derive(Deserialize)]
+struct RegisterExampleRequest {
+    name: String,
+    #[serde(default)]
+    description: String,
+    inputs: Vec<Vec<f64>>,
+    targets: Vec<Vec<f64>>,
+    /// Recommended architecture; one hidden layer of inputs + targets neurons if omitted
+    architecture: Option<Vec<usize>>,
+}
+
 /// Train request
 #[derive(Deserialize)]
 struct TrainRequest {
@@ -66,6 +264,39 @@ struct TrainRequest {
     learning_rate: f64,
     /// Optional seed for reproducible training (random if not specified)
     seed: Option<u64>,
+    /// Decision threshold for the streamed classification metrics (default 0.5)
+    threshold: Option<f64>,
+    /// Stop as soon as the loss falls below this value
+    target_loss: Option<f64>,
+    /// Loss weight of each class, indexed by target class
+    class_weights: Option<Vec<f64>>,
+    /// Loss weight of each training sample, in example order
+    sample_weights: Option<Vec<f64>>,
+    /// Scale inputs with a normalizer fit on the example (`minmax` or `zscore`)
+    normalize: Option<Normalization>,
+    /// Scale targets with a normalizer fit on the example; eval outputs are mapped back
+    normalize_targets: Option<Normalization>,
+}
+
+impl TrainRequest {
+    /// The requested sample weighting, checked against the training targets
+    fn sample_weights(&self, targets: &[Vec<f64>]) -> Result<SampleWeights, (StatusCode, String)> {
+        let weights = match (&self.class_weights, &self.sample_weights) {
+            (Some(_), Some(_)) => {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    "Specify class_weights or sample_weights, not both".to_string(),
+                ));
+            }
+            (Some(weights), None) => SampleWeights::PerClass(weights.clone()),
+            (None, Some(weights)) => SampleWeights::PerSample(weights.clone()),
+            (None, None) => SampleWeights::None,
+        };
+        weights
+            .resolve(targets)
+            .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+        Ok(weights)
+    }
 }
 
 /// Train response
@@ -74,6 +305,59 @@ struct TrainResponse {
     model_id: String,
     example: String,
     epochs: u32,
+    /// Epochs actually trained; fewer than `epochs` when `target_loss` was reached
+    epochs_trained: u32,
+}
+
+/// Cancel response
+#[derive(Serialize)]
+struct CancelResponse {
+    model_id: String,
+    status: String,
+}
+
+/// Number of completed, cancelled or failed jobs kept for `GET /api/jobs/:id`
+///
+/// Older finished jobs are forgotten so a long-running server does not grow
+/// with every job; their models stay stored.
+pub const MAX_FINISHED_JOBS: usize = 100;
+
+/// Background training jobs by job id
+#[derive(Default)]
+struct JobTable {
+    by_id: HashMap<String, JobResponse>,
+    /// Ids of finished jobs, oldest first
+    finished: VecDeque<String>,
+}
+
+/// Background training job, as reported by `GET /api/jobs/:id`
+#[derive(Clone, Serialize)]
+struct JobResponse {
+    job_id: String,
+    /// `queued`, `running`, `completed`, `cancelled` or `failed`
+    status: &'static str,
+    example: String,
+    epochs: u32,
+    /// Epochs finished so far
+    epochs_trained: u32,
+    /// Training loss of the last finished epoch
+    loss: Option<f64>,
+    /// Estimated seconds left while running, from the average epoch time
+    eta_secs: Option<f64>,
+    /// Id of the stored model once the job completed
+    model_id: Option<String>,
+    /// Why the job failed
+    error: Option<String>,
+}
+
+/// Cancels a training run when the SSE stream or WebSocket that reports it is
+/// dropped, i.e. when the client disconnects
+struct CancelOnDrop(CancellationToken);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
 }
 
 /// Eval request
@@ -100,6 +384,28 @@ struct ModelInfoResponse {
     total_parameters: usize,
 }
 
+impl ModelInfoResponse {
+    fn new(model_id: String, stored_model: &StoredModel) -> Self {
+        // Calculate total parameters
+        let mut total_params = 0;
+        for weight in &stored_model.network.weights {
+            total_params += weight.rows * weight.cols;
+        }
+        for bias in &stored_model.network.biases {
+            total_params += bias.rows;
+        }
+
+        ModelInfoResponse {
+            model_id,
+            example: stored_model.example.clone(),
+            architecture: stored_model.network.layers.clone(),
+            epochs: stored_model.epochs,
+            learning_rate: stored_model.learning_rate,
+            total_parameters: total_params,
+        }
+    }
+}
+
 /// Health check endpoint
 async fn health() -> Json<HealthResponse> {
     Json(HealthResponse {
@@ -124,6 +430,24 @@ async fn list_examples() -> Json<Vec<ExampleInfo>> {
     Json(examples_info)
 }
 
+/// Register a custom example, making it available to the training endpoints by name
+async fn register_example(
+    Json(req): Json<RegisterExampleRequest>,
+) -> Result<(StatusCode, Json<ExampleInfo>), (StatusCode, String)> {
+    let bad_request = |e: anyhow::Error| (StatusCode::BAD_REQUEST, e.to_string());
+    let mut example = examples::Example::custom(&req.name, &req.description, req.inputs, req.targets).map_err(bad_request)?;
+    if let Some(architecture) = req.architecture {
+        example.recommended_arch = architecture;
+    }
+    let info = ExampleInfo {
+        name: example.name.to_string(),
+        description: example.description.to_string(),
+        architecture: example.recommended_arch.clone(),
+    };
+    examples::register_example(example).map_err(bad_request)?;
+    Ok((StatusCode::CREATED, Json(info)))
+}
+
 /// Train a new model
 async fn train(
     State(state): State<AppState>,
@@ -138,6 +462,8 @@ async fn train(
             )
         })?;
 
+    let sample_weights = req.sample_weights(&example.targets)?;
+
     // Create network (seeded if seed provided, random otherwise)
     let network = match req.seed {
         Some(seed) => Network::new_seeded(example.recommended_arch.clone(), SIGMOID, req.learning_rate, seed),
@@ -151,20 +477,31 @@ async fn train(
         checkpoint_path: None,
         verbose: false,
         example_name: Some(example.name.to_string()),
+        target_loss: req.target_loss,
+        sample_weights,
+        normalization: req.normalize,
+        target_normalization: req.normalize_targets,
+        ..Default::default()
     };
 
     // Train
+    let model_id = Uuid::new_v4().to_string();
     let mut controller = TrainingController::new(network, config);
-    controller
+    state
+        .attach_tensorboard(&mut controller, &model_id)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let history = controller
         .train(example.inputs.clone(), example.targets.clone())
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let epochs_trained = history.len() as u32;
 
     // Store model
-    let model_id = Uuid::new_v4().to_string();
     let stored_model = StoredModel {
+        normalizer: controller.normalizer().cloned(),
+        target_normalizer: controller.target_normalizer().cloned(),
         network: controller.into_network(),
         example: req.example.clone(),
-        epochs: req.epochs,
+        epochs: epochs_trained,
         learning_rate: req.learning_rate,
     };
 
@@ -178,6 +515,7 @@ async fn train(
         model_id,
         example: req.example,
         epochs: req.epochs,
+        epochs_trained,
     }))
 }
 
@@ -194,26 +532,62 @@ async fn eval(
 
     // Clone network for evaluation
     let mut network = stored_model.network.clone();
-
-    // Validate input dimensions
-    if req.input.len() != network.layers[0] {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            format!(
-                "Invalid input dimensions: expected {}, got {}",
-                network.layers[0],
-                req.input.len()
-            ),
-        ));
-    }
+    let input = match &stored_model.normalizer {
+        Some(normalizer) => normalizer.transform(&req.input),
+        None => req.input,
+    };
 
     // Run prediction
-    let input_matrix = neural_network::matrix::Matrix::from(req.input);
-    let output = network.feed_forward(input_matrix);
+    let input_matrix = neural_network::matrix::Matrix::from(input);
+    let output = network.try_feed_forward(input_matrix).map_err(|e| {
+        (StatusCode::BAD_REQUEST, format!("Invalid input dimensions: {}", e))
+    })?;
 
-    Ok(Json(EvalResponse {
-        output: output.data,
-    }))
+    let output = match &stored_model.target_normalizer {
+        Some(normalizer) => normalizer.inverse_transform(&output.data),
+        None => output.data,
+    };
+
+    Ok(Json(EvalResponse { output }))
+}
+
+/// Header carrying the password of an encrypted checkpoint upload
+const PASSWORD_HEADER: &str = "x-checkpoint-password";
+
+/// Upload a checkpoint file (JSON or binary, optionally compressed) as a new model
+///
+/// Only the network, normalizers and metadata are decoded, so stripped
+/// inference-only checkpoints work as well as full ones. Encrypted
+/// checkpoints are decrypted with the password in the `X-Checkpoint-Password`
+/// header, or else with the server's `checkpoint_key`.
+async fn upload_model(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<ModelInfoResponse>), (StatusCode, String)> {
+    let secret = match headers.get(PASSWORD_HEADER) {
+        Some(password) => {
+            let password = password
+                .to_str()
+                .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid checkpoint password header".to_string()))?;
+            Some(Secret::Password(password.to_string()))
+        }
+        None => state.options.checkpoint_key.clone(),
+    };
+    // Decoding and key derivation are CPU-bound, so keep them off the async workers
+    let model = tokio::task::spawn_blocking(move || {
+        InferenceModel::from_bytes_with_secret(body.to_vec(), secret.as_ref())
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Checkpoint decoding failed: {}", e)))?
+    .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid checkpoint: {:#}", e)))?;
+
+    let model_id = Uuid::new_v4().to_string();
+    let stored_model = StoredModel::from(model);
+    let info = ModelInfoResponse::new(model_id.clone(), &stored_model);
+    state.models.lock().unwrap().insert(model_id, stored_model);
+
+    Ok((StatusCode::CREATED, Json(info)))
 }
 
 /// Get model information
@@ -226,25 +600,95 @@ async fn model_info(
         .get(&model_id)
         .ok_or_else(|| (StatusCode::NOT_FOUND, "Model not found".to_string()))?;
 
-    // Calculate total parameters
-    let mut total_params = 0;
-    for weight in &stored_model.network.weights {
-        total_params += weight.rows * weight.cols;
-    }
-    for bias in &stored_model.network.biases {
-        total_params += bias.rows;
+    Ok(Json(ModelInfoResponse::new(model_id, stored_model)))
+}
+
+/// Progress sent from a streamed training run to its SSE stream or WebSocket
+enum Progress {
+    Epoch(EpochRecord),
+    Batch {
+        epoch: u32,
+        batch: usize,
+        batches: usize,
+        running_loss: f64,
+    },
+    /// Training holds after `epoch` until resumed (WebSocket runs only)
+    Paused { epoch: u32 },
+    /// Training continues after `epoch` (WebSocket runs only)
+    Resumed { epoch: u32 },
+    /// Epochs after `epoch` train with a new learning rate (WebSocket runs only)
+    LearningRate { epoch: u32, learning_rate: f64 },
+    /// The run ended: `completed` (the model is stored by now), `cancelled`
+    /// or `failed` with the error
+    Done {
+        status: &'static str,
+        epochs_trained: u32,
+        error: Option<String>,
+    },
+}
+
+impl Progress {
+    /// SSE event name and WebSocket message type of the progress
+    fn kind(&self) -> &'static str {
+        match self {
+            Progress::Epoch(_) => "epoch",
+            Progress::Batch { .. } => "batch",
+            Progress::Paused { .. } => "paused",
+            Progress::Resumed { .. } => "resumed",
+            Progress::LearningRate { .. } => "learning_rate",
+            Progress::Done { .. } => "done",
+        }
     }
 
-    Ok(Json(ModelInfoResponse {
-        model_id,
-        example: stored_model.example.clone(),
-        architecture: stored_model.network.layers.clone(),
-        epochs: stored_model.epochs,
-        learning_rate: stored_model.learning_rate,
-        total_parameters: total_params,
-    }))
+    /// JSON payload reporting the progress of run `model_id`
+    fn data(&self, model_id: &str, total_epochs: u32) -> serde_json::Value {
+        match self {
+            Progress::Epoch(record) => {
+                let metrics = record.metrics.unwrap_or_default();
+                serde_json::json!({
+                    "model_id": model_id,
+                    "epoch": record.epoch,
+                    "loss": record.loss,
+                    "accuracy": metrics.accuracy,
+                    "precision": metrics.precision,
+                    "recall": metrics.recall,
+                    "f1": metrics.f1,
+                    "elapsed_secs": record.elapsed_secs,
+                    "epoch_secs": record.epoch_secs,
+                    "learning_rate": record.learning_rate,
+                    "eta_secs": record.eta_secs(total_epochs)
+                })
+            }
+            Progress::Batch { epoch, batch, batches, running_loss } => serde_json::json!({
+                "model_id": model_id,
+                "epoch": epoch,
+                "batch": batch,
+                "batches": batches,
+                "running_loss": running_loss
+            }),
+            Progress::Paused { epoch } | Progress::Resumed { epoch } => serde_json::json!({
+                "model_id": model_id,
+                "epoch": epoch
+            }),
+            Progress::LearningRate { epoch, learning_rate } => serde_json::json!({
+                "model_id": model_id,
+                "epoch": epoch,
+                "learning_rate": learning_rate
+            }),
+            Progress::Done { status, epochs_trained, error } => serde_json::json!({
+                "model_id": model_id,
+                "status": status,
+                "epochs_trained": epochs_trained,
+                "error": error
+            }),
+        }
+    }
 }
 
+/// Seconds an epoch runs before the stream reports batch progress, and
+/// between those reports
+const BATCH_PROGRESS_SECS: f64 = 0.5;
+
 /// Train with SSE progress streaming
 async fn train_stream(
     State(state): State<AppState>,
@@ -259,104 +703,373 @@ async fn train_stream(
             )
         })?;
 
+    let sample_weights = req.sample_weights(&example.targets)?;
+
     // Create channel for progress updates (use std mpsc for Send compatibility)
-    let (tx, rx) = std::sync::mpsc::channel::<(u32, f64)>();
+    let (tx, rx) = std::sync::mpsc::channel::<Progress>();
 
-    // Spawn blocking training task
-    let example_name = req.example.clone();
-    let epochs = req.epochs;
-    let learning_rate = req.learning_rate;
-    let seed = req.seed;
-    let state_clone = state.clone();
-    let inputs = example.inputs.clone();
-    let targets = example.targets.clone();
-    let arch = example.recommended_arch.clone();
+    let model_id = Uuid::new_v4().to_string();
+    let token = CancellationToken::new();
+    state.runs.lock().unwrap().insert(model_id.clone(), token.clone());
+    let guard = CancelOnDrop(token.clone());
+    let event_model_id = model_id.clone();
+    let total_epochs = req.epochs;
 
+    // Spawn blocking training task
     tokio::task::spawn_blocking(move || {
-        // Create network (seeded if seed provided, random otherwise)
-        let network = match seed {
-            Some(s) => Network::new_seeded(arch, SIGMOID, learning_rate, s),
-            None => Network::new(arch, SIGMOID, learning_rate),
-        };
-
-        // Create training config
-        let config = TrainingConfig {
-            epochs,
-            checkpoint_interval: None,
-            checkpoint_path: None,
-            verbose: false,
-            example_name: Some(example_name.clone()),
-        };
+        let mut controller = state.run_controller(&req, &example, sample_weights, token, &model_id);
+        let done = tx.clone();
+        report_progress(&mut controller, move |progress| {
+            let _ = tx.send(progress);
+        });
 
-        let mut controller = TrainingController::new(network, config);
+        // Train the network; cancelled and failed runs are discarded
+        let result = controller.train(example.inputs, example.targets).map(|_| ());
+        let _ = done.send(state.end_run(controller, result, model_id, req.example));
+    });
 
-        // Add callback to send progress
-        let tx_clone = tx.clone();
-        controller.add_callback(Box::new(move |epoch, loss, _network| {
-            let _ = tx_clone.send((epoch, loss));
-        }));
-
-        // Train the network
-        if let Ok(()) = controller.train(inputs, targets) {
-            // Store model after training
-            let model_id = Uuid::new_v4().to_string();
-            let stored_model = StoredModel {
-                network: controller.into_network(),
-                example: example_name,
-                epochs,
-                learning_rate,
-            };
-            state_clone
-                .models
-                .lock()
-                .unwrap()
-                .insert(model_id, stored_model);
+    // Create SSE stream from std mpsc receiver; the guard travels with the
+    // stream so a disconnecting client cancels training
+    let stream = stream::unfold((rx, guard), move |(rx, guard)| {
+        let model_id = event_model_id.clone();
+        async move {
+            // Convert std::sync::mpsc to async stream
+            match rx.try_recv() {
+                Ok(progress) => {
+                    let data = progress.data(&model_id, total_epochs);
+                    let event = match progress {
+                        Progress::Epoch(_) => Event::default(),
+                        _ => Event::default().event(progress.kind()),
+                    };
+                    Some((Ok::<_, Infallible>(event.data(data.to_string())), (rx, guard)))
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {
+                    // Wait a bit and try again
+                    tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+                    Some((
+                        Ok::<_, Infallible>(Event::default().comment("heartbeat")),
+                        (rx, guard)
+                    ))
+                }
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => None,
+            }
         }
     });
 
-    // Create SSE stream from std mpsc receiver
-    let stream = stream::unfold(rx, |rx| async move {
-        // Convert std::sync::mpsc to async stream
-        match rx.try_recv() {
-            Ok((epoch, loss)) => {
-                let data = serde_json::json!({
-                    "epoch": epoch,
-                    "loss": loss
+    Ok(Sse::new(stream))
+}
+
+/// Cancel a streamed, WebSocket or background training run
+///
+/// Training stops before its next weight update and the model is discarded.
+async fn cancel_training(
+    State(state): State<AppState>,
+    Path(model_id): Path<String>,
+) -> Result<(StatusCode, Json<CancelResponse>), (StatusCode, String)> {
+    let runs = state.runs.lock().unwrap();
+    let token = runs
+        .get(&model_id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "No running training job".to_string()))?;
+    token.cancel();
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(CancelResponse {
+            model_id,
+            status: "cancelling".to_string(),
+        }),
+    ))
+}
+
+/// Start a background training job
+///
+/// Answers `202 Accepted` right away; the job trains on a blocking task once
+/// one of the `ServerOptions::max_jobs` slots is free, and `GET /api/jobs/:id`
+/// reports its progress. The job id is also the id of the stored model and
+/// of the run for `POST /api/train/:id/cancel`.
+async fn create_job(
+    State(state): State<AppState>,
+    Json(req): Json<TrainRequest>,
+) -> Result<(StatusCode, Json<JobResponse>), (StatusCode, String)> {
+    let example = examples::get_example(&req.example)
+        .ok_or_else(|| (StatusCode::BAD_REQUEST, format!("Unknown example: {}", req.example)))?;
+    let sample_weights = req.sample_weights(&example.targets)?;
+
+    let job_id = Uuid::new_v4().to_string();
+    let job = JobResponse {
+        job_id: job_id.clone(),
+        status: "queued",
+        example: req.example.clone(),
+        epochs: req.epochs,
+        epochs_trained: 0,
+        loss: None,
+        eta_secs: None,
+        model_id: None,
+        error: None,
+    };
+    state.jobs.lock().unwrap().by_id.insert(job_id.clone(), job.clone());
+    let token = CancellationToken::new();
+    state.runs.lock().unwrap().insert(job_id.clone(), token.clone());
+
+    tokio::spawn(async move {
+        // The job stays queued until a slot frees up
+        let Ok(slot) = state.job_slots.clone().acquire_owned().await else {
+            return;
+        };
+        tokio::task::spawn_blocking(move || {
+            let _slot = slot;
+            state.update_job(&job_id, |job| job.status = "running");
+            let mut controller = state.run_controller(&req, &example, sample_weights, token, &job_id);
+            let (progress_state, progress_id) = (state.clone(), job_id.clone());
+            let total_epochs = req.epochs;
+            report_progress(&mut controller, move |progress| {
+                if let Progress::Epoch(record) = progress {
+                    progress_state.update_job(&progress_id, |job| {
+                        job.epochs_trained = record.epoch;
+                        job.loss = Some(record.loss);
+                        job.eta_secs = Some(record.eta_secs(total_epochs));
+                    });
+                }
+            });
+
+            let result = controller.train(example.inputs, example.targets).map(|_| ());
+            let done = state.end_run(controller, result, job_id.clone(), req.example);
+            if let Progress::Done { status, epochs_trained, error } = done {
+                state.finish_job(&job_id, |job| {
+                    job.status = status;
+                    job.epochs_trained = epochs_trained;
+                    job.eta_secs = None;
+                    job.model_id = (status == "completed").then(|| job_id.clone());
+                    job.error = error;
                 });
-                Some((
-                    Ok::<_, Infallible>(Event::default().data(data.to_string())),
-                    rx
-                ))
             }
-            Err(std::sync::mpsc::TryRecvError::Empty) => {
-                // Wait a bit and try again
-                tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-                Some((
-                    Ok::<_, Infallible>(Event::default().comment("heartbeat")),
-                    rx
-                ))
+        });
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// Report the status and progress of a background training job
+async fn job_status(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+) -> Result<Json<JobResponse>, (StatusCode, String)> {
+    let jobs = state.jobs.lock().unwrap();
+    let job = jobs
+        .by_id
+        .get(&job_id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Job not found".to_string()))?;
+
+    Ok(Json(job.clone()))
+}
+
+/// Message a client sends over the `/api/ws` WebSocket
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    /// Begin training, with the fields of a `POST /api/train/stream` body
+    Start(TrainRequest),
+    /// Hold training after the current epoch until `resume`
+    Pause,
+    /// Continue a paused run
+    Resume,
+    /// Stop before the next weight update and discard the model
+    Cancel,
+    /// Train the following epochs with a new learning rate
+    SetLearningRate { learning_rate: f64 },
+}
+
+/// Train over a WebSocket, with progress out and control messages in
+async fn train_ws(State(state): State<AppState>, ws: WebSocketUpgrade) -> Response {
+    ws.on_upgrade(move |socket| run_ws(state, socket))
+}
+
+/// Send `data` as a JSON text message of type `kind`
+async fn send_ws(socket: &mut WebSocket, kind: &str, mut data: serde_json::Value) -> Result<(), axum::Error> {
+    data["type"] = kind.into();
+    socket.send(Message::Text(data.to_string())).await
+}
+
+/// Serve one WebSocket: wait for a valid `start`, then stream the run while
+/// forwarding control messages to it
+///
+/// Closing the socket cancels the run, as does `POST /api/train/:id/cancel`.
+async fn run_ws(state: AppState, mut socket: WebSocket) {
+    // Invalid start messages are reported and another one awaited
+    let (req, example, sample_weights) = loop {
+        let text = match socket.recv().await {
+            Some(Ok(Message::Text(text))) => text,
+            Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+            Some(Ok(_)) => continue,
+        };
+        let start = match serde_json::from_str::<ClientMessage>(&text) {
+            Ok(ClientMessage::Start(req)) => examples::get_example(&req.example)
+                .ok_or_else(|| format!("Unknown example: {}", req.example))
+                .and_then(|example| {
+                    let weights = req.sample_weights(&example.targets).map_err(|(_, message)| message)?;
+                    Ok((req, example, weights))
+                }),
+            Ok(_) => Err("No training run in progress; send a start message first".to_string()),
+            Err(e) => Err(format!("Invalid message: {}", e)),
+        };
+        match start {
+            Ok(start) => break start,
+            Err(message) => {
+                if send_ws(&mut socket, "error", serde_json::json!({ "message": message })).await.is_err() {
+                    return;
+                }
             }
-            Err(std::sync::mpsc::TryRecvError::Disconnected) => None,
         }
+    };
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Progress>();
+    let (control_tx, control_rx) = std::sync::mpsc::channel::<ClientMessage>();
+
+    let model_id = Uuid::new_v4().to_string();
+    let token = CancellationToken::new();
+    state.runs.lock().unwrap().insert(model_id.clone(), token.clone());
+    let _guard = CancelOnDrop(token.clone());
+    let total_epochs = req.epochs;
+    let started = serde_json::json!({ "model_id": model_id, "epochs": total_epochs });
+    if send_ws(&mut socket, "started", started).await.is_err() {
+        state.runs.lock().unwrap().remove(&model_id);
+        return;
+    }
+
+    let run_model_id = model_id.clone();
+    let run_token = token.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut controller = state.run_controller(&req, &example, sample_weights, run_token, &run_model_id);
+        let done = tx.clone();
+        report_progress(&mut controller, move |progress| {
+            let _ = tx.send(progress);
+        });
+
+        let result = train_controlled(&mut controller, example.inputs, example.targets, &control_rx, &done);
+        let _ = done.send(state.end_run(controller, result, run_model_id, req.example));
     });
 
-    Ok(Sse::new(stream))
+    loop {
+        tokio::select! {
+            progress = rx.recv() => {
+                let Some(progress) = progress else { break };
+                let data = progress.data(&model_id, total_epochs);
+                if send_ws(&mut socket, progress.kind(), data).await.is_err() {
+                    break;
+                }
+                if let Progress::Done { .. } = progress {
+                    let _ = socket.send(Message::Close(None)).await;
+                    break;
+                }
+            }
+            message = socket.recv() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                };
+                let error = match serde_json::from_str::<ClientMessage>(&text) {
+                    Ok(ClientMessage::Start(_)) => Some("Training already started".to_string()),
+                    Ok(control) => {
+                        if let ClientMessage::Cancel = control {
+                            token.cancel();
+                        }
+                        let _ = control_tx.send(control);
+                        None
+                    }
+                    Err(e) => Some(format!("Invalid message: {}", e)),
+                };
+                if let Some(message) = error
+                    && send_ws(&mut socket, "error", serde_json::json!({ "message": message })).await.is_err()
+                {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// How often a paused WebSocket run checks whether it was cancelled
+const PAUSE_POLL: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// Train `controller` one epoch at a time, applying the control messages
+/// received before each epoch
+///
+/// A paused run waits for `resume` or cancellation, checking the token every
+/// `PAUSE_POLL` so `POST /api/train/:id/cancel` also ends the pause. The new
+/// learning rate of `set_learning_rate` is applied to the network directly;
+/// `pause`, `resume` and learning rate changes are acknowledged through
+/// `progress` once they take effect.
+fn train_controlled(
+    controller: &mut TrainingController,
+    inputs: Vec<Vec<f64>>,
+    targets: Vec<Vec<f64>>,
+    controls: &std::sync::mpsc::Receiver<ClientMessage>,
+    progress: &tokio::sync::mpsc::UnboundedSender<Progress>,
+) -> anyhow::Result<()> {
+    controller.start(inputs, targets)?;
+    let mut paused = false;
+    while !controller.is_finished() {
+        let mut pending: Vec<ClientMessage> = controls.try_iter().collect();
+        loop {
+            for control in pending.drain(..) {
+                let epoch = controller.history().len() as u32;
+                let _ = match control {
+                    ClientMessage::Pause if !paused => {
+                        paused = true;
+                        progress.send(Progress::Paused { epoch })
+                    }
+                    ClientMessage::Resume if paused => {
+                        paused = false;
+                        progress.send(Progress::Resumed { epoch })
+                    }
+                    ClientMessage::SetLearningRate { learning_rate } => {
+                        controller.network_mut().learning_rate = learning_rate;
+                        progress.send(Progress::LearningRate { epoch, learning_rate })
+                    }
+                    _ => Ok(()),
+                };
+            }
+            if !paused || controller.is_cancelled() {
+                break;
+            }
+            match controls.recv_timeout(PAUSE_POLL) {
+                Ok(control) => pending.push(control),
+                Err(RecvTimeoutError::Timeout) => {}
+                // The socket closed, which cancels the run
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        controller.train_epochs(1)?;
+    }
+    controller.finish()?;
+    Ok(())
 }
 
 /// Run the web server on the specified address
 pub async fn run_server(addr: &str) -> Result<(), anyhow::Error> {
+    run_server_with_options(addr, ServerOptions::default()).await
+}
+
+/// Run the web server on the specified address with custom options
+pub async fn run_server_with_options(addr: &str, options: ServerOptions) -> Result<(), anyhow::Error> {
     use tower_http::services::ServeDir;
     use tower_http::cors::CorsLayer;
 
-    let state = AppState::new();
+    let state = AppState::new(options);
 
     // API routes
     let api_routes = Router::new()
         .route("/health", get(health))
-        .route("/api/examples", get(list_examples))
+        .route("/api/examples", get(list_examples).post(register_example))
         .route("/api/train", post(train))
         .route("/api/train/stream", post(train_stream))
+        .route("/api/train/:id/cancel", post(cancel_training))
+        .route("/api/ws", get(train_ws))
+        .route("/api/jobs", post(create_job))
+        .route("/api/jobs/:id", get(job_status))
         .route("/api/eval", post(eval))
+        .route("/api/models", post(upload_model))
         .route("/api/models/:id", get(model_info))
         .with_state(state);
 
@@ -6,7 +6,7 @@ use clap::Parser;
 /// Neural Network REST API Server
 ///
 /// Provides REST API endpoints for training and evaluating neural networks,
-/// with Server-Sent Events (SSE) for real-time training progress.
+/// with Server-Sent Events (SSE) and a WebSocket for real-time training progress.
 #[derive(Parser, Debug)]
 #[command(name = "neural-net-server")]
 #[command(author, version, about, long_about = None)]
@@ -18,6 +18,18 @@ struct Args {
     /// Port number to listen on
     #[arg(short, long, default_value = "2421")]
     port: u16,
+
+    /// Write TensorBoard event files for each training run to <DIR>/<model_id>/
+    #[arg(long, value_name = "DIR")]
+    tensorboard_dir: Option<std::path::PathBuf>,
+
+    /// Decrypt uploaded checkpoints with the 256-bit key in this file (raw or hex)
+    #[arg(long, value_name = "FILE")]
+    checkpoint_key_file: Option<std::path::PathBuf>,
+
+    /// Background jobs that train at once; later ones wait as queued [default: CPU cores]
+    #[arg(long, value_name = "N")]
+    max_jobs: Option<usize>,
 }
 
 #[tokio::main]
@@ -36,11 +48,34 @@ async fn main() {
     println!("  - API Examples:    http://{}/api/examples", addr);
     println!("  - Train (sync):    POST http://{}/api/train", addr);
     println!("  - Train (stream):  POST http://{}/api/train/stream", addr);
+    println!("  - Train (socket):  ws://{}/api/ws", addr);
+    println!("  - Train (job):     POST http://{}/api/jobs", addr);
+    println!("  - Job Status:      GET  http://{}/api/jobs/:id", addr);
     println!("  - Evaluate:        POST http://{}/api/eval", addr);
+    println!("  - Upload Model:    POST http://{}/api/models", addr);
     println!("  - Model Info:      GET  http://{}/api/models/:id", addr);
+    if let Some(dir) = &args.tensorboard_dir {
+        println!("TensorBoard logs: {}", dir.display());
+    }
     println!();
 
-    if let Err(e) = neural_net_server::run_server(&addr).await {
+    let checkpoint_key = match &args.checkpoint_key_file {
+        Some(path) => match neural_network::encryption::Secret::from_key_file(path) {
+            Ok(key) => Some(key),
+            Err(e) => {
+                eprintln!("Error: {:#}", e);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+    let options = neural_net_server::ServerOptions {
+        tensorboard_dir: args.tensorboard_dir,
+        checkpoint_key,
+        max_jobs: args.max_jobs,
+        ..Default::default()
+    };
+    if let Err(e) = neural_net_server::run_server_with_options(&addr, options).await {
         eprintln!("Server error: {}", e);
         std::process::exit(1);
     }
@@ -195,3 +195,444 @@ async fn test_eval_nonexistent_model() {
 
     handle.abort();
 }
+
+#[tokio::test]
+async fn test_eval_wrong_input_dimensions() {
+    let handle = start_test_server(3016).await;
+    sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let train_body = json!({
+        "example": "and",
+        "epochs": 10,
+        "learning_rate": 0.5
+    });
+    let train_result: serde_json::Value = client
+        .post("http://127.0.0.1:3016/api/train")
+        .json(&train_body)
+        .send()
+        .await
+        .expect("Should train model")
+        .json()
+        .await
+        .unwrap();
+
+    let eval_body = json!({
+        "model_id": train_result["model_id"],
+        "input": [0.0, 0.0, 1.0]
+    });
+    let response = client
+        .post("http://127.0.0.1:3016/api/eval")
+        .json(&eval_body)
+        .send()
+        .await
+        .expect("Should get response");
+
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    let message = response.text().await.unwrap();
+    assert!(message.contains("Expected a 2x1 matrix, got 3x1"), "Unexpected message: {}", message);
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_train_writes_tensorboard_events() {
+    let log_dir = tempfile::TempDir::new().unwrap();
+    let options = neural_net_server::ServerOptions {
+        tensorboard_dir: Some(log_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let handle = tokio::spawn(async move {
+        neural_net_server::run_server_with_options("127.0.0.1:3017", options).await
+    });
+    sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let request_body = json!({
+        "example": "and",
+        "epochs": 10,
+        "learning_rate": 0.5
+    });
+
+    let body: serde_json::Value = client
+        .post("http://127.0.0.1:3017/api/train")
+        .json(&request_body)
+        .send()
+        .await
+        .expect("Should get response")
+        .json()
+        .await
+        .expect("Should parse JSON");
+
+    let run_dir = log_dir.path().join(body["model_id"].as_str().unwrap());
+    let files: Vec<_> = std::fs::read_dir(&run_dir).expect("Run directory should exist").collect();
+    assert_eq!(files.len(), 1, "One event file per run");
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_train_stops_at_target_loss() {
+    let handle = start_test_server(3018).await;
+    sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let request_body = json!({
+        "example": "and",
+        "epochs": 100000,
+        "learning_rate": 1.0,
+        "seed": 3,
+        "target_loss": 0.05
+    });
+
+    let body: serde_json::Value = client
+        .post("http://127.0.0.1:3018/api/train")
+        .json(&request_body)
+        .send()
+        .await
+        .expect("Should get response")
+        .json()
+        .await
+        .expect("Should parse JSON");
+
+    assert_eq!(body["epochs"], 100000);
+    let trained = body["epochs_trained"].as_u64().unwrap();
+    assert!(trained > 0 && trained < 100000, "Should stop early, trained {}", trained);
+
+    let info: serde_json::Value = client
+        .get(format!("http://127.0.0.1:3018/api/models/{}", body["model_id"].as_str().unwrap()))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(info["epochs"].as_u64(), Some(trained), "Model info reports epochs actually trained");
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_eval_applies_normalization() {
+    let handle = start_test_server(3026).await;
+    sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .post("http://127.0.0.1:3026/api/train")
+        .json(&json!({
+            "example": "iris",
+            "epochs": 300,
+            "learning_rate": 0.3,
+            "seed": 42,
+            "normalize": "zscore"
+        }))
+        .send()
+        .await
+        .expect("Should get response");
+    assert_eq!(response.status(), 200);
+    let model_id = response.json::<serde_json::Value>().await.unwrap()["model_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    // Raw measurements in cm; the server scales them like the training data
+    let response = client
+        .post("http://127.0.0.1:3026/api/eval")
+        .json(&json!({"model_id": model_id, "input": [5.1, 3.5, 1.4, 0.2]}))
+        .send()
+        .await
+        .expect("Should get response");
+    let output: Vec<f64> = serde_json::from_value(response.json::<serde_json::Value>().await.unwrap()["output"].clone())
+        .unwrap();
+    assert!(output[0] > output[1] && output[0] > output[2], "Setosa expected, got {:?}", output);
+
+    let response = client
+        .post("http://127.0.0.1:3026/api/train")
+        .json(&json!({"example": "iris", "epochs": 1, "learning_rate": 0.3, "normalize": "log"}))
+        .send()
+        .await
+        .expect("Should get response");
+    assert!(response.status().is_client_error(), "Unknown normalization is rejected");
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_eval_inverts_target_scaling() {
+    let handle = start_test_server(3028).await;
+    sleep(Duration::from_millis(100)).await;
+
+    // y = 100x + 500, far outside the sigmoid output range
+    let inputs: Vec<Vec<f64>> = (0..=10).map(|i| vec![i as f64 / 10.0]).collect();
+    let targets: Vec<Vec<f64>> = inputs.iter().map(|x| vec![100.0 * x[0] + 500.0]).collect();
+    let client = reqwest::Client::new();
+    let response = client
+        .post("http://127.0.0.1:3028/api/examples")
+        .json(&json!({"name": "api-line", "inputs": inputs, "targets": targets, "architecture": [1, 4, 1]}))
+        .send()
+        .await
+        .expect("Should get response");
+    assert_eq!(response.status(), 201);
+
+    let response = client
+        .post("http://127.0.0.1:3028/api/train")
+        .json(&json!({
+            "example": "api-line",
+            "epochs": 3000,
+            "learning_rate": 0.5,
+            "seed": 3,
+            "normalize_targets": "minmax"
+        }))
+        .send()
+        .await
+        .expect("Should get response");
+    assert_eq!(response.status(), 200);
+    let model_id = response.json::<serde_json::Value>().await.unwrap()["model_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let response = client
+        .post("http://127.0.0.1:3028/api/eval")
+        .json(&json!({"model_id": model_id, "input": [0.5]}))
+        .send()
+        .await
+        .expect("Should get response");
+    let output: Vec<f64> = serde_json::from_value(response.json::<serde_json::Value>().await.unwrap()["output"].clone())
+        .unwrap();
+    assert!((output[0] - 550.0).abs() < 10.0, "Output in target units, got {:?}", output);
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_register_custom_example() {
+    let handle = start_test_server(3027).await;
+    sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .post("http://127.0.0.1:3027/api/examples")
+        .json(&json!({
+            "name": "api-nand",
+            "description": "NAND gate",
+            "inputs": [[0.0, 0.0], [0.0, 1.0], [1.0, 0.0], [1.0, 1.0]],
+            "targets": [[1.0], [1.0], [1.0], [0.0]],
+            "architecture": [2, 3, 1]
+        }))
+        .send()
+        .await
+        .expect("Should get response");
+    assert_eq!(response.status(), 201);
+    let info: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(info["architecture"], json!([2, 3, 1]));
+
+    let names: Vec<serde_json::Value> = client
+        .get("http://127.0.0.1:3027/api/examples")
+        .send()
+        .await
+        .unwrap()
+        .json::<Vec<serde_json::Value>>()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|ex| ex["name"].clone())
+        .collect();
+    assert!(names.contains(&json!("api-nand")));
+
+    // The registered example trains by name like a built-in one
+    let response = client
+        .post("http://127.0.0.1:3027/api/train")
+        .json(&json!({"example": "api-nand", "epochs": 10, "learning_rate": 0.5}))
+        .send()
+        .await
+        .expect("Should get response");
+    assert_eq!(response.status(), 200);
+
+    for body in [
+        json!({"name": "xor", "inputs": [[0.0, 0.0]], "targets": [[0.0]]}),
+        json!({"name": "ragged", "inputs": [[0.0, 0.0], [1.0]], "targets": [[0.0], [1.0]]}),
+        json!({"name": "wide", "inputs": [[0.0, 0.0]], "targets": [[0.0]], "architecture": [3, 1]}),
+    ] {
+        let response = client.post("http://127.0.0.1:3027/api/examples").json(&body).send().await.unwrap();
+        assert_eq!(response.status(), 400, "{} is rejected", body["name"]);
+    }
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_train_with_class_weights() {
+    let handle = start_test_server(3019).await;
+    sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let train = |body: serde_json::Value| client.post("http://127.0.0.1:3019/api/train").json(&body).send();
+
+    let response = train(json!({
+        "example": "and",
+        "epochs": 100,
+        "learning_rate": 0.5,
+        "class_weights": [1.0, 3.0]
+    }))
+    .await
+    .expect("Should get response");
+    assert_eq!(response.status(), 200);
+
+    let response = train(json!({
+        "example": "and",
+        "epochs": 100,
+        "learning_rate": 0.5,
+        "sample_weights": [1.0, 2.0]
+    }))
+    .await
+    .expect("Should get response");
+    assert_eq!(response.status(), 400, "One weight per sample is required");
+    assert!(response.text().await.unwrap().contains("2 sample weights for 4 samples"));
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_upload_stripped_checkpoint() {
+    use neural_network::{activations::SIGMOID, checkpoint::CheckpointMetadata, network::Network};
+
+    let handle = start_test_server(3029).await;
+    sleep(Duration::from_millis(100)).await;
+
+    let mut network = Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 4);
+    let metadata = CheckpointMetadata {
+        version: "1.0".to_string(),
+        example: "xor".to_string(),
+        epoch: 200,
+        total_epochs: 200,
+        learning_rate: 0.5,
+        timestamp: "2025-10-13T12:00:00Z".to_string(),
+        ..Default::default()
+    };
+    let bytes = network.to_checkpoint(metadata).stripped().to_bytes().unwrap();
+
+    let client = reqwest::Client::new();
+    let response = client
+        .post("http://127.0.0.1:3029/api/models")
+        .body(bytes)
+        .send()
+        .await
+        .expect("Should get response");
+    assert_eq!(response.status(), 201);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["example"], "xor");
+    assert_eq!(body["epochs"], 200);
+    assert_eq!(body["architecture"], json!([2, 3, 1]));
+
+    let response = client
+        .post("http://127.0.0.1:3029/api/eval")
+        .json(&json!({"model_id": body["model_id"], "input": [1.0, 0.0]}))
+        .send()
+        .await
+        .expect("Should get response");
+    let output: Vec<f64> = serde_json::from_value(response.json::<serde_json::Value>().await.unwrap()["output"].clone())
+        .unwrap();
+    let expected = network.feed_forward(neural_network::matrix::Matrix::from(vec![1.0, 0.0])).data;
+    assert_eq!(output, expected);
+
+    let response = client
+        .post("http://127.0.0.1:3029/api/models")
+        .body("not a checkpoint")
+        .send()
+        .await
+        .expect("Should get response");
+    assert_eq!(response.status(), 400);
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_upload_encrypted_checkpoint() {
+    use neural_network::{activations::SIGMOID, checkpoint::CheckpointMetadata, encryption::Secret, network::Network};
+
+    let key = Secret::Key([3; 32]);
+    let options = neural_net_server::ServerOptions {
+        checkpoint_key: Some(key.clone()),
+        ..Default::default()
+    };
+    let handle = tokio::spawn(async move {
+        neural_net_server::run_server_with_options("127.0.0.1:3030", options).await
+    });
+    sleep(Duration::from_millis(100)).await;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("model.bin");
+    let metadata = CheckpointMetadata {
+        version: "1.0".to_string(),
+        example: "and".to_string(),
+        ..Default::default()
+    };
+    let checkpoint = Network::new_seeded(vec![2, 2, 1], SIGMOID, 0.5, 8).to_checkpoint(metadata);
+
+    let client = reqwest::Client::new();
+    checkpoint.save_with_secret(&path, Some(&key)).unwrap();
+    let response = client
+        .post("http://127.0.0.1:3030/api/models")
+        .body(std::fs::read(&path).unwrap())
+        .send()
+        .await
+        .expect("Should get response");
+    assert_eq!(response.status(), 201, "The server key decrypts the upload");
+
+    checkpoint.save_with_secret(&path, Some(&Secret::Key([4; 32]))).unwrap();
+    let response = client
+        .post("http://127.0.0.1:3030/api/models")
+        .body(std::fs::read(&path).unwrap())
+        .send()
+        .await
+        .expect("Should get response");
+    assert_eq!(response.status(), 400);
+    assert!(response.text().await.unwrap().contains("wrong password or key"));
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_preloaded_models() {
+    use neural_network::{activations::SIGMOID, checkpoint::CheckpointMetadata, inference::InferenceModel, network::Network};
+
+    let metadata = CheckpointMetadata {
+        version: "1.0".to_string(),
+        example: "xor".to_string(),
+        epoch: 250,
+        ..Default::default()
+    };
+    let model = InferenceModel::from(Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 5).to_checkpoint(metadata));
+    let options = neural_net_server::ServerOptions {
+        models: vec![("xor".to_string(), model)],
+        ..Default::default()
+    };
+    let handle = tokio::spawn(async move {
+        neural_net_server::run_server_with_options("127.0.0.1:3031", options).await
+    });
+    sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let info: serde_json::Value = client
+        .get("http://127.0.0.1:3031/api/models/xor")
+        .send()
+        .await
+        .expect("Should get response")
+        .json()
+        .await
+        .expect("Should parse JSON");
+    assert_eq!(info["architecture"], json!([2, 3, 1]));
+    assert_eq!(info["epochs"], 250);
+
+    let response = client
+        .post("http://127.0.0.1:3031/api/eval")
+        .json(&json!({ "model_id": "xor", "input": [1.0, 0.0] }))
+        .send()
+        .await
+        .expect("Should get response");
+    assert!(response.status().is_success());
+
+    handle.abort();
+}
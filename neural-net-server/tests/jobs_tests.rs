@@ -0,0 +1,252 @@
+// Integration tests for background training jobs
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::time::sleep;
+
+async fn start_test_server(port: u16) -> tokio::task::JoinHandle<Result<(), anyhow::Error>> {
+    let addr = format!("127.0.0.1:{}", port);
+    tokio::spawn(async move {
+        neural_net_server::run_server(&addr).await
+    })
+}
+
+/// Poll `GET /api/jobs/:id` until the job leaves `queued` and `running`
+async fn wait_for_job(client: &reqwest::Client, port: u16, job_id: &str) -> Value {
+    for _ in 0..600 {
+        let job: Value = client
+            .get(format!("http://127.0.0.1:{}/api/jobs/{}", port, job_id))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        if job["status"] != "queued" && job["status"] != "running" {
+            return job;
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+    panic!("Job {} did not finish within 30s", job_id);
+}
+
+#[tokio::test]
+async fn test_job_returns_immediately_and_completes() {
+    let handle = start_test_server(3037).await;
+    sleep(Duration::from_millis(100)).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post("http://127.0.0.1:3037/api/jobs")
+        .json(&json!({"example": "and", "epochs": 200, "learning_rate": 0.5, "seed": 42}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let job: Value = response.json().await.unwrap();
+    assert_eq!(job["status"], "queued");
+    assert_eq!(job["epochs"], 200);
+    assert!(job["model_id"].is_null());
+    let job_id = job["job_id"].as_str().unwrap().to_string();
+
+    let job = wait_for_job(&client, 3037, &job_id).await;
+    assert_eq!(job["status"], "completed");
+    assert_eq!(job["epochs_trained"], 200);
+    assert!(job["loss"].as_f64().unwrap() > 0.0);
+    assert!(job["error"].is_null());
+
+    // The trained model is stored and can be evaluated
+    let model_id = job["model_id"].as_str().unwrap();
+    let response = client
+        .post("http://127.0.0.1:3037/api/eval")
+        .json(&json!({"model_id": model_id, "input": [1.0, 1.0]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_job_reports_progress_and_can_be_cancelled() {
+    let handle = start_test_server(3038).await;
+    sleep(Duration::from_millis(100)).await;
+    let client = reqwest::Client::new();
+
+    let job: Value = client
+        .post("http://127.0.0.1:3038/api/jobs")
+        .json(&json!({"example": "xor", "epochs": 10_000_000, "learning_rate": 0.5}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let job_id = job["job_id"].as_str().unwrap().to_string();
+
+    let mut running = Value::Null;
+    for _ in 0..600 {
+        running = client
+            .get(format!("http://127.0.0.1:3038/api/jobs/{}", job_id))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        if running["epochs_trained"].as_u64().unwrap() > 0 {
+            break;
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(running["status"], "running");
+    assert!(running["loss"].is_f64());
+    assert!(running["eta_secs"].as_f64().unwrap() > 0.0);
+
+    let response = client
+        .post(format!("http://127.0.0.1:3038/api/train/{}/cancel", job_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    let job = wait_for_job(&client, 3038, &job_id).await;
+    assert_eq!(job["status"], "cancelled");
+    assert!(job["model_id"].is_null(), "Cancelled jobs store no model");
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_job_errors() {
+    let handle = start_test_server(3039).await;
+    sleep(Duration::from_millis(100)).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post("http://127.0.0.1:3039/api/jobs")
+        .json(&json!({"example": "nonexistent", "epochs": 10, "learning_rate": 0.5}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = client
+        .get("http://127.0.0.1:3039/api/jobs/no-such-job")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_oldest_finished_jobs_are_evicted() {
+    let handle = start_test_server(3040).await;
+    sleep(Duration::from_millis(100)).await;
+    let client = reqwest::Client::new();
+
+    let mut job_ids = Vec::new();
+    for _ in 0..=neural_net_server::MAX_FINISHED_JOBS {
+        let job: Value = client
+            .post("http://127.0.0.1:3040/api/jobs")
+            .json(&json!({"example": "and", "epochs": 1, "learning_rate": 0.5}))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let job_id = job["job_id"].as_str().unwrap().to_string();
+        assert_eq!(wait_for_job(&client, 3040, &job_id).await["status"], "completed");
+        job_ids.push(job_id);
+    }
+
+    let response = client
+        .get(format!("http://127.0.0.1:3040/api/jobs/{}", job_ids[0]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND, "The oldest finished job is forgotten");
+    let response = client
+        .get(format!("http://127.0.0.1:3040/api/jobs/{}", job_ids[1]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Its model is still stored
+    let response = client
+        .post("http://127.0.0.1:3040/api/eval")
+        .json(&json!({"model_id": job_ids[0], "input": [1.0, 1.0]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_jobs_beyond_max_jobs_stay_queued() {
+    let options = neural_net_server::ServerOptions {
+        max_jobs: Some(1),
+        ..Default::default()
+    };
+    let handle = tokio::spawn(async move {
+        neural_net_server::run_server_with_options("127.0.0.1:3041", options).await
+    });
+    sleep(Duration::from_millis(100)).await;
+    let client = reqwest::Client::new();
+
+    let mut job_ids = Vec::new();
+    for epochs in [10_000_000, 10] {
+        let job: Value = client
+            .post("http://127.0.0.1:3041/api/jobs")
+            .json(&json!({"example": "xor", "epochs": epochs, "learning_rate": 0.5}))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        job_ids.push(job["job_id"].as_str().unwrap().to_string());
+    }
+    let status = |job_id: String| {
+        let client = client.clone();
+        async move {
+            let job: Value = client
+                .get(format!("http://127.0.0.1:3041/api/jobs/{}", job_id))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            job["status"].as_str().unwrap().to_string()
+        }
+    };
+
+    for _ in 0..600 {
+        if status(job_ids[0].clone()).await == "running" {
+            break;
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(status(job_ids[0].clone()).await, "running");
+    sleep(Duration::from_millis(200)).await;
+    assert_eq!(status(job_ids[1].clone()).await, "queued", "Only one job trains at a time");
+
+    // Cancelling the first job frees its slot for the second
+    client
+        .post(format!("http://127.0.0.1:3041/api/train/{}/cancel", job_ids[0]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(wait_for_job(&client, 3041, &job_ids[1]).await["status"], "completed");
+    assert_eq!(status(job_ids[0].clone()).await, "cancelled");
+
+    handle.abort();
+}
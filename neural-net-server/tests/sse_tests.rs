@@ -10,6 +10,22 @@ async fn start_test_server(port: u16) -> tokio::task::JoinHandle<Result<(), anyh
     })
 }
 
+/// The `(event name, data)` pairs of an SSE body; unnamed events are `message`
+fn parse_events(body: &str) -> Vec<(String, serde_json::Value)> {
+    let mut events = Vec::new();
+    let mut name = "message".to_string();
+    for line in body.lines() {
+        if let Some(event) = line.strip_prefix("event: ") {
+            name = event.to_string();
+        } else if let Some(data) = line.strip_prefix("data: ") {
+            events.push((name.clone(), serde_json::from_str(data).unwrap()));
+        } else if line.is_empty() {
+            name = "message".to_string();
+        }
+    }
+    events
+}
+
 #[tokio::test]
 async fn test_train_stream_endpoint_exists() {
     let handle = start_test_server(3020).await;
@@ -123,3 +139,162 @@ async fn test_sse_invalid_example() {
 
     handle.abort();
 }
+
+#[tokio::test]
+async fn test_sse_events_include_classification_metrics() {
+    let handle = start_test_server(3024).await;
+    sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+
+    let request_body = json!({
+        "example": "and",
+        "epochs": 3,
+        "learning_rate": 0.5,
+        "seed": 42,
+        "threshold": 0.5
+    });
+
+    let body = client
+        .post("http://127.0.0.1:3024/api/train/stream")
+        .json(&request_body)
+        .send()
+        .await
+        .expect("Should get response")
+        .text()
+        .await
+        .expect("Stream should finish after training");
+
+    let events: Vec<serde_json::Value> = parse_events(&body)
+        .into_iter()
+        .filter(|(name, _)| name == "message")
+        .map(|(_, data)| data)
+        .collect();
+    assert_eq!(events.len(), 3, "One event per epoch");
+    for event in &events {
+        for key in ["loss", "accuracy", "precision", "recall", "f1", "elapsed_secs", "epoch_secs", "eta_secs"] {
+            assert!(event[key].is_f64(), "Event should include {}", key);
+        }
+    }
+    assert_eq!(events[2]["eta_secs"], 0.0, "Nothing left after the last epoch");
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_sse_stream_ends_with_done_event() {
+    let handle = start_test_server(3032).await;
+    sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+
+    let request_body = json!({
+        "example": "xor",
+        "epochs": 20,
+        "learning_rate": 0.5,
+        "seed": 42
+    });
+
+    let body = client
+        .post("http://127.0.0.1:3032/api/train/stream")
+        .json(&request_body)
+        .send()
+        .await
+        .expect("Should get response")
+        .text()
+        .await
+        .expect("Stream should finish after training");
+
+    let events = parse_events(&body);
+    let (name, done) = events.last().expect("Stream should carry events");
+    assert_eq!(name, "done", "The last event reports the end of the run");
+    assert_eq!(done["status"], "completed");
+    assert_eq!(done["epochs_trained"], 20);
+    assert!(done["error"].is_null());
+    let model_id = done["model_id"].as_str().unwrap();
+    assert_eq!(events[0].1["model_id"], model_id, "Progress events carry the same id");
+    assert_eq!(events.iter().filter(|(name, _)| name == "done").count(), 1);
+
+    // The model is stored by the time the done event arrives
+    let info = client
+        .get(format!("http://127.0.0.1:3032/api/models/{}", model_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(info.status(), reqwest::StatusCode::OK);
+    let info: serde_json::Value = info.json().await.unwrap();
+    assert_eq!(info["epochs"], 20);
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_cancel_streamed_training() {
+    let handle = start_test_server(3025).await;
+    sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+
+    let request_body = json!({
+        "example": "xor",
+        "epochs": 10_000_000,
+        "learning_rate": 0.5
+    });
+
+    let mut response = client
+        .post("http://127.0.0.1:3025/api/train/stream")
+        .json(&request_body)
+        .send()
+        .await
+        .expect("Should get response");
+
+    // Read until the first progress event to learn the run's id
+    let mut buffer = String::new();
+    let model_id = loop {
+        let chunk = response.chunk().await.unwrap().expect("Stream ended before any event");
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+        let event = buffer
+            .lines()
+            .filter_map(|line| line.strip_prefix("data: "))
+            .find_map(|data| serde_json::from_str::<serde_json::Value>(data).ok());
+        if let Some(event) = event {
+            break event["model_id"].as_str().unwrap().to_string();
+        }
+    };
+
+    let cancel = client
+        .post(format!("http://127.0.0.1:3025/api/train/{}/cancel", model_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(cancel.status(), reqwest::StatusCode::ACCEPTED);
+
+    // The stream ends once training stops, reporting the cancellation
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while let Some(chunk) = response.chunk().await.unwrap() {
+            buffer.push_str(&String::from_utf8_lossy(&chunk));
+        }
+    })
+    .await
+    .expect("Cancelled training should end the stream");
+    let (name, done) = parse_events(&buffer).pop().unwrap();
+    assert_eq!(name, "done");
+    assert_eq!(done["status"], "cancelled");
+    assert_eq!(done["model_id"], model_id.as_str());
+
+    // Cancelled runs are not stored and can no longer be cancelled
+    let info = client
+        .get(format!("http://127.0.0.1:3025/api/models/{}", model_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(info.status(), reqwest::StatusCode::NOT_FOUND);
+    let again = client
+        .post(format!("http://127.0.0.1:3025/api/train/{}/cancel", model_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(again.status(), reqwest::StatusCode::NOT_FOUND);
+
+    handle.abort();
+}
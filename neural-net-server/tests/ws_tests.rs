@@ -0,0 +1,177 @@
+// Integration tests for the training WebSocket
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::time::{sleep, timeout};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn start_test_server(port: u16) -> tokio::task::JoinHandle<Result<(), anyhow::Error>> {
+    let addr = format!("127.0.0.1:{}", port);
+    tokio::spawn(async move {
+        neural_net_server::run_server(&addr).await
+    })
+}
+
+async fn connect(port: u16) -> Socket {
+    let (socket, _) = connect_async(format!("ws://127.0.0.1:{}/api/ws", port)).await.unwrap();
+    socket
+}
+
+async fn send(socket: &mut Socket, message: Value) {
+    socket.send(Message::Text(message.to_string())).await.unwrap();
+}
+
+/// The next JSON message, `None` once the server closes the socket
+async fn next(socket: &mut Socket) -> Option<Value> {
+    loop {
+        let message = timeout(Duration::from_secs(30), socket.next()).await.expect("No message within 30s")?;
+        match message.unwrap() {
+            Message::Text(text) => return Some(serde_json::from_str(&text).unwrap()),
+            Message::Close(_) => return None,
+            _ => continue,
+        }
+    }
+}
+
+/// Skip messages until one of type `kind`
+async fn next_of(socket: &mut Socket, kind: &str) -> Value {
+    loop {
+        let message = next(socket).await.unwrap_or_else(|| panic!("Socket closed before a {} message", kind));
+        if message["type"] == kind {
+            return message;
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_ws_streams_epochs_and_ends_with_done() {
+    let handle = start_test_server(3033).await;
+    sleep(Duration::from_millis(100)).await;
+
+    let mut socket = connect(3033).await;
+    send(&mut socket, json!({"type": "start", "example": "and", "epochs": 20, "learning_rate": 0.5, "seed": 42})).await;
+
+    let started = next(&mut socket).await.unwrap();
+    assert_eq!(started["type"], "started");
+    assert_eq!(started["epochs"], 20);
+    let model_id = started["model_id"].as_str().unwrap().to_string();
+
+    let mut epochs = 0;
+    let done = loop {
+        let message = next(&mut socket).await.expect("Socket closed before done");
+        match message["type"].as_str().unwrap() {
+            "epoch" => {
+                epochs += 1;
+                assert_eq!(message["model_id"], model_id.as_str());
+                assert_eq!(message["learning_rate"], 0.5);
+            }
+            "batch" => {}
+            "done" => break message,
+            other => panic!("Unexpected message type {}", other),
+        }
+    };
+    assert_eq!(epochs, 20);
+    assert_eq!(done["status"], "completed");
+    assert_eq!(done["epochs_trained"], 20);
+    assert!(next(&mut socket).await.is_none(), "The server closes the socket after done");
+
+    let info: Value = reqwest::get(format!("http://127.0.0.1:3033/api/models/{}", model_id))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(info["epochs"], 20);
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_ws_invalid_start_is_reported_and_retried() {
+    let handle = start_test_server(3034).await;
+    sleep(Duration::from_millis(100)).await;
+
+    let mut socket = connect(3034).await;
+    send(&mut socket, json!({"type": "pause"})).await;
+    let error = next(&mut socket).await.unwrap();
+    assert_eq!(error["type"], "error");
+    assert!(error["message"].as_str().unwrap().contains("start"));
+
+    send(&mut socket, json!({"type": "start", "example": "nonexistent", "epochs": 5, "learning_rate": 0.5})).await;
+    let error = next(&mut socket).await.unwrap();
+    assert_eq!(error["type"], "error");
+    assert!(error["message"].as_str().unwrap().contains("Unknown example"));
+
+    socket.send(Message::Text("not json".to_string())).await.unwrap();
+    let error = next(&mut socket).await.unwrap();
+    assert_eq!(error["type"], "error");
+
+    send(&mut socket, json!({"type": "start", "example": "and", "epochs": 5, "learning_rate": 0.5})).await;
+    assert_eq!(next(&mut socket).await.unwrap()["type"], "started");
+    assert_eq!(next_of(&mut socket, "done").await["status"], "completed");
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_ws_pause_set_learning_rate_and_resume() {
+    let handle = start_test_server(3035).await;
+    sleep(Duration::from_millis(100)).await;
+
+    let mut socket = connect(3035).await;
+    send(&mut socket, json!({"type": "start", "example": "xor", "epochs": 1_000_000, "learning_rate": 0.5})).await;
+    assert_eq!(next(&mut socket).await.unwrap()["type"], "started");
+
+    send(&mut socket, json!({"type": "pause"})).await;
+    let paused = next_of(&mut socket, "paused").await;
+    let paused_at = paused["epoch"].as_u64().unwrap();
+
+    send(&mut socket, json!({"type": "set_learning_rate", "learning_rate": 0.1})).await;
+    let changed = next(&mut socket).await.unwrap();
+    assert_eq!(changed["type"], "learning_rate", "No epochs train while paused");
+    assert_eq!(changed["learning_rate"], 0.1);
+    assert_eq!(changed["epoch"].as_u64().unwrap(), paused_at);
+
+    send(&mut socket, json!({"type": "resume"})).await;
+    assert_eq!(next(&mut socket).await.unwrap()["type"], "resumed");
+    let epoch = next_of(&mut socket, "epoch").await;
+    assert_eq!(epoch["epoch"].as_u64().unwrap(), paused_at + 1);
+    assert_eq!(epoch["learning_rate"], 0.1);
+
+    send(&mut socket, json!({"type": "cancel"})).await;
+    let done = next_of(&mut socket, "done").await;
+    assert_eq!(done["status"], "cancelled");
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_ws_cancel_while_paused() {
+    let handle = start_test_server(3036).await;
+    sleep(Duration::from_millis(100)).await;
+
+    let mut socket = connect(3036).await;
+    send(&mut socket, json!({"type": "start", "example": "xor", "epochs": 1_000_000, "learning_rate": 0.5})).await;
+    let model_id = next(&mut socket).await.unwrap()["model_id"].as_str().unwrap().to_string();
+    send(&mut socket, json!({"type": "pause"})).await;
+    next_of(&mut socket, "paused").await;
+
+    // The REST cancel endpoint also ends a paused WebSocket run
+    let response = reqwest::Client::new()
+        .post(format!("http://127.0.0.1:3036/api/train/{}/cancel", model_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+
+    let done = next_of(&mut socket, "done").await;
+    assert_eq!(done["status"], "cancelled");
+    let info = reqwest::get(format!("http://127.0.0.1:3036/api/models/{}", model_id)).await.unwrap();
+    assert_eq!(info.status(), reqwest::StatusCode::NOT_FOUND, "Cancelled runs are discarded");
+
+    handle.abort();
+}
@@ -4,8 +4,11 @@
 use wasm_bindgen::prelude::*;
 use neural_network::{
     activations::SIGMOID,
+    callbacks::from_fn,
     examples,
+    inference::InferenceModel,
     network::Network,
+    normalize::{Normalization, Normalizer},
     training::{TrainingConfig, TrainingController},
 };
 use serde::{Deserialize, Serialize};
@@ -47,6 +50,17 @@ pub struct TrainingProgress {
 pub struct NeuralNetwork {
     network: Network,
     example_name: Option<String>,
+    /// Run in progress for incremental training (`startTraining`/`trainEpochs`)
+    session: Option<TrainingController>,
+    last_loss: Option<f64>,
+    /// Input scaling fit by the next training run
+    normalization: Option<Normalization>,
+    /// Input scaling fit by the last training run, applied by evaluate
+    normalizer: Option<Normalizer>,
+    /// Target scaling fit by the next training run
+    target_normalization: Option<Normalization>,
+    /// Target scaling fit by the last training run, undone by evaluate
+    target_normalizer: Option<Normalizer>,
 }
 
 #[wasm_bindgen]
@@ -62,6 +76,12 @@ impl NeuralNetwork {
         Ok(NeuralNetwork {
             network,
             example_name: None,
+            session: None,
+            last_loss: None,
+            normalization: None,
+            normalizer: None,
+            target_normalization: None,
+            target_normalizer: None,
         })
     }
 
@@ -79,9 +99,42 @@ impl NeuralNetwork {
         Ok(NeuralNetwork {
             network,
             example_name: Some(example_name.to_string()),
+            session: None,
+            last_loss: None,
+            normalization: None,
+            normalizer: None,
+            target_normalization: None,
+            target_normalizer: None,
         })
     }
 
+    /// Scale inputs with a normalizer fit by the next training run
+    /// Pass "minmax", "zscore", or nothing to train on raw inputs
+    #[wasm_bindgen(js_name = setNormalization)]
+    pub fn set_normalization(&mut self, kind: Option<String>) -> Result<(), JsValue> {
+        self.normalization = match kind.as_deref() {
+            None => None,
+            Some("minmax") => Some(Normalization::MinMax),
+            Some("zscore") => Some(Normalization::ZScore),
+            Some(other) => return Err(JsValue::from_str(&format!("Unknown normalization: {}", other))),
+        };
+        Ok(())
+    }
+
+    /// Scale targets with a normalizer fit by the next training run
+    /// Pass "minmax" or "zscore" for regression targets outside the output
+    /// range; evaluate maps outputs back to the original scale
+    #[wasm_bindgen(js_name = setTargetNormalization)]
+    pub fn set_target_normalization(&mut self, kind: Option<String>) -> Result<(), JsValue> {
+        self.target_normalization = match kind.as_deref() {
+            None => None,
+            Some("minmax") => Some(Normalization::MinMax),
+            Some("zscore") => Some(Normalization::ZScore),
+            Some(other) => return Err(JsValue::from_str(&format!("Unknown normalization: {}", other))),
+        };
+        Ok(())
+    }
+
     /// Train the network on a built-in example
     /// Accepts an optional JavaScript callback for progress updates
     pub fn train(&mut self, example_name: &str, epochs: u32, progress_callback: Option<js_sys::Function>) -> Result<(), JsValue> {
@@ -98,16 +151,19 @@ impl NeuralNetwork {
             checkpoint_path: None,
             verbose: false,
             example_name: Some(example_name.to_string()),
+            normalization: self.normalization,
+            target_normalization: self.target_normalization,
+            ..Default::default()
         };
 
         let mut controller = TrainingController::new(self.network.clone(), config);
 
         // Add callback to call JavaScript progress function
         if let Some(callback) = progress_callback {
-            controller.add_callback(Box::new(move |epoch, loss, _network| {
+            controller.add_callback(from_fn(move |record, _network| {
                 let this = JsValue::null();
-                let epoch_js = JsValue::from_f64(epoch as f64);
-                let loss_js = JsValue::from_f64(loss);
+                let epoch_js = JsValue::from_f64(record.epoch as f64);
+                let loss_js = JsValue::from_f64(record.loss);
 
                 // Call the JavaScript callback with (epoch, loss)
                 let _ = callback.call2(&this, &epoch_js, &loss_js);
@@ -120,11 +176,77 @@ impl NeuralNetwork {
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
         // Update internal network
+        self.normalizer = controller.normalizer().cloned();
+        self.target_normalizer = controller.target_normalizer().cloned();
         self.network = controller.into_network();
 
         Ok(())
     }
 
+    /// Begin incremental training on a built-in example
+    /// Call trainEpochs repeatedly (e.g. once per animation frame) to advance it
+    #[wasm_bindgen(js_name = startTraining)]
+    pub fn start_training(&mut self, example_name: &str, epochs: u32) -> Result<(), JsValue> {
+        let example = examples::get_example(example_name)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown example: {}", example_name)))?;
+
+        self.example_name = Some(example_name.to_string());
+
+        let config = TrainingConfig {
+            epochs,
+            example_name: Some(example_name.to_string()),
+            normalization: self.normalization,
+            target_normalization: self.target_normalization,
+            ..Default::default()
+        };
+        let mut controller = TrainingController::new(self.network.clone(), config);
+        controller
+            .start(example.inputs.clone(), example.targets.clone())
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        self.normalizer = controller.normalizer().cloned();
+        self.target_normalizer = controller.target_normalizer().cloned();
+        self.session = Some(controller);
+        self.last_loss = None;
+        Ok(())
+    }
+
+    /// Train up to n more epochs of the run begun with startTraining
+    /// Returns the number of epochs trained; 0 once the run is complete
+    #[wasm_bindgen(js_name = trainEpochs)]
+    pub fn train_epochs(&mut self, n: u32) -> Result<u32, JsValue> {
+        // A failed run is dropped; a finished one is wrapped up
+        let Some(mut controller) = self.session.take() else {
+            return Ok(0);
+        };
+
+        let trained = controller
+            .train_epochs(n)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.last_loss = controller.history().last().map(|r| r.loss).or(self.last_loss);
+
+        if controller.is_finished() {
+            controller.finish().map_err(|e| JsValue::from_str(&e.to_string()))?;
+            self.network = controller.into_network();
+        } else {
+            self.network = controller.network().clone();
+            self.session = Some(controller);
+        }
+        Ok(trained)
+    }
+
+    /// Whether an incremental training run is in progress
+    #[wasm_bindgen(js_name = isTraining)]
+    pub fn is_training(&self) -> bool {
+        self.session.is_some()
+    }
+
+    /// Loss of the last epoch trained by trainEpochs
+    #[wasm_bindgen(js_name = lastLoss)]
+    pub fn last_loss(&self) -> Option<f64> {
+        self.last_loss
+    }
+
     /// Train with custom inputs and targets
     #[wasm_bindgen(js_name = trainCustom)]
     pub fn train_custom(
@@ -156,6 +278,9 @@ impl NeuralNetwork {
             checkpoint_path: None,
             verbose: false,
             example_name: None,
+            normalization: self.normalization,
+            target_normalization: self.target_normalization,
+            ..Default::default()
         };
 
         let mut controller = TrainingController::new(self.network.clone(), config);
@@ -164,26 +289,31 @@ impl NeuralNetwork {
             .train(inputs, targets)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
+        self.normalizer = controller.normalizer().cloned();
+        self.target_normalizer = controller.target_normalizer().cloned();
         self.network = controller.into_network();
 
         Ok(())
     }
 
     /// Evaluate the network on a single input
+    /// Inputs are scaled like the training data first and outputs mapped
+    /// back to the scale of the training targets
     pub fn evaluate(&mut self, input: Vec<f64>) -> Result<Vec<f64>, JsValue> {
-        // Validate input dimensions
-        if input.len() != self.network.layers[0] {
-            return Err(JsValue::from_str(&format!(
-                "Invalid input dimensions: expected {}, got {}",
-                self.network.layers[0],
-                input.len()
-            )));
-        }
-
+        let input = match &self.normalizer {
+            Some(normalizer) => normalizer.transform(&input),
+            None => input,
+        };
         let input_matrix = neural_network::matrix::Matrix::from(input);
-        let output = self.network.feed_forward(input_matrix);
-
-        Ok(output.data)
+        let output = self
+            .network
+            .try_feed_forward(input_matrix)
+            .map_err(|e| JsValue::from_str(&format!("Invalid input dimensions: {}", e)))?;
+
+        Ok(match &self.target_normalizer {
+            Some(normalizer) => normalizer.inverse_transform(&output.data),
+            None => output.data,
+        })
     }
 
     /// Get the network architecture
@@ -229,6 +359,18 @@ impl NeuralNetwork {
             .map_err(|e| JsValue::from_str(&e.to_string()))
     }
 
+    /// Get per-layer histograms of weights and biases
+    /// Returns [{layer, weights: {min, max, counts}, biases: {...}}, ...]
+    #[wasm_bindgen(js_name = getWeightHistograms)]
+    pub fn get_weight_histograms(&self, bins: usize) -> Result<JsValue, JsValue> {
+        if bins == 0 {
+            return Err(JsValue::from_str("Bin count must be at least 1"));
+        }
+        let histograms = self.network.weight_histograms(bins);
+        serde_wasm_bindgen::to_value(&histograms)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
     /// Serialize the network to JSON string
     #[wasm_bindgen(js_name = toJSON)]
     pub fn to_json(&self) -> Result<String, JsValue> {
@@ -245,6 +387,33 @@ impl NeuralNetwork {
         Ok(NeuralNetwork {
             network,
             example_name: None,
+            session: None,
+            last_loss: None,
+            normalization: None,
+            normalizer: None,
+            target_normalization: None,
+            target_normalizer: None,
+        })
+    }
+
+    /// Load a trained model from the bytes of a checkpoint file
+    /// Accepts JSON or binary checkpoints, optionally compressed; only the
+    /// network and normalizers are decoded, so inference-only checkpoints
+    /// work as well as full ones
+    #[wasm_bindgen(js_name = fromCheckpoint)]
+    pub fn from_checkpoint(bytes: Vec<u8>) -> Result<NeuralNetwork, JsValue> {
+        let model = InferenceModel::from_bytes(bytes)
+            .map_err(|e| JsValue::from_str(&format!("{:#}", e)))?;
+
+        Ok(NeuralNetwork {
+            network: model.network,
+            example_name: Some(model.metadata.example),
+            session: None,
+            last_loss: None,
+            normalization: None,
+            normalizer: model.normalizer,
+            target_normalization: None,
+            target_normalizer: model.target_normalizer,
         })
     }
 }
@@ -268,6 +437,31 @@ pub fn list_examples() -> Result<JsValue, JsValue> {
         .map_err(|e| JsValue::from_str(&e.to_string()))
 }
 
+/// Register custom training data under a name usable with fromExample and train
+/// Inputs and targets are flat arrays of inputSize and targetSize values per sample
+#[wasm_bindgen(js_name = registerExample)]
+pub fn register_example(
+    name: &str,
+    description: &str,
+    inputs_flat: Vec<f64>,
+    targets_flat: Vec<f64>,
+    input_size: usize,
+    target_size: usize,
+) -> Result<(), JsValue> {
+    if input_size == 0
+        || target_size == 0
+        || !inputs_flat.len().is_multiple_of(input_size)
+        || !targets_flat.len().is_multiple_of(target_size)
+    {
+        return Err(JsValue::from_str("Inputs and targets must be whole samples of a non-zero size"));
+    }
+    let inputs = inputs_flat.chunks(input_size).map(<[f64]>::to_vec).collect();
+    let targets = targets_flat.chunks(target_size).map(<[f64]>::to_vec).collect();
+    examples::Example::custom(name, description, inputs, targets)
+        .and_then(examples::register_example)
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
 /// Get details about a specific example
 #[wasm_bindgen(js_name = getExampleInfo)]
 pub fn get_example_info(name: &str) -> Result<JsValue, JsValue> {
@@ -314,6 +508,13 @@ mod tests {
         assert_eq!(net.get_architecture(), vec![2, 3, 1]);
     }
 
+    #[test]
+    fn test_register_example_for_from_example() {
+        register_example("wasm-not", "NOT gate", vec![0.0, 1.0], vec![1.0, 0.0], 1, 1).unwrap();
+        let net = NeuralNetwork::from_example("wasm-not", 0.5, Some(1)).unwrap();
+        assert_eq!(net.get_architecture(), vec![1, 2, 1]);
+    }
+
     #[test]
     fn test_create_network_seeded() {
         // Test that seeded networks are reproducible
@@ -352,6 +553,48 @@ mod tests {
         assert_eq!(network.get_parameter_count(), 13);
     }
 
+    #[test]
+    fn test_incremental_training() {
+        let mut network = NeuralNetwork::from_example("and", 0.5, Some(3)).unwrap();
+        network.start_training("and", 25).unwrap();
+
+        let mut steps = Vec::new();
+        while network.is_training() {
+            steps.push(network.train_epochs(10).unwrap());
+        }
+
+        assert_eq!(steps, vec![10, 10, 5]);
+        assert!(network.last_loss().is_some());
+        assert_eq!(network.train_epochs(10).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_target_normalization_maps_outputs_back() {
+        let mut network = NeuralNetwork::new(vec![1, 4, 1], 0.5, Some(2)).unwrap();
+        network.set_target_normalization(Some("minmax".to_string())).unwrap();
+        network
+            .train_custom(vec![0.0, 0.5, 1.0], vec![100.0, 150.0, 200.0], 1, 1, 10)
+            .unwrap();
+
+        let output = network.evaluate(vec![0.5]).unwrap();
+        assert!(output[0] > 100.0 && output[0] < 200.0, "{:?}", output);
+    }
+
+    #[test]
+    fn test_from_checkpoint() {
+        let mut network = NeuralNetwork::new(vec![2, 3, 1], 0.5, Some(5)).unwrap();
+        let metadata = neural_network::checkpoint::CheckpointMetadata {
+            version: "1.0".to_string(),
+            example: "xor".to_string(),
+            ..Default::default()
+        };
+        let json = serde_json::to_vec(&network.network.to_checkpoint(metadata).stripped()).unwrap();
+
+        let mut restored = NeuralNetwork::from_checkpoint(json).unwrap();
+        assert_eq!(restored.example_name, Some("xor".to_string()));
+        assert_eq!(restored.evaluate(vec![1.0, 0.0]).unwrap(), network.evaluate(vec![1.0, 0.0]).unwrap());
+    }
+
     #[test]
     fn test_serialization() {
         let network = NeuralNetwork::new(vec![2, 3, 1], 0.5, None).unwrap();
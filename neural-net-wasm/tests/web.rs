@@ -0,0 +1,26 @@
+// Tests that run inside a JavaScript engine (see scripts/check-wasm.sh)
+//
+// Training goes through TrainingController, which times epochs and calls; a
+// clock that panics on wasm32-unknown-unknown only shows up here, not in
+// the native unit tests.
+#![cfg(target_arch = "wasm32")]
+
+use neural_net_wasm::NeuralNetwork;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn test_train_runs_in_wasm() {
+    let mut network = NeuralNetwork::from_example("xor", 0.5, Some(42)).unwrap();
+    network.train("xor", 20, None).unwrap();
+    assert_eq!(network.evaluate(vec![1.0, 0.0]).unwrap().len(), 1);
+}
+
+#[wasm_bindgen_test]
+fn test_incremental_training_runs_in_wasm() {
+    let mut network = NeuralNetwork::from_example("and", 0.5, Some(3)).unwrap();
+    network.start_training("and", 15).unwrap();
+    while network.is_training() {
+        network.train_epochs(10).unwrap();
+    }
+    assert!(network.last_loss().is_some());
+}
@@ -0,0 +1,28 @@
+// Inference speed and model size: f64 network vs int8 quantized network
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use neural_network::activations::SIGMOID;
+use neural_network::matrix::Matrix;
+use neural_network::network::Network;
+use neural_network::quantize::QuantizedNetwork;
+
+fn bench_inference(c: &mut Criterion) {
+    let mut network = Network::new_seeded(vec![64, 128, 128, 10], SIGMOID, 0.1, 42);
+    let quantized = QuantizedNetwork::from_network(&network);
+    let input: Vec<f64> = (0..64).map(|i| (i as f64 / 64.0) - 0.5).collect();
+
+    println!(
+        "parameter size: f64 {} bytes, int8 {} bytes",
+        QuantizedNetwork::float_size_bytes(&network),
+        quantized.size_bytes()
+    );
+
+    let mut group = c.benchmark_group("inference");
+    group.bench_function("f64", |b| {
+        b.iter(|| network.feed_forward(black_box(Matrix::from(input.clone()))))
+    });
+    group.bench_function("int8", |b| b.iter(|| quantized.predict(black_box(&input))));
+    group.finish();
+}
+
+criterion_group!(benches, bench_inference);
+criterion_main!(benches);
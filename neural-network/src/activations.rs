@@ -1,27 +1,81 @@
 use std::f64::consts::E;
 use serde::{Serialize, Deserialize, Serializer, Deserializer};
 
+/// An activation function and its derivative
+///
+/// The derivative is given both the function's input and its output, so
+/// each activation can use whichever is cheaper; back-propagation keeps
+/// both for every layer.
 #[derive(Clone, Copy, Debug)]
 pub struct Activation {
+    /// Name used in checkpoints and on the command line
+    pub name: &'static str,
     pub function: fn(&f64) -> f64,
-    pub derivative: fn(&f64) -> f64,
+    /// Derivative at input `x`, given `x` and the output `function(x)`
+    pub derivative: fn(&f64, &f64) -> f64,
 }
 
+/// Logistic sigmoid, with outputs in (0, 1)
 pub const SIGMOID: Activation = Activation {
+    name: "sigmoid",
     function: |x| 1.0 / (1.0 + E.powf(-x)),
-    derivative: |x| x * (1.0 - x),
+    derivative: |_, y| y * (1.0 - y),
 };
 
-// Custom serialization for Activation
-// We serialize it as a string identifier since function pointers can't be serialized
+/// Rectified linear unit, `max(0, x)`
+pub const RELU: Activation = Activation {
+    name: "relu",
+    function: |x| x.max(0.0),
+    derivative: |_, y| if *y > 0.0 { 1.0 } else { 0.0 },
+};
+
+/// Hyperbolic tangent, with outputs in (-1, 1)
+pub const TANH: Activation = Activation {
+    name: "tanh",
+    function: |x| x.tanh(),
+    derivative: |_, y| 1.0 - y * y,
+};
+
+/// Gaussian error linear unit, in the tanh approximation
+///
+/// GELU is not invertible below its minimum at x = -0.75, so its derivative
+/// is taken from the input.
+pub const GELU: Activation = Activation {
+    name: "gelu",
+    function: gelu,
+    derivative: |x, _| gelu_derivative(*x),
+};
+
+/// Every activation, in the order they are listed to users
+pub const ACTIVATIONS: [Activation; 4] = [SIGMOID, RELU, TANH, GELU];
+
+impl Activation {
+    /// The activation called `name`, if there is one
+    pub fn from_name(name: &str) -> Option<Activation> {
+        ACTIVATIONS.into_iter().find(|activation| activation.name == name)
+    }
+}
+
+/// sqrt(2 / pi), the scale inside GELU's tanh approximation
+const GELU_SCALE: f64 = 0.797_884_560_802_865_4;
+
+fn gelu(x: &f64) -> f64 {
+    0.5 * x * (1.0 + (GELU_SCALE * (x + 0.044715 * x.powi(3))).tanh())
+}
+
+/// Derivative of GELU with respect to its input `x`
+fn gelu_derivative(x: f64) -> f64 {
+    let t = (GELU_SCALE * (x + 0.044715 * x.powi(3))).tanh();
+    0.5 * (1.0 + t) + 0.5 * x * (1.0 - t * t) * GELU_SCALE * (1.0 + 3.0 * 0.044715 * x * x)
+}
+
+// Activations are serialized by name, since function pointers can't be serialized
 impl Serialize for Activation {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        // For now, we only have SIGMOID
-        // In the future, we could compare function pointers or use a registry
-        serializer.serialize_str("sigmoid")
+        serializer.serialize_str(self.name)
     }
 }
 
@@ -31,12 +85,46 @@ impl<'de> Deserialize<'de> for Activation {
         D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        match s.as_str() {
-            "sigmoid" => Ok(SIGMOID),
-            _ => Err(serde::de::Error::custom(format!(
+        Activation::from_name(&s).ok_or_else(|| {
+            serde::de::Error::custom(format!(
                 "Unknown activation function: {}",
                 s
-            ))),
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derivatives() {
+        for activation in ACTIVATIONS {
+            for x in [-3.0, -2.0, -0.5, -0.1, 0.3, 1.0, 2.5] {
+                let h = 1e-6;
+                let numeric = ((activation.function)(&(x + h)) - (activation.function)(&(x - h))) / (2.0 * h);
+                let y = (activation.function)(&x);
+                let analytic = (activation.derivative)(&x, &y);
+                assert!(
+                    (numeric - analytic).abs() < 1e-5,
+                    "{} at {}: {} vs {}",
+                    activation.name,
+                    x,
+                    numeric,
+                    analytic
+                );
+            }
+            assert_eq!(Activation::from_name(activation.name).unwrap().name, activation.name);
         }
+        assert!(Activation::from_name("swish").is_none());
+    }
+
+    #[test]
+    fn test_gelu_slope_below_its_minimum() {
+        // GELU falls towards its minimum at -0.75, so the slope is negative
+        let x = -2.0;
+        let slope = (GELU.derivative)(&x, &gelu(&x));
+        assert!((slope + 0.0861).abs() < 1e-3, "{}", slope);
     }
 }
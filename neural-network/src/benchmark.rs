@@ -0,0 +1,181 @@
+/// Throughput benchmark of the forward and backward passes
+///
+/// Times a seeded random network on random samples in four phases, each run
+/// repeatedly for at least `min_time`:
+///
+/// - `feed-forward`: `Network::feed_forward`, which allocates its matrices
+/// - `feed-forward (workspace)`: `Network::feed_forward_with` on a preallocated `Workspace`
+/// - `backprop (per sample)`: `Network::train_sample_with`, a forward pass,
+///   backward pass and update for every sample
+/// - `backprop (batch of N)`: `Network::compute_batch_gradients_with` and one
+///   update per mini-batch of `batch` samples
+///
+/// Each phase reports samples per second and, given a counter of heap
+/// allocations (usually from a counting `#[global_allocator]` in the binary),
+/// the allocations per sample. Workspaces are warmed up before timing, so the
+/// workspace phases should show no allocations.
+///
+/// ```
+/// use neural_network::{activations::SIGMOID, benchmark::Benchmark};
+/// use std::time::Duration;
+///
+/// let bench = Benchmark {
+///     layers: vec![4, 8, 2],
+///     min_time: Duration::from_millis(10),
+///     ..Benchmark::new(SIGMOID)
+/// };
+/// for phase in bench.run(None).phases {
+///     println!("{}: {:.0} samples/s", phase.name, phase.samples_per_second());
+/// }
+/// ```
+use crate::activations::Activation;
+use crate::matrix::Matrix;
+use crate::network::Network;
+use crate::workspace::BatchWorkspace;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Settings of a benchmark run
+#[derive(Debug, Clone)]
+pub struct Benchmark {
+    /// All layer sizes from input to output
+    pub layers: Vec<usize>,
+
+    /// Samples per mini-batch in the batch phase
+    pub batch: usize,
+
+    /// Activation of the benchmarked network
+    pub activation: Activation,
+
+    /// Shortest time each phase is run for
+    pub min_time: Duration,
+
+    /// Seed of the network and samples
+    pub seed: u64,
+}
+
+/// Timing of one benchmark phase
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Phase {
+    /// What was measured, e.g. `feed-forward`
+    pub name: String,
+
+    /// Samples processed while timing
+    pub samples: u64,
+
+    /// Wall-clock time of those samples
+    pub seconds: f64,
+
+    /// Heap allocations while timing, if a counter was given
+    pub allocations: Option<u64>,
+}
+
+impl Phase {
+    /// Throughput of the phase
+    pub fn samples_per_second(&self) -> f64 {
+        self.samples as f64 / self.seconds
+    }
+
+    /// Average heap allocations per sample, if they were counted
+    pub fn allocations_per_sample(&self) -> Option<f64> {
+        self.allocations.map(|count| count as f64 / self.samples as f64)
+    }
+}
+
+/// Results of a benchmark run, in phase order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    /// One entry per phase
+    pub phases: Vec<Phase>,
+}
+
+impl Benchmark {
+    /// An MNIST-sized `[784, 128, 10]` network with batches of 32, one second per phase
+    pub fn new(activation: Activation) -> Self {
+        Self {
+            layers: vec![784, 128, 10],
+            batch: 32,
+            activation,
+            min_time: Duration::from_secs(1),
+            seed: 42,
+        }
+    }
+
+    /// Time every phase, reading `allocation_count` before and after each
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are fewer than two layers, a layer or the batch size is
+    /// zero
+    pub fn run(&self, allocation_count: Option<fn() -> u64>) -> BenchmarkReport {
+        assert!(
+            self.layers.len() >= 2 && !self.layers.contains(&0),
+            "A benchmark needs at least an input and an output layer, all above zero"
+        );
+        assert!(self.batch > 0, "The batch size must be at least 1");
+
+        let mut network = Network::new_seeded(self.layers.clone(), self.activation, 0.01, self.seed);
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let (input_size, output_size) = (self.layers[0], self.layers[self.layers.len() - 1]);
+        let inputs: Vec<Vec<f64>> = (0..self.batch)
+            .map(|_| (0..input_size).map(|_| rng.gen_range(0.0..1.0)).collect())
+            .collect();
+        let targets: Vec<Vec<f64>> = (0..self.batch)
+            .map(|_| (0..output_size).map(|_| rng.gen_range(0.0..1.0)).collect())
+            .collect();
+        let samples = inputs.len() as u64;
+
+        let mut phases = Vec::new();
+        let mut next = 0;
+        phases.push(self.time("feed-forward", 1, allocation_count, || {
+            network.feed_forward(Matrix::from(inputs[next].clone()));
+            next = (next + 1) % inputs.len();
+        }));
+
+        let mut workspace = network.workspace();
+        network.feed_forward_with(&inputs[0], &mut workspace);
+        phases.push(self.time("feed-forward (workspace)", 1, allocation_count, || {
+            network.feed_forward_with(&inputs[next], &mut workspace);
+            next = (next + 1) % inputs.len();
+        }));
+
+        network.train_sample_with(&inputs[0], &targets[0], &mut workspace);
+        phases.push(self.time("backprop (per sample)", 1, allocation_count, || {
+            network.train_sample_with(&inputs[next], &targets[next], &mut workspace);
+            next = (next + 1) % inputs.len();
+        }));
+
+        let batch: Vec<usize> = (0..inputs.len()).collect();
+        let mut batch_workspace = BatchWorkspace::new(&self.layers);
+        network.compute_batch_gradients_with(&inputs, &targets, &batch, None, &mut batch_workspace);
+        let name = format!("backprop (batch of {})", self.batch);
+        phases.push(self.time(&name, samples, allocation_count, || {
+            let gradients = network.compute_batch_gradients_with(&inputs, &targets, &batch, None, &mut batch_workspace);
+            network.apply_gradients(gradients);
+        }));
+
+        BenchmarkReport { phases }
+    }
+
+    /// Run `step`, which processes `samples` samples, until `min_time` has passed
+    fn time(&self, name: &str, samples: u64, allocation_count: Option<fn() -> u64>, mut step: impl FnMut()) -> Phase {
+        let allocations_before = allocation_count.map(|count| count());
+        let start = Instant::now();
+        let mut steps = 0u64;
+        while steps == 0 || start.elapsed() < self.min_time {
+            step();
+            steps += 1;
+        }
+        let seconds = start.elapsed().as_secs_f64();
+        let allocations = allocation_count.zip(allocations_before).map(|(count, before)| count() - before);
+
+        Phase {
+            name: name.to_string(),
+            samples: steps * samples,
+            seconds,
+            allocations,
+        }
+    }
+}
@@ -0,0 +1,105 @@
+/// Decision boundaries of two-input models
+///
+/// `DecisionGrid::sample` feeds a regular grid of points covering a 2D input
+/// range through a model and keeps the predicted class and its confidence at
+/// every point, so the `visualize` tool can color the regions where the model
+/// picks each class, e.g. for the `xor`, `moons` and `spirals` examples.
+use crate::inference::InferenceModel;
+use crate::training::class_of;
+use anyhow::Result;
+
+/// Predicted class and confidence on a square grid of inputs
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecisionGrid {
+    /// Range of the first input, left to right
+    pub x_range: (f64, f64),
+
+    /// Range of the second input, bottom to top
+    pub y_range: (f64, f64),
+
+    /// Number of points along each axis
+    pub resolution: usize,
+
+    /// Number of classes the model distinguishes (2 for a single output)
+    pub num_classes: usize,
+
+    /// Predicted class of each point, row by row from the top row
+    pub classes: Vec<usize>,
+
+    /// Confidence in `[0, 1]` of each prediction: the distance of a single
+    /// output from 0.5, doubled, or the largest of several outputs
+    pub confidence: Vec<f64>,
+}
+
+impl DecisionGrid {
+    /// Predict every point of a `resolution` x `resolution` grid spanning
+    /// `x_range` and `y_range`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the model does not take exactly two inputs or the
+    /// resolution is below 2
+    pub fn sample(model: &mut InferenceModel, x_range: (f64, f64), y_range: (f64, f64), resolution: usize) -> Result<Self> {
+        let inputs = model.network.layers[0];
+        if inputs != 2 {
+            anyhow::bail!("Decision boundaries need a network with 2 inputs, this one has {}", inputs);
+        }
+        if resolution < 2 {
+            anyhow::bail!("The grid resolution must be at least 2, got {}", resolution);
+        }
+        let outputs = *model.network.layers.last().unwrap_or(&1);
+        let step = |(low, high): (f64, f64), i: usize| low + (high - low) * i as f64 / (resolution - 1) as f64;
+
+        let mut classes = Vec::with_capacity(resolution * resolution);
+        let mut confidence = Vec::with_capacity(resolution * resolution);
+        for row in (0..resolution).rev() {
+            let y = step(y_range, row);
+            for column in 0..resolution {
+                let output = model.predict(&[step(x_range, column), y])?;
+                classes.push(class_of(&output));
+                confidence.push(match output[..] {
+                    [p] => ((p - 0.5).abs() * 2.0).min(1.0),
+                    _ => output.iter().copied().fold(0.0, f64::max).min(1.0),
+                });
+            }
+        }
+        Ok(DecisionGrid {
+            x_range,
+            y_range,
+            resolution,
+            num_classes: outputs.max(2),
+            classes,
+            confidence,
+        })
+    }
+
+    /// Index into `classes` and `confidence` of the point in `row` (from the
+    /// top) and `column` (from the left)
+    pub fn index(&self, row: usize, column: usize) -> usize {
+        row * self.resolution + column
+    }
+}
+
+/// Ranges of the two inputs of `inputs`, widened by `margin` times their
+/// span on each side
+///
+/// Inputs that never vary get a range of one around their value.
+///
+/// # Errors
+///
+/// Returns an error if `inputs` is empty or its rows do not have two values
+pub fn input_ranges(inputs: &[Vec<f64>], margin: f64) -> Result<((f64, f64), (f64, f64))> {
+    if inputs.is_empty() {
+        anyhow::bail!("No inputs to take the plot ranges from");
+    }
+    if let Some(row) = inputs.iter().find(|row| row.len() != 2) {
+        anyhow::bail!("Decision boundaries need 2 inputs per sample, got {}", row.len());
+    }
+    let range = |i: usize| {
+        let low = inputs.iter().map(|row| row[i]).fold(f64::INFINITY, f64::min);
+        let high = inputs.iter().map(|row| row[i]).fold(f64::NEG_INFINITY, f64::max);
+        let pad = if high > low { (high - low) * margin } else { 0.5 };
+        (low - pad, high + pad)
+    };
+    Ok((range(0), range(1)))
+}
@@ -0,0 +1,450 @@
+/// Hooks into the training loop of `TrainingController`
+///
+/// Everything that reacts to training progress (checkpointing, early
+/// stopping, file loggers, progress bars, server-sent events) is a
+/// `TrainingCallback`. Callbacks are registered with
+/// `TrainingController::add_callback` and run in registration order; the
+/// checkpointing, early stopping and target loss configured through
+/// `TrainingConfig` are appended as the built-in `Checkpointer`,
+/// `EarlyStopping` and `TargetLoss` callbacks.
+///
+/// For one-off reactions to finished epochs, wrap a closure with `from_fn`
+/// (`from_batch_fn` does the same for progress within long epochs):
+///
+/// ```
+/// use neural_network::callbacks::from_fn;
+/// # use neural_network::{activations::SIGMOID, network::Network, training::*};
+/// # let mut controller = TrainingController::new(
+/// #     Network::new(vec![2, 2, 1], SIGMOID, 0.5),
+/// #     TrainingConfig { epochs: 3, ..Default::default() },
+/// # );
+/// controller.add_callback(from_fn(|record, _network| {
+///     println!("epoch {}: loss {:.4}", record.epoch, record.loss);
+/// }));
+/// # controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+/// ```
+use crate::checkpoint::{CheckpointMetadata, HyperparameterOverride, RunLineage, TrainingState};
+use crate::encryption::Secret;
+use crate::history::{EpochRecord, TrainingHistory};
+use crate::network::Network;
+use crate::normalize::Normalizer;
+use crate::training::TrainingConfig;
+use std::path::PathBuf;
+use std::time::Duration;
+use web_time::Instant;
+
+/// What the training loop should do after an epoch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallbackAction {
+    /// Keep training
+    Continue,
+    /// Stop after the current epoch; `on_train_end` still runs
+    Stop,
+}
+
+/// State passed to `TrainingCallback::on_epoch_end`
+pub struct EpochContext<'a> {
+    /// Statistics of the epoch that just finished
+    pub record: &'a EpochRecord,
+
+    /// Every epoch recorded so far, including `record`
+    pub history: &'a TrainingHistory,
+
+    /// Epochs of the runs before the checkpoint this run resumed from, empty
+    /// for a fresh run; checkpoints save `previous_history.continued_by(history)`
+    pub previous_history: &'a TrainingHistory,
+
+    /// Epochs trained before this run resumed, added to `record.epoch` and
+    /// `total_epochs` in checkpoint metadata
+    pub previous_epochs: u32,
+
+    /// Resumes that led to this run, as saved in checkpoints
+    pub lineage: &'a [RunLineage],
+
+    /// The network after the epoch's updates
+    pub network: &'a Network,
+
+    /// Number of epochs the current `train` call will run unless stopped early
+    pub total_epochs: u32,
+
+    /// Optimizer and shuffle state after the epoch, as saved in checkpoints
+    pub training_state: &'a TrainingState,
+
+    /// Input transform the network is trained with, as saved in checkpoints
+    pub normalizer: Option<&'a Normalizer>,
+
+    /// Target transform the network is trained with, as saved in checkpoints
+    pub target_normalizer: Option<&'a Normalizer>,
+
+    /// When the first run of this model began, as saved in checkpoint metadata
+    pub created_at: Option<&'a str>,
+
+    /// Fingerprint of the data this run trains on, as saved in checkpoint metadata
+    pub dataset_fingerprint: Option<&'a str>,
+
+    /// Hyperparameters changed when resuming, as saved in checkpoint metadata
+    pub overrides: &'a [HyperparameterOverride],
+}
+
+/// State passed to `TrainingCallback::on_batch_end`
+pub struct BatchContext<'a> {
+    /// Epoch being trained, counting from 1
+    pub epoch: u32,
+
+    /// Index of the update within the epoch, counting from 0
+    pub batch: usize,
+
+    /// Number of updates the epoch makes
+    pub batches: usize,
+
+    /// Samples processed so far in this epoch, including this batch
+    pub samples: usize,
+
+    /// Mean loss of the samples processed so far in this epoch
+    ///
+    /// Each sample's (weighted) squared error is measured in the forward pass
+    /// before its update, so this lags the end-of-epoch loss a little.
+    pub running_loss: f64,
+
+    /// The network after the update
+    pub network: &'a Network,
+}
+
+impl EpochContext<'_> {
+    /// Estimated seconds until `total_epochs` are done (see `EpochRecord::eta_secs`)
+    pub fn eta_secs(&self) -> f64 {
+        self.record.eta_secs(self.total_epochs)
+    }
+}
+
+/// Training loop hooks; every method has a no-op default
+pub trait TrainingCallback {
+    /// Called once before the first epoch
+    ///
+    /// # Errors
+    ///
+    /// An error aborts training before any update is made
+    fn on_train_start(&mut self, _config: &TrainingConfig, _network: &Network) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Called after every weight update
+    ///
+    /// An epoch makes one update per sample, or per `batch_size` samples when
+    /// that is set. This runs in the inner loop; keep it cheap.
+    fn on_batch_end(&mut self, _ctx: &BatchContext) {}
+
+    /// Called after every epoch once its record has been added to the history
+    ///
+    /// All callbacks run for the epoch even if one of them asks to stop.
+    ///
+    /// # Errors
+    ///
+    /// An error aborts training and is returned from `train`
+    fn on_epoch_end(&mut self, _ctx: &EpochContext) -> anyhow::Result<CallbackAction> {
+        Ok(CallbackAction::Continue)
+    }
+
+    /// Called once after the last epoch, including after an early stop
+    ///
+    /// Not called when training fails with an error.
+    fn on_train_end(&mut self, _history: &TrainingHistory, _network: &Network) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Closure adapter created by `from_fn`
+pub struct FnCallback<F>(F);
+
+/// Run `f` with the record of every finished epoch
+pub fn from_fn<F>(f: F) -> FnCallback<F>
+where
+    F: FnMut(&EpochRecord, &Network),
+{
+    FnCallback(f)
+}
+
+impl<F> TrainingCallback for FnCallback<F>
+where
+    F: FnMut(&EpochRecord, &Network),
+{
+    fn on_epoch_end(&mut self, ctx: &EpochContext) -> anyhow::Result<CallbackAction> {
+        (self.0)(ctx.record, ctx.network);
+        Ok(CallbackAction::Continue)
+    }
+}
+
+/// Closure adapter created by `from_batch_fn`
+pub struct FnBatchCallback<F> {
+    f: F,
+    interval: Duration,
+    last: Instant,
+}
+
+/// Run `f` with the progress of the current epoch at most once per `interval`
+///
+/// The first call of an epoch comes once it has run for `interval`, so
+/// epochs shorter than that report nothing; this keeps progress displays
+/// quiet for small datasets and busy for large ones.
+///
+/// ```
+/// use neural_network::callbacks::from_batch_fn;
+/// use std::time::Duration;
+/// # use neural_network::{activations::SIGMOID, network::Network, training::*};
+/// # let mut controller = TrainingController::new(
+/// #     Network::new(vec![2, 2, 1], SIGMOID, 0.5),
+/// #     TrainingConfig { epochs: 3, ..Default::default() },
+/// # );
+/// controller.add_callback(from_batch_fn(Duration::from_secs(1), |ctx| {
+///     println!("epoch {}: batch {} of {}, loss {:.4}", ctx.epoch, ctx.batch + 1, ctx.batches, ctx.running_loss);
+/// }));
+/// # controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+/// ```
+pub fn from_batch_fn<F>(interval: Duration, f: F) -> FnBatchCallback<F>
+where
+    F: FnMut(&BatchContext),
+{
+    FnBatchCallback {
+        f,
+        interval,
+        last: Instant::now(),
+    }
+}
+
+impl<F> TrainingCallback for FnBatchCallback<F>
+where
+    F: FnMut(&BatchContext),
+{
+    fn on_batch_end(&mut self, ctx: &BatchContext) {
+        // Batch 0 ends shortly after the epoch starts
+        if ctx.batch == 0 {
+            self.last = Instant::now();
+        }
+        if self.last.elapsed() >= self.interval {
+            (self.f)(ctx);
+            self.last = Instant::now();
+        }
+    }
+}
+
+/// Saves a checkpoint (with the history so far) to one path
+///
+/// A checkpoint is written at the end of an epoch when either trigger fires:
+/// every `every_epochs` epochs, or once the `every` duration has elapsed since
+/// the last save (or the start of training). The time trigger suits long runs
+/// whose epochs vary in length; it is checked only at epoch boundaries.
+/// `numbered` keeps every checkpoint in its own file instead of overwriting
+/// one, leaving a series of snapshots of the run.
+pub struct Checkpointer {
+    path: PathBuf,
+    example: String,
+    numbered: bool,
+    epoch_interval: Option<u32>,
+    time_interval: Option<Duration>,
+    last_save: Instant,
+    /// Seed, optimizer and dataset path of the current run, recorded in the metadata
+    seed: Option<u64>,
+    optimizer: Option<String>,
+    dataset_path: Option<String>,
+    /// `TrainingConfig::encryption` of the current run
+    secret: Option<Secret>,
+}
+
+impl Checkpointer {
+    /// Checkpoint to `path`, recording `example` in the metadata
+    ///
+    /// Without a trigger configured no checkpoint is written.
+    pub fn new(path: impl Into<PathBuf>, example: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            example: example.into(),
+            numbered: false,
+            epoch_interval: None,
+            time_interval: None,
+            last_save: Instant::now(),
+            seed: None,
+            optimizer: None,
+            dataset_path: None,
+            secret: None,
+        }
+    }
+
+    /// Save every `interval` epochs
+    pub fn every_epochs(mut self, interval: u32) -> Self {
+        self.epoch_interval = Some(interval);
+        self
+    }
+
+    /// Save whenever `interval` has passed since the last save
+    pub fn every(mut self, interval: Duration) -> Self {
+        self.time_interval = Some(interval);
+        self
+    }
+
+    /// Save each checkpoint to its own file, named after the path with the
+    /// epoch appended to the file stem (`model.json` becomes
+    /// `model_epoch000100.json` at epoch 100)
+    pub fn numbered(mut self) -> Self {
+        self.numbered = true;
+        self
+    }
+
+    /// Path of the checkpoint saved at `epoch`
+    fn path_at(&self, epoch: u32) -> PathBuf {
+        if !self.numbered {
+            return self.path.clone();
+        }
+        let stem = self.path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        let name = match self.path.extension() {
+            Some(extension) => format!("{}_epoch{:06}.{}", stem, epoch, extension.to_string_lossy()),
+            None => format!("{}_epoch{:06}", stem, epoch),
+        };
+        self.path.with_file_name(name)
+    }
+
+    fn is_due(&self, epoch: u32) -> bool {
+        let by_epoch = self.epoch_interval.is_some_and(|n| n > 0 && epoch.is_multiple_of(n));
+        let by_time = self.time_interval.is_some_and(|t| self.last_save.elapsed() >= t);
+        by_epoch || by_time
+    }
+}
+
+impl TrainingCallback for Checkpointer {
+    fn on_train_start(&mut self, config: &TrainingConfig, _network: &Network) -> anyhow::Result<()> {
+        self.last_save = Instant::now();
+        self.seed = config.seed;
+        self.optimizer = Some(config.optimizer.name().to_string());
+        self.dataset_path = config.dataset_path.clone();
+        self.secret = config.encryption.clone();
+        Ok(())
+    }
+
+    fn on_epoch_end(&mut self, ctx: &EpochContext) -> anyhow::Result<CallbackAction> {
+        let epoch = ctx.record.epoch;
+        if self.is_due(epoch) {
+            let history = ctx.previous_history.continued_by(ctx.history);
+            let metadata = CheckpointMetadata {
+                version: "1.0".to_string(),
+                example: self.example.clone(),
+                epoch: ctx.previous_epochs + epoch,
+                total_epochs: ctx.previous_epochs + ctx.total_epochs,
+                learning_rate: ctx.network.learning_rate,
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                created_at: ctx.created_at.map(str::to_string),
+                crate_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+                training_secs: Some(history.elapsed_secs()),
+                final_loss: Some(ctx.record.loss),
+                seed: self.seed,
+                optimizer: self.optimizer.clone(),
+                dataset_fingerprint: ctx.dataset_fingerprint.map(str::to_string),
+                dataset_path: self.dataset_path.clone(),
+                overrides: ctx.overrides.to_vec(),
+            };
+            ctx.network
+                .to_checkpoint(metadata)
+                .with_history(history)
+                .with_lineage(ctx.lineage.to_vec())
+                .with_training_state(Some(ctx.training_state.clone()))
+                .with_normalizer(ctx.normalizer.cloned())
+                .with_target_normalizer(ctx.target_normalizer.cloned())
+                .save_with_secret(&self.path_at(ctx.previous_epochs + epoch), self.secret.as_ref())?;
+            self.last_save = Instant::now();
+        }
+        Ok(CallbackAction::Continue)
+    }
+}
+
+/// Stops training once the monitored loss has not improved for `patience` epochs
+///
+/// The validation loss is monitored when present, the training loss otherwise.
+pub struct EarlyStopping {
+    patience: u32,
+    verbose: bool,
+    best_loss: f64,
+    epochs_without_improvement: u32,
+}
+
+impl EarlyStopping {
+    /// Stop after `patience` epochs without improvement
+    pub fn new(patience: u32) -> Self {
+        Self {
+            patience,
+            verbose: false,
+            best_loss: f64::INFINITY,
+            epochs_without_improvement: 0,
+        }
+    }
+
+    /// Print a message when stopping
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+}
+
+impl TrainingCallback for EarlyStopping {
+    fn on_train_start(&mut self, _config: &TrainingConfig, _network: &Network) -> anyhow::Result<()> {
+        self.best_loss = f64::INFINITY;
+        self.epochs_without_improvement = 0;
+        Ok(())
+    }
+
+    fn on_epoch_end(&mut self, ctx: &EpochContext) -> anyhow::Result<CallbackAction> {
+        let monitored = ctx.record.validation_loss.unwrap_or(ctx.record.loss);
+        if monitored < self.best_loss {
+            self.best_loss = monitored;
+            self.epochs_without_improvement = 0;
+        } else {
+            self.epochs_without_improvement += 1;
+        }
+
+        if self.epochs_without_improvement < self.patience {
+            return Ok(CallbackAction::Continue);
+        }
+        if self.verbose {
+            println!(
+                "Early stopping at epoch {}: no improvement for {} epochs",
+                ctx.record.epoch, self.patience
+            );
+        }
+        Ok(CallbackAction::Stop)
+    }
+}
+
+/// Stops training once the monitored loss falls below a target
+///
+/// The validation loss is monitored when present, the training loss
+/// otherwise. The history then shows how many epochs were actually needed.
+pub struct TargetLoss {
+    target: f64,
+    verbose: bool,
+}
+
+impl TargetLoss {
+    /// Stop as soon as the loss is below `target`
+    pub fn new(target: f64) -> Self {
+        Self { target, verbose: false }
+    }
+
+    /// Print a message when stopping
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+}
+
+impl TrainingCallback for TargetLoss {
+    fn on_epoch_end(&mut self, ctx: &EpochContext) -> anyhow::Result<CallbackAction> {
+        let monitored = ctx.record.validation_loss.unwrap_or(ctx.record.loss);
+        if monitored >= self.target {
+            return Ok(CallbackAction::Continue);
+        }
+        if self.verbose {
+            println!(
+                "Reached target loss {} at epoch {} of {}",
+                self.target, ctx.record.epoch, ctx.total_epochs
+            );
+        }
+        Ok(CallbackAction::Stop)
+    }
+}
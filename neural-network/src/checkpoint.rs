@@ -3,20 +3,173 @@
 /// This module provides structures and methods for checkpointing neural network
 /// training sessions. Checkpoints include both the network state (weights, biases)
 /// and metadata about the training session (epoch, timestamp, etc.).
+///
+/// Files ending in `.gz` or `.zst` are compressed with gzip or zstd on top
+/// of their JSON or binary encoding, e.g. `model.json.gz` or `model.bin.zst`.
+/// Loading detects both the compression and the format from the leading
+/// bytes, so a compressed checkpoint can be used wherever a plain one can.
+///
+/// Saved checkpoints carry a SHA-256 checksum of the encoded network that is
+/// verified on load, so a file damaged or truncated mid-write fails with a
+/// "Checkpoint corrupted" error instead of loading wrong weights or failing
+/// deep inside the decoder.
+///
+/// `save_with_secret` additionally encrypts the file (see `encryption`);
+/// `load_with_secret` decrypts it, and `load` refuses it with a message
+/// asking for the password or key file.
+///
+/// Failures that come from the file's contents rather than from reading it
+/// carry a `CheckpointError`, which `anyhow::Error::downcast_ref` finds, so
+/// callers can tell an unusable checkpoint from a missing file.
+use crate::encryption::{self, Secret};
+use crate::history::TrainingHistory;
 use crate::network::Network;
+use crate::normalize::Normalizer;
+use crate::optimizer::{Optimizer, OptimizerState};
 use anyhow::{Context, Result};
+use matrix::error::MatrixError;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
 
 /// Supported checkpoint format version
 const CHECKPOINT_VERSION: &str = "1.0";
 
+/// Leading bytes identifying a binary checkpoint file
+pub(crate) const BINARY_MAGIC: &[u8; 4] = b"NNCB";
+
+/// Layout version of a binary checkpoint, stored as a little-endian `u16`
+/// right after `BINARY_MAGIC`
+///
+/// bincode encodes fields positionally, so bump this whenever a field is
+/// added to `Checkpoint` or `CheckpointMetadata` and branch on it in
+/// `Checkpoint::from_bytes`.
+const BINARY_VERSION: u16 = 1;
+
+/// Leading bytes of a gzip stream
+const GZIP_MAGIC: &[u8; 2] = &[0x1f, 0x8b];
+
+/// Leading bytes of a zstd frame
+const ZSTD_MAGIC: &[u8; 4] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// On-disk encoding of a checkpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckpointFormat {
+    /// Pretty-printed JSON, human readable and diffable
+    Json,
+
+    /// Compact bincode encoding with 8 bytes per weight, several times
+    /// smaller and faster to load than JSON for larger networks
+    Binary,
+}
+
+impl CheckpointFormat {
+    /// Pick the format from the file extension: `.bin` is binary, anything else JSON
+    ///
+    /// A compression extension is skipped, so `model.bin.zst` is binary.
+    pub fn from_path(path: &Path) -> Self {
+        let path = match Compression::from_path(path) {
+            Compression::None => path,
+            _ => Path::new(path.file_stem().unwrap_or_default()),
+        };
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("bin") => CheckpointFormat::Binary,
+            _ => CheckpointFormat::Json,
+        }
+    }
+}
+
+/// Compression applied to a checkpoint file on top of its format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Compression {
+    /// Stored as encoded
+    #[default]
+    None,
+
+    /// gzip (DEFLATE), readable with `gunzip`
+    Gzip,
+
+    /// Zstandard, readable with `zstd -d`
+    Zstd,
+}
+
+impl Compression {
+    /// Pick the compression from the file extension: `.gz` is gzip, `.zst`
+    /// zstd, anything else uncompressed
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("zst") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+
+    /// Detect the compression of file contents from their magic bytes
+    pub fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(GZIP_MAGIC) {
+            Compression::Gzip
+        } else if bytes.starts_with(ZSTD_MAGIC) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+
+    /// Compress `bytes`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the encoder fails
+    pub fn compress(&self, bytes: Vec<u8>) -> Result<Vec<u8>> {
+        match self {
+            Compression::None => Ok(bytes),
+            Compression::Gzip => {
+                let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(&bytes).context("Failed to compress checkpoint")?;
+                encoder.finish().context("Failed to compress checkpoint")
+            }
+            Compression::Zstd => Ok(ruzstd::encoding::compress_to_vec(
+                bytes.as_slice(),
+                ruzstd::encoding::CompressionLevel::Fastest,
+            )),
+        }
+    }
+
+    /// Decompress `bytes` written by `compress`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the compressed data is malformed or truncated
+    pub fn decompress(&self, bytes: Vec<u8>) -> Result<Vec<u8>> {
+        let mut decompressed = Vec::new();
+        match self {
+            Compression::None => return Ok(bytes),
+            Compression::Gzip => {
+                flate2::read::GzDecoder::new(bytes.as_slice())
+                    .read_to_end(&mut decompressed)
+                    .context("Failed to decompress gzip checkpoint")?;
+            }
+            Compression::Zstd => {
+                ruzstd::decoding::StreamingDecoder::new(bytes.as_slice())
+                    .context("Failed to decompress zstd checkpoint")?
+                    .read_to_end(&mut decompressed)
+                    .context("Failed to decompress zstd checkpoint")?;
+            }
+        }
+        Ok(decompressed)
+    }
+}
+
 /// Metadata about a training checkpoint
 ///
 /// Contains information about when and where the checkpoint was created,
-/// including training progress and hyperparameters.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// including training progress and hyperparameters. The optional fields are
+/// filled in by `TrainingController::save_checkpoint` and are `None` in
+/// checkpoints written before they existed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CheckpointMetadata {
     /// Checkpoint format version (for future compatibility)
     pub version: String,
@@ -24,10 +177,11 @@ pub struct CheckpointMetadata {
     /// Name of the example/problem being trained
     pub example: String,
 
-    /// Current epoch number (how far training has progressed)
+    /// Current epoch number (how far training has progressed), counting the
+    /// epochs of every run this checkpoint was resumed from
     pub epoch: u32,
 
-    /// Total planned epochs
+    /// Total planned epochs, including those of earlier runs
     pub total_epochs: u32,
 
     /// Learning rate used during training
@@ -35,6 +189,195 @@ pub struct CheckpointMetadata {
 
     /// ISO 8601 timestamp of when checkpoint was created
     pub timestamp: String,
+
+    /// ISO 8601 timestamp of when the first run of this model began, kept
+    /// across resumes
+    #[serde(default)]
+    pub created_at: Option<String>,
+
+    /// Version of the `neural-network` crate that wrote the checkpoint
+    #[serde(default)]
+    pub crate_version: Option<String>,
+
+    /// Seconds spent training, summed over every resumed run
+    #[serde(default)]
+    pub training_secs: Option<f64>,
+
+    /// Training loss of the last recorded epoch
+    #[serde(default)]
+    pub final_loss: Option<f64>,
+
+    /// Seed of the shuffle order, configured or drawn at random for the run
+    #[serde(default)]
+    pub seed: Option<u64>,
+
+    /// Name of the optimizer (`sgd`, `momentum`, `adam`)
+    #[serde(default)]
+    pub optimizer: Option<String>,
+
+    /// `Dataset::fingerprint` of the data the last run trained on
+    #[serde(default)]
+    pub dataset_fingerprint: Option<String>,
+
+    /// File the last run read its training data from, as given to it
+    #[serde(default)]
+    pub dataset_path: Option<String>,
+
+    /// Hyperparameters changed when resuming, oldest first, kept across
+    /// resumes
+    #[serde(default)]
+    pub overrides: Vec<HyperparameterOverride>,
+}
+
+/// A hyperparameter a resumed run changed from its checkpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HyperparameterOverride {
+    /// Name of the hyperparameter, e.g. `learning_rate`
+    pub name: String,
+
+    /// Value in the checkpoint the run resumed from
+    pub before: String,
+
+    /// Value the run continued with
+    pub after: String,
+
+    /// Epoch of the checkpoint the run resumed from, after which the new
+    /// value applies
+    pub epoch: u32,
+}
+
+impl CheckpointMetadata {
+    /// Labelled values of the optional fields that are set, for display
+    pub fn details(&self) -> Vec<(&'static str, String)> {
+        let mut details = Vec::new();
+        if let Some(created_at) = &self.created_at {
+            details.push(("Created", created_at.clone()));
+        }
+        if let Some(version) = &self.crate_version {
+            details.push(("Crate version", version.clone()));
+        }
+        if let Some(secs) = self.training_secs {
+            details.push(("Training time", format!("{:.2}s", secs)));
+        }
+        if let Some(loss) = self.final_loss {
+            details.push(("Final loss", format!("{:.6}", loss)));
+        }
+        if let Some(seed) = self.seed {
+            details.push(("Seed", seed.to_string()));
+        }
+        if let Some(optimizer) = &self.optimizer {
+            details.push(("Optimizer", optimizer.clone()));
+        }
+        if let Some(fingerprint) = &self.dataset_fingerprint {
+            details.push(("Dataset fingerprint", fingerprint.clone()));
+        }
+        if let Some(path) = &self.dataset_path {
+            details.push(("Dataset path", path.clone()));
+        }
+        for o in &self.overrides {
+            details.push(("Overridden", format!("{} {} -> {} after epoch {}", o.name, o.before, o.after, o.epoch)));
+        }
+        details
+    }
+}
+
+/// Why the contents of a checkpoint can't be used
+///
+/// Attached as context to the errors of `Checkpoint::load`,
+/// `Checkpoint::load_with_secret` and `Network::from_checkpoint`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CheckpointError {
+    /// The file is truncated, damaged or fails its checksum
+    Corrupted(PathBuf),
+
+    /// The file is encrypted and the secret is missing or wrong
+    Encrypted(PathBuf),
+
+    /// The contents decode, but not as a checkpoint
+    Invalid,
+
+    /// The checkpoint was written in another format version
+    UnsupportedVersion {
+        /// Version recorded in the checkpoint
+        found: String,
+
+        /// Version this library reads
+        expected: String,
+    },
+
+    /// A weight or bias matrix doesn't match the layer sizes
+    ShapeMismatch(MatrixError),
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckpointError::Corrupted(path) => {
+                write!(f, "Checkpoint corrupted: {} is damaged or truncated", path.display())
+            }
+            CheckpointError::Encrypted(path) => write!(f, "Failed to load {}", path.display()),
+            CheckpointError::Invalid => write!(f, "Failed to deserialize checkpoint"),
+            CheckpointError::UnsupportedVersion { found, expected } => {
+                write!(f, "Unsupported checkpoint version: {}. Expected: {}", found, expected)
+            }
+            CheckpointError::ShapeMismatch(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+/// Decrypt the contents of a checkpoint file if they are encrypted
+pub(crate) fn decrypt_contents(contents: Vec<u8>, secret: Option<&Secret>) -> Result<Vec<u8>> {
+    if !encryption::is_encrypted(&contents) {
+        return Ok(contents);
+    }
+    match secret {
+        Some(secret) => encryption::decrypt(&contents, secret),
+        None => anyhow::bail!("Checkpoint is encrypted; a password or key file is needed to load it"),
+    }
+}
+
+/// Training loop state needed to continue a run exactly
+///
+/// Weights alone are not enough: the optimizer's running statistics and the
+/// position in the shuffle sequence would otherwise restart on resume.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrainingState {
+    /// Epochs trained so far across all resumed runs; the shuffle order of the
+    /// next epoch is derived from `seed` and this count
+    pub epochs_trained: u32,
+
+    /// Seed of the shuffle order
+    pub seed: u64,
+
+    /// Whether samples were shuffled every epoch
+    pub shuffle: bool,
+
+    /// Samples per update, `None` for per-sample updates
+    pub batch_size: Option<usize>,
+
+    /// Update rule the run used
+    pub optimizer: Optimizer,
+
+    /// Running statistics of `optimizer`
+    pub optimizer_state: OptimizerState,
+}
+
+/// A resume in a checkpoint's lineage: the checkpoint a run continued from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunLineage {
+    /// Path of the parent checkpoint, as given when resuming
+    pub parent_path: String,
+
+    /// Hex SHA-256 of the parent's network, as in `Checkpoint::checksum`
+    pub parent_checksum: String,
+
+    /// Epoch of the parent checkpoint, where the run picked up
+    pub parent_epoch: u32,
+
+    /// ISO 8601 timestamp of when the run resumed
+    pub resumed_at: String,
 }
 
 /// Complete checkpoint containing network state and metadata
@@ -48,6 +391,262 @@ pub struct Checkpoint {
 
     /// The neural network state (weights, biases, architecture)
     pub network: Network,
+
+    /// Per-epoch training telemetry recorded up to this checkpoint
+    ///
+    /// Older checkpoints without history deserialize to an empty history.
+    #[serde(default)]
+    pub history: TrainingHistory,
+
+    /// Optimizer and shuffle state of the run, for exact continuation
+    ///
+    /// Absent in checkpoints saved outside a training run and in older files.
+    #[serde(default)]
+    pub training_state: Option<TrainingState>,
+
+    /// Input transform to apply before the network, if it was trained on
+    /// normalized inputs
+    #[serde(default)]
+    pub normalizer: Option<Normalizer>,
+
+    /// Transform of the targets the network was trained on; invert its
+    /// outputs with it to get predictions in the original units
+    #[serde(default)]
+    pub target_normalizer: Option<Normalizer>,
+
+    /// Hex SHA-256 of the bincode-encoded `network`, written by `save` and
+    /// verified by `load`
+    ///
+    /// Absent in checkpoints built in memory and in older files, which are
+    /// loaded without verification.
+    #[serde(default)]
+    pub checksum: Option<String>,
+
+    /// Resumes that led to this checkpoint, oldest first; empty if it was
+    /// trained in a single run
+    #[serde(default)]
+    pub lineage: Vec<RunLineage>,
+}
+
+impl Checkpoint {
+    /// Attach training history to this checkpoint
+    pub fn with_history(mut self, history: TrainingHistory) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Attach the training loop state to this checkpoint
+    pub fn with_training_state(mut self, state: Option<TrainingState>) -> Self {
+        self.training_state = state;
+        self
+    }
+
+    /// Attach the resume lineage to this checkpoint
+    pub fn with_lineage(mut self, lineage: Vec<RunLineage>) -> Self {
+        self.lineage = lineage;
+        self
+    }
+
+    /// Attach the input normalizer to this checkpoint
+    pub fn with_normalizer(mut self, normalizer: Option<Normalizer>) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    /// Attach the target normalizer to this checkpoint
+    pub fn with_target_normalizer(mut self, normalizer: Option<Normalizer>) -> Self {
+        self.target_normalizer = normalizer;
+        self
+    }
+
+    /// A copy for deployment with only the metadata, network and normalizers
+    ///
+    /// The history, training state and lineage are dropped, so the file is
+    /// smaller and loads faster; a stripped checkpoint can still be resumed,
+    /// but starts a fresh optimizer and shuffle sequence.
+    pub fn stripped(&self) -> Self {
+        Checkpoint {
+            metadata: self.metadata.clone(),
+            network: self.network.clone(),
+            history: TrainingHistory::default(),
+            training_state: None,
+            normalizer: self.normalizer.clone(),
+            target_normalizer: self.target_normalizer.clone(),
+            checksum: self.checksum.clone(),
+            lineage: Vec::new(),
+        }
+    }
+
+    /// Save the checkpoint, creating parent directories
+    ///
+    /// The format and compression are chosen from the extension (see
+    /// `CheckpointFormat::from_path` and `Compression::from_path`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails or the file cannot be written
+    pub fn save(&self, path: &Path) -> Result<()> {
+        self.save_as(path, CheckpointFormat::from_path(path))
+    }
+
+    /// Save the checkpoint in an explicit format, creating parent directories
+    ///
+    /// The file is still compressed if its extension asks for it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails or the file cannot be written
+    pub fn save_as(&self, path: &Path, format: CheckpointFormat) -> Result<()> {
+        self.write(path, format, None)
+    }
+
+    /// Save the checkpoint, encrypted with `secret` if one is given
+    ///
+    /// The format and compression are chosen from the extension as for
+    /// `save`, and encryption is applied last.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization or encryption fails or the file
+    /// cannot be written
+    pub fn save_with_secret(&self, path: &Path, secret: Option<&Secret>) -> Result<()> {
+        self.write(path, CheckpointFormat::from_path(path), secret)
+    }
+
+    fn write(&self, path: &Path, format: CheckpointFormat, secret: Option<&Secret>) -> Result<()> {
+        let checksum = Some(network_checksum(&self.network)?);
+        if self.checksum != checksum {
+            return Checkpoint { checksum, ..self.clone() }.write(path, format, secret);
+        }
+
+        let bytes = match format {
+            CheckpointFormat::Json => serde_json::to_vec_pretty(self)
+                .context("Failed to serialize checkpoint")?,
+            CheckpointFormat::Binary => self.to_bytes()?,
+        };
+        let mut bytes = Compression::from_path(path).compress(bytes)?;
+        if let Some(secret) = secret {
+            bytes = encryption::encrypt(&bytes, secret)?;
+        }
+
+        // Ensure parent directory exists
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+
+        fs::write(path, bytes)
+            .with_context(|| format!("Failed to write checkpoint to {}", path.display()))?;
+
+        Ok(())
+    }
+
+    /// Load a checkpoint (network, metadata and history) from a JSON or binary file
+    ///
+    /// The format and compression are detected from the file contents, not
+    /// the extension.
+    /// Unlike `Network::load_checkpoint`, this does not validate the version;
+    /// use it for tools that only inspect checkpoint contents.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or can't be decoded, and a
+    /// "Checkpoint corrupted" error if it is truncated or its checksum does
+    /// not match, or if it is encrypted
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::load_with_secret(path, None)
+    }
+
+    /// Load a checkpoint, decrypting it with `secret` if it is encrypted
+    ///
+    /// Unencrypted files load as with `load`, whether or not a secret is given.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `load`, and an error if the file is encrypted
+    /// and `secret` is missing or wrong
+    pub fn load_with_secret(path: &Path, secret: Option<&Secret>) -> Result<Self> {
+        let contents = fs::read(path)
+            .with_context(|| format!("Failed to read checkpoint from {}", path.display()))?;
+        let contents = decrypt_contents(contents, secret).with_context(|| CheckpointError::Encrypted(path.to_path_buf()))?;
+        let corrupted = || CheckpointError::Corrupted(path.to_path_buf());
+        let contents = Compression::detect(&contents).decompress(contents).with_context(corrupted)?;
+
+        let checkpoint: Self = if contents.starts_with(BINARY_MAGIC) {
+            match Self::from_bytes(&contents) {
+                Ok(checkpoint) => checkpoint,
+                // Written by a newer (or older) layout, not damaged
+                Err(e) if e.is::<CheckpointError>() => return Err(e),
+                Err(e) => return Err(e).with_context(corrupted),
+            }
+        } else {
+            match serde_json::from_slice(&contents) {
+                Ok(checkpoint) => checkpoint,
+                Err(e) if e.is_eof() => return Err(e).with_context(corrupted),
+                Err(e) => return Err(e).context(CheckpointError::Invalid),
+            }
+        };
+        checkpoint.verify().with_context(corrupted)?;
+        Ok(checkpoint)
+    }
+
+    /// Check the network against the stored checksum, if there is one
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checksum does not match
+    pub fn verify(&self) -> Result<()> {
+        if let Some(expected) = &self.checksum {
+            let actual = network_checksum(&self.network)?;
+            if &actual != expected {
+                anyhow::bail!("Network checksum mismatch: expected {}, got {}", expected, actual);
+            }
+        }
+        Ok(())
+    }
+
+    /// Encode in the binary checkpoint format (magic bytes and layout
+    /// version followed by bincode)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = BINARY_MAGIC.to_vec();
+        bytes.extend_from_slice(&BINARY_VERSION.to_le_bytes());
+        bincode::serialize_into(&mut bytes, self).context("Failed to serialize checkpoint")?;
+        Ok(bytes)
+    }
+
+    /// Decode a checkpoint written by `to_bytes`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the magic bytes are missing, the layout version is
+    /// not one this library reads (`CheckpointError::UnsupportedVersion`) or
+    /// the payload is malformed
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let payload = bytes
+            .strip_prefix(BINARY_MAGIC)
+            .context("Not a binary checkpoint (missing header)")?;
+        let (version, payload) = payload
+            .split_first_chunk()
+            .context("Binary checkpoint truncated before its format version")?;
+        match u16::from_le_bytes(*version) {
+            BINARY_VERSION => bincode::deserialize(payload).context("Failed to deserialize binary checkpoint"),
+            found => Err(CheckpointError::UnsupportedVersion {
+                found: found.to_string(),
+                expected: BINARY_VERSION.to_string(),
+            }
+            .into()),
+        }
+    }
+}
+
+/// Hex SHA-256 of the bincode encoding of `network`
+pub(crate) fn network_checksum(network: &Network) -> Result<String> {
+    let bytes = bincode::serialize(network).context("Failed to serialize network")?;
+    Ok(format!("{:x}", Sha256::digest(bytes)))
 }
 
 impl Network {
@@ -76,6 +675,7 @@ impl Network {
     ///     total_epochs: 1000,
     ///     learning_rate: 0.5,
     ///     timestamp: chrono::Utc::now().to_rfc3339(),
+    ///     ..Default::default()
     /// };
     ///
     /// let checkpoint = network.to_checkpoint(metadata);
@@ -84,6 +684,12 @@ impl Network {
         Checkpoint {
             metadata,
             network: self.clone(),
+            history: TrainingHistory::default(),
+            training_state: None,
+            normalizer: None,
+            target_normalizer: None,
+            checksum: None,
+            lineage: Vec::new(),
         }
     }
 
@@ -100,7 +706,8 @@ impl Network {
     ///
     /// # Errors
     ///
-    /// Returns an error if the checkpoint version is not supported
+    /// Returns an error if the checkpoint version is not supported or the
+    /// weight and bias shapes do not match the layer sizes
     ///
     /// # Examples
     ///
@@ -117,6 +724,7 @@ impl Network {
     ///     total_epochs: 1000,
     ///     learning_rate: 0.5,
     ///     timestamp: chrono::Utc::now().to_rfc3339(),
+    ///     ..Default::default()
     /// };
     ///
     /// let checkpoint = network.to_checkpoint(metadata);
@@ -125,21 +733,22 @@ impl Network {
     pub fn from_checkpoint(checkpoint: Checkpoint) -> Result<Self> {
         // Validate checkpoint version
         if checkpoint.metadata.version != CHECKPOINT_VERSION {
-            anyhow::bail!(
-                "Unsupported checkpoint version: {}. Expected: {}",
-                checkpoint.metadata.version,
-                CHECKPOINT_VERSION
-            );
+            return Err(CheckpointError::UnsupportedVersion {
+                found: checkpoint.metadata.version,
+                expected: CHECKPOINT_VERSION.to_string(),
+            }
+            .into());
         }
 
+        checkpoint.network.validate_shapes().map_err(CheckpointError::ShapeMismatch)?;
         Ok(checkpoint.network)
     }
 
     /// Save a checkpoint to a file
     ///
-    /// Serializes the network and metadata to JSON format and writes to the
-    /// specified path. The file is created if it doesn't exist, or overwritten
-    /// if it does.
+    /// Serializes the network and metadata and writes to the specified path:
+    /// paths ending in `.bin` use the compact binary format, anything else
+    /// JSON. The file is created if it doesn't exist, or overwritten if it does.
     ///
     /// # Arguments
     ///
@@ -173,33 +782,20 @@ impl Network {
     ///     total_epochs: 1000,
     ///     learning_rate: 0.5,
     ///     timestamp: chrono::Utc::now().to_rfc3339(),
+    ///     ..Default::default()
     /// };
     ///
     /// network.save_checkpoint(Path::new("checkpoint.json"), metadata)
     ///     .expect("Failed to save checkpoint");
     /// ```
     pub fn save_checkpoint(&self, path: &Path, metadata: CheckpointMetadata) -> Result<()> {
-        let checkpoint = self.to_checkpoint(metadata);
-
-        let json = serde_json::to_string_pretty(&checkpoint)
-            .context("Failed to serialize checkpoint")?;
-
-        // Ensure parent directory exists
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)
-                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
-        }
-
-        fs::write(path, json)
-            .with_context(|| format!("Failed to write checkpoint to {}", path.display()))?;
-
-        Ok(())
+        self.to_checkpoint(metadata).save(path)
     }
 
     /// Load a checkpoint from a file
     ///
-    /// Reads and deserializes a checkpoint from the specified JSON file, then
-    /// restores the network state.
+    /// Reads and deserializes a checkpoint from the specified JSON or binary
+    /// file, then restores the network state.
     ///
     /// # Arguments
     ///
@@ -214,7 +810,7 @@ impl Network {
     ///
     /// Returns an error if:
     /// - The file doesn't exist or can't be read
-    /// - The file contains invalid JSON
+    /// - The file contains invalid JSON or a malformed binary checkpoint
     /// - The checkpoint version is unsupported
     /// - Deserialization fails
     ///
@@ -230,11 +826,7 @@ impl Network {
     /// println!("Resumed from epoch {}", metadata.epoch);
     /// ```
     pub fn load_checkpoint(path: &Path) -> Result<(Self, CheckpointMetadata)> {
-        let contents = fs::read_to_string(path)
-            .with_context(|| format!("Failed to read checkpoint from {}", path.display()))?;
-
-        let checkpoint: Checkpoint = serde_json::from_str(&contents)
-            .context("Failed to deserialize checkpoint")?;
+        let checkpoint = Checkpoint::load(path)?;
 
         let metadata = checkpoint.metadata.clone();
         let network = Self::from_checkpoint(checkpoint)?;
@@ -262,6 +854,7 @@ mod tests {
             total_epochs: 100,
             learning_rate: 0.5,
             timestamp: "2025-10-13T12:00:00Z".to_string(),
+            ..Default::default()
         };
 
         assert_eq!(metadata.version, "1.0");
@@ -278,6 +871,7 @@ mod tests {
             total_epochs: 1000,
             learning_rate: 0.5,
             timestamp: "2025-10-13T12:00:00Z".to_string(),
+            ..Default::default()
         };
 
         let checkpoint = network.to_checkpoint(metadata);
@@ -296,6 +890,7 @@ mod tests {
             total_epochs: 1000,
             learning_rate: 0.5,
             timestamp: "2025-10-13T12:00:00Z".to_string(),
+            ..Default::default()
         };
 
         let checkpoint = network.to_checkpoint(metadata);
@@ -314,6 +909,7 @@ mod tests {
             total_epochs: 1000,
             learning_rate: 0.5,
             timestamp: "2025-10-13T12:00:00Z".to_string(),
+            ..Default::default()
         };
 
         let checkpoint = network.to_checkpoint(metadata);
@@ -321,4 +917,25 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_binary_checkpoint_records_layout_version() {
+        let network = Network::new(vec![2, 3, 1], SIGMOID, 0.5);
+        let checkpoint = network.to_checkpoint(CheckpointMetadata {
+            version: "1.0".to_string(),
+            example: "xor".to_string(),
+            ..Default::default()
+        });
+
+        let mut bytes = checkpoint.to_bytes().unwrap();
+        assert_eq!(&bytes[4..6], &BINARY_VERSION.to_le_bytes());
+
+        bytes[4..6].copy_from_slice(&(BINARY_VERSION + 1).to_le_bytes());
+        let err = Checkpoint::from_bytes(&bytes).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<CheckpointError>(),
+            Some(CheckpointError::UnsupportedVersion { found, .. }) if *found == (BINARY_VERSION + 1).to_string()
+        ));
+    }
+
 }
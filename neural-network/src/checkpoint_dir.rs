@@ -0,0 +1,128 @@
+/// Listing and pruning a directory of checkpoints
+///
+/// Long runs leave many checkpoints behind, for example the numbered files of
+/// `Checkpointer::numbered` (`snapshot_epoch000100.json`, ...). `scan` reads
+/// the metadata of every checkpoint in a directory and `Retention` decides
+/// which of them to delete: the most recent `keep_last` and the `keep_best`
+/// with the lowest final loss survive. Files that cannot be read (damaged,
+/// or encrypted without the secret) are listed with the reason but never
+/// selected for removal.
+///
+/// ```no_run
+/// use neural_network::checkpoint_dir::{self, Retention};
+/// use std::path::Path;
+///
+/// let entries = checkpoint_dir::scan(Path::new("runs"), None).unwrap();
+/// let retention = Retention { keep_last: Some(3), keep_best: Some(1) };
+/// for entry in retention.removals(&entries) {
+///     std::fs::remove_file(&entry.path).unwrap();
+/// }
+/// ```
+use crate::checkpoint::{Checkpoint, CheckpointMetadata};
+use crate::encryption::Secret;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File extensions a checkpoint may have, compressed or not
+const EXTENSIONS: [&str; 4] = ["json", "bin", "gz", "zst"];
+
+/// One checkpoint file of a directory
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckpointEntry {
+    /// Path of the file
+    pub path: PathBuf,
+
+    /// Size of the file in bytes
+    pub size_bytes: u64,
+
+    /// Metadata of the checkpoint, `None` if it could not be read
+    pub metadata: Option<CheckpointMetadata>,
+
+    /// Why the checkpoint could not be read
+    pub error: Option<String>,
+}
+
+impl CheckpointEntry {
+    /// Training loss of the last recorded epoch, if known
+    pub fn final_loss(&self) -> Option<f64> {
+        self.metadata.as_ref().and_then(|m| m.final_loss)
+    }
+}
+
+/// Which checkpoints of a directory to keep
+///
+/// A checkpoint is kept if either rule keeps it. With neither rule set every
+/// readable checkpoint is removed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Retention {
+    /// Keep this many of the most recently saved checkpoints
+    pub keep_last: Option<usize>,
+
+    /// Keep this many checkpoints with the lowest final loss
+    pub keep_best: Option<usize>,
+}
+
+impl Retention {
+    /// The readable entries this retention does not keep, oldest first
+    ///
+    /// Recency is the metadata timestamp, ties broken by epoch. Checkpoints
+    /// without a final loss rank after all others for `keep_best`.
+    pub fn removals<'a>(&self, entries: &'a [CheckpointEntry]) -> Vec<&'a CheckpointEntry> {
+        let mut readable: Vec<&CheckpointEntry> = entries.iter().filter(|e| e.metadata.is_some()).collect();
+        readable.sort_by(|a, b| recency(a).cmp(&recency(b)));
+
+        let mut keep = vec![false; readable.len()];
+        if let Some(last) = self.keep_last {
+            let start = readable.len().saturating_sub(last);
+            keep[start..].iter_mut().for_each(|k| *k = true);
+        }
+        if let Some(best) = self.keep_best {
+            let mut by_loss: Vec<usize> = (0..readable.len()).collect();
+            by_loss.sort_by(|&a, &b| {
+                let loss = |i: usize| readable[i].final_loss().unwrap_or(f64::INFINITY);
+                loss(a).total_cmp(&loss(b))
+            });
+            by_loss.iter().take(best).for_each(|&i| keep[i] = true);
+        }
+
+        readable.into_iter().zip(keep).filter(|(_, keep)| !keep).map(|(entry, _)| entry).collect()
+    }
+}
+
+/// Sort key of an entry by when it was saved
+fn recency(entry: &CheckpointEntry) -> (&str, u32) {
+    let metadata = entry.metadata.as_ref().expect("only readable entries are ordered");
+    (metadata.timestamp.as_str(), metadata.epoch)
+}
+
+/// Read every checkpoint directly inside `dir`, sorted by file name
+///
+/// Files with a checkpoint extension (`.json`, `.bin`, `.gz`, `.zst`) are
+/// included; subdirectories are not searched. Encrypted checkpoints are
+/// decrypted with `secret` when given.
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be read
+pub fn scan(dir: &Path, secret: Option<&Secret>) -> Result<Vec<CheckpointEntry>> {
+    let mut entries = Vec::new();
+    for file in fs::read_dir(dir).with_context(|| format!("Failed to read directory {}", dir.display()))? {
+        let path = file.with_context(|| format!("Failed to read directory {}", dir.display()))?.path();
+        let is_checkpoint = path.is_file()
+            && path.extension().and_then(|e| e.to_str()).is_some_and(|e| EXTENSIONS.contains(&e));
+        if !is_checkpoint {
+            continue;
+        }
+
+        let size_bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        let (metadata, error) = match Checkpoint::load_with_secret(&path, secret) {
+            Ok(checkpoint) => (Some(checkpoint.metadata), None),
+            Err(e) => (None, Some(format!("{:#}", e))),
+        };
+        entries.push(CheckpointEntry { path, size_bytes, metadata, error });
+    }
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(entries)
+}
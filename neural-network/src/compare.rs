@@ -0,0 +1,236 @@
+/// Differences between two checkpoints
+///
+/// `compare` loads two checkpoints of the same architecture and reports how
+/// far every layer's weights and biases moved (L2 distance and largest
+/// absolute change) along with the metadata fields that differ. Comparing a
+/// checkpoint with the one it was resumed from shows whether, and where, the
+/// resumed run changed the model. `Evaluation::of` measures each model's
+/// loss, accuracy and confusion matrix on the same data, showing whether the
+/// change helped.
+use crate::checkpoint::Checkpoint;
+use crate::dataset::Dataset;
+use crate::inference::InferenceModel;
+use crate::matrix::Matrix;
+use crate::metrics::{ClassificationMetrics, ConfusionCounts, ConfusionMatrix, DEFAULT_THRESHOLD};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// How much the parameters of one layer changed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerDiff {
+    /// Index of the weight matrix, 0 for the first hidden layer
+    pub layer: usize,
+
+    /// Euclidean distance between the two weight matrices
+    pub weight_l2: f64,
+
+    /// Largest absolute change of a single weight
+    pub weight_max_abs: f64,
+
+    /// Euclidean distance between the two bias vectors
+    pub bias_l2: f64,
+
+    /// Largest absolute change of a single bias
+    pub bias_max_abs: f64,
+}
+
+impl LayerDiff {
+    /// Whether no weight or bias of the layer changed
+    pub fn is_unchanged(&self) -> bool {
+        self.weight_max_abs == 0.0 && self.bias_max_abs == 0.0
+    }
+}
+
+/// A metadata field whose value differs between the two checkpoints
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetadataChange {
+    /// Field name as in `CheckpointMetadata`
+    pub field: String,
+
+    /// Value in the first checkpoint
+    pub before: String,
+
+    /// Value in the second checkpoint
+    pub after: String,
+}
+
+/// Per-layer parameter changes and metadata differences of two checkpoints
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointDiff {
+    /// Layer sizes shared by both networks
+    pub layers: Vec<usize>,
+
+    /// Parameter changes, one per weight matrix
+    pub layer_diffs: Vec<LayerDiff>,
+
+    /// Metadata fields that differ
+    pub metadata: Vec<MetadataChange>,
+}
+
+impl CheckpointDiff {
+    /// Compare `before` with `after`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the networks have different layer sizes
+    pub fn between(before: &Checkpoint, after: &Checkpoint) -> Result<Self> {
+        let (a, b) = (&before.network, &after.network);
+        if a.layers != b.layers {
+            anyhow::bail!("Incompatible architectures: {:?} vs {:?}", a.layers, b.layers);
+        }
+        a.validate_shapes()?;
+        b.validate_shapes()?;
+
+        let layer_diffs = (0..a.weights.len())
+            .map(|layer| {
+                let (weight_l2, weight_max_abs) = distance(&a.weights[layer], &b.weights[layer]);
+                let (bias_l2, bias_max_abs) = distance(&a.biases[layer], &b.biases[layer]);
+                LayerDiff {
+                    layer,
+                    weight_l2,
+                    weight_max_abs,
+                    bias_l2,
+                    bias_max_abs,
+                }
+            })
+            .collect();
+
+        let (m, n) = (&before.metadata, &after.metadata);
+        let fields = [
+            ("version", m.version.clone(), n.version.clone()),
+            ("example", m.example.clone(), n.example.clone()),
+            ("epoch", m.epoch.to_string(), n.epoch.to_string()),
+            ("total_epochs", m.total_epochs.to_string(), n.total_epochs.to_string()),
+            ("learning_rate", m.learning_rate.to_string(), n.learning_rate.to_string()),
+            ("timestamp", m.timestamp.clone(), n.timestamp.clone()),
+        ];
+        let metadata = fields
+            .into_iter()
+            .filter(|(_, before, after)| before != after)
+            .map(|(field, before, after)| MetadataChange {
+                field: field.to_string(),
+                before,
+                after,
+            })
+            .collect();
+
+        Ok(CheckpointDiff {
+            layers: a.layers.clone(),
+            layer_diffs,
+            metadata,
+        })
+    }
+
+    /// Euclidean distance between all parameters of the two networks
+    pub fn total_l2(&self) -> f64 {
+        self.layer_diffs
+            .iter()
+            .map(|d| d.weight_l2.powi(2) + d.bias_l2.powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Whether the two networks have identical parameters
+    pub fn is_unchanged(&self) -> bool {
+        self.layer_diffs.iter().all(LayerDiff::is_unchanged)
+    }
+}
+
+/// Samples read at a time by `Evaluation::of`
+const EVALUATE_CHUNK: usize = 4096;
+
+/// Loss and classification metrics of a model on a dataset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Evaluation {
+    /// Number of samples evaluated
+    pub samples: usize,
+
+    /// Squared error summed over the outputs and averaged over the samples,
+    /// in the units of the data's targets
+    pub loss: f64,
+
+    /// Metrics at the decision threshold
+    pub metrics: ClassificationMetrics,
+
+    /// Class predictions against the data's classes
+    pub confusion: ConfusionMatrix,
+}
+
+impl Evaluation {
+    /// Evaluate `model` on every sample of `data` at the default threshold
+    ///
+    /// The model's normalizers are applied as in prediction, so models with
+    /// different normalizers are measured on the same scale.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `data` is empty or its input or target width does
+    /// not match the network
+    pub fn of<D: Dataset + ?Sized>(model: &mut InferenceModel, data: &D) -> Result<Self> {
+        Self::at_threshold(model, data, DEFAULT_THRESHOLD)
+    }
+
+    /// Evaluate `model` on every sample of `data`, classifying outputs at
+    /// `threshold`
+    ///
+    /// # Errors
+    ///
+    /// As for `Evaluation::of`
+    pub fn at_threshold<D: Dataset + ?Sized>(model: &mut InferenceModel, data: &D, threshold: f64) -> Result<Self> {
+        let layers = &model.network.layers;
+        let (inputs, outputs) = (layers[0], layers[layers.len() - 1]);
+        if data.input_dim() != inputs || data.target_dim() != outputs {
+            anyhow::bail!(
+                "Data with {} inputs and {} targets does not fit a network with {} inputs and {} outputs",
+                data.input_dim(),
+                data.target_dim(),
+                inputs,
+                outputs
+            );
+        }
+        if data.is_empty() {
+            anyhow::bail!("Cannot evaluate on an empty dataset");
+        }
+
+        let mut loss = 0.0;
+        let mut counts = ConfusionCounts::default();
+        let mut confusion = ConfusionMatrix::for_outputs(outputs);
+        for start in (0..data.len()).step_by(EVALUATE_CHUNK) {
+            let (chunk_inputs, chunk_targets) = data.chunk(start..(start + EVALUATE_CHUNK).min(data.len()));
+            for (input, target) in chunk_inputs.iter().zip(&chunk_targets) {
+                let output = model.predict(input)?;
+                loss += output.iter().zip(target).map(|(o, t)| (t - o) * (t - o)).sum::<f64>();
+                counts.observe(&output, target, threshold);
+                confusion.observe(&output, target, threshold);
+            }
+        }
+        Ok(Evaluation {
+            samples: data.len(),
+            loss: loss / data.len() as f64,
+            metrics: counts.metrics(),
+            confusion,
+        })
+    }
+}
+
+/// Load the checkpoints at `before` and `after` and compare them
+///
+/// # Errors
+///
+/// Returns an error if either checkpoint cannot be loaded or the networks
+/// have different layer sizes
+pub fn compare(before: &Path, after: &Path) -> Result<CheckpointDiff> {
+    CheckpointDiff::between(&Checkpoint::load(before)?, &Checkpoint::load(after)?)
+}
+
+/// L2 distance and largest absolute difference of two same-shaped matrices
+fn distance(a: &Matrix, b: &Matrix) -> (f64, f64) {
+    let (sum, max) = a
+        .data
+        .iter()
+        .zip(&b.data)
+        .map(|(x, y)| (y - x).abs())
+        .fold((0.0, 0.0_f64), |(sum, max), d| (sum + d * d, max.max(d)));
+    (sum.sqrt(), max)
+}
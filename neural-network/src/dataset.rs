@@ -0,0 +1,440 @@
+/// A common interface over training data
+///
+/// Built-in examples, generated datasets and file loaders all implement
+/// `Dataset`, so code that trains or inspects data does not care where the
+/// samples come from. `TrainingController::train_dataset` trains on any
+/// dataset:
+///
+/// ```
+/// use neural_network::dataset::{Dataset, VecDataset};
+/// # use neural_network::{activations::SIGMOID, network::Network, training::*};
+///
+/// let data = VecDataset::new(vec![vec![0.0, 1.0], vec![1.0, 1.0]], vec![vec![1.0], vec![0.0]]).unwrap();
+/// assert_eq!((data.input_dim(), data.target_dim()), (2, 1));
+///
+/// let network = Network::new(vec![data.input_dim(), 3, data.target_dim()], SIGMOID, 0.5);
+/// let mut controller = TrainingController::new(network, TrainingConfig { epochs: 10, ..Default::default() });
+/// controller.train_dataset(&data).unwrap();
+/// ```
+use crate::examples::Example;
+use crate::training::class_of;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand_distr::{Distribution, StandardNormal};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::ops::Range;
+
+/// Indexed samples of fixed input and target size
+pub trait Dataset {
+    /// Number of samples
+    fn len(&self) -> usize;
+
+    /// Whether the dataset has no samples
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Input and target of sample `i`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of range
+    fn get(&self, i: usize) -> (Vec<f64>, Vec<f64>);
+
+    /// Length of every input
+    fn input_dim(&self) -> usize;
+
+    /// Length of every target
+    fn target_dim(&self) -> usize;
+
+    /// Inputs and targets of the samples in `range`, in sample order
+    ///
+    /// File-backed datasets override this to read the range in one pass;
+    /// streamed training loads its chunks through it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds
+    fn chunk(&self, range: Range<usize>) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+        range.map(|i| self.get(i)).unzip()
+    }
+
+    /// Every input and every target, in sample order
+    fn to_vecs(&self) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+        self.chunk(0..self.len())
+    }
+
+    /// Per-column statistics and class distribution, for sanity-checking
+    /// data before training
+    ///
+    /// Reads the samples once, a chunk at a time, so it also works on
+    /// file-backed datasets larger than memory.
+    fn describe(&self) -> DatasetSummary {
+        summarize(self)
+    }
+
+    /// Hex SHA-256 of the dimensions and every value of every sample, in
+    /// sample order
+    ///
+    /// Equal to `fingerprint` of `to_vecs()`, but reads the samples a chunk at
+    /// a time. Checkpoints record it so a model can be traced back to the
+    /// exact data it was trained on.
+    fn fingerprint(&self) -> String {
+        let mut hasher = Fingerprint::new(self.input_dim(), self.target_dim());
+        for start in (0..self.len()).step_by(DESCRIBE_CHUNK) {
+            let (inputs, targets) = self.chunk(start..(start + DESCRIBE_CHUNK).min(self.len()));
+            inputs.iter().zip(&targets).for_each(|(input, target)| hasher.add(input, target));
+        }
+        hasher.finish()
+    }
+}
+
+/// Hex SHA-256 of in-memory samples, as by `Dataset::fingerprint`
+pub fn fingerprint(inputs: &[Vec<f64>], targets: &[Vec<f64>]) -> String {
+    let (input_dim, target_dim) = (inputs.first().map_or(0, Vec::len), targets.first().map_or(0, Vec::len));
+    let mut hasher = Fingerprint::new(input_dim, target_dim);
+    inputs.iter().zip(targets).for_each(|(input, target)| hasher.add(input, target));
+    hasher.finish()
+}
+
+/// SHA-256 over the sample dimensions and the bits of every value
+struct Fingerprint(Sha256);
+
+impl Fingerprint {
+    fn new(input_dim: usize, target_dim: usize) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update((input_dim as u64).to_le_bytes());
+        hasher.update((target_dim as u64).to_le_bytes());
+        Fingerprint(hasher)
+    }
+
+    fn add(&mut self, input: &[f64], target: &[f64]) {
+        for value in input.iter().chain(target) {
+            self.0.update(value.to_bits().to_le_bytes());
+        }
+    }
+
+    fn finish(self) -> String {
+        format!("{:x}", self.0.finalize())
+    }
+}
+
+/// A dataset held in memory as input and target vectors
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VecDataset {
+    inputs: Vec<Vec<f64>>,
+    targets: Vec<Vec<f64>>,
+}
+
+impl VecDataset {
+    /// Pair `inputs[i]` with `targets[i]`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the counts differ or the inputs (or targets) do
+    /// not all have the same length
+    pub fn new(inputs: Vec<Vec<f64>>, targets: Vec<Vec<f64>>) -> anyhow::Result<Self> {
+        if inputs.len() != targets.len() {
+            anyhow::bail!("{} inputs but {} targets", inputs.len(), targets.len());
+        }
+        for (name, rows) in [("input", &inputs), ("target", &targets)] {
+            if let Some(first) = rows.first()
+                && let Some(i) = rows.iter().position(|row| row.len() != first.len())
+            {
+                anyhow::bail!(
+                    "Sample {} has {} {} values, sample 0 has {}",
+                    i,
+                    rows[i].len(),
+                    name,
+                    first.len()
+                );
+            }
+        }
+        Ok(Self { inputs, targets })
+    }
+
+    /// Input vectors, one per sample
+    pub fn inputs(&self) -> &[Vec<f64>] {
+        &self.inputs
+    }
+
+    /// Target vectors, one per sample
+    pub fn targets(&self) -> &[Vec<f64>] {
+        &self.targets
+    }
+
+    /// The input and target vectors
+    pub fn into_vecs(self) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+        (self.inputs, self.targets)
+    }
+}
+
+impl Dataset for VecDataset {
+    fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    fn get(&self, i: usize) -> (Vec<f64>, Vec<f64>) {
+        (self.inputs[i].clone(), self.targets[i].clone())
+    }
+
+    fn input_dim(&self) -> usize {
+        self.inputs.first().map_or(0, Vec::len)
+    }
+
+    fn target_dim(&self) -> usize {
+        self.targets.first().map_or(0, Vec::len)
+    }
+}
+
+impl Dataset for Example {
+    fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    fn get(&self, i: usize) -> (Vec<f64>, Vec<f64>) {
+        (self.inputs[i].clone(), self.targets[i].clone())
+    }
+
+    fn input_dim(&self) -> usize {
+        self.recommended_arch[0]
+    }
+
+    fn target_dim(&self) -> usize {
+        self.recommended_arch[self.recommended_arch.len() - 1]
+    }
+}
+
+/// Seeded Gaussian noise for augmenting inputs
+///
+/// The noise of a sample depends only on the seed, the epoch and the
+/// sample's index, so every epoch sees fresh noise while seeded runs stay
+/// reproducible regardless of the order samples are visited in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InputNoise {
+    /// Standard deviation of the noise added to every input value
+    pub std_dev: f64,
+
+    pub seed: u64,
+}
+
+impl InputNoise {
+    /// Add the noise of sample `index` in `epoch` to `input`
+    pub fn apply(&self, input: &mut [f64], epoch: u64, index: usize) {
+        if self.std_dev == 0.0 {
+            return;
+        }
+        let stream = epoch.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ (index as u64).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        let mut rng = StdRng::seed_from_u64(self.seed ^ stream);
+        for x in input {
+            let sample: f64 = StandardNormal.sample(&mut rng);
+            *x += self.std_dev * sample;
+        }
+    }
+}
+
+/// A dataset whose inputs get fresh Gaussian noise every epoch
+///
+/// Targets are unchanged. Jittering the few samples of a tiny dataset keeps
+/// the network from fitting their exact positions. `TrainingConfig::input_noise`
+/// applies the same noise inside the training loop; use this wrapper to
+/// inspect or export augmented data.
+#[derive(Debug, Clone)]
+pub struct NoisyDataset<D> {
+    dataset: D,
+    noise: InputNoise,
+    epoch: u64,
+}
+
+impl<D: Dataset> NoisyDataset<D> {
+    /// Wrap `dataset`, adding noise of standard deviation `std_dev`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `std_dev` is negative or not finite
+    pub fn new(dataset: D, std_dev: f64, seed: u64) -> anyhow::Result<Self> {
+        if !(std_dev >= 0.0 && std_dev.is_finite()) {
+            anyhow::bail!("Input noise must be a finite standard deviation >= 0, got {}", std_dev);
+        }
+        Ok(Self {
+            dataset,
+            noise: InputNoise { std_dev, seed },
+            epoch: 0,
+        })
+    }
+
+    /// Epoch whose noise `get` returns, counting from 0
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Switch to the noise of `epoch`
+    pub fn set_epoch(&mut self, epoch: u64) {
+        self.epoch = epoch;
+    }
+
+    /// The wrapped dataset
+    pub fn into_inner(self) -> D {
+        self.dataset
+    }
+}
+
+impl<D: Dataset> Dataset for NoisyDataset<D> {
+    fn len(&self) -> usize {
+        self.dataset.len()
+    }
+
+    fn get(&self, i: usize) -> (Vec<f64>, Vec<f64>) {
+        let (mut input, target) = self.dataset.get(i);
+        self.noise.apply(&mut input, self.epoch, i);
+        (input, target)
+    }
+
+    fn input_dim(&self) -> usize {
+        self.dataset.input_dim()
+    }
+
+    fn target_dim(&self) -> usize {
+        self.dataset.target_dim()
+    }
+}
+
+/// Samples read at a time by `Dataset::describe`
+const DESCRIBE_CHUNK: usize = 4096;
+
+/// Statistics of one input or target column
+///
+/// Computed over the finite values only; `non_finite` counts the NaN and
+/// infinite values left out. Without finite values the statistics are NaN.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ColumnStats {
+    pub mean: f64,
+    /// Population standard deviation
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+    pub non_finite: usize,
+}
+
+impl ColumnStats {
+    /// Statistics of `values`
+    pub fn of(values: &[f64]) -> Self {
+        let mut column = Accumulator::EMPTY;
+        values.iter().for_each(|&x| column.add(x));
+        column.stats()
+    }
+}
+
+/// Summary of a dataset returned by `Dataset::describe`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatasetSummary {
+    pub samples: usize,
+    /// Statistics of every input column
+    pub inputs: Vec<ColumnStats>,
+    /// Statistics of every target column
+    pub targets: Vec<ColumnStats>,
+    /// Number of samples of each class, as grouped by `training::class_of`
+    pub class_counts: Vec<usize>,
+}
+
+/// Running count, mean, sum of squared deviations (Welford) and range of a column
+#[derive(Clone, Copy)]
+struct Accumulator {
+    count: usize,
+    mean: f64,
+    m2: f64,
+    min: f64,
+    max: f64,
+    non_finite: usize,
+}
+
+impl Accumulator {
+    const EMPTY: Accumulator = Accumulator {
+        count: 0,
+        mean: 0.0,
+        m2: 0.0,
+        min: f64::INFINITY,
+        max: f64::NEG_INFINITY,
+        non_finite: 0,
+    };
+
+    fn add(&mut self, x: f64) {
+        if !x.is_finite() {
+            self.non_finite += 1;
+            return;
+        }
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+    }
+
+    fn stats(&self) -> ColumnStats {
+        if self.count == 0 {
+            return ColumnStats {
+                mean: f64::NAN,
+                std_dev: f64::NAN,
+                min: f64::NAN,
+                max: f64::NAN,
+                non_finite: self.non_finite,
+            };
+        }
+        ColumnStats {
+            mean: self.mean,
+            std_dev: (self.m2 / self.count as f64).sqrt(),
+            min: self.min,
+            max: self.max,
+            non_finite: self.non_finite,
+        }
+    }
+}
+
+fn summarize<D: Dataset + ?Sized>(data: &D) -> DatasetSummary {
+    let mut inputs = vec![Accumulator::EMPTY; data.input_dim()];
+    let mut targets = vec![Accumulator::EMPTY; data.target_dim()];
+    let mut class_counts = Vec::new();
+    for start in (0..data.len()).step_by(DESCRIBE_CHUNK) {
+        let (chunk_inputs, chunk_targets) = data.chunk(start..(start + DESCRIBE_CHUNK).min(data.len()));
+        for (input, target) in chunk_inputs.iter().zip(&chunk_targets) {
+            inputs.iter_mut().zip(input).for_each(|(column, &x)| column.add(x));
+            targets.iter_mut().zip(target).for_each(|(column, &y)| column.add(y));
+            let class = class_of(target);
+            if class_counts.len() <= class {
+                class_counts.resize(class + 1, 0);
+            }
+            class_counts[class] += 1;
+        }
+    }
+    DatasetSummary {
+        samples: data.len(),
+        inputs: inputs.iter().map(Accumulator::stats).collect(),
+        targets: targets.iter().map(Accumulator::stats).collect(),
+        class_counts,
+    }
+}
+
+/// A boxed dataset, so the kind of dataset can be chosen at runtime
+impl<D: Dataset + ?Sized> Dataset for Box<D> {
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    fn get(&self, i: usize) -> (Vec<f64>, Vec<f64>) {
+        (**self).get(i)
+    }
+
+    fn input_dim(&self) -> usize {
+        (**self).input_dim()
+    }
+
+    fn target_dim(&self) -> usize {
+        (**self).target_dim()
+    }
+
+    fn chunk(&self, range: Range<usize>) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+        (**self).chunk(range)
+    }
+}
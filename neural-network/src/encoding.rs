@@ -0,0 +1,136 @@
+/// Conversions between class labels and network targets
+///
+/// Classifiers in this crate have one sigmoid output per class and are
+/// trained on one-hot targets: 1 for the sample's class, 0 everywhere else.
+/// `one_hot_encode` turns integer labels into such targets and `argmax`
+/// turns an output back into a class index. `ClassEncoder` does the same
+/// for named labels, as found in the label column of a CSV file:
+///
+/// ```
+/// use neural_network::encoding::ClassEncoder;
+///
+/// let labels = ["cat", "dog", "cat", "bird"];
+/// let encoder = ClassEncoder::fit(&labels);
+/// assert_eq!(encoder.names(), ["bird", "cat", "dog"]);
+///
+/// let targets = encoder.encode_all(&labels).unwrap();
+/// assert_eq!(targets[1], vec![0.0, 0.0, 1.0]);
+/// assert_eq!(encoder.decode(&[0.1, 0.7, 0.3]), Some("cat"));
+/// ```
+use serde::{Deserialize, Serialize};
+
+/// Target vector of length `classes` with a 1 at `label`
+///
+/// # Panics
+///
+/// Panics if `label >= classes`
+pub fn one_hot(label: usize, classes: usize) -> Vec<f64> {
+    assert!(label < classes, "Label {} out of range for {} classes", label, classes);
+    let mut target = vec![0.0; classes];
+    target[label] = 1.0;
+    target
+}
+
+/// One-hot targets for integer class labels
+///
+/// With `classes` of `None` the number of classes is one more than the
+/// largest label.
+///
+/// # Errors
+///
+/// Returns an error if a label is not below `classes`
+pub fn one_hot_encode(labels: &[usize], classes: Option<usize>) -> anyhow::Result<Vec<Vec<f64>>> {
+    let classes = classes.unwrap_or_else(|| labels.iter().max().map_or(0, |&max| max + 1));
+    if let Some(i) = labels.iter().position(|&label| label >= classes) {
+        anyhow::bail!("Sample {} has label {}, expected fewer than {} classes", i, labels[i], classes);
+    }
+    Ok(labels.iter().map(|&label| one_hot(label, classes)).collect())
+}
+
+/// Index of the largest value, or `None` if `values` is empty
+///
+/// Ties go to the first index.
+pub fn argmax(values: &[f64]) -> Option<usize> {
+    (0..values.len()).reduce(|best, i| if values[i] > values[best] { i } else { best })
+}
+
+/// Class index predicted by every output
+pub fn decode_all(outputs: &[Vec<f64>]) -> Vec<Option<usize>> {
+    outputs.iter().map(|output| argmax(output)).collect()
+}
+
+/// Maps class names to one-hot targets and back
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassEncoder {
+    names: Vec<String>,
+}
+
+impl ClassEncoder {
+    /// Encoder whose class `i` is `names[i]`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a name appears twice
+    pub fn new(names: Vec<String>) -> anyhow::Result<Self> {
+        if let Some(i) = (1..names.len()).find(|&i| names[..i].contains(&names[i])) {
+            anyhow::bail!("Class name '{}' appears more than once", names[i]);
+        }
+        Ok(Self { names })
+    }
+
+    /// Encoder for the distinct `labels`, numbered in sorted order
+    ///
+    /// Sorting makes the class indices independent of sample order.
+    pub fn fit<S: AsRef<str>>(labels: &[S]) -> Self {
+        let mut names: Vec<String> = labels.iter().map(|label| label.as_ref().to_string()).collect();
+        names.sort();
+        names.dedup();
+        Self { names }
+    }
+
+    /// Class names, by class index
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Number of classes
+    pub fn classes(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Class index of `name`
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    /// One-hot target for `name`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `name` is not a known class
+    pub fn encode(&self, name: &str) -> anyhow::Result<Vec<f64>> {
+        let index = self
+            .index(name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown class '{}', expected one of {:?}", name, self.names))?;
+        Ok(one_hot(index, self.classes()))
+    }
+
+    /// One-hot targets for every label
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a label is not a known class
+    pub fn encode_all<S: AsRef<str>>(&self, labels: &[S]) -> anyhow::Result<Vec<Vec<f64>>> {
+        labels.iter().map(|label| self.encode(label.as_ref())).collect()
+    }
+
+    /// Name of the class an output predicts (its largest value)
+    ///
+    /// Returns `None` if the output is empty or wider than the class list.
+    pub fn decode(&self, output: &[f64]) -> Option<&str> {
+        if output.len() > self.classes() {
+            return None;
+        }
+        argmax(output).map(|i| self.names[i].as_str())
+    }
+}
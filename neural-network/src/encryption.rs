@@ -0,0 +1,204 @@
+/// Encryption of checkpoints at rest
+///
+/// Models trained on sensitive data can leak it through their weights, so
+/// their checkpoints can be encrypted with AES-256-GCM. The key is either
+/// read from a key file of 32 random bytes or derived from a password with
+/// PBKDF2-HMAC-SHA256 and a random salt. An encrypted file is laid out as:
+///
+/// ```text
+/// "NNCE" | kind (1 byte) | PBKDF2 rounds (u32 LE) | salt (16) | nonce (12) | ciphertext + tag
+/// ```
+///
+/// `kind` is 1 for a key file and 2 for a password; the rounds and salt are
+/// unused for key files. The header is authenticated along with the
+/// ciphertext, so a wrong key, a wrong password and a damaged file all fail
+/// to decrypt rather than producing garbage. Encryption wraps the whole file,
+/// after any compression, so `Checkpoint::load_with_secret` detects it from
+/// the leading bytes like the other encodings.
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{Context, Result};
+use rand::RngCore;
+use sha2::Sha256;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Leading bytes identifying an encrypted file
+const ENCRYPTED_MAGIC: &[u8; 4] = b"NNCE";
+
+/// PBKDF2 rounds for password-derived keys, as recommended by OWASP for
+/// PBKDF2-HMAC-SHA256
+pub const PBKDF2_ROUNDS: u32 = 600_000;
+
+const KIND_KEY: u8 = 1;
+const KIND_PASSWORD: u8 = 2;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = ENCRYPTED_MAGIC.len() + 1 + 4 + SALT_LEN + NONCE_LEN;
+
+/// Key material for encrypting and decrypting checkpoints
+#[derive(Clone, PartialEq, Eq)]
+pub enum Secret {
+    /// A password, stretched into a key with PBKDF2
+    Password(String),
+
+    /// A 256-bit key, used as is
+    Key([u8; 32]),
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Secret::Password(_) => f.write_str("Secret::Password(..)"),
+            Secret::Key(_) => f.write_str("Secret::Key(..)"),
+        }
+    }
+}
+
+impl Secret {
+    /// Read a key file holding 32 bytes, raw or as 64 hex digits
+    ///
+    /// Create one with e.g. `openssl rand -hex 32 > model.key`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or does not hold a 256-bit key
+    pub fn from_key_file(path: &Path) -> Result<Self> {
+        let contents = fs::read(path).with_context(|| format!("Failed to read key file {}", path.display()))?;
+        if let Ok(key) = <[u8; 32]>::try_from(contents.as_slice()) {
+            return Ok(Secret::Key(key));
+        }
+        let hex = String::from_utf8_lossy(&contents);
+        let hex = hex.trim();
+        if hex.len() != 64 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            anyhow::bail!("Key file {} must hold 32 bytes, raw or as 64 hex digits", path.display());
+        }
+        let mut key = [0; 32];
+        for (i, byte) in key.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16)?;
+        }
+        Ok(Secret::Key(key))
+    }
+
+    /// The AES key for a file with this header's salt and rounds
+    fn derive_key(&self, salt: &[u8], rounds: u32) -> [u8; 32] {
+        match self {
+            Secret::Key(key) => *key,
+            Secret::Password(password) => {
+                let mut key = [0; 32];
+                pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, rounds, &mut key);
+                key
+            }
+        }
+    }
+
+    fn kind(&self) -> u8 {
+        match self {
+            Secret::Key(_) => KIND_KEY,
+            Secret::Password(_) => KIND_PASSWORD,
+        }
+    }
+}
+
+/// Whether `bytes` start like a file written by `encrypt`
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(ENCRYPTED_MAGIC)
+}
+
+/// Encrypt `plaintext` with a fresh salt and nonce
+///
+/// # Errors
+///
+/// Returns an error if the cipher fails
+pub fn encrypt(plaintext: &[u8], secret: &Secret) -> Result<Vec<u8>> {
+    let rounds = match secret {
+        Secret::Key(_) => 0,
+        Secret::Password(_) => PBKDF2_ROUNDS,
+    };
+    let mut salt = [0; SALT_LEN];
+    let mut nonce = [0; NONCE_LEN];
+    rand::rngs::OsRng.fill_bytes(&mut salt);
+    rand::rngs::OsRng.fill_bytes(&mut nonce);
+
+    let mut bytes = ENCRYPTED_MAGIC.to_vec();
+    bytes.push(secret.kind());
+    bytes.extend_from_slice(&rounds.to_le_bytes());
+    bytes.extend_from_slice(&salt);
+    bytes.extend_from_slice(&nonce);
+
+    let cipher = Aes256Gcm::new(&secret.derive_key(&salt, rounds).into());
+    let payload = Payload { msg: plaintext, aad: &bytes };
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), payload)
+        .map_err(|_| anyhow::anyhow!("Failed to encrypt checkpoint"))?;
+    bytes.extend_from_slice(&ciphertext);
+    Ok(bytes)
+}
+
+/// Decrypt a file written by `encrypt`
+///
+/// # Errors
+///
+/// Returns an error if `bytes` are not encrypted, were encrypted with the
+/// other kind of secret, name a PBKDF2 round count other than
+/// `PBKDF2_ROUNDS`, or the secret is wrong or the data damaged
+pub fn decrypt(bytes: &[u8], secret: &Secret) -> Result<Vec<u8>> {
+    if !is_encrypted(bytes) {
+        anyhow::bail!("Not an encrypted checkpoint (missing header)");
+    }
+    if bytes.len() < HEADER_LEN {
+        anyhow::bail!("Encrypted checkpoint is truncated");
+    }
+    let (header, ciphertext) = bytes.split_at(HEADER_LEN);
+    let kind = header[4];
+    if kind != secret.kind() {
+        let needed = if kind == KIND_PASSWORD { "a password" } else { "a key file" };
+        anyhow::bail!("Checkpoint is encrypted with {}", needed);
+    }
+    let rounds = u32::from_le_bytes(header[5..9].try_into().unwrap());
+    // The header is untrusted until authenticated, so an arbitrary round
+    // count would let a crafted file stall key derivation
+    if kind == KIND_PASSWORD && rounds != PBKDF2_ROUNDS {
+        anyhow::bail!("Unsupported PBKDF2 round count {} (expected {})", rounds, PBKDF2_ROUNDS);
+    }
+    let (salt, nonce) = header[9..].split_at(SALT_LEN);
+
+    let cipher = Aes256Gcm::new(&secret.derive_key(salt, rounds).into());
+    let payload = Payload { msg: ciphertext, aad: header };
+    cipher
+        .decrypt(Nonce::from_slice(nonce), payload)
+        .map_err(|_| anyhow::anyhow!("Failed to decrypt checkpoint: wrong password or key, or the file is damaged"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_roundtrip_and_tampering() {
+        let secret = Secret::Key([7; 32]);
+        let mut bytes = encrypt(b"weights", &secret).unwrap();
+        assert!(is_encrypted(&bytes));
+        assert_eq!(decrypt(&bytes, &secret).unwrap(), b"weights");
+
+        assert!(decrypt(&bytes, &Secret::Key([8; 32])).is_err());
+        let err = decrypt(&bytes, &Secret::Password("weights".to_string())).unwrap_err();
+        assert!(err.to_string().contains("encrypted with a key file"), "{}", err);
+
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        assert!(decrypt(&bytes, &secret).is_err(), "Damaged data fails authentication");
+    }
+
+    #[test]
+    fn test_rejects_excessive_rounds() {
+        let mut bytes = ENCRYPTED_MAGIC.to_vec();
+        bytes.push(KIND_PASSWORD);
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        bytes.extend_from_slice(&[0; SALT_LEN + NONCE_LEN + 16]);
+
+        let err = decrypt(&bytes, &Secret::Password("secret".to_string())).unwrap_err();
+        assert!(err.to_string().contains("PBKDF2 round count"), "{}", err);
+    }
+}
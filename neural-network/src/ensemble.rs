@@ -0,0 +1,169 @@
+/// Ensemble prediction
+///
+/// This module combines several independently trained networks (typically the
+/// same architecture trained with different seeds) into a single predictor.
+/// Outputs are either averaged or decided by majority vote.
+use crate::matrix::Matrix;
+use crate::network::Network;
+use anyhow::{Context, Result, bail};
+use std::path::Path;
+
+/// How member outputs are combined into an ensemble prediction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Combine {
+    /// Mean of the member outputs
+    #[default]
+    Average,
+
+    /// Share of members voting for each output
+    ///
+    /// With several outputs each member votes for its largest output; with a
+    /// single output each member votes for it when it exceeds 0.5.
+    Vote,
+}
+
+/// A group of networks whose predictions are combined
+#[derive(Debug, Clone)]
+pub struct Ensemble {
+    /// Member networks, all with the same input and output sizes
+    pub networks: Vec<Network>,
+
+    /// Combination strategy used by `predict`
+    pub combine: Combine,
+}
+
+impl Ensemble {
+    /// Create an ensemble from trained networks
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `networks` is empty or the members disagree on the
+    /// number of inputs or outputs
+    pub fn new(networks: Vec<Network>, combine: Combine) -> Result<Self> {
+        let Some(first) = networks.first() else {
+            bail!("An ensemble needs at least one network");
+        };
+        let inputs = first.layers[0];
+        let outputs = first.layers[first.layers.len() - 1];
+
+        for (i, network) in networks.iter().enumerate() {
+            let layers = &network.layers;
+            if layers[0] != inputs || layers[layers.len() - 1] != outputs {
+                bail!(
+                    "Network {} has {} inputs and {} outputs, expected {} and {}",
+                    i,
+                    layers[0],
+                    layers[layers.len() - 1],
+                    inputs,
+                    outputs
+                );
+            }
+        }
+
+        Ok(Ensemble { networks, combine })
+    }
+
+    /// Load every checkpoint and combine the networks into an ensemble
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any checkpoint cannot be loaded or the networks are
+    /// incompatible (see `new`)
+    pub fn from_checkpoints<P: AsRef<Path>>(paths: &[P], combine: Combine) -> Result<Self> {
+        let networks = paths
+            .iter()
+            .map(|path| {
+                let path = path.as_ref();
+                Network::load_checkpoint(path)
+                    .map(|(network, _)| network)
+                    .with_context(|| format!("Failed to load ensemble member {}", path.display()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Self::new(networks, combine)
+    }
+
+    /// Number of member networks
+    pub fn len(&self) -> usize {
+        self.networks.len()
+    }
+
+    /// Whether the ensemble has no members
+    pub fn is_empty(&self) -> bool {
+        self.networks.is_empty()
+    }
+
+    /// Output of every member for one input
+    pub fn member_outputs(&mut self, input: &[f64]) -> Vec<Vec<f64>> {
+        self.networks
+            .iter_mut()
+            .map(|network| network.feed_forward(Matrix::from(input.to_vec())).data)
+            .collect()
+    }
+
+    /// Combined prediction for one input
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input` does not match the size of the input layer
+    pub fn predict(&mut self, input: &[f64]) -> Vec<f64> {
+        let outputs = self.member_outputs(input);
+        combine_outputs(&outputs, self.combine)
+    }
+}
+
+/// Combine member outputs with the given strategy
+pub fn combine_outputs(outputs: &[Vec<f64>], combine: Combine) -> Vec<f64> {
+    let width = outputs.first().map_or(0, Vec::len);
+    let members = outputs.len() as f64;
+    let mut combined = vec![0.0; width];
+
+    for output in outputs {
+        match combine {
+            Combine::Average => {
+                for (c, o) in combined.iter_mut().zip(output) {
+                    *c += o;
+                }
+            }
+            Combine::Vote if width == 1 => {
+                if output[0] > 0.5 {
+                    combined[0] += 1.0;
+                }
+            }
+            Combine::Vote => {
+                let winner = output
+                    .iter()
+                    .enumerate()
+                    .max_by(|a, b| a.1.total_cmp(b.1))
+                    .map_or(0, |(i, _)| i);
+                combined[winner] += 1.0;
+            }
+        }
+    }
+
+    combined.iter_mut().for_each(|c| *c /= members);
+    combined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combine_average() {
+        let outputs = vec![vec![0.2, 0.8], vec![0.4, 0.6]];
+        let combined = combine_outputs(&outputs, Combine::Average);
+        assert!((combined[0] - 0.3).abs() < 1e-12);
+        assert!((combined[1] - 0.7).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_combine_vote() {
+        let outputs = vec![vec![0.9, 0.1], vec![0.2, 0.8], vec![0.6, 0.4], vec![0.7, 0.3]];
+        assert_eq!(combine_outputs(&outputs, Combine::Vote), vec![0.75, 0.25]);
+
+        let single = vec![vec![0.9], vec![0.4], vec![0.7]];
+        let combined = combine_outputs(&single, Combine::Vote);
+        assert!((combined[0] - 2.0 / 3.0).abs() < 1e-12);
+    }
+}
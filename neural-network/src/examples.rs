@@ -1,8 +1,20 @@
 /// Training examples for logic gates
 ///
 /// This module provides pre-configured examples of classic machine learning problems:
-/// AND, OR, and XOR logic gates. Each example includes the training data, recommended
-/// architecture, and hyperparameters.
+/// AND, OR, and XOR logic gates, small classification tasks and the `sine`
+/// regression. Each example includes the training data, recommended
+/// architecture, and hyperparameters. `synthetic()` additionally generates
+/// benchmark datasets of controllable difficulty with a known accuracy ceiling,
+/// and `spirals()`, `moons()` and `circles()` generate nonlinear 2D datasets
+/// whose decision boundaries are easy to plot. `make_blobs()` generates
+/// Gaussian clusters of any size and dimension. Applications add their own
+/// named datasets at runtime with `register_example`.
+use crate::dataset::{Dataset, VecDataset};
+use crate::encoding::one_hot;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::{PoisonError, RwLock, RwLockReadGuard};
+
 /// Represents a training example with inputs, targets, and recommended configuration
 #[derive(Debug, Clone)]
 pub struct Example {
@@ -36,7 +48,8 @@ pub struct Example {
 ///
 /// # Returns
 ///
-/// `Some(Example)` if the example exists, `None` otherwise
+/// `Some(Example)` if a built-in or registered example (see
+/// `register_example`) has that name, `None` otherwise
 ///
 /// # Examples
 ///
@@ -342,15 +355,42 @@ pub fn get_example(name: &str) -> Option<Example> {
             recommended_lr: 0.5,
         }),
 
-        _ => None,
+        "sine" => {
+            // x in [-pi, pi] scaled to [-1, 1], sin(x) scaled to [0, 1]
+            let xs = (0..SINE_SAMPLES).map(|i| -1.0 + 2.0 * i as f64 / (SINE_SAMPLES - 1) as f64);
+            let inputs: Vec<Vec<f64>> = xs.map(|x| vec![x]).collect();
+            let targets = inputs
+                .iter()
+                .map(|x| vec![0.5 + 0.5 * (x[0] * std::f64::consts::PI).sin()])
+                .collect();
+            Some(Example {
+                name: "sine",
+                description: "Sine wave regression - learns y = sin(x) over one period, with x scaled to [-1, 1] and y to [0, 1]. Demonstrates continuous outputs rather than classes.",
+                inputs,
+                targets,
+                recommended_arch: vec![1, 8, 1],
+                recommended_epochs: 20000,
+                recommended_lr: 0.5,
+            })
+        }
+
+        "spirals" => Some(spirals(GENERATED_SAMPLES_PER_CLASS, 0.0, GENERATED_SEED)),
+        "moons" => Some(moons(GENERATED_SAMPLES_PER_CLASS, 0.1, GENERATED_SEED)),
+        "circles" => Some(circles(GENERATED_SAMPLES_PER_CLASS, 0.05, GENERATED_SEED)),
+
+        _ => registered_example(name),
     }
 }
 
+/// Number of evenly spaced points in the `sine` example
+pub const SINE_SAMPLES: usize = 32;
+
 /// List all available example names
 ///
 /// # Returns
 ///
-/// A vector of example names that can be passed to `get_example()`
+/// A vector of example names that can be passed to `get_example()`: the
+/// built-in examples followed by registered ones in registration order
 ///
 /// # Examples
 ///
@@ -361,7 +401,457 @@ pub fn get_example(name: &str) -> Option<Example> {
 /// assert!(examples.contains(&"xor"));
 /// ```
 pub fn list_examples() -> Vec<&'static str> {
-    vec!["and", "or", "xor", "parity3", "quadrant", "adder2", "iris", "pattern3x3"]
+    let mut names = BUILTIN_EXAMPLES.to_vec();
+    names.extend(read_registry().iter().map(|ex| ex.name));
+    names
+}
+
+/// Names of the compile-time examples
+const BUILTIN_EXAMPLES: [&str; 12] = [
+    "and", "or", "xor", "parity3", "quadrant", "adder2", "iris", "pattern3x3", "sine", "spirals", "moons", "circles",
+];
+
+/// Examples registered at runtime, in registration order
+static REGISTRY: RwLock<Vec<Example>> = RwLock::new(Vec::new());
+
+/// The registry, recovered if a thread panicked while holding it
+fn read_registry() -> RwLockReadGuard<'static, Vec<Example>> {
+    REGISTRY.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn registered_example(name: &str) -> Option<Example> {
+    read_registry().iter().find(|ex| ex.name == name).cloned()
+}
+
+/// Make `example` available by its name to `get_example`
+///
+/// Applications use this to train on their own data by name, e.g. through
+/// the WASM `fromExample` or the server's `/api/train`. Registering a name
+/// again replaces the earlier example. Built-in examples cannot be replaced.
+///
+/// # Errors
+///
+/// Returns an error if the name is empty or built in, the example has no
+/// samples, or its samples do not match `recommended_arch`
+///
+/// # Examples
+///
+/// ```
+/// use neural_network::examples::{Example, get_example, register_example};
+///
+/// let nand = Example::custom(
+///     "nand",
+///     "NAND gate",
+///     vec![vec![0.0, 0.0], vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]],
+///     vec![vec![1.0], vec![1.0], vec![1.0], vec![0.0]],
+/// )
+/// .unwrap();
+/// register_example(nand).unwrap();
+/// assert_eq!(get_example("nand").unwrap().targets[3], vec![0.0]);
+/// ```
+pub fn register_example(example: Example) -> anyhow::Result<()> {
+    if example.name.is_empty() {
+        anyhow::bail!("Example name must not be empty");
+    }
+    if BUILTIN_EXAMPLES.contains(&example.name) {
+        anyhow::bail!("'{}' is a built-in example and cannot be replaced", example.name);
+    }
+    let data = VecDataset::new(example.inputs.clone(), example.targets.clone())?;
+    if data.is_empty() {
+        anyhow::bail!("Example '{}' has no samples", example.name);
+    }
+    let arch = &example.recommended_arch;
+    if arch.len() < 2 || arch[0] != data.input_dim() || arch[arch.len() - 1] != data.target_dim() {
+        anyhow::bail!(
+            "Example '{}' has {} inputs and {} targets per sample, but recommended architecture {:?}",
+            example.name,
+            data.input_dim(),
+            data.target_dim(),
+            arch
+        );
+    }
+
+    let mut registry = REGISTRY.write().unwrap_or_else(PoisonError::into_inner);
+    match registry.iter_mut().find(|ex| ex.name == example.name) {
+        Some(existing) => *existing = example,
+        None => registry.push(example),
+    }
+    Ok(())
+}
+
+/// Remove a registered example, returning it
+///
+/// Built-in examples are never removed.
+pub fn unregister_example(name: &str) -> Option<Example> {
+    let mut registry = REGISTRY.write().unwrap_or_else(PoisonError::into_inner);
+    let index = registry.iter().position(|ex| ex.name == name)?;
+    Some(registry.remove(index))
+}
+
+impl Example {
+    /// An example built from runtime data, ready for `register_example`
+    ///
+    /// The recommended architecture has one hidden layer of
+    /// `input_dim + target_dim` neurons, trained for 1000 epochs at learning
+    /// rate 0.5; adjust the fields to taste. The name and description are
+    /// leaked to give them the `'static` lifetime of the built-in examples,
+    /// so create custom examples once rather than per request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are no samples or they differ in size
+    pub fn custom(
+        name: &str,
+        description: &str,
+        inputs: Vec<Vec<f64>>,
+        targets: Vec<Vec<f64>>,
+    ) -> anyhow::Result<Self> {
+        let data = VecDataset::new(inputs, targets)?;
+        if data.is_empty() {
+            anyhow::bail!("Example '{}' has no samples", name);
+        }
+        let (input_dim, target_dim) = (data.input_dim(), data.target_dim());
+        let (inputs, targets) = data.into_vecs();
+        Ok(Self {
+            name: Box::leak(name.into()),
+            description: Box::leak(description.into()),
+            inputs,
+            targets,
+            recommended_arch: vec![input_dim, input_dim + target_dim, target_dim],
+            recommended_epochs: 1000,
+            recommended_lr: 0.5,
+        })
+    }
+}
+
+/// Samples per class of the built-in `spirals`, `moons` and `circles` examples
+pub const GENERATED_SAMPLES_PER_CLASS: usize = 50;
+
+/// Seed of the built-in `spirals`, `moons` and `circles` examples
+pub const GENERATED_SEED: u64 = 42;
+
+/// Turns each arm of `spirals()` makes around the origin
+const SPIRAL_TURNS: f64 = 1.25;
+
+/// Two interleaved spiral arms, one per class
+///
+/// Arm 0 winds outwards from the origin for `SPIRAL_TURNS` turns; arm 1 is
+/// arm 0 rotated by half a turn. `noise` is the standard deviation of
+/// Gaussian noise added to each coordinate. Inputs lie roughly in
+/// [-1, 1] x [-1, 1]; targets are 0 for arm 0 and 1 for arm 1. The same
+/// `seed` always yields the same dataset.
+///
+/// # Examples
+///
+/// ```
+/// use neural_network::examples::spirals;
+///
+/// let data = spirals(100, 0.02, 7);
+/// assert_eq!(data.inputs.len(), 200);
+/// ```
+pub fn spirals(samples_per_class: usize, noise: f64, seed: u64) -> Example {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut inputs = Vec::with_capacity(2 * samples_per_class);
+    let mut targets = Vec::with_capacity(2 * samples_per_class);
+
+    for class in 0..2 {
+        let sign = if class == 0 { 1.0 } else { -1.0 };
+        for i in 0..samples_per_class {
+            // Start a little way out so the arms do not meet at the origin
+            let radius = 0.1 + 0.9 * i as f64 / (samples_per_class.max(2) - 1) as f64;
+            let angle = radius * SPIRAL_TURNS * 2.0 * std::f64::consts::PI;
+            inputs.push(vec![
+                sign * radius * angle.cos() + noise * standard_normal(&mut rng),
+                sign * radius * angle.sin() + noise * standard_normal(&mut rng),
+            ]);
+            targets.push(vec![class as f64]);
+        }
+    }
+
+    Example {
+        name: "spirals",
+        description: "Two spirals - two interleaved spiral arms. A hard nonlinear problem that needs two hidden layers.",
+        inputs,
+        targets,
+        recommended_arch: vec![2, 16, 16, 1],
+        recommended_epochs: 20000,
+        recommended_lr: 0.5,
+    }
+}
+
+/// Two interleaving half circles, one per class
+///
+/// The classic "moons" dataset, shifted and scaled so the inputs lie roughly
+/// in [-1, 1] x [-0.5, 0.5]. `noise` is the standard deviation of Gaussian
+/// noise added to each coordinate before scaling. Targets are 0 for the
+/// upper moon and 1 for the lower one.
+pub fn moons(samples_per_class: usize, noise: f64, seed: u64) -> Example {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut inputs = Vec::with_capacity(2 * samples_per_class);
+    let mut targets = Vec::with_capacity(2 * samples_per_class);
+
+    for class in 0..2 {
+        for i in 0..samples_per_class {
+            let angle = std::f64::consts::PI * i as f64 / (samples_per_class.max(2) - 1) as f64;
+            let (x, y) = if class == 0 {
+                (angle.cos(), angle.sin())
+            } else {
+                (1.0 - angle.cos(), 0.5 - angle.sin())
+            };
+            // Center on the origin: x spans [-1, 2] and y [-0.5, 1]
+            let x = x + noise * standard_normal(&mut rng) - 0.5;
+            let y = y + noise * standard_normal(&mut rng) - 0.25;
+            inputs.push(vec![x / 1.5, y / 1.5]);
+            targets.push(vec![class as f64]);
+        }
+    }
+
+    Example {
+        name: "moons",
+        description: "Two moons - two interleaving half circles. Not linearly separable, but a small hidden layer bends the boundary between them.",
+        inputs,
+        targets,
+        recommended_arch: vec![2, 8, 1],
+        recommended_epochs: 10000,
+        recommended_lr: 0.5,
+    }
+}
+
+/// A small circle inside a larger one, one per class
+///
+/// The outer circle has radius 1 and the inner one radius 0.5, both centered
+/// on the origin. `noise` is the standard deviation of Gaussian noise added
+/// to each coordinate. Targets are 0 for the outer circle and 1 for the inner.
+pub fn circles(samples_per_class: usize, noise: f64, seed: u64) -> Example {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut inputs = Vec::with_capacity(2 * samples_per_class);
+    let mut targets = Vec::with_capacity(2 * samples_per_class);
+
+    for (class, radius) in [(0, 1.0), (1, 0.5)] {
+        for i in 0..samples_per_class {
+            let angle = 2.0 * std::f64::consts::PI * i as f64 / samples_per_class as f64;
+            inputs.push(vec![
+                radius * angle.cos() + noise * standard_normal(&mut rng),
+                radius * angle.sin() + noise * standard_normal(&mut rng),
+            ]);
+            targets.push(vec![class as f64]);
+        }
+    }
+
+    Example {
+        name: "circles",
+        description: "Concentric circles - one class surrounds the other. Needs a closed decision boundary, which no single line can draw.",
+        inputs,
+        targets,
+        recommended_arch: vec![2, 8, 1],
+        recommended_epochs: 10000,
+        recommended_lr: 0.5,
+    }
+}
+
+/// Gaussian clusters around given centers, one class per center
+///
+/// `n_samples` are split as evenly as possible between the centers, earlier
+/// centers getting the remainder, and each sample is its center plus
+/// Gaussian noise of standard deviation `std` in every coordinate. Targets
+/// are one-hot with one output per center; samples are ordered by class.
+/// The same `seed` always yields the same dataset, of any size, which makes
+/// this handy for benchmarks and tests.
+///
+/// # Panics
+///
+/// Panics if `centers` is empty, the centers differ in length or have no
+/// coordinates, or `std` is negative or not finite
+///
+/// # Examples
+///
+/// ```
+/// use neural_network::examples::make_blobs;
+///
+/// let data = make_blobs(1000, &[vec![-1.0, 0.0], vec![1.0, 0.0], vec![0.0, 2.0]], 0.3, 7);
+/// assert_eq!(data.inputs.len(), 1000);
+/// assert_eq!(data.recommended_arch, vec![2, 5, 3]);
+/// ```
+pub fn make_blobs(n_samples: usize, centers: &[Vec<f64>], std: f64, seed: u64) -> Example {
+    assert!(!centers.is_empty(), "Blobs need at least one center");
+    let dim = centers[0].len();
+    assert!(
+        dim > 0 && centers.iter().all(|c| c.len() == dim),
+        "Blob centers must all have the same, non-zero number of coordinates"
+    );
+    assert!(std.is_finite() && std >= 0.0, "Blob standard deviation must be a non-negative finite number");
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let classes = centers.len();
+    let mut inputs = Vec::with_capacity(n_samples);
+    let mut targets = Vec::with_capacity(n_samples);
+
+    for (class, center) in centers.iter().enumerate() {
+        let count = n_samples / classes + usize::from(class < n_samples % classes);
+        let target = one_hot(class, classes);
+        for _ in 0..count {
+            inputs.push(center.iter().map(|c| c + std * standard_normal(&mut rng)).collect());
+            targets.push(target.clone());
+        }
+    }
+
+    Example {
+        name: "blobs",
+        description: "Gaussian blobs - one cluster of points per class around a fixed center.",
+        inputs,
+        targets,
+        recommended_arch: vec![dim, dim + classes, classes],
+        recommended_epochs: 2000,
+        recommended_lr: 0.1,
+    }
+}
+
+/// Number of samples generated per class by `synthetic()`
+pub const SYNTHETIC_SAMPLES_PER_CLASS: usize = 100;
+
+/// A generated benchmark dataset with a known accuracy ceiling
+///
+/// Each class is an isotropic unit-variance Gaussian. The class means lie on a
+/// line through the origin along a random unit `direction`, spaced
+/// `separability` standard deviations apart. Because the means are collinear,
+/// the optimal decision rule only depends on the projection onto `direction`,
+/// which gives a closed-form Bayes-optimal accuracy.
+#[derive(Debug, Clone)]
+pub struct SyntheticExample {
+    /// Generated samples with one-hot targets, usable like any other example
+    pub example: Example,
+
+    /// Accuracy of the Bayes-optimal classifier on the generating distribution
+    pub bayes_accuracy: f64,
+
+    /// Unit vector along which the class means are placed
+    pub direction: Vec<f64>,
+
+    /// Distance between neighbouring class means, in standard deviations
+    pub separability: f64,
+}
+
+impl SyntheticExample {
+    /// Number of classes in the dataset
+    pub fn num_classes(&self) -> usize {
+        self.example.recommended_arch[self.example.recommended_arch.len() - 1]
+    }
+
+    /// Class chosen by the Bayes-optimal classifier for an input
+    ///
+    /// This is the ground truth the generated accuracy ceiling refers to: the
+    /// class whose mean is closest to the projection of `input` onto `direction`.
+    pub fn bayes_predict(&self, input: &[f64]) -> usize {
+        let projection: f64 = input.iter().zip(&self.direction).map(|(x, d)| x * d).sum();
+        let offset = (self.num_classes() - 1) as f64 / 2.0;
+        let position = if self.separability > 0.0 {
+            projection / self.separability + offset
+        } else {
+            0.0
+        };
+        position.round().clamp(0.0, (self.num_classes() - 1) as f64) as usize
+    }
+}
+
+/// Generate a classification dataset with a known Bayes-optimal accuracy
+///
+/// Produces `SYNTHETIC_SAMPLES_PER_CLASS` samples for each of `n_classes`
+/// classes in `n_features` dimensions. `separability` is the distance between
+/// neighbouring class means in standard deviations: `0.0` makes the classes
+/// indistinguishable (ceiling `1 / n_classes`), while values around `4.0` or
+/// more make them almost perfectly separable. The same `seed` always yields the
+/// same dataset.
+///
+/// # Panics
+///
+/// Panics if `n_features` is zero, `n_classes` is less than two, or
+/// `separability` is negative or not finite.
+///
+/// # Examples
+///
+/// ```
+/// use neural_network::examples::synthetic;
+///
+/// let data = synthetic(4, 3, 2.0, 42);
+/// assert_eq!(data.example.inputs[0].len(), 4);
+/// assert_eq!(data.example.targets[0].len(), 3);
+/// assert!(data.bayes_accuracy > 1.0 / 3.0 && data.bayes_accuracy < 1.0);
+/// ```
+pub fn synthetic(n_features: usize, n_classes: usize, separability: f64, seed: u64) -> SyntheticExample {
+    assert!(n_features > 0, "Synthetic data needs at least one feature");
+    assert!(n_classes >= 2, "Synthetic data needs at least two classes");
+    assert!(
+        separability.is_finite() && separability >= 0.0,
+        "Separability must be a non-negative finite number"
+    );
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut gaussian = move || standard_normal(&mut rng);
+
+    let mut direction: Vec<f64> = (0..n_features).map(|_| gaussian()).collect();
+    let length = direction.iter().map(|d| d * d).sum::<f64>().sqrt();
+    if length > 0.0 {
+        direction.iter_mut().for_each(|d| *d /= length);
+    } else {
+        direction[0] = 1.0;
+    }
+
+    let offset = (n_classes - 1) as f64 / 2.0;
+    let mut inputs = Vec::with_capacity(n_classes * SYNTHETIC_SAMPLES_PER_CLASS);
+    let mut targets = Vec::with_capacity(n_classes * SYNTHETIC_SAMPLES_PER_CLASS);
+
+    for class in 0..n_classes {
+        let distance = (class as f64 - offset) * separability;
+        let mut target = vec![0.0; n_classes];
+        target[class] = 1.0;
+
+        for _ in 0..SYNTHETIC_SAMPLES_PER_CLASS {
+            inputs.push(direction.iter().map(|d| d * distance + gaussian()).collect());
+            targets.push(target.clone());
+        }
+    }
+
+    // Outer classes are only misclassified on one side, inner classes on both
+    let tail = standard_normal_cdf(-separability / 2.0);
+    let bayes_accuracy = 1.0 - 2.0 * (n_classes - 1) as f64 / n_classes as f64 * tail;
+
+    SyntheticExample {
+        example: Example {
+            name: "synthetic",
+            description: "Gaussian classes spaced along a random direction, with a known Bayes-optimal accuracy ceiling.",
+            inputs,
+            targets,
+            recommended_arch: vec![n_features, n_features + n_classes, n_classes],
+            recommended_epochs: 2000,
+            recommended_lr: 0.1,
+        },
+        bayes_accuracy,
+        direction,
+        separability,
+    }
+}
+
+/// Draw from the standard normal distribution
+fn standard_normal(rng: &mut StdRng) -> f64 {
+    // Box-Muller transform; 1.0 - gen keeps the log argument in (0, 1]
+    let u1: f64 = 1.0 - rng.r#gen::<f64>();
+    let u2: f64 = rng.r#gen::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+/// Standard normal cumulative distribution function
+fn standard_normal_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+}
+
+/// Error function (Abramowitz and Stegun 7.1.26, absolute error below 1.5e-7)
+fn erf(x: f64) -> f64 {
+    let sign = if x < 0.0 { -1.0 } else { 1.0 };
+    let x = x.abs();
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t * (0.254829592
+        + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    sign * (1.0 - poly * (-x * x).exp())
 }
 
 #[cfg(test)]
@@ -416,4 +906,17 @@ mod tests {
             assert!(ex.recommended_lr > 0.0 && ex.recommended_lr <= 1.0);
         }
     }
+
+    #[test]
+    fn test_standard_normal_cdf() {
+        assert!((standard_normal_cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((standard_normal_cdf(1.0) - 0.841_344_746).abs() < 1e-6);
+        assert!((standard_normal_cdf(-1.96) - 0.024_997_895).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_synthetic_bayes_accuracy_bounds() {
+        assert!((synthetic(2, 4, 0.0, 1).bayes_accuracy - 0.25).abs() < 1e-7);
+        assert!(synthetic(2, 2, 10.0, 1).bayes_accuracy > 0.999);
+    }
 }
@@ -0,0 +1,209 @@
+/// Numerical gradient check of back-propagation
+///
+/// Compares the gradients `Network::compute_gradients` returns with central
+/// finite differences of the same squared-error loss,
+/// `(L(p + epsilon) - L(p - epsilon)) / (2 * epsilon)` for every weight and
+/// bias, both summed over a set of samples. Each layer is scored by the
+/// relative error `|analytic - numeric| / (|analytic| + |numeric|)` of its
+/// weight and bias gradients (L2 norms over the layer). In f64 a correct
+/// backward pass stays around 1e-8 or below; a large error means the
+/// derivative of an activation, or the backward pass itself, is wrong.
+///
+/// ```
+/// use neural_network::{activations::SIGMOID, gradcheck::GradCheck};
+///
+/// let report = GradCheck::default().run_random(&[2, 3, 1], SIGMOID, 10, 42);
+/// assert!(report.passed(), "max relative error {}", report.max_error());
+/// ```
+use crate::activations::Activation;
+use crate::matrix::Matrix;
+use crate::network::{Gradients, Network};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// Settings of a gradient check
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradCheck {
+    /// Step added to and subtracted from each parameter
+    pub epsilon: f64,
+
+    /// Largest relative error a layer may have for the check to pass
+    pub tolerance: f64,
+}
+
+impl Default for GradCheck {
+    fn default() -> Self {
+        Self {
+            epsilon: 1e-5,
+            tolerance: 1e-6,
+        }
+    }
+}
+
+/// Relative errors of one layer's gradients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerError {
+    /// Index of the weight layer, from the input
+    pub layer: usize,
+
+    /// Relative error of the weight gradients
+    pub weights: f64,
+
+    /// Relative error of the bias gradients
+    pub biases: f64,
+}
+
+impl LayerError {
+    /// The larger of the weight and bias errors, NaN if either is NaN
+    pub fn max(&self) -> f64 {
+        if self.weights.is_nan() { self.weights } else { self.weights.max(self.biases) }
+    }
+}
+
+/// Outcome of a gradient check
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GradCheckReport {
+    /// Per-layer relative errors, from the input
+    pub layers: Vec<LayerError>,
+
+    /// Number of samples the gradients were summed over
+    pub samples: usize,
+
+    /// Finite-difference step that was used
+    pub epsilon: f64,
+
+    /// Tolerance `passed` compares against
+    pub tolerance: f64,
+}
+
+impl GradCheckReport {
+    /// Largest relative error of any layer
+    pub fn max_error(&self) -> f64 {
+        self.layers.iter().map(LayerError::max).fold(0.0, |max, error| if error > max || error.is_nan() { error } else { max })
+    }
+
+    /// Whether every layer is within the tolerance
+    ///
+    /// A NaN error fails the check.
+    pub fn passed(&self) -> bool {
+        self.layers.iter().all(|layer| layer.max() <= self.tolerance)
+    }
+}
+
+impl GradCheck {
+    /// Check the gradients of `network` on the given samples
+    ///
+    /// The network itself is left untouched; a copy is perturbed.
+    pub fn run(&self, network: &Network, inputs: &[Vec<f64>], targets: &[Vec<f64>]) -> GradCheckReport {
+        let mut network = network.clone();
+        let samples: Vec<(Matrix, Matrix)> = inputs
+            .iter()
+            .zip(targets)
+            .map(|(input, target)| (Matrix::from(input.clone()), Matrix::from(target.clone())))
+            .collect();
+
+        let analytic = analytic_gradients(&mut network, &samples);
+        let numeric = self.numeric_gradients(&mut network, &samples);
+        let layers = (0..analytic.weights.len())
+            .map(|layer| LayerError {
+                layer,
+                weights: relative_error(&analytic.weights[layer], &numeric.weights[layer]),
+                biases: relative_error(&analytic.biases[layer], &numeric.biases[layer]),
+            })
+            .collect();
+
+        GradCheckReport {
+            layers,
+            samples: samples.len(),
+            epsilon: self.epsilon,
+            tolerance: self.tolerance,
+        }
+    }
+
+    /// Check a seeded random network of the given layer sizes on `samples`
+    /// random samples, with inputs in [-1, 1) and targets in [0, 1)
+    pub fn run_random(&self, layers: &[usize], activation: Activation, samples: usize, seed: u64) -> GradCheckReport {
+        let network = Network::new_seeded(layers.to_vec(), activation, 0.5, seed);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (inputs, outputs) = (layers[0], layers[layers.len() - 1]);
+        let inputs: Vec<Vec<f64>> = (0..samples)
+            .map(|_| (0..inputs).map(|_| rng.gen_range(-1.0..1.0)).collect())
+            .collect();
+        let targets: Vec<Vec<f64>> = (0..samples)
+            .map(|_| (0..outputs).map(|_| rng.gen_range(0.0..1.0)).collect())
+            .collect();
+        self.run(&network, &inputs, &targets)
+    }
+
+    /// Central differences of the loss for every weight and bias
+    fn numeric_gradients(&self, network: &mut Network, samples: &[(Matrix, Matrix)]) -> Gradients {
+        let mut gradients = Gradients {
+            weights: network.weights.iter().map(|w| Matrix::zeros(w.rows, w.cols)).collect(),
+            biases: network.biases.iter().map(|b| Matrix::zeros(b.rows, b.cols)).collect(),
+        };
+        for layer in 0..gradients.weights.len() {
+            for i in 0..gradients.weights[layer].data.len() {
+                gradients.weights[layer].data[i] = self.derivative(network, samples, |n| &mut n.weights, layer, i);
+            }
+            for i in 0..gradients.biases[layer].data.len() {
+                gradients.biases[layer].data[i] = self.derivative(network, samples, |n| &mut n.biases, layer, i);
+            }
+        }
+        gradients
+    }
+
+    /// Central difference of the loss for element `i` of `parameter(network)[layer]`
+    fn derivative(
+        &self,
+        network: &mut Network,
+        samples: &[(Matrix, Matrix)],
+        parameter: fn(&mut Network) -> &mut Vec<Matrix>,
+        layer: usize,
+        i: usize,
+    ) -> f64 {
+        let original = parameter(network)[layer].data[i];
+        parameter(network)[layer].data[i] = original + self.epsilon;
+        let plus = loss(network, samples);
+        parameter(network)[layer].data[i] = original - self.epsilon;
+        let minus = loss(network, samples);
+        parameter(network)[layer].data[i] = original;
+        (plus - minus) / (2.0 * self.epsilon)
+    }
+}
+
+/// Back-propagated gradients summed over the samples
+fn analytic_gradients(network: &mut Network, samples: &[(Matrix, Matrix)]) -> Gradients {
+    let mut total = Gradients {
+        weights: network.weights.iter().map(|w| Matrix::zeros(w.rows, w.cols)).collect(),
+        biases: network.biases.iter().map(|b| Matrix::zeros(b.rows, b.cols)).collect(),
+    };
+    for (input, target) in samples {
+        let output = network.feed_forward(input.clone());
+        let gradients = network.compute_gradients(&output, target);
+        let sums = total.weights.iter_mut().chain(total.biases.iter_mut());
+        for (sum, gradient) in sums.zip(gradients.weights.iter().chain(&gradients.biases)) {
+            sum.data.iter_mut().zip(&gradient.data).for_each(|(s, g)| *s += g);
+        }
+    }
+    total
+}
+
+/// Squared-error loss `0.5 * sum((target - output)^2)` summed over the samples
+fn loss(network: &mut Network, samples: &[(Matrix, Matrix)]) -> f64 {
+    samples
+        .iter()
+        .map(|(input, target)| {
+            let output = network.feed_forward(input.clone());
+            0.5 * output.data.iter().zip(&target.data).map(|(o, t)| (t - o).powi(2)).sum::<f64>()
+        })
+        .sum()
+}
+
+/// `|a - b| / (|a| + |b|)` over all elements, 0 when both are zero
+fn relative_error(analytic: &Matrix, numeric: &Matrix) -> f64 {
+    let norm = |values: &mut dyn Iterator<Item = f64>| values.map(|v| v * v).sum::<f64>().sqrt();
+    let difference = norm(&mut analytic.data.iter().zip(&numeric.data).map(|(a, n)| a - n));
+    let scale = norm(&mut analytic.data.iter().copied()) + norm(&mut numeric.data.iter().copied());
+    if scale == 0.0 { 0.0 } else { difference / scale }
+}
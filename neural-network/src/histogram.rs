@@ -0,0 +1,112 @@
+/// Binned histograms of network parameters
+///
+/// This module summarizes the distribution of weights and biases per layer so
+/// visualization front-ends (the `visualize` tool, the WASM bindings) can draw
+/// them without shipping every parameter value.
+use serde::{Deserialize, Serialize};
+
+/// Equal-width histogram over a closed value range
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Histogram {
+    /// Lower edge of the first bin
+    pub min: f64,
+
+    /// Upper edge of the last bin
+    pub max: f64,
+
+    /// Number of values falling into each bin
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    /// Bin `values` into `bins` equal-width bins spanning their range
+    ///
+    /// The last bin includes the maximum. When all values are equal the range
+    /// is widened by 0.5 on each side so the values land in the middle bin.
+    /// Non-finite values are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bins` is zero
+    pub fn from_values(values: &[f64], bins: usize) -> Self {
+        assert!(bins > 0, "Histogram needs at least one bin");
+
+        let finite = values.iter().copied().filter(|v| v.is_finite());
+        let (mut min, mut max) = finite
+            .clone()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+
+        if min > max {
+            // No finite values
+            min = 0.0;
+            max = 0.0;
+        }
+        if max - min <= f64::EPSILON {
+            min -= 0.5;
+            max += 0.5;
+        }
+
+        let mut counts = vec![0; bins];
+        let width = (max - min) / bins as f64;
+        for v in finite {
+            let bin = (((v - min) / width) as usize).min(bins - 1);
+            counts[bin] += 1;
+        }
+
+        Histogram { min, max, counts }
+    }
+
+    /// Width of every bin
+    pub fn bin_width(&self) -> f64 {
+        (self.max - self.min) / self.counts.len() as f64
+    }
+
+    /// `(lower, upper)` edges of each bin
+    pub fn bin_edges(&self) -> Vec<(f64, f64)> {
+        let width = self.bin_width();
+        (0..self.counts.len())
+            .map(|i| (self.min + i as f64 * width, self.min + (i + 1) as f64 * width))
+            .collect()
+    }
+
+    /// Total number of binned values
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+}
+
+/// Weight and bias histograms for one layer of connections
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerHistogram {
+    /// Index of the weight matrix (layer `i` connects neurons `i -> i+1`)
+    pub layer: usize,
+
+    /// Distribution of the weights
+    pub weights: Histogram,
+
+    /// Distribution of the biases
+    pub biases: Histogram,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_counts() {
+        let hist = Histogram::from_values(&[0.0, 0.1, 0.5, 0.9, 1.0], 2);
+        assert_eq!(hist.counts, vec![2, 3]);
+        assert_eq!(hist.total(), 5);
+        assert_eq!(hist.bin_edges(), vec![(0.0, 0.5), (0.5, 1.0)]);
+    }
+
+    #[test]
+    fn test_histogram_constant_and_non_finite() {
+        let hist = Histogram::from_values(&[2.0, 2.0, f64::NAN], 3);
+        assert_eq!(hist.counts, vec![0, 2, 0]);
+        assert_eq!((hist.min, hist.max), (1.5, 2.5));
+
+        let empty = Histogram::from_values(&[], 4);
+        assert_eq!(empty.total(), 0);
+    }
+}
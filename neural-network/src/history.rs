@@ -0,0 +1,263 @@
+/// Training telemetry history
+///
+/// This module records per-epoch training statistics (loss, per-output loss,
+/// validation loss, classification metrics, learning rate, wall-clock and
+/// per-epoch time, gradient norm, per-layer weight norms) collected by the
+/// `TrainingController`. The same history is embedded in checkpoints so tools
+/// like `visualize` can plot the training trajectory; a resumed run appends
+/// to it with `continued_by`, so the checkpoint it saves holds the curve of
+/// every run so far.
+use crate::metrics::ClassificationMetrics;
+use serde::{Deserialize, Serialize};
+
+/// Statistics recorded at the end of a single training epoch
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EpochRecord {
+    /// Epoch number (1-based)
+    pub epoch: u32,
+
+    /// Mean squared error loss over the training set
+    pub loss: f64,
+
+    /// Frobenius norm of each weight matrix, ordered from input to output
+    pub weight_norms: Vec<f64>,
+
+    /// Global gradient L2 norm, averaged over the update steps of the epoch
+    #[serde(default)]
+    pub gradient_norm: f64,
+
+    /// Loss contributed by each output neuron; these sum to `loss`
+    #[serde(default)]
+    pub output_losses: Vec<f64>,
+
+    /// Mean squared error loss over the validation set, if one is configured
+    #[serde(default)]
+    pub validation_loss: Option<f64>,
+
+    /// Classification metrics over the training set, if a decision threshold is configured
+    #[serde(default)]
+    pub metrics: Option<ClassificationMetrics>,
+
+    /// Classification metrics over the validation set
+    #[serde(default)]
+    pub validation_metrics: Option<ClassificationMetrics>,
+
+    /// Learning rate used for the updates of this epoch
+    #[serde(default)]
+    pub learning_rate: f64,
+
+    /// Wall-clock seconds from the start of training to the end of this
+    /// epoch, excluding pauses between `train_epochs` calls
+    #[serde(default)]
+    pub elapsed_secs: f64,
+
+    /// Wall-clock seconds spent on this epoch, including evaluation
+    #[serde(default)]
+    pub epoch_secs: f64,
+}
+
+impl EpochRecord {
+    /// Estimated seconds left until `total_epochs` are done, assuming the
+    /// remaining epochs take as long as the average epoch so far
+    pub fn eta_secs(&self, total_epochs: u32) -> f64 {
+        if self.epoch == 0 {
+            return 0.0;
+        }
+        let remaining = total_epochs.saturating_sub(self.epoch);
+        self.elapsed_secs / f64::from(self.epoch) * f64::from(remaining)
+    }
+}
+
+/// Per-epoch training telemetry
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrainingHistory {
+    /// Recorded epochs in training order
+    pub epochs: Vec<EpochRecord>,
+}
+
+impl TrainingHistory {
+    /// Create an empty history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a record for a completed epoch
+    pub fn record(&mut self, record: EpochRecord) {
+        self.epochs.push(record);
+    }
+
+    /// This history followed by `next`, a run resumed after its last epoch
+    ///
+    /// The epochs and elapsed times of `next` count from 1 and 0 for that
+    /// run; they are shifted to continue where this history ends.
+    pub fn continued_by(&self, next: &TrainingHistory) -> TrainingHistory {
+        let (epoch, elapsed) = self.last().map_or((0, 0.0), |r| (r.epoch, r.elapsed_secs));
+        let mut history = self.clone();
+        history.epochs.extend(next.epochs.iter().map(|r| EpochRecord {
+            epoch: epoch + r.epoch,
+            elapsed_secs: elapsed + r.elapsed_secs,
+            ..r.clone()
+        }));
+        history
+    }
+
+    /// Number of recorded epochs
+    pub fn len(&self) -> usize {
+        self.epochs.len()
+    }
+
+    /// Whether no epochs have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.epochs.is_empty()
+    }
+
+    /// The most recent epoch record, if any
+    pub fn last(&self) -> Option<&EpochRecord> {
+        self.epochs.last()
+    }
+
+    /// Loss value for every recorded epoch
+    pub fn losses(&self) -> Vec<f64> {
+        self.epochs.iter().map(|r| r.loss).collect()
+    }
+
+    /// `(epoch, loss)` pairs for plotting
+    pub fn loss_series(&self) -> Vec<(u32, f64)> {
+        self.epochs.iter().map(|r| (r.epoch, r.loss)).collect()
+    }
+
+    /// Learning rate for every recorded epoch
+    pub fn learning_rates(&self) -> Vec<f64> {
+        self.epochs.iter().map(|r| r.learning_rate).collect()
+    }
+
+    /// Total wall-clock seconds spent training, as of the last recorded epoch
+    pub fn elapsed_secs(&self) -> f64 {
+        self.last().map_or(0.0, |r| r.elapsed_secs)
+    }
+
+    /// `(epoch, accuracy)` for every epoch that computed classification metrics
+    pub fn accuracy_series(&self) -> Vec<(u32, f64)> {
+        self.epochs
+            .iter()
+            .filter_map(|r| r.metrics.map(|m| (r.epoch, m.accuracy)))
+            .collect()
+    }
+
+    /// `(epoch, validation loss)` for every epoch that computed one
+    pub fn validation_losses(&self) -> Vec<(u32, f64)> {
+        self.epochs
+            .iter()
+            .filter_map(|r| r.validation_loss.map(|l| (r.epoch, l)))
+            .collect()
+    }
+
+    /// Gradient norm for every recorded epoch
+    pub fn gradient_norms(&self) -> Vec<f64> {
+        self.epochs.iter().map(|r| r.gradient_norm).collect()
+    }
+
+    /// Number of output neurons with per-output losses recorded
+    pub fn num_outputs(&self) -> usize {
+        self.epochs
+            .iter()
+            .map(|r| r.output_losses.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// `(epoch, loss)` pairs for a single output neuron
+    pub fn output_loss_series(&self, output: usize) -> Vec<(u32, f64)> {
+        self.epochs
+            .iter()
+            .filter_map(|r| r.output_losses.get(output).map(|&l| (r.epoch, l)))
+            .collect()
+    }
+
+    /// Number of weight layers tracked in the history
+    pub fn num_layers(&self) -> usize {
+        self.epochs
+            .iter()
+            .map(|r| r.weight_norms.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// `(epoch, norm)` pairs for the weight matrix of one layer
+    ///
+    /// Layer `0` is the matrix connecting the input layer to the first hidden layer.
+    pub fn weight_norm_series(&self, layer: usize) -> Vec<(u32, f64)> {
+        self.epochs
+            .iter()
+            .filter_map(|r| r.weight_norms.get(layer).map(|&n| (r.epoch, n)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(epoch: u32, loss: f64, norms: Vec<f64>) -> EpochRecord {
+        EpochRecord {
+            epoch,
+            loss,
+            weight_norms: norms,
+            gradient_norm: loss * 2.0,
+            output_losses: vec![loss * 0.25, loss * 0.75],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_empty_history() {
+        let history = TrainingHistory::new();
+        assert!(history.is_empty());
+        assert_eq!(history.num_layers(), 0);
+        assert!(history.last().is_none());
+    }
+
+    #[test]
+    fn test_weight_norm_series() {
+        let mut history = TrainingHistory::new();
+        history.record(record(1, 0.5, vec![1.0, 2.0]));
+        history.record(record(2, 0.4, vec![1.5, 2.5]));
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.num_layers(), 2);
+        assert_eq!(history.losses(), vec![0.5, 0.4]);
+        assert_eq!(history.gradient_norms(), vec![1.0, 0.8]);
+        assert_eq!(history.weight_norm_series(1), vec![(1, 2.0), (2, 2.5)]);
+        assert!(history.weight_norm_series(5).is_empty());
+        assert_eq!(history.num_outputs(), 2);
+        let series = history.output_loss_series(1);
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0], (1, 0.375));
+        assert!((series[1].1 - 0.3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_continued_by_shifts_epochs_and_elapsed_time() {
+        let mut first = TrainingHistory::new();
+        first.record(EpochRecord { elapsed_secs: 1.0, ..record(1, 0.5, vec![1.0]) });
+        first.record(EpochRecord { elapsed_secs: 2.0, ..record(2, 0.4, vec![1.0]) });
+        let mut resumed = TrainingHistory::new();
+        resumed.record(EpochRecord { elapsed_secs: 0.5, ..record(1, 0.3, vec![1.0]) });
+
+        let history = first.continued_by(&resumed);
+        assert_eq!(history.loss_series(), vec![(1, 0.5), (2, 0.4), (3, 0.3)]);
+        assert_eq!(history.elapsed_secs(), 2.5);
+        assert_eq!(TrainingHistory::new().continued_by(&resumed), resumed);
+    }
+
+    #[test]
+    fn test_records_without_newer_fields_deserialize() {
+        let json = r#"{"epochs":[{"epoch":1,"loss":0.25,"weight_norms":[1.0]}]}"#;
+        let history: TrainingHistory = serde_json::from_str(json).unwrap();
+
+        assert_eq!(history.loss_series(), vec![(1, 0.25)]);
+        assert_eq!(history.learning_rates(), vec![0.0]);
+        assert_eq!(history.elapsed_secs(), 0.0);
+        assert!(history.accuracy_series().is_empty());
+    }
+}
@@ -0,0 +1,188 @@
+/// Classification datasets from folders of images
+///
+/// `ImageFolder::load` reads a directory laid out one folder per class:
+///
+/// ```text
+/// digits/
+///   0/  a.png  b.jpg ...
+///   1/  c.png ...
+/// ```
+///
+/// Every PNG or JPEG image is converted to grayscale, resized to the target
+/// resolution and flattened row by row into an input of `width * height`
+/// values in `[0, 1]`. Targets are one-hot over the class folders, numbered
+/// in sorted name order as by `ClassEncoder::fit`. `to_tensor` stacks the
+/// images into a `Tensor3` of shape `(samples, height, width)` for
+/// convolutional layers.
+///
+/// Available with the `images` feature.
+use crate::dataset::{Dataset, VecDataset};
+use crate::encoding::ClassEncoder;
+use crate::matrix::Tensor3;
+use anyhow::Context;
+use image::imageops::{self, FilterType};
+use std::path::{Path, PathBuf};
+
+/// File extensions loaded as images, compared case-insensitively
+pub const IMAGE_EXTENSIONS: [&str; 3] = ["png", "jpg", "jpeg"];
+
+/// Grayscale images labelled by the folder they were found in
+#[derive(Debug, Clone)]
+pub struct ImageFolder {
+    data: VecDataset,
+    classes: ClassEncoder,
+    labels: Vec<usize>,
+    paths: Vec<PathBuf>,
+    width: u32,
+    height: u32,
+}
+
+impl ImageFolder {
+    /// Load every image below `dir`, resized to `width` x `height`
+    ///
+    /// Class folders are visited in sorted order and the images in each in
+    /// sorted file name order, so the sample order does not depend on the
+    /// file system. Files at the top level, nested folders and files without
+    /// an image extension are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the size is zero, `dir` cannot be read, has no
+    /// class folders, a class folder has no images, or an image cannot be
+    /// decoded
+    pub fn load(dir: &Path, width: u32, height: u32) -> anyhow::Result<Self> {
+        if width == 0 || height == 0 {
+            anyhow::bail!("Image size must be at least 1x1, got {}x{}", width, height);
+        }
+        let mut folders = Vec::new();
+        for entry in read_dir(dir)? {
+            if entry.is_dir() {
+                folders.push(entry);
+            }
+        }
+        if folders.is_empty() {
+            anyhow::bail!("No class folders in {}", dir.display());
+        }
+        let names: Vec<String> = folders
+            .iter()
+            .map(|folder| folder.file_name().unwrap_or_default().to_string_lossy().into_owned())
+            .collect();
+        let classes = ClassEncoder::new(names)?;
+
+        let mut inputs = Vec::new();
+        let mut targets = Vec::new();
+        let mut labels = Vec::new();
+        let mut paths = Vec::new();
+        for (class, folder) in folders.iter().enumerate() {
+            let images: Vec<PathBuf> = read_dir(folder)?.into_iter().filter(|path| is_image(path)).collect();
+            if images.is_empty() {
+                anyhow::bail!("Class folder {} has no PNG or JPEG images", folder.display());
+            }
+            let target = classes.encode(&classes.names()[class])?;
+            for path in images {
+                inputs.push(load_grayscale(&path, width, height)?);
+                targets.push(target.clone());
+                labels.push(class);
+                paths.push(path);
+            }
+        }
+
+        Ok(Self {
+            data: VecDataset::new(inputs, targets)?,
+            classes,
+            labels,
+            paths,
+            width,
+            height,
+        })
+    }
+
+    /// Class names, by class index
+    pub fn classes(&self) -> &ClassEncoder {
+        &self.classes
+    }
+
+    /// Class index of every sample
+    pub fn labels(&self) -> &[usize] {
+        &self.labels
+    }
+
+    /// Image file of every sample
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Width of every image after resizing
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Height of every image after resizing
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// All images as one tensor of shape `(samples, height, width)`
+    pub fn to_tensor(&self) -> Tensor3 {
+        let (inputs, _) = self.data.to_vecs();
+        Tensor3 {
+            batch: inputs.len(),
+            rows: self.height as usize,
+            cols: self.width as usize,
+            data: inputs.concat(),
+        }
+    }
+
+    /// The samples as an in-memory dataset
+    pub fn into_dataset(self) -> VecDataset {
+        self.data
+    }
+}
+
+impl Dataset for ImageFolder {
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn get(&self, i: usize) -> (Vec<f64>, Vec<f64>) {
+        self.data.get(i)
+    }
+
+    fn input_dim(&self) -> usize {
+        self.data.input_dim()
+    }
+
+    fn target_dim(&self) -> usize {
+        self.data.target_dim()
+    }
+}
+
+/// Entries of `dir`, sorted by path
+fn read_dir(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let entries = std::fs::read_dir(dir).with_context(|| format!("Failed to read directory: {}", dir.display()))?;
+    let mut paths = entries
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Failed to read directory: {}", dir.display()))?;
+    paths.sort();
+    Ok(paths)
+}
+
+/// Whether `path` is a file with one of the `IMAGE_EXTENSIONS`
+fn is_image(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Pixels of the image at `path` in grayscale, resized and scaled to `[0, 1]`
+fn load_grayscale(path: &Path, width: u32, height: u32) -> anyhow::Result<Vec<f64>> {
+    let image = image::open(path).with_context(|| format!("Failed to load image: {}", path.display()))?;
+    let mut gray = image.to_luma8();
+    if gray.dimensions() != (width, height) {
+        gray = imageops::resize(&gray, width, height, FilterType::Triangle);
+    }
+    Ok(gray.pixels().map(|p| f64::from(p.0[0]) / 255.0).collect())
+}
@@ -0,0 +1,161 @@
+/// Inference-only models for deployment
+///
+/// A deployed model needs the network and the transforms around it, not the
+/// training history, optimizer state and lineage that let a run resume.
+/// `Checkpoint::stripped` drops those before saving, and `InferenceModel`
+/// loads any checkpoint while keeping only what prediction uses. From JSON
+/// the training fields are skipped by the parser instead of being built, so
+/// a full checkpoint with a long history loads nearly as fast as a stripped
+/// one. `InferenceModel::from_bytes` decodes checkpoints already in memory,
+/// for the server and the WASM build, which have no file to read. Encrypted
+/// checkpoints load with `load_with_secret` and `from_bytes_with_secret`.
+use crate::checkpoint::{BINARY_MAGIC, Checkpoint, CheckpointMetadata, Compression, decrypt_contents, network_checksum};
+use crate::encryption::Secret;
+use crate::matrix::Matrix;
+use crate::network::Network;
+use crate::normalize::Normalizer;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// A network with its metadata and normalizers, ready for prediction
+#[derive(Debug, Clone)]
+pub struct InferenceModel {
+    /// Metadata of the checkpoint the model was loaded from
+    pub metadata: CheckpointMetadata,
+
+    /// Trained network
+    pub network: Network,
+
+    /// Applied to inputs before the network
+    pub normalizer: Option<Normalizer>,
+
+    /// Inverted on the network's outputs
+    pub target_normalizer: Option<Normalizer>,
+}
+
+/// The fields of a JSON checkpoint that inference reads; the rest are skipped
+#[derive(Deserialize)]
+struct InferenceFields {
+    metadata: CheckpointMetadata,
+    network: Network,
+    #[serde(default)]
+    normalizer: Option<Normalizer>,
+    #[serde(default)]
+    target_normalizer: Option<Normalizer>,
+    #[serde(default)]
+    checksum: Option<String>,
+}
+
+impl InferenceModel {
+    /// Load the model in a checkpoint file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or decoded, or a
+    /// "Checkpoint corrupted" error if its checksum does not match
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::load_with_secret(path, None)
+    }
+
+    /// Load the model in a checkpoint file, decrypting it with `secret` if
+    /// it is encrypted
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `load`, and an error if the file is encrypted
+    /// and `secret` is missing or wrong
+    pub fn load_with_secret(path: &Path, secret: Option<&Secret>) -> Result<Self> {
+        let contents = fs::read(path)
+            .with_context(|| format!("Failed to read checkpoint from {}", path.display()))?;
+        Self::from_bytes_with_secret(contents, secret)
+            .with_context(|| format!("Failed to load model from {}", path.display()))
+    }
+
+    /// Decode the model in the contents of a checkpoint file
+    ///
+    /// Like `Checkpoint::load`, this accepts JSON and binary checkpoints,
+    /// optionally gzip or zstd compressed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the contents are encrypted or not a checkpoint or
+    /// the network's shapes are inconsistent, and a "Checkpoint corrupted"
+    /// error if the checksum does not match
+    pub fn from_bytes(contents: Vec<u8>) -> Result<Self> {
+        Self::from_bytes_with_secret(contents, None)
+    }
+
+    /// Decode the model in the contents of a checkpoint file, decrypting
+    /// them with `secret` if they are encrypted
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `from_bytes`, and an error if the contents are
+    /// encrypted and `secret` is missing or wrong
+    pub fn from_bytes_with_secret(contents: Vec<u8>, secret: Option<&Secret>) -> Result<Self> {
+        let contents = decrypt_contents(contents, secret)?;
+        let corrupted = "Checkpoint corrupted: data is damaged or truncated";
+        let contents = Compression::detect(&contents).decompress(contents).context(corrupted)?;
+
+        let model = if contents.starts_with(BINARY_MAGIC) {
+            // bincode cannot skip fields, so binary files are decoded whole
+            let checkpoint = Checkpoint::from_bytes(&contents).context(corrupted)?;
+            checkpoint.verify().context(corrupted)?;
+            InferenceModel::from(checkpoint)
+        } else {
+            let fields: InferenceFields = match serde_json::from_slice(&contents) {
+                Ok(fields) => fields,
+                Err(e) if e.is_eof() => return Err(e).context(corrupted),
+                Err(e) => return Err(e).context("Failed to deserialize checkpoint"),
+            };
+            if let Some(expected) = &fields.checksum {
+                let actual = network_checksum(&fields.network)?;
+                if &actual != expected {
+                    return Err(anyhow::anyhow!("Network checksum mismatch: expected {}, got {}", expected, actual))
+                        .context(corrupted);
+                }
+            }
+            InferenceModel {
+                metadata: fields.metadata,
+                network: fields.network,
+                normalizer: fields.normalizer,
+                target_normalizer: fields.target_normalizer,
+            }
+        };
+        model.network.validate_shapes()?;
+        Ok(model)
+    }
+
+    /// Predict the outputs for one input
+    ///
+    /// The input is scaled like the training data first and the outputs
+    /// mapped back to the scale of the training targets.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input does not match the network's input layer
+    pub fn predict(&mut self, input: &[f64]) -> Result<Vec<f64>> {
+        let input = match &self.normalizer {
+            Some(normalizer) => normalizer.transform(input),
+            None => input.to_vec(),
+        };
+        let output = self.network.try_feed_forward(Matrix::from(input))?;
+        Ok(match &self.target_normalizer {
+            Some(normalizer) => normalizer.inverse_transform(&output.data),
+            None => output.data,
+        })
+    }
+}
+
+impl From<Checkpoint> for InferenceModel {
+    fn from(checkpoint: Checkpoint) -> Self {
+        InferenceModel {
+            metadata: checkpoint.metadata,
+            network: checkpoint.network,
+            normalizer: checkpoint.normalizer,
+            target_normalizer: checkpoint.target_normalizer,
+        }
+    }
+}
@@ -3,10 +3,51 @@ extern crate derive_builder;
 pub mod network;
 pub mod activations;
 pub mod examples;
+pub mod dataset;
+pub mod normalize;
+pub mod encoding;
+pub mod sampling;
+pub mod streaming;
+#[cfg(feature = "images")]
+pub mod image_folder;
 pub mod checkpoint;
+pub mod checkpoint_dir;
+pub mod encryption;
+pub mod compare;
+pub mod inference;
+pub mod summary;
 pub mod training;
+pub mod callbacks;
+pub mod history;
+pub mod quantize;
+pub mod prune;
+pub mod onnx;
+pub mod safetensors;
+pub mod npz;
+pub mod ensemble;
+pub mod histogram;
+pub mod plot;
+pub mod boundary;
+pub mod workspace;
+pub mod metrics;
+pub mod loggers;
+pub mod tensorboard;
+pub mod sweep;
+pub mod lr_finder;
+pub mod gradcheck;
+pub mod benchmark;
+pub mod optimizer;
 
 pub mod matrix {
 
     pub use matrix::matrix::Matrix;
+    pub use matrix::tensor::Tensor3;
+    pub use matrix::assert_matrix_eq;
+    pub use matrix::error::MatrixError;
+
+    #[cfg(feature = "ndarray")]
+    pub use matrix::ndarray::to_samples;
+
+    #[cfg(feature = "gpu")]
+    pub use matrix::gpu;
 }
\ No newline at end of file
@@ -0,0 +1,89 @@
+/// Built-in callbacks that log training progress to files
+///
+/// Each logger is a `TrainingCallback` for `TrainingController::add_callback`.
+/// A logger that fails to write reports the first failure on stderr and stops
+/// logging instead of aborting training.
+use crate::callbacks::{CallbackAction, EpochContext, TrainingCallback};
+use crate::history::EpochRecord;
+use anyhow::Context;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Column names written as the first line of a new CSV log
+pub const CSV_HEADER: &str =
+    "epoch,loss,validation_loss,accuracy,precision,recall,f1,learning_rate,elapsed_secs";
+
+/// Appends one CSV row per epoch
+///
+/// Optional values (validation loss, classification metrics) are left empty
+/// when they were not computed. Rows are flushed every epoch so the file can
+/// be inspected while training runs.
+pub struct CsvLogger {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>,
+}
+
+impl CsvLogger {
+    /// Open `path` for appending, writing the header if the file is new or empty
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or the header cannot be written
+    pub fn create(path: &Path) -> anyhow::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open CSV log: {}", path.display()))?;
+        let is_empty = file.metadata()?.len() == 0;
+
+        let mut writer = BufWriter::new(file);
+        if is_empty {
+            writeln!(writer, "{}", CSV_HEADER)?;
+            writer.flush()?;
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer: Some(writer),
+        })
+    }
+
+    /// Append the row for one epoch
+    pub fn log(&mut self, record: &EpochRecord) -> std::io::Result<()> {
+        let Some(writer) = self.writer.as_mut() else {
+            return Ok(());
+        };
+        writeln!(writer, "{}", csv_row(record))?;
+        writer.flush()
+    }
+}
+
+impl TrainingCallback for CsvLogger {
+    fn on_epoch_end(&mut self, ctx: &EpochContext) -> anyhow::Result<CallbackAction> {
+        if let Err(e) = self.log(ctx.record) {
+            eprintln!("Stopped logging to {}: {}", self.path.display(), e);
+            self.writer = None;
+        }
+        Ok(CallbackAction::Continue)
+    }
+}
+
+/// Format one record in `CSV_HEADER` column order
+fn csv_row(record: &EpochRecord) -> String {
+    let optional = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+    let metrics = record.metrics;
+    [
+        record.epoch.to_string(),
+        record.loss.to_string(),
+        optional(record.validation_loss),
+        optional(metrics.map(|m| m.accuracy)),
+        optional(metrics.map(|m| m.precision)),
+        optional(metrics.map(|m| m.recall)),
+        optional(metrics.map(|m| m.f1)),
+        record.learning_rate.to_string(),
+        record.elapsed_secs.to_string(),
+    ]
+    .join(",")
+}
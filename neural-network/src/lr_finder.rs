@@ -0,0 +1,211 @@
+/// Learning-rate range test
+///
+/// Trains a copy of a network for a short run while raising the learning
+/// rate exponentially from `min_lr` to `max_lr`, one epoch per step, and
+/// records the loss after every step. Plotting loss against learning rate
+/// shows where training starts to make progress and where it blows up; a
+/// good `learning_rate` lies on the steep downward slope, below the minimum.
+///
+/// ```
+/// use neural_network::{activations::SIGMOID, examples, lr_finder::LrFinder, network::Network};
+///
+/// let xor = examples::get_example("xor").unwrap();
+/// let network = Network::new_seeded(xor.recommended_arch.clone(), SIGMOID, 0.5, 42);
+/// let result = LrFinder::default().run(&network, &xor.inputs, &xor.targets);
+/// println!("try a learning rate around {:?}", result.suggestion());
+/// ```
+use crate::network::Network;
+use serde::{Deserialize, Serialize};
+
+/// Settings of a learning-rate range test
+#[derive(Debug, Clone, PartialEq)]
+pub struct LrFinder {
+    /// Learning rate of the first step
+    pub min_lr: f64,
+
+    /// Learning rate of the last step
+    pub max_lr: f64,
+
+    /// Number of steps (epochs) between `min_lr` and `max_lr`
+    pub steps: usize,
+
+    /// Stop once the smoothed loss exceeds this multiple of the best loss
+    ///
+    /// Sigmoid outputs bound the squared error, so even a diverging run
+    /// rarely gets far above its best loss; keep this small.
+    pub divergence_factor: f64,
+}
+
+impl Default for LrFinder {
+    fn default() -> Self {
+        Self {
+            min_lr: 1e-4,
+            max_lr: 100.0,
+            steps: 100,
+            divergence_factor: 2.0,
+        }
+    }
+}
+
+/// Loss recorded at one learning rate
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LrPoint {
+    pub learning_rate: f64,
+
+    /// Mean squared error over the data after the step
+    pub loss: f64,
+
+    /// Exponentially smoothed loss, less noisy for picking a rate
+    pub smoothed_loss: f64,
+}
+
+/// Losses recorded by `LrFinder::run`, in order of increasing learning rate
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LrFindResult {
+    pub points: Vec<LrPoint>,
+
+    /// Whether the run stopped early because the loss diverged
+    pub diverged: bool,
+}
+
+/// Weight of the previous smoothed loss in the moving average
+const SMOOTHING: f64 = 0.7;
+
+impl LrFinder {
+    /// Learning rate of step `i`
+    fn learning_rate(&self, i: usize) -> f64 {
+        if self.steps <= 1 {
+            return self.min_lr;
+        }
+        let t = i as f64 / (self.steps - 1) as f64;
+        self.min_lr * (self.max_lr / self.min_lr).powf(t)
+    }
+
+    /// Run the range test on a copy of `network`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rates are not positive with `min_lr < max_lr`, or if
+    /// `inputs` is empty
+    pub fn run(&self, network: &Network, inputs: &[Vec<f64>], targets: &[Vec<f64>]) -> LrFindResult {
+        assert!(
+            self.min_lr > 0.0 && self.min_lr < self.max_lr,
+            "Learning rate range must satisfy 0 < min_lr < max_lr"
+        );
+        assert!(!inputs.is_empty(), "The range test needs at least one sample");
+
+        let mut network = network.clone();
+        let mut workspace = network.workspace();
+        let mut result = LrFindResult::default();
+        let mut average = 0.0;
+        let mut best = f64::INFINITY;
+
+        for i in 0..self.steps {
+            network.learning_rate = self.learning_rate(i);
+            for (input, target) in inputs.iter().zip(targets) {
+                network.feed_forward_with(input, &mut workspace);
+                network.compute_gradients_with(target, &mut workspace);
+                network.apply_gradients(workspace.gradients());
+            }
+
+            let mut loss = 0.0;
+            for (input, target) in inputs.iter().zip(targets) {
+                let output = network.feed_forward_with(input, &mut workspace);
+                loss += target.iter().zip(&output.data).map(|(t, o)| (t - o).powi(2)).sum::<f64>();
+            }
+            loss /= inputs.len() as f64;
+
+            // Bias-corrected exponential moving average
+            average = SMOOTHING * average + (1.0 - SMOOTHING) * loss;
+            let smoothed_loss = average / (1.0 - SMOOTHING.powi(i as i32 + 1));
+
+            if !smoothed_loss.is_finite() || smoothed_loss > self.divergence_factor * best {
+                result.diverged = true;
+                break;
+            }
+            best = best.min(smoothed_loss);
+            result.points.push(LrPoint {
+                learning_rate: network.learning_rate,
+                loss,
+                smoothed_loss,
+            });
+        }
+        result
+    }
+}
+
+impl LrFindResult {
+    /// Index of the point with the lowest smoothed loss
+    fn min_index(&self) -> Option<usize> {
+        let loss = |i: usize| self.points[i].smoothed_loss;
+        (0..self.points.len()).min_by(|&a, &b| loss(a).total_cmp(&loss(b)))
+    }
+
+    /// Learning rate with the lowest smoothed loss
+    pub fn min_loss_rate(&self) -> Option<f64> {
+        self.min_index().map(|i| self.points[i].learning_rate)
+    }
+
+    /// Suggested learning rate: where the smoothed loss falls fastest on
+    /// the way down to its minimum
+    ///
+    /// The slope is measured against the log of the learning rate, so each
+    /// step counts equally. Past the minimum the loss oscillates, so drops
+    /// there are ignored. Returns `None` if the loss never decreased.
+    pub fn suggestion(&self) -> Option<f64> {
+        let end = self.min_index()?;
+        self.points[..=end]
+            .windows(2)
+            .map(|w| {
+                let log_step = (w[1].learning_rate / w[0].learning_rate).ln();
+                ((w[1].smoothed_loss - w[0].smoothed_loss) / log_step, w[0].learning_rate)
+            })
+            .filter(|&(slope, _)| slope < 0.0)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, rate)| rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_learning_rates_are_geometric() {
+        let finder = LrFinder {
+            min_lr: 0.01,
+            max_lr: 100.0,
+            steps: 5,
+            ..Default::default()
+        };
+        let rates: Vec<f64> = (0..5).map(|i| finder.learning_rate(i)).collect();
+
+        for (rate, expected) in rates.iter().zip([0.01, 0.1, 1.0, 10.0, 100.0]) {
+            assert!((rate - expected).abs() < 1e-9 * expected, "{} != {}", rate, expected);
+        }
+    }
+
+    #[test]
+    fn test_suggestion_is_steepest_descent() {
+        let point = |learning_rate, smoothed_loss| LrPoint {
+            learning_rate,
+            loss: smoothed_loss,
+            smoothed_loss,
+        };
+        let result = LrFindResult {
+            points: vec![
+                point(0.01, 0.30),
+                point(0.1, 0.29),
+                point(1.0, 0.10),
+                point(10.0, 0.05),
+                point(100.0, 0.40),
+                point(1000.0, 0.06),
+            ],
+            diverged: false,
+        };
+
+        // The larger drop after the minimum is oscillation, not progress
+        assert_eq!(result.suggestion(), Some(0.1));
+        assert_eq!(result.min_loss_rate(), Some(10.0));
+    }
+}
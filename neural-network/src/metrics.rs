@@ -0,0 +1,212 @@
+/// Classification metrics for networks with sigmoid outputs
+///
+/// Every output neuron is treated as an independent binary classifier: an
+/// output at or above the decision threshold predicts the positive class, and
+/// a target of 0.5 or more is a positive label. Counts are pooled over all
+/// outputs and samples (micro-averaging), so single-output examples like XOR
+/// get the usual binary metrics and multi-output examples get one summary.
+/// `ConfusionMatrix` instead counts whole-sample class predictions, for a
+/// per-class breakdown of multi-class models.
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Default decision threshold for sigmoid outputs
+pub const DEFAULT_THRESHOLD: f64 = 0.5;
+
+/// True/false positive/negative counts accumulated over predictions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConfusionCounts {
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub true_negatives: usize,
+    pub false_negatives: usize,
+}
+
+impl ConfusionCounts {
+    /// Count the predictions of one sample
+    pub fn observe(&mut self, outputs: &[f64], targets: &[f64], threshold: f64) {
+        for (&output, &target) in outputs.iter().zip(targets) {
+            match (output >= threshold, target >= 0.5) {
+                (true, true) => self.true_positives += 1,
+                (true, false) => self.false_positives += 1,
+                (false, false) => self.true_negatives += 1,
+                (false, true) => self.false_negatives += 1,
+            }
+        }
+    }
+
+    /// Total number of predictions counted
+    pub fn total(&self) -> usize {
+        self.true_positives + self.false_positives + self.true_negatives + self.false_negatives
+    }
+
+    /// Derive accuracy, precision, recall and F1 from the counts
+    pub fn metrics(&self) -> ClassificationMetrics {
+        ClassificationMetrics {
+            accuracy: ratio(self.true_positives + self.true_negatives, self.total()),
+            precision: ratio(self.true_positives, self.true_positives + self.false_positives),
+            recall: ratio(self.true_positives, self.true_positives + self.false_negatives),
+            f1: ratio(
+                2 * self.true_positives,
+                2 * self.true_positives + self.false_positives + self.false_negatives,
+            ),
+        }
+    }
+}
+
+/// Accuracy, precision, recall and F1 score, each in `[0, 1]`
+///
+/// Ratios with a zero denominator (e.g. precision when nothing was predicted
+/// positive) are reported as 0.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClassificationMetrics {
+    pub accuracy: f64,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+}
+
+impl ClassificationMetrics {
+    /// Compute metrics for a set of network outputs against their targets
+    pub fn compute(outputs: &[Vec<f64>], targets: &[Vec<f64>], threshold: f64) -> Self {
+        let mut counts = ConfusionCounts::default();
+        for (output, target) in outputs.iter().zip(targets) {
+            counts.observe(output, target, threshold);
+        }
+        counts.metrics()
+    }
+}
+
+impl fmt::Display for ClassificationMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "accuracy = {:.4}, precision = {:.4}, recall = {:.4}, F1 = {:.4}",
+            self.accuracy, self.precision, self.recall, self.f1
+        )
+    }
+}
+
+/// Counts of each actual class against each predicted class
+///
+/// A sample's class is the index of its largest output or target; with a
+/// single output it is 1 when the output reaches the threshold (the target
+/// 0.5) and 0 otherwise.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfusionMatrix {
+    /// `counts[actual][predicted]`: samples of class `actual` predicted as
+    /// `predicted`
+    pub counts: Vec<Vec<usize>>,
+}
+
+impl ConfusionMatrix {
+    /// Empty matrix for a network with `outputs` output neurons: one class per
+    /// output, or two for a single output
+    pub fn for_outputs(outputs: usize) -> Self {
+        let classes = outputs.max(2);
+        ConfusionMatrix {
+            counts: vec![vec![0; classes]; classes],
+        }
+    }
+
+    /// Count the prediction of one sample
+    pub fn observe(&mut self, outputs: &[f64], targets: &[f64], threshold: f64) {
+        let actual = class_index(targets, 0.5);
+        let predicted = class_index(outputs, threshold);
+        if actual < self.counts.len() && predicted < self.counts.len() {
+            self.counts[actual][predicted] += 1;
+        }
+    }
+
+    /// Number of classes
+    pub fn num_classes(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Number of samples counted
+    pub fn total(&self) -> usize {
+        self.counts.iter().flatten().sum()
+    }
+
+    /// Samples whose predicted class is their actual class
+    pub fn correct(&self) -> usize {
+        (0..self.num_classes()).map(|c| self.counts[c][c]).sum()
+    }
+
+    /// Fraction of samples classified correctly
+    pub fn accuracy(&self) -> f64 {
+        ratio(self.correct(), self.total())
+    }
+
+    /// Samples whose actual class is `class`
+    pub fn support(&self, class: usize) -> usize {
+        self.counts[class].iter().sum()
+    }
+
+    /// Fraction of the samples of `class` classified correctly (its recall),
+    /// or `None` if there are none
+    pub fn class_accuracy(&self, class: usize) -> Option<f64> {
+        let support = self.support(class);
+        (support > 0).then(|| ratio(self.counts[class][class], support))
+    }
+
+    /// Fraction of the predictions of `class` that are correct, or `None` if
+    /// it was never predicted
+    pub fn class_precision(&self, class: usize) -> Option<f64> {
+        let predicted: usize = self.counts.iter().map(|row| row[class]).sum();
+        (predicted > 0).then(|| ratio(self.counts[class][class], predicted))
+    }
+}
+
+/// Class of one output or target vector, see `ConfusionMatrix`
+fn class_index(values: &[f64], threshold: f64) -> usize {
+    match values {
+        [value] => usize::from(*value >= threshold),
+        _ => (0..values.len()).max_by(|&a, &b| values[a].total_cmp(&values[b])).unwrap_or(0),
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics_from_counts() {
+        let outputs = vec![vec![0.9], vec![0.8], vec![0.2], vec![0.6], vec![0.1]];
+        let targets = vec![vec![1.0], vec![1.0], vec![1.0], vec![0.0], vec![0.0]];
+
+        let m = ClassificationMetrics::compute(&outputs, &targets, DEFAULT_THRESHOLD);
+
+        // TP = 2, FN = 1, FP = 1, TN = 1
+        assert_eq!(m.accuracy, 3.0 / 5.0);
+        assert_eq!(m.precision, 2.0 / 3.0);
+        assert_eq!(m.recall, 2.0 / 3.0);
+        assert!((m.f1 - 2.0 / 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_threshold_moves_decision_boundary() {
+        let outputs = vec![vec![0.6], vec![0.4]];
+        let targets = vec![vec![0.0], vec![1.0]];
+
+        assert_eq!(ClassificationMetrics::compute(&outputs, &targets, 0.5).accuracy, 0.0);
+        assert_eq!(ClassificationMetrics::compute(&outputs, &targets, 0.3).recall, 1.0);
+        assert_eq!(ClassificationMetrics::compute(&outputs, &targets, 0.7).accuracy, 0.5);
+    }
+
+    #[test]
+    fn test_no_positive_predictions_gives_zero_precision() {
+        let m = ClassificationMetrics::compute(&[vec![0.1, 0.2]], &[vec![0.0, 1.0]], 0.5);
+        assert_eq!(m.precision, 0.0);
+        assert_eq!(m.f1, 0.0);
+        assert_eq!(m.accuracy, 0.5);
+    }
+}
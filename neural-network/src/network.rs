@@ -1,9 +1,34 @@
+use matrix::error::MatrixError;
 use matrix::matrix::Matrix;
 use rand::SeedableRng;
 use rand::rngs::StdRng;
 use serde::{Serialize, Deserialize};
 
 use crate::activations::Activation;
+use crate::histogram::{Histogram, LayerHistogram};
+
+/// Loss gradients with respect to each layer's weights and biases
+#[derive(Debug, Clone)]
+pub struct Gradients {
+    /// Weight gradients, same shapes as `Network::weights`
+    pub weights: Vec<Matrix>,
+
+    /// Bias gradients, same shapes as `Network::biases`
+    pub biases: Vec<Matrix>,
+}
+
+impl Gradients {
+    /// Global L2 norm across all weight and bias gradients
+    pub fn norm(&self) -> f64 {
+        self.weights
+            .iter()
+            .chain(self.biases.iter())
+            .flat_map(|m| m.data.iter())
+            .map(|g| g * g)
+            .sum::<f64>()
+            .sqrt()
+    }
+}
 
 
 #[derive(Debug, Clone, Builder, Serialize, Deserialize)]
@@ -13,6 +38,10 @@ pub struct Network {
     pub biases: Vec<Matrix>,
     #[serde(skip)]
     data: Vec<Matrix>,
+    /// Inputs to the activation of every layer after the input layer, from
+    /// the last feed_forward call
+    #[serde(skip)]
+    pre_activations: Vec<Matrix>,
     pub activation: Activation,
     pub learning_rate: f64,
 }
@@ -36,6 +65,7 @@ impl Network {
             weights,
             biases,
             data: vec![],
+            pre_activations: vec![],
             activation,
             learning_rate
         }
@@ -59,11 +89,54 @@ impl Network {
             weights,
             biases,
             data: vec![],
+            pre_activations: vec![],
             activation,
             learning_rate,
         }
     }
 
+    /// Check that every weight and bias matrix matches the layer sizes
+    ///
+    /// Networks built with `new` always pass; this guards networks restored
+    /// from external data such as checkpoints.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MatrixError::DimensionMismatch` for the first matrix whose
+    /// shape is wrong
+    pub fn validate_shapes(&self) -> Result<(), MatrixError> {
+        for (i, pair) in self.layers.windows(2).enumerate() {
+            let expected = [(pair[1], pair[0]), (pair[1], 1)];
+            for (matrix, expected) in [self.weights.get(i), self.biases.get(i)].into_iter().zip(expected) {
+                let found = matrix.map_or((0, 0), |m| (m.rows, m.cols));
+                if found != expected {
+                    return Err(MatrixError::DimensionMismatch { expected, found });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Run a forward pass, checking the input size first
+    ///
+    /// Use this instead of `feed_forward` for inputs from users (CLI
+    /// arguments, HTTP requests, JavaScript) so a wrong size becomes an error
+    /// rather than a panic.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MatrixError::DimensionMismatch` if `inputs` does not hold
+    /// exactly one value per input neuron
+    pub fn try_feed_forward(&mut self, inputs: Matrix) -> Result<Matrix, MatrixError> {
+        if inputs.data.len() != self.layers[0] {
+            return Err(MatrixError::DimensionMismatch {
+                expected: (self.layers[0], 1),
+                found: (inputs.rows, inputs.cols),
+            });
+        }
+        Ok(self.feed_forward(inputs))
+    }
+
     pub fn feed_forward(&mut self, inputs: Matrix) -> Matrix {
 
         assert!(self.layers[0] == inputs.data.len(), "Invalid Number of Inputs");
@@ -71,13 +144,16 @@ impl Network {
         let mut current = inputs;
 
         self.data = vec![current.clone()];
+        self.pre_activations.clear();
 
 
       for i in 0..self.layers.len() -1 {
-            current = self.weights[i]
+            let mut z = self.weights[i]
             .dot_multiply(&current)
-            .add(&self.biases[i]).map(self.activation.function);
+            .add(&self.biases[i]);
+            current = z.map(self.activation.function);
             
+            self.pre_activations.push(z);
             self.data.push(current.clone());
       }
 
@@ -102,43 +178,120 @@ impl Network {
         self.weights.iter().map(|m| (m.rows, m.cols)).collect()
     }
 
-    pub fn back_propogate(&mut self, inputs:Matrix, targets:Matrix) {
+    /// Get the Frobenius (L2) norm of each weight matrix, from input to output
+    pub fn weight_norms(&self) -> Vec<f64> {
+        self.weights
+            .iter()
+            .map(|m| m.data.iter().map(|w| w * w).sum::<f64>().sqrt())
+            .collect()
+    }
 
-        let mut errors = targets.subtract(&inputs);
+    /// Binned histograms of the weights and biases of each layer
+    ///
+    /// Each layer uses its own value range, split into `bins` equal-width bins.
+    pub fn weight_histograms(&self, bins: usize) -> Vec<LayerHistogram> {
+        self.weights
+            .iter()
+            .zip(self.biases.iter())
+            .enumerate()
+            .map(|(layer, (w, b))| LayerHistogram {
+                layer,
+                weights: Histogram::from_values(&w.data, bins),
+                biases: Histogram::from_values(&b.data, bins),
+            })
+            .collect()
+    }
 
-        let mut gradients = inputs.clone().map(self.activation.derivative);
+    /// Index of the first weight layer whose weights or biases contain NaN or infinity
+    pub fn first_non_finite_layer(&self) -> Option<usize> {
+        self.weights
+            .iter()
+            .zip(self.biases.iter())
+            .position(|(w, b)| w.data.iter().chain(b.data.iter()).any(|v| !v.is_finite()))
+    }
 
+    /// Compute loss gradients for the sample passed to the last `feed_forward` call
+    ///
+    /// `outputs` must be the result of that call. Gradients are taken of the
+    /// squared-error loss `0.5 * sum((target - output)^2)`, so a descent step
+    /// subtracts them (see `apply_gradients`).
+    pub fn compute_gradients(&self, outputs: &Matrix, targets: &Matrix) -> Gradients {
+        let layer_count = self.layers.len() - 1;
+        let mut weights = Vec::with_capacity(layer_count);
+        let mut biases = Vec::with_capacity(layer_count);
+
+        // delta holds dLoss/dz for the layer being processed
+        let mut delta = outputs
+            .subtract(targets)
+            .elementwise_multiply(&self.activation_derivative(&self.pre_activations[layer_count - 1], outputs));
+
+        for i in (0..layer_count).rev() {
+            weights.push(delta.dot_multiply_transposed_rhs(&self.data[i]));
+            biases.push(delta.clone());
+
+            if i > 0 {
+                delta = self.weights[i]
+                    .dot_multiply_transposed_lhs(&delta)
+                    .elementwise_multiply(&self.activation_derivative(&self.pre_activations[i - 1], &self.data[i]));
+            }
+        }
 
-      
+        weights.reverse();
+        biases.reverse();
 
-        for i in (0..self.layers.len() -1).rev(){
-           
-            gradients = gradients.elementwise_multiply(&errors).map(|x| x * self.learning_rate);
-           
-           
-            
-           
-            self.weights[i] = self.weights[i].add(&gradients.dot_multiply(&self.data[i].transpose()));
+        Gradients { weights, biases }
+    }
 
-            
-            
-        
-            self.biases[i] = self.biases[i].add(&gradients);
+    /// Derivative of the activation at each element of a layer, from its
+    /// inputs `z` and outputs `a`
+    fn activation_derivative(&self, z: &Matrix, a: &Matrix) -> Matrix {
+        let derivative = self.activation.derivative;
+        Matrix {
+            rows: a.rows,
+            cols: a.cols,
+            data: z.data.iter().zip(&a.data).map(|(z, a)| derivative(z, a)).collect(),
+        }
+    }
+
+    /// Take a gradient-descent step scaled by the learning rate
+    ///
+    /// Parameters are updated in place, without allocating.
+    pub fn apply_gradients(&mut self, gradients: &Gradients) {
+        self.apply_scaled_gradients(gradients, 1.0);
+    }
 
-            errors = self.weights[i].transpose().dot_multiply(&errors);
-            gradients = self.data[i].map(self.activation.derivative);
+    /// Take a gradient-descent step scaled by the learning rate times `scale`
+    ///
+    /// Used to weight a sample's update without copying its gradients.
+    pub fn apply_scaled_gradients(&mut self, gradients: &Gradients, scale: f64) {
+        let learning_rate = self.learning_rate * scale;
+        let parameters = self.weights.iter_mut().chain(self.biases.iter_mut());
+        let steps = gradients.weights.iter().chain(gradients.biases.iter());
+        for (parameter, gradient) in parameters.zip(steps) {
+            assert!(
+                parameter.rows == gradient.rows && parameter.cols == gradient.cols,
+                "Cannot subtract matrices with different dimensions"
+            );
+            for (p, g) in parameter.data.iter_mut().zip(&gradient.data) {
+                *p -= g * learning_rate;
+            }
+        }
+    }
 
-        }      
+    pub fn back_propogate(&mut self, inputs:Matrix, targets:Matrix) {
+        let gradients = self.compute_gradients(&inputs, &targets);
+        self.apply_gradients(&gradients);
     }
 
+    /// Train on every sample in order for `epochs` epochs, without printing
+    ///
+    /// `training::TrainingController` adds loss tracking, progress
+    /// callbacks and checkpoints; the CLI shows its progress on a bar.
     pub fn train(&mut self, inputs: Vec<Vec<f64>>, targets: Vec<Vec<f64>>, epochs: u32) {
-		for i in 1..=epochs {
-			if epochs < 100 || i % (epochs / 100) == 0 {
-				println!("Epoch {} of {}", i, epochs);
-			}
+		let mut workspace = self.workspace();
+		for _ in 1..=epochs {
 			for j in 0..inputs.len() {
-				let outputs = self.feed_forward(Matrix::from(inputs[j].clone()));
-				self.back_propogate(outputs,Matrix::from( targets[j].clone()));
+				self.train_sample_with(&inputs[j], &targets[j], &mut workspace);
 			}
 		}
 	}
@@ -0,0 +1,193 @@
+/// Input feature scaling fit on training data
+///
+/// Sigmoid units saturate on large inputs, so features measured on very
+/// different scales (the iris measurements in cm, raw sensor readings) train
+/// slowly unless they are rescaled first. Set `TrainingConfig::normalization`
+/// and the controller fits a `Normalizer` on the training inputs, trains on
+/// the scaled data and saves the normalizer in checkpoints. The CLI `eval`
+/// command, the server's `/api/eval` and the WASM `evaluate` then scale raw
+/// inputs the same way before the forward pass.
+///
+/// The same transforms scale regression targets with a large range into the
+/// output range of the network (`TrainingConfig::target_normalization`);
+/// `Normalizer::invert` maps predictions back to the original units.
+use crate::dataset::Dataset;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Samples read at a time by `Normalization::fit_dataset`
+const FIT_CHUNK: usize = 4096;
+
+/// How inputs are scaled, chosen in `TrainingConfig::normalization`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Normalization {
+    /// Map each feature's training range to [0, 1]
+    MinMax,
+
+    /// Shift each feature to mean 0 and scale it to standard deviation 1
+    ZScore,
+}
+
+impl Normalization {
+    /// Lowercase name (`minmax`, `zscore`)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Normalization::MinMax => "minmax",
+            Normalization::ZScore => "zscore",
+        }
+    }
+
+    /// Fit a normalizer to `inputs`
+    ///
+    /// A feature that is constant in `inputs` is only shifted, not scaled.
+    pub fn fit(&self, inputs: &[Vec<f64>]) -> Normalizer {
+        let dim = inputs.first().map_or(0, Vec::len);
+        let column = |j: usize| inputs.iter().map(move |x| x[j]);
+        match self {
+            Normalization::MinMax => Normalizer::MinMax {
+                min: (0..dim).map(|j| column(j).fold(f64::INFINITY, f64::min)).collect(),
+                max: (0..dim).map(|j| column(j).fold(f64::NEG_INFINITY, f64::max)).collect(),
+            },
+            Normalization::ZScore => {
+                let n = inputs.len() as f64;
+                let mean: Vec<f64> = (0..dim).map(|j| column(j).sum::<f64>() / n).collect();
+                let std_dev = (0..dim)
+                    .map(|j| (column(j).map(|x| (x - mean[j]).powi(2)).sum::<f64>() / n).sqrt())
+                    .collect();
+                Normalizer::ZScore { mean, std_dev }
+            }
+        }
+    }
+
+    /// Fit a normalizer to the inputs of `data` in one pass, a chunk at a time
+    ///
+    /// Unlike `fit`, the inputs need not fit in memory together, so this
+    /// suits file-backed datasets. The result matches `fit` up to rounding.
+    pub fn fit_dataset<D: Dataset + ?Sized>(&self, data: &D) -> Normalizer {
+        self.fit_chunks(data.input_dim(), data.len(), |range| data.chunk(range).0)
+    }
+
+    /// Fit a normalizer to the targets of `data`, like `fit_dataset`
+    pub fn fit_dataset_targets<D: Dataset + ?Sized>(&self, data: &D) -> Normalizer {
+        self.fit_chunks(data.target_dim(), data.len(), |range| data.chunk(range).1)
+    }
+
+    /// Fit to `len` vectors of length `dim` read by `read` a chunk at a time
+    fn fit_chunks(&self, dim: usize, len: usize, read: impl Fn(Range<usize>) -> Vec<Vec<f64>>) -> Normalizer {
+        let mut count = 0.0;
+        // Running min and max, or Welford's running mean and sum of squared deviations
+        let (mut low, mut high) = match self {
+            Normalization::MinMax => (vec![f64::INFINITY; dim], vec![f64::NEG_INFINITY; dim]),
+            Normalization::ZScore => (vec![0.0; dim], vec![0.0; dim]),
+        };
+        for start in (0..len).step_by(FIT_CHUNK) {
+            for input in &read(start..(start + FIT_CHUNK).min(len)) {
+                count += 1.0;
+                for (j, &x) in input.iter().enumerate().take(dim) {
+                    match self {
+                        Normalization::MinMax => {
+                            low[j] = low[j].min(x);
+                            high[j] = high[j].max(x);
+                        }
+                        Normalization::ZScore => {
+                            let delta = x - low[j];
+                            low[j] += delta / count;
+                            high[j] += delta * (x - low[j]);
+                        }
+                    }
+                }
+            }
+        }
+        match self {
+            Normalization::MinMax => Normalizer::MinMax { min: low, max: high },
+            Normalization::ZScore => Normalizer::ZScore {
+                std_dev: high.iter().map(|m2| (m2 / count).sqrt()).collect(),
+                mean: low,
+            },
+        }
+    }
+}
+
+/// A fitted input transform, saved in checkpoints
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Normalizer {
+    /// `(x - min) / (max - min)` per feature
+    MinMax { min: Vec<f64>, max: Vec<f64> },
+
+    /// `(x - mean) / std_dev` per feature
+    ZScore { mean: Vec<f64>, std_dev: Vec<f64> },
+}
+
+impl Normalizer {
+    /// The kind of normalization this was fit with
+    pub fn kind(&self) -> Normalization {
+        match self {
+            Normalizer::MinMax { .. } => Normalization::MinMax,
+            Normalizer::ZScore { .. } => Normalization::ZScore,
+        }
+    }
+
+    /// Number of features the normalizer was fit on
+    pub fn dim(&self) -> usize {
+        match self {
+            Normalizer::MinMax { min, .. } => min.len(),
+            Normalizer::ZScore { mean, .. } => mean.len(),
+        }
+    }
+
+    /// Offset and scale of every feature
+    fn coefficients(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        let (offsets, spreads) = match self {
+            Normalizer::MinMax { min, max } => (min, max),
+            Normalizer::ZScore { mean, std_dev } => (mean, std_dev),
+        };
+        offsets.iter().zip(spreads).map(|(&offset, &spread)| {
+            let scale = match self {
+                Normalizer::MinMax { .. } => spread - offset,
+                Normalizer::ZScore { .. } => spread,
+            };
+            // Constant features are only shifted
+            (offset, if scale > 0.0 { scale } else { 1.0 })
+        })
+    }
+
+    /// Scale `input` in place
+    ///
+    /// Values beyond `dim()` are left unchanged, so a wrong-sized input still
+    /// reaches the network's own size check.
+    pub fn apply(&self, input: &mut [f64]) {
+        for (x, (offset, scale)) in input.iter_mut().zip(self.coefficients()) {
+            *x = (*x - offset) / scale;
+        }
+    }
+
+    /// Scaled copy of `input`
+    pub fn transform(&self, input: &[f64]) -> Vec<f64> {
+        let mut output = input.to_vec();
+        self.apply(&mut output);
+        output
+    }
+
+    /// Scale every input in place
+    pub fn apply_all(&self, inputs: &mut [Vec<f64>]) {
+        for input in inputs {
+            self.apply(input);
+        }
+    }
+
+    /// Undo `apply` in place, e.g. to turn a prediction of scaled targets
+    /// back into the original units
+    pub fn invert(&self, output: &mut [f64]) {
+        for (y, (offset, scale)) in output.iter_mut().zip(self.coefficients()) {
+            *y = *y * scale + offset;
+        }
+    }
+
+    /// Copy of `output` with `apply` undone
+    pub fn inverse_transform(&self, output: &[f64]) -> Vec<f64> {
+        let mut input = output.to_vec();
+        self.invert(&mut input);
+        input
+    }
+}
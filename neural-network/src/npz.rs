@@ -0,0 +1,325 @@
+/// Network parameters as a NumPy `.npz` archive
+///
+/// `save` writes the weights and biases of a `Network` as an uncompressed
+/// `.npz` file, the zip of `.npy` arrays that `numpy.savez` writes and
+/// `numpy.load` reads. Layer `i` is stored as two little-endian `float64`
+/// arrays, next to two scalars describing the network:
+///
+/// ```text
+/// layers.{i}.weight   [outputs, inputs]
+/// layers.{i}.bias     [outputs]
+/// activation          unicode scalar, e.g. 'sigmoid'
+/// learning_rate       float64 scalar
+/// ```
+///
+/// `load` also reads archives written by `numpy.savez_compressed`, `float32`
+/// and Fortran-ordered arrays, and biases of shape `[outputs, 1]`. Without
+/// the scalars the activation is sigmoid and the learning rate defaults to
+/// `DEFAULT_LEARNING_RATE`.
+use crate::activations::{Activation, SIGMOID};
+use crate::matrix::Matrix;
+use crate::network::Network;
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Learning rate of networks loaded from archives without a `learning_rate`
+pub const DEFAULT_LEARNING_RATE: f64 = 0.5;
+
+/// Magic string at the start of every `.npy` array
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+/// Write the parameters of `network` to an `.npz` file
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written
+pub fn save(network: &Network, path: &Path) -> Result<()> {
+    let bytes = to_bytes(network)?;
+    fs::write(path, bytes).with_context(|| format!("Failed to write npz file {}", path.display()))
+}
+
+/// Load a network from an `.npz` file
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read, is not an `.npz` archive,
+/// or its arrays do not form a stack of dense layers
+pub fn load(path: &Path) -> Result<Network> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read npz file {}", path.display()))?;
+    from_bytes(&bytes).with_context(|| format!("Failed to load npz file {}", path.display()))
+}
+
+/// Encode the parameters and metadata of `network` as `.npz` bytes
+///
+/// # Errors
+///
+/// Returns an error if the archive cannot be written
+pub fn to_bytes(network: &Network) -> Result<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let mut add = |name: &str, array: Vec<u8>| -> Result<()> {
+        zip.start_file(format!("{}.npy", name), options)?;
+        zip.write_all(&array)?;
+        Ok(())
+    };
+    for (i, (weights, bias)) in network.weights.iter().zip(&network.biases).enumerate() {
+        add(&format!("layers.{}.weight", i), npy("<f8", &[weights.rows, weights.cols], &le_bytes(&weights.data)))?;
+        add(&format!("layers.{}.bias", i), npy("<f8", &[bias.rows], &le_bytes(&bias.data)))?;
+    }
+    let activation: Vec<u8> = network.activation.name.chars().flat_map(|c| u32::from(c).to_le_bytes()).collect();
+    add("activation", npy(&format!("<U{}", network.activation.name.chars().count()), &[], &activation))?;
+    add("learning_rate", npy("<f8", &[], &network.learning_rate.to_le_bytes()))?;
+    Ok(zip.finish()?.into_inner())
+}
+
+/// Decode a network from `.npz` bytes
+///
+/// # Errors
+///
+/// Returns an error if the bytes are not a zip archive, an array is missing,
+/// unexpected or has the wrong shape or dtype, or the activation is unknown
+pub fn from_bytes(bytes: &[u8]) -> Result<Network> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).context("Not an npz archive")?;
+    let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+    let mut array = |name: &str| -> Result<Option<Array>> {
+        let mut file = match archive.by_name(&format!("{}.npy", name)) {
+            Ok(file) => file,
+            Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        Array::decode(&bytes).with_context(|| format!("Invalid array '{}'", name)).map(Some)
+    };
+
+    let mut sizes = Vec::new();
+    let mut parameters = Vec::new();
+    while let Some(weights) = array(&format!("layers.{}.weight", parameters.len()))? {
+        let i = parameters.len();
+        let &[rows, cols] = &weights.shape[..] else {
+            anyhow::bail!("Array 'layers.{}.weight' has shape {:?}, expected [outputs, inputs]", i, weights.shape);
+        };
+        match sizes.last() {
+            None => sizes.push(cols),
+            Some(&previous) if previous != cols => anyhow::bail!(
+                "Array 'layers.{}.weight' takes {} inputs but layer {} has {} outputs",
+                i,
+                cols,
+                i - 1,
+                previous
+            ),
+            Some(_) => {}
+        }
+        sizes.push(rows);
+
+        let name = format!("layers.{}.bias", i);
+        let bias = array(&name)?.with_context(|| format!("Missing array '{}'", name))?;
+        if bias.shape != [rows] && bias.shape != [rows, 1] {
+            anyhow::bail!("Array '{}' has shape {:?}, expected [{}]", name, bias.shape, rows);
+        }
+        parameters.push((
+            weights.matrix(&format!("layers.{}.weight", i))?,
+            Matrix::new(rows, 1, bias.floats(&name)?)?,
+        ));
+    }
+    if parameters.is_empty() {
+        anyhow::bail!("No 'layers.0.weight' array found");
+    }
+
+    let activation = match array("activation")? {
+        Some(array) => {
+            let name = array.string().context("Array 'activation' is not a string")?;
+            Activation::from_name(&name).with_context(|| format!("Unsupported activation '{}'", name))?
+        }
+        None => SIGMOID,
+    };
+    let learning_rate = match array("learning_rate")? {
+        Some(array) => match array.floats("learning_rate")?[..] {
+            [rate] => rate,
+            _ => anyhow::bail!("Array 'learning_rate' must hold a single value"),
+        },
+        None => DEFAULT_LEARNING_RATE,
+    };
+    let expected = |name: &str| {
+        let name = name.strip_suffix(".npy").unwrap_or(name);
+        name == "activation" || name == "learning_rate" || is_parameter(name, parameters.len())
+    };
+    if let Some(name) = names.iter().find(|name| !expected(name)) {
+        anyhow::bail!("Unexpected array '{}'", name);
+    }
+
+    let mut network = Network::new(sizes, activation, learning_rate);
+    for (i, (weights, bias)) in parameters.into_iter().enumerate() {
+        network.weights[i] = weights;
+        network.biases[i] = bias;
+    }
+    network.validate_shapes()?;
+    Ok(network)
+}
+
+/// Whether `name` is the weight or bias of one of the first `layers` layers
+fn is_parameter(name: &str, layers: usize) -> bool {
+    (0..layers).any(|i| name == format!("layers.{}.weight", i) || name == format!("layers.{}.bias", i))
+}
+
+/// Little-endian bytes of `values`
+fn le_bytes(values: &[f64]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// A version 1.0 `.npy` array of dtype `descr` in C order
+fn npy(descr: &str, shape: &[usize], data: &[u8]) -> Vec<u8> {
+    let shape = match shape {
+        [n] => format!("({},)", n),
+        _ => format!("({})", shape.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(", ")),
+    };
+    let mut header = format!("{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}", descr, shape);
+    // NumPy pads the header with spaces so the data starts 64-byte aligned
+    let unpadded = MAGIC.len() + 4 + header.len() + 1;
+    header.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+    header.push('\n');
+
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend_from_slice(&[1, 0]);
+    bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(header.as_bytes());
+    bytes.extend_from_slice(data);
+    bytes
+}
+
+/// A decoded `.npy` array
+struct Array {
+    descr: String,
+    fortran_order: bool,
+    shape: Vec<usize>,
+    data: Vec<u8>,
+}
+
+impl Array {
+    /// Decode a `.npy` array of any format version
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        let rest = bytes.strip_prefix(MAGIC).context("Not a .npy array")?;
+        let (header_len, rest) = match rest {
+            [1, _, a, b, rest @ ..] => (usize::from(u16::from_le_bytes([*a, *b])), rest),
+            [2 | 3, _, a, b, c, d, rest @ ..] => (usize::try_from(u32::from_le_bytes([*a, *b, *c, *d]))?, rest),
+            _ => anyhow::bail!("Unsupported .npy format version"),
+        };
+        if rest.len() < header_len {
+            anyhow::bail!("Truncated .npy header");
+        }
+        let (header, data) = rest.split_at(header_len);
+        let header = std::str::from_utf8(header).context("Invalid .npy header")?;
+
+        let descr = header_value(header, "descr")?;
+        let descr = descr.trim_matches(|c| c == '\'' || c == '"').to_string();
+        let fortran_order = match header_value(header, "fortran_order")? {
+            "True" => true,
+            "False" => false,
+            other => anyhow::bail!("Invalid fortran_order '{}'", other),
+        };
+        let shape = header_value(header, "shape")?
+            .trim_matches(|c| c == '(' || c == ')')
+            .split(',')
+            .map(str::trim)
+            .filter(|dim| !dim.is_empty())
+            .map(|dim| dim.parse().with_context(|| format!("Invalid dimension '{}'", dim)))
+            .collect::<Result<Vec<usize>>>()?;
+        Ok(Self { descr, fortran_order, shape, data: data.to_vec() })
+    }
+
+    /// Elements of a `float64` or `float32` array in storage order
+    fn floats(&self, name: &str) -> Result<Vec<f64>> {
+        let values: Vec<f64> = match self.descr.as_str() {
+            "<f8" | "|f8" => self.data.chunks_exact(8).map(|b| f64::from_le_bytes(b.try_into().unwrap())).collect(),
+            "<f4" | "|f4" => {
+                self.data.chunks_exact(4).map(|b| f64::from(f32::from_le_bytes(b.try_into().unwrap()))).collect()
+            }
+            descr => anyhow::bail!("Array '{}' has dtype {}; only <f8 and <f4 are supported", name, descr),
+        };
+        let expected: usize = self.shape.iter().product();
+        if values.len() != expected {
+            anyhow::bail!("Array '{}' of shape {:?} holds {} values", name, self.shape, values.len());
+        }
+        Ok(values)
+    }
+
+    /// The array as a matrix; vectors are a single row
+    fn matrix(&self, name: &str) -> Result<Matrix> {
+        let values = self.floats(name)?;
+        match self.shape[..] {
+            [n] => Ok(Matrix::new(1, n, values)?),
+            [rows, cols] if self.fortran_order => Ok(Matrix::new(cols, rows, values)?.transpose()),
+            [rows, cols] => Ok(Matrix::new(rows, cols, values)?),
+            _ => anyhow::bail!("Array '{}' of shape {:?} is not a matrix or vector", name, self.shape),
+        }
+    }
+
+    /// The value of a little-endian unicode scalar
+    fn string(&self) -> Option<String> {
+        if !self.descr.starts_with("<U") || !self.shape.is_empty() {
+            return None;
+        }
+        self.data
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+            .take_while(|&c| c != 0)
+            .map(char::from_u32)
+            .collect()
+    }
+}
+
+/// The raw text of entry `key` in a `.npy` header dictionary
+fn header_value<'a>(header: &'a str, key: &str) -> Result<&'a str> {
+    let start = header
+        .find(&format!("'{}':", key))
+        .with_context(|| format!("The .npy header has no '{}'", key))?
+        + key.len()
+        + 3;
+    let rest = header[start..].trim_start();
+    let end = if rest.starts_with('(') {
+        rest.find(')').map(|i| i + 1)
+    } else {
+        rest.find([',', '}'])
+    };
+    Ok(rest[..end.context("Malformed .npy header")?].trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_npy_header_is_aligned_and_parsed_back() {
+        let bytes = npy("<f8", &[3, 2], &le_bytes(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]));
+        assert_eq!((bytes.len() - 48) % 64, 0);
+
+        let array = Array::decode(&bytes).unwrap();
+        assert_eq!(array.descr, "<f8");
+        assert!(!array.fortran_order);
+        assert_eq!(array.shape, vec![3, 2]);
+        assert_eq!(array.matrix("w").unwrap().data, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    }
+
+    #[test]
+    fn test_fortran_order_is_transposed() {
+        let bytes = npy("<f8", &[2, 3], &le_bytes(&[1.0, 4.0, 2.0, 5.0, 3.0, 6.0]));
+        let mut array = Array::decode(&bytes).unwrap();
+        array.fortran_order = true;
+
+        let matrix = array.matrix("w").unwrap();
+        assert_eq!((matrix.rows, matrix.cols), (2, 3));
+        assert_eq!(matrix.data, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    }
+
+    #[test]
+    fn test_unicode_scalar() {
+        let data: Vec<u8> = "relu".chars().flat_map(|c| u32::from(c).to_le_bytes()).chain([0; 4]).collect();
+        let array = Array::decode(&npy("<U5", &[], &data)).unwrap();
+        assert!(array.shape.is_empty());
+        assert_eq!(array.string().as_deref(), Some("relu"));
+    }
+}
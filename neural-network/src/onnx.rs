@@ -0,0 +1,603 @@
+/// Import and export of simple multilayer perceptrons as ONNX files
+///
+/// `load` reads an ONNX model whose graph is a plain stack of dense layers,
+/// each followed by a sigmoid, as exported from PyTorch (`nn.Linear` +
+/// `nn.Sigmoid`), Keras or scikit-learn. A dense layer is either one `Gemm`
+/// node or a `MatMul` followed by an `Add` of the bias; `Identity` nodes are
+/// skipped. The weights must be stored as initializers in the file itself.
+/// Any other operator, a layer without a sigmoid, or a graph that branches is
+/// rejected, since `Network` applies the sigmoid after every layer.
+///
+/// `save` writes a sigmoid `Network` as such a graph: one `Gemm` (with
+/// `transB`) and one `Sigmoid` node per layer, with double-precision
+/// initializers `layers.{i}.weight` and `layers.{i}.bias`, the input `input`
+/// of shape `[batch, inputs]` and the output `output`.
+///
+/// The ONNX protobuf is encoded and decoded by hand: only the handful of
+/// fields needed for such graphs are handled, so no protobuf code generation
+/// is required.
+use crate::activations::SIGMOID;
+use crate::matrix::Matrix;
+use crate::network::Network;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// ONNX `TensorProto.DataType` of 32-bit floats
+const FLOAT: u64 = 1;
+
+/// ONNX `TensorProto.DataType` of 64-bit floats
+const DOUBLE: u64 = 11;
+
+/// ONNX IR version written by `save`
+const IR_VERSION: u64 = 8;
+
+/// Version of the default operator set written by `save`
+const OPSET_VERSION: u64 = 13;
+
+/// ONNX `AttributeProto.AttributeType` of integers
+const INT_ATTRIBUTE: u64 = 2;
+
+/// Load the network stored in an ONNX file
+///
+/// The imported network trains with `learning_rate` when fine-tuned.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or decoded, or its graph is
+/// not a stack of dense layers with sigmoid activations
+pub fn load(path: &Path, learning_rate: f64) -> Result<Network> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read ONNX model from {}", path.display()))?;
+    from_bytes(&bytes, learning_rate).with_context(|| format!("Failed to import ONNX model {}", path.display()))
+}
+
+/// Decode a network from the bytes of an ONNX file
+///
+/// # Errors
+///
+/// Returns an error if the bytes are not an ONNX model or its graph is not a
+/// stack of dense layers with sigmoid activations
+pub fn from_bytes(bytes: &[u8], learning_rate: f64) -> Result<Network> {
+    let graph = Graph::decode(bytes)?;
+    let layers = graph.dense_layers()?;
+
+    let mut sizes = vec![layers[0].0.cols];
+    sizes.extend(layers.iter().map(|(weights, _)| weights.rows));
+    let mut network = Network::new(sizes, SIGMOID, learning_rate);
+    for (i, (weights, bias)) in layers.into_iter().enumerate() {
+        network.weights[i] = weights;
+        network.biases[i] = bias;
+    }
+    network.validate_shapes()?;
+    Ok(network)
+}
+
+/// Write `network` as an ONNX file
+///
+/// # Errors
+///
+/// Returns an error if the network does not use the sigmoid activation or
+/// the file cannot be written
+pub fn save(network: &Network, path: &Path) -> Result<()> {
+    let bytes = to_bytes(network)?;
+    fs::write(path, bytes).with_context(|| format!("Failed to write ONNX model {}", path.display()))
+}
+
+/// Encode `network` as the bytes of an ONNX file
+///
+/// # Errors
+///
+/// Returns an error if the network does not use the sigmoid activation,
+/// the only one the graph written here can express
+pub fn to_bytes(network: &Network) -> Result<Vec<u8>> {
+    if network.activation.name != SIGMOID.name {
+        anyhow::bail!(
+            "Only sigmoid networks can be exported to ONNX, this one uses {}",
+            network.activation.name
+        );
+    }
+
+    let mut graph = Message::default();
+    let mut current = "input".to_string();
+    let layers = network.weights.len();
+    for i in 0..layers {
+        let (weight_name, bias_name) = (format!("layers.{}.weight", i), format!("layers.{}.bias", i));
+        let dense = format!("layers.{}.dense", i);
+        let output = if i + 1 == layers { "output".to_string() } else { format!("layers.{}.output", i) };
+
+        let mut trans_b = Message::default();
+        trans_b.string(1, "transB");
+        trans_b.varint(3, 1);
+        trans_b.varint(20, INT_ATTRIBUTE);
+        let mut gemm = Message::default();
+        for input in [&current, &weight_name, &bias_name] {
+            gemm.string(1, input);
+        }
+        gemm.string(2, &dense);
+        gemm.string(3, &format!("Gemm_{}", i));
+        gemm.string(4, "Gemm");
+        gemm.message(5, trans_b);
+        graph.message(1, gemm);
+
+        let mut sigmoid = Message::default();
+        sigmoid.string(1, &dense);
+        sigmoid.string(2, &output);
+        sigmoid.string(3, &format!("Sigmoid_{}", i));
+        sigmoid.string(4, "Sigmoid");
+        graph.message(1, sigmoid);
+
+        current = output;
+    }
+    graph.string(2, "neural-net-rs");
+    for (i, (weights, bias)) in network.weights.iter().zip(&network.biases).enumerate() {
+        graph.message(5, tensor(&format!("layers.{}.weight", i), &[weights.rows, weights.cols], &weights.data));
+        graph.message(5, tensor(&format!("layers.{}.bias", i), &[bias.rows], &bias.data));
+    }
+    graph.message(11, value_info("input", network.layers[0]));
+    graph.message(12, value_info("output", network.layers[network.layers.len() - 1]));
+
+    let mut opset = Message::default();
+    opset.string(1, "");
+    opset.varint(2, OPSET_VERSION);
+    let mut model = Message::default();
+    model.varint(1, IR_VERSION);
+    model.string(2, "neural-net-rs");
+    model.message(7, graph);
+    model.message(8, opset);
+    Ok(model.bytes)
+}
+
+/// A protobuf message being encoded
+#[derive(Default)]
+struct Message {
+    bytes: Vec<u8>,
+}
+
+impl Message {
+    fn raw_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.bytes.push((value as u8 & 0x7f) | 0x80);
+            value >>= 7;
+        }
+        self.bytes.push(value as u8);
+    }
+
+    fn varint(&mut self, field: u64, value: u64) {
+        self.raw_varint(field << 3);
+        self.raw_varint(value);
+    }
+
+    fn bytes(&mut self, field: u64, value: &[u8]) {
+        self.raw_varint((field << 3) | 2);
+        self.raw_varint(value.len() as u64);
+        self.bytes.extend_from_slice(value);
+    }
+
+    fn string(&mut self, field: u64, value: &str) {
+        self.bytes(field, value.as_bytes());
+    }
+
+    fn message(&mut self, field: u64, value: Message) {
+        self.bytes(field, &value.bytes);
+    }
+}
+
+/// A double `TensorProto` initializer
+fn tensor(name: &str, dims: &[usize], values: &[f64]) -> Message {
+    let mut tensor = Message::default();
+    for &dim in dims {
+        tensor.varint(1, dim as u64);
+    }
+    tensor.varint(2, DOUBLE);
+    tensor.string(8, name);
+    tensor.bytes(9, &values.iter().flat_map(|v| v.to_le_bytes()).collect::<Vec<_>>());
+    tensor
+}
+
+/// A `ValueInfoProto` of a `[batch, size]` double tensor
+fn value_info(name: &str, size: usize) -> Message {
+    let mut batch = Message::default();
+    batch.string(2, "batch");
+    let mut features = Message::default();
+    features.varint(1, size as u64);
+    let mut shape = Message::default();
+    shape.message(1, batch);
+    shape.message(1, features);
+    let mut tensor_type = Message::default();
+    tensor_type.varint(1, DOUBLE);
+    tensor_type.message(2, shape);
+    let mut type_proto = Message::default();
+    type_proto.message(1, tensor_type);
+    let mut info = Message::default();
+    info.string(1, name);
+    info.message(2, type_proto);
+    info
+}
+
+/// A protobuf field value, by wire type
+enum Value<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+/// Iterator over the `(field number, value)` pairs of a protobuf message
+struct Fields<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Fields<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn varint(&mut self) -> Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = self.bytes.split_first().context("Truncated varint")?;
+            self.bytes = rest;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        anyhow::bail!("Varint longer than 10 bytes")
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            anyhow::bail!("Field of {} bytes runs past the end of the message", len);
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn field(&mut self) -> Result<(u64, Value<'a>)> {
+        let key = self.varint()?;
+        let value = match key & 7 {
+            0 => Value::Varint(self.varint()?),
+            1 => Value::Fixed64(u64::from_le_bytes(self.take(8)?.try_into()?)),
+            2 => {
+                let len = usize::try_from(self.varint()?)?;
+                Value::Bytes(self.take(len)?)
+            }
+            5 => Value::Fixed32(u32::from_le_bytes(self.take(4)?.try_into()?)),
+            wire => anyhow::bail!("Unsupported protobuf wire type {}", wire),
+        };
+        Ok((key >> 3, value))
+    }
+}
+
+impl<'a> Iterator for Fields<'a> {
+    type Item = Result<(u64, Value<'a>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        let field = self.field();
+        if field.is_err() {
+            self.bytes = &[];
+        }
+        Some(field)
+    }
+}
+
+fn string(bytes: &[u8]) -> Result<String> {
+    String::from_utf8(bytes.to_vec()).context("Invalid UTF-8 string")
+}
+
+/// Values of a repeated varint field, packed or not
+fn push_varints(values: &mut Vec<u64>, value: Value) -> Result<()> {
+    match value {
+        Value::Varint(v) => values.push(v),
+        Value::Bytes(packed) => {
+            let mut fields = Fields::new(packed);
+            while !fields.bytes.is_empty() {
+                values.push(fields.varint()?);
+            }
+        }
+        _ => anyhow::bail!("Expected a varint field"),
+    }
+    Ok(())
+}
+
+/// An initializer: a constant tensor stored in the graph
+struct Tensor {
+    dims: Vec<usize>,
+    values: Vec<f64>,
+}
+
+impl Tensor {
+    /// Decode a `TensorProto`, returning its name
+    fn decode(bytes: &[u8]) -> Result<(String, Self)> {
+        let mut name = String::new();
+        let mut dims = Vec::new();
+        let mut data_type = 0;
+        let mut values = Vec::new();
+        let mut raw = None;
+        for field in Fields::new(bytes) {
+            match field? {
+                (1, value) => push_varints(&mut dims, value)?,
+                (2, Value::Varint(v)) => data_type = v,
+                (4, Value::Bytes(packed)) => {
+                    values.extend(packed.chunks_exact(4).map(|c| f64::from(f32::from_le_bytes(c.try_into().unwrap()))))
+                }
+                (4, Value::Fixed32(v)) => values.push(f64::from(f32::from_bits(v))),
+                (8, Value::Bytes(b)) => name = string(b)?,
+                (9, Value::Bytes(b)) => raw = Some(b),
+                (10, Value::Bytes(packed)) => {
+                    values.extend(packed.chunks_exact(8).map(|c| f64::from_le_bytes(c.try_into().unwrap())))
+                }
+                (10, Value::Fixed64(v)) => values.push(f64::from_bits(v)),
+                (14, Value::Varint(1)) => anyhow::bail!("Tensor '{}' uses external data, which is not supported", name),
+                _ => {}
+            }
+        }
+        if let Some(raw) = raw {
+            values = match data_type {
+                FLOAT => raw.chunks_exact(4).map(|c| f64::from(f32::from_le_bytes(c.try_into().unwrap()))).collect(),
+                DOUBLE => raw.chunks_exact(8).map(|c| f64::from_le_bytes(c.try_into().unwrap())).collect(),
+                _ => Vec::new(),
+            };
+        }
+        if data_type != FLOAT && data_type != DOUBLE {
+            anyhow::bail!("Tensor '{}' has data type {}; only float and double are supported", name, data_type);
+        }
+        let dims: Vec<usize> = dims.into_iter().map(usize::try_from).collect::<Result<_, _>>()?;
+        let expected: usize = dims.iter().product();
+        if values.len() != expected {
+            anyhow::bail!("Tensor '{}' of shape {:?} holds {} values", name, dims, values.len());
+        }
+        Ok((name, Self { dims, values }))
+    }
+
+    /// The tensor as a `rows x cols` matrix; vectors are a single row
+    fn matrix(&self, name: &str) -> Result<Matrix> {
+        let (rows, cols) = match self.dims[..] {
+            [n] => (1, n),
+            [rows, cols] => (rows, cols),
+            _ => anyhow::bail!("Initializer '{}' of shape {:?} is not a matrix or vector", name, self.dims),
+        };
+        Ok(Matrix::new(rows, cols, self.values.clone())?)
+    }
+}
+
+/// A node of the graph
+struct Node {
+    name: String,
+    op_type: String,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    /// Integer and float attributes (`transB`, `alpha`, ...)
+    attributes: HashMap<String, f64>,
+}
+
+impl Node {
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        let mut node = Node {
+            name: String::new(),
+            op_type: String::new(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            attributes: HashMap::new(),
+        };
+        for field in Fields::new(bytes) {
+            match field? {
+                (1, Value::Bytes(b)) => node.inputs.push(string(b)?),
+                (2, Value::Bytes(b)) => node.outputs.push(string(b)?),
+                (3, Value::Bytes(b)) => node.name = string(b)?,
+                (4, Value::Bytes(b)) => node.op_type = string(b)?,
+                (5, Value::Bytes(b)) => {
+                    let mut name = String::new();
+                    let mut value = None;
+                    for field in Fields::new(b) {
+                        match field? {
+                            (1, Value::Bytes(b)) => name = string(b)?,
+                            (2, Value::Fixed32(f)) => value = Some(f64::from(f32::from_bits(f))),
+                            (3, Value::Varint(i)) => value = Some(i as i64 as f64),
+                            _ => {}
+                        }
+                    }
+                    if let Some(value) = value {
+                        node.attributes.insert(name, value);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(node)
+    }
+
+    fn attribute(&self, name: &str, default: f64) -> f64 {
+        self.attributes.get(name).copied().unwrap_or(default)
+    }
+
+    /// Name for error messages: the node name, or its op type
+    fn label(&self) -> &str {
+        if self.name.is_empty() { &self.op_type } else { &self.name }
+    }
+}
+
+/// The parts of an ONNX graph needed to import it
+struct Graph {
+    nodes: Vec<Node>,
+    initializers: HashMap<String, Tensor>,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+}
+
+impl Graph {
+    /// Decode the graph of a `ModelProto`
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        let mut graph = None;
+        for field in Fields::new(bytes) {
+            if let (7, Value::Bytes(b)) = field.context("Not an ONNX model")? {
+                graph = Some(b);
+            }
+        }
+        let graph = graph.context("Not an ONNX model: no graph found")?;
+
+        let mut decoded = Graph {
+            nodes: Vec::new(),
+            initializers: HashMap::new(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+        };
+        for field in Fields::new(graph) {
+            match field.context("Malformed ONNX graph")? {
+                (1, Value::Bytes(b)) => decoded.nodes.push(Node::decode(b)?),
+                (5, Value::Bytes(b)) => {
+                    let (name, tensor) = Tensor::decode(b)?;
+                    decoded.initializers.insert(name, tensor);
+                }
+                (11, Value::Bytes(b)) => decoded.inputs.push(value_info_name(b)?),
+                (12, Value::Bytes(b)) => decoded.outputs.push(value_info_name(b)?),
+                _ => {}
+            }
+        }
+        // Older exporters also list initializers as graph inputs
+        decoded.inputs.retain(|name| !decoded.initializers.contains_key(name));
+        Ok(decoded)
+    }
+
+    fn initializer(&self, name: &str, node: &Node) -> Result<Matrix> {
+        let tensor = self.initializers.get(name).with_context(|| {
+            format!("Input '{}' of node '{}' is not a constant initializer", name, node.label())
+        })?;
+        tensor.matrix(name)
+    }
+
+    /// The only node reading `value`, if any
+    fn consumer(&self, value: &str) -> Result<Option<&Node>> {
+        let mut consumers = self.nodes.iter().filter(|node| node.inputs.iter().any(|input| input == value));
+        let first = consumers.next();
+        if consumers.next().is_some() {
+            anyhow::bail!("Value '{}' feeds more than one node; only sequential graphs can be imported", value);
+        }
+        Ok(first)
+    }
+
+    /// The weight (`out x in`) and bias (`out x 1`) of every layer, input first
+    fn dense_layers(&self) -> Result<Vec<(Matrix, Matrix)>> {
+        let [input] = &self.inputs[..] else {
+            anyhow::bail!("Expected one graph input, found {}", self.inputs.len());
+        };
+        let mut current = input.clone();
+        let mut layers: Vec<(Matrix, Matrix)> = Vec::new();
+        // Pre-activation layer waiting for its sigmoid
+        let mut pending: Option<(Matrix, Matrix)> = None;
+
+        while let Some(node) = self.consumer(&current)? {
+            let [output] = &node.outputs[..] else {
+                anyhow::bail!("Node '{}' must have exactly one output", node.label());
+            };
+            let other_input = || -> Result<&String> {
+                node.inputs
+                    .iter()
+                    .find(|input| **input != current)
+                    .with_context(|| format!("Node '{}' is missing an input", node.label()))
+            };
+            match node.op_type.as_str() {
+                "Identity" => {}
+                "Gemm" if pending.is_none() => {
+                    if node.inputs.first() != Some(&current) || node.attribute("transA", 0.0) != 0.0 {
+                        anyhow::bail!("Gemm node '{}' must multiply the layer input from the left", node.label());
+                    }
+                    let b = self.initializer(node.inputs.get(1).context("Gemm node has no weights")?, node)?;
+                    let mut weights = if node.attribute("transB", 0.0) != 0.0 { b } else { b.transpose() };
+                    let alpha = node.attribute("alpha", 1.0);
+                    let beta = node.attribute("beta", 1.0);
+                    weights.data.iter_mut().for_each(|w| *w *= alpha);
+                    let mut bias = match node.inputs.get(2).filter(|name| !name.is_empty()) {
+                        Some(name) => self.bias(name, node, weights.rows)?,
+                        None => Matrix::zeros(weights.rows, 1),
+                    };
+                    bias.data.iter_mut().for_each(|b| *b *= beta);
+                    pending = Some((weights, bias));
+                }
+                "MatMul" if pending.is_none() => {
+                    if node.inputs.first() != Some(&current) {
+                        anyhow::bail!("MatMul node '{}' must multiply the layer input from the left", node.label());
+                    }
+                    let weights = self.initializer(other_input()?, node)?.transpose();
+                    let rows = weights.rows;
+                    pending = Some((weights, Matrix::zeros(rows, 1)));
+                }
+                "Add" if pending.is_some() => {
+                    let (weights, bias) = pending.as_mut().expect("checked by the match guard");
+                    let added = self.bias(other_input()?, node, weights.rows)?;
+                    *bias = bias.add(&added);
+                }
+                "Sigmoid" => {
+                    let layer = pending.take().with_context(|| {
+                        format!("Sigmoid node '{}' does not follow a dense layer", node.label())
+                    })?;
+                    if let Some((previous, _)) = layers.last()
+                        && previous.rows != layer.0.cols
+                    {
+                        anyhow::bail!(
+                            "Layer {} takes {} inputs but the previous layer has {} outputs",
+                            layers.len(),
+                            layer.0.cols,
+                            previous.rows
+                        );
+                    }
+                    layers.push(layer);
+                }
+                "Gemm" | "MatMul" | "Add" => {
+                    anyhow::bail!(
+                        "Node '{}' ({}) is not part of a dense layer followed by a sigmoid",
+                        node.label(),
+                        node.op_type
+                    );
+                }
+                other => anyhow::bail!(
+                    "Unsupported ONNX operator '{}' in node '{}': only Gemm, MatMul, Add, Sigmoid and Identity can be imported",
+                    other,
+                    node.label()
+                ),
+            }
+            current = output.clone();
+        }
+
+        if pending.is_some() {
+            anyhow::bail!("The last layer has no Sigmoid activation; every layer of a Network applies a sigmoid");
+        }
+        if !self.outputs.contains(&current) {
+            anyhow::bail!("The layer stack ends at '{}', which is not a graph output", current);
+        }
+        if layers.is_empty() {
+            anyhow::bail!("The graph has no dense layers");
+        }
+        Ok(layers)
+    }
+
+    /// A bias initializer of `rows` values as a column
+    fn bias(&self, name: &str, node: &Node, rows: usize) -> Result<Matrix> {
+        let bias = self.initializer(name, node)?;
+        if bias.data.len() != rows {
+            anyhow::bail!(
+                "Bias '{}' of node '{}' has {} values, expected {}",
+                name,
+                node.label(),
+                bias.data.len(),
+                rows
+            );
+        }
+        Ok(Matrix::new(rows, 1, bias.data)?)
+    }
+}
+
+/// Name of a `ValueInfoProto`
+fn value_info_name(bytes: &[u8]) -> Result<String> {
+    for field in Fields::new(bytes) {
+        if let (1, Value::Bytes(b)) = field? {
+            return string(b);
+        }
+    }
+    Ok(String::new())
+}
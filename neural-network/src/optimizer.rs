@@ -0,0 +1,210 @@
+/// Update rules that turn gradients into parameter steps
+///
+/// `Sgd` is the plain gradient-descent step of `Network::apply_gradients`.
+/// `Momentum` and `Adam` keep running statistics of past gradients in an
+/// `OptimizerState`. Checkpoints written during training store that state, so
+/// a resumed run continues with the same velocities and moment estimates
+/// instead of starting them from zero.
+use crate::matrix::Matrix;
+use crate::network::{Gradients, Network};
+use serde::{Deserialize, Serialize};
+
+/// Update rule selected by `TrainingConfig::optimizer`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Optimizer {
+    /// Plain gradient descent: `p -= lr * g`
+    #[default]
+    Sgd,
+
+    /// Heavy-ball momentum: `v = beta * v + g; p -= lr * v`
+    Momentum { beta: f64 },
+
+    /// Adam with bias-corrected first and second moment estimates
+    Adam { beta1: f64, beta2: f64, epsilon: f64 },
+}
+
+impl Optimizer {
+    /// Momentum with the usual `beta = 0.9`
+    pub fn momentum() -> Self {
+        Optimizer::Momentum { beta: 0.9 }
+    }
+
+    /// Adam with the defaults from the paper
+    pub fn adam() -> Self {
+        Optimizer::Adam {
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1e-8,
+        }
+    }
+
+    /// Lowercase name (`sgd`, `momentum`, `adam`)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Optimizer::Sgd => "sgd",
+            Optimizer::Momentum { .. } => "momentum",
+            Optimizer::Adam { .. } => "adam",
+        }
+    }
+
+    /// Fresh state for training `network`, with all statistics at zero
+    pub fn initial_state(&self, network: &Network) -> OptimizerState {
+        let zeros = || -> Vec<Matrix> {
+            parameters(network).map(|p| Matrix::zeros(p.rows, p.cols)).collect()
+        };
+        match self {
+            Optimizer::Sgd => OptimizerState::Sgd,
+            Optimizer::Momentum { .. } => OptimizerState::Momentum { velocity: zeros() },
+            Optimizer::Adam { .. } => OptimizerState::Adam {
+                step: 0,
+                first_moment: zeros(),
+                second_moment: zeros(),
+            },
+        }
+    }
+}
+
+/// Running statistics of an `Optimizer`
+///
+/// Each list holds one matrix per parameter matrix of the network: the
+/// weights from input to output, then the biases.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum OptimizerState {
+    /// Plain gradient descent keeps no state
+    #[default]
+    Sgd,
+
+    Momentum {
+        velocity: Vec<Matrix>,
+    },
+
+    Adam {
+        /// Number of updates taken, for bias correction
+        step: u64,
+        first_moment: Vec<Matrix>,
+        second_moment: Vec<Matrix>,
+    },
+}
+
+/// Weight matrices followed by bias matrices
+fn parameters(network: &Network) -> impl Iterator<Item = &Matrix> {
+    network.weights.iter().chain(&network.biases)
+}
+
+impl OptimizerState {
+    /// Whether this state belongs to `optimizer` and fits `network`
+    pub fn matches(&self, optimizer: &Optimizer, network: &Network) -> bool {
+        let fits = |matrices: &[Matrix]| {
+            matrices.len() == network.weights.len() + network.biases.len()
+                && matrices.iter().zip(parameters(network)).all(|(m, p)| m.rows == p.rows && m.cols == p.cols)
+        };
+        match (optimizer, self) {
+            (Optimizer::Sgd, OptimizerState::Sgd) => true,
+            (Optimizer::Momentum { .. }, OptimizerState::Momentum { velocity }) => fits(velocity),
+            (
+                Optimizer::Adam { .. },
+                OptimizerState::Adam {
+                    first_moment,
+                    second_moment,
+                    ..
+                },
+            ) => fits(first_moment) && fits(second_moment),
+            _ => false,
+        }
+    }
+
+    /// Update `network` with `gradients` scaled by `scale`
+    ///
+    /// The state is reset first if it does not match `optimizer` and the
+    /// network. With `Sgd` this is `Network::apply_scaled_gradients`.
+    pub fn apply(&mut self, optimizer: &Optimizer, network: &mut Network, gradients: &Gradients, scale: f64) {
+        if !self.matches(optimizer, network) {
+            *self = optimizer.initial_state(network);
+        }
+        if *optimizer == Optimizer::Sgd {
+            network.apply_scaled_gradients(gradients, scale);
+            return;
+        }
+        let learning_rate = network.learning_rate;
+        let parameters = network.weights.iter_mut().chain(network.biases.iter_mut());
+        let steps = gradients.weights.iter().chain(&gradients.biases);
+
+        match (optimizer, self) {
+            (Optimizer::Momentum { beta }, OptimizerState::Momentum { velocity }) => {
+                for ((parameter, gradient), velocity) in parameters.zip(steps).zip(velocity) {
+                    for ((p, g), v) in parameter.data.iter_mut().zip(&gradient.data).zip(&mut velocity.data) {
+                        *v = beta * *v + g * scale;
+                        *p -= learning_rate * *v;
+                    }
+                }
+            }
+            (
+                Optimizer::Adam { beta1, beta2, epsilon },
+                OptimizerState::Adam {
+                    step,
+                    first_moment,
+                    second_moment,
+                },
+            ) => {
+                *step += 1;
+                let correction1 = 1.0 - beta1.powf(*step as f64);
+                let correction2 = 1.0 - beta2.powf(*step as f64);
+                let moments = first_moment.iter_mut().zip(second_moment.iter_mut());
+                for ((parameter, gradient), (m, v)) in parameters.zip(steps).zip(moments) {
+                    let values = parameter.data.iter_mut().zip(&gradient.data);
+                    for ((p, g), (m, v)) in values.zip(m.data.iter_mut().zip(&mut v.data)) {
+                        let g = g * scale;
+                        *m = beta1 * *m + (1.0 - beta1) * g;
+                        *v = beta2 * *v + (1.0 - beta2) * g * g;
+                        *p -= learning_rate * (*m / correction1) / ((*v / correction2).sqrt() + epsilon);
+                    }
+                }
+            }
+            _ => unreachable!("state was reset to match the optimizer"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activations::SIGMOID;
+
+    fn unit_gradients(network: &Network) -> Gradients {
+        let ones = |m: &Matrix| Matrix::new(m.rows, m.cols, vec![1.0; m.rows * m.cols]).unwrap();
+        Gradients {
+            weights: network.weights.iter().map(ones).collect(),
+            biases: network.biases.iter().map(ones).collect(),
+        }
+    }
+
+    #[test]
+    fn test_momentum_accumulates_velocity() {
+        let mut network = Network::new_seeded(vec![2, 1], SIGMOID, 0.1, 1);
+        let before = network.weights[0].data[0];
+        let gradients = unit_gradients(&network);
+        let optimizer = Optimizer::Momentum { beta: 0.5 };
+        let mut state = OptimizerState::default();
+
+        state.apply(&optimizer, &mut network, &gradients, 1.0);
+        state.apply(&optimizer, &mut network, &gradients, 1.0);
+
+        // Steps of 0.1 * 1 and 0.1 * (0.5 + 1)
+        assert!((before - network.weights[0].data[0] - 0.25).abs() < 1e-12);
+        assert!(state.matches(&optimizer, &network));
+    }
+
+    #[test]
+    fn test_adam_first_step_is_learning_rate() {
+        let mut network = Network::new_seeded(vec![2, 1], SIGMOID, 0.01, 1);
+        let before = network.biases[0].data[0];
+        let gradients = unit_gradients(&network);
+        let mut state = OptimizerState::default();
+
+        state.apply(&Optimizer::adam(), &mut network, &gradients, 3.0);
+
+        // Bias correction makes the first step lr * g / |g|
+        assert!((before - network.biases[0].data[0] - 0.01).abs() < 1e-9);
+        assert!(matches!(state, OptimizerState::Adam { step: 1, .. }));
+    }
+}
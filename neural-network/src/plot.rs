@@ -0,0 +1,333 @@
+/// SVG charts of training histories and evaluations
+///
+/// This module renders series such as the loss or weight norms recorded in a
+/// `TrainingHistory` as standalone SVG line charts, for the `visualize` tool
+/// and the CLI's `plot-loss` command, and confusion matrices as heatmaps for
+/// the CLI's `test` command.
+use crate::history::TrainingHistory;
+use crate::metrics::ConfusionMatrix;
+
+/// Named `(epoch, value)` series for a line chart
+pub type Series = (String, Vec<(u32, f64)>);
+
+/// Line colors of the series, cycled when there are more series
+pub const SERIES_COLORS: [&str; 6] = ["#4a90e2", "#e24a4a", "#50b848", "#f5a623", "#9b59b6", "#1abc9c"];
+
+/// Size and value axis of a chart
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChartOptions {
+    /// Width of the SVG canvas in pixels
+    pub width: u32,
+
+    /// Height of the SVG canvas in pixels
+    pub height: u32,
+
+    /// Plot values on a base-10 logarithmic axis; values that are not
+    /// positive are left out
+    pub log_scale: bool,
+}
+
+impl Default for ChartOptions {
+    fn default() -> Self {
+        ChartOptions {
+            width: 1200,
+            height: 800,
+            log_scale: false,
+        }
+    }
+}
+
+/// Training and, if recorded, validation loss of `history`
+pub fn loss_series(history: &TrainingHistory) -> Vec<Series> {
+    let mut series = vec![("Training loss".to_string(), history.loss_series())];
+    let validation = history.validation_losses();
+    if !validation.is_empty() {
+        series.push(("Validation loss".to_string(), validation));
+    }
+    series
+}
+
+/// Render the loss curves of `history`
+pub fn loss_chart_svg(history: &TrainingHistory, options: &ChartOptions) -> String {
+    line_chart_svg("Loss", "MSE", &loss_series(history), options)
+}
+
+/// Mapping of values to the vertical axis
+struct ValueAxis {
+    log_scale: bool,
+    /// Lowest and highest value (their logarithms on a log axis)
+    low: f64,
+    high: f64,
+}
+
+impl ValueAxis {
+    fn of(series: &[Series], log_scale: bool) -> Self {
+        let values = || series.iter().flat_map(|(_, s)| s.iter().map(|&(_, v)| v)).filter(|v| v.is_finite());
+        if log_scale {
+            let positive = || values().filter(|&v| v > 0.0).map(f64::log10);
+            let low = positive().fold(f64::INFINITY, f64::min);
+            let high = positive().fold(f64::NEG_INFINITY, f64::max);
+            if low > high {
+                return ValueAxis { log_scale, low: 0.0, high: 1.0 };
+            }
+            // Whole decades, at least one apart
+            let (low, high) = (low.floor(), high.ceil());
+            ValueAxis { log_scale, low, high: high.max(low + 1.0) }
+        } else {
+            let high = values().fold(0.0_f64, f64::max).max(f64::EPSILON);
+            ValueAxis { log_scale, low: 0.0, high }
+        }
+    }
+
+    /// Position of `value` from 0 (bottom) to 1 (top), if it can be drawn
+    fn fraction(&self, value: f64) -> Option<f64> {
+        if !value.is_finite() || (self.log_scale && value <= 0.0) {
+            return None;
+        }
+        let value = if self.log_scale { value.log10() } else { value };
+        Some((value - self.low) / (self.high - self.low))
+    }
+
+    /// Label of the tick at `fraction` of the axis
+    fn label(&self, fraction: f64) -> String {
+        let value = self.low + fraction * (self.high - self.low);
+        if self.log_scale {
+            format!("{:e}", 10f64.powf(value.round()))
+        } else {
+            format!("{:.3}", value)
+        }
+    }
+
+    /// Number of tick intervals: one per decade on a log axis
+    fn ticks(&self) -> usize {
+        if self.log_scale { (self.high - self.low).round() as usize } else { 5 }
+    }
+}
+
+/// Render one polyline per series on shared epoch and value axes
+///
+/// A linear value axis starts at zero; a logarithmic one spans the decades
+/// of the positive values.
+pub fn line_chart_svg(title: &str, y_label: &str, series: &[Series], options: &ChartOptions) -> String {
+    let width = options.width as f64;
+    let height = options.height as f64;
+    let margin = 80.0;
+    let plot_width = width - 2.0 * margin;
+    let plot_height = height - 2.0 * margin;
+
+    let points = || series.iter().flat_map(|(_, s)| s.iter().copied());
+    let first_epoch = points().map(|(e, _)| e).min().unwrap_or(0) as f64;
+    let last_epoch = points().map(|(e, _)| e).max().unwrap_or(0) as f64;
+    let epoch_span = (last_epoch - first_epoch).max(1.0);
+    let axis = ValueAxis::of(series, options.log_scale);
+
+    let to_x = |epoch: u32| margin + (epoch as f64 - first_epoch) / epoch_span * plot_width;
+    let to_y = |fraction: f64| margin + plot_height - fraction * plot_height;
+
+    let mut svg = String::new();
+    svg.push_str(&format!(
+        r##"<?xml version="1.0" encoding="UTF-8"?>
+<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}">
+<defs>
+  <style>
+    .axis {{ stroke: #333; stroke-width: 1; }}
+    .grid {{ stroke: #ddd; stroke-width: 1; }}
+    .series {{ fill: none; stroke-width: 2; }}
+    .tick-label {{ font-family: Arial, sans-serif; font-size: 11px; fill: #666; }}
+    .axis-label {{ font-family: Arial, sans-serif; font-size: 14px; fill: #333; }}
+    .title {{ font-family: Arial, sans-serif; font-size: 20px; font-weight: bold; fill: #333; }}
+  </style>
+</defs>
+
+<!-- Background -->
+<rect width="{w}" height="{h}" fill="#f5f7fa"/>
+
+<!-- Title -->
+<text x="{cx}" y="30" class="title" text-anchor="middle">{title}</text>
+
+"##,
+        w = options.width,
+        h = options.height,
+        cx = options.width / 2,
+        title = title,
+    ));
+
+    // Grid lines and tick labels
+    svg.push_str("<!-- Axes -->\n");
+    let value_ticks = axis.ticks();
+    for i in 0..=value_ticks {
+        let fraction = i as f64 / value_ticks as f64;
+        let y = to_y(fraction);
+        svg.push_str(&format!(
+            r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" class="grid"/>
+<text x="{:.1}" y="{:.1}" class="tick-label" text-anchor="end">{}</text>
+"##,
+            margin, y, margin + plot_width, y,
+            margin - 8.0, y + 4.0, axis.label(fraction)
+        ));
+    }
+    let epoch_ticks = 5;
+    for i in 0..=epoch_ticks {
+        let fraction = i as f64 / epoch_ticks as f64;
+        svg.push_str(&format!(
+            r##"<text x="{:.1}" y="{:.1}" class="tick-label" text-anchor="middle">{:.0}</text>
+"##,
+            margin + fraction * plot_width, margin + plot_height + 18.0, first_epoch + fraction * epoch_span
+        ));
+    }
+    svg.push_str(&format!(
+        r##"<line x1="{m:.1}" y1="{m:.1}" x2="{m:.1}" y2="{b:.1}" class="axis"/>
+<line x1="{m:.1}" y1="{b:.1}" x2="{r:.1}" y2="{b:.1}" class="axis"/>
+<text x="{cx:.1}" y="{xl:.1}" class="axis-label" text-anchor="middle">Epoch</text>
+<text x="20" y="{cy:.1}" class="axis-label" text-anchor="middle" transform="rotate(-90 20 {cy:.1})">{yl}</text>
+"##,
+        m = margin,
+        b = margin + plot_height,
+        r = margin + plot_width,
+        cx = margin + plot_width / 2.0,
+        xl = margin + plot_height + 45.0,
+        cy = margin + plot_height / 2.0,
+        yl = if options.log_scale { format!("{} (log scale)", y_label) } else { y_label.to_string() },
+    ));
+
+    // One polyline per series
+    svg.push_str("<!-- Series -->\n");
+    for (i, (name, values)) in series.iter().enumerate() {
+        let color = SERIES_COLORS[i % SERIES_COLORS.len()];
+        let points: Vec<String> = values
+            .iter()
+            .filter_map(|&(epoch, value)| {
+                axis.fraction(value).map(|fraction| format!("{:.1},{:.1}", to_x(epoch), to_y(fraction)))
+            })
+            .collect();
+        svg.push_str(&format!(
+            r##"<polyline points="{}" stroke="{}" class="series">
+  <title>{}</title>
+</polyline>
+"##,
+            points.join(" "), color, name
+        ));
+    }
+
+    // Legend
+    svg.push_str(&format!(
+        "\n<!-- Legend -->\n<g transform=\"translate({:.1}, {:.1})\">\n",
+        margin + plot_width - 140.0,
+        margin + 10.0
+    ));
+    for (i, (name, _)) in series.iter().enumerate() {
+        let color = SERIES_COLORS[i % SERIES_COLORS.len()];
+        let y = i as f64 * 18.0;
+        svg.push_str(&format!(
+            r##"  <line x1="0" y1="{y:.1}" x2="30" y2="{y:.1}" stroke="{color}" stroke-width="3"/>
+  <text x="40" y="{ty:.1}" class="tick-label">{name}</text>
+"##,
+            y = y,
+            ty = y + 4.0,
+            color = color,
+            name = name
+        ));
+    }
+    svg.push_str("</g>\n\n</svg>");
+
+    svg
+}
+
+/// Render a confusion matrix as a heatmap, actual classes down and
+/// predicted classes across
+///
+/// Each cell shows its count and its share of the actual class; the shade
+/// follows that share, so a good model has a dark diagonal whatever the
+/// class sizes. The per-class accuracy is listed to the right of each row.
+pub fn confusion_matrix_svg(confusion: &ConfusionMatrix, options: &ChartOptions) -> String {
+    let width = options.width as f64;
+    let height = options.height as f64;
+    let margin = 100.0;
+    let classes = confusion.num_classes().max(1);
+    let side = (width.min(height) - 2.0 * margin).max(1.0);
+    let cell = side / classes as f64;
+    let left = (width - side) / 2.0;
+    let top = margin;
+
+    let mut svg = String::new();
+    svg.push_str(&format!(
+        r##"<?xml version="1.0" encoding="UTF-8"?>
+<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}">
+<defs>
+  <style>
+    .cell {{ stroke: #fff; stroke-width: 1; }}
+    .count {{ font-family: Arial, sans-serif; font-size: 13px; }}
+    .tick-label {{ font-family: Arial, sans-serif; font-size: 11px; fill: #666; }}
+    .axis-label {{ font-family: Arial, sans-serif; font-size: 14px; fill: #333; }}
+    .title {{ font-family: Arial, sans-serif; font-size: 20px; font-weight: bold; fill: #333; }}
+  </style>
+</defs>
+
+<!-- Background -->
+<rect width="{w}" height="{h}" fill="#f5f7fa"/>
+
+<!-- Title -->
+<text x="{cx}" y="30" class="title" text-anchor="middle">Confusion Matrix</text>
+<text x="{cx}" y="52" class="tick-label" text-anchor="middle">{correct} of {total} correct ({accuracy:.1}%)</text>
+
+<!-- Cells -->
+"##,
+        w = options.width,
+        h = options.height,
+        cx = options.width / 2,
+        correct = confusion.correct(),
+        total = confusion.total(),
+        accuracy = confusion.accuracy() * 100.0,
+    ));
+
+    for (actual, row) in confusion.counts.iter().enumerate() {
+        let support = confusion.support(actual);
+        for (predicted, &count) in row.iter().enumerate() {
+            let share = if support > 0 { count as f64 / support as f64 } else { 0.0 };
+            // From white to the first series color as the share grows
+            let shade = |full: f64| (255.0 - (255.0 - full) * share).round() as u8;
+            let (x, y) = (left + predicted as f64 * cell, top + actual as f64 * cell);
+            svg.push_str(&format!(
+                r##"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="rgb({},{},{})" class="cell">
+  <title>Actual {}, predicted {}: {} ({:.1}%)</title>
+</rect>
+<text x="{:.1}" y="{:.1}" class="count" text-anchor="middle" fill="{}">{}</text>
+"##,
+                x, y, cell, cell, shade(74.0), shade(144.0), shade(226.0),
+                actual, predicted, count, share * 100.0,
+                x + cell / 2.0, y + cell / 2.0 + 5.0, if share > 0.5 { "#fff" } else { "#333" }, count
+            ));
+        }
+    }
+
+    // Class labels, axis titles and per-class accuracy
+    svg.push_str("\n<!-- Labels -->\n");
+    for class in 0..confusion.num_classes() {
+        let center = class as f64 * cell + cell / 2.0;
+        let accuracy = confusion
+            .class_accuracy(class)
+            .map_or("-".to_string(), |a| format!("{:.1}%", a * 100.0));
+        svg.push_str(&format!(
+            r##"<text x="{:.1}" y="{:.1}" class="tick-label" text-anchor="middle">{}</text>
+<text x="{:.1}" y="{:.1}" class="tick-label" text-anchor="end">{}</text>
+<text x="{:.1}" y="{:.1}" class="tick-label" text-anchor="start">{}</text>
+"##,
+            left + center, top - 8.0, class,
+            left - 8.0, top + center + 4.0, class,
+            left + side + 8.0, top + center + 4.0, accuracy
+        ));
+    }
+    svg.push_str(&format!(
+        r##"<text x="{cx:.1}" y="{py:.1}" class="axis-label" text-anchor="middle">Predicted class</text>
+<text x="{ax:.1}" y="{cy:.1}" class="axis-label" text-anchor="middle" transform="rotate(-90 {ax:.1} {cy:.1})">Actual class</text>
+<text x="{rx:.1}" y="{py:.1}" class="axis-label" text-anchor="start">Accuracy</text>
+</svg>"##,
+        cx = left + side / 2.0,
+        py = top - 28.0,
+        ax = left - 40.0,
+        cy = top + side / 2.0,
+        rx = left + side + 8.0,
+    ));
+
+    svg
+}
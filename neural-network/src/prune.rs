@@ -0,0 +1,74 @@
+/// Magnitude pruning of trained weights
+///
+/// `prune` zeroes the smallest weights by absolute value across all layers
+/// (global, unstructured pruning) until the requested fraction of weights is
+/// zero. Biases are left untouched. A pruned network keeps its dense
+/// matrices; `sparse_size_bytes` estimates what storing only the nonzero
+/// weights would take, as a `u32` index and an `f64` value each.
+///
+/// ```
+/// use neural_network::{activations::SIGMOID, network::Network, prune};
+///
+/// let mut network = Network::new_seeded(vec![4, 8, 2], SIGMOID, 0.5, 42);
+/// prune::prune(&mut network, 0.75);
+/// assert_eq!(prune::sparsity(&network), 0.75);
+/// ```
+use crate::network::Network;
+
+/// Bytes of one stored nonzero weight: a `u32` index and an `f64` value
+const SPARSE_ENTRY_BYTES: usize = 4 + 8;
+
+/// Zero the smallest-magnitude weights until `sparsity` of all weights are zero
+///
+/// Weights that are already zero count towards the target. Returns the
+/// number of weights this call set to zero.
+///
+/// # Panics
+///
+/// Panics if `sparsity` is not within [0, 1]
+pub fn prune(network: &mut Network, sparsity: f64) -> usize {
+    assert!((0.0..=1.0).contains(&sparsity), "Sparsity must be between 0 and 1, got {}", sparsity);
+
+    let mut magnitudes: Vec<(f64, usize, usize)> = network
+        .weights
+        .iter()
+        .enumerate()
+        .flat_map(|(layer, w)| w.data.iter().enumerate().map(move |(i, v)| (v.abs(), layer, i)))
+        .collect();
+    let target = (sparsity * magnitudes.len() as f64).round() as usize;
+    magnitudes.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut pruned = 0;
+    for &(magnitude, layer, i) in &magnitudes[..target] {
+        if magnitude != 0.0 {
+            network.weights[layer].data[i] = 0.0;
+            pruned += 1;
+        }
+    }
+    pruned
+}
+
+/// Fraction of weights that are zero, 0 for a network without weights
+pub fn sparsity(network: &Network) -> f64 {
+    let total = weight_count(network);
+    if total == 0 {
+        return 0.0;
+    }
+    (total - nonzero_weights(network)) as f64 / total as f64
+}
+
+/// Number of weights, ignoring biases
+pub fn weight_count(network: &Network) -> usize {
+    network.weights.iter().map(|w| w.data.len()).sum()
+}
+
+/// Number of weights that are not zero
+pub fn nonzero_weights(network: &Network) -> usize {
+    network.weights.iter().flat_map(|w| &w.data).filter(|&&v| v != 0.0).count()
+}
+
+/// Bytes needed to store the nonzero weights sparsely and the biases densely
+pub fn sparse_size_bytes(network: &Network) -> usize {
+    let biases: usize = network.biases.iter().map(|b| b.data.len()).sum();
+    nonzero_weights(network) * SPARSE_ENTRY_BYTES + biases * 8
+}
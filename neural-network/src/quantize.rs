@@ -0,0 +1,306 @@
+/// Post-training int8 quantization
+///
+/// This module converts a trained `Network` into a `QuantizedNetwork` for
+/// inference. Each weight matrix is stored as signed 8-bit integers with a
+/// per-layer affine scale and zero-point, shrinking the model roughly 8x.
+/// `QuantizedNetwork::with_bits` uses fewer levels (down to 2 bits), which
+/// `size_bytes` counts as bit-packed storage.
+/// During inference the layer inputs are also quantized (symmetrically, per
+/// call) so that the matrix-vector products run on integers; biases stay in
+/// floating point and are added after dequantizing the accumulator.
+use crate::activations::Activation;
+use crate::network::Network;
+use anyhow::{Context, Result};
+use matrix::matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Affine int8 quantization parameters: `real = scale * (quantized - zero_point)`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QuantParams {
+    /// Step size between adjacent quantized values
+    pub scale: f64,
+
+    /// Quantized value that represents real zero
+    pub zero_point: i8,
+
+    /// Bit width; quantized values lie in `[-2^(bits-1), 2^(bits-1) - 1]`
+    #[serde(default = "default_bits")]
+    pub bits: u8,
+}
+
+fn default_bits() -> u8 {
+    8
+}
+
+impl QuantParams {
+    /// Choose int8 parameters covering the range of `values` (always including zero)
+    pub fn from_range(values: &[f64]) -> Self {
+        Self::from_range_bits(values, 8)
+    }
+
+    /// Choose parameters of the given bit width covering the range of
+    /// `values` (always including zero)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is not between 2 and 8
+    pub fn from_range_bits(values: &[f64], bits: u8) -> Self {
+        assert!((2..=8).contains(&bits), "Quantization needs between 2 and 8 bits, got {}", bits);
+        let min = values.iter().copied().fold(0.0_f64, f64::min);
+        let max = values.iter().copied().fold(0.0_f64, f64::max);
+
+        if max - min <= f64::EPSILON {
+            return QuantParams { scale: 1.0, zero_point: 0, bits };
+        }
+
+        let (low, high) = Self::limits(bits);
+        let scale = (max - min) / (high - low);
+        let zero_point = (low - min / scale).round().clamp(low, high) as i8;
+        QuantParams { scale, zero_point, bits }
+    }
+
+    /// Smallest and largest quantized value of a bit width
+    fn limits(bits: u8) -> (f64, f64) {
+        let half = (1i32 << (bits - 1)) as f64;
+        (-half, half - 1.0)
+    }
+
+    /// Quantize a single value, saturating at the range of the bit width
+    pub fn quantize(&self, value: f64) -> i8 {
+        let (low, high) = Self::limits(self.bits);
+        (value / self.scale + self.zero_point as f64)
+            .round()
+            .clamp(low, high) as i8
+    }
+
+    /// Recover the approximate real value of a quantized value
+    pub fn dequantize(&self, value: i8) -> f64 {
+        self.scale * (value as i32 - self.zero_point as i32) as f64
+    }
+}
+
+/// One fully connected layer with quantized weights
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuantizedLayer {
+    /// Number of output neurons
+    pub rows: usize,
+
+    /// Number of input neurons
+    pub cols: usize,
+
+    /// Row-major quantized weights
+    pub weights: Vec<i8>,
+
+    /// Quantization parameters shared by all weights of this layer
+    pub params: QuantParams,
+
+    /// Biases, kept in floating point
+    pub biases: Vec<f64>,
+}
+
+impl QuantizedLayer {
+    fn from_matrices(weights: &Matrix, biases: &Matrix, bits: u8) -> Self {
+        let params = QuantParams::from_range_bits(&weights.data, bits);
+        QuantizedLayer {
+            rows: weights.rows,
+            cols: weights.cols,
+            weights: weights.data.iter().map(|&w| params.quantize(w)).collect(),
+            params,
+            biases: biases.data.clone(),
+        }
+    }
+
+    /// Weights converted back to floating point
+    pub fn dequantized_weights(&self) -> Matrix {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.weights.iter().map(|&q| self.params.dequantize(q)).collect(),
+        }
+    }
+
+    /// Pre-activation output `W x + b` computed with integer accumulation
+    fn forward(&self, input: &[f64]) -> Vec<f64> {
+        // Symmetric per-call quantization of the layer input
+        let max_abs = input.iter().fold(0.0_f64, |m, x| m.max(x.abs()));
+        let input_scale = if max_abs > 0.0 { max_abs / 127.0 } else { 1.0 };
+        let quantized_input: Vec<i32> = input
+            .iter()
+            .map(|&x| (x / input_scale).round().clamp(-127.0, 127.0) as i32)
+            .collect();
+        let input_sum: i32 = quantized_input.iter().sum();
+        let zero_point = self.params.zero_point as i32;
+        let output_scale = self.params.scale * input_scale;
+
+        self.weights
+            .chunks_exact(self.cols)
+            .zip(&self.biases)
+            .map(|(row, bias)| {
+                let acc: i32 = row
+                    .iter()
+                    .zip(&quantized_input)
+                    .map(|(&w, &x)| w as i32 * x)
+                    .sum();
+                output_scale * (acc - zero_point * input_sum) as f64 + bias
+            })
+            .collect()
+    }
+}
+
+/// Inference-only network with int8 weights
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuantizedNetwork {
+    /// Amount of neurons in each layer
+    pub layers: Vec<usize>,
+
+    /// Quantized layers from input to output
+    pub quantized_layers: Vec<QuantizedLayer>,
+
+    /// Activation applied after every layer
+    pub activation: Activation,
+}
+
+impl QuantizedNetwork {
+    /// Quantize a trained network to int8
+    pub fn from_network(network: &Network) -> Self {
+        Self::with_bits(network, 8)
+    }
+
+    /// Quantize a trained network to `bits` bits per weight
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is not between 2 and 8
+    pub fn with_bits(network: &Network, bits: u8) -> Self {
+        QuantizedNetwork {
+            layers: network.layers.clone(),
+            quantized_layers: network
+                .weights
+                .iter()
+                .zip(&network.biases)
+                .map(|(w, b)| QuantizedLayer::from_matrices(w, b, bits))
+                .collect(),
+            activation: network.activation,
+        }
+    }
+
+    /// Run inference on a single input vector
+    ///
+    /// # Panics
+    ///
+    /// Panics if `inputs` does not match the size of the input layer
+    pub fn predict(&self, inputs: &[f64]) -> Vec<f64> {
+        assert!(self.layers[0] == inputs.len(), "Invalid Number of Inputs");
+
+        let mut current = inputs.to_vec();
+        for layer in &self.quantized_layers {
+            current = layer
+                .forward(&current)
+                .into_iter()
+                .map(|x| (self.activation.function)(&x))
+                .collect();
+        }
+        current
+    }
+
+    /// Convert back to a floating point network with the quantized weights
+    ///
+    /// Useful for measuring the accuracy lost to quantization with the
+    /// regular training and evaluation tools.
+    pub fn dequantize(&self, learning_rate: f64) -> Network {
+        let mut network = Network::new(self.layers.clone(), self.activation, learning_rate);
+        for (i, layer) in self.quantized_layers.iter().enumerate() {
+            network.weights[i] = layer.dequantized_weights();
+            network.biases[i] = Matrix {
+                rows: layer.rows,
+                cols: 1,
+                data: layer.biases.clone(),
+            };
+        }
+        network
+    }
+
+    /// Bits per weight of the first layer (all layers share a width)
+    pub fn bits(&self) -> u8 {
+        self.quantized_layers.first().map_or(8, |l| l.params.bits)
+    }
+
+    /// Bytes needed to store the parameters (bit-packed weights, f64 biases, scales)
+    pub fn size_bytes(&self) -> usize {
+        self.quantized_layers
+            .iter()
+            .map(|l| {
+                (l.weights.len() * l.params.bits as usize).div_ceil(8)
+                    + l.biases.len() * 8
+                    + std::mem::size_of::<QuantParams>()
+            })
+            .sum()
+    }
+
+    /// Bytes needed to store the parameters of the original f64 network
+    pub fn float_size_bytes(network: &Network) -> usize {
+        network
+            .weights
+            .iter()
+            .chain(&network.biases)
+            .map(|m| m.data.len() * 8)
+            .sum()
+    }
+
+    /// Save the quantized network as JSON
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails or the file cannot be written
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string(self).context("Failed to serialize quantized network")?;
+        fs::write(path, json)
+            .with_context(|| format!("Failed to write quantized network to {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Load a quantized network saved with `save`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read quantized network from {}", path.display()))?;
+        serde_json::from_str(&json).context("Failed to parse quantized network")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quant_params_roundtrip() {
+        let values = [-1.0, -0.25, 0.0, 0.5, 2.0];
+        let params = QuantParams::from_range(&values);
+        for &v in &values {
+            let restored = params.dequantize(params.quantize(v));
+            assert!((restored - v).abs() <= params.scale / 2.0 + 1e-12);
+        }
+        assert_eq!(params.dequantize(params.zero_point), 0.0);
+    }
+
+    #[test]
+    fn test_quant_params_constant_values() {
+        let params = QuantParams::from_range(&[0.0, 0.0]);
+        assert_eq!(params.quantize(0.0), 0);
+        assert_eq!(params.dequantize(0), 0.0);
+    }
+
+    #[test]
+    fn test_quant_params_low_bits_saturate() {
+        let params = QuantParams::from_range_bits(&[-1.0, 2.0], 4);
+        assert_eq!(params.quantize(-1.0), -8);
+        assert_eq!(params.quantize(2.0), 7);
+        assert_eq!(params.quantize(10.0), 7);
+        assert!((params.dequantize(params.quantize(0.5)) - 0.5).abs() <= params.scale / 2.0 + 1e-12);
+    }
+}
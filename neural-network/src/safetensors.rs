@@ -0,0 +1,207 @@
+/// Network parameters in the safetensors format
+///
+/// `save` writes the weights and biases of a `Network` as a safetensors file,
+/// the format used by Hugging Face and PyTorch to exchange tensors without
+/// executing code on load. Layer `i` is stored as two little-endian `F64`
+/// tensors:
+///
+/// ```text
+/// layers.{i}.weight   [outputs, inputs]
+/// layers.{i}.bias     [outputs]
+/// ```
+///
+/// The `__metadata__` header records the layer sizes (`"2,3,1"`), the
+/// activation and the learning rate, so `load` rebuilds the same network.
+/// Files written elsewhere may omit the metadata or store `F32` tensors: the
+/// layer sizes are then taken from the weight shapes, the activation is
+/// sigmoid and the learning rate defaults to `DEFAULT_LEARNING_RATE`. `from_bytes` reads the tensors as
+/// views into the borrowed buffer; only the final `Matrix` values are copied.
+use crate::activations::{Activation, SIGMOID};
+use crate::matrix::Matrix;
+use crate::network::Network;
+use ::safetensors::tensor::TensorView;
+use ::safetensors::{Dtype, SafeTensors};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Value of the `format` metadata entry written by `save`
+pub const FORMAT: &str = "neural-net-rs";
+
+/// Learning rate of networks loaded from files without one in their metadata
+pub const DEFAULT_LEARNING_RATE: f64 = 0.5;
+
+/// Write the parameters of `network` to a safetensors file
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written
+pub fn save(network: &Network, path: &Path) -> Result<()> {
+    let bytes = to_bytes(network)?;
+    fs::write(path, bytes).with_context(|| format!("Failed to write safetensors file {}", path.display()))
+}
+
+/// Load a network from a safetensors file
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read, is not a safetensors file,
+/// or its tensors do not form a stack of dense layers
+pub fn load(path: &Path) -> Result<Network> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read safetensors file {}", path.display()))?;
+    from_bytes(&bytes).with_context(|| format!("Failed to load safetensors file {}", path.display()))
+}
+
+/// Encode the parameters and metadata of `network` as safetensors bytes
+///
+/// # Errors
+///
+/// Returns an error if the tensors cannot be serialized
+pub fn to_bytes(network: &Network) -> Result<Vec<u8>> {
+    let mut buffers = Vec::new();
+    for (i, (weights, bias)) in network.weights.iter().zip(&network.biases).enumerate() {
+        buffers.push((format!("layers.{}.weight", i), vec![weights.rows, weights.cols], le_bytes(&weights.data)));
+        buffers.push((format!("layers.{}.bias", i), vec![bias.rows], le_bytes(&bias.data)));
+    }
+    let tensors = buffers
+        .iter()
+        .map(|(name, shape, data)| Ok((name.as_str(), TensorView::new(Dtype::F64, shape.clone(), data)?)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let layers: Vec<String> = network.layers.iter().map(|size| size.to_string()).collect();
+    let metadata = HashMap::from([
+        ("format".to_string(), FORMAT.to_string()),
+        ("layers".to_string(), layers.join(",")),
+        ("activation".to_string(), network.activation.name.to_string()),
+        ("learning_rate".to_string(), network.learning_rate.to_string()),
+    ]);
+    Ok(::safetensors::serialize(tensors, &Some(metadata))?)
+}
+
+/// Decode a network from safetensors bytes
+///
+/// # Errors
+///
+/// Returns an error if the bytes are not a safetensors file, a tensor is
+/// missing, unexpected or has the wrong shape or dtype, or the metadata
+/// contradicts the tensors
+pub fn from_bytes(bytes: &[u8]) -> Result<Network> {
+    let (_, header) = SafeTensors::read_metadata(bytes)?;
+    let tensors = SafeTensors::deserialize(bytes)?;
+    let metadata = header.metadata().clone().unwrap_or_default();
+
+    let mut sizes = Vec::new();
+    let mut parameters = Vec::new();
+    while let Ok(weights) = tensors.tensor(&format!("layers.{}.weight", parameters.len())) {
+        let i = parameters.len();
+        let &[rows, cols] = weights.shape() else {
+            anyhow::bail!("Tensor 'layers.{}.weight' has shape {:?}, expected [outputs, inputs]", i, weights.shape());
+        };
+        match sizes.last() {
+            None => sizes.push(cols),
+            Some(&previous) if previous != cols => anyhow::bail!(
+                "Tensor 'layers.{}.weight' takes {} inputs but layer {} has {} outputs",
+                i,
+                cols,
+                i - 1,
+                previous
+            ),
+            Some(_) => {}
+        }
+        sizes.push(rows);
+
+        let name = format!("layers.{}.bias", i);
+        let bias = tensors
+            .tensor(&name)
+            .map_err(|_| anyhow::anyhow!("Missing tensor '{}'", name))?;
+        if bias.shape() != [rows] && bias.shape() != [rows, 1] {
+            anyhow::bail!("Tensor '{}' has shape {:?}, expected [{}]", name, bias.shape(), rows);
+        }
+        parameters.push((
+            Matrix::new(rows, cols, values(&weights, &format!("layers.{}.weight", i))?)?,
+            Matrix::new(rows, 1, values(&bias, &name)?)?,
+        ));
+    }
+    if parameters.is_empty() {
+        anyhow::bail!("No 'layers.0.weight' tensor found");
+    }
+    if let Some(name) = tensors.names().into_iter().find(|name| !is_parameter(name, parameters.len())) {
+        anyhow::bail!("Unexpected tensor '{}'", name);
+    }
+
+    if let Some(layers) = metadata.get("layers") {
+        let expected: Vec<String> = sizes.iter().map(|size| size.to_string()).collect();
+        if *layers != expected.join(",") {
+            anyhow::bail!("Metadata lists layers {} but the tensors form {:?}", layers, sizes);
+        }
+    }
+    let activation = match metadata.get("activation") {
+        Some(name) => Activation::from_name(name).with_context(|| format!("Unsupported activation '{}'", name))?,
+        None => SIGMOID,
+    };
+    let learning_rate = match metadata.get("learning_rate") {
+        Some(rate) => rate
+            .parse()
+            .with_context(|| format!("Invalid learning_rate '{}' in metadata", rate))?,
+        None => DEFAULT_LEARNING_RATE,
+    };
+
+    let mut network = Network::new(sizes, activation, learning_rate);
+    for (i, (weights, bias)) in parameters.into_iter().enumerate() {
+        network.weights[i] = weights;
+        network.biases[i] = bias;
+    }
+    network.validate_shapes()?;
+    Ok(network)
+}
+
+/// Whether `name` is the weight or bias of one of the first `layers` layers
+fn is_parameter(name: &str, layers: usize) -> bool {
+    (0..layers).any(|i| name == format!("layers.{}.weight", i) || name == format!("layers.{}.bias", i))
+}
+
+/// Little-endian bytes of `values`
+fn le_bytes(values: &[f64]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// Elements of an `F64` or `F32` tensor
+fn values(tensor: &TensorView, name: &str) -> Result<Vec<f64>> {
+    match tensor.dtype() {
+        Dtype::F64 => Ok(tensor
+            .data()
+            .chunks_exact(8)
+            .map(|b| f64::from_le_bytes(b.try_into().unwrap()))
+            .collect()),
+        Dtype::F32 => Ok(tensor
+            .data()
+            .chunks_exact(4)
+            .map(|b| f64::from(f32::from_le_bytes(b.try_into().unwrap())))
+            .collect()),
+        dtype => anyhow::bail!("Tensor '{}' has dtype {:?}; only F64 and F32 are supported", name, dtype),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_keeps_parameters_and_metadata() {
+        let network = Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.25, 3);
+        let bytes = to_bytes(&network).unwrap();
+
+        let (_, header) = SafeTensors::read_metadata(&bytes).unwrap();
+        let metadata = header.metadata().as_ref().unwrap();
+        assert_eq!(metadata["layers"], "2,3,1");
+        assert_eq!(metadata["activation"], "sigmoid");
+        assert_eq!(metadata["learning_rate"], "0.25");
+
+        let loaded = from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.layers, network.layers);
+        assert_eq!(loaded.learning_rate, 0.25);
+        assert_eq!(loaded.weights, network.weights);
+        assert_eq!(loaded.biases, network.biases);
+    }
+}
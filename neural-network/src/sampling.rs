@@ -0,0 +1,148 @@
+/// Stratified splitting and class balancing
+///
+/// Samples are grouped by `training::class_of` their target: the index of
+/// the largest value, or 0/1 for a single output. `stratified_split` holds
+/// out the same fraction of every class, so a small test set still contains
+/// each class in its original proportion. `Balance` evens out class counts
+/// of imbalanced data by repeating minority samples (`Oversample`) or
+/// dropping majority samples (`Undersample`). Balance only the training
+/// part of a split; repeated samples in the test set would inflate scores.
+///
+/// ```
+/// use neural_network::dataset::VecDataset;
+/// use neural_network::sampling::{Balance, class_counts, stratified_split};
+///
+/// let inputs = (0..12).map(|i| vec![i as f64]).collect();
+/// let targets = (0..12).map(|i| vec![if i < 9 { 0.0 } else { 1.0 }]).collect();
+/// let data = VecDataset::new(inputs, targets).unwrap();
+///
+/// let (train, test) = stratified_split(&data, 1.0 / 3.0, 42).unwrap();
+/// assert_eq!((class_counts(&train), class_counts(&test)), (vec![6, 2], vec![3, 1]));
+/// assert_eq!(class_counts(&Balance::Oversample.apply(&train, 42)), vec![6, 6]);
+/// ```
+use crate::dataset::{Dataset, VecDataset};
+use crate::training::class_of;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// Number of samples of each class, indexed by class
+pub fn class_counts<D: Dataset + ?Sized>(data: &D) -> Vec<usize> {
+    class_indices(data).iter().map(Vec::len).collect()
+}
+
+/// Sample indices of each class, in sample order
+fn class_indices<D: Dataset + ?Sized>(data: &D) -> Vec<Vec<usize>> {
+    let mut classes: Vec<Vec<usize>> = Vec::new();
+    for i in 0..data.len() {
+        let class = class_of(&data.get(i).1);
+        if classes.len() <= class {
+            classes.resize(class + 1, Vec::new());
+        }
+        classes[class].push(i);
+    }
+    classes
+}
+
+/// The samples at `indices`, in that order
+fn subset<D: Dataset + ?Sized>(data: &D, indices: &[usize]) -> VecDataset {
+    let (inputs, targets) = indices.iter().map(|&i| data.get(i)).unzip();
+    VecDataset::new(inputs, targets).expect("samples of one dataset have matching sizes")
+}
+
+/// Split `data` into training and test sets with `test_fraction` of every
+/// class in the test set
+///
+/// Each class contributes `round(count * test_fraction)` randomly chosen
+/// samples to the test set. Both sets keep the original sample order. The
+/// same `seed` always gives the same split.
+///
+/// # Errors
+///
+/// Returns an error if `test_fraction` is not in `(0, 1)` or either set
+/// would be empty
+pub fn stratified_split<D: Dataset + ?Sized>(
+    data: &D,
+    test_fraction: f64,
+    seed: u64,
+) -> anyhow::Result<(VecDataset, VecDataset)> {
+    if !(test_fraction > 0.0 && test_fraction < 1.0) {
+        anyhow::bail!("Test fraction must be between 0 and 1, got {}", test_fraction);
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut train = Vec::with_capacity(data.len());
+    let mut test = Vec::new();
+    for mut indices in class_indices(data) {
+        let held_out = (indices.len() as f64 * test_fraction).round() as usize;
+        indices.shuffle(&mut rng);
+        test.extend_from_slice(&indices[..held_out]);
+        train.extend_from_slice(&indices[held_out..]);
+    }
+    if train.is_empty() || test.is_empty() {
+        anyhow::bail!(
+            "Test fraction {} of {} samples leaves an empty training or test set",
+            test_fraction,
+            data.len()
+        );
+    }
+    train.sort_unstable();
+    test.sort_unstable();
+    Ok((subset(data, &train), subset(data, &test)))
+}
+
+/// How `Balance::apply` evens out class counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Balance {
+    /// Repeat randomly drawn samples of every smaller class until it is as
+    /// large as the largest class
+    Oversample,
+
+    /// Keep a random subset of every larger class as large as the smallest
+    /// class
+    Undersample,
+}
+
+impl Balance {
+    /// Lowercase name (`oversample`, `undersample`)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Balance::Oversample => "oversample",
+            Balance::Undersample => "undersample",
+        }
+    }
+
+    /// A copy of `data` with the same number of samples in every class
+    ///
+    /// Classes without samples are ignored. Oversampling keeps every
+    /// original sample in order and appends the repeats; undersampling keeps
+    /// the original order of the samples it retains. The same `seed` always
+    /// gives the same result.
+    pub fn apply<D: Dataset + ?Sized>(&self, data: &D, seed: u64) -> VecDataset {
+        let classes: Vec<Vec<usize>> = class_indices(data).into_iter().filter(|c| !c.is_empty()).collect();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let indices = match self {
+            Balance::Oversample => {
+                let target = classes.iter().map(Vec::len).max().unwrap_or(0);
+                let mut indices: Vec<usize> = (0..data.len()).collect();
+                for class in &classes {
+                    for _ in class.len()..target {
+                        indices.push(class[rng.gen_range(0..class.len())]);
+                    }
+                }
+                indices
+            }
+            Balance::Undersample => {
+                let target = classes.iter().map(Vec::len).min().unwrap_or(0);
+                let mut indices = Vec::with_capacity(target * classes.len());
+                for class in &classes {
+                    indices.extend(class.choose_multiple(&mut rng, target));
+                }
+                indices.sort_unstable();
+                indices
+            }
+        };
+        subset(data, &indices)
+    }
+}
@@ -0,0 +1,528 @@
+/// File-backed datasets for data larger than memory
+///
+/// `CsvDataset`, `IdxDataset` and, with the `parquet` feature,
+/// `ParquetDataset` implement `Dataset` by reading samples from
+/// disk on demand. Opening a file checks it in one pass and remembers where
+/// each sample starts; afterwards only the samples asked for are read.
+/// `TrainingController::train_streaming` trains on such a dataset one chunk
+/// at a time, so only a chunk of samples is ever held in memory:
+///
+/// ```no_run
+/// use neural_network::activations::SIGMOID;
+/// use neural_network::dataset::Dataset;
+/// use neural_network::network::Network;
+/// use neural_network::streaming::CsvDataset;
+/// use neural_network::training::{TrainingConfig, TrainingController};
+///
+/// // Every column but the last is an input, the last is the target
+/// let data = CsvDataset::open("large.csv".as_ref(), 1).unwrap();
+/// let network = Network::new(vec![data.input_dim(), 16, 1], SIGMOID, 0.1);
+/// let mut controller = TrainingController::new(network, TrainingConfig::default());
+/// controller.train_streaming(data, 10_000).unwrap();
+/// ```
+///
+/// The files must not change while a dataset is open; a sample that no
+/// longer parses panics.
+use crate::dataset::Dataset;
+use crate::encoding::one_hot;
+use anyhow::Context;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// A CSV file of numeric columns, read row by row
+///
+/// The last `target_columns` columns of each row are the target, the others
+/// the input. A first line that does not parse as numbers is taken as a
+/// header. Blank lines are skipped.
+#[derive(Debug)]
+pub struct CsvDataset {
+    path: PathBuf,
+    header: Option<Vec<String>>,
+    offsets: Vec<u64>,
+    input_dim: usize,
+    target_dim: usize,
+    reader: Mutex<BufReader<File>>,
+}
+
+impl CsvDataset {
+    /// Open `path`, whose last `target_columns` columns are the targets
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, has no data rows, a value
+    /// is not a number, rows differ in length, or there is not at least one
+    /// input and one target column
+    pub fn open(path: &Path, target_columns: usize) -> anyhow::Result<Self> {
+        let file = File::open(path).with_context(|| format!("Failed to open CSV file: {}", path.display()))?;
+        let mut reader = BufReader::new(file);
+        let mut header = None;
+        let mut offsets = Vec::new();
+        let mut columns = None;
+        let mut line = String::new();
+        let mut offset = 0;
+        for number in 1.. {
+            line.clear();
+            let read = reader
+                .read_line(&mut line)
+                .with_context(|| format!("Failed to read CSV file: {}", path.display()))?;
+            if read == 0 {
+                break;
+            }
+            let start = offset;
+            offset += read as u64;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let values = match parse_row(&line) {
+                Ok(values) => values,
+                Err(_) if number == 1 => {
+                    header = Some(line.trim().split(',').map(|name| name.trim().to_string()).collect());
+                    continue;
+                }
+                Err(e) => anyhow::bail!("{}:{}: {}", path.display(), number, e),
+            };
+            let expected = *columns.get_or_insert(values.len());
+            if values.len() != expected {
+                anyhow::bail!(
+                    "{}:{}: expected {} columns, found {}",
+                    path.display(),
+                    number,
+                    expected,
+                    values.len()
+                );
+            }
+            offsets.push(start);
+        }
+
+        let Some(columns) = columns else {
+            anyhow::bail!("CSV file has no data rows: {}", path.display());
+        };
+        if target_columns == 0 || target_columns >= columns {
+            anyhow::bail!(
+                "Cannot use {} target columns of {} in {}; at least one input and one target column are needed",
+                target_columns,
+                columns,
+                path.display()
+            );
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            header,
+            offsets,
+            input_dim: columns - target_columns,
+            target_dim: target_columns,
+            reader: Mutex::new(reader),
+        })
+    }
+
+    /// Column names from the header line, if the file has one
+    pub fn header(&self) -> Option<&[String]> {
+        self.header.as_deref()
+    }
+
+    /// Read `count` rows starting at row `start`
+    fn read_rows(&self, start: usize, count: usize) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+        let mut reader = self.reader.lock().unwrap_or_else(|e| e.into_inner());
+        let mut inputs = Vec::with_capacity(count);
+        let mut targets = Vec::with_capacity(count);
+        if count == 0 {
+            return (inputs, targets);
+        }
+        let changed = |e: &dyn std::fmt::Display| -> ! {
+            panic!("CSV file {} changed while in use: {}", self.path.display(), e)
+        };
+        reader
+            .seek(SeekFrom::Start(self.offsets[start]))
+            .unwrap_or_else(|e| changed(&e));
+        let mut line = String::new();
+        while inputs.len() < count {
+            line.clear();
+            match reader.read_line(&mut line) {
+                Ok(0) => changed(&"unexpected end of file"),
+                Ok(_) => {}
+                Err(e) => changed(&e),
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut values = parse_row(&line).unwrap_or_else(|e| changed(&e));
+            if values.len() != self.input_dim + self.target_dim {
+                changed(&format!("row {} has {} columns", start + inputs.len(), values.len()));
+            }
+            targets.push(values.split_off(self.input_dim));
+            inputs.push(values);
+        }
+        (inputs, targets)
+    }
+}
+
+/// The comma-separated numbers of one line
+fn parse_row(line: &str) -> anyhow::Result<Vec<f64>> {
+    line.trim()
+        .split(',')
+        .map(|field| {
+            let field = field.trim();
+            field
+                .parse()
+                .map_err(|_| anyhow::anyhow!("'{}' is not a number", field))
+        })
+        .collect()
+}
+
+impl Dataset for CsvDataset {
+    fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    fn get(&self, i: usize) -> (Vec<f64>, Vec<f64>) {
+        assert!(i < self.len(), "Sample {} out of range for {} samples", i, self.len());
+        let (mut inputs, mut targets) = self.read_rows(i, 1);
+        (inputs.remove(0), targets.remove(0))
+    }
+
+    fn input_dim(&self) -> usize {
+        self.input_dim
+    }
+
+    fn target_dim(&self) -> usize {
+        self.target_dim
+    }
+
+    fn chunk(&self, range: Range<usize>) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "Range {:?} out of range for {} samples",
+            range,
+            self.len()
+        );
+        self.read_rows(range.start, range.len())
+    }
+}
+
+/// Images and labels in the IDX format of MNIST and similar datasets
+///
+/// The image file holds unsigned bytes (type `0x08`) with the sample count as
+/// its first dimension; each sample's remaining dimensions are flattened into
+/// one input scaled to `[0, 1]`. The label file holds one unsigned byte per
+/// sample, which becomes a one-hot target with one class more than the
+/// largest label. Labels are held in memory; pixels are read on demand.
+#[derive(Debug)]
+pub struct IdxDataset {
+    path: PathBuf,
+    labels: Vec<u8>,
+    classes: usize,
+    input_dim: usize,
+    data_start: u64,
+    reader: Mutex<BufReader<File>>,
+}
+
+/// IDX type code of unsigned bytes
+const IDX_UBYTE: u8 = 0x08;
+
+impl IdxDataset {
+    /// Open an image file and its label file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be read, is not an unsigned byte IDX
+    /// file, is shorter than its header says, or the files have different
+    /// sample counts
+    pub fn open(images: &Path, labels: &Path) -> anyhow::Result<Self> {
+        let file = File::open(images).with_context(|| format!("Failed to open IDX file: {}", images.display()))?;
+        let file_len = file.metadata()?.len();
+        let mut reader = BufReader::new(file);
+        let dims = read_idx_header(&mut reader, images)?;
+        let samples = dims[0];
+        let input_dim: usize = dims[1..].iter().product();
+        let data_start = 4 + 4 * dims.len() as u64;
+        if input_dim == 0 {
+            anyhow::bail!("IDX file {} has empty samples", images.display());
+        }
+        if file_len < data_start + (samples * input_dim) as u64 {
+            anyhow::bail!(
+                "IDX file {} is truncated: {} samples of {} bytes need {} bytes, found {}",
+                images.display(),
+                samples,
+                input_dim,
+                data_start + (samples * input_dim) as u64,
+                file_len
+            );
+        }
+
+        let label_file =
+            File::open(labels).with_context(|| format!("Failed to open IDX file: {}", labels.display()))?;
+        let mut label_reader = BufReader::new(label_file);
+        let label_dims = read_idx_header(&mut label_reader, labels)?;
+        if label_dims[0] != samples {
+            anyhow::bail!(
+                "IDX files {} and {} have {} images but {} labels",
+                images.display(),
+                labels.display(),
+                samples,
+                label_dims[0]
+            );
+        }
+        let mut label_bytes = vec![0; samples];
+        label_reader
+            .read_exact(&mut label_bytes)
+            .with_context(|| format!("IDX file {} is truncated", labels.display()))?;
+        let classes = label_bytes.iter().max().map_or(0, |&max| max as usize + 1);
+
+        Ok(Self {
+            path: images.to_path_buf(),
+            labels: label_bytes,
+            classes,
+            input_dim,
+            data_start,
+            reader: Mutex::new(reader),
+        })
+    }
+
+    /// Number of classes (one more than the largest label)
+    pub fn classes(&self) -> usize {
+        self.classes
+    }
+
+    /// Integer label of every sample
+    pub fn labels(&self) -> &[u8] {
+        &self.labels
+    }
+}
+
+/// Dimensions of an unsigned byte IDX file, leaving `reader` at the data
+fn read_idx_header(reader: &mut impl Read, path: &Path) -> anyhow::Result<Vec<usize>> {
+    let mut magic = [0; 4];
+    reader
+        .read_exact(&mut magic)
+        .with_context(|| format!("IDX file {} is missing its header", path.display()))?;
+    if magic[..2] != [0, 0] || magic[3] == 0 {
+        anyhow::bail!("{} is not an IDX file", path.display());
+    }
+    if magic[2] != IDX_UBYTE {
+        anyhow::bail!(
+            "IDX file {} holds type 0x{:02x}, only unsigned bytes (0x08) are supported",
+            path.display(),
+            magic[2]
+        );
+    }
+    let mut dims = Vec::with_capacity(magic[3] as usize);
+    for _ in 0..magic[3] {
+        let mut dim = [0; 4];
+        reader
+            .read_exact(&mut dim)
+            .with_context(|| format!("IDX file {} is missing its header", path.display()))?;
+        dims.push(u32::from_be_bytes(dim) as usize);
+    }
+    Ok(dims)
+}
+
+impl Dataset for IdxDataset {
+    fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    fn get(&self, i: usize) -> (Vec<f64>, Vec<f64>) {
+        assert!(i < self.len(), "Sample {} out of range for {} samples", i, self.len());
+        let (mut inputs, mut targets) = self.chunk(i..i + 1);
+        (inputs.remove(0), targets.remove(0))
+    }
+
+    fn input_dim(&self) -> usize {
+        self.input_dim
+    }
+
+    fn target_dim(&self) -> usize {
+        self.classes
+    }
+
+    fn chunk(&self, range: Range<usize>) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "Range {:?} out of range for {} samples",
+            range,
+            self.len()
+        );
+        let mut bytes = vec![0; range.len() * self.input_dim];
+        let mut reader = self.reader.lock().unwrap_or_else(|e| e.into_inner());
+        reader
+            .seek(SeekFrom::Start(self.data_start + (range.start * self.input_dim) as u64))
+            .and_then(|_| reader.read_exact(&mut bytes))
+            .unwrap_or_else(|e| panic!("IDX file {} changed while in use: {}", self.path.display(), e));
+        let inputs = bytes
+            .chunks(self.input_dim)
+            .map(|pixels| pixels.iter().map(|&p| f64::from(p) / 255.0).collect())
+            .collect();
+        let targets = self.labels[range].iter().map(|&label| one_hot(label as usize, self.classes)).collect();
+        (inputs, targets)
+    }
+}
+
+/// A Parquet file of numeric columns, read row group by row group
+///
+/// Files written by pandas (`DataFrame.to_parquet`), polars or Arrow can be
+/// trained on directly. As with `CsvDataset`, the last `target_columns`
+/// columns are the target and the others the input. Integer, float and
+/// boolean columns are converted to `f64`; null values become NaN.
+/// Uncompressed and Snappy-compressed files are supported.
+///
+/// Available with the `parquet` feature.
+#[cfg(feature = "parquet")]
+#[derive(Debug)]
+pub struct ParquetDataset {
+    path: PathBuf,
+    metadata: parquet::arrow::arrow_reader::ArrowReaderMetadata,
+    columns: Vec<String>,
+    group_rows: Vec<usize>,
+    input_dim: usize,
+    target_dim: usize,
+}
+
+#[cfg(feature = "parquet")]
+impl ParquetDataset {
+    /// Open `path`, whose last `target_columns` columns are the targets
+    ///
+    /// Only the file's metadata is read; rows are read by `chunk`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not Parquet, a
+    /// column is not numeric or boolean, or there is not at least one input
+    /// and one target column
+    pub fn open(path: &Path, target_columns: usize) -> anyhow::Result<Self> {
+        use arrow_schema::DataType;
+        use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ArrowReaderOptions};
+
+        let file = File::open(path).with_context(|| format!("Failed to open Parquet file: {}", path.display()))?;
+        let metadata = ArrowReaderMetadata::load(&file, ArrowReaderOptions::new())
+            .with_context(|| format!("Failed to read Parquet file: {}", path.display()))?;
+        let schema = metadata.schema().clone();
+        for field in schema.fields() {
+            let data_type = field.data_type();
+            if !(data_type.is_numeric() || *data_type == DataType::Boolean) {
+                anyhow::bail!(
+                    "Column '{}' of {} has type {}, expected numbers or booleans",
+                    field.name(),
+                    path.display(),
+                    data_type
+                );
+            }
+        }
+        let columns = schema.fields().len();
+        if target_columns == 0 || target_columns >= columns {
+            anyhow::bail!(
+                "Cannot use {} target columns of {} in {}; at least one input and one target column are needed",
+                target_columns,
+                columns,
+                path.display()
+            );
+        }
+        let group_rows = metadata
+            .metadata()
+            .row_groups()
+            .iter()
+            .map(|group| group.num_rows() as usize)
+            .collect();
+        Ok(Self {
+            path: path.to_path_buf(),
+            columns: schema.fields().iter().map(|field| field.name().clone()).collect(),
+            metadata,
+            group_rows,
+            input_dim: columns - target_columns,
+            target_dim: target_columns,
+        })
+    }
+
+    /// Column names, inputs first
+    pub fn column_names(&self) -> &[String] {
+        &self.columns
+    }
+}
+
+#[cfg(feature = "parquet")]
+impl Dataset for ParquetDataset {
+    fn len(&self) -> usize {
+        self.group_rows.iter().sum()
+    }
+
+    fn get(&self, i: usize) -> (Vec<f64>, Vec<f64>) {
+        assert!(i < self.len(), "Sample {} out of range for {} samples", i, self.len());
+        let (mut inputs, mut targets) = self.chunk(i..i + 1);
+        (inputs.remove(0), targets.remove(0))
+    }
+
+    fn input_dim(&self) -> usize {
+        self.input_dim
+    }
+
+    fn target_dim(&self) -> usize {
+        self.target_dim
+    }
+
+    fn chunk(&self, range: Range<usize>) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+        use arrow_array::Array;
+        use arrow_array::cast::AsArray;
+        use arrow_array::types::Float64Type;
+        use arrow_schema::DataType;
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "Range {:?} out of range for {} samples",
+            range,
+            self.len()
+        );
+        let mut inputs = Vec::with_capacity(range.len());
+        let mut targets = Vec::with_capacity(range.len());
+        if range.is_empty() {
+            return (inputs, targets);
+        }
+
+        // Read only the row groups that overlap the range
+        let mut groups = Vec::new();
+        let mut skip = 0;
+        let mut first_row = 0;
+        for (group, rows) in self.group_rows.iter().enumerate() {
+            if first_row < range.end && first_row + rows > range.start {
+                if groups.is_empty() {
+                    skip = range.start - first_row;
+                }
+                groups.push(group);
+            }
+            first_row += rows;
+        }
+        let changed = |e: &dyn std::fmt::Display| -> ! {
+            panic!("Parquet file {} changed while in use: {}", self.path.display(), e)
+        };
+        let file = File::open(&self.path).unwrap_or_else(|e| changed(&e));
+        let reader = ParquetRecordBatchReaderBuilder::new_with_metadata(file, self.metadata.clone())
+            .with_row_groups(groups)
+            .with_offset(skip)
+            .with_limit(range.len())
+            .with_batch_size(range.len())
+            .build()
+            .unwrap_or_else(|e| changed(&e));
+        for batch in reader {
+            let batch = batch.unwrap_or_else(|e| changed(&e));
+            let columns: Vec<_> = batch
+                .columns()
+                .iter()
+                .map(|column| arrow_cast::cast(column, &DataType::Float64).unwrap_or_else(|e| changed(&e)))
+                .collect();
+            let columns: Vec<_> = columns.iter().map(|column| column.as_primitive::<Float64Type>()).collect();
+            let value = |column: &arrow_array::Float64Array, row| {
+                if column.is_null(row) { f64::NAN } else { column.value(row) }
+            };
+            for row in 0..batch.num_rows() {
+                inputs.push(columns[..self.input_dim].iter().map(|c| value(c, row)).collect());
+                targets.push(columns[self.input_dim..].iter().map(|c| value(c, row)).collect());
+            }
+        }
+        if inputs.len() != range.len() {
+            changed(&format!("expected {} rows, read {}", range.len(), inputs.len()));
+        }
+        (inputs, targets)
+    }
+}
@@ -0,0 +1,158 @@
+/// Overview of what a checkpoint holds
+///
+/// `CheckpointSummary::of` collects what `info` shows about a checkpoint:
+/// its metadata, the parameter count and weight and bias statistics of every
+/// layer, and a summary of the training history. The summary serializes to
+/// JSON for scripts that inspect models without decoding the whole
+/// checkpoint format themselves.
+use crate::checkpoint::{Checkpoint, CheckpointMetadata, RunLineage};
+use crate::dataset::ColumnStats;
+use crate::history::TrainingHistory;
+use serde::{Deserialize, Serialize};
+
+/// Parameters of one weight matrix and bias vector
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerSummary {
+    /// Index of the weight matrix, 0 for the first hidden layer
+    pub layer: usize,
+
+    /// Neurons feeding the layer
+    pub inputs: usize,
+
+    /// Neurons of the layer
+    pub outputs: usize,
+
+    /// Number of weights, `inputs * outputs`
+    pub weights: usize,
+
+    /// Number of biases, `outputs`
+    pub biases: usize,
+
+    /// Statistics of the weights
+    pub weight_stats: ColumnStats,
+
+    /// Statistics of the biases
+    pub bias_stats: ColumnStats,
+}
+
+impl LayerSummary {
+    /// Weights and biases of the layer
+    pub fn parameters(&self) -> usize {
+        self.weights + self.biases
+    }
+}
+
+/// Loss and time of a training history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistorySummary {
+    /// Number of recorded epochs
+    pub epochs: usize,
+
+    /// Loss of the first recorded epoch
+    pub first_loss: f64,
+
+    /// Loss of the last recorded epoch
+    pub final_loss: f64,
+
+    /// Lowest loss of any epoch
+    pub best_loss: f64,
+
+    /// Epoch with the lowest loss
+    pub best_epoch: u32,
+
+    /// Validation loss of the last epoch that computed one
+    pub final_validation_loss: Option<f64>,
+
+    /// Training accuracy of the last epoch that computed metrics
+    pub final_accuracy: Option<f64>,
+
+    /// Wall-clock seconds spent training
+    pub elapsed_secs: f64,
+}
+
+impl HistorySummary {
+    /// Summary of `history`, or `None` if it has no epochs
+    pub fn of(history: &TrainingHistory) -> Option<Self> {
+        let first = history.epochs.first()?;
+        let last = history.last()?;
+        let best = history
+            .epochs
+            .iter()
+            .min_by(|a, b| a.loss.total_cmp(&b.loss))
+            .unwrap_or(last);
+        Some(HistorySummary {
+            epochs: history.len(),
+            first_loss: first.loss,
+            final_loss: last.loss,
+            best_loss: best.loss,
+            best_epoch: best.epoch,
+            final_validation_loss: history.validation_losses().last().map(|&(_, loss)| loss),
+            final_accuracy: history.accuracy_series().last().map(|&(_, accuracy)| accuracy),
+            elapsed_secs: history.elapsed_secs(),
+        })
+    }
+}
+
+/// Metadata, per-layer parameters and history of a checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointSummary {
+    /// Metadata as saved
+    pub metadata: CheckpointMetadata,
+
+    /// Number of neurons in each layer, input to output
+    pub architecture: Vec<usize>,
+
+    /// Name of the activation function of every layer
+    pub activation: String,
+
+    /// Parameters of each weight matrix and bias vector
+    pub layers: Vec<LayerSummary>,
+
+    /// Total number of weights and biases
+    pub total_parameters: usize,
+
+    /// Input normalization, if one is saved
+    pub normalizer: Option<String>,
+
+    /// Target scaling, if one is saved
+    pub target_normalizer: Option<String>,
+
+    /// Summary of the training history, if it has epochs
+    pub history: Option<HistorySummary>,
+
+    /// Resumes that led to the checkpoint
+    pub lineage: Vec<RunLineage>,
+}
+
+impl CheckpointSummary {
+    /// Summarize `checkpoint`
+    pub fn of(checkpoint: &Checkpoint) -> Self {
+        let network = &checkpoint.network;
+        let layers: Vec<LayerSummary> = network
+            .weights
+            .iter()
+            .zip(&network.biases)
+            .enumerate()
+            .map(|(layer, (weights, biases))| LayerSummary {
+                layer,
+                inputs: weights.cols,
+                outputs: weights.rows,
+                weights: weights.data.len(),
+                biases: biases.data.len(),
+                weight_stats: ColumnStats::of(&weights.data),
+                bias_stats: ColumnStats::of(&biases.data),
+            })
+            .collect();
+        CheckpointSummary {
+            metadata: checkpoint.metadata.clone(),
+            architecture: network.layers.clone(),
+            activation: network.activation.name.to_string(),
+            total_parameters: layers.iter().map(LayerSummary::parameters).sum(),
+            layers,
+            normalizer: checkpoint.normalizer.as_ref().map(|n| n.kind().name().to_string()),
+            target_normalizer: checkpoint.target_normalizer.as_ref().map(|n| n.kind().name().to_string()),
+            history: HistorySummary::of(&checkpoint.history),
+            lineage: checkpoint.lineage.clone(),
+        }
+    }
+}
@@ -0,0 +1,645 @@
+/// Hyperparameter sweeps over the bundled examples
+///
+/// A `SweepSpec` (usually loaded from TOML) lists candidate learning rates,
+/// hidden-layer layouts and epoch counts. `grid` expands them into every
+/// combination, `run_trials` trains one network per trial and the resulting
+/// `SweepReport` ranks the trials by validation loss (training loss when no
+/// validation split is configured) and keeps the checkpoint of the best one.
+/// `SweepSpec::run_parallel` trains several trials at once on worker
+/// threads.
+///
+/// ```toml
+/// example = "xor"
+/// learning_rates = [0.1, 0.5, 1.0]
+/// hidden_layers = [[2], [4], [4, 4]]
+/// epochs = [1000, 5000]
+/// validation_split = 0.25  # optional
+/// seed = 42                # optional, makes every trial reproducible
+/// ```
+///
+/// Adding a `[random]` table switches to random search: trials are drawn
+/// until the trial or time budget runs out, which explores large spaces more
+/// cheaply than the full grid. Learning rates and epochs can be given as
+/// ranges; otherwise they are drawn from the candidate lists above.
+///
+/// ```toml
+/// [random]
+/// max_trials = 20
+/// max_secs = 120.0
+/// learning_rate = [0.01, 2.0]  # log-uniform
+/// epochs = [500, 5000]         # uniform
+/// ```
+use crate::activations::SIGMOID;
+use crate::checkpoint::Checkpoint;
+use crate::examples::{self, Example};
+use crate::network::Network;
+use crate::training::{TrainingConfig, TrainingController, Validation};
+use anyhow::Context;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Search space and fixed settings of a grid sweep
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SweepSpec {
+    /// Name of the bundled example to train on
+    pub example: String,
+
+    /// Candidate learning rates
+    #[serde(default)]
+    pub learning_rates: Vec<f64>,
+
+    /// Candidate hidden layer sizes; input and output sizes come from the example
+    pub hidden_layers: Vec<Vec<usize>>,
+
+    /// Candidate numbers of training epochs
+    #[serde(default)]
+    pub epochs: Vec<u32>,
+
+    /// Fraction of samples held out to score each trial
+    #[serde(default)]
+    pub validation_split: Option<f64>,
+
+    /// Seed for weight initialization, shared by every trial; also seeds
+    /// random search sampling
+    #[serde(default)]
+    pub seed: Option<u64>,
+
+    /// Random search settings; the full grid is trained when absent
+    #[serde(default)]
+    pub random: Option<RandomSearch>,
+}
+
+impl SweepSpec {
+    /// Parse a spec from TOML
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the TOML is malformed or a candidate list is empty
+    pub fn from_toml(text: &str) -> anyhow::Result<Self> {
+        let spec: SweepSpec = toml::from_str(text).context("Invalid sweep spec")?;
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    /// Read and parse a TOML spec file
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read sweep spec: {}", path.display()))?;
+        Self::from_toml(&text)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        let random = self.random.as_ref();
+        for (name, empty) in [
+            (
+                "learning_rates",
+                self.learning_rates.is_empty() && random.is_none_or(|r| r.learning_rate.is_none()),
+            ),
+            ("hidden_layers", self.hidden_layers.is_empty()),
+            ("epochs", self.epochs.is_empty() && random.is_none_or(|r| r.epochs.is_none())),
+        ] {
+            if empty {
+                anyhow::bail!("Sweep spec needs at least one value in `{}`", name);
+            }
+        }
+        if let Some(random) = random {
+            random.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Number of trials the sweep will run, if known in advance
+    ///
+    /// A random search bounded only by time returns `None`.
+    pub fn planned_trials(&self) -> Option<usize> {
+        match &self.random {
+            Some(random) => random.max_trials,
+            None => Some(self.grid().len()),
+        }
+    }
+
+    /// Draw one trial for random search
+    ///
+    /// Ranges in `search` take precedence over the candidate lists; hidden
+    /// layers are always picked from the list.
+    pub fn sample_trial(&self, search: &RandomSearch, rng: &mut impl Rng) -> Trial {
+        let learning_rate = match search.learning_rate {
+            Some((min, max)) => rng.gen_range(min.ln()..=max.ln()).exp(),
+            None => *self.learning_rates.choose(rng).expect("validated non-empty"),
+        };
+        let epochs = match search.epochs {
+            Some((min, max)) => rng.gen_range(min..=max),
+            None => *self.epochs.choose(rng).expect("validated non-empty"),
+        };
+        Trial {
+            learning_rate,
+            hidden_layers: self.hidden_layers.choose(rng).expect("validated non-empty").clone(),
+            epochs,
+        }
+    }
+
+    /// Run the sweep this spec describes: random search when a `[random]`
+    /// table is present, the full grid otherwise
+    pub fn run(&self, on_result: impl FnMut(usize, &TrialResult)) -> anyhow::Result<SweepReport> {
+        self.run_parallel(1, on_result)
+    }
+
+    /// Run the sweep with up to `threads` trials training at once
+    ///
+    /// `on_result` is called on the calling thread as trials finish, with
+    /// the number of trials finished before. A seeded random search draws
+    /// the same trials in the same order as a sequential run, but a time
+    /// budget may cut it off at a different trial.
+    pub fn run_parallel(
+        &self,
+        threads: usize,
+        on_result: impl FnMut(usize, &TrialResult),
+    ) -> anyhow::Result<SweepReport> {
+        let settings = self.settings()?;
+        Ok(match &self.random {
+            Some(search) => {
+                let mut rng = random_search_rng(self);
+                let start = Instant::now();
+                let next = |started: usize| {
+                    search
+                        .allows_trial(started, start.elapsed())
+                        .then(|| self.sample_trial(search, &mut rng))
+                };
+                run_pool(&settings, threads, next, on_result)
+            }
+            None => {
+                let grid = self.grid();
+                run_pool(&settings, threads, |i| grid.get(i).cloned(), on_result)
+            }
+        })
+    }
+
+    /// Every combination of the candidate values, in spec order
+    pub fn grid(&self) -> Vec<Trial> {
+        let mut trials = Vec::new();
+        for hidden_layers in &self.hidden_layers {
+            for &learning_rate in &self.learning_rates {
+                for &epochs in &self.epochs {
+                    trials.push(Trial {
+                        learning_rate,
+                        hidden_layers: hidden_layers.clone(),
+                        epochs,
+                    });
+                }
+            }
+        }
+        trials
+    }
+
+    /// Settings shared by every trial of this spec
+    pub fn settings(&self) -> anyhow::Result<TrialSettings> {
+        let example = examples::get_example(&self.example)
+            .ok_or_else(|| anyhow::anyhow!("Unknown example: {}", self.example))?;
+        Ok(TrialSettings {
+            example,
+            validation_split: self.validation_split,
+            seed: self.seed,
+        })
+    }
+}
+
+/// Budget and parameter distributions of a random search
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RandomSearch {
+    /// Stop after this many trials
+    #[serde(default)]
+    pub max_trials: Option<usize>,
+
+    /// Start no new trial once this many seconds have passed
+    #[serde(default)]
+    pub max_secs: Option<f64>,
+
+    /// Learning rate range `[min, max]`, sampled log-uniformly
+    #[serde(default)]
+    pub learning_rate: Option<(f64, f64)>,
+
+    /// Epoch range `[min, max]`, sampled uniformly
+    #[serde(default)]
+    pub epochs: Option<(u32, u32)>,
+}
+
+impl RandomSearch {
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.max_trials.is_none() && self.max_secs.is_none() {
+            anyhow::bail!("Random search needs a budget: set `max_trials`, `max_secs` or both");
+        }
+        if let Some((min, max)) = self.learning_rate
+            && !(min > 0.0 && min <= max)
+        {
+            anyhow::bail!("Random search `learning_rate` range must satisfy 0 < min <= max");
+        }
+        if let Some((min, max)) = self.epochs
+            && !(min > 0 && min <= max)
+        {
+            anyhow::bail!("Random search `epochs` range must satisfy 0 < min <= max");
+        }
+        Ok(())
+    }
+
+    /// Whether the budget allows another trial after `completed` trials and
+    /// `elapsed` time
+    ///
+    /// The time budget is only checked between trials, so the last trial may
+    /// run past it.
+    pub fn allows_trial(&self, completed: usize, elapsed: Duration) -> bool {
+        self.max_trials.is_none_or(|n| completed < n)
+            && self.max_secs.is_none_or(|secs| elapsed.as_secs_f64() < secs)
+    }
+}
+
+/// One point in the search space
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trial {
+    pub learning_rate: f64,
+    pub hidden_layers: Vec<usize>,
+    pub epochs: u32,
+}
+
+/// Data and settings shared by all trials of a sweep
+pub struct TrialSettings {
+    pub example: Example,
+    pub validation_split: Option<f64>,
+    pub seed: Option<u64>,
+}
+
+impl TrialSettings {
+    /// Full layer sizes for `hidden_layers` on this example
+    fn architecture(&self, hidden_layers: &[usize]) -> Vec<usize> {
+        let arch = &self.example.recommended_arch;
+        let mut layers = vec![arch[0]];
+        layers.extend_from_slice(hidden_layers);
+        layers.push(arch[arch.len() - 1]);
+        layers
+    }
+}
+
+/// Outcome of training one trial
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrialResult {
+    pub trial: Trial,
+
+    /// Full layer sizes, including input and output
+    pub architecture: Vec<usize>,
+
+    /// Training loss after the last epoch
+    pub final_loss: Option<f64>,
+
+    /// Validation loss after the last epoch, if a split was configured
+    pub validation_loss: Option<f64>,
+
+    /// Epoch with the lowest monitored loss
+    pub best_epoch: Option<u32>,
+
+    /// Wall-clock seconds spent on this trial
+    pub elapsed_secs: f64,
+
+    /// Why training failed (e.g. divergence), in which case the losses are empty
+    pub error: Option<String>,
+}
+
+impl TrialResult {
+    /// Loss used for ranking: validation loss if available, else training loss
+    pub fn score(&self) -> Option<f64> {
+        self.validation_loss.or(self.final_loss)
+    }
+}
+
+/// Train a single trial
+pub fn run_trial(settings: &TrialSettings, trial: &Trial) -> TrialResult {
+    train_trial(settings, trial).0
+}
+
+/// Train a single trial, returning its controller along with the result
+fn train_trial(settings: &TrialSettings, trial: &Trial) -> (TrialResult, TrainingController) {
+    let architecture = settings.architecture(&trial.hidden_layers);
+    let network = match settings.seed {
+        Some(seed) => Network::new_seeded(architecture.clone(), SIGMOID, trial.learning_rate, seed),
+        None => Network::new(architecture.clone(), SIGMOID, trial.learning_rate),
+    };
+    let config = TrainingConfig {
+        epochs: trial.epochs,
+        example_name: Some(settings.example.name.to_string()),
+        validation: settings.validation_split.map_or(Validation::None, Validation::Split),
+        seed: settings.seed,
+        ..Default::default()
+    };
+
+    let start = Instant::now();
+    let mut controller = TrainingController::new(network, config);
+    let outcome = controller.train(settings.example.inputs.clone(), settings.example.targets.clone());
+    let elapsed_secs = start.elapsed().as_secs_f64();
+
+    let (last, error) = match outcome {
+        Ok(history) => (history.last().cloned(), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    let result = TrialResult {
+        trial: trial.clone(),
+        architecture,
+        final_loss: last.as_ref().map(|r| r.loss),
+        validation_loss: last.as_ref().and_then(|r| r.validation_loss),
+        best_epoch: controller.best_epoch(),
+        elapsed_secs,
+        error,
+    };
+    (result, controller)
+}
+
+/// Train every trial in order, calling `on_result` after each one
+pub fn run_trials(
+    settings: &TrialSettings,
+    trials: &[Trial],
+    on_result: impl FnMut(usize, &TrialResult),
+) -> SweepReport {
+    run_pool(settings, 1, |i| trials.get(i).cloned(), on_result)
+}
+
+/// Train randomly sampled trials until the budget of `search` is spent,
+/// calling `on_result` after each one
+///
+/// Sampling is seeded from `spec.seed` when set, so a seeded random search
+/// with a trial budget is reproducible.
+pub fn run_random(
+    settings: &TrialSettings,
+    spec: &SweepSpec,
+    search: &RandomSearch,
+    on_result: impl FnMut(usize, &TrialResult),
+) -> SweepReport {
+    let mut rng = random_search_rng(spec);
+    let start = Instant::now();
+    let next = |started: usize| {
+        search
+            .allows_trial(started, start.elapsed())
+            .then(|| spec.sample_trial(search, &mut rng))
+    };
+    run_pool(settings, 1, next, on_result)
+}
+
+/// Sampling generator of a random search, seeded from `spec.seed` when set
+fn random_search_rng(spec: &SweepSpec) -> StdRng {
+    match spec.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// Train the trials `next` hands out on `threads` worker threads
+///
+/// `next` is called with the number of trials started so far and returns
+/// `None` once the sweep is done. Results are passed to `on_result` on the
+/// calling thread in the order they finish, and the checkpoint of the
+/// best-scoring trial is kept in the report.
+fn run_pool(
+    settings: &TrialSettings,
+    threads: usize,
+    next: impl FnMut(usize) -> Option<Trial> + Send,
+    mut on_result: impl FnMut(usize, &TrialResult),
+) -> SweepReport {
+    let next = Mutex::new((next, 0));
+    let best: Mutex<Option<(f64, Checkpoint)>> = Mutex::new(None);
+    let (sender, receiver) = mpsc::channel();
+    let mut results = Vec::new();
+
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            let sender = sender.clone();
+            let (next, best) = (&next, &best);
+            scope.spawn(move || {
+                loop {
+                    let trial = {
+                        let mut guard = next.lock().expect("no worker panicked");
+                        let (next, started) = &mut *guard;
+                        let trial = next(*started);
+                        *started += usize::from(trial.is_some());
+                        trial
+                    };
+                    let Some(trial) = trial else { break };
+
+                    let (result, controller) = train_trial(settings, &trial);
+                    if let Some(score) = result.score() {
+                        let mut best = best.lock().expect("no worker panicked");
+                        if best.as_ref().is_none_or(|(best_score, _)| score < *best_score) {
+                            *best = Some((score, controller.to_checkpoint()));
+                        }
+                    }
+                    if sender.send(result).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+        for result in receiver {
+            on_result(results.len(), &result);
+            results.push(result);
+        }
+    });
+
+    let mut report = SweepReport::new(settings.example.name, results);
+    report.best_checkpoint = best.into_inner().expect("no worker panicked").map(|(_, checkpoint)| checkpoint);
+    report
+}
+
+/// Trial results ranked from best to worst
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepReport {
+    pub example: String,
+
+    /// Results sorted by `TrialResult::score`, failed trials last
+    pub results: Vec<TrialResult>,
+
+    /// Final checkpoint of the best trial, kept in memory by the runners
+    #[serde(skip)]
+    pub best_checkpoint: Option<Checkpoint>,
+}
+
+impl SweepReport {
+    /// Rank `results` by score
+    pub fn new(example: &str, mut results: Vec<TrialResult>) -> Self {
+        results.sort_by(|a, b| match (a.score(), b.score()) {
+            (Some(x), Some(y)) => x.total_cmp(&y),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+        Self {
+            example: example.to_string(),
+            results,
+            best_checkpoint: None,
+        }
+    }
+
+    /// The best-scoring trial, if any succeeded
+    pub fn best(&self) -> Option<&TrialResult> {
+        self.results.first().filter(|r| r.score().is_some())
+    }
+
+    /// Plain-text ranking table
+    pub fn summary(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "Sweep results for {} ({} trials)", self.example, self.results.len());
+        let _ = writeln!(
+            out,
+            "{:>4}  {:<16} {:>8} {:>8} {:>12} {:>12} {:>6} {:>8}",
+            "rank", "architecture", "lr", "epochs", "loss", "val loss", "best", "time (s)"
+        );
+        for (i, r) in self.results.iter().enumerate() {
+            let loss = |v: Option<f64>| v.map_or("-".to_string(), |l| format!("{:.6}", l));
+            let _ = write!(
+                out,
+                "{:>4}  {:<16} {:>8} {:>8} {:>12} {:>12} {:>6} {:>8.2}",
+                i + 1,
+                format!("{:?}", r.architecture),
+                r.trial.learning_rate,
+                r.trial.epochs,
+                loss(r.final_loss),
+                loss(r.validation_loss),
+                r.best_epoch.map_or("-".to_string(), |e| e.to_string()),
+                r.elapsed_secs
+            );
+            if let Some(error) = &r.error {
+                let _ = write!(out, "  failed: {}", error);
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Markdown ranking table, for pasting into issues and notes
+    pub fn markdown(&self) -> String {
+        let mut out = String::new();
+        let scored_by = if self.results.iter().any(|r| r.validation_loss.is_some()) {
+            "validation loss"
+        } else {
+            "training loss"
+        };
+        let _ = writeln!(out, "# Sweep results for {}", self.example);
+        let _ = writeln!(out);
+        let _ = writeln!(out, "{} trials, ranked by {}.", self.results.len(), scored_by);
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "| Rank | Architecture | Learning rate | Epochs | Loss | Validation loss | Best epoch | Time (s) |"
+        );
+        let _ = writeln!(out, "|---:|---|---:|---:|---:|---:|---:|---:|");
+        for (i, r) in self.results.iter().enumerate() {
+            let loss = |v: Option<f64>| v.map_or("-".to_string(), |l| format!("{:.6}", l));
+            let final_loss = match &r.error {
+                Some(error) => format!("failed: {}", error.replace('|', "\\|")),
+                None => loss(r.final_loss),
+            };
+            let _ = writeln!(
+                out,
+                "| {} | {:?} | {} | {} | {} | {} | {} | {:.2} |",
+                i + 1,
+                r.architecture,
+                r.trial.learning_rate,
+                r.trial.epochs,
+                final_loss,
+                loss(r.validation_loss),
+                r.best_epoch.map_or("-".to_string(), |e| e.to_string()),
+                r.elapsed_secs
+            );
+        }
+        out
+    }
+
+    /// Write the report as markdown if `path` ends in `.md`, as JSON otherwise
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let text = if path.extension().is_some_and(|ext| ext == "md") {
+            self.markdown()
+        } else {
+            serde_json::to_string_pretty(self)?
+        };
+        std::fs::write(path, text).with_context(|| format!("Failed to write sweep report: {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"
+        example = "xor"
+        learning_rates = [0.1, 0.5]
+        hidden_layers = [[2], [3, 2]]
+        epochs = [10, 20, 30]
+    "#;
+
+    #[test]
+    fn test_grid_is_cartesian_product() {
+        let spec = SweepSpec::from_toml(SPEC).unwrap();
+        let grid = spec.grid();
+
+        assert_eq!(grid.len(), 2 * 2 * 3);
+        assert_eq!(grid[0], Trial { learning_rate: 0.1, hidden_layers: vec![2], epochs: 10 });
+        assert_eq!(grid[11], Trial { learning_rate: 0.5, hidden_layers: vec![3, 2], epochs: 30 });
+    }
+
+    #[test]
+    fn test_spec_rejects_empty_lists_and_unknown_keys() {
+        assert!(SweepSpec::from_toml(&SPEC.replace("[10, 20, 30]", "[]")).is_err());
+        assert!(SweepSpec::from_toml(&format!("{}\nmomentum = 0.9", SPEC)).is_err());
+    }
+
+    #[test]
+    fn test_random_search_samples_within_ranges() {
+        let spec = SweepSpec::from_toml(&format!(
+            "{}\n[random]\nmax_trials = 50\nlearning_rate = [0.01, 2.0]\nepochs = [5, 15]",
+            SPEC
+        ))
+        .unwrap();
+        let search = spec.random.clone().unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for _ in 0..50 {
+            let trial = spec.sample_trial(&search, &mut rng);
+            assert!((0.01..=2.0).contains(&trial.learning_rate));
+            assert!((5..=15).contains(&trial.epochs));
+            assert!(spec.hidden_layers.contains(&trial.hidden_layers));
+        }
+    }
+
+    #[test]
+    fn test_random_search_requires_budget_and_valid_ranges() {
+        assert!(SweepSpec::from_toml(&format!("{}\n[random]", SPEC)).is_err());
+        assert!(SweepSpec::from_toml(&format!("{}\n[random]\nmax_secs = 1.0\nlearning_rate = [0.0, 1.0]", SPEC)).is_err());
+        assert!(SweepSpec::from_toml(&format!("{}\n[random]\nmax_trials = 2\nepochs = [10, 5]", SPEC)).is_err());
+
+        let no_lists = "example = \"xor\"\nhidden_layers = [[2]]\n[random]\nmax_trials = 1\nlearning_rate = [0.1, 1.0]\nepochs = [1, 2]";
+        assert!(SweepSpec::from_toml(no_lists).is_ok(), "Ranges replace the candidate lists");
+    }
+
+    #[test]
+    fn test_budget_stops_at_first_exhausted_limit() {
+        let search = RandomSearch {
+            max_trials: Some(3),
+            max_secs: Some(10.0),
+            ..Default::default()
+        };
+        assert!(search.allows_trial(2, Duration::from_secs(1)));
+        assert!(!search.allows_trial(3, Duration::from_secs(1)));
+        assert!(!search.allows_trial(0, Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_architecture_wraps_hidden_layers() {
+        let settings = SweepSpec::from_toml(SPEC).unwrap().settings().unwrap();
+        assert_eq!(settings.architecture(&[3, 2]), vec![2, 3, 2, 1]);
+    }
+}
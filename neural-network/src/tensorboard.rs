@@ -0,0 +1,275 @@
+/// TensorBoard event file writer
+///
+/// Writes the `events.out.tfevents.*` files TensorBoard reads: a sequence of
+/// TFRecord frames, each holding one `Event` protocol buffer. Only the few
+/// message fields needed for scalars and histograms are encoded, by hand, so
+/// no protobuf or TensorFlow dependency is required. Point TensorBoard at the
+/// parent of the log directory to compare runs:
+///
+/// ```text
+/// tensorboard --logdir runs/
+/// ```
+use crate::callbacks::{CallbackAction, EpochContext, TrainingCallback};
+use crate::histogram::Histogram;
+use crate::history::EpochRecord;
+use crate::network::Network;
+use anyhow::Context;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of bins in weight and bias histograms
+const HISTOGRAM_BINS: usize = 30;
+
+/// Writes scalar and histogram summaries to a TensorBoard event file
+pub struct TensorBoardWriter {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>,
+    histogram_interval: u32,
+}
+
+impl TensorBoardWriter {
+    /// Create `log_dir` if needed and start a new event file inside it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or file cannot be created
+    pub fn create(log_dir: &Path) -> anyhow::Result<Self> {
+        fs::create_dir_all(log_dir)
+            .with_context(|| format!("Failed to create TensorBoard log directory: {}", log_dir.display()))?;
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let name = format!("events.out.tfevents.{}.{}.neural-net", now.as_secs(), std::process::id());
+        let path = log_dir.join(name);
+        let file = File::create(&path)
+            .with_context(|| format!("Failed to create TensorBoard event file: {}", path.display()))?;
+
+        let mut writer = Self {
+            path,
+            writer: Some(BufWriter::new(file)),
+            histogram_interval: 100,
+        };
+
+        // Every event file starts with a version record
+        let mut event = event_header(wall_time(), 0);
+        encode_bytes(&mut event, 3, b"brain.Event:2");
+        writer.write_record(&event)?;
+        writer.flush()?;
+        Ok(writer)
+    }
+
+    /// Write weight and bias histograms every `interval` epochs (default 100)
+    ///
+    /// Histograms are much larger than scalars, so logging them every epoch
+    /// of a long run produces event files TensorBoard is slow to load.
+    pub fn with_histogram_interval(mut self, interval: u32) -> Self {
+        self.histogram_interval = interval.max(1);
+        self
+    }
+
+    /// Path of the event file being written
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record a scalar value under `tag`
+    pub fn add_scalar(&mut self, tag: &str, value: f64, step: u64) -> std::io::Result<()> {
+        let mut summary_value = Vec::new();
+        encode_bytes(&mut summary_value, 1, tag.as_bytes());
+        encode_key(&mut summary_value, 2, WIRE_FIXED32);
+        summary_value.extend_from_slice(&(value as f32).to_le_bytes());
+        self.write_summary(&summary_value, step)
+    }
+
+    /// Record the distribution of `values` under `tag`
+    pub fn add_histogram(&mut self, tag: &str, values: &[f64], step: u64) -> std::io::Result<()> {
+        let histogram = Histogram::from_values(values, HISTOGRAM_BINS);
+        let finite = values.iter().copied().filter(|v| v.is_finite());
+
+        let mut proto = Vec::new();
+        encode_double(&mut proto, 1, histogram.min);
+        encode_double(&mut proto, 2, histogram.max);
+        encode_double(&mut proto, 3, histogram.total() as f64);
+        encode_double(&mut proto, 4, finite.clone().sum());
+        encode_double(&mut proto, 5, finite.map(|v| v * v).sum());
+        let limits: Vec<f64> = histogram.bin_edges().iter().map(|&(_, upper)| upper).collect();
+        let counts: Vec<f64> = histogram.counts.iter().map(|&c| c as f64).collect();
+        encode_packed_doubles(&mut proto, 6, &limits);
+        encode_packed_doubles(&mut proto, 7, &counts);
+
+        let mut summary_value = Vec::new();
+        encode_bytes(&mut summary_value, 1, tag.as_bytes());
+        encode_bytes(&mut summary_value, 5, &proto);
+        self.write_summary(&summary_value, step)
+    }
+
+    /// Record the scalars of one epoch and, on histogram epochs, the
+    /// parameter distributions of `network`
+    ///
+    /// Scalars are tagged `loss`, `validation/loss`, `metrics/accuracy` (and
+    /// precision, recall, F1, with `validation/` counterparts),
+    /// `learning_rate` and `gradient_norm`. Histograms are tagged
+    /// `layer_<i>/weights` and `layer_<i>/biases`.
+    pub fn log_epoch(&mut self, record: &EpochRecord, network: &Network) -> std::io::Result<()> {
+        let step = u64::from(record.epoch);
+        self.add_scalar("loss", record.loss, step)?;
+        if let Some(loss) = record.validation_loss {
+            self.add_scalar("validation/loss", loss, step)?;
+        }
+        for (prefix, metrics) in [("metrics", record.metrics), ("validation/metrics", record.validation_metrics)] {
+            if let Some(m) = metrics {
+                self.add_scalar(&format!("{}/accuracy", prefix), m.accuracy, step)?;
+                self.add_scalar(&format!("{}/precision", prefix), m.precision, step)?;
+                self.add_scalar(&format!("{}/recall", prefix), m.recall, step)?;
+                self.add_scalar(&format!("{}/f1", prefix), m.f1, step)?;
+            }
+        }
+        self.add_scalar("learning_rate", record.learning_rate, step)?;
+        self.add_scalar("gradient_norm", record.gradient_norm, step)?;
+
+        if record.epoch == 1 || record.epoch.is_multiple_of(self.histogram_interval) {
+            for (i, (weights, biases)) in network.weights.iter().zip(&network.biases).enumerate() {
+                self.add_histogram(&format!("layer_{}/weights", i), &weights.data, step)?;
+                self.add_histogram(&format!("layer_{}/biases", i), &biases.data, step)?;
+            }
+        }
+        self.flush()
+    }
+
+    /// Flush buffered events to disk
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        match self.writer.as_mut() {
+            Some(writer) => writer.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Wrap `value` (an encoded `Summary.Value`) in an event and write it
+    fn write_summary(&mut self, value: &[u8], step: u64) -> std::io::Result<()> {
+        let mut summary = Vec::new();
+        encode_bytes(&mut summary, 1, value);
+        let mut event = event_header(wall_time(), step);
+        encode_bytes(&mut event, 5, &summary);
+        self.write_record(&event)
+    }
+
+    /// Write one TFRecord frame: length, CRC of length, data, CRC of data
+    fn write_record(&mut self, data: &[u8]) -> std::io::Result<()> {
+        let Some(writer) = self.writer.as_mut() else {
+            return Ok(());
+        };
+        let length = (data.len() as u64).to_le_bytes();
+        writer.write_all(&length)?;
+        writer.write_all(&masked_crc32c(&length).to_le_bytes())?;
+        writer.write_all(data)?;
+        writer.write_all(&masked_crc32c(data).to_le_bytes())
+    }
+}
+
+/// Logs every epoch via `log_epoch`; a write failure is reported once on
+/// stderr and disables the writer instead of aborting training
+impl TrainingCallback for TensorBoardWriter {
+    fn on_epoch_end(&mut self, ctx: &EpochContext) -> anyhow::Result<CallbackAction> {
+        if let Err(e) = self.log_epoch(ctx.record, ctx.network) {
+            eprintln!("Stopped writing TensorBoard events to {}: {}", self.path.display(), e);
+            self.writer = None;
+        }
+        Ok(CallbackAction::Continue)
+    }
+}
+
+const WIRE_VARINT: u8 = 0;
+const WIRE_FIXED64: u8 = 1;
+const WIRE_BYTES: u8 = 2;
+const WIRE_FIXED32: u8 = 5;
+
+fn wall_time() -> f64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64()
+}
+
+/// `Event.wall_time` (field 1) and `Event.step` (field 2)
+fn event_header(wall_time: f64, step: u64) -> Vec<u8> {
+    let mut event = Vec::new();
+    encode_double(&mut event, 1, wall_time);
+    encode_key(&mut event, 2, WIRE_VARINT);
+    encode_varint(&mut event, step);
+    event
+}
+
+fn encode_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn encode_key(buf: &mut Vec<u8>, field: u32, wire_type: u8) {
+    encode_varint(buf, (u64::from(field) << 3) | u64::from(wire_type));
+}
+
+fn encode_double(buf: &mut Vec<u8>, field: u32, value: f64) {
+    encode_key(buf, field, WIRE_FIXED64);
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn encode_bytes(buf: &mut Vec<u8>, field: u32, bytes: &[u8]) {
+    encode_key(buf, field, WIRE_BYTES);
+    encode_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+fn encode_packed_doubles(buf: &mut Vec<u8>, field: u32, values: &[f64]) {
+    let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+    encode_bytes(buf, field, &bytes);
+}
+
+/// CRC-32C (Castagnoli) lookup table
+const CRC32C_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0x82F6_3B78 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32c(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        CRC32C_TABLE[((crc ^ u32::from(byte)) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+/// The CRC variant TFRecord stores, rotated and offset so that CRCs of data
+/// containing CRCs stay well distributed
+pub fn masked_crc32c(data: &[u8]) -> u32 {
+    let crc = crc32c(data);
+    crc.rotate_right(15).wrapping_add(0xa282_ead8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32c_check_value() {
+        assert_eq!(crc32c(b"123456789"), 0xE306_9283);
+        assert_eq!(crc32c(b""), 0);
+    }
+
+    #[test]
+    fn test_varint_encoding() {
+        let mut buf = Vec::new();
+        encode_varint(&mut buf, 1);
+        encode_varint(&mut buf, 300);
+        assert_eq!(buf, vec![0x01, 0xAC, 0x02]);
+    }
+}
@@ -1,26 +1,457 @@
 /// Training controller for managing neural network training with callbacks and checkpointing
-use crate::checkpoint::CheckpointMetadata;
+use crate::callbacks::{BatchContext, CallbackAction, Checkpointer, EarlyStopping, EpochContext, TargetLoss, TrainingCallback};
+use crate::checkpoint::{Checkpoint, CheckpointMetadata, HyperparameterOverride, RunLineage, TrainingState, network_checksum};
+use crate::dataset::{self, Dataset, InputNoise};
+use crate::encryption::Secret;
+use crate::history::{EpochRecord, TrainingHistory};
+use crate::metrics::{ClassificationMetrics, ConfusionCounts};
 use crate::network::Network;
+use crate::normalize::{Normalization, Normalizer};
 use crate::matrix::Matrix;
-use std::path::PathBuf;
+use crate::network::Gradients;
+use crate::optimizer::Optimizer;
+use crate::workspace::{BatchWorkspace, Workspace};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand_distr::{Distribution, StandardNormal};
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use web_time::Instant;
 
 /// Configuration for training a neural network
+#[derive(Default)]
 pub struct TrainingConfig {
     pub epochs: u32,
     pub checkpoint_interval: Option<u32>,
+    /// Also checkpoint whenever this much wall-clock time has passed since
+    /// the last checkpoint (checked at the end of each epoch)
+    pub checkpoint_every: Option<Duration>,
     pub checkpoint_path: Option<PathBuf>,
     pub verbose: bool,
     pub example_name: Option<String>,
+    /// File the training data was read from, recorded in checkpoint metadata
+    pub dataset_path: Option<String>,
+    /// Restore the network from `checkpoint_path` when training diverges
+    pub rollback_on_divergence: bool,
+    /// Held-out data scored each epoch; when present its loss (instead of
+    /// the training loss) drives early stopping and best-model selection
+    pub validation: Validation,
+    /// Stop once the monitored loss has not improved for this many epochs
+    pub early_stopping_patience: Option<u32>,
+    /// Stop once the monitored loss falls below this value
+    pub target_loss: Option<f64>,
+    /// Restore the network from the epoch with the lowest monitored loss
+    /// when training ends
+    pub restore_best: bool,
+    /// Visit the training samples in a different random order every epoch
+    pub shuffle: bool,
+    /// Seed for the shuffle order; the same seed reproduces the same order
+    /// for every epoch. A random seed is drawn when `None` unless
+    /// `deterministic` is set.
+    pub seed: Option<u64>,
+    /// Decision threshold for classification metrics; when set, accuracy,
+    /// precision, recall and F1 are computed every epoch (see `metrics`)
+    pub classification_threshold: Option<f64>,
+    /// Make runs bit-for-bit reproducible: every random choice is derived
+    /// from `seed` (`DETERMINISTIC_SEED` when unset) and, with the `gpu`
+    /// feature, the single-precision GPU backend is switched off until the
+    /// run finishes. Initialize the network with
+    /// `Network::new_seeded` as well.
+    pub deterministic: bool,
+    /// Samples per weight update; `None` updates after every sample
+    ///
+    /// The gradients of a batch are averaged before the update. A batch at
+    /// least as large as the training set gives full-batch gradient descent.
+    /// With the `parallel` feature each batch is split across threads (see
+    /// `Network::compute_batch_gradients_with`).
+    pub batch_size: Option<usize>,
+    /// Weight each training sample's contribution to the loss and gradients,
+    /// e.g. to upweight rare classes in an imbalanced dataset
+    pub sample_weights: SampleWeights,
+    /// Update rule; momentum and Adam state is saved in checkpoints
+    pub optimizer: Optimizer,
+    /// Add annealed Gaussian noise to every gradient before the update
+    pub gradient_noise: Option<GradientNoise>,
+    /// Scale the inputs with a normalizer fit on the training samples
+    ///
+    /// Validation inputs get the same transform. A normalizer from an
+    /// earlier run or checkpoint of the same kind is reused, and one of any
+    /// kind is kept when this is `None`, so the network always sees inputs
+    /// on the scale it was trained on (see `TrainingController::normalizer`).
+    pub normalization: Option<Normalization>,
+    /// Scale the targets with a normalizer fit on the training samples
+    ///
+    /// For regression targets outside the output range of the network:
+    /// `MinMax` maps them to [0, 1] for sigmoid outputs, `ZScore` centres
+    /// them for tanh outputs. Validation targets get the same transform and
+    /// the loss is measured in scaled units. Invert predictions with
+    /// `TrainingController::target_normalizer`, which is reused and saved in
+    /// checkpoints like the input normalizer.
+    pub target_normalization: Option<Normalization>,
+    /// Add Gaussian noise of this standard deviation to the training inputs,
+    /// drawn afresh every epoch from the run's seed (see `dataset::NoisyDataset`)
+    ///
+    /// The noise is added after normalization, so it is measured in
+    /// normalized units when `normalization` is set. The loss and metrics
+    /// are computed on the clean inputs.
+    pub input_noise: Option<f64>,
+    /// Encrypt the checkpoints written by the run with this secret
+    ///
+    /// It also decrypts the checkpoint read by `from_checkpoint` and the one
+    /// restored by `rollback_on_divergence` (see `encryption`).
+    pub encryption: Option<Secret>,
 }
 
-/// Callback function type for training progress
-pub type TrainingCallback = Box<dyn FnMut(u32, f64, &Network)>;
+/// Gaussian gradient noise whose variance decays as training progresses
+///
+/// The noise added in epoch `t` (counted from 0 across resumed runs) has
+/// variance `eta / (1 + t)^gamma`. Early noise helps the tiny example
+/// problems escape plateaus and poor local minima; the annealing lets
+/// training settle later. The noise is drawn from the run's seed, so seeded
+/// runs stay reproducible.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GradientNoise {
+    /// Initial variance
+    pub eta: f64,
+
+    /// Decay exponent of the variance
+    pub gamma: f64,
+}
+
+impl Default for GradientNoise {
+    /// `eta = 0.01`, `gamma = 0.55`, as in "Adding Gradient Noise Improves
+    /// Learning for Very Deep Networks" (Neelakantan et al.)
+    fn default() -> Self {
+        Self { eta: 0.01, gamma: 0.55 }
+    }
+}
+
+impl GradientNoise {
+    /// Noise of initial variance `eta` with the default decay
+    pub fn new(eta: f64) -> Self {
+        Self { eta, ..Default::default() }
+    }
+
+    /// Standard deviation of the noise in epoch `t`
+    pub fn std_dev(&self, t: u32) -> f64 {
+        (self.eta / (1.0 + f64::from(t)).powf(self.gamma)).sqrt()
+    }
+}
+
+/// Mixed into the seed of the gradient noise RNG so it differs from the
+/// shuffle RNG of the same epoch
+const NOISE_STREAM: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Mixed into the shuffle seeds of streamed chunks
+const CHUNK_STREAM: u64 = 0xBF58_476D_1CE4_E5B9;
+
+/// Mixed into the seed of the input noise so it differs from the gradient noise
+const INPUT_NOISE_STREAM: u64 = 0xD1B5_4A32_D192_ED03;
+
+/// Copy `gradients` into `noisy` and add noise of standard deviation `std_dev`
+fn add_noise(noisy: &mut Gradients, gradients: &Gradients, std_dev: f64, rng: &mut StdRng) {
+    let targets = noisy.weights.iter_mut().chain(noisy.biases.iter_mut());
+    let sources = gradients.weights.iter().chain(&gradients.biases);
+    for (target, source) in targets.zip(sources) {
+        for (t, s) in target.data.iter_mut().zip(&source.data) {
+            let sample: f64 = StandardNormal.sample(rng);
+            *t = s + std_dev * sample;
+        }
+    }
+}
+
+/// Seed used by deterministic runs that do not set `TrainingConfig::seed`
+pub const DETERMINISTIC_SEED: u64 = 42;
+
+/// Inputs paired with their targets
+type Samples = (Vec<Vec<f64>>, Vec<Vec<f64>>);
+
+/// Source of the validation set used by `TrainingController::train`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Validation {
+    /// No validation; the training loss is monitored
+    #[default]
+    None,
+
+    /// Hold out this fraction (in `(0, 1)`) of the samples passed to `train`
+    ///
+    /// The held-out samples are taken from the end, so the split is
+    /// deterministic. Shuffle the data first if it is ordered by class.
+    Split(f64),
+
+    /// Score an explicit validation set
+    Set {
+        inputs: Vec<Vec<f64>>,
+        targets: Vec<Vec<f64>>,
+    },
+}
+
+impl Validation {
+    /// Split `inputs`/`targets` into training and validation samples
+    fn split(
+        &self,
+        mut inputs: Vec<Vec<f64>>,
+        mut targets: Vec<Vec<f64>>,
+    ) -> anyhow::Result<(Samples, Option<Samples>)> {
+        match self {
+            Validation::None => Ok(((inputs, targets), None)),
+            Validation::Split(fraction) => {
+                if !(*fraction > 0.0 && *fraction < 1.0) {
+                    anyhow::bail!("Validation fraction must be between 0 and 1, got {}", fraction);
+                }
+                let held_out = (inputs.len() as f64 * fraction).round() as usize;
+                if held_out == 0 || held_out >= inputs.len() {
+                    anyhow::bail!(
+                        "Validation fraction {} of {} samples leaves an empty training or validation set",
+                        fraction,
+                        inputs.len()
+                    );
+                }
+                let split = inputs.len() - held_out;
+                let validation = (inputs.split_off(split), targets.split_off(split));
+                Ok(((inputs, targets), Some(validation)))
+            }
+            Validation::Set { inputs: val_inputs, targets: val_targets } => {
+                if val_inputs.is_empty() || val_inputs.len() != val_targets.len() {
+                    anyhow::bail!(
+                        "Validation set needs matching, non-empty inputs and targets (got {} and {})",
+                        val_inputs.len(),
+                        val_targets.len()
+                    );
+                }
+                Ok(((inputs, targets), Some((val_inputs.clone(), val_targets.clone()))))
+            }
+        }
+    }
+}
+
+/// Loss weights of the training samples
+///
+/// A sample's weight multiplies its squared error, so its gradient step is
+/// scaled by the weight as well. Weights apply to the training loss and
+/// updates only; the validation loss and classification metrics stay
+/// unweighted.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum SampleWeights {
+    /// Every sample has weight 1
+    #[default]
+    None,
+
+    /// One weight per sample passed to `train`, in the same order
+    PerSample(Vec<f64>),
+
+    /// One weight per class, indexed by `class_of` the sample's target
+    PerClass(Vec<f64>),
+}
+
+/// Class of a target vector: the index of its largest value, or for a single
+/// output 1 if the target is at least 0.5 and 0 otherwise
+pub fn class_of(target: &[f64]) -> usize {
+    match target {
+        [t] => usize::from(*t >= 0.5),
+        _ => (0..target.len()).max_by(|&a, &b| target[a].total_cmp(&target[b])).unwrap_or(0),
+    }
+}
+
+impl SampleWeights {
+    /// One weight per sample of `targets`, or `None` when unweighted
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the number of per-sample weights does not match,
+    /// a sample's class has no class weight, or a weight is negative or
+    /// not finite
+    pub fn resolve(&self, targets: &[Vec<f64>]) -> anyhow::Result<Option<Vec<f64>>> {
+        let weights = match self {
+            SampleWeights::None => return Ok(None),
+            SampleWeights::PerSample(weights) => {
+                if weights.len() != targets.len() {
+                    anyhow::bail!(
+                        "Got {} sample weights for {} samples",
+                        weights.len(),
+                        targets.len()
+                    );
+                }
+                weights.clone()
+            }
+            SampleWeights::PerClass(class_weights) => targets
+                .iter()
+                .map(|target| {
+                    let class = class_of(target);
+                    class_weights.get(class).copied().ok_or_else(|| {
+                        anyhow::anyhow!("No class weight for class {} ({} given)", class, class_weights.len())
+                    })
+                })
+                .collect::<anyhow::Result<_>>()?,
+        };
+        if let Some(w) = weights.iter().find(|w| !(w.is_finite() && **w >= 0.0)) {
+            anyhow::bail!("Sample weights must be finite and non-negative, got {}", w);
+        }
+        Ok(Some(weights))
+    }
+}
+
+/// Error returned when training produces a NaN or infinite loss
+///
+/// Training stops at the first such epoch so no garbage checkpoint is written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DivergenceError {
+    /// Epoch in which the divergence was detected
+    pub epoch: u32,
+
+    /// Loss computed for that epoch
+    pub loss: f64,
+
+    /// First weight layer (input to output) that produced non-finite values
+    pub layer: Option<usize>,
+
+    /// Epoch of the checkpoint the network was rolled back to, if any
+    pub rolled_back_to: Option<u32>,
+}
+
+impl fmt::Display for DivergenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Training diverged at epoch {}: loss is {}", self.epoch, self.loss)?;
+        match self.layer {
+            Some(layer) => write!(f, " (first non-finite values in layer {} -> {})", layer, layer + 1)?,
+            None => write!(f, " (no non-finite parameters or activations found)")?,
+        }
+        if let Some(epoch) = self.rolled_back_to {
+            write!(f, ". Rolled back to checkpoint from epoch {}", epoch)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for DivergenceError {}
+
+/// Reset `order` to `0..n` and shuffle it for the given epoch
+///
+/// Each epoch gets its own RNG derived from `seed`, so the order of any epoch
+/// can be reproduced without replaying the earlier ones.
+fn shuffle_order(order: &mut [usize], seed: u64, epoch: u32) {
+    for (i, slot) in order.iter_mut().enumerate() {
+        *slot = i;
+    }
+    let mut rng = StdRng::seed_from_u64(seed.wrapping_add(u64::from(epoch)));
+    order.shuffle(&mut rng);
+}
+
+/// Shared flag that asks a running `train` call to stop
+///
+/// Clones share the flag, so a token can be handed to another thread (a
+/// Ctrl-C handler, a server request) while training runs. Training checks it
+/// before every epoch and every weight update. Once cancelled a token stays
+/// cancelled; use a fresh token for the next run.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a token that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether cancellation has been requested
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
 
 /// Controller for training neural networks with advanced features
 pub struct TrainingController {
     network: Network,
     config: TrainingConfig,
-    callbacks: Vec<TrainingCallback>,
+    callbacks: Vec<Box<dyn TrainingCallback>>,
+    history: TrainingHistory,
+    /// History restored by `from_checkpoint` and of earlier runs, continued by
+    /// `history` in saved checkpoints
+    previous_history: TrainingHistory,
+    /// Epoch of the checkpoint restored by `from_checkpoint` plus the epochs of
+    /// earlier runs, where epoch counting continues
+    previous_epochs: u32,
+    /// Resumes that led to this controller, ending with the one by `from_checkpoint`
+    lineage: Vec<RunLineage>,
+    /// When the first run of this model began, kept by `from_checkpoint`
+    created_at: Option<String>,
+    /// Fingerprint of the data the last run started on
+    dataset_fingerprint: Option<String>,
+    /// Hyperparameters changed on this and earlier resumes, see `record_override`
+    overrides: Vec<HyperparameterOverride>,
+    best_epoch: Option<u32>,
+    cancellation: CancellationToken,
+    run: Option<Run>,
+    /// State left by the last run or loaded from a checkpoint; the next run
+    /// continues from it
+    state: Option<TrainingState>,
+    /// Input transform the network is trained with
+    normalizer: Option<Normalizer>,
+    target_normalizer: Option<Normalizer>,
+}
+
+/// Dataset trained in chunks by `TrainingController::start_streaming`
+struct Stream {
+    dataset: Box<dyn Dataset + Send>,
+    chunk_size: usize,
+}
+
+/// State of a run between `start` and `finish`
+struct Run {
+    /// Training samples, or the current chunk when streaming
+    inputs: Vec<Vec<f64>>,
+    targets: Vec<Vec<f64>>,
+    validation: Option<Samples>,
+    stream: Option<Stream>,
+    workspace: Workspace,
+    /// Buffers for averaging gradients when `batch_size` is set
+    batch: Option<BatchWorkspace>,
+    /// Weight of each training sample, if weighted
+    weights: Option<Vec<f64>>,
+    /// Buffer for noisy gradients and the noise RNG, reseeded every epoch
+    noise: Option<(Gradients, StdRng)>,
+    /// Inputs without augmentation noise, when `input_noise` is set;
+    /// `inputs` then holds the current epoch's noisy copy
+    clean_inputs: Option<Vec<Vec<f64>>>,
+    /// Sample order, reshuffled in place every epoch when shuffling
+    order: Vec<usize>,
+    /// Optimizer and shuffle state, kept current for checkpoints
+    state: TrainingState,
+    /// Last completed epoch
+    epoch: u32,
+    /// Training time spent in earlier `train_epochs` calls
+    elapsed: Duration,
+    /// Lowest monitored loss so far; the network is only kept if it will be restored
+    best_loss: f64,
+    best_network: Option<Network>,
+    /// Built-in callbacks for this run's config
+    builtin: Vec<Box<dyn TrainingCallback>>,
+    stopped: bool,
+    /// Keeps the GPU backend off for the lifetime of a deterministic run
+    #[cfg(feature = "gpu")]
+    _cpu_only: Option<crate::matrix::gpu::CpuOnlyGuard>,
+}
+
+impl Run {
+    /// Sample ranges trained one after another in every epoch
+    fn chunks(&self) -> Vec<Range<usize>> {
+        match &self.stream {
+            Some(stream) => {
+                let len = stream.dataset.len();
+                (0..len).step_by(stream.chunk_size).map(|start| start..(start + stream.chunk_size).min(len)).collect()
+            }
+            None => std::iter::once(0..self.inputs.len()).collect(),
+        }
+    }
 }
 
 impl TrainingController {
@@ -30,73 +461,739 @@ impl TrainingController {
             network,
             config,
             callbacks: Vec::new(),
+            history: TrainingHistory::new(),
+            previous_history: TrainingHistory::new(),
+            previous_epochs: 0,
+            lineage: Vec::new(),
+            created_at: Some(chrono::Utc::now().to_rfc3339()),
+            dataset_fingerprint: None,
+            overrides: Vec::new(),
+            best_epoch: None,
+            cancellation: CancellationToken::new(),
+            run: None,
+            state: None,
+            normalizer: None,
+            target_normalizer: None,
         }
     }
 
-    /// Add a callback function to be called after each epoch
-    pub fn add_callback(&mut self, callback: TrainingCallback) {
-        self.callbacks.push(callback);
+    /// Register a callback; callbacks run in the order they were added
+    ///
+    /// Use `callbacks::from_fn` to register a closure.
+    pub fn add_callback(&mut self, callback: impl TrainingCallback + 'static) {
+        self.callbacks.push(Box::new(callback));
     }
 
-    /// Calculate mean squared error loss
-    fn calculate_loss(&mut self, inputs: &[Vec<f64>], targets: &[Vec<f64>]) -> f64 {
-        let mut total_loss = 0.0;
-        for i in 0..inputs.len() {
-            let output = self.network.feed_forward(Matrix::from(inputs[i].clone()));
-            let target = Matrix::from(targets[i].clone());
+    /// Token that cancels this controller's training when triggered
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation.clone()
+    }
+
+    /// Use `token` (e.g. one shared by several runs) for cancellation
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancellation = token;
+    }
+
+    /// Whether the last `train` call stopped because it was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    /// Built-in callbacks for the checkpointing, early stopping and target loss set in the config
+    fn config_callbacks(&self) -> Vec<Box<dyn TrainingCallback>> {
+        let mut callbacks: Vec<Box<dyn TrainingCallback>> = Vec::new();
+        if let Some(path) = &self.config.checkpoint_path
+            && (self.config.checkpoint_interval.is_some() || self.config.checkpoint_every.is_some())
+        {
+            let example = self.config.example_name.clone().unwrap_or_else(|| "training".to_string());
+            let mut checkpointer = Checkpointer::new(path.clone(), example);
+            if let Some(interval) = self.config.checkpoint_interval {
+                checkpointer = checkpointer.every_epochs(interval);
+            }
+            if let Some(every) = self.config.checkpoint_every {
+                checkpointer = checkpointer.every(every);
+            }
+            callbacks.push(Box::new(checkpointer));
+        }
+        if let Some(patience) = self.config.early_stopping_patience {
+            callbacks.push(Box::new(EarlyStopping::new(patience).verbose(self.config.verbose)));
+        }
+        if let Some(target) = self.config.target_loss {
+            callbacks.push(Box::new(TargetLoss::new(target).verbose(self.config.verbose)));
+        }
+        callbacks
+    }
+
+    /// Calculate the squared error of each output neuron, averaged over samples,
+    /// and the classification metrics if a threshold is configured
+    ///
+    /// The aggregate loss is the sum of the returned losses. With `weights`
+    /// each sample's squared error is scaled by its weight.
+    fn evaluate(
+        &self,
+        inputs: &[Vec<f64>],
+        targets: &[Vec<f64>],
+        weights: Option<&[f64]>,
+        workspace: &mut Workspace,
+    ) -> (Vec<f64>, Option<ClassificationMetrics>) {
+        let outputs = self.network.layers[self.network.layers.len() - 1];
+        let mut losses = vec![0.0; outputs];
+        let mut counts = ConfusionCounts::default();
+        self.accumulate(inputs, targets, weights, workspace, &mut losses, &mut counts);
+        losses.iter_mut().for_each(|l| *l /= inputs.len() as f64);
+        let metrics = self.config.classification_threshold.map(|_| counts.metrics());
+        (losses, metrics)
+    }
+
+    /// `evaluate` over every chunk of a streamed run
+    fn evaluate_stream(&self, run: &mut Run) -> anyhow::Result<(Vec<f64>, Option<ClassificationMetrics>)> {
+        let outputs = self.network.layers[self.network.layers.len() - 1];
+        let mut losses = vec![0.0; outputs];
+        let mut counts = ConfusionCounts::default();
+        let mut samples = 0;
+        for range in run.chunks() {
+            samples += range.len();
+            self.load_chunk(run, range)?;
+            let inputs = run.clean_inputs.as_ref().unwrap_or(&run.inputs);
+            self.accumulate(inputs, &run.targets, run.weights.as_deref(), &mut run.workspace, &mut losses, &mut counts);
+        }
+        losses.iter_mut().for_each(|l| *l /= samples as f64);
+        let metrics = self.config.classification_threshold.map(|_| counts.metrics());
+        Ok((losses, metrics))
+    }
+
+    /// Add the squared errors of `inputs` to `losses` and their predictions to `counts`
+    fn accumulate(
+        &self,
+        inputs: &[Vec<f64>],
+        targets: &[Vec<f64>],
+        weights: Option<&[f64]>,
+        workspace: &mut Workspace,
+        losses: &mut [f64],
+        counts: &mut ConfusionCounts,
+    ) {
+        for (i, (input, target)) in inputs.iter().zip(targets).enumerate() {
+            let output = self.network.feed_forward_with(input, workspace);
+            let weight = weights.map_or(1.0, |w| w[i]);
 
             // Calculate MSE
-            for j in 0..output.data.len() {
-                let error = target.data[j] - output.data[j];
-                total_loss += error * error;
+            for (loss, (t, o)) in losses.iter_mut().zip(target.iter().zip(&output.data)) {
+                let error = t - o;
+                *loss += weight * error * error;
+            }
+            if let Some(threshold) = self.config.classification_threshold {
+                counts.observe(&output.data, target, threshold);
             }
         }
-        total_loss / (inputs.len() as f64)
+    }
+
+    /// Replace the samples of a streamed run with the chunk `range`
+    ///
+    /// The chunk is normalized, its class weights are resolved and, with
+    /// input noise, a clean copy is kept. Does nothing without streaming.
+    fn load_chunk(&self, run: &mut Run, range: Range<usize>) -> anyhow::Result<()> {
+        let Some(stream) = &run.stream else {
+            return Ok(());
+        };
+        let (mut inputs, mut targets) = stream.dataset.chunk(range);
+        if let Some(normalizer) = &self.normalizer {
+            normalizer.apply_all(&mut inputs);
+        }
+        if let Some(normalizer) = &self.target_normalizer {
+            normalizer.apply_all(&mut targets);
+        }
+        run.weights = self.config.sample_weights.resolve(&targets)?;
+        run.clean_inputs = self.config.input_noise.map(|_| inputs.clone());
+        run.order.clear();
+        run.order.extend(0..inputs.len());
+        run.inputs = inputs;
+        run.targets = targets;
+        Ok(())
     }
 
     /// Train the network with the configured settings
+    ///
+    /// Returns the training history (per-epoch loss, validation loss,
+    /// classification metrics, learning rate, elapsed time, gradient norm and
+    /// weight norms), which also remains available through `history()` and is
+    /// embedded in every checkpoint written during training.
+    ///
+    /// When the cancellation token fires, training stops before the next
+    /// weight update and returns normally, like an early stop: the partial
+    /// epoch is not recorded, `restore_best` still applies and `on_train_end`
+    /// still runs. Check `is_cancelled()` to tell the two apart.
+    ///
+    /// This is `start`, `train_epochs` for all configured epochs and `finish`
+    /// in one call.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the validation configuration is invalid, training
+    /// diverges (a `DivergenceError`), a checkpoint cannot be written or a
+    /// callback fails
     pub fn train(
         &mut self,
         inputs: Vec<Vec<f64>>,
         targets: Vec<Vec<f64>>,
+    ) -> anyhow::Result<TrainingHistory> {
+        self.start(inputs, targets)?;
+        self.train_epochs(self.config.epochs)?;
+        self.finish()
+    }
+
+    /// Train on every sample of `dataset`
+    ///
+    /// The samples are copied into memory first; otherwise this is `train`.
+    ///
+    /// # Errors
+    ///
+    /// As for `train`
+    pub fn train_dataset<D: Dataset + ?Sized>(&mut self, dataset: &D) -> anyhow::Result<TrainingHistory> {
+        let (inputs, targets) = dataset.to_vecs();
+        self.train(inputs, targets)
+    }
+
+    /// Begin a run on `inputs`/`targets` without training yet
+    ///
+    /// Use with `train_epochs` and `finish` to train incrementally, e.g. a
+    /// few epochs per animation frame or request. The data, shuffle order,
+    /// best-model tracking and callback state are kept in memory between
+    /// calls, so training in steps gives the same result as one `train`
+    /// call. Starting again abandons any run in progress.
+    ///
+    /// A new run continues the optimizer statistics and shuffle sequence of
+    /// the previous finished run, or of the checkpoint the controller was
+    /// created from, unless the optimizer changed. Its epochs are numbered
+    /// from 1 again in `history`; the earlier runs' epochs move to
+    /// `full_history`, and checkpoints count on from them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the validation configuration or batch size is
+    /// invalid or a callback's `on_train_start` fails
+    pub fn start(&mut self, inputs: Vec<Vec<f64>>, targets: Vec<Vec<f64>>) -> anyhow::Result<()> {
+        self.run = None;
+        self.check_config()?;
+        self.dataset_fingerprint = Some(dataset::fingerprint(&inputs, &targets));
+        let mut weights = self.config.sample_weights.resolve(&targets)?;
+        let ((mut inputs, mut targets), mut validation) = self.config.validation.split(inputs, targets)?;
+        // A validation split holds out samples from the end
+        if let Some(w) = &mut weights {
+            w.truncate(inputs.len());
+        }
+
+        self.fit_normalizer(|kind| kind.fit(&inputs));
+        self.fit_target_normalizer(|kind| kind.fit(&targets));
+        if let Some(normalizer) = &self.normalizer {
+            normalizer.apply_all(&mut inputs);
+            if let Some((val_inputs, _)) = &mut validation {
+                normalizer.apply_all(val_inputs);
+            }
+        }
+        if let Some(normalizer) = &self.target_normalizer {
+            normalizer.apply_all(&mut targets);
+            if let Some((_, val_targets)) = &mut validation {
+                normalizer.apply_all(val_targets);
+            }
+        }
+        self.begin(inputs, targets, weights, validation, None)
+    }
+
+    /// Begin a run that streams `dataset` in chunks of `chunk_size` samples
+    ///
+    /// Like `start`, but only one chunk is held in memory at a time, so a
+    /// file-backed dataset (see `streaming`) larger than memory can be
+    /// trained. Every epoch reads the dataset twice: once to train, chunk
+    /// by chunk, and once to measure the loss and metrics of the epoch.
+    /// With `shuffle` the chunks are visited in a random order and the
+    /// samples within each chunk are shuffled.
+    ///
+    /// Normalizers are fit, and the dataset fingerprinted, in extra passes
+    /// over the data before training.
+    /// Per-sample weights and a validation split need the whole dataset in
+    /// memory; use per-class weights and an explicit validation set instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `chunk_size` is zero, the dataset is empty, or the
+    /// configuration is invalid or unsupported for streaming
+    pub fn start_streaming<D: Dataset + Send + 'static>(&mut self, dataset: D, chunk_size: usize) -> anyhow::Result<()> {
+        self.run = None;
+        self.check_config()?;
+        if chunk_size == 0 {
+            anyhow::bail!("Chunk size must be at least 1");
+        }
+        if dataset.is_empty() {
+            anyhow::bail!("Cannot train on an empty dataset");
+        }
+        if matches!(self.config.sample_weights, SampleWeights::PerSample(_)) {
+            anyhow::bail!("Per-sample weights are not supported when streaming; use per-class weights");
+        }
+        if matches!(self.config.validation, Validation::Split(_)) {
+            anyhow::bail!("A validation split is not supported when streaming; pass a validation set");
+        }
+        let (_, mut validation) = self.config.validation.split(Vec::new(), Vec::new())?;
+
+        self.dataset_fingerprint = Some(dataset.fingerprint());
+        self.fit_normalizer(|kind| kind.fit_dataset(&dataset));
+        self.fit_target_normalizer(|kind| kind.fit_dataset_targets(&dataset));
+        if let Some(normalizer) = &self.normalizer
+            && let Some((val_inputs, _)) = &mut validation
+        {
+            normalizer.apply_all(val_inputs);
+        }
+        if let Some(normalizer) = &self.target_normalizer
+            && let Some((_, val_targets)) = &mut validation
+        {
+            normalizer.apply_all(val_targets);
+        }
+        let stream = Stream {
+            dataset: Box::new(dataset),
+            chunk_size,
+        };
+        self.begin(Vec::new(), Vec::new(), None, validation, Some(stream))
+    }
+
+    /// Train on `dataset` streamed in chunks of `chunk_size` samples
+    ///
+    /// This is `start_streaming`, `train_epochs` for all configured epochs
+    /// and `finish` in one call.
+    ///
+    /// # Errors
+    ///
+    /// As for `start_streaming` and `train`
+    pub fn train_streaming<D: Dataset + Send + 'static>(
+        &mut self,
+        dataset: D,
+        chunk_size: usize,
+    ) -> anyhow::Result<TrainingHistory> {
+        self.start_streaming(dataset, chunk_size)?;
+        self.train_epochs(self.config.epochs)?;
+        self.finish()
+    }
+
+    /// Reject invalid settings before a run starts
+    fn check_config(&self) -> anyhow::Result<()> {
+        if self.config.batch_size == Some(0) {
+            anyhow::bail!("Batch size must be at least 1");
+        }
+        if let Some(noise) = self.config.gradient_noise
+            && !(noise.eta >= 0.0 && noise.eta.is_finite() && noise.gamma >= 0.0)
+        {
+            anyhow::bail!("Gradient noise needs a finite eta >= 0 and gamma >= 0, got {:?}", noise);
+        }
+        if let Some(std_dev) = self.config.input_noise
+            && !(std_dev >= 0.0 && std_dev.is_finite())
+        {
+            anyhow::bail!("Input noise must be a finite standard deviation >= 0, got {}", std_dev);
+        }
+        Ok(())
+    }
+
+    /// Fit the configured normalizer with `fit`, unless the current one can be reused
+    fn fit_normalizer(&mut self, fit: impl FnOnce(Normalization) -> Normalizer) {
+        self.normalizer = match (self.config.normalization, self.normalizer.take()) {
+            (Some(kind), Some(existing)) if existing.kind() == kind && existing.dim() == self.network.layers[0] => {
+                Some(existing)
+            }
+            (Some(kind), _) => Some(fit(kind)),
+            (None, existing) => existing,
+        };
+    }
+
+    /// Fit the configured target normalizer with `fit`, unless the current one can be reused
+    fn fit_target_normalizer(&mut self, fit: impl FnOnce(Normalization) -> Normalizer) {
+        let outputs = self.network.layers[self.network.layers.len() - 1];
+        self.target_normalizer = match (self.config.target_normalization, self.target_normalizer.take()) {
+            (Some(kind), Some(existing)) if existing.kind() == kind && existing.dim() == outputs => Some(existing),
+            (Some(kind), _) => Some(fit(kind)),
+            (None, existing) => existing,
+        };
+    }
+
+    /// Set up the run on prepared (split and normalized) samples
+    fn begin(
+        &mut self,
+        inputs: Vec<Vec<f64>>,
+        targets: Vec<Vec<f64>>,
+        weights: Option<Vec<f64>>,
+        validation: Option<Samples>,
+        stream: Option<Stream>,
     ) -> anyhow::Result<()> {
-        for epoch in 1..=self.config.epochs {
-            // Train one epoch
-            for j in 0..inputs.len() {
-                let outputs = self.network.feed_forward(Matrix::from(inputs[j].clone()));
-                self.network.back_propogate(outputs, Matrix::from(targets[j].clone()));
+        self.best_epoch = None;
+        // Epochs of an earlier run on this controller become part of the
+        // previous history, so the new run's records and checkpoint epochs
+        // continue after them
+        if let Some(last) = self.history.last() {
+            self.previous_epochs += last.epoch;
+            self.previous_history = self.full_history();
+            self.history = TrainingHistory::new();
+        }
+        let previous = self.state.take().unwrap_or_default();
+        let resumed = previous.epochs_trained > 0;
+        let seed = match (self.config.seed, self.config.deterministic) {
+            (Some(seed), _) => seed,
+            (None, _) if resumed => previous.seed,
+            (None, true) => DETERMINISTIC_SEED,
+            (None, false) => rand::random(),
+        };
+        let optimizer_state = if previous.optimizer_state.matches(&self.config.optimizer, &self.network) {
+            previous.optimizer_state
+        } else {
+            self.config.optimizer.initial_state(&self.network)
+        };
+
+        let mut builtin = self.config_callbacks();
+        for callback in self.callbacks.iter_mut().chain(builtin.iter_mut()) {
+            callback.on_train_start(&self.config, &self.network)?;
+        }
+
+        self.run = Some(Run {
+            order: (0..inputs.len()).collect(),
+            clean_inputs: self.config.input_noise.map(|_| inputs.clone()),
+            inputs,
+            targets,
+            validation,
+            stream,
+            // Scratch buffers reused by every sample of every epoch
+            workspace: self.network.workspace(),
+            batch: self.config.batch_size.map(|_| BatchWorkspace::new(&self.network.layers)),
+            weights,
+            noise: self.config.gradient_noise.map(|_| {
+                let gradients = self.network.workspace().gradients().clone();
+                (gradients, StdRng::seed_from_u64(seed))
+            }),
+            state: TrainingState {
+                epochs_trained: previous.epochs_trained,
+                seed,
+                shuffle: self.config.shuffle,
+                batch_size: self.config.batch_size,
+                optimizer: self.config.optimizer,
+                optimizer_state,
+            },
+            epoch: 0,
+            elapsed: Duration::ZERO,
+            best_loss: f64::INFINITY,
+            best_network: None,
+            builtin,
+            stopped: false,
+            #[cfg(feature = "gpu")]
+            _cpu_only: self.config.deterministic.then(crate::matrix::gpu::cpu_only),
+        });
+        Ok(())
+    }
+
+    /// Train up to `n` more epochs of the run begun with `start`
+    ///
+    /// Fewer epochs run if the configured `epochs` total is reached, a
+    /// callback stops training or the run is cancelled; `is_finished` then
+    /// returns true. Time between calls does not count towards the recorded
+    /// `elapsed_secs`.
+    ///
+    /// Returns the number of epochs completed by this call.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no run was started, training diverges (a
+    /// `DivergenceError`) or a callback fails. The run is abandoned after an
+    /// error.
+    pub fn train_epochs(&mut self, n: u32) -> anyhow::Result<u32> {
+        let mut run = self
+            .run
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("No training run in progress; call `start` first"))?;
+
+        let call_start = Instant::now();
+        let first_epoch = run.epoch;
+        while run.epoch - first_epoch < n && !run.stopped && run.epoch < self.config.epochs {
+            if self.cancellation.is_cancelled() || !self.train_epoch(&mut run, call_start)? {
+                run.stopped = true;
             }
+        }
+        run.elapsed += call_start.elapsed();
+
+        let completed = run.epoch - first_epoch;
+        self.run = Some(run);
+        Ok(completed)
+    }
+
+    /// Whether the current run has no epochs left to train
+    ///
+    /// True once the configured epochs are done, a callback stopped training
+    /// or the run was cancelled, and when no run is in progress.
+    pub fn is_finished(&self) -> bool {
+        self.run
+            .as_ref()
+            .is_none_or(|run| run.stopped || run.epoch >= self.config.epochs)
+    }
+
+    /// End the run begun with `start`
+    ///
+    /// Restores the best network if `restore_best` is set, runs the
+    /// callbacks' `on_train_end` and returns the history. Can be called
+    /// before all epochs are trained.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no run was started or a callback fails
+    pub fn finish(&mut self) -> anyhow::Result<TrainingHistory> {
+        let mut run = self
+            .run
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("No training run in progress; call `start` first"))?;
+
+        if let Some(network) = run.best_network.take() {
+            self.network = network;
+        }
+
+        for callback in self.callbacks.iter_mut().chain(run.builtin.iter_mut()) {
+            callback.on_train_end(&self.history, &self.network)?;
+        }
+        self.state = Some(run.state);
+
+        Ok(self.history.clone())
+    }
 
-            // Calculate loss for callbacks
-            let loss = self.calculate_loss(&inputs, &targets);
+    /// Train and record the next epoch of `run`
+    ///
+    /// Returns false if training should stop: a callback asked to, or the
+    /// run was cancelled during the epoch (which is then not recorded).
+    fn train_epoch(&mut self, run: &mut Run, call_start: Instant) -> anyhow::Result<bool> {
+        let epoch = run.epoch + 1;
+        let epoch_start = Instant::now();
+        let noise_std_dev = self.config.gradient_noise.map_or(0.0, |n| n.std_dev(run.state.epochs_trained));
+        if let Some((_, rng)) = &mut run.noise {
+            // A stream of its own per epoch, independent of the shuffle order
+            let epoch_seed = run.state.seed.wrapping_add(u64::from(run.state.epochs_trained));
+            *rng = StdRng::seed_from_u64(epoch_seed ^ NOISE_STREAM);
+        }
 
-            // Verbose output
-            if self.config.verbose
-                && (self.config.epochs < 100 || epoch % (self.config.epochs / 100) == 0) {
-                    println!("Epoch {} of {}: loss = {:.6}", epoch, self.config.epochs, loss);
+        // Train one epoch; without streaming the whole data is one chunk
+        let chunks = run.chunks();
+        let mut chunk_order: Vec<usize> = (0..chunks.len()).collect();
+        if self.config.shuffle && chunks.len() > 1 {
+            shuffle_order(&mut chunk_order, run.state.seed ^ CHUNK_STREAM, run.state.epochs_trained + 1);
+        }
+        let batch_size = self.config.batch_size.unwrap_or(1);
+        let batches = chunks.iter().map(|c| c.len().div_ceil(batch_size)).sum();
+        let mut batch = 0;
+        let mut gradient_norm_sum = 0.0;
+        let mut loss_sum = 0.0;
+        let mut samples = 0;
+        let mut updates = 0;
+        for c in chunk_order {
+            let range = chunks[c].clone();
+            self.load_chunk(run, range.clone())?;
+            if self.config.shuffle {
+                // Chunk 0 (all of the data unless streaming) uses the run's seed
+                let seed = run.state.seed ^ (c as u64).wrapping_mul(CHUNK_STREAM);
+                shuffle_order(&mut run.order, seed, run.state.epochs_trained + 1);
+            }
+            if let (Some(std_dev), Some(clean)) = (self.config.input_noise, &run.clean_inputs) {
+                let noise = InputNoise {
+                    std_dev,
+                    seed: run.state.seed ^ INPUT_NOISE_STREAM,
+                };
+                for (i, (input, clean)) in run.inputs.iter_mut().zip(clean).enumerate() {
+                    input.copy_from_slice(clean);
+                    noise.apply(input, u64::from(run.state.epochs_trained), range.start + i);
                 }
+            }
 
-            // Call callbacks
-            for callback in &mut self.callbacks {
-                callback(epoch, loss, &self.network);
-            }
-
-            // Save checkpoint if needed
-            if let (Some(interval), Some(path)) = (self.config.checkpoint_interval, &self.config.checkpoint_path)
-                && epoch % interval == 0 {
-                    let metadata = CheckpointMetadata {
-                        version: "1.0".to_string(),
-                        example: self.config.example_name.clone().unwrap_or_else(|| "training".to_string()),
-                        epoch,
-                        total_epochs: self.config.epochs,
-                        learning_rate: self.network.learning_rate,
-                        timestamp: chrono::Utc::now().to_rfc3339(),
-                    };
-                    self.network.save_checkpoint(path, metadata)?;
+            for indices in run.order.chunks(batch_size) {
+                if self.cancellation.is_cancelled() {
+                    return Ok(false);
+                }
+                let weights = run.weights.as_deref();
+                let (gradients, scale) = match &mut run.batch {
+                    Some(workspace) => {
+                        self.network.compute_batch_gradients_with(
+                            &run.inputs,
+                            &run.targets,
+                            indices,
+                            weights,
+                            workspace,
+                        );
+                        loss_sum += workspace.loss();
+                        (workspace.gradients(), 1.0)
+                    }
+                    None => {
+                        let j = indices[0];
+                        let weight = weights.map_or(1.0, |w| w[j]);
+                        self.network.feed_forward_with(&run.inputs[j], &mut run.workspace);
+                        self.network.compute_gradients_with(&run.targets[j], &mut run.workspace);
+                        loss_sum += weight * run.workspace.squared_error(&run.targets[j]);
+                        (run.workspace.gradients(), weight)
+                    }
+                };
+                let gradients = match &mut run.noise {
+                    Some((noisy, rng)) => {
+                        add_noise(noisy, gradients, noise_std_dev, rng);
+                        &*noisy
+                    }
+                    None => gradients,
+                };
+                gradient_norm_sum += gradients.norm() * scale;
+                run.state
+                    .optimizer_state
+                    .apply(&self.config.optimizer, &mut self.network, gradients, scale);
+                updates += 1;
+                samples += indices.len();
+                let ctx = BatchContext {
+                    epoch,
+                    batch,
+                    batches,
+                    samples,
+                    running_loss: loss_sum / samples as f64,
+                    network: &self.network,
+                };
+                for callback in self.callbacks.iter_mut().chain(run.builtin.iter_mut()) {
+                    callback.on_batch_end(&ctx);
                 }
+                batch += 1;
+            }
         }
+        run.epoch = epoch;
+        run.state.epochs_trained += 1;
+        let gradient_norm = if updates == 0 {
+            0.0
+        } else {
+            gradient_norm_sum / updates as f64
+        };
 
-        Ok(())
+        // Calculate loss for callbacks
+        let (output_losses, metrics) = if run.stream.is_some() {
+            self.evaluate_stream(run)?
+        } else {
+            let inputs = run.clean_inputs.as_ref().unwrap_or(&run.inputs);
+            self.evaluate(inputs, &run.targets, run.weights.as_deref(), &mut run.workspace)
+        };
+        let inputs = run.clean_inputs.as_ref().unwrap_or(&run.inputs);
+        let loss: f64 = output_losses.iter().sum();
+
+        if !loss.is_finite() || self.network.first_non_finite_layer().is_some() {
+            return Err(self.handle_divergence(epoch, loss, inputs).into());
+        }
+
+        let (validation_loss, validation_metrics) = match &run.validation {
+            Some((val_inputs, val_targets)) => {
+                let (losses, metrics) = self.evaluate(val_inputs, val_targets, None, &mut run.workspace);
+                (Some(losses.iter().sum::<f64>()), metrics)
+            }
+            None => (None, None),
+        };
+
+        self.history.record(EpochRecord {
+            epoch,
+            loss,
+            weight_norms: self.network.weight_norms(),
+            gradient_norm,
+            output_losses,
+            validation_loss,
+            metrics,
+            validation_metrics,
+            learning_rate: self.network.learning_rate,
+            elapsed_secs: (run.elapsed + call_start.elapsed()).as_secs_f64(),
+            epoch_secs: epoch_start.elapsed().as_secs_f64(),
+        });
+
+        // Track the best epoch by validation loss when available
+        let monitored = validation_loss.unwrap_or(loss);
+        if monitored < run.best_loss {
+            run.best_loss = monitored;
+            if self.config.restore_best {
+                run.best_network = Some(self.network.clone());
+            }
+            self.best_epoch = Some(epoch);
+        }
+
+        // Verbose output
+        if self.config.verbose
+            && (self.config.epochs < 100 || epoch.is_multiple_of(self.config.epochs / 100))
+        {
+            let record = self.history.last().expect("epoch was just recorded");
+            let timing = format!(
+                "elapsed {:.1}s, ETA {:.1}s",
+                record.elapsed_secs,
+                record.eta_secs(self.config.epochs)
+            );
+            match validation_loss {
+                Some(val) => println!(
+                    "Epoch {} of {}: loss = {:.6}, validation loss = {:.6} ({})",
+                    epoch, self.config.epochs, loss, val, timing
+                ),
+                None => println!("Epoch {} of {}: loss = {:.6} ({})", epoch, self.config.epochs, loss, timing),
+            }
+            if let Some(m) = &record.metrics {
+                println!("  {}", m);
+            }
+            if let Some(m) = &record.validation_metrics {
+                println!("  validation {}", m);
+            }
+            if record.output_losses.len() > 1 {
+                let per_output: Vec<String> =
+                    record.output_losses.iter().map(|l| format!("{:.6}", l)).collect();
+                println!("  per-output loss: [{}]", per_output.join(", "));
+            }
+        }
+
+        // Call callbacks, user-registered first
+        let ctx = EpochContext {
+            record: self.history.last().expect("epoch was just recorded"),
+            history: &self.history,
+            previous_history: &self.previous_history,
+            previous_epochs: self.previous_epochs,
+            lineage: &self.lineage,
+            network: &self.network,
+            total_epochs: self.config.epochs,
+            training_state: &run.state,
+            normalizer: self.normalizer.as_ref(),
+            target_normalizer: self.target_normalizer.as_ref(),
+            created_at: self.created_at.as_deref(),
+            dataset_fingerprint: self.dataset_fingerprint.as_deref(),
+            overrides: &self.overrides,
+        };
+        let mut stop = false;
+        for callback in self.callbacks.iter_mut().chain(run.builtin.iter_mut()) {
+            stop |= callback.on_epoch_end(&ctx)? == CallbackAction::Stop;
+        }
+        Ok(!stop)
+    }
+
+    /// Build a divergence error, rolling back to the last checkpoint if configured
+    fn handle_divergence(&mut self, epoch: u32, loss: f64, inputs: &[Vec<f64>]) -> DivergenceError {
+        let layer = self.network.first_non_finite_layer().or_else(|| {
+            inputs.iter().find_map(|input| {
+                self.network.feed_forward(Matrix::from(input.clone()));
+                self.network
+                    .get_activations()
+                    .iter()
+                    .skip(1)
+                    .position(|layer| layer.iter().any(|v| !v.is_finite()))
+            })
+        });
+
+        let mut rolled_back_to = None;
+        if self.config.rollback_on_divergence
+            && let Some(path) = &self.config.checkpoint_path
+            && path.exists()
+            && let Ok(checkpoint) = Checkpoint::load_with_secret(path, self.config.encryption.as_ref())
+            && checkpoint.network.layers == self.network.layers
+        {
+            let checkpoint_epoch = checkpoint.metadata.epoch;
+            self.network = checkpoint.network;
+            // Checkpoint epochs count from the start of the first run, records from this run's
+            let run_epoch = checkpoint_epoch.saturating_sub(self.previous_epochs);
+            self.history.epochs.retain(|r| r.epoch <= run_epoch);
+            rolled_back_to = Some(checkpoint_epoch);
+        }
+
+        DivergenceError {
+            epoch,
+            loss,
+            layer,
+            rolled_back_to,
+        }
     }
 
     /// Get a reference to the trained network
@@ -104,19 +1201,165 @@ impl TrainingController {
         &self.network
     }
 
+    /// Get a mutable reference to the network, e.g. to change its activation
+    /// before resuming
+    pub fn network_mut(&mut self) -> &mut Network {
+        &mut self.network
+    }
+
+    /// Note in saved checkpoints that hyperparameter `name` changed from
+    /// `before` to `after` on resuming
+    ///
+    /// Only records the change; the caller applies it, through the config or
+    /// `network_mut`. The override is dated to the epoch the controller
+    /// resumed from.
+    pub fn record_override(&mut self, name: &str, before: impl std::fmt::Display, after: impl std::fmt::Display) {
+        self.overrides.push(HyperparameterOverride {
+            name: name.to_string(),
+            before: before.to_string(),
+            after: after.to_string(),
+            epoch: self.previous_epochs,
+        });
+    }
+
+    /// Hyperparameters changed on resuming, this run's last
+    pub fn overrides(&self) -> &[HyperparameterOverride] {
+        &self.overrides
+    }
+
+    /// Get the per-epoch telemetry recorded by the current or last run
+    pub fn history(&self) -> &TrainingHistory {
+        &self.history
+    }
+
+    /// The history of the checkpoint this controller resumed from, continued
+    /// by the epochs recorded since
+    ///
+    /// This is the history saved in checkpoints; it equals `history()` for
+    /// the first run of a controller created with `new`.
+    pub fn full_history(&self) -> TrainingHistory {
+        self.previous_history.continued_by(&self.history)
+    }
+
+    /// Epoch with the lowest monitored loss (validation loss if configured,
+    /// training loss otherwise) during the last `train` call
+    pub fn best_epoch(&self) -> Option<u32> {
+        self.best_epoch
+    }
+
+    /// Optimizer and shuffle state of the current run, or else of the last
+    /// finished run or the checkpoint the controller was created from
+    pub fn training_state(&self) -> Option<&TrainingState> {
+        self.run.as_ref().map(|run| &run.state).or(self.state.as_ref())
+    }
+
+    /// Input transform fit by `TrainingConfig::normalization`, if any
+    ///
+    /// Apply it to raw inputs before feeding them to the trained network.
+    pub fn normalizer(&self) -> Option<&Normalizer> {
+        self.normalizer.as_ref()
+    }
+
+    /// Target transform fit by `TrainingConfig::target_normalization`, if any
+    ///
+    /// The network predicts scaled targets; `Normalizer::invert` its outputs
+    /// to get predictions in the original units.
+    pub fn target_normalizer(&self) -> Option<&Normalizer> {
+        self.target_normalizer.as_ref()
+    }
+
     /// Create a training controller from a checkpoint file
+    ///
+    /// Besides the network, the normalizers, optimizer statistics and shuffle
+    /// position saved with the checkpoint are restored, so training continues exactly
+    /// as if it had not stopped, given the same data and a config with the
+    /// same optimizer, shuffling and batch size (see `TrainingState`). The
+    /// checkpoint's history is kept too: checkpoints saved by the resumed run
+    /// continue its curve (see `full_history`), count epochs from the
+    /// checkpoint's epoch and add it to their lineage.
     pub fn from_checkpoint(
         checkpoint_path: &std::path::Path,
         config: TrainingConfig,
     ) -> anyhow::Result<Self> {
-        let (network, _metadata) = Network::load_checkpoint(checkpoint_path)?;
+        let checkpoint = Checkpoint::load_with_secret(checkpoint_path, config.encryption.as_ref())?;
+        let state = checkpoint.training_state.clone();
+        let normalizer = checkpoint.normalizer.clone();
+        let target_normalizer = checkpoint.target_normalizer.clone();
+        let previous_history = checkpoint.history.clone();
+        let previous_epochs = checkpoint.metadata.epoch;
+        let created_at = checkpoint.metadata.created_at.clone();
+        let dataset_fingerprint = checkpoint.metadata.dataset_fingerprint.clone();
+        let overrides = checkpoint.metadata.overrides.clone();
+        let mut lineage = checkpoint.lineage.clone();
+        lineage.push(RunLineage {
+            parent_path: checkpoint_path.display().to_string(),
+            parent_checksum: network_checksum(&checkpoint.network)?,
+            parent_epoch: previous_epochs,
+            resumed_at: chrono::Utc::now().to_rfc3339(),
+        });
+        let network = Network::from_checkpoint(checkpoint)?;
         Ok(Self {
             network,
             config,
             callbacks: Vec::new(),
+            history: TrainingHistory::new(),
+            previous_history,
+            previous_epochs,
+            lineage,
+            created_at,
+            dataset_fingerprint,
+            overrides,
+            best_epoch: None,
+            cancellation: CancellationToken::new(),
+            run: None,
+            state,
+            normalizer,
+            target_normalizer,
         })
     }
 
+    /// Save the current network and history as a checkpoint at `path`
+    ///
+    /// The metadata records the last completed epoch, counting from the
+    /// checkpoint this controller resumed from, so a checkpoint taken
+    /// after a cancelled run can be resumed like any other. The training
+    /// state (see `training_state`) and the normalizers are saved as well,
+    /// along with the training time, final loss, seed, optimizer, dataset
+    /// fingerprint and dataset path in the metadata.
+    pub fn save_checkpoint(&self, path: &Path) -> anyhow::Result<()> {
+        self.to_checkpoint().save_with_secret(path, self.config.encryption.as_ref())
+    }
+
+    /// The checkpoint `save_checkpoint` writes, built in memory
+    pub fn to_checkpoint(&self) -> Checkpoint {
+        let history = self.full_history();
+        let metadata = CheckpointMetadata {
+            version: "1.0".to_string(),
+            example: self.config.example_name.clone().unwrap_or_else(|| "training".to_string()),
+            epoch: self.previous_epochs + self.history.last().map_or(0, |r| r.epoch),
+            total_epochs: self.previous_epochs + self.config.epochs,
+            learning_rate: self.network.learning_rate,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            created_at: self.created_at.clone(),
+            crate_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            training_secs: history.last().map(|r| r.elapsed_secs),
+            final_loss: history.last().map(|r| r.loss),
+            // The seed the run actually used, drawn at random when none was configured
+            seed: self.training_state().map(|state| state.seed).or(self.config.seed),
+            optimizer: Some(self.config.optimizer.name().to_string()),
+            dataset_fingerprint: self.dataset_fingerprint.clone(),
+            dataset_path: self.config.dataset_path.clone(),
+            overrides: self.overrides.clone(),
+        };
+        self.network
+            .to_checkpoint(metadata)
+            .with_history(history)
+            .with_lineage(self.lineage.clone())
+            .with_training_state(self.training_state().cloned())
+            .with_normalizer(self.normalizer.clone())
+            .with_target_normalizer(self.target_normalizer.clone())
+    }
+
     /// Consume the controller and return the network
     pub fn into_network(self) -> Network {
         self.network
@@ -0,0 +1,280 @@
+/// Preallocated scratch buffers for the training loop
+///
+/// `feed_forward` and `compute_gradients` allocate fresh matrices for every
+/// activation, delta and gradient on every sample. A `Workspace` holds all of
+/// them for one architecture so the `_with` variants below can run a full
+/// forward/backward/update step without touching the heap. Results are
+/// bit-identical to the allocating methods.
+///
+/// `BatchWorkspace` does the same for mini-batches: it averages the gradients
+/// of many samples, summing chunks of the batch on separate threads when the
+/// `parallel` feature is enabled.
+use crate::matrix::Matrix;
+use crate::network::{Gradients, Network};
+
+/// Intermediate matrices for one forward and backward pass
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    /// Activations of every layer, including the input layer
+    activations: Vec<Matrix>,
+
+    /// Inputs to the activation of every layer after the input layer
+    pre_activations: Vec<Matrix>,
+
+    /// Gradients of the last backward pass
+    ///
+    /// The bias gradient of a layer equals its delta, so `gradients.biases`
+    /// doubles as the delta buffer while back-propagating.
+    gradients: Gradients,
+}
+
+impl Workspace {
+    /// Allocate buffers for a network with the given layer sizes
+    pub fn new(layers: &[usize]) -> Self {
+        let activations = layers.iter().map(|&n| Matrix::zeros(n, 1)).collect();
+        let pre_activations = layers.iter().skip(1).map(|&n| Matrix::zeros(n, 1)).collect();
+        let (weights, biases) = layers
+            .windows(2)
+            .map(|pair| (Matrix::zeros(pair[1], pair[0]), Matrix::zeros(pair[1], 1)))
+            .unzip();
+
+        Workspace {
+            activations,
+            pre_activations,
+            gradients: Gradients { weights, biases },
+        }
+    }
+
+    /// Whether the buffers match the given layer sizes
+    pub fn fits(&self, layers: &[usize]) -> bool {
+        self.activations.len() == layers.len()
+            && self.activations.iter().zip(layers).all(|(a, &n)| a.rows == n)
+    }
+
+    /// Activations of every layer from the last `feed_forward_with` call
+    pub fn activations(&self) -> &[Matrix] {
+        &self.activations
+    }
+
+    /// Output layer activations from the last `feed_forward_with` call
+    pub fn output(&self) -> &Matrix {
+        self.activations.last().expect("a workspace has at least one layer")
+    }
+
+    /// Gradients from the last `compute_gradients_with` call
+    pub fn gradients(&self) -> &Gradients {
+        &self.gradients
+    }
+
+    /// Summed squared error of the last output against `targets`
+    pub fn squared_error(&self, targets: &[f64]) -> f64 {
+        targets.iter().zip(&self.output().data).map(|(t, o)| (t - o).powi(2)).sum()
+    }
+}
+
+/// Samples per chunk when summing the gradients of a batch
+///
+/// Each chunk is summed on its own (on its own thread with the `parallel`
+/// feature) and the chunk sums are then added in order. The grouping does not
+/// depend on the number of threads, so serial and parallel builds produce
+/// bit-identical results.
+pub const GRADIENT_CHUNK: usize = 32;
+
+/// Scratch buffers for `Network::compute_batch_gradients_with`
+#[derive(Debug, Clone)]
+pub struct BatchWorkspace {
+    layers: Vec<usize>,
+
+    /// A workspace, a running gradient sum and a running loss sum for each
+    /// chunk of the batch
+    chunks: Vec<(Workspace, Gradients, f64)>,
+
+    /// Averaged gradients of the last batch
+    gradients: Gradients,
+
+    /// Summed loss of the last batch
+    loss: f64,
+}
+
+impl BatchWorkspace {
+    /// Allocate buffers for a network with the given layer sizes
+    ///
+    /// Buffers for further chunks are added when a larger batch comes along.
+    pub fn new(layers: &[usize]) -> Self {
+        let workspace = Workspace::new(layers);
+        BatchWorkspace {
+            layers: layers.to_vec(),
+            gradients: workspace.gradients.clone(),
+            chunks: Vec::new(),
+            loss: 0.0,
+        }
+    }
+
+    /// Averaged gradients from the last `compute_batch_gradients_with` call
+    pub fn gradients(&self) -> &Gradients {
+        &self.gradients
+    }
+
+    /// Summed (weighted) squared error of the samples in the last
+    /// `compute_batch_gradients_with` call, from the forward passes
+    pub fn loss(&self) -> f64 {
+        self.loss
+    }
+}
+
+/// Set every gradient to zero
+fn clear(gradients: &mut Gradients) {
+    for m in gradients.weights.iter_mut().chain(gradients.biases.iter_mut()) {
+        m.data.fill(0.0);
+    }
+}
+
+/// Add `gradients` to `sum`, scaled by `factor`
+fn accumulate(sum: &mut Gradients, gradients: &Gradients, factor: f64) {
+    let sums = sum.weights.iter_mut().chain(sum.biases.iter_mut());
+    let terms = gradients.weights.iter().chain(gradients.biases.iter());
+    for (s, g) in sums.zip(terms) {
+        for (s, g) in s.data.iter_mut().zip(&g.data) {
+            *s += g * factor;
+        }
+    }
+}
+
+impl Network {
+    /// Create a workspace sized for this network
+    pub fn workspace(&self) -> Workspace {
+        Workspace::new(&self.layers)
+    }
+
+    /// Forward pass that writes every activation into `workspace`
+    ///
+    /// Equivalent to `feed_forward`, but allocation-free and without touching
+    /// the activations cached on the network.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input` does not match the input layer or the workspace was
+    /// created for a different architecture
+    pub fn feed_forward_with<'w>(&self, input: &[f64], workspace: &'w mut Workspace) -> &'w Matrix {
+        assert!(workspace.fits(&self.layers), "Workspace does not match the network architecture");
+        assert!(self.layers[0] == input.len(), "Invalid Number of Inputs");
+
+        let function = self.activation.function;
+        workspace.activations[0].data.copy_from_slice(input);
+        for i in 0..self.weights.len() {
+            let (done, rest) = workspace.activations.split_at_mut(i + 1);
+            let z = &mut workspace.pre_activations[i];
+            self.weights[i].dot_multiply_into(&done[i], z);
+            for ((value, z), bias) in rest[0].data.iter_mut().zip(&mut z.data).zip(&self.biases[i].data) {
+                *z += bias;
+                *value = function(z);
+            }
+        }
+
+        workspace.output()
+    }
+
+    /// Backward pass for the sample passed to the last `feed_forward_with` call
+    ///
+    /// Equivalent to `compute_gradients`; the result is available from
+    /// `workspace.gradients()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `targets` does not match the output layer
+    pub fn compute_gradients_with(&self, targets: &[f64], workspace: &mut Workspace) {
+        let derivative = self.activation.derivative;
+        let layer_count = self.layers.len() - 1;
+        let Workspace { activations, pre_activations, gradients } = workspace;
+        assert!(targets.len() == self.layers[layer_count], "Invalid Number of Targets");
+
+        // dLoss/dz of the output layer
+        let output = &activations[layer_count];
+        let z = &pre_activations[layer_count - 1];
+        for (((delta, o), z), t) in gradients.biases[layer_count - 1].data.iter_mut().zip(&output.data).zip(&z.data).zip(targets) {
+            *delta = (o - t) * derivative(z, o);
+        }
+
+        for i in (0..layer_count).rev() {
+            gradients.biases[i].dot_multiply_transposed_rhs_into(&activations[i], &mut gradients.weights[i]);
+
+            if i > 0 {
+                let (lower, upper) = gradients.biases.split_at_mut(i);
+                self.weights[i].dot_multiply_transposed_lhs_into(&upper[0], &mut lower[i - 1]);
+                for ((delta, a), z) in lower[i - 1].data.iter_mut().zip(&activations[i].data).zip(&pre_activations[i - 1].data) {
+                    *delta *= derivative(z, a);
+                }
+            }
+        }
+    }
+
+    /// Forward pass, backward pass and gradient-descent step for one sample
+    ///
+    /// Equivalent to `feed_forward` followed by `back_propogate`.
+    pub fn train_sample_with(&mut self, input: &[f64], targets: &[f64], workspace: &mut Workspace) {
+        self.feed_forward_with(input, workspace);
+        self.compute_gradients_with(targets, workspace);
+        self.apply_gradients(workspace.gradients());
+    }
+
+    /// Average the gradients of the samples at `batch` (indices into `inputs`
+    /// and `targets`) and return them
+    ///
+    /// With `weights` (indexed like `inputs`) each sample's gradient is scaled
+    /// by its weight before averaging over the batch size. The batch is split
+    /// into chunks of `GRADIENT_CHUNK` samples; with the `parallel` feature
+    /// the chunks are processed on rayon's thread pool.
+    /// For a single-sample batch the result equals `compute_gradients_with`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch` is empty, an index is out of range, a sample does not
+    /// match the network, or the workspace was created for a different
+    /// architecture
+    pub fn compute_batch_gradients_with<'w>(
+        &self,
+        inputs: &[Vec<f64>],
+        targets: &[Vec<f64>],
+        batch: &[usize],
+        weights: Option<&[f64]>,
+        workspace: &'w mut BatchWorkspace,
+    ) -> &'w Gradients {
+        assert!(!batch.is_empty(), "A batch needs at least one sample");
+        assert!(workspace.layers == self.layers, "Workspace does not match the network architecture");
+
+        let chunk_count = batch.len().div_ceil(GRADIENT_CHUNK);
+        while workspace.chunks.len() < chunk_count {
+            workspace.chunks.push((self.workspace(), workspace.gradients.clone(), 0.0));
+        }
+
+        let sum_chunk = |(indices, (chunk, sum, loss)): (&[usize], &mut (Workspace, Gradients, f64))| {
+            clear(sum);
+            *loss = 0.0;
+            for &j in indices {
+                let weight = weights.map_or(1.0, |w| w[j]);
+                self.feed_forward_with(&inputs[j], chunk);
+                self.compute_gradients_with(&targets[j], chunk);
+                accumulate(sum, &chunk.gradients, weight);
+                *loss += weight * chunk.squared_error(&targets[j]);
+            }
+        };
+        let chunks = &mut workspace.chunks[..chunk_count];
+
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            batch.par_chunks(GRADIENT_CHUNK).zip(chunks.par_iter_mut()).for_each(sum_chunk);
+        }
+        #[cfg(not(feature = "parallel"))]
+        batch.chunks(GRADIENT_CHUNK).zip(chunks.iter_mut()).for_each(sum_chunk);
+
+        let scale = 1.0 / batch.len() as f64;
+        clear(&mut workspace.gradients);
+        workspace.loss = 0.0;
+        for (_, sum, loss) in &workspace.chunks[..chunk_count] {
+            accumulate(&mut workspace.gradients, sum, scale);
+            workspace.loss += loss;
+        }
+        &workspace.gradients
+    }
+}
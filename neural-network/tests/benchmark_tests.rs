@@ -0,0 +1,67 @@
+// Integration tests for the throughput benchmark
+use neural_network::activations::SIGMOID;
+use neural_network::benchmark::Benchmark;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::time::Duration;
+
+/// Counts allocations made by the current thread, like a binary's counting
+/// allocator but unaffected by tests running in parallel
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<u64> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> u64 {
+    ALLOCATIONS.with(Cell::get)
+}
+
+fn small_benchmark() -> Benchmark {
+    Benchmark {
+        layers: vec![8, 16, 4],
+        batch: 4,
+        min_time: Duration::from_millis(20),
+        ..Benchmark::new(SIGMOID)
+    }
+}
+
+#[test]
+fn test_every_phase_is_timed() {
+    let report = small_benchmark().run(None);
+    let names: Vec<&str> = report.phases.iter().map(|phase| phase.name.as_str()).collect();
+    assert_eq!(
+        names,
+        ["feed-forward", "feed-forward (workspace)", "backprop (per sample)", "backprop (batch of 4)"]
+    );
+    for phase in &report.phases {
+        assert!(phase.seconds >= 0.02, "{:?}", phase);
+        assert!(phase.samples > 0 && phase.samples_per_second() > 0.0, "{:?}", phase);
+        assert_eq!(phase.allocations, None);
+    }
+    assert_eq!(report.phases[3].samples % 4, 0, "Whole batches are counted");
+}
+
+#[test]
+fn test_allocations_are_counted_per_sample() {
+    let report = small_benchmark().run(Some(allocations));
+    let per_sample: Vec<f64> = report.phases.iter().map(|phase| phase.allocations_per_sample().unwrap()).collect();
+
+    assert!(per_sample[0] >= 1.0, "The allocating forward pass allocates: {:?}", per_sample);
+    assert_eq!(per_sample[1], 0.0, "The workspace forward pass does not allocate");
+    assert_eq!(per_sample[2], 0.0, "A workspace training step does not allocate");
+}
@@ -0,0 +1,60 @@
+// Integration tests for decision-boundary grids
+use neural_network::activations::SIGMOID;
+use neural_network::boundary::{DecisionGrid, input_ranges};
+use neural_network::checkpoint::CheckpointMetadata;
+use neural_network::inference::InferenceModel;
+use neural_network::matrix::Matrix;
+use neural_network::network::Network;
+
+fn model(network: Network) -> InferenceModel {
+    InferenceModel {
+        metadata: CheckpointMetadata::default(),
+        network,
+        normalizer: None,
+        target_normalizer: None,
+    }
+}
+
+#[test]
+fn test_grid_follows_a_linear_boundary() {
+    // One sigmoid unit that fires when x > y
+    let mut network = Network::new(vec![2, 1], SIGMOID, 0.5);
+    network.weights[0] = Matrix::new(1, 2, vec![10.0, -10.0]).unwrap();
+    network.biases[0] = Matrix::new(1, 1, vec![0.0]).unwrap();
+    let mut model = model(network);
+
+    let grid = DecisionGrid::sample(&mut model, (0.0, 1.0), (0.0, 1.0), 5).unwrap();
+    assert_eq!(grid.num_classes, 2);
+    assert_eq!(grid.classes.len(), 25);
+    // The top row is y = 1, so its left corner is far from the boundary
+    assert_eq!(grid.classes[grid.index(0, 0)], 0);
+    assert_eq!(grid.classes[grid.index(4, 4)], 1, "Bottom right is x = 1, y = 0");
+    assert_eq!(grid.classes[grid.index(4, 0)], 1, "Bottom left sits on the boundary, where the 0.5 threshold picks class 1");
+    assert!(grid.confidence[grid.index(4, 0)] < 1e-9);
+    assert!(grid.confidence[grid.index(4, 4)] > 0.99);
+    assert!(grid.confidence.iter().all(|c| (0.0..=1.0).contains(c)));
+}
+
+#[test]
+fn test_grid_rejects_other_input_sizes() {
+    let mut three_inputs = model(Network::new_seeded(vec![3, 2, 1], SIGMOID, 0.5, 1));
+    let err = DecisionGrid::sample(&mut three_inputs, (0.0, 1.0), (0.0, 1.0), 10).unwrap_err();
+    assert!(err.to_string().contains("need a network with 2 inputs, this one has 3"), "{}", err);
+
+    let mut two_inputs = model(Network::new_seeded(vec![2, 3, 3], SIGMOID, 0.5, 1));
+    assert!(DecisionGrid::sample(&mut two_inputs, (0.0, 1.0), (0.0, 1.0), 1).is_err());
+    let grid = DecisionGrid::sample(&mut two_inputs, (0.0, 1.0), (0.0, 1.0), 3).unwrap();
+    assert_eq!(grid.num_classes, 3);
+    assert!(grid.classes.iter().all(|&class| class < 3));
+}
+
+#[test]
+fn test_input_ranges_are_padded() {
+    let inputs = vec![vec![0.0, 2.0], vec![1.0, 2.0]];
+    let (x, y) = input_ranges(&inputs, 0.1).unwrap();
+    assert!((x.0 + 0.1).abs() < 1e-12 && (x.1 - 1.1).abs() < 1e-12, "{:?}", x);
+    assert_eq!(y, (1.5, 2.5), "A constant input gets a range of one");
+
+    assert!(input_ranges(&[], 0.1).is_err());
+    assert!(input_ranges(&[vec![0.0, 1.0, 2.0]], 0.1).is_err());
+}
@@ -0,0 +1,179 @@
+// Integration tests for the TrainingCallback hooks
+use neural_network::activations::SIGMOID;
+use neural_network::callbacks::{
+    BatchContext, CallbackAction, Checkpointer, EarlyStopping, EpochContext, TrainingCallback, from_batch_fn,
+};
+use neural_network::checkpoint::Checkpoint;
+use neural_network::history::TrainingHistory;
+use neural_network::network::Network;
+use neural_network::training::{TrainingConfig, TrainingController};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Records the name of every hook invocation
+struct Recorder {
+    events: Arc<Mutex<Vec<String>>>,
+    stop_at: Option<u32>,
+}
+
+impl TrainingCallback for Recorder {
+    fn on_train_start(&mut self, config: &TrainingConfig, _network: &Network) -> anyhow::Result<()> {
+        self.events.lock().unwrap().push(format!("start {}", config.epochs));
+        Ok(())
+    }
+
+    fn on_batch_end(&mut self, ctx: &BatchContext) {
+        self.events.lock().unwrap().push(format!("batch {}.{}", ctx.epoch, ctx.batch));
+    }
+
+    fn on_epoch_end(&mut self, ctx: &EpochContext) -> anyhow::Result<CallbackAction> {
+        assert_eq!(ctx.history.last(), Some(ctx.record));
+        self.events.lock().unwrap().push(format!("epoch {}", ctx.record.epoch));
+        if self.stop_at == Some(ctx.record.epoch) {
+            return Ok(CallbackAction::Stop);
+        }
+        Ok(CallbackAction::Continue)
+    }
+
+    fn on_train_end(&mut self, history: &TrainingHistory, _network: &Network) -> anyhow::Result<()> {
+        self.events.lock().unwrap().push(format!("end {}", history.len()));
+        Ok(())
+    }
+}
+
+fn controller(epochs: u32) -> TrainingController {
+    let config = TrainingConfig {
+        epochs,
+        ..Default::default()
+    };
+    TrainingController::new(Network::new(vec![2, 2, 1], SIGMOID, 0.5), config)
+}
+
+fn recorder(stop_at: Option<u32>) -> (Recorder, Arc<Mutex<Vec<String>>>) {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    (Recorder { events: events.clone(), stop_at }, events)
+}
+
+#[test]
+fn test_hooks_run_in_order() {
+    let (callback, events) = recorder(None);
+    let mut controller = controller(2);
+    controller.add_callback(callback);
+    controller
+        .train(vec![vec![0.0, 0.0], vec![1.0, 1.0]], vec![vec![0.0], vec![1.0]])
+        .unwrap();
+
+    assert_eq!(*events.lock().unwrap(), vec![
+        "start 2", "batch 1.0", "batch 1.1", "epoch 1", "batch 2.0", "batch 2.1", "epoch 2", "end 2",
+    ]);
+}
+
+#[test]
+fn test_stop_action_ends_training_after_all_callbacks_ran() {
+    let (stopper, _) = recorder(Some(3));
+    let (observer, events) = recorder(None);
+    let mut controller = controller(10);
+    controller.add_callback(stopper);
+    controller.add_callback(observer);
+    let history = controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+
+    assert_eq!(history.len(), 3);
+    let events = events.lock().unwrap();
+    assert!(events.contains(&"epoch 3".to_string()), "Later callbacks still see the stopping epoch");
+    assert_eq!(events.last().unwrap(), "end 3");
+}
+
+struct Failing;
+
+impl TrainingCallback for Failing {
+    fn on_epoch_end(&mut self, ctx: &EpochContext) -> anyhow::Result<CallbackAction> {
+        anyhow::bail!("failed at epoch {}", ctx.record.epoch)
+    }
+}
+
+#[test]
+fn test_callback_error_aborts_training() {
+    let (observer, events) = recorder(None);
+    let mut controller = controller(5);
+    controller.add_callback(Failing);
+    controller.add_callback(observer);
+    let err = controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap_err();
+
+    assert_eq!(err.to_string(), "failed at epoch 1");
+    assert_eq!(controller.history().len(), 1);
+    assert!(!events.lock().unwrap().iter().any(|e| e.starts_with("end")), "No end hook on failure");
+}
+
+#[test]
+fn test_early_stopping_as_registered_callback() {
+    // A zero learning rate never improves the loss after the first epoch
+    let config = TrainingConfig {
+        epochs: 50,
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![2, 2, 1], SIGMOID, 0.0), config);
+    controller.add_callback(EarlyStopping::new(2));
+    let history = controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+
+    assert_eq!(history.len(), 3);
+}
+
+#[test]
+fn test_running_loss_ends_at_epoch_loss() {
+    // With a zero learning rate the running loss covers an unchanged network
+    for batch_size in [None, Some(2)] {
+        let config = TrainingConfig {
+            epochs: 2,
+            batch_size,
+            ..Default::default()
+        };
+        let mut controller = TrainingController::new(Network::new_seeded(vec![2, 2, 1], SIGMOID, 0.0, 7), config);
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let sink = progress.clone();
+        controller.add_callback(from_batch_fn(Duration::ZERO, move |ctx| {
+            sink.lock().unwrap().push((ctx.epoch, ctx.batch, ctx.batches, ctx.samples, ctx.running_loss));
+        }));
+        let inputs = vec![vec![0.0, 0.0], vec![0.0, 1.0], vec![1.0, 1.0]];
+        let targets = vec![vec![0.0], vec![1.0], vec![0.0]];
+        let history = controller.train(inputs, targets).unwrap();
+
+        let progress = progress.lock().unwrap();
+        let batches = if batch_size.is_some() { 2 } else { 3 };
+        assert_eq!(progress.len(), 2 * batches);
+        let &(epoch, batch, total, samples, running_loss) = progress.last().unwrap();
+        assert_eq!((epoch, batch, total, samples), (2, batches - 1, batches, 3));
+        assert!((running_loss - history.epochs[1].loss).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_batch_progress_is_throttled() {
+    let mut controller = controller(20);
+    let calls = Arc::new(Mutex::new(0));
+    let sink = calls.clone();
+    controller.add_callback(from_batch_fn(Duration::from_secs(60), move |_ctx| {
+        *sink.lock().unwrap() += 1;
+    }));
+    controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+
+    assert_eq!(*calls.lock().unwrap(), 0, "Short epochs report no batch progress");
+}
+
+#[test]
+fn test_numbered_checkpointer_keeps_every_snapshot() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let mut controller = controller(10);
+    controller.add_callback(Checkpointer::new(temp_dir.path().join("run.json"), "xor").every_epochs(4).numbered());
+    controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+
+    let mut names: Vec<String> = std::fs::read_dir(temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["run_epoch000004.json", "run_epoch000008.json"]);
+
+    let snapshot = Checkpoint::load(&temp_dir.path().join("run_epoch000008.json")).unwrap();
+    assert_eq!(snapshot.metadata.epoch, 8);
+    assert_eq!(snapshot.history.len(), 8);
+}
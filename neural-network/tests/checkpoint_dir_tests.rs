@@ -0,0 +1,96 @@
+// Integration tests for listing and pruning checkpoint directories
+use neural_network::activations::SIGMOID;
+use neural_network::checkpoint::CheckpointMetadata;
+use neural_network::checkpoint_dir::{self, CheckpointEntry, Retention};
+use neural_network::network::Network;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Save a checkpoint at `epoch` with the given loss, one hour apart per epoch
+fn save(dir: &Path, name: &str, epoch: u32, loss: Option<f64>) {
+    let network = Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 42);
+    let metadata = CheckpointMetadata {
+        version: "1.0".to_string(),
+        example: "xor".to_string(),
+        epoch,
+        total_epochs: 500,
+        learning_rate: 0.5,
+        timestamp: format!("2026-01-01T{:02}:00:00+00:00", epoch / 100),
+        final_loss: loss,
+        ..Default::default()
+    };
+    network.save_checkpoint(&dir.join(name), metadata).unwrap();
+}
+
+fn names(entries: &[&CheckpointEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .map(|e| e.path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect()
+}
+
+fn run_dir() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    save(dir.path(), "c_epoch000100.json", 100, Some(0.30));
+    save(dir.path(), "a_epoch000200.json", 200, Some(0.10));
+    save(dir.path(), "b_epoch000300.json", 300, Some(0.20));
+    save(dir.path(), "d_epoch000400.json", 400, None);
+    dir
+}
+
+#[test]
+fn test_scan_reads_metadata_and_skips_other_files() {
+    let dir = run_dir();
+    fs::write(dir.path().join("loss.csv"), "epoch,loss\n").unwrap();
+    fs::create_dir(dir.path().join("nested.json")).unwrap();
+
+    let entries = checkpoint_dir::scan(dir.path(), None).unwrap();
+    let paths: Vec<&CheckpointEntry> = entries.iter().collect();
+    assert_eq!(names(&paths), ["a_epoch000200.json", "b_epoch000300.json", "c_epoch000100.json", "d_epoch000400.json"]);
+    assert_eq!(entries[0].metadata.as_ref().unwrap().epoch, 200);
+    assert_eq!(entries[0].final_loss(), Some(0.10));
+    assert!(entries.iter().all(|e| e.size_bytes > 0 && e.error.is_none()));
+}
+
+#[test]
+fn test_scan_reports_unreadable_files() {
+    let dir = run_dir();
+    fs::write(dir.path().join("notes.json"), "not a checkpoint").unwrap();
+
+    let entries = checkpoint_dir::scan(dir.path(), None).unwrap();
+    let notes = entries.iter().find(|e| e.path.ends_with("notes.json")).unwrap();
+    assert!(notes.metadata.is_none());
+    assert!(notes.error.is_some());
+    assert!(!Retention::default().removals(&entries).iter().any(|e| e.path.ends_with("notes.json")),
+        "Unreadable files are never removed");
+}
+
+#[test]
+fn test_scan_missing_directory_fails() {
+    assert!(checkpoint_dir::scan(Path::new("/nonexistent/runs"), None).is_err());
+}
+
+#[test]
+fn test_keep_last_keeps_most_recent() {
+    let entries = checkpoint_dir::scan(run_dir().path(), None).unwrap();
+    let retention = Retention { keep_last: Some(2), keep_best: None };
+    assert_eq!(names(&retention.removals(&entries)), ["c_epoch000100.json", "a_epoch000200.json"]);
+}
+
+#[test]
+fn test_keep_best_keeps_lowest_loss() {
+    let entries = checkpoint_dir::scan(run_dir().path(), None).unwrap();
+    let retention = Retention { keep_last: None, keep_best: Some(2) };
+    assert_eq!(names(&retention.removals(&entries)), ["c_epoch000100.json", "d_epoch000400.json"]);
+}
+
+#[test]
+fn test_retention_rules_combine() {
+    let entries = checkpoint_dir::scan(run_dir().path(), None).unwrap();
+    let retention = Retention { keep_last: Some(1), keep_best: Some(1) };
+    assert_eq!(names(&retention.removals(&entries)), ["c_epoch000100.json", "b_epoch000300.json"]);
+
+    let keep_all = Retention { keep_last: Some(10), keep_best: None };
+    assert!(keep_all.removals(&entries).is_empty());
+}
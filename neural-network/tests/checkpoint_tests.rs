@@ -1,7 +1,7 @@
 // Integration tests for checkpoint functionality
 use neural_network::network::Network;
 use neural_network::activations::SIGMOID;
-use neural_network::checkpoint::CheckpointMetadata;
+use neural_network::checkpoint::{Checkpoint, CheckpointError, CheckpointMetadata};
 use std::fs;
 use tempfile::TempDir;
 
@@ -21,6 +21,7 @@ fn test_create_checkpoint() {
         total_epochs: 10000,
         learning_rate: 0.5,
         timestamp: "2025-10-13T12:00:00Z".to_string(),
+        ..Default::default()
     };
 
     let checkpoint = network.to_checkpoint(metadata.clone());
@@ -46,6 +47,7 @@ fn test_checkpoint_from_network() {
         total_epochs: 100,
         learning_rate: 0.5,
         timestamp: chrono::Utc::now().to_rfc3339(),
+        ..Default::default()
     };
 
     let checkpoint = network.to_checkpoint(metadata);
@@ -74,6 +76,7 @@ fn test_save_and_load_checkpoint() {
         total_epochs: 1000,
         learning_rate: 0.5,
         timestamp: chrono::Utc::now().to_rfc3339(),
+        ..Default::default()
     };
 
     // Save checkpoint
@@ -111,6 +114,7 @@ fn test_checkpoint_preserves_predictions() {
         total_epochs: 1000,
         learning_rate: 0.5,
         timestamp: chrono::Utc::now().to_rfc3339(),
+        ..Default::default()
     };
 
     network.save_checkpoint(&checkpoint_path, metadata).unwrap();
@@ -140,6 +144,7 @@ fn test_checkpoint_file_format() {
         total_epochs: 1000,
         learning_rate: 0.5,
         timestamp: chrono::Utc::now().to_rfc3339(),
+        ..Default::default()
     };
 
     network.save_checkpoint(&checkpoint_path, metadata).unwrap();
@@ -222,6 +227,7 @@ fn test_checkpoint_metadata_fields() {
         total_epochs: 10000,
         learning_rate: 0.5,
         timestamp: "2025-10-13T12:34:56Z".to_string(),
+        ..Default::default()
     };
 
     // All fields should be accessible
@@ -258,6 +264,7 @@ fn test_resume_training_from_checkpoint() {
         total_epochs: 500,
         learning_rate: 0.5,
         timestamp: chrono::Utc::now().to_rfc3339(),
+        ..Default::default()
     };
 
     network.save_checkpoint(&checkpoint_path, metadata).unwrap();
@@ -290,6 +297,7 @@ fn test_checkpoint_serialization_is_deterministic() {
         total_epochs: 1000,
         learning_rate: 0.5,
         timestamp: "2025-10-13T12:00:00Z".to_string(), // Fixed timestamp for determinism
+        ..Default::default()
     };
 
     network.save_checkpoint(&path1, metadata.clone()).unwrap();
@@ -303,3 +311,233 @@ fn test_checkpoint_serialization_is_deterministic() {
 
     // TempDir automatically cleans up when dropped
 }
+
+fn fixed_metadata() -> CheckpointMetadata {
+    CheckpointMetadata {
+        version: "1.0".to_string(),
+        example: "xor".to_string(),
+        epoch: 100,
+        total_epochs: 1000,
+        learning_rate: 0.5,
+        timestamp: "2025-10-13T12:00:00Z".to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_binary_checkpoint_roundtrip_is_exact() {
+    let temp_dir = create_temp_dir();
+    let path = temp_dir.path().join("model.bin");
+
+    let mut network = Network::new(vec![2, 3, 1], SIGMOID, 0.5);
+    network.train(vec![vec![0.0, 1.0]], vec![vec![1.0]], 10);
+    network.save_checkpoint(&path, fixed_metadata()).unwrap();
+
+    assert!(fs::read(&path).unwrap().starts_with(b"NNCB"));
+
+    let (restored, metadata) = Network::load_checkpoint(&path).unwrap();
+    assert_eq!(metadata.epoch, 100);
+    assert_eq!(restored.layers, network.layers);
+    assert_eq!(restored.weights, network.weights);
+    assert_eq!(restored.biases, network.biases);
+}
+
+#[test]
+fn test_binary_checkpoint_is_smaller_than_json() {
+    let temp_dir = create_temp_dir();
+    let json_path = temp_dir.path().join("model.json");
+    let bin_path = temp_dir.path().join("model.bin");
+
+    let network = Network::new(vec![64, 128, 10], SIGMOID, 0.5);
+    network.save_checkpoint(&json_path, fixed_metadata()).unwrap();
+    network.save_checkpoint(&bin_path, fixed_metadata()).unwrap();
+
+    let json_size = fs::metadata(&json_path).unwrap().len();
+    let bin_size = fs::metadata(&bin_path).unwrap().len();
+    assert!(bin_size * 2 < json_size, "binary {} vs json {}", bin_size, json_size);
+}
+
+#[test]
+fn test_checkpoint_format_detected_from_contents() {
+    let temp_dir = create_temp_dir();
+    let bin_path = temp_dir.path().join("model.bin");
+    let renamed = temp_dir.path().join("model.checkpoint");
+
+    let network = Network::new(vec![2, 3, 1], SIGMOID, 0.5);
+    network.save_checkpoint(&bin_path, fixed_metadata()).unwrap();
+    fs::rename(&bin_path, &renamed).unwrap();
+
+    let (restored, _) = Network::load_checkpoint(&renamed).unwrap();
+    assert_eq!(restored.weights, network.weights);
+}
+
+#[test]
+fn test_truncated_binary_checkpoint_fails() {
+    let temp_dir = create_temp_dir();
+    let path = temp_dir.path().join("model.bin");
+
+    let network = Network::new(vec![2, 3, 1], SIGMOID, 0.5);
+    network.save_checkpoint(&path, fixed_metadata()).unwrap();
+
+    let bytes = fs::read(&path).unwrap();
+    fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+    assert!(Network::load_checkpoint(&path).is_err());
+
+    fs::write(&path, b"NNCB garbage").unwrap();
+    assert!(Network::load_checkpoint(&path).is_err());
+}
+
+#[test]
+fn test_checkpoint_with_mismatched_weight_shape_fails() {
+    let temp_dir = create_temp_dir();
+    let path = temp_dir.path().join("bad_shape.json");
+
+    let mut network = Network::new(vec![2, 3, 1], SIGMOID, 0.5);
+    network.weights[1] = neural_network::matrix::Matrix::zeros(1, 2);
+    network.save_checkpoint(&path, fixed_metadata()).unwrap();
+
+    let err = Network::load_checkpoint(&path).unwrap_err();
+    assert!(err.to_string().contains("Expected a 1x3 matrix, got 1x2"), "{}", err);
+    assert!(matches!(err.downcast_ref::<CheckpointError>(), Some(CheckpointError::ShapeMismatch(_))));
+}
+
+#[test]
+fn test_compressed_checkpoints_roundtrip() {
+    let temp_dir = create_temp_dir();
+    let network = Network::new(vec![16, 32, 4], SIGMOID, 0.5);
+
+    for (name, magic) in [
+        ("model.json.gz", &[0x1f, 0x8b][..]),
+        ("model.bin.gz", &[0x1f, 0x8b][..]),
+        ("model.json.zst", &[0x28, 0xb5, 0x2f, 0xfd][..]),
+        ("model.bin.zst", &[0x28, 0xb5, 0x2f, 0xfd][..]),
+    ] {
+        let path = temp_dir.path().join(name);
+        network.save_checkpoint(&path, fixed_metadata()).unwrap();
+        assert!(fs::read(&path).unwrap().starts_with(magic), "{}", name);
+
+        let (restored, metadata) = Network::load_checkpoint(&path).unwrap();
+        assert_eq!(metadata.epoch, 100, "{}", name);
+        assert_eq!(restored.weights, network.weights, "{}", name);
+    }
+
+    let json = temp_dir.path().join("model.json");
+    network.save_checkpoint(&json, fixed_metadata()).unwrap();
+    let json_size = fs::metadata(&json).unwrap().len();
+    let gz_size = fs::metadata(temp_dir.path().join("model.json.gz")).unwrap().len();
+    assert!(gz_size * 2 < json_size, "gzip {} vs json {}", gz_size, json_size);
+}
+
+#[test]
+fn test_compression_detected_from_contents() {
+    let temp_dir = create_temp_dir();
+    let network = Network::new(vec![2, 3, 1], SIGMOID, 0.5);
+
+    for name in ["model.bin.zst", "model.json.gz"] {
+        let path = temp_dir.path().join(name);
+        let renamed = temp_dir.path().join("model.checkpoint");
+        network.save_checkpoint(&path, fixed_metadata()).unwrap();
+        fs::rename(&path, &renamed).unwrap();
+
+        let (restored, _) = Network::load_checkpoint(&renamed).unwrap();
+        assert_eq!(restored.weights, network.weights, "{}", name);
+    }
+}
+
+#[test]
+fn test_truncated_compressed_checkpoint_fails() {
+    let temp_dir = create_temp_dir();
+    let network = Network::new(vec![2, 3, 1], SIGMOID, 0.5);
+
+    for name in ["model.json.gz", "model.bin.zst"] {
+        let path = temp_dir.path().join(name);
+        network.save_checkpoint(&path, fixed_metadata()).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+        assert!(Network::load_checkpoint(&path).is_err(), "{}", name);
+    }
+}
+
+#[test]
+fn test_saved_checkpoint_has_verified_checksum() {
+    let temp_dir = create_temp_dir();
+    let path = temp_dir.path().join("model.json");
+
+    let network = Network::new(vec![2, 3, 1], SIGMOID, 0.5);
+    network.save_checkpoint(&path, fixed_metadata()).unwrap();
+    let checkpoint = Checkpoint::load(&path).unwrap();
+    assert_eq!(checkpoint.checksum.as_ref().map(String::len), Some(64));
+
+    // Change one weight without updating the checksum
+    let mut json: serde_json::Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+    json["network"]["weights"][0]["data"][0] = serde_json::json!(42.0);
+    fs::write(&path, serde_json::to_vec(&json).unwrap()).unwrap();
+
+    let err = Network::load_checkpoint(&path).unwrap_err();
+    assert!(err.to_string().starts_with("Checkpoint corrupted"), "{}", err);
+    assert!(format!("{:#}", err).contains("checksum mismatch"), "{:#}", err);
+}
+
+#[test]
+fn test_truncated_checkpoint_reports_corruption() {
+    let temp_dir = create_temp_dir();
+    let network = Network::new(vec![2, 3, 1], SIGMOID, 0.5);
+
+    for name in ["model.json", "model.bin", "model.json.gz"] {
+        let path = temp_dir.path().join(name);
+        network.save_checkpoint(&path, fixed_metadata()).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+
+        let err = Network::load_checkpoint(&path).unwrap_err();
+        assert!(err.to_string().starts_with("Checkpoint corrupted"), "{}: {}", name, err);
+    }
+}
+
+#[test]
+fn test_checkpoint_errors_are_typed() {
+    let temp_dir = create_temp_dir();
+    let network = Network::new(vec![2, 3, 1], SIGMOID, 0.5);
+    let path = temp_dir.path().join("model.json");
+
+    // Truncated
+    network.save_checkpoint(&path, fixed_metadata()).unwrap();
+    let bytes = fs::read(&path).unwrap();
+    fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+    let err = Checkpoint::load(&path).unwrap_err();
+    assert_eq!(err.downcast_ref::<CheckpointError>(), Some(&CheckpointError::Corrupted(path.clone())));
+
+    // Valid JSON, but not a checkpoint
+    fs::write(&path, r#"{"notes": true}"#).unwrap();
+    let err = Checkpoint::load(&path).unwrap_err();
+    assert_eq!(err.downcast_ref::<CheckpointError>(), Some(&CheckpointError::Invalid));
+
+    // Another format version
+    let metadata = CheckpointMetadata { version: "9.9".to_string(), ..fixed_metadata() };
+    network.save_checkpoint(&path, metadata).unwrap();
+    let err = Network::load_checkpoint(&path).unwrap_err();
+    assert!(matches!(err.downcast_ref::<CheckpointError>(), Some(CheckpointError::UnsupportedVersion { found, .. }) if found == "9.9"));
+
+    // A missing file is an I/O error, not a checkpoint error
+    let err = Checkpoint::load(&temp_dir.path().join("missing.json")).unwrap_err();
+    assert!(err.downcast_ref::<CheckpointError>().is_none());
+}
+
+#[test]
+fn test_json_checkpoint_without_training_details_loads() {
+    let temp_dir = create_temp_dir();
+    let path = temp_dir.path().join("old.json");
+    let network = Network::new(vec![2, 3, 1], SIGMOID, 0.5);
+    network.save_checkpoint(&path, fixed_metadata()).unwrap();
+
+    // Checkpoints written before the optional fields lack them entirely
+    let mut json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    let metadata = json["metadata"].as_object_mut().unwrap();
+    metadata.retain(|key, _| ["version", "example", "epoch", "total_epochs", "learning_rate", "timestamp"].contains(&key.as_str()));
+    fs::write(&path, serde_json::to_string(&json).unwrap()).unwrap();
+
+    let checkpoint = Checkpoint::load(&path).unwrap();
+    assert_eq!(checkpoint.metadata.epoch, 100);
+    assert!(checkpoint.metadata.crate_version.is_none());
+    assert!(checkpoint.metadata.details().is_empty());
+}
@@ -0,0 +1,148 @@
+// Integration tests for checkpoint comparison
+use neural_network::activations::SIGMOID;
+use neural_network::checkpoint::CheckpointMetadata;
+use neural_network::compare::{compare, CheckpointDiff, Evaluation};
+use neural_network::examples::get_example;
+use neural_network::inference::InferenceModel;
+use neural_network::network::Network;
+use neural_network::training::{TrainingConfig, TrainingController};
+use tempfile::TempDir;
+
+fn metadata(epoch: u32) -> CheckpointMetadata {
+    CheckpointMetadata {
+        version: "1.0".to_string(),
+        example: "xor".to_string(),
+        epoch,
+        total_epochs: 100,
+        learning_rate: 0.5,
+        timestamp: "2025-10-13T12:00:00Z".to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_diff_reports_per_layer_changes() {
+    let before = Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 1);
+    let mut after = before.clone();
+    after.weights[1].data[2] += 0.3;
+    after.weights[1].data[0] -= 0.4;
+    after.biases[0].data[1] += 0.1;
+
+    let diff = CheckpointDiff::between(&before.to_checkpoint(metadata(10)), &after.to_checkpoint(metadata(20))).unwrap();
+
+    assert_eq!(diff.layers, vec![2, 3, 1]);
+    let (first, second) = (&diff.layer_diffs[0], &diff.layer_diffs[1]);
+    assert_eq!(first.weight_l2, 0.0);
+    assert!((first.bias_l2 - 0.1).abs() < 1e-12 && (first.bias_max_abs - 0.1).abs() < 1e-12);
+    assert!((second.weight_l2 - 0.5).abs() < 1e-12, "{}", second.weight_l2);
+    assert!((second.weight_max_abs - 0.4).abs() < 1e-12);
+    assert!(second.bias_l2 == 0.0 && !second.is_unchanged());
+    assert!((diff.total_l2() - (0.25_f64 + 0.01).sqrt()).abs() < 1e-12);
+    assert!(!diff.is_unchanged());
+
+    assert_eq!(diff.metadata.len(), 1);
+    let change = &diff.metadata[0];
+    assert_eq!((change.field.as_str(), change.before.as_str(), change.after.as_str()), ("epoch", "10", "20"));
+}
+
+#[test]
+fn test_identical_checkpoints_have_no_differences() {
+    let network = Network::new_seeded(vec![2, 2, 1], SIGMOID, 0.5, 4);
+    let checkpoint = network.to_checkpoint(metadata(5));
+    let diff = CheckpointDiff::between(&checkpoint, &checkpoint).unwrap();
+
+    assert!(diff.is_unchanged());
+    assert!(diff.metadata.is_empty());
+    assert_eq!(diff.total_l2(), 0.0);
+}
+
+#[test]
+fn test_compare_resumed_checkpoint_files() {
+    let dir = TempDir::new().unwrap();
+    let (first, resumed) = (dir.path().join("first.json"), dir.path().join("resumed.bin"));
+    let xor = get_example("xor").unwrap();
+    let config = |epochs| TrainingConfig {
+        epochs,
+        example_name: Some("xor".to_string()),
+        ..Default::default()
+    };
+
+    let mut controller = TrainingController::new(Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 9), config(20));
+    controller.train_dataset(&xor).unwrap();
+    controller.save_checkpoint(&first).unwrap();
+    let mut controller = TrainingController::from_checkpoint(&first, config(30)).unwrap();
+    controller.train_dataset(&xor).unwrap();
+    controller.save_checkpoint(&resumed).unwrap();
+
+    let diff = compare(&first, &resumed).unwrap();
+    assert!(diff.layer_diffs.iter().all(|d| !d.is_unchanged()), "Resuming changes every layer");
+    let epoch = diff.metadata.iter().find(|c| c.field == "epoch").unwrap();
+    assert_eq!((epoch.before.as_str(), epoch.after.as_str()), ("20", "50"));
+
+    let other = dir.path().join("other.json");
+    Network::new_seeded(vec![2, 4, 1], SIGMOID, 0.5, 9)
+        .save_checkpoint(&other, metadata(0))
+        .unwrap();
+    let error = compare(&first, &other).unwrap_err().to_string();
+    assert!(error.contains("Incompatible architectures: [2, 3, 1] vs [2, 4, 1]"), "{}", error);
+    assert!(compare(&first, &dir.path().join("missing.json")).is_err());
+}
+
+#[test]
+fn test_evaluation_matches_the_model_loss() {
+    let xor = get_example("xor").unwrap();
+    let network = Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 4);
+    let mut model = InferenceModel::from(network.to_checkpoint(metadata(0)));
+
+    let evaluation = Evaluation::of(&mut model, &xor).unwrap();
+    let expected = xor
+        .inputs
+        .iter()
+        .zip(&xor.targets)
+        .map(|(input, target)| {
+            let output = model.predict(input).unwrap();
+            (target[0] - output[0]).powi(2)
+        })
+        .sum::<f64>()
+        / 4.0;
+    assert_eq!(evaluation.samples, 4);
+    assert!((evaluation.loss - expected).abs() < 1e-12, "{} vs {}", evaluation.loss, expected);
+    assert!((0.0..=1.0).contains(&evaluation.metrics.accuracy));
+
+    let iris = get_example("iris").unwrap();
+    let error = Evaluation::of(&mut model, &iris).unwrap_err().to_string();
+    assert!(error.contains("does not fit a network with 2 inputs and 1 outputs"), "{}", error);
+}
+
+#[test]
+fn test_confusion_matrix_counts_classes() {
+    use neural_network::metrics::ConfusionMatrix;
+
+    let mut confusion = ConfusionMatrix::for_outputs(3);
+    confusion.observe(&[0.9, 0.1, 0.0], &[1.0, 0.0, 0.0], 0.5);
+    confusion.observe(&[0.2, 0.7, 0.1], &[1.0, 0.0, 0.0], 0.5);
+    confusion.observe(&[0.1, 0.8, 0.1], &[0.0, 1.0, 0.0], 0.5);
+    confusion.observe(&[0.1, 0.6, 0.3], &[0.0, 0.0, 1.0], 0.5);
+
+    assert_eq!(confusion.counts, vec![vec![1, 1, 0], vec![0, 1, 0], vec![0, 1, 0]]);
+    assert_eq!((confusion.total(), confusion.correct()), (4, 2));
+    assert_eq!(confusion.accuracy(), 0.5);
+    assert_eq!(confusion.support(0), 2);
+    assert_eq!(confusion.class_accuracy(0), Some(0.5));
+    assert_eq!(confusion.class_accuracy(2), Some(0.0));
+    assert_eq!(confusion.class_precision(1), Some(1.0 / 3.0));
+    assert_eq!(confusion.class_precision(2), None);
+
+    // A single output is two classes split at the threshold
+    let mut binary = ConfusionMatrix::for_outputs(1);
+    binary.observe(&[0.6], &[1.0], 0.7);
+    binary.observe(&[0.8], &[0.0], 0.7);
+    assert_eq!(binary.counts, vec![vec![0, 1], vec![1, 0]]);
+
+    let xor = get_example("xor").unwrap();
+    let network = Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 4);
+    let mut model = InferenceModel::from(network.to_checkpoint(metadata(0)));
+    let evaluation = Evaluation::at_threshold(&mut model, &xor, 0.5).unwrap();
+    assert_eq!(evaluation.confusion.total(), 4);
+    assert_eq!(evaluation.confusion.num_classes(), 2);
+}
@@ -0,0 +1,125 @@
+// Integration tests for the Dataset trait
+use neural_network::activations::SIGMOID;
+use neural_network::dataset::{Dataset, NoisyDataset, VecDataset};
+use neural_network::examples::get_example;
+use neural_network::network::Network;
+use neural_network::training::{TrainingConfig, TrainingController};
+
+#[test]
+fn test_vec_dataset_access() {
+    let data = VecDataset::new(
+        vec![vec![0.0, 1.0, 2.0], vec![3.0, 4.0, 5.0]],
+        vec![vec![1.0], vec![0.0]],
+    )
+    .unwrap();
+
+    assert_eq!(data.len(), 2);
+    assert!(!data.is_empty());
+    assert_eq!((data.input_dim(), data.target_dim()), (3, 1));
+    assert_eq!(data.get(1), (vec![3.0, 4.0, 5.0], vec![0.0]));
+    assert_eq!(data.to_vecs(), data.clone().into_vecs());
+}
+
+#[test]
+fn test_vec_dataset_rejects_ragged_data() {
+    let err = VecDataset::new(vec![vec![0.0]], vec![]).unwrap_err();
+    assert_eq!(err.to_string(), "1 inputs but 0 targets");
+
+    let err = VecDataset::new(vec![vec![0.0], vec![1.0, 2.0]], vec![vec![0.0], vec![1.0]]).unwrap_err();
+    assert_eq!(err.to_string(), "Sample 1 has 2 input values, sample 0 has 1");
+
+    let empty = VecDataset::new(vec![], vec![]).unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(empty.input_dim(), 0);
+}
+
+#[test]
+fn test_example_is_a_dataset() {
+    let iris = get_example("iris").unwrap();
+    let data: &dyn Dataset = &iris;
+
+    assert_eq!(data.len(), iris.inputs.len());
+    assert_eq!((data.input_dim(), data.target_dim()), (4, 3));
+    assert_eq!(data.get(0), (iris.inputs[0].clone(), iris.targets[0].clone()));
+}
+
+#[test]
+fn test_train_dataset_matches_train() {
+    let xor = get_example("xor").unwrap();
+    let controller = || {
+        let config = TrainingConfig {
+            epochs: 50,
+            ..Default::default()
+        };
+        TrainingController::new(Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 9), config)
+    };
+
+    let mut from_vecs = controller();
+    let expected = from_vecs.train(xor.inputs.clone(), xor.targets.clone()).unwrap();
+    let mut from_dataset = controller();
+    let history = from_dataset.train_dataset(&xor).unwrap();
+
+    assert_eq!(history.last().unwrap().loss, expected.last().unwrap().loss);
+}
+
+#[test]
+fn test_noisy_dataset_adds_fresh_noise_per_epoch() {
+    let clean = VecDataset::new(vec![vec![0.0; 200]; 3], vec![vec![1.0]; 3]).unwrap();
+    let mut noisy = NoisyDataset::new(clean.clone(), 0.5, 7).unwrap();
+    assert_eq!((noisy.len(), noisy.input_dim(), noisy.target_dim()), (3, 200, 1));
+
+    let (input, target) = noisy.get(1);
+    assert_eq!(target, vec![1.0]);
+    assert_eq!(noisy.get(1).0, input, "Same epoch, same noise");
+    assert_ne!(noisy.get(2).0, input, "Every sample gets its own noise");
+
+    let mean = input.iter().sum::<f64>() / 200.0;
+    let std_dev = (input.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / 200.0).sqrt();
+    assert!(mean.abs() < 0.15 && (std_dev - 0.5).abs() < 0.1, "mean {}, std dev {}", mean, std_dev);
+
+    noisy.set_epoch(1);
+    assert_eq!(noisy.epoch(), 1);
+    assert_ne!(noisy.get(1).0, input, "New epoch, new noise");
+    assert_eq!(noisy.into_inner(), clean);
+
+    assert!(NoisyDataset::new(VecDataset::default(), -0.1, 0).is_err());
+}
+
+#[test]
+fn test_describe_reports_column_stats_and_classes() {
+    let data = VecDataset::new(
+        vec![vec![1.0, 5.0], vec![3.0, 5.0], vec![f64::NAN, 5.0], vec![2.0, 5.0]],
+        vec![vec![0.0, 1.0], vec![1.0, 0.0], vec![0.0, 1.0], vec![0.0, 1.0]],
+    )
+    .unwrap();
+    let summary = data.describe();
+
+    assert_eq!(summary.samples, 4);
+    let first = summary.inputs[0];
+    assert_eq!((first.mean, first.min, first.max, first.non_finite), (2.0, 1.0, 3.0, 1));
+    assert!((first.std_dev - (2.0f64 / 3.0).sqrt()).abs() < 1e-12);
+    assert_eq!((summary.inputs[1].std_dev, summary.inputs[1].non_finite), (0.0, 0));
+    assert_eq!(summary.targets[1].mean, 0.75);
+    assert_eq!(summary.class_counts, vec![1, 3]);
+
+    let iris = get_example("iris").unwrap().describe();
+    assert_eq!((iris.inputs.len(), iris.class_counts), (4, vec![20, 20, 20]));
+    let empty = VecDataset::default().describe();
+    assert_eq!((empty.samples, empty.class_counts.len()), (0, 0));
+}
+
+#[test]
+fn test_fingerprint_identifies_the_data() {
+    let xor = get_example("xor").unwrap();
+    let fingerprint = neural_network::dataset::fingerprint(&xor.inputs, &xor.targets);
+
+    assert_eq!(fingerprint.len(), 64);
+    assert_eq!(xor.fingerprint(), fingerprint, "Trait and slices agree");
+    let data = VecDataset::new(xor.inputs.clone(), xor.targets.clone()).unwrap();
+    assert_eq!(data.fingerprint(), fingerprint);
+
+    let mut targets = xor.targets.clone();
+    targets[0][0] = 1.0 - targets[0][0];
+    assert_ne!(neural_network::dataset::fingerprint(&xor.inputs, &targets), fingerprint);
+    assert_ne!(neural_network::dataset::fingerprint(&xor.inputs[1..], &xor.targets[1..]), fingerprint);
+}
@@ -0,0 +1,76 @@
+// Integration tests for deterministic training mode
+use neural_network::activations::SIGMOID;
+use neural_network::examples;
+use neural_network::history::TrainingHistory;
+use neural_network::network::Network;
+use neural_network::training::{TrainingConfig, TrainingController, Validation};
+
+/// Train iris with every source of randomness in play (shuffling, a
+/// validation split, best-model restore) and return the bits of the result
+fn run(seed: Option<u64>) -> (Vec<u64>, TrainingHistory) {
+    let example = examples::get_example("iris").unwrap();
+    let network = Network::new_seeded(example.recommended_arch.clone(), SIGMOID, 0.3, 7);
+    let config = TrainingConfig {
+        epochs: 40,
+        deterministic: true,
+        seed,
+        shuffle: true,
+        validation: Validation::Split(0.2),
+        restore_best: true,
+        classification_threshold: Some(0.5),
+        ..Default::default()
+    };
+
+    let mut controller = TrainingController::new(network, config);
+    let history = controller.train(example.inputs.clone(), example.targets.clone()).unwrap();
+
+    let network = controller.into_network();
+    let bits = network
+        .weights
+        .iter()
+        .chain(&network.biases)
+        .flat_map(|m| m.data.iter().map(|v| v.to_bits()))
+        .collect();
+    (bits, history)
+}
+
+fn history_bits(history: &TrainingHistory) -> Vec<u64> {
+    history
+        .epochs
+        .iter()
+        .flat_map(|r| {
+            [Some(r.loss), r.validation_loss, Some(r.gradient_norm), r.metrics.map(|m| m.f1)]
+                .into_iter()
+                .flatten()
+                .chain(r.output_losses.iter().copied())
+                .chain(r.weight_norms.iter().copied())
+                .map(f64::to_bits)
+        })
+        .collect()
+}
+
+#[test]
+fn test_deterministic_runs_are_bit_identical() {
+    let (weights_a, history_a) = run(Some(1234));
+    let (weights_b, history_b) = run(Some(1234));
+
+    assert_eq!(weights_a, weights_b, "Final parameters must match bit for bit");
+    assert_eq!(history_bits(&history_a), history_bits(&history_b));
+}
+
+#[test]
+fn test_deterministic_mode_seeds_unseeded_runs() {
+    let (weights_a, history_a) = run(None);
+    let (weights_b, history_b) = run(None);
+
+    assert_eq!(weights_a, weights_b, "A fixed seed replaces the random one");
+    assert_eq!(history_bits(&history_a), history_bits(&history_b));
+}
+
+#[test]
+fn test_seed_changes_deterministic_result() {
+    let (weights_a, _) = run(Some(1));
+    let (weights_b, _) = run(Some(2));
+
+    assert_ne!(weights_a, weights_b, "Different seeds shuffle differently");
+}
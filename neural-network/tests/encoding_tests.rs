@@ -0,0 +1,60 @@
+// Integration tests for one-hot encoding helpers
+use neural_network::encoding::{ClassEncoder, argmax, decode_all, one_hot, one_hot_encode};
+use neural_network::examples::get_example;
+
+#[test]
+fn test_one_hot_encode_labels() {
+    assert_eq!(one_hot(2, 4), vec![0.0, 0.0, 1.0, 0.0]);
+
+    let targets = one_hot_encode(&[0, 2, 1], None).unwrap();
+    assert_eq!(targets, vec![vec![1.0, 0.0, 0.0], vec![0.0, 0.0, 1.0], vec![0.0, 1.0, 0.0]]);
+    assert_eq!(one_hot_encode(&[0], Some(3)).unwrap(), vec![vec![1.0, 0.0, 0.0]]);
+    assert!(one_hot_encode(&[], None).unwrap().is_empty());
+
+    let err = one_hot_encode(&[0, 3], Some(3)).unwrap_err();
+    assert_eq!(err.to_string(), "Sample 1 has label 3, expected fewer than 3 classes");
+}
+
+#[test]
+fn test_argmax_decodes_outputs() {
+    assert_eq!(argmax(&[0.2, 0.9, 0.4]), Some(1));
+    assert_eq!(argmax(&[0.5, 0.5]), Some(0));
+    assert_eq!(argmax(&[]), None);
+    assert_eq!(decode_all(&[vec![0.1, 0.8], vec![0.6, 0.3]]), vec![Some(1), Some(0)]);
+
+    // Decoding the iris targets recovers 20 samples of each class
+    let iris = get_example("iris").unwrap();
+    let classes = decode_all(&iris.targets);
+    for class in 0..3 {
+        assert_eq!(classes.iter().filter(|&&c| c == Some(class)).count(), 20);
+    }
+}
+
+#[test]
+fn test_class_encoder_round_trip() {
+    let labels = ["virginica", "setosa", "versicolor", "setosa"];
+    let encoder = ClassEncoder::fit(&labels);
+
+    assert_eq!(encoder.classes(), 3);
+    assert_eq!(encoder.index("versicolor"), Some(1));
+    let targets = encoder.encode_all(&labels).unwrap();
+    let decoded: Vec<&str> = targets.iter().map(|t| encoder.decode(t).unwrap()).collect();
+    assert_eq!(decoded, labels);
+
+    assert_eq!(encoder.decode(&[]), None);
+    assert_eq!(encoder.decode(&[0.0; 4]), None);
+    let err = encoder.encode("rose").unwrap_err();
+    assert!(err.to_string().starts_with("Unknown class 'rose'"));
+}
+
+#[test]
+fn test_class_encoder_with_given_order() {
+    let encoder = ClassEncoder::new(vec!["no".to_string(), "yes".to_string()]).unwrap();
+    assert_eq!(encoder.encode("yes").unwrap(), vec![0.0, 1.0]);
+
+    let json = serde_json::to_string(&encoder).unwrap();
+    assert_eq!(serde_json::from_str::<ClassEncoder>(&json).unwrap(), encoder);
+
+    let err = ClassEncoder::new(vec!["a".to_string(), "b".to_string(), "a".to_string()]).unwrap_err();
+    assert_eq!(err.to_string(), "Class name 'a' appears more than once");
+}
@@ -0,0 +1,85 @@
+// Integration tests for encrypted checkpoints
+use neural_network::activations::SIGMOID;
+use neural_network::checkpoint::{Checkpoint, CheckpointMetadata};
+use neural_network::encryption::{self, Secret};
+use neural_network::inference::InferenceModel;
+use neural_network::network::Network;
+use neural_network::training::{TrainingConfig, TrainingController};
+use std::fs;
+use tempfile::TempDir;
+
+fn metadata() -> CheckpointMetadata {
+    CheckpointMetadata {
+        version: "1.0".to_string(),
+        example: "xor".to_string(),
+        epoch: 10,
+        total_epochs: 10,
+        learning_rate: 0.5,
+        timestamp: "2025-10-13T12:00:00Z".to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_encrypted_checkpoint_needs_the_password() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("model.json.gz");
+    let network = Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 1);
+    let secret = Secret::Password("correct horse".to_string());
+    network.to_checkpoint(metadata()).save_with_secret(&path, Some(&secret)).unwrap();
+
+    let contents = fs::read(&path).unwrap();
+    assert!(encryption::is_encrypted(&contents));
+    assert!(!String::from_utf8_lossy(&contents).contains("weights"), "Nothing is stored in the clear");
+
+    let err = Checkpoint::load(&path).unwrap_err();
+    assert!(format!("{:#}", err).contains("encrypted; a password or key file is needed"), "{:#}", err);
+    let wrong = Secret::Password("battery staple".to_string());
+    let err = Checkpoint::load_with_secret(&path, Some(&wrong)).unwrap_err();
+    assert!(format!("{:#}", err).contains("wrong password or key"), "{:#}", err);
+
+    let loaded = Checkpoint::load_with_secret(&path, Some(&secret)).unwrap();
+    assert_eq!(loaded.network.weights, network.weights);
+}
+
+#[test]
+fn test_training_writes_and_resumes_encrypted_checkpoints() {
+    let temp_dir = TempDir::new().unwrap();
+    let key_path = temp_dir.path().join("model.key");
+    fs::write(&key_path, "00112233445566778899aabbccddeeff00112233445566778899aabbccddeeff\n").unwrap();
+    let secret = Secret::from_key_file(&key_path).unwrap();
+    let path = temp_dir.path().join("model.bin");
+    let (inputs, targets) = (vec![vec![0.0, 1.0], vec![1.0, 0.0]], vec![vec![1.0], vec![0.0]]);
+
+    let config = |epochs| TrainingConfig {
+        epochs,
+        checkpoint_interval: Some(5),
+        checkpoint_path: Some(path.clone()),
+        encryption: Some(secret.clone()),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new_seeded(vec![2, 2, 1], SIGMOID, 0.5, 2), config(10));
+    controller.train(inputs.clone(), targets.clone()).unwrap();
+    assert!(encryption::is_encrypted(&fs::read(&path).unwrap()), "The periodic checkpoint is encrypted");
+
+    let mut resumed = TrainingController::from_checkpoint(&path, config(5)).unwrap();
+    resumed.train(inputs, targets).unwrap();
+    let checkpoint = Checkpoint::load_with_secret(&path, Some(&secret)).unwrap();
+    assert_eq!(checkpoint.metadata.epoch, 15);
+    assert!(TrainingController::from_checkpoint(&path, TrainingConfig::default()).is_err());
+
+    let model = InferenceModel::from_bytes_with_secret(fs::read(&path).unwrap(), Some(&secret)).unwrap();
+    assert_eq!(model.network.weights, checkpoint.network.weights);
+}
+
+#[test]
+fn test_key_file_must_hold_a_256_bit_key() {
+    let temp_dir = TempDir::new().unwrap();
+    let key_path = temp_dir.path().join("model.key");
+
+    fs::write(&key_path, [9u8; 32]).unwrap();
+    assert_eq!(Secret::from_key_file(&key_path).unwrap(), Secret::Key([9; 32]));
+    fs::write(&key_path, "too short").unwrap();
+    let err = Secret::from_key_file(&key_path).unwrap_err();
+    assert!(err.to_string().contains("must hold 32 bytes"), "{}", err);
+}
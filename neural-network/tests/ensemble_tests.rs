@@ -0,0 +1,77 @@
+// Integration tests for ensemble prediction
+use neural_network::activations::SIGMOID;
+use neural_network::checkpoint::CheckpointMetadata;
+use neural_network::ensemble::{Combine, Ensemble};
+use neural_network::examples::get_example;
+use neural_network::matrix::Matrix;
+use neural_network::network::Network;
+use tempfile::TempDir;
+
+fn trained_xor(seed: u64) -> Network {
+    let ex = get_example("xor").unwrap();
+    let mut network = Network::new_seeded(ex.recommended_arch.clone(), SIGMOID, 0.5, seed);
+    network.train(ex.inputs.clone(), ex.targets.clone(), 3000);
+    network
+}
+
+#[test]
+fn test_ensemble_average_is_mean_of_members() {
+    let mut a = trained_xor(1);
+    let mut b = trained_xor(2);
+    let mut ensemble = Ensemble::new(vec![a.clone(), b.clone()], Combine::Average).unwrap();
+
+    let input = vec![1.0, 0.0];
+    let expected = (a.feed_forward(Matrix::from(input.clone())).data[0]
+        + b.feed_forward(Matrix::from(input.clone())).data[0])
+        / 2.0;
+    assert!((ensemble.predict(&input)[0] - expected).abs() < 1e-12);
+}
+
+#[test]
+fn test_ensemble_vote_solves_xor() {
+    let networks = (1..=3).map(trained_xor).collect();
+    let mut ensemble = Ensemble::new(networks, Combine::Vote).unwrap();
+    let ex = get_example("xor").unwrap();
+
+    for (input, target) in ex.inputs.iter().zip(&ex.targets) {
+        let share = ensemble.predict(input)[0];
+        assert_eq!(share > 0.5, target[0] == 1.0, "Majority should match target for {:?}", input);
+    }
+}
+
+#[test]
+fn test_ensemble_rejects_incompatible_networks() {
+    let a = Network::new(vec![2, 3, 1], SIGMOID, 0.5);
+    let b = Network::new(vec![2, 3, 2], SIGMOID, 0.5);
+
+    assert!(Ensemble::new(vec![a, b], Combine::Average).is_err());
+    assert!(Ensemble::new(vec![], Combine::Average).is_err());
+}
+
+#[test]
+fn test_ensemble_from_checkpoints() {
+    let temp_dir = TempDir::new().unwrap();
+    let paths: Vec<_> = (1..=2)
+        .map(|seed| {
+            let path = temp_dir.path().join(format!("member_{}.json", seed));
+            let metadata = CheckpointMetadata {
+                version: "1.0".to_string(),
+                example: "xor".to_string(),
+                epoch: 3000,
+                total_epochs: 3000,
+                learning_rate: 0.5,
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                ..Default::default()
+            };
+            trained_xor(seed).save_checkpoint(&path, metadata).unwrap();
+            path
+        })
+        .collect();
+
+    let ensemble = Ensemble::from_checkpoints(&paths, Combine::Average).unwrap();
+    assert_eq!(ensemble.len(), 2);
+
+    let missing = temp_dir.path().join("missing.json");
+    let err = Ensemble::from_checkpoints(&[missing], Combine::Average).unwrap_err();
+    assert!(err.to_string().contains("missing.json"));
+}
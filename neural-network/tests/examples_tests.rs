@@ -27,7 +27,7 @@ fn test_get_xor_example() {
 #[test]
 fn test_list_examples() {
     let examples = list_examples();
-    assert_eq!(examples.len(), 8);
+    assert_eq!(examples.len(), 12);
     assert!(examples.contains(&"and"));
     assert!(examples.contains(&"or"));
     assert!(examples.contains(&"xor"));
@@ -36,6 +36,10 @@ fn test_list_examples() {
     assert!(examples.contains(&"adder2"));
     assert!(examples.contains(&"iris"));
     assert!(examples.contains(&"pattern3x3"));
+    assert!(examples.contains(&"sine"));
+    assert!(examples.contains(&"spirals"));
+    assert!(examples.contains(&"moons"));
+    assert!(examples.contains(&"circles"));
 }
 
 #[test]
@@ -151,3 +155,182 @@ fn test_recommended_learning_rate() {
     assert!(ex.recommended_lr > 0.0);
     assert!(ex.recommended_lr <= 1.0);
 }
+
+#[test]
+fn test_sine_covers_one_period() {
+    use neural_network::examples::SINE_SAMPLES;
+
+    let ex = get_example("sine").unwrap();
+    assert_eq!(ex.inputs.len(), SINE_SAMPLES);
+    assert_eq!(ex.inputs[0], vec![-1.0]);
+    assert_eq!(ex.inputs[SINE_SAMPLES - 1], vec![1.0]);
+    assert!(ex.targets.iter().all(|t| (0.0..=1.0).contains(&t[0])));
+
+    // sin(-pi/2) and sin(pi/2) are the extremes of the scaled targets
+    let (min, max) = ex.targets.iter().fold((1.0f64, 0.0f64), |(lo, hi), t| (lo.min(t[0]), hi.max(t[0])));
+    assert!(min < 0.01 && max > 0.99);
+}
+
+#[test]
+fn test_sine_is_learnable() {
+    use neural_network::activations::SIGMOID;
+    use neural_network::network::Network;
+    use neural_network::training::{TrainingConfig, TrainingController};
+
+    let ex = get_example("sine").unwrap();
+    let config = TrainingConfig {
+        epochs: 2000,
+        ..Default::default()
+    };
+    let network = Network::new_seeded(ex.recommended_arch.clone(), SIGMOID, ex.recommended_lr, 42);
+    let mut controller = TrainingController::new(network, config);
+    let history = controller.train(ex.inputs, ex.targets).unwrap();
+
+    // Predicting the mean 0.5 everywhere scores 0.125
+    assert!(history.last().unwrap().loss < 0.02, "loss {}", history.last().unwrap().loss);
+}
+
+#[test]
+fn test_generated_2d_datasets() {
+    use neural_network::examples::{circles, moons, spirals};
+
+    for generate in [spirals, moons, circles] {
+        let a = generate(30, 0.1, 5);
+        assert_eq!(a.inputs.len(), 60);
+        assert!(a.inputs.iter().all(|x| x.len() == 2 && x.iter().all(|v| v.abs() < 1.5)));
+        assert_eq!(a.targets.iter().filter(|t| t[0] == 1.0).count(), 30, "{} is balanced", a.name);
+        assert_eq!(a.inputs, generate(30, 0.1, 5).inputs, "Same seed gives same {}", a.name);
+        assert_ne!(a.inputs, generate(30, 0.1, 6).inputs, "Different seeds give different {}", a.name);
+        assert_eq!(generate(30, 0.0, 5).inputs, generate(30, 0.0, 6).inputs, "Without noise the seed is unused");
+    }
+}
+
+#[test]
+fn test_circles_radii() {
+    use neural_network::examples::circles;
+
+    let data = circles(20, 0.0, 1);
+    for (input, target) in data.inputs.iter().zip(&data.targets) {
+        let radius = input[0].hypot(input[1]);
+        let expected = if target[0] == 0.0 { 1.0 } else { 0.5 };
+        assert!((radius - expected).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_make_blobs() {
+    use neural_network::examples::make_blobs;
+
+    let centers = [vec![5.0, 0.0, 0.0], vec![0.0, 5.0, 0.0], vec![0.0, 0.0, 5.0]];
+    let data = make_blobs(3001, &centers, 0.5, 3);
+    assert_eq!(data.inputs.len(), 3001);
+    assert_eq!(data.recommended_arch, vec![3, 6, 3]);
+    assert_eq!(data.inputs, make_blobs(3001, &centers, 0.5, 3).inputs, "Same seed gives same blobs");
+    assert_ne!(data.inputs, make_blobs(3001, &centers, 0.5, 4).inputs, "Different seeds give different blobs");
+
+    // The first class takes the remainder; every sample lies near its own center
+    let counts: Vec<usize> = (0..3).map(|c| data.targets.iter().filter(|t| argmax(t) == c).count()).collect();
+    assert_eq!(counts, vec![1001, 1000, 1000]);
+    for (input, target) in data.inputs.iter().zip(&data.targets) {
+        let center = &centers[argmax(target)];
+        let distance = input.iter().zip(center).map(|(x, c)| (x - c).powi(2)).sum::<f64>().sqrt();
+        assert!(distance < 4.0, "{:?} is far from {:?}", input, center);
+    }
+
+    let tight = make_blobs(4, &[vec![1.0, 2.0]], 0.0, 9);
+    assert!(tight.inputs.iter().all(|x| *x == vec![1.0, 2.0]));
+}
+
+fn argmax(values: &[f64]) -> usize {
+    values
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map(|(i, _)| i)
+        .unwrap()
+}
+
+#[test]
+fn test_synthetic_shape_and_determinism() {
+    use neural_network::examples::{SYNTHETIC_SAMPLES_PER_CLASS, synthetic};
+
+    let a = synthetic(5, 3, 1.5, 7);
+    let b = synthetic(5, 3, 1.5, 7);
+    let c = synthetic(5, 3, 1.5, 8);
+
+    assert_eq!(a.example.inputs.len(), 3 * SYNTHETIC_SAMPLES_PER_CLASS);
+    assert_eq!(a.example.recommended_arch, vec![5, 8, 3]);
+    assert!(a.example.inputs.iter().all(|x| x.len() == 5));
+    assert!(a.example.targets.iter().all(|t| t.iter().sum::<f64>() == 1.0));
+    assert_eq!(a.example.inputs, b.example.inputs, "Same seed gives same data");
+    assert_ne!(a.example.inputs, c.example.inputs, "Different seeds give different data");
+
+    let norm: f64 = a.direction.iter().map(|d| d * d).sum::<f64>().sqrt();
+    assert!((norm - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_synthetic_bayes_classifier_matches_ceiling() {
+    use neural_network::examples::synthetic;
+
+    for (classes, separability) in [(2, 2.0), (3, 1.0), (4, 3.0)] {
+        let mut correct = 0;
+        let mut total = 0;
+        let mut ceiling = 0.0;
+
+        // Pool several seeds so the empirical estimate is tight
+        for seed in 0..20 {
+            let data = synthetic(3, classes, separability, seed);
+            ceiling = data.bayes_accuracy;
+            for (input, target) in data.example.inputs.iter().zip(&data.example.targets) {
+                correct += (data.bayes_predict(input) == argmax(target)) as usize;
+                total += 1;
+            }
+        }
+
+        let accuracy = correct as f64 / total as f64;
+        assert!(
+            (accuracy - ceiling).abs() < 0.03,
+            "{} classes at separability {}: empirical {} vs ceiling {}",
+            classes, separability, accuracy, ceiling
+        );
+    }
+}
+
+#[test]
+fn test_synthetic_trained_network_approaches_ceiling() {
+    use neural_network::activations::SIGMOID;
+    use neural_network::examples::synthetic;
+    use neural_network::matrix::Matrix;
+    use neural_network::network::Network;
+    use neural_network::training::{TrainingConfig, TrainingController};
+
+    let data = synthetic(2, 2, 3.0, 1);
+    let network = Network::new_seeded(data.example.recommended_arch.clone(), SIGMOID, 0.1, 42);
+    let config = TrainingConfig {
+        epochs: 200,
+        ..Default::default()
+    };
+
+    let mut controller = TrainingController::new(network, config);
+    controller.train(data.example.inputs.clone(), data.example.targets.clone()).unwrap();
+    let mut network = controller.network().clone();
+
+    let correct = data
+        .example
+        .inputs
+        .iter()
+        .zip(&data.example.targets)
+        .filter(|(input, target)| {
+            let output = network.feed_forward(Matrix::from((*input).clone()));
+            argmax(&output.data) == argmax(target)
+        })
+        .count();
+    let accuracy = correct as f64 / data.example.inputs.len() as f64;
+
+    assert!(
+        accuracy > data.bayes_accuracy - 0.1,
+        "Network accuracy {} should approach the ceiling {}",
+        accuracy, data.bayes_accuracy
+    );
+}
@@ -0,0 +1,55 @@
+// Integration tests for the numerical gradient check
+use neural_network::activations::{ACTIVATIONS, Activation, GELU, SIGMOID};
+use neural_network::examples;
+use neural_network::gradcheck::GradCheck;
+use neural_network::network::Network;
+
+#[test]
+fn test_backpropagation_passes_for_every_activation() {
+    for activation in ACTIVATIONS {
+        let report = GradCheck::default().run_random(&[3, 5, 4, 2], activation, 10, 7);
+        assert_eq!(report.layers.len(), 3);
+        assert_eq!(report.samples, 10);
+        assert!(report.passed(), "{}: max relative error {}", activation.name, report.max_error());
+    }
+}
+
+#[test]
+fn test_gelu_passes_below_its_minimum() {
+    // Wide layers push many inputs below GELU's minimum at -0.75, where its
+    // output no longer determines the slope
+    let report = GradCheck::default().run_random(&[8, 24, 24, 3], GELU, 10, 11);
+    assert!(report.passed(), "max relative error {}", report.max_error());
+}
+
+#[test]
+fn test_wrong_derivative_is_detected() {
+    // Twice the sigmoid's true derivative
+    let broken = Activation {
+        name: "broken",
+        function: SIGMOID.function,
+        derivative: |_, y| 2.0 * y * (1.0 - y),
+    };
+    let report = GradCheck::default().run_random(&[2, 3, 1], broken, 10, 3);
+    assert!(!report.passed());
+    assert!(report.max_error() > 0.1, "{}", report.max_error());
+}
+
+#[test]
+fn test_check_on_example_leaves_network_unchanged() {
+    let xor = examples::get_example("xor").unwrap();
+    let network = Network::new_seeded(xor.recommended_arch.clone(), SIGMOID, 0.5, 42);
+    let checker = GradCheck {
+        epsilon: 1e-4,
+        tolerance: 1e-5,
+    };
+
+    let report = checker.run(&network, &xor.inputs, &xor.targets);
+    assert_eq!(report.samples, xor.inputs.len());
+    assert_eq!(report.epsilon, 1e-4);
+    assert!(report.passed(), "max relative error {}", report.max_error());
+
+    let fresh = Network::new_seeded(xor.recommended_arch.clone(), SIGMOID, 0.5, 42);
+    assert_eq!(network.weights, fresh.weights);
+    assert_eq!(network.biases, fresh.biases);
+}
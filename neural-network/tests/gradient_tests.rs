@@ -0,0 +1,54 @@
+// Integration tests for gradient computation
+use neural_network::activations::SIGMOID;
+use neural_network::matrix::Matrix;
+use neural_network::network::Network;
+
+/// Squared-error loss matching the convention used by `compute_gradients`
+fn loss(network: &mut Network, input: &[f64], target: &[f64]) -> f64 {
+    let output = network.feed_forward(Matrix::from(input.to_vec()));
+    output
+        .data
+        .iter()
+        .zip(target)
+        .map(|(o, t)| 0.5 * (t - o) * (t - o))
+        .sum()
+}
+
+#[test]
+fn test_gradients_match_finite_differences() {
+    let mut network = Network::new_seeded(vec![2, 3, 2], SIGMOID, 0.5, 7);
+    let input = [0.3, -0.8];
+    let target = [1.0, 0.0];
+
+    let output = network.feed_forward(Matrix::from(input.to_vec()));
+    let gradients = network.compute_gradients(&output, &Matrix::from(target.to_vec()));
+
+    let epsilon = 1e-6;
+    for layer in 0..network.weights.len() {
+        for k in 0..network.weights[layer].data.len() {
+            let original = network.weights[layer].data[k];
+            network.weights[layer].data[k] = original + epsilon;
+            let plus = loss(&mut network, &input, &target);
+            network.weights[layer].data[k] = original - epsilon;
+            let minus = loss(&mut network, &input, &target);
+            network.weights[layer].data[k] = original;
+
+            let numeric = (plus - minus) / (2.0 * epsilon);
+            let analytic = gradients.weights[layer].data[k];
+            assert!(
+                (numeric - analytic).abs() < 1e-6,
+                "Weight gradient mismatch at layer {} index {}: {} vs {}",
+                layer, k, analytic, numeric
+            );
+        }
+    }
+}
+
+#[test]
+fn test_gradient_norm_is_zero_at_target() {
+    let mut network = Network::new_seeded(vec![2, 2, 1], SIGMOID, 0.5, 1);
+    let output = network.feed_forward(Matrix::from(vec![1.0, 0.0]));
+    let gradients = network.compute_gradients(&output, &output.clone());
+
+    assert_eq!(gradients.norm(), 0.0);
+}
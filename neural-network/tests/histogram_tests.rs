@@ -0,0 +1,31 @@
+// Integration tests for weight histograms
+use neural_network::activations::SIGMOID;
+use neural_network::network::Network;
+
+#[test]
+fn test_weight_histograms_cover_every_parameter() {
+    let network = Network::new_seeded(vec![4, 6, 3], SIGMOID, 0.5, 7);
+    let histograms = network.weight_histograms(8);
+
+    assert_eq!(histograms.len(), 2, "One histogram pair per weight matrix");
+    for (hist, (w, b)) in histograms.iter().zip(network.weights.iter().zip(&network.biases)) {
+        assert_eq!(hist.weights.counts.len(), 8);
+        assert_eq!(hist.weights.total(), w.data.len());
+        assert_eq!(hist.biases.total(), b.data.len());
+
+        let min = w.data.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = w.data.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        assert_eq!((hist.weights.min, hist.weights.max), (min, max));
+    }
+    assert_eq!(histograms[1].layer, 1);
+}
+
+#[test]
+fn test_weight_histograms_serialize() {
+    let network = Network::new(vec![2, 2, 1], SIGMOID, 0.5);
+    let json = serde_json::to_value(network.weight_histograms(4)).unwrap();
+
+    assert_eq!(json[0]["layer"], 0);
+    assert_eq!(json[0]["weights"]["counts"].as_array().unwrap().len(), 4);
+    assert!(json[1]["biases"]["min"].is_number());
+}
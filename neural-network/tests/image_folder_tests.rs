@@ -0,0 +1,72 @@
+// Tests for the image-folder loader (run with `--features images`)
+#![cfg(feature = "images")]
+
+use image::{GrayImage, Luma, Rgb, RgbImage};
+use neural_network::dataset::Dataset;
+use neural_network::image_folder::ImageFolder;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// A `size` x `size` image of one gray level
+fn solid(path: &Path, size: u32, level: u8) {
+    GrayImage::from_pixel(size, size, Luma([level])).save(path).unwrap();
+}
+
+#[test]
+fn test_image_folder_labels_by_folder_in_sorted_order() {
+    let dir = TempDir::new().unwrap();
+    for class in ["light", "dark"] {
+        fs::create_dir(dir.path().join(class)).unwrap();
+    }
+    solid(&dir.path().join("light/b.png"), 4, 255);
+    solid(&dir.path().join("light/a.png"), 8, 255);
+    solid(&dir.path().join("dark/x.PNG"), 4, 0);
+    fs::write(dir.path().join("dark/notes.txt"), "not an image").unwrap();
+    fs::write(dir.path().join("README"), "ignored").unwrap();
+
+    let data = ImageFolder::load(dir.path(), 2, 3).unwrap();
+
+    assert_eq!(data.classes().names(), ["dark", "light"]);
+    assert_eq!(data.labels(), [0, 1, 1]);
+    let files: Vec<_> = data.paths().iter().map(|p| p.file_name().unwrap().to_str().unwrap()).collect();
+    assert_eq!(files, ["x.PNG", "a.png", "b.png"]);
+    assert_eq!((data.len(), data.input_dim(), data.target_dim()), (3, 6, 2));
+    assert_eq!(data.get(0), (vec![0.0; 6], vec![1.0, 0.0]));
+    assert_eq!(data.get(1), (vec![1.0; 6], vec![0.0, 1.0]), "Resized to 2x3 regardless of source size");
+
+    let tensor = data.to_tensor();
+    assert_eq!((tensor.batch, tensor.rows, tensor.cols), (3, 3, 2));
+    assert_eq!(tensor.matrix(2).data, vec![1.0; 6]);
+}
+
+#[test]
+fn test_image_folder_converts_color_to_grayscale() {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir(dir.path().join("red")).unwrap();
+    let mut image = RgbImage::from_pixel(2, 1, Rgb([255, 0, 0]));
+    image.put_pixel(1, 0, Rgb([255, 255, 255]));
+    image.save(dir.path().join("red/pixel.png")).unwrap();
+
+    let data = ImageFolder::load(dir.path(), 2, 1).unwrap();
+    let (input, _) = data.get(0);
+    assert!(input[0] > 0.1 && input[0] < 0.5, "Red is darker than white: {:?}", input);
+    assert_eq!(input[1], 1.0);
+}
+
+#[test]
+fn test_image_folder_rejects_bad_layouts() {
+    let dir = TempDir::new().unwrap();
+    let error = ImageFolder::load(dir.path(), 4, 4).unwrap_err().to_string();
+    assert!(error.contains("No class folders"), "{}", error);
+
+    fs::create_dir(dir.path().join("empty")).unwrap();
+    let error = ImageFolder::load(dir.path(), 4, 4).unwrap_err().to_string();
+    assert!(error.contains("has no PNG or JPEG images"), "{}", error);
+
+    fs::write(dir.path().join("empty/broken.jpg"), "not a jpeg").unwrap();
+    let error = ImageFolder::load(dir.path(), 4, 4).unwrap_err().to_string();
+    assert!(error.contains("Failed to load image"), "{}", error);
+
+    assert!(ImageFolder::load(dir.path(), 0, 4).is_err());
+}
@@ -0,0 +1,84 @@
+// Integration tests for stripped checkpoints and inference-only loading
+use neural_network::activations::SIGMOID;
+use neural_network::checkpoint::Checkpoint;
+use neural_network::inference::InferenceModel;
+use neural_network::network::Network;
+use neural_network::normalize::Normalization;
+use neural_network::training::{TrainingConfig, TrainingController};
+use std::fs;
+use tempfile::TempDir;
+
+/// A checkpoint of a short run on inputs scaled by 10, with every training field set
+fn trained_checkpoint(dir: &TempDir) -> Checkpoint {
+    let path = dir.path().join("full.json");
+    let inputs = vec![vec![0.0, 0.0], vec![0.0, 10.0], vec![10.0, 0.0], vec![10.0, 10.0]];
+    let targets = vec![vec![0.0], vec![1.0], vec![1.0], vec![0.0]];
+    let config = TrainingConfig {
+        epochs: 20,
+        seed: Some(1),
+        shuffle: true,
+        normalization: Some(Normalization::MinMax),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 1), config);
+    controller.train(inputs, targets).unwrap();
+    controller.save_checkpoint(&path).unwrap();
+    Checkpoint::load(&path).unwrap()
+}
+
+#[test]
+fn test_stripped_checkpoint_keeps_only_inference_state() {
+    let temp_dir = TempDir::new().unwrap();
+    let full = trained_checkpoint(&temp_dir);
+    assert!(full.training_state.is_some());
+
+    let stripped = full.stripped();
+    assert!(stripped.history.is_empty());
+    assert!(stripped.training_state.is_none());
+    assert!(stripped.lineage.is_empty());
+    assert_eq!(stripped.network.weights, full.network.weights);
+    assert_eq!(stripped.normalizer, full.normalizer);
+    assert_eq!(stripped.metadata.epoch, full.metadata.epoch);
+
+    let (full_path, stripped_path) = (temp_dir.path().join("full.bin"), temp_dir.path().join("stripped.bin"));
+    full.save(&full_path).unwrap();
+    stripped.save(&stripped_path).unwrap();
+    assert!(fs::metadata(&stripped_path).unwrap().len() < fs::metadata(&full_path).unwrap().len());
+    assert!(Network::load_checkpoint(&stripped_path).is_ok(), "Stripped checkpoints are still checkpoints");
+}
+
+#[test]
+fn test_inference_model_loads_full_and_stripped_checkpoints() {
+    let temp_dir = TempDir::new().unwrap();
+    let full = trained_checkpoint(&temp_dir);
+    let mut expected = InferenceModel::from(full.clone());
+    let prediction = expected.predict(&[10.0, 0.0]).unwrap();
+
+    let paths = ["full.json", "stripped.json", "stripped.bin", "stripped.json.gz", "stripped.bin.zst"];
+    for (i, name) in paths.into_iter().enumerate() {
+        let path = temp_dir.path().join(name);
+        let checkpoint = if i == 0 { full.clone() } else { full.stripped() };
+        checkpoint.save(&path).unwrap();
+
+        let mut model = InferenceModel::load(&path).unwrap();
+        assert_eq!(model.network.weights, full.network.weights, "{}", name);
+        assert_eq!(model.normalizer, full.normalizer, "{}", name);
+        assert_eq!(model.metadata.epoch, 20, "{}", name);
+        assert_eq!(model.predict(&[10.0, 0.0]).unwrap(), prediction, "{}", name);
+    }
+}
+
+#[test]
+fn test_inference_model_rejects_corrupted_checkpoint() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("stripped.json");
+    trained_checkpoint(&temp_dir).stripped().save(&path).unwrap();
+
+    let mut json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    json["network"]["learning_rate"] = serde_json::json!(0.25);
+    let err = InferenceModel::from_bytes(serde_json::to_vec(&json).unwrap()).unwrap_err();
+    assert!(format!("{:#}", err).contains("Checkpoint corrupted"), "{:#}", err);
+
+    let err = InferenceModel::from_bytes(b"{\"metadata\":".to_vec()).unwrap_err();
+    assert!(format!("{:#}", err).contains("Checkpoint corrupted"), "{:#}", err);
+}
@@ -0,0 +1,67 @@
+// Integration tests for the built-in logging callbacks
+use neural_network::activations::SIGMOID;
+use neural_network::loggers::{CSV_HEADER, CsvLogger};
+use neural_network::network::Network;
+use neural_network::training::{TrainingConfig, TrainingController};
+use std::fs;
+use tempfile::TempDir;
+
+fn train_with_csv_log(path: &std::path::Path, epochs: u32) {
+    let config = TrainingConfig {
+        epochs,
+        classification_threshold: Some(0.5),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![2, 3, 1], SIGMOID, 0.25), config);
+    controller.add_callback(CsvLogger::create(path).unwrap());
+    controller
+        .train(
+            vec![vec![0.0, 0.0], vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]],
+            vec![vec![0.0], vec![1.0], vec![1.0], vec![0.0]],
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_csv_logger_writes_header_and_rows() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("log.csv");
+
+    train_with_csv_log(&path, 5);
+
+    let contents = fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines[0], CSV_HEADER);
+    assert_eq!(lines.len(), 6);
+
+    let columns = CSV_HEADER.split(',').count();
+    for (i, line) in lines[1..].iter().enumerate() {
+        let fields: Vec<&str> = line.split(',').collect();
+        assert_eq!(fields.len(), columns);
+        assert_eq!(fields[0], (i + 1).to_string());
+        assert!(fields[1].parse::<f64>().unwrap().is_finite());
+        assert!(fields[2].is_empty(), "No validation set configured");
+        assert!(fields[3].parse::<f64>().is_ok(), "Accuracy is logged");
+        assert_eq!(fields[7], "0.25");
+    }
+}
+
+#[test]
+fn test_csv_logger_appends_to_existing_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("log.csv");
+
+    train_with_csv_log(&path, 3);
+    train_with_csv_log(&path, 2);
+
+    let contents = fs::read_to_string(&path).unwrap();
+    assert_eq!(contents.lines().count(), 6, "One header and five rows");
+    assert_eq!(contents.matches("epoch,").count(), 1, "Header is written once");
+}
+
+#[test]
+fn test_csv_logger_reports_unopenable_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("missing").join("log.csv");
+    assert!(CsvLogger::create(&path).is_err());
+}
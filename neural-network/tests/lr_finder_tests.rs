@@ -0,0 +1,54 @@
+// Integration tests for the learning-rate range test
+use neural_network::activations::SIGMOID;
+use neural_network::examples;
+use neural_network::lr_finder::LrFinder;
+use neural_network::network::Network;
+
+#[test]
+fn test_range_test_records_increasing_rates() {
+    let xor = examples::get_example("xor").unwrap();
+    let network = Network::new_seeded(xor.recommended_arch.clone(), SIGMOID, 0.5, 42);
+    let finder = LrFinder {
+        min_lr: 0.001,
+        max_lr: 10.0,
+        steps: 50,
+        ..Default::default()
+    };
+
+    let result = finder.run(&network, &xor.inputs, &xor.targets);
+
+    assert!(!result.points.is_empty());
+    assert!(result.points.len() <= 50);
+    assert!((result.points[0].learning_rate - 0.001).abs() < 1e-12);
+    assert!(result.points.windows(2).all(|w| w[0].learning_rate < w[1].learning_rate));
+    assert_eq!(network.learning_rate, 0.5, "The network itself is not trained");
+}
+
+#[test]
+fn test_suggestion_lies_within_range() {
+    let and = examples::get_example("and").unwrap();
+    let network = Network::new_seeded(and.recommended_arch.clone(), SIGMOID, 0.5, 7);
+
+    let result = LrFinder::default().run(&network, &and.inputs, &and.targets);
+    let suggestion = result.suggestion().expect("The loss should fall somewhere in the range");
+
+    assert!((1e-4..=100.0).contains(&suggestion));
+    assert!(result.min_loss_rate().unwrap() >= suggestion);
+}
+
+#[test]
+fn test_huge_rates_stop_on_divergence() {
+    let xor = examples::get_example("xor").unwrap();
+    let network = Network::new_seeded(xor.recommended_arch.clone(), SIGMOID, 0.5, 1);
+    let finder = LrFinder {
+        min_lr: 1.0,
+        max_lr: 1e6,
+        steps: 200,
+        divergence_factor: 1.05,
+    };
+
+    let result = finder.run(&network, &xor.inputs, &xor.targets);
+
+    assert!(result.diverged);
+    assert!(result.points.len() < 200);
+}
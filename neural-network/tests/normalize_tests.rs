@@ -0,0 +1,138 @@
+// Integration tests for input normalization
+use neural_network::activations::SIGMOID;
+use neural_network::checkpoint::Checkpoint;
+use neural_network::examples::get_example;
+use neural_network::network::Network;
+use neural_network::normalize::{Normalization, Normalizer};
+use neural_network::training::{TrainingConfig, TrainingController};
+use tempfile::TempDir;
+
+fn assert_close(actual: &[f64], expected: &[f64]) {
+    assert_eq!(actual.len(), expected.len());
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-12, "{:?} != {:?}", actual, expected);
+    }
+}
+
+#[test]
+fn test_minmax_maps_training_range_to_unit_interval() {
+    let inputs = vec![vec![0.0, 10.0, 5.0], vec![4.0, 30.0, 5.0], vec![2.0, 20.0, 5.0]];
+    let normalizer = Normalization::MinMax.fit(&inputs);
+
+    assert_eq!(normalizer.kind(), Normalization::MinMax);
+    assert_eq!(normalizer.dim(), 3);
+    assert_close(&normalizer.transform(&inputs[1]), &[1.0, 1.0, 0.0]);
+    assert_close(&normalizer.transform(&inputs[2]), &[0.5, 0.5, 0.0]);
+    // Unseen values may fall outside [0, 1]; constant features are only shifted
+    assert_close(&normalizer.transform(&[8.0, 0.0, 7.0]), &[2.0, -0.5, 2.0]);
+}
+
+#[test]
+fn test_zscore_standardizes_features() {
+    let inputs = vec![vec![1.0, 3.0], vec![3.0, 3.0], vec![5.0, 3.0], vec![7.0, 3.0]];
+    let normalizer = Normalization::ZScore.fit(&inputs);
+    let Normalizer::ZScore { mean, std_dev } = &normalizer else {
+        panic!("expected a z-score normalizer");
+    };
+    assert_close(mean, &[4.0, 3.0]);
+    assert_close(std_dev, &[5.0f64.sqrt(), 0.0]);
+
+    let mut scaled = inputs.clone();
+    normalizer.apply_all(&mut scaled);
+    let column: Vec<f64> = scaled.iter().map(|x| x[0]).collect();
+    let mean = column.iter().sum::<f64>() / 4.0;
+    let variance = column.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / 4.0;
+    assert!(mean.abs() < 1e-12 && (variance - 1.0).abs() < 1e-12);
+    assert!(scaled.iter().all(|x| x[1] == 0.0));
+}
+
+#[test]
+fn test_normalization_names_round_trip_through_serde() {
+    for kind in [Normalization::MinMax, Normalization::ZScore] {
+        let json = serde_json::to_string(&kind).unwrap();
+        assert_eq!(json, format!("\"{}\"", kind.name()));
+        assert_eq!(serde_json::from_str::<Normalization>(&json).unwrap(), kind);
+    }
+}
+
+#[test]
+fn test_controller_saves_and_reuses_normalizer() {
+    let iris = get_example("iris").unwrap();
+    let config = || TrainingConfig {
+        epochs: 5,
+        normalization: Some(Normalization::ZScore),
+        ..Default::default()
+    };
+    let network = Network::new_seeded(iris.recommended_arch.clone(), SIGMOID, 0.3, 7);
+    let mut controller = TrainingController::new(network, config());
+    controller.train(iris.inputs.clone(), iris.targets.clone()).unwrap();
+
+    let fitted = controller.normalizer().cloned().unwrap();
+    assert_eq!(fitted, Normalization::ZScore.fit(&iris.inputs));
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("iris.json");
+    controller.save_checkpoint(&path).unwrap();
+    assert_eq!(Checkpoint::load(&path).unwrap().normalizer, Some(fitted.clone()));
+
+    // A resumed run keeps the saved transform even when trained on other data
+    let mut resumed = TrainingController::from_checkpoint(&path, config()).unwrap();
+    resumed.train(iris.inputs[..30].to_vec(), iris.targets[..30].to_vec()).unwrap();
+    assert_eq!(resumed.normalizer(), Some(&fitted));
+}
+
+#[test]
+fn test_controller_without_normalization_has_no_normalizer() {
+    let xor = get_example("xor").unwrap();
+    let config = TrainingConfig {
+        epochs: 2,
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 1), config);
+    controller.train(xor.inputs, xor.targets).unwrap();
+    assert!(controller.normalizer().is_none());
+}
+
+#[test]
+fn test_invert_undoes_transform() {
+    let data = vec![vec![500.0, -2.0], vec![600.0, 2.0], vec![550.0, 0.0]];
+    for kind in [Normalization::MinMax, Normalization::ZScore] {
+        let normalizer = kind.fit(&data);
+        for row in &data {
+            assert_close(&normalizer.inverse_transform(&normalizer.transform(row)), row);
+        }
+    }
+    // Constant features were only shifted, so they are only shifted back
+    let constant = Normalization::MinMax.fit(&[vec![3.0], vec![3.0]]);
+    assert_close(&constant.inverse_transform(&[0.5]), &[3.5]);
+}
+
+#[test]
+fn test_target_normalization_trains_large_regression_targets() {
+    // y = 100x + 500 lies far outside the sigmoid output range
+    let inputs: Vec<Vec<f64>> = (0..11).map(|i| vec![i as f64 / 10.0]).collect();
+    let targets: Vec<Vec<f64>> = inputs.iter().map(|x| vec![100.0 * x[0] + 500.0]).collect();
+    let config = TrainingConfig {
+        epochs: 3000,
+        target_normalization: Some(Normalization::MinMax),
+        ..Default::default()
+    };
+    let network = Network::new_seeded(vec![1, 4, 1], SIGMOID, 0.5, 3);
+    let mut controller = TrainingController::new(network, config);
+    let history = controller.train(inputs.clone(), targets.clone()).unwrap();
+    assert!(history.last().unwrap().loss < 0.01, "The loss is measured on scaled targets");
+
+    let scaler = controller.target_normalizer().cloned().unwrap();
+    assert_eq!(scaler, Normalization::MinMax.fit(&targets));
+    let mut network = controller.network().clone();
+    let output = network.feed_forward(neural_network::matrix::Matrix::from(vec![0.5]));
+    let prediction = scaler.inverse_transform(&output.data);
+    assert!((prediction[0] - 550.0).abs() < 10.0, "prediction {:?}", prediction);
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("regression.json");
+    controller.save_checkpoint(&path).unwrap();
+    let checkpoint = Checkpoint::load(&path).unwrap();
+    assert_eq!(checkpoint.target_normalizer, Some(scaler));
+    assert!(checkpoint.normalizer.is_none());
+}
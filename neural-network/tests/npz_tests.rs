@@ -0,0 +1,111 @@
+// Integration tests for NumPy .npz serialization
+use neural_network::activations::{SIGMOID, TANH};
+use neural_network::matrix::Matrix;
+use neural_network::network::Network;
+use neural_network::npz;
+use std::io::{Cursor, Read, Write};
+use tempfile::TempDir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// A `.npy` array as written by `numpy.save`
+fn npy(descr: &str, shape: &str, data: &[u8]) -> Vec<u8> {
+    let header = format!("{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}\n", descr, shape);
+    let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+    bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(header.as_bytes());
+    bytes.extend_from_slice(data);
+    bytes
+}
+
+fn le_f32(values: &[f32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// A zip of `(name, array)` entries, deflated as by `numpy.savez_compressed`
+fn archive(arrays: &[(&str, Vec<u8>)]) -> Vec<u8> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, array) in arrays {
+        zip.start_file(format!("{}.npy", name), options).unwrap();
+        zip.write_all(array).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+/// A 2-2-1 network as saved from PyTorch: float32 arrays, no scalars
+fn foreign_arrays() -> Vec<(&'static str, Vec<u8>)> {
+    vec![
+        ("layers.0.weight", npy("<f4", "(2, 2)", &le_f32(&[0.5, -1.0, 0.25, 2.0]))),
+        ("layers.0.bias", npy("<f4", "(2,)", &le_f32(&[0.1, -0.2]))),
+        ("layers.1.weight", npy("<f4", "(1, 2)", &le_f32(&[1.0, -2.0]))),
+        ("layers.1.bias", npy("<f4", "(1,)", &le_f32(&[0.25]))),
+    ]
+}
+
+#[test]
+fn test_saved_network_predicts_the_same_after_loading() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("model.npz");
+    let mut network = Network::new_seeded(vec![2, 4, 3, 1], TANH, 0.3, 11);
+    npz::save(&network, &path).unwrap();
+
+    let mut loaded = npz::load(&path).unwrap();
+    assert_eq!(loaded.layers, vec![2, 4, 3, 1]);
+    assert_eq!(loaded.activation.name, "tanh");
+    assert_eq!(loaded.learning_rate, 0.3);
+    for input in [vec![0.0, 1.0], vec![0.75, -0.5]] {
+        assert_eq!(
+            loaded.feed_forward(Matrix::from(input.clone())).data,
+            network.feed_forward(Matrix::from(input)).data
+        );
+    }
+
+    // One uncompressed .npy entry per array, readable by numpy.load
+    let mut zip = ZipArchive::new(std::fs::File::open(&path).unwrap()).unwrap();
+    assert_eq!(zip.len(), 8);
+    let mut entry = zip.by_name("layers.1.weight.npy").unwrap();
+    assert_eq!(entry.compression(), CompressionMethod::Stored);
+    let mut bytes = Vec::new();
+    entry.read_to_end(&mut bytes).unwrap();
+    assert!(bytes.starts_with(b"\x93NUMPY\x01\x00"));
+    let header = String::from_utf8_lossy(&bytes[10..]);
+    assert!(header.starts_with("{'descr': '<f8', 'fortran_order': False, 'shape': (3, 4), }"), "{}", header);
+}
+
+#[test]
+fn test_load_compressed_f32_arrays_without_scalars() {
+    let mut network = npz::from_bytes(&archive(&foreign_arrays())).unwrap();
+    assert_eq!(network.layers, vec![2, 2, 1]);
+    assert_eq!(network.activation.name, SIGMOID.name);
+    assert_eq!(network.learning_rate, npz::DEFAULT_LEARNING_RATE);
+    assert_eq!(network.weights[0].data, vec![0.5, -1.0, 0.25, 2.0]);
+    assert_eq!((network.biases[0].rows, network.biases[0].cols), (2, 1));
+
+    let output = network.feed_forward(Matrix::from(vec![1.0, 0.5])).data[0];
+    assert!(output > 0.0 && output < 1.0);
+}
+
+#[test]
+fn test_load_rejects_inconsistent_archives() {
+    let error = |arrays: &[(&str, Vec<u8>)]| format!("{:#}", npz::from_bytes(&archive(arrays)).unwrap_err());
+
+    let mut arrays = foreign_arrays();
+    arrays.remove(3);
+    assert!(error(&arrays).contains("Missing array 'layers.1.bias'"), "{}", error(&arrays));
+
+    let mut arrays = foreign_arrays();
+    arrays[2].1 = npy("<f4", "(1, 3)", &le_f32(&[1.0, -2.0, 0.5]));
+    assert!(error(&arrays).contains("takes 3 inputs but layer 0 has 2 outputs"), "{}", error(&arrays));
+
+    let mut arrays = foreign_arrays();
+    arrays[0].1 = npy("<i8", "(2, 2)", &[0; 32]);
+    assert!(error(&arrays).contains("only <f8 and <f4"), "{}", error(&arrays));
+
+    let mut arrays = foreign_arrays();
+    arrays.push(("extra", npy("<f8", "()", &1.0f64.to_le_bytes())));
+    assert!(error(&arrays).contains("Unexpected array 'extra.npy'"), "{}", error(&arrays));
+
+    assert!(error(&[]).contains("No 'layers.0.weight' array found"));
+    assert!(npz::from_bytes(b"not a zip file").is_err());
+}
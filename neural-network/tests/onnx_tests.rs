@@ -0,0 +1,245 @@
+// Integration tests for ONNX model import and export
+use neural_network::activations::{SIGMOID, TANH};
+use neural_network::matrix::Matrix;
+use neural_network::network::Network;
+use neural_network::onnx;
+use std::fs;
+use tempfile::TempDir;
+
+// Minimal protobuf encoding of the ONNX messages the importer reads
+
+fn varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn bytes_field(out: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    varint(out, field << 3 | 2);
+    varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+fn varint_field(out: &mut Vec<u8>, field: u64, value: u64) {
+    varint(out, field << 3);
+    varint(out, value);
+}
+
+/// A float tensor stored as raw little-endian bytes
+fn float_tensor(name: &str, dims: &[u64], values: &[f32]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for &dim in dims {
+        varint_field(&mut out, 1, dim);
+    }
+    varint_field(&mut out, 2, 1);
+    bytes_field(&mut out, 8, name.as_bytes());
+    let raw: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+    bytes_field(&mut out, 9, &raw);
+    out
+}
+
+/// A double tensor stored in the packed `double_data` field
+fn double_tensor(name: &str, dims: &[u64], values: &[f64]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut packed_dims = Vec::new();
+    for &dim in dims {
+        varint(&mut packed_dims, dim);
+    }
+    bytes_field(&mut out, 1, &packed_dims);
+    varint_field(&mut out, 2, 11);
+    bytes_field(&mut out, 8, name.as_bytes());
+    let data: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+    bytes_field(&mut out, 10, &data);
+    out
+}
+
+fn node_with(op_type: &str, inputs: &[&str], output: &str, int_attributes: &[(&str, u64)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for input in inputs {
+        bytes_field(&mut out, 1, input.as_bytes());
+    }
+    bytes_field(&mut out, 2, output.as_bytes());
+    bytes_field(&mut out, 4, op_type.as_bytes());
+    for (name, value) in int_attributes {
+        let mut attribute = Vec::new();
+        bytes_field(&mut attribute, 1, name.as_bytes());
+        varint_field(&mut attribute, 3, *value);
+        bytes_field(&mut out, 5, &attribute);
+    }
+    out
+}
+
+fn node(op_type: &str, inputs: &[&str], output: &str) -> Vec<u8> {
+    node_with(op_type, inputs, output, &[])
+}
+
+fn value_info(name: &str) -> Vec<u8> {
+    let mut out = Vec::new();
+    bytes_field(&mut out, 1, name.as_bytes());
+    out
+}
+
+fn model(nodes: &[Vec<u8>], initializers: &[Vec<u8>], inputs: &[&str], output: &str) -> Vec<u8> {
+    let mut graph = Vec::new();
+    for node in nodes {
+        bytes_field(&mut graph, 1, node);
+    }
+    for initializer in initializers {
+        bytes_field(&mut graph, 5, initializer);
+    }
+    for input in inputs {
+        bytes_field(&mut graph, 11, &value_info(input));
+    }
+    bytes_field(&mut graph, 12, &value_info(output));
+
+    let mut out = Vec::new();
+    varint_field(&mut out, 1, 8);
+    bytes_field(&mut out, 2, b"pytorch");
+    bytes_field(&mut out, 7, &graph);
+    out
+}
+
+/// A 2-3-1 network as exported from `nn.Linear` + `nn.Sigmoid` layers
+fn pytorch_mlp() -> Vec<u8> {
+    model(
+        &[
+            node_with("Gemm", &["input", "fc1.weight", "fc1.bias"], "h", &[("transB", 1)]),
+            node("Sigmoid", &["h"], "a"),
+            node_with("Gemm", &["a", "fc2.weight", "fc2.bias"], "o", &[("transB", 1)]),
+            node("Sigmoid", &["o"], "output"),
+        ],
+        &[
+            float_tensor("fc1.weight", &[3, 2], &[0.5, -1.0, 0.25, 2.0, -0.75, 1.5]),
+            float_tensor("fc1.bias", &[3], &[0.1, -0.2, 0.3]),
+            float_tensor("fc2.weight", &[1, 3], &[1.0, -2.0, 0.5]),
+            float_tensor("fc2.bias", &[1], &[0.25]),
+        ],
+        &["input"],
+        "output",
+    )
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+#[test]
+fn test_import_gemm_layers_from_pytorch() {
+    let mut network = onnx::from_bytes(&pytorch_mlp(), 0.3).unwrap();
+
+    assert_eq!(network.layers, vec![2, 3, 1]);
+    assert_eq!(network.learning_rate, 0.3);
+    assert_eq!((network.weights[0].rows, network.weights[0].cols), (3, 2));
+    assert_eq!(network.weights[0].data[1], -1.0);
+    assert_eq!(network.biases[1].data, vec![0.25]);
+
+    let hidden: Vec<f64> = [(0.5, -1.0, 0.1), (0.25, 2.0, -0.2), (-0.75, 1.5, 0.3)]
+        .iter()
+        .map(|&(w1, w2, b)| sigmoid(w1 * 1.0 + w2 * 0.5 + f64::from(b as f32)))
+        .collect();
+    let expected = sigmoid(hidden[0] - 2.0 * hidden[1] + 0.5 * hidden[2] + 0.25);
+    let output = network.feed_forward(Matrix::from(vec![1.0, 0.5]));
+    assert!((output.data[0] - expected).abs() < 1e-6, "{} vs {}", output.data[0], expected);
+}
+
+#[test]
+fn test_import_matmul_add_layers() {
+    // Keras-style layers: weights are (inputs x outputs) and the bias is added
+    // separately; initializers are also listed as graph inputs
+    let bytes = model(
+        &[
+            node("MatMul", &["x", "w"], "xw"),
+            node("Add", &["b", "xw"], "z"),
+            node("Identity", &["z"], "z2"),
+            node("Sigmoid", &["z2"], "y"),
+        ],
+        &[
+            double_tensor("w", &[2, 2], &[1.0, 2.0, 3.0, 4.0]),
+            double_tensor("b", &[1, 2], &[0.5, -0.5]),
+        ],
+        &["x", "w", "b"],
+        "y",
+    );
+    let mut network = onnx::from_bytes(&bytes, 0.5).unwrap();
+
+    assert_eq!(network.layers, vec![2, 2]);
+    assert_eq!(network.weights[0].data, vec![1.0, 3.0, 2.0, 4.0]);
+    let output = network.feed_forward(Matrix::from(vec![1.0, -1.0]));
+    for (actual, expected) in output.data.iter().zip([sigmoid(1.0 - 3.0 + 0.5), sigmoid(2.0 - 4.0 - 0.5)]) {
+        assert!((actual - expected).abs() < 1e-12, "{} vs {}", actual, expected);
+    }
+}
+
+#[test]
+fn test_import_rejects_unsupported_graphs() {
+    let error = |bytes: &[u8]| format!("{:#}", onnx::from_bytes(bytes, 0.5).unwrap_err());
+    let weights = || vec![double_tensor("w", &[2, 1], &[1.0, 2.0])];
+
+    let relu = model(&[node("MatMul", &["x", "w"], "z"), node("Relu", &["z"], "y")], &weights(), &["x"], "y");
+    assert!(error(&relu).contains("Unsupported ONNX operator 'Relu'"), "{}", error(&relu));
+
+    let linear = model(&[node("MatMul", &["x", "w"], "y")], &weights(), &["x"], "y");
+    assert!(error(&linear).contains("no Sigmoid activation"), "{}", error(&linear));
+
+    let branching = model(
+        &[
+            node("MatMul", &["x", "w"], "z"),
+            node("Sigmoid", &["z"], "y"),
+            node("Sigmoid", &["z"], "y2"),
+        ],
+        &weights(),
+        &["x"],
+        "y",
+    );
+    assert!(error(&branching).contains("feeds more than one node"), "{}", error(&branching));
+
+    let missing = model(&[node("MatMul", &["x", "v"], "z"), node("Sigmoid", &["z"], "y")], &weights(), &["x"], "y");
+    assert!(error(&missing).contains("'v' of node 'MatMul' is not a constant initializer"), "{}", error(&missing));
+
+    assert!(onnx::from_bytes(b"not an onnx file", 0.5).is_err());
+    assert!(onnx::from_bytes(&pytorch_mlp()[..40], 0.5).is_err());
+}
+
+#[test]
+fn test_imported_network_can_be_fine_tuned() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("mlp.onnx");
+    fs::write(&path, pytorch_mlp()).unwrap();
+
+    let mut network = onnx::load(&path, 0.5).unwrap();
+    let inputs = vec![vec![1.0, 0.5]];
+    let before = network.feed_forward(Matrix::from(inputs[0].clone())).data[0];
+    network.train(inputs.clone(), vec![vec![1.0]], 50);
+    let after = network.feed_forward(Matrix::from(inputs[0].clone())).data[0];
+    assert!(after > before, "{} -> {}", before, after);
+
+    let error = onnx::load(&dir.path().join("missing.onnx"), 0.5).unwrap_err();
+    assert!(error.to_string().contains("Failed to read ONNX model"));
+}
+
+#[test]
+fn test_exported_network_imports_unchanged() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("exported.onnx");
+    let mut network = Network::new_seeded(vec![2, 4, 3, 1], SIGMOID, 0.3, 7);
+    onnx::save(&network, &path).unwrap();
+
+    let mut imported = onnx::load(&path, 0.3).unwrap();
+    assert_eq!(imported.layers, network.layers);
+    assert_eq!(imported.weights, network.weights);
+    assert_eq!(imported.biases, network.biases);
+    let input = vec![0.25, -0.75];
+    assert_eq!(
+        imported.feed_forward(Matrix::from(input.clone())).data,
+        network.feed_forward(Matrix::from(input)).data
+    );
+}
+
+#[test]
+fn test_export_rejects_other_activations() {
+    let network = Network::new_seeded(vec![2, 3, 1], TANH, 0.1, 1);
+    let error = onnx::to_bytes(&network).unwrap_err();
+    assert!(error.to_string().contains("Only sigmoid networks"), "{}", error);
+}
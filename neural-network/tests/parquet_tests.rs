@@ -0,0 +1,85 @@
+// Tests for the Parquet loader (run with `--features parquet`)
+#![cfg(feature = "parquet")]
+
+use arrow_array::{ArrayRef, BooleanArray, Float64Array, Int32Array, RecordBatch, StringArray};
+use neural_network::dataset::Dataset;
+use neural_network::streaming::ParquetDataset;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+/// Write `columns` to `path` in row groups of at most `group_size` rows
+fn write(path: &Path, columns: Vec<(&str, ArrayRef)>, group_size: usize) {
+    let batch = RecordBatch::try_from_iter(columns).unwrap();
+    let properties = WriterProperties::builder()
+        .set_max_row_group_size(group_size)
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(File::create(path).unwrap(), batch.schema(), Some(properties)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+}
+
+#[test]
+fn test_parquet_dataset_reads_ranges_across_row_groups() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("data.parquet");
+    let x: Vec<i32> = (0..10).collect();
+    let scaled: Vec<Option<f64>> = (0..10).map(|i| (i != 4).then_some(i as f64 / 10.0)).collect();
+    let label: Vec<bool> = (0..10).map(|i| i % 3 == 0).collect();
+    write(
+        &path,
+        vec![
+            ("x", Arc::new(Int32Array::from(x)) as ArrayRef),
+            ("scaled", Arc::new(Float64Array::from(scaled))),
+            ("label", Arc::new(BooleanArray::from(label))),
+        ],
+        4,
+    );
+
+    let data = ParquetDataset::open(&path, 1).unwrap();
+    assert_eq!(data.column_names(), ["x", "scaled", "label"]);
+    assert_eq!((data.len(), data.input_dim(), data.target_dim()), (10, 2, 1));
+    assert_eq!(data.get(3), (vec![3.0, 0.3], vec![1.0]));
+
+    // Rows 2..9 span all three row groups
+    let (inputs, targets) = data.chunk(2..9);
+    assert_eq!(inputs.len(), 7);
+    assert_eq!(inputs[0], vec![2.0, 0.2]);
+    assert!(inputs[2][1].is_nan(), "Nulls become NaN");
+    assert_eq!(inputs[6], vec![8.0, 0.8]);
+    assert_eq!(targets[4], vec![1.0]);
+    // NaN != NaN, so compare the printed values
+    let rows: (Vec<_>, Vec<_>) = (0..10).map(|i| data.get(i)).unzip();
+    assert_eq!(format!("{:?}", data.to_vecs()), format!("{:?}", rows));
+    assert_eq!(data.describe().class_counts, vec![6, 4]);
+}
+
+#[test]
+fn test_parquet_dataset_rejects_unsupported_files() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("text.parquet");
+    write(
+        &path,
+        vec![
+            ("x", Arc::new(Float64Array::from(vec![1.0])) as ArrayRef),
+            ("name", Arc::new(StringArray::from(vec!["a"]))),
+        ],
+        10,
+    );
+    let error = ParquetDataset::open(&path, 1).unwrap_err().to_string();
+    assert!(error.contains("Column 'name'") && error.contains("expected numbers or booleans"), "{}", error);
+
+    let path = dir.path().join("one.parquet");
+    write(&path, vec![("x", Arc::new(Float64Array::from(vec![1.0])) as ArrayRef)], 10);
+    let error = ParquetDataset::open(&path, 1).unwrap_err().to_string();
+    assert!(error.contains("at least one input and one target column"), "{}", error);
+
+    let path = dir.path().join("not.parquet");
+    std::fs::write(&path, "1,2\n").unwrap();
+    assert!(ParquetDataset::open(&path, 1).is_err());
+}
@@ -0,0 +1,51 @@
+// Integration tests for SVG charts
+use neural_network::metrics::ConfusionMatrix;
+use neural_network::plot::{ChartOptions, Series, confusion_matrix_svg, line_chart_svg};
+
+fn polyline_points(svg: &str) -> Vec<usize> {
+    svg.split("<polyline points=\"")
+        .skip(1)
+        .map(|rest| rest[..rest.find('"').unwrap()].split_whitespace().count())
+        .collect()
+}
+
+#[test]
+fn test_linear_and_log_scale_line_charts() {
+    let series: Vec<Series> = vec![
+        ("Training loss".to_string(), vec![(1, 1.0), (2, 0.1), (3, 0.01), (4, 0.0)]),
+        ("Validation loss".to_string(), vec![(2, 0.5), (4, 0.05)]),
+    ];
+
+    let linear = line_chart_svg("Loss", "MSE", &series, &ChartOptions::default());
+    assert!(linear.contains("width=\"1200\" height=\"800\""));
+    assert_eq!(polyline_points(&linear), vec![4, 2]);
+    assert!(linear.contains(">1.000</text>"), "The linear axis ends at the largest value");
+    assert!(linear.contains("Validation loss"));
+
+    let options = ChartOptions {
+        width: 600,
+        height: 400,
+        log_scale: true,
+    };
+    let log = line_chart_svg("Loss", "MSE", &series, &options);
+    assert!(log.contains("width=\"600\" height=\"400\""));
+    assert_eq!(polyline_points(&log), vec![3, 2], "The zero loss cannot be drawn on a log axis");
+    for decade in ["1e-2", "1e-1", "1e0"] {
+        assert!(log.contains(&format!(">{}</text>", decade)), "Missing tick {}: {}", decade, log);
+    }
+    assert!(log.contains("MSE (log scale)"));
+}
+
+#[test]
+fn test_confusion_matrix_heatmap() {
+    let confusion = ConfusionMatrix {
+        counts: vec![vec![5, 1, 0], vec![0, 4, 2], vec![0, 0, 0]],
+    };
+    let svg = confusion_matrix_svg(&confusion, &ChartOptions { width: 600, height: 600, ..Default::default() });
+
+    assert!(svg.contains("width=\"600\" height=\"600\""));
+    assert_eq!(svg.matches("class=\"cell\"").count(), 9, "One cell per pair of classes");
+    assert!(svg.contains("9 of 12 correct (75.0%)"), "{}", svg);
+    assert!(svg.contains(">83.3%<") && svg.contains(">66.7%<"), "Per-class accuracy: {}", svg);
+    assert!(svg.contains(">-<"), "A class without samples has no accuracy");
+}
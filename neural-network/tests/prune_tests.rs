@@ -0,0 +1,69 @@
+// Integration tests for magnitude pruning
+use neural_network::activations::SIGMOID;
+use neural_network::examples::get_example;
+use neural_network::matrix::Matrix;
+use neural_network::network::Network;
+use neural_network::prune;
+
+#[test]
+fn test_prune_reaches_target_sparsity() {
+    let mut network = Network::new_seeded(vec![10, 20, 5], SIGMOID, 0.5, 42);
+    let pruned = prune::prune(&mut network, 0.8);
+
+    assert_eq!(pruned, 240);
+    assert_eq!(prune::nonzero_weights(&network), 60);
+    assert!((prune::sparsity(&network) - 0.8).abs() < 1e-12);
+    assert!(network.biases.iter().flat_map(|b| &b.data).all(|&b| b != 0.0), "Biases are not pruned");
+}
+
+#[test]
+fn test_prune_removes_smallest_weights_across_layers() {
+    let mut network = Network::new_seeded(vec![2, 2, 1], SIGMOID, 0.5, 42);
+    network.weights[0] = Matrix { rows: 2, cols: 2, data: vec![0.1, -2.0, 0.3, -0.05] };
+    network.weights[1] = Matrix { rows: 1, cols: 2, data: vec![-0.2, 1.0] };
+
+    prune::prune(&mut network, 0.5);
+
+    assert_eq!(network.weights[0].data, vec![0.0, -2.0, 0.3, 0.0]);
+    assert_eq!(network.weights[1].data, vec![0.0, 1.0]);
+}
+
+#[test]
+fn test_prune_counts_existing_zeros() {
+    let mut network = Network::new_seeded(vec![2, 2, 1], SIGMOID, 0.5, 42);
+    network.weights[0].data[0] = 0.0;
+
+    assert_eq!(prune::prune(&mut network, 0.5), 2);
+    assert_eq!(prune::prune(&mut network, 0.5), 0);
+    assert_eq!(prune::prune(&mut network, 0.0), 0);
+}
+
+#[test]
+fn test_sparse_size_shrinks_with_sparsity() {
+    let mut network = Network::new_seeded(vec![16, 32, 4], SIGMOID, 0.5, 42);
+    let dense = prune::sparse_size_bytes(&network);
+    prune::prune(&mut network, 0.9);
+
+    assert_eq!(prune::sparse_size_bytes(&network), 64 * 12 + 36 * 8);
+    assert!(prune::sparse_size_bytes(&network) * 5 < dense);
+}
+
+#[test]
+fn test_light_pruning_keeps_xor_solved() {
+    let ex = get_example("xor").unwrap();
+    let mut network = Network::new_seeded(ex.recommended_arch.clone(), SIGMOID, 0.5, 42);
+    network.train(ex.inputs.clone(), ex.targets.clone(), 5000);
+    prune::prune(&mut network, 0.1);
+
+    for (input, target) in ex.inputs.iter().zip(&ex.targets) {
+        let output = network.feed_forward(Matrix::from(input.clone()));
+        assert_eq!(output.data[0].round(), target[0]);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Sparsity must be between 0 and 1")]
+fn test_prune_rejects_invalid_sparsity() {
+    let mut network = Network::new_seeded(vec![2, 2, 1], SIGMOID, 0.5, 42);
+    prune::prune(&mut network, 1.5);
+}
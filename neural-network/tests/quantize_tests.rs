@@ -0,0 +1,87 @@
+// Integration tests for int8 quantization
+use neural_network::activations::SIGMOID;
+use neural_network::examples::get_example;
+use neural_network::matrix::Matrix;
+use neural_network::network::Network;
+use neural_network::quantize::QuantizedNetwork;
+use tempfile::TempDir;
+
+fn trained_xor() -> Network {
+    let ex = get_example("xor").unwrap();
+    let mut network = Network::new_seeded(ex.recommended_arch.clone(), SIGMOID, 0.5, 42);
+    network.train(ex.inputs.clone(), ex.targets.clone(), 5000);
+    network
+}
+
+#[test]
+fn test_quantized_predictions_match_float_network() {
+    let mut network = trained_xor();
+    let quantized = QuantizedNetwork::from_network(&network);
+    let ex = get_example("xor").unwrap();
+
+    for (input, target) in ex.inputs.iter().zip(&ex.targets) {
+        let expected = network.feed_forward(Matrix::from(input.clone())).data;
+        let actual = quantized.predict(input);
+        assert!((expected[0] - actual[0]).abs() < 0.05,
+            "Quantized output {} too far from {}", actual[0], expected[0]);
+        assert_eq!(actual[0].round(), target[0], "Quantized model should still solve XOR");
+    }
+}
+
+#[test]
+fn test_quantized_network_is_smaller() {
+    let network = Network::new(vec![16, 32, 4], SIGMOID, 0.5);
+    let quantized = QuantizedNetwork::from_network(&network);
+
+    assert!(quantized.size_bytes() * 3 < QuantizedNetwork::float_size_bytes(&network));
+    assert_eq!(quantized.quantized_layers.len(), 2);
+    assert_eq!(quantized.quantized_layers[0].weights.len(), 16 * 32);
+}
+
+#[test]
+fn test_lower_bit_widths_pack_smaller() {
+    let network = Network::new_seeded(vec![16, 32, 4], SIGMOID, 0.5, 42);
+    let int8 = QuantizedNetwork::from_network(&network);
+    let int4 = QuantizedNetwork::with_bits(&network, 4);
+
+    assert_eq!(int4.bits(), 4);
+    assert_eq!(int8.size_bytes() - int4.size_bytes(), (16 * 32 + 32 * 4) / 2);
+    assert!(int4.quantized_layers.iter().flat_map(|l| &l.weights).all(|&q| (-8..=7).contains(&q)));
+}
+
+#[test]
+fn test_four_bit_network_still_solves_xor() {
+    let ex = get_example("xor").unwrap();
+    let quantized = QuantizedNetwork::with_bits(&trained_xor(), 4);
+
+    for (input, target) in ex.inputs.iter().zip(&ex.targets) {
+        assert_eq!(quantized.predict(input)[0].round(), target[0]);
+    }
+}
+
+#[test]
+fn test_dequantize_restores_close_weights() {
+    let network = trained_xor();
+    let restored = QuantizedNetwork::from_network(&network).dequantize(network.learning_rate);
+
+    for (original, restored) in network.weights.iter().zip(&restored.weights) {
+        let range = original.data.iter().fold(0.0_f64, |m, w| m.max(w.abs())) * 2.0;
+        for (a, b) in original.data.iter().zip(&restored.data) {
+            assert!((a - b).abs() <= range / 255.0, "Weight error exceeds one quantization step");
+        }
+    }
+}
+
+#[test]
+fn test_quantized_save_and_load() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("quantized.json");
+
+    let quantized = QuantizedNetwork::from_network(&trained_xor());
+    quantized.save(&path).unwrap();
+    let loaded = QuantizedNetwork::load(&path).unwrap();
+
+    assert_eq!(loaded.layers, quantized.layers);
+    assert_eq!(loaded.quantized_layers[0].weights, quantized.quantized_layers[0].weights);
+    assert_eq!(loaded.quantized_layers[1].params.zero_point, quantized.quantized_layers[1].params.zero_point);
+}
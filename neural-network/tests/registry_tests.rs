@@ -0,0 +1,50 @@
+// Integration tests for runtime-registered examples
+use neural_network::dataset::Dataset;
+use neural_network::examples::{Example, get_example, list_examples, register_example, unregister_example};
+
+fn not_gate(name: &str) -> Example {
+    Example::custom(name, "NOT gate", vec![vec![0.0], vec![1.0]], vec![vec![1.0], vec![0.0]]).unwrap()
+}
+
+#[test]
+fn test_registered_example_is_found_by_name() {
+    let builtin = list_examples().len();
+    register_example(not_gate("not")).unwrap();
+
+    let example = get_example("not").unwrap();
+    assert_eq!((example.name, example.description), ("not", "NOT gate"));
+    assert_eq!(example.recommended_arch, vec![1, 2, 1]);
+    assert_eq!((example.input_dim(), example.target_dim()), (1, 1));
+    assert_eq!(list_examples().len(), builtin + 1);
+    assert_eq!(list_examples().last(), Some(&"not"));
+
+    // Registering again replaces the example in place
+    let mut wider = not_gate("not");
+    wider.recommended_arch = vec![1, 4, 1];
+    register_example(wider).unwrap();
+    assert_eq!(get_example("not").unwrap().recommended_arch, vec![1, 4, 1]);
+    assert_eq!(list_examples().len(), builtin + 1);
+
+    assert_eq!(unregister_example("not").unwrap().name, "not");
+    assert!(get_example("not").is_none());
+    assert!(unregister_example("not").is_none());
+    assert!(unregister_example("xor").is_none(), "Built-ins are never removed");
+    assert!(get_example("xor").is_some());
+}
+
+#[test]
+fn test_invalid_registrations_are_rejected() {
+    let err = register_example(not_gate("xor")).unwrap_err();
+    assert_eq!(err.to_string(), "'xor' is a built-in example and cannot be replaced");
+
+    assert!(register_example(not_gate("")).is_err());
+
+    let mut mismatched = not_gate("mismatched");
+    mismatched.recommended_arch = vec![2, 3, 1];
+    let err = register_example(mismatched).unwrap_err();
+    assert!(err.to_string().contains("recommended architecture [2, 3, 1]"));
+    assert!(get_example("mismatched").is_none());
+
+    assert!(Example::custom("empty", "", vec![], vec![]).is_err());
+    assert!(Example::custom("ragged", "", vec![vec![0.0], vec![]], vec![vec![0.0], vec![1.0]]).is_err());
+}
@@ -1,6 +1,7 @@
 // Integration tests for resuming training from checkpoints
 use neural_network::activations::SIGMOID;
-use neural_network::checkpoint::CheckpointMetadata;
+use neural_network::callbacks::from_fn;
+use neural_network::checkpoint::{Checkpoint, CheckpointMetadata};
 use neural_network::network::Network;
 use neural_network::training::{TrainingConfig, TrainingController};
 use std::sync::{Arc, Mutex};
@@ -23,6 +24,7 @@ fn test_resume_from_checkpoint_basic() {
         checkpoint_path: Some(checkpoint_path.clone()),
         verbose: false,
         example_name: None,
+        ..Default::default()
     };
 
     let mut controller = TrainingController::new(network, config);
@@ -41,6 +43,7 @@ fn test_resume_from_checkpoint_basic() {
             checkpoint_path: None,
             verbose: false,
             example_name: None,
+            ..Default::default()
         },
     )
     .expect("Should load from checkpoint");
@@ -69,6 +72,7 @@ fn test_resume_preserves_network_state() {
         total_epochs: 200,
         learning_rate: 0.5,
         timestamp: chrono::Utc::now().to_rfc3339(),
+        ..Default::default()
     };
 
     network.save_checkpoint(&checkpoint_path, metadata).unwrap();
@@ -86,6 +90,7 @@ fn test_resume_preserves_network_state() {
             checkpoint_path: None,
             verbose: false,
             example_name: None,
+            ..Default::default()
         },
     )
     .unwrap();
@@ -112,6 +117,7 @@ fn test_resume_with_continued_training() {
         checkpoint_path: Some(checkpoint_path.clone()),
         verbose: false,
         example_name: None,
+        ..Default::default()
     };
 
     let mut controller = TrainingController::new(network, config);
@@ -134,6 +140,7 @@ fn test_resume_with_continued_training() {
             checkpoint_path: None,
             verbose: false,
             example_name: None,
+            ..Default::default()
         },
     )
     .unwrap();
@@ -161,6 +168,7 @@ fn test_resume_with_callbacks() {
         checkpoint_path: Some(checkpoint_path.clone()),
         verbose: false,
         example_name: None,
+        ..Default::default()
     };
 
     let mut controller = TrainingController::new(network, config);
@@ -175,6 +183,7 @@ fn test_resume_with_callbacks() {
             checkpoint_path: None,
             verbose: false,
             example_name: None,
+            ..Default::default()
         },
     )
     .unwrap();
@@ -182,7 +191,7 @@ fn test_resume_with_callbacks() {
     let callback_count = Arc::new(Mutex::new(0));
     let count_clone = callback_count.clone();
 
-    resumed_controller.add_callback(Box::new(move |_e, _l, _n| {
+    resumed_controller.add_callback(from_fn(move |_record, _network| {
         *count_clone.lock().unwrap() += 1;
     }));
 
@@ -202,6 +211,7 @@ fn test_resume_nonexistent_checkpoint() {
         checkpoint_path: None,
         verbose: false,
         example_name: None,
+        ..Default::default()
     };
 
     let result = TrainingController::from_checkpoint(&checkpoint_path, config);
@@ -222,6 +232,7 @@ fn test_resume_with_new_checkpoint_path() {
         checkpoint_path: Some(old_checkpoint.clone()),
         verbose: false,
         example_name: None,
+        ..Default::default()
     };
 
     let mut controller = TrainingController::new(network, config);
@@ -237,6 +248,7 @@ fn test_resume_with_new_checkpoint_path() {
             checkpoint_path: Some(new_checkpoint.clone()),
             verbose: false,
             example_name: None,
+            ..Default::default()
         },
     )
     .unwrap();
@@ -260,6 +272,7 @@ fn test_resume_metadata_continuity() {
         checkpoint_path: Some(checkpoint_path.clone()),
         verbose: false,
         example_name: None,
+        ..Default::default()
     };
 
     let mut controller = TrainingController::new(network, config);
@@ -278,6 +291,7 @@ fn test_resume_metadata_continuity() {
             checkpoint_path: None,
             verbose: false,
             example_name: None,
+            ..Default::default()
         },
     )
     .unwrap();
@@ -287,3 +301,181 @@ fn test_resume_metadata_continuity() {
 
     // TempDir automatically cleans up when dropped
 }
+
+#[test]
+fn test_resume_continues_optimizer_and_shuffle_exactly() {
+    use neural_network::optimizer::{Optimizer, OptimizerState};
+
+    let temp_dir = create_temp_dir();
+    let inputs = vec![vec![0.0, 0.0], vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]];
+    let targets = vec![vec![0.0], vec![1.0], vec![1.0], vec![0.0]];
+
+    for (name, optimizer) in [("momentum", Optimizer::momentum()), ("adam", Optimizer::adam())] {
+        let checkpoint_path = temp_dir.path().join(format!("{}.json", name));
+        let config = |epochs| TrainingConfig {
+            epochs,
+            shuffle: true,
+            seed: Some(11),
+            batch_size: Some(2),
+            optimizer,
+            ..Default::default()
+        };
+        let network = Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.1, 4);
+
+        // One uninterrupted run of 40 epochs
+        let mut straight = TrainingController::new(network.clone(), config(40));
+        straight.train(inputs.clone(), targets.clone()).unwrap();
+
+        // 25 epochs, checkpoint, then 15 more from the checkpoint
+        let mut first = TrainingController::new(
+            network,
+            TrainingConfig {
+                checkpoint_interval: Some(25),
+                checkpoint_path: Some(checkpoint_path.clone()),
+                ..config(25)
+            },
+        );
+        first.train(inputs.clone(), targets.clone()).unwrap();
+        let mut resumed = TrainingController::from_checkpoint(&checkpoint_path, config(15)).unwrap();
+        let state = resumed.training_state().expect("Checkpoint carries training state");
+        assert_eq!(state.epochs_trained, 25);
+        assert_ne!(state.optimizer_state, OptimizerState::Sgd);
+        resumed.train(inputs.clone(), targets.clone()).unwrap();
+
+        assert_eq!(resumed.network().weights, straight.network().weights, "{} resume is exact", name);
+        assert_eq!(resumed.network().biases, straight.network().biases);
+        assert_eq!(resumed.training_state().unwrap().epochs_trained, 40);
+    }
+}
+
+#[test]
+fn test_resume_with_other_optimizer_starts_fresh_state() {
+    use neural_network::optimizer::{Optimizer, OptimizerState};
+
+    let temp_dir = create_temp_dir();
+    let checkpoint_path = temp_dir.path().join("adam.json");
+    let config = TrainingConfig {
+        epochs: 5,
+        optimizer: Optimizer::adam(),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![2, 2, 1], SIGMOID, 0.1), config);
+    controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+    controller.save_checkpoint(&checkpoint_path).unwrap();
+
+    let config = TrainingConfig {
+        epochs: 5,
+        optimizer: Optimizer::momentum(),
+        ..Default::default()
+    };
+    let mut resumed = TrainingController::from_checkpoint(&checkpoint_path, config).unwrap();
+    resumed.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+
+    let state = resumed.training_state().unwrap();
+    assert!(matches!(state.optimizer_state, OptimizerState::Momentum { .. }));
+    assert_eq!(state.epochs_trained, 10);
+}
+
+#[test]
+fn test_resumed_checkpoints_continue_the_loss_curve() {
+    let temp_dir = create_temp_dir();
+    let first_path = temp_dir.path().join("first.json");
+    let second_path = temp_dir.path().join("second.json");
+    let inputs = vec![vec![0.0, 1.0], vec![1.0, 0.0]];
+    let targets = vec![vec![1.0], vec![0.0]];
+
+    let mut first = TrainingController::new(
+        Network::new_seeded(vec![2, 2, 1], SIGMOID, 0.5, 1),
+        TrainingConfig {
+            epochs: 20,
+            ..Default::default()
+        },
+    );
+    first.train(inputs.clone(), targets.clone()).unwrap();
+    first.save_checkpoint(&first_path).unwrap();
+
+    let config = TrainingConfig {
+        epochs: 10,
+        checkpoint_interval: Some(10),
+        checkpoint_path: Some(second_path.clone()),
+        ..Default::default()
+    };
+    let mut resumed = TrainingController::from_checkpoint(&first_path, config).unwrap();
+    resumed.train(inputs, targets).unwrap();
+    assert_eq!(resumed.history().len(), 10, "history() covers this run only");
+
+    let history = Checkpoint::load(&second_path).unwrap().history;
+    let epochs: Vec<u32> = history.epochs.iter().map(|r| r.epoch).collect();
+    assert_eq!(epochs, (1..=30).collect::<Vec<_>>());
+    assert_eq!(history.epochs[..20], first.history().epochs[..]);
+    assert_eq!(history.epochs[29].loss, resumed.history().last().unwrap().loss);
+    assert!(history.epochs[20].elapsed_secs >= history.epochs[19].elapsed_secs);
+    assert_eq!(resumed.full_history(), history);
+}
+
+#[test]
+fn test_resumed_checkpoints_count_epochs_and_record_lineage() {
+    let temp_dir = create_temp_dir();
+    let paths: Vec<_> = (0..3).map(|i| temp_dir.path().join(format!("run{}.bin", i))).collect();
+    let inputs = vec![vec![0.0, 1.0]];
+    let targets = vec![vec![1.0]];
+    let config = |epochs| TrainingConfig {
+        epochs,
+        ..Default::default()
+    };
+
+    let mut controller = TrainingController::new(Network::new_seeded(vec![2, 2, 1], SIGMOID, 0.5, 1), config(30));
+    controller.train(inputs.clone(), targets.clone()).unwrap();
+    controller.save_checkpoint(&paths[0]).unwrap();
+    for (i, epochs) in [(1, 20), (2, 10)] {
+        let mut resumed = TrainingController::from_checkpoint(&paths[i - 1], config(epochs)).unwrap();
+        resumed.train(inputs.clone(), targets.clone()).unwrap();
+        resumed.save_checkpoint(&paths[i]).unwrap();
+    }
+
+    let first = Checkpoint::load(&paths[0]).unwrap();
+    let last = Checkpoint::load(&paths[2]).unwrap();
+    assert!(first.lineage.is_empty());
+    assert_eq!((last.metadata.epoch, last.metadata.total_epochs), (60, 60));
+    assert_eq!(last.training_state.unwrap().epochs_trained, 60);
+
+    let parents: Vec<_> = last.lineage.iter().map(|r| (r.parent_path.clone(), r.parent_epoch)).collect();
+    assert_eq!(
+        parents,
+        vec![(paths[0].display().to_string(), 30), (paths[1].display().to_string(), 50)]
+    );
+    assert_eq!(Some(&last.lineage[0].parent_checksum), first.checksum.as_ref());
+}
+
+#[test]
+fn test_recorded_overrides_are_saved() {
+    let temp_dir = create_temp_dir();
+    let first = temp_dir.path().join("first.json");
+    let second = temp_dir.path().join("second.bin");
+    let config = |path: &std::path::Path| TrainingConfig {
+        epochs: 10,
+        checkpoint_interval: Some(10),
+        checkpoint_path: Some(path.to_path_buf()),
+        ..Default::default()
+    };
+
+    let mut controller = TrainingController::new(Network::new_seeded(vec![2, 2, 1], SIGMOID, 0.5, 3), config(&first));
+    controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+    assert!(controller.overrides().is_empty());
+
+    let mut resumed = TrainingController::from_checkpoint(&first, config(&second)).unwrap();
+    resumed.network_mut().learning_rate = 0.2;
+    resumed.record_override("learning_rate", 0.5, 0.2);
+    resumed.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+
+    // Both the periodic checkpoint and an explicit save carry the override
+    let explicit = temp_dir.path().join("explicit.json");
+    resumed.save_checkpoint(&explicit).unwrap();
+    for path in [&second, &explicit] {
+        let metadata = Checkpoint::load(path).unwrap().metadata;
+        assert_eq!(metadata.learning_rate, 0.2);
+        assert_eq!(metadata.overrides.len(), 1, "{}", path.display());
+        let o = &metadata.overrides[0];
+        assert_eq!((o.name.as_str(), o.before.as_str(), o.after.as_str(), o.epoch), ("learning_rate", "0.5", "0.2", 10));
+    }
+}
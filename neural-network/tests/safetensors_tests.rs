@@ -0,0 +1,128 @@
+// Integration tests for safetensors serialization
+use neural_network::activations::{SIGMOID, TANH};
+use neural_network::matrix::Matrix;
+use neural_network::network::Network;
+use neural_network::safetensors as st;
+use safetensors::tensor::TensorView;
+use safetensors::Dtype;
+use std::collections::HashMap;
+use std::fs;
+use tempfile::TempDir;
+
+fn le_f32(values: &[f32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// Serialize `(name, dtype, shape, bytes)` tensors with optional metadata
+fn encode(tensors: &[(&str, Dtype, Vec<usize>, Vec<u8>)], metadata: &[(&str, &str)]) -> Vec<u8> {
+    let views: Vec<(&str, TensorView)> = tensors
+        .iter()
+        .map(|(name, dtype, shape, data)| (*name, TensorView::new(*dtype, shape.clone(), data).unwrap()))
+        .collect();
+    let metadata: HashMap<String, String> =
+        metadata.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    let metadata = if metadata.is_empty() { None } else { Some(metadata) };
+    safetensors::serialize(views, &metadata).unwrap()
+}
+
+/// A 2-2-1 network as exported from PyTorch: F32 tensors, no metadata
+fn foreign_tensors() -> Vec<(&'static str, Dtype, Vec<usize>, Vec<u8>)> {
+    vec![
+        ("layers.0.weight", Dtype::F32, vec![2, 2], le_f32(&[0.5, -1.0, 0.25, 2.0])),
+        ("layers.0.bias", Dtype::F32, vec![2], le_f32(&[0.1, -0.2])),
+        ("layers.1.weight", Dtype::F32, vec![1, 2], le_f32(&[1.0, -2.0])),
+        ("layers.1.bias", Dtype::F32, vec![1], le_f32(&[0.25])),
+    ]
+}
+
+#[test]
+fn test_saved_network_predicts_the_same_after_loading() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("model.safetensors");
+    let mut network = Network::new_seeded(vec![2, 4, 3, 1], SIGMOID, 0.3, 11);
+    st::save(&network, &path).unwrap();
+
+    let mut loaded = st::load(&path).unwrap();
+    assert_eq!(loaded.layers, vec![2, 4, 3, 1]);
+    assert_eq!(loaded.learning_rate, 0.3);
+    for input in [vec![0.0, 1.0], vec![0.75, -0.5]] {
+        assert_eq!(
+            loaded.feed_forward(Matrix::from(input.clone())).data,
+            network.feed_forward(Matrix::from(input)).data
+        );
+    }
+
+    // 8-byte length, JSON header, then the raw parameters
+    let bytes = fs::read(&path).unwrap();
+    let header_len = u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize;
+    let header: serde_json::Value = serde_json::from_slice(&bytes[8..8 + header_len]).unwrap();
+    assert_eq!(header["__metadata__"]["format"], st::FORMAT);
+    assert_eq!(header["layers.1.weight"]["shape"], serde_json::json!([3, 4]));
+    assert_eq!(header["layers.2.bias"]["dtype"], "F64");
+}
+
+#[test]
+fn test_activation_is_saved_and_restored() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("tanh.safetensors");
+    let mut network = Network::new_seeded(vec![2, 3, 1], TANH, 0.1, 2);
+    st::save(&network, &path).unwrap();
+
+    let mut loaded = st::load(&path).unwrap();
+    assert_eq!(loaded.activation.name, "tanh");
+    let input = vec![0.5, -1.0];
+    assert_eq!(
+        loaded.feed_forward(Matrix::from(input.clone())).data,
+        network.feed_forward(Matrix::from(input)).data
+    );
+}
+
+#[test]
+fn test_load_f32_tensors_without_metadata() {
+    let mut network = st::from_bytes(&encode(&foreign_tensors(), &[])).unwrap();
+
+    assert_eq!(network.layers, vec![2, 2, 1]);
+    assert_eq!(network.learning_rate, st::DEFAULT_LEARNING_RATE);
+    assert_eq!(network.weights[0].data, vec![0.5, -1.0, 0.25, 2.0]);
+    assert_eq!(network.biases[1].data, vec![0.25]);
+    let output = network.feed_forward(Matrix::from(vec![1.0, 0.0]));
+    assert_eq!(output.data.len(), 1);
+
+    let network = st::from_bytes(&encode(&foreign_tensors(), &[("learning_rate", "0.05")])).unwrap();
+    assert_eq!(network.learning_rate, 0.05);
+}
+
+#[test]
+fn test_load_rejects_inconsistent_files() {
+    let error = |tensors: &[(&str, Dtype, Vec<usize>, Vec<u8>)], metadata: &[(&str, &str)]| {
+        format!("{:#}", st::from_bytes(&encode(tensors, metadata)).unwrap_err())
+    };
+    let tensors = foreign_tensors();
+
+    let message = error(&tensors, &[("layers", "2,3,1")]);
+    assert!(message.contains("Metadata lists layers 2,3,1"), "{}", message);
+    let message = error(&tensors, &[("activation", "softmax")]);
+    assert!(message.contains("Unsupported activation 'softmax'"), "{}", message);
+    let message = error(&tensors, &[("learning_rate", "fast")]);
+    assert!(message.contains("Invalid learning_rate 'fast'"), "{}", message);
+
+    let message = error(&tensors[..3], &[]);
+    assert!(message.contains("Missing tensor 'layers.1.bias'"), "{}", message);
+    let mut extra = tensors.clone();
+    extra.push(("embedding", Dtype::F32, vec![1], le_f32(&[1.0])));
+    let message = error(&extra, &[]);
+    assert!(message.contains("Unexpected tensor 'embedding'"), "{}", message);
+    let mut mismatched = tensors.clone();
+    mismatched[2] = ("layers.1.weight", Dtype::F32, vec![1, 3], le_f32(&[1.0, 2.0, 3.0]));
+    let message = error(&mismatched, &[]);
+    assert!(message.contains("takes 3 inputs but layer 0 has 2 outputs"), "{}", message);
+    let mut integers = tensors.clone();
+    integers[1] = ("layers.0.bias", Dtype::I32, vec![2], vec![0; 8]);
+    let message = error(&integers, &[]);
+    assert!(message.contains("only F64 and F32 are supported"), "{}", message);
+
+    assert!(st::from_bytes(b"not a safetensors file").is_err());
+    let dir = TempDir::new().unwrap();
+    let message = st::load(&dir.path().join("missing.safetensors")).unwrap_err().to_string();
+    assert!(message.contains("Failed to read safetensors file"));
+}
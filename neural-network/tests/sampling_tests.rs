@@ -0,0 +1,81 @@
+// Integration tests for stratified splitting and class balancing
+use neural_network::dataset::{Dataset, VecDataset};
+use neural_network::examples::get_example;
+use neural_network::sampling::{Balance, class_counts, stratified_split};
+
+/// 10 samples of class 0, 4 of class 1 and 2 of class 2, one-hot encoded
+fn imbalanced() -> VecDataset {
+    let classes = [0; 10].into_iter().chain([1; 4]).chain([2; 2]);
+    let (inputs, targets) = classes
+        .enumerate()
+        .map(|(i, class)| {
+            let mut target = vec![0.0; 3];
+            target[class] = 1.0;
+            (vec![i as f64], target)
+        })
+        .unzip();
+    VecDataset::new(inputs, targets).unwrap()
+}
+
+#[test]
+fn test_stratified_split_keeps_class_proportions() {
+    let iris = get_example("iris").unwrap();
+    let (train, test) = stratified_split(&iris, 0.25, 1).unwrap();
+
+    assert_eq!(class_counts(&iris), vec![20, 20, 20]);
+    assert_eq!(class_counts(&train), vec![15, 15, 15]);
+    assert_eq!(class_counts(&test), vec![5, 5, 5]);
+
+    // Every sample lands in exactly one set, in the original order
+    let position = |x: &Vec<f64>| iris.inputs.iter().position(|y| y == x).unwrap();
+    let mut seen: Vec<usize> = train.inputs().iter().chain(test.inputs()).map(position).collect();
+    assert!(train.inputs().windows(2).all(|w| position(&w[0]) < position(&w[1])));
+    seen.sort_unstable();
+    assert_eq!(seen, (0..60).collect::<Vec<_>>());
+
+    let (again, _) = stratified_split(&iris, 0.25, 1).unwrap();
+    assert_eq!(again, train, "Same seed, same split");
+    assert_ne!(stratified_split(&iris, 0.25, 2).unwrap().0, train, "Other seed, other split");
+}
+
+#[test]
+fn test_stratified_split_rejects_bad_fractions() {
+    let data = imbalanced();
+    for fraction in [0.0, 1.0, -0.5, f64::NAN] {
+        assert!(stratified_split(&data, fraction, 0).is_err(), "{} is rejected", fraction);
+    }
+    let err = stratified_split(&data, 0.01, 0).unwrap_err();
+    assert_eq!(err.to_string(), "Test fraction 0.01 of 16 samples leaves an empty training or test set");
+}
+
+#[test]
+fn test_oversample_repeats_minority_samples() {
+    let data = imbalanced();
+    let balanced = Balance::Oversample.apply(&data, 3);
+
+    assert_eq!(class_counts(&balanced), vec![10, 10, 10]);
+    assert_eq!(&balanced.inputs()[..16], data.inputs(), "Original samples come first");
+    for i in 16..balanced.len() {
+        assert!(data.inputs().contains(&balanced.get(i).0));
+    }
+    assert_eq!(Balance::Oversample.apply(&data, 3), balanced);
+}
+
+#[test]
+fn test_undersample_drops_majority_samples() {
+    let data = imbalanced();
+    let balanced = Balance::Undersample.apply(&data, 3);
+
+    assert_eq!(class_counts(&balanced), vec![2, 2, 2]);
+    let x: Vec<f64> = balanced.inputs().iter().map(|x| x[0]).collect();
+    assert!(x.windows(2).all(|w| w[0] < w[1]), "Kept samples stay in order: {:?}", x);
+    assert_eq!(&x[4..], [14.0, 15.0], "The smallest class is kept whole");
+}
+
+#[test]
+fn test_single_output_classes_use_threshold() {
+    let xor = get_example("xor").unwrap();
+    assert_eq!(class_counts(&xor), vec![2, 2]);
+    assert_eq!(Balance::Undersample.name(), "undersample");
+    assert_eq!(serde_json::to_string(&Balance::Oversample).unwrap(), "\"oversample\"");
+}
@@ -1,11 +1,11 @@
 // Integration tests for Network and Matrix serialization
 use neural_network::network::Network;
 use neural_network::activations::SIGMOID;
-use neural_network::matrix::Matrix;
+use neural_network::matrix::{Matrix, assert_matrix_eq};
 
 #[test]
 fn test_serialize_matrix() {
-    let matrix = Matrix::new(2, 3, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    let matrix = Matrix::new(2, 3, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
     let json = serde_json::to_string(&matrix).expect("Serialization failed");
 
     assert!(json.contains("rows"));
@@ -15,7 +15,7 @@ fn test_serialize_matrix() {
 
 #[test]
 fn test_deserialize_matrix() {
-    let matrix = Matrix::new(2, 3, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    let matrix = Matrix::new(2, 3, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
     let json = serde_json::to_string(&matrix).unwrap();
     let restored: Matrix = serde_json::from_str(&json).expect("Deserialization failed");
 
@@ -24,6 +24,13 @@ fn test_deserialize_matrix() {
     assert_eq!(matrix.data, restored.data);
 }
 
+#[test]
+fn test_deserialize_rejects_inconsistent_shape() {
+    let json = r#"{"rows": 2, "cols": 2, "data": [1.0, 2.0, 3.0]}"#;
+    let err = serde_json::from_str::<Matrix>(json).unwrap_err();
+    assert!(err.to_string().contains("Matrix data has 3 values but shape is 2x2"), "{}", err);
+}
+
 #[test]
 fn test_matrix_serialization_roundtrip() {
     let original = Matrix::random(3, 4);
@@ -33,14 +40,7 @@ fn test_matrix_serialization_roundtrip() {
     let restored: Matrix = serde_json::from_str(&json).unwrap();
 
     // Should be identical (or very close due to JSON floating point precision)
-    assert_eq!(original.rows, restored.rows);
-    assert_eq!(original.cols, restored.cols);
-    assert_eq!(original.data.len(), restored.data.len());
-
-    // Check values are approximately equal (within floating point precision)
-    for (orig, rest) in original.data.iter().zip(restored.data.iter()) {
-        assert!((orig - rest).abs() < 1e-10, "Values differ: {} vs {}", orig, rest);
-    }
+    assert_matrix_eq!(original, restored, 1e-10);
 }
 
 #[test]
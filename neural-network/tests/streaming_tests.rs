@@ -0,0 +1,190 @@
+// Integration tests for file-backed datasets and streamed training
+use neural_network::activations::SIGMOID;
+use neural_network::dataset::{Dataset, VecDataset};
+use neural_network::examples::get_example;
+use neural_network::network::Network;
+use neural_network::normalize::Normalization;
+use neural_network::streaming::{CsvDataset, IdxDataset};
+use neural_network::training::{TrainingConfig, TrainingController, Validation};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+fn write(dir: &TempDir, name: &str, contents: &[u8]) -> PathBuf {
+    let path = dir.path().join(name);
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+/// An unsigned byte IDX file with the given dimensions
+fn idx(dims: &[u32], data: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0, 0, 0x08, dims.len() as u8];
+    for dim in dims {
+        bytes.extend_from_slice(&dim.to_be_bytes());
+    }
+    bytes.extend_from_slice(data);
+    bytes
+}
+
+fn parameters(network: &Network) -> Vec<u64> {
+    network
+        .weights
+        .iter()
+        .chain(&network.biases)
+        .flat_map(|m| m.data.iter().map(|v| v.to_bits()))
+        .collect()
+}
+
+/// Train iris with `chunk_size`, or in memory if `None`
+fn train_iris(chunk_size: Option<usize>) -> (Vec<u64>, Vec<f64>) {
+    let iris = get_example("iris").unwrap();
+    let network = Network::new_seeded(iris.recommended_arch.clone(), SIGMOID, 0.3, 7);
+    let config = TrainingConfig {
+        epochs: 15,
+        seed: Some(5),
+        shuffle: true,
+        batch_size: Some(4),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(network, config);
+    let history = match chunk_size {
+        Some(size) => controller.train_streaming(iris.clone(), size).unwrap(),
+        None => controller.train_dataset(&iris).unwrap(),
+    };
+    let losses = history.epochs.iter().map(|r| r.loss).collect();
+    (parameters(&controller.into_network()), losses)
+}
+
+#[test]
+fn test_csv_dataset_reads_rows_on_demand() {
+    let dir = TempDir::new().unwrap();
+    let path = write(&dir, "data.csv", b"x1,x2,y\n0,1,1\n\n1,1,0\n0.5, 0.25 ,1\n");
+    let data = CsvDataset::open(&path, 1).unwrap();
+
+    assert_eq!(data.header().unwrap(), ["x1", "x2", "y"]);
+    assert_eq!((data.len(), data.input_dim(), data.target_dim()), (3, 2, 1));
+    assert_eq!(data.get(2), (vec![0.5, 0.25], vec![1.0]));
+
+    let (inputs, targets) = data.chunk(1..3);
+    assert_eq!(inputs, vec![vec![1.0, 1.0], vec![0.5, 0.25]]);
+    assert_eq!(targets, vec![vec![0.0], vec![1.0]]);
+    assert_eq!(data.to_vecs(), (0..3).map(|i| data.get(i)).unzip());
+
+    // No header, two target columns
+    let path = write(&dir, "plain.csv", b"1,2,3\n4,5,6\n");
+    let data = CsvDataset::open(&path, 2).unwrap();
+    assert!(data.header().is_none());
+    assert_eq!(data.get(1), (vec![4.0], vec![5.0, 6.0]));
+}
+
+#[test]
+fn test_csv_dataset_rejects_malformed_files() {
+    let dir = TempDir::new().unwrap();
+    let error = |contents: &[u8], target_columns| {
+        let path = write(&dir, "bad.csv", contents);
+        CsvDataset::open(&path, target_columns).unwrap_err().to_string()
+    };
+
+    assert!(error(b"1,2\n3,x\n", 1).contains(":2: 'x' is not a number"));
+    assert!(error(b"1,2\n3,4,5\n", 1).contains(":2: expected 2 columns, found 3"));
+    assert!(error(b"a,b\n", 1).contains("no data rows"));
+    assert!(error(b"1,2\n", 2).contains("at least one input and one target column"));
+    assert!(CsvDataset::open(Path::new("missing.csv"), 1).is_err());
+}
+
+#[test]
+fn test_idx_dataset_scales_pixels_and_one_hot_encodes_labels() {
+    let dir = TempDir::new().unwrap();
+    let images = write(&dir, "images.idx", &idx(&[3, 2, 2], &[0, 255, 51, 102, 255, 0, 0, 0, 1, 2, 3, 4]));
+    let labels = write(&dir, "labels.idx", &idx(&[3], &[2, 0, 1]));
+    let data = IdxDataset::open(&images, &labels).unwrap();
+
+    assert_eq!((data.len(), data.input_dim(), data.target_dim()), (3, 4, 3));
+    assert_eq!((data.classes(), data.labels()), (3, &[2, 0, 1][..]));
+    assert_eq!(data.get(0), (vec![0.0, 1.0, 0.2, 0.4], vec![0.0, 0.0, 1.0]));
+    let (inputs, targets) = data.chunk(1..3);
+    assert_eq!(inputs[1], vec![1.0 / 255.0, 2.0 / 255.0, 3.0 / 255.0, 4.0 / 255.0]);
+    assert_eq!(targets, vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]]);
+
+    let few_labels = write(&dir, "few.idx", &idx(&[2], &[0, 1]));
+    let error = IdxDataset::open(&images, &few_labels).unwrap_err().to_string();
+    assert!(error.contains("have 3 images but 2 labels"), "{}", error);
+    let truncated = write(&dir, "truncated.idx", &idx(&[3, 2, 2], &[0; 8]));
+    let error = IdxDataset::open(&truncated, &labels).unwrap_err().to_string();
+    assert!(error.contains("is truncated"), "{}", error);
+}
+
+#[test]
+fn test_streaming_in_one_chunk_matches_in_memory_training() {
+    let (in_memory, losses) = train_iris(None);
+    let (streamed, streamed_losses) = train_iris(Some(1000));
+
+    assert_eq!(in_memory, streamed);
+    assert_eq!(losses, streamed_losses);
+}
+
+#[test]
+fn test_streaming_in_small_chunks_is_seeded_and_learns() {
+    let (a, losses) = train_iris(Some(7));
+    let (b, _) = train_iris(Some(7));
+    let (in_memory, _) = train_iris(None);
+
+    assert_eq!(a, b, "The chunk and sample order follow the seed");
+    assert_ne!(a, in_memory, "Samples are only shuffled within a chunk");
+    assert!(losses.last().unwrap() < &losses[0]);
+}
+
+#[test]
+fn test_streaming_fits_normalizer_and_uses_validation_set() {
+    let dir = TempDir::new().unwrap();
+    let rows: String = (0..40).map(|i| format!("{},{}\n", i * 10, (i % 2))).collect();
+    let path = write(&dir, "data.csv", rows.as_bytes());
+    let data = CsvDataset::open(&path, 1).unwrap();
+    let (inputs, targets) = data.to_vecs();
+    let config = TrainingConfig {
+        epochs: 3,
+        normalization: Some(Normalization::ZScore),
+        validation: Validation::Set {
+            inputs: inputs[..4].to_vec(),
+            targets: targets[..4].to_vec(),
+        },
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new_seeded(vec![1, 2, 1], SIGMOID, 0.5, 1), config);
+    let history = controller.train_streaming(data, 16).unwrap();
+
+    assert_eq!(history.len(), 3);
+    assert!(history.epochs.iter().all(|r| r.validation_loss.is_some()));
+    let in_memory = controller.normalizer().unwrap().clone();
+    let mut reference = TrainingController::new(
+        Network::new_seeded(vec![1, 2, 1], SIGMOID, 0.5, 1),
+        TrainingConfig {
+            epochs: 1,
+            normalization: Some(Normalization::ZScore),
+            ..Default::default()
+        },
+    );
+    reference.train_dataset(&VecDataset::new(inputs, targets).unwrap()).unwrap();
+    let (mut a, mut b) = (vec![vec![123.0]], vec![vec![123.0]]);
+    in_memory.apply_all(&mut a);
+    reference.normalizer().unwrap().apply_all(&mut b);
+    assert!((a[0][0] - b[0][0]).abs() < 1e-9);
+}
+
+#[test]
+fn test_streaming_rejects_unsupported_settings() {
+    let iris = get_example("iris").unwrap();
+    let network = || Network::new_seeded(iris.recommended_arch.clone(), SIGMOID, 0.3, 7);
+
+    let mut controller = TrainingController::new(network(), TrainingConfig::default());
+    let error = controller.train_streaming(iris.clone(), 0).unwrap_err();
+    assert_eq!(error.to_string(), "Chunk size must be at least 1");
+
+    let config = TrainingConfig {
+        validation: Validation::Split(0.2),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(network(), config);
+    let error = controller.train_streaming(iris.clone(), 10).unwrap_err();
+    assert!(error.to_string().contains("validation split is not supported"));
+}
@@ -0,0 +1,51 @@
+// Integration tests for checkpoint summaries
+use neural_network::activations::SIGMOID;
+use neural_network::checkpoint::Checkpoint;
+use neural_network::dataset::ColumnStats;
+use neural_network::network::Network;
+use neural_network::summary::CheckpointSummary;
+use neural_network::training::{TrainingConfig, TrainingController};
+use tempfile::TempDir;
+
+#[test]
+fn test_summary_counts_parameters_and_summarizes_history() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("model.json");
+    let inputs = vec![vec![0.0, 0.0], vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]];
+    let targets = vec![vec![0.0], vec![1.0], vec![1.0], vec![0.0]];
+    let config = TrainingConfig {
+        epochs: 30,
+        seed: Some(3),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new_seeded(vec![2, 4, 1], SIGMOID, 0.5, 3), config);
+    controller.train(inputs, targets).unwrap();
+    controller.save_checkpoint(&path).unwrap();
+    let checkpoint = Checkpoint::load(&path).unwrap();
+
+    let summary = CheckpointSummary::of(&checkpoint);
+    assert_eq!(summary.architecture, vec![2, 4, 1]);
+    assert_eq!(summary.layers.len(), 2);
+    assert_eq!((summary.layers[0].inputs, summary.layers[0].outputs), (2, 4));
+    assert_eq!(summary.layers[0].parameters(), 12);
+    assert_eq!(summary.layers[1].parameters(), 5);
+    assert_eq!(summary.total_parameters, 17);
+    assert_eq!(summary.layers[1].weight_stats, ColumnStats::of(&checkpoint.network.weights[1].data));
+
+    let history = summary.history.expect("The run recorded epochs");
+    assert_eq!(history.epochs, 30);
+    assert_eq!(history.first_loss, checkpoint.history.epochs[0].loss);
+    assert_eq!(history.final_loss, checkpoint.history.last().unwrap().loss);
+    assert!(history.best_loss <= history.final_loss && history.best_loss <= history.first_loss);
+
+    let json = serde_json::to_value(CheckpointSummary::of(&checkpoint.stripped())).unwrap();
+    assert_eq!(json["total_parameters"], 17);
+    assert!(json["history"].is_null(), "A stripped checkpoint has no history: {}", json);
+}
+
+#[test]
+fn test_column_stats_of_values() {
+    let stats = ColumnStats::of(&[1.0, 3.0, f64::NAN]);
+    assert_eq!((stats.mean, stats.std_dev, stats.min, stats.max), (2.0, 1.0, 1.0, 3.0));
+    assert_eq!(stats.non_finite, 1);
+}
@@ -0,0 +1,123 @@
+// Integration tests for hyperparameter sweeps
+use neural_network::sweep::{SweepReport, SweepSpec, run_trials};
+use tempfile::TempDir;
+
+const SPEC: &str = r#"
+example = "xor"
+learning_rates = [0.0, 1.0]
+hidden_layers = [[2], [4]]
+epochs = [200]
+seed = 7
+"#;
+
+#[test]
+fn test_sweep_ranks_trials_by_final_loss() {
+    let spec = SweepSpec::from_toml(SPEC).unwrap();
+    let settings = spec.settings().unwrap();
+    let trials = spec.grid();
+
+    let mut seen = Vec::new();
+    let report = run_trials(&settings, &trials, |i, _| seen.push(i));
+
+    assert_eq!(seen, vec![0, 1, 2, 3], "Progress is reported for every trial");
+    assert_eq!(report.results.len(), 4);
+    let scores: Vec<f64> = report.results.iter().map(|r| r.score().unwrap()).collect();
+    assert!(scores.windows(2).all(|w| w[0] <= w[1]), "Sorted best first: {:?}", scores);
+    assert_eq!(report.best().unwrap().trial.learning_rate, 1.0, "A zero learning rate never learns");
+    assert!(report.summary().contains("[2, 4, 1]"));
+}
+
+#[test]
+fn test_sweep_uses_validation_loss_when_split() {
+    let spec = SweepSpec::from_toml(&format!("{}\nvalidation_split = 0.25", SPEC)).unwrap();
+    let report = run_trials(&spec.settings().unwrap(), &spec.grid()[..1], |_, _| {});
+
+    let result = &report.results[0];
+    assert!(result.validation_loss.is_some());
+    assert_eq!(result.score(), result.validation_loss);
+}
+
+#[test]
+fn test_seeded_sweep_is_reproducible_and_report_roundtrips() {
+    let spec = SweepSpec::from_toml(SPEC).unwrap();
+    let settings = spec.settings().unwrap();
+    let first = run_trials(&settings, &spec.grid(), |_, _| {});
+    let second = run_trials(&settings, &spec.grid(), |_, _| {});
+    let losses = |r: &SweepReport| r.results.iter().map(|t| t.final_loss).collect::<Vec<_>>();
+    assert_eq!(losses(&first), losses(&second));
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("report.json");
+    first.save(&path).unwrap();
+    let loaded: SweepReport = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(losses(&loaded), losses(&first));
+}
+
+#[test]
+fn test_unknown_example_is_an_error() {
+    let spec = SweepSpec::from_toml(&SPEC.replace("xor", "nand3")).unwrap();
+    assert!(spec.settings().is_err());
+}
+
+#[test]
+fn test_random_search_respects_trial_budget_and_is_reproducible() {
+    let spec = SweepSpec::from_toml(&format!(
+        "{}\n[random]\nmax_trials = 3\nlearning_rate = [0.1, 2.0]\nepochs = [50, 100]",
+        SPEC
+    ))
+    .unwrap();
+
+    let first = spec.run(|_, _| {}).unwrap();
+    let second = spec.run(|_, _| {}).unwrap();
+
+    assert_eq!(first.results.len(), 3);
+    let trials = |r: &SweepReport| r.results.iter().map(|t| t.trial.clone()).collect::<Vec<_>>();
+    assert_eq!(trials(&first), trials(&second), "Seeded sampling draws the same trials");
+    assert!(first.summary().contains("3 trials"));
+}
+
+#[test]
+fn test_random_search_time_budget() {
+    let spec = SweepSpec::from_toml(&format!("{}\n[random]\nmax_secs = 0.0", SPEC)).unwrap();
+    let report = spec.run(|_, _| {}).unwrap();
+    assert!(report.results.is_empty(), "An exhausted time budget starts no trials");
+    assert!(report.best().is_none());
+}
+
+#[test]
+fn test_parallel_sweep_matches_sequential_and_keeps_best_checkpoint() {
+    let spec = SweepSpec::from_toml(SPEC).unwrap();
+    let sequential = spec.run(|_, _| {}).unwrap();
+    let mut seen = Vec::new();
+    let parallel = spec.run_parallel(3, |i, _| seen.push(i)).unwrap();
+
+    assert_eq!(seen, vec![0, 1, 2, 3], "Progress counts finished trials");
+    let ranked = |r: &SweepReport| {
+        r.results.iter().map(|t| (t.trial.clone(), t.final_loss)).collect::<Vec<_>>()
+    };
+    assert_eq!(ranked(&parallel), ranked(&sequential), "Seeded trials train the same on any thread");
+
+    let best = parallel.best().unwrap();
+    let checkpoint = parallel.best_checkpoint.as_ref().expect("The best trial's checkpoint is kept");
+    assert_eq!(checkpoint.network.layers, best.architecture);
+    assert_eq!(checkpoint.network.learning_rate, best.trial.learning_rate);
+    assert_eq!(checkpoint.metadata.final_loss, best.final_loss);
+    assert_eq!(checkpoint.metadata.total_epochs, best.trial.epochs);
+}
+
+#[test]
+fn test_markdown_report() {
+    let spec = SweepSpec::from_toml(SPEC).unwrap();
+    let report = spec.run(|_, _| {}).unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("report.md");
+    report.save(&path).unwrap();
+
+    let markdown = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(markdown, report.markdown());
+    assert!(markdown.starts_with("# Sweep results for xor\n\n4 trials, ranked by training loss."), "{}", markdown);
+    let rows: Vec<&str> = markdown.lines().filter(|line| line.starts_with("| ")).collect();
+    assert_eq!(rows.len(), 5, "Header and one row per trial: {}", markdown);
+    assert!(rows[1].starts_with("| 1 | "), "{}", rows[1]);
+    assert!(rows[1].contains("| 1 | 200 |"), "The best trial has learning rate 1: {}", rows[1]);
+}
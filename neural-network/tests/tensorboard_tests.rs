@@ -0,0 +1,81 @@
+// Integration tests for the TensorBoard event writer
+use neural_network::activations::SIGMOID;
+use neural_network::network::Network;
+use neural_network::tensorboard::{TensorBoardWriter, masked_crc32c};
+use neural_network::training::{TrainingConfig, TrainingController};
+use std::fs;
+use tempfile::TempDir;
+
+/// Split an event file into its TFRecord payloads, checking both CRCs
+fn read_records(bytes: &[u8]) -> Vec<Vec<u8>> {
+    let mut records = Vec::new();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let length_bytes = &bytes[pos..pos + 8];
+        let length = u64::from_le_bytes(length_bytes.try_into().unwrap()) as usize;
+        let length_crc = u32::from_le_bytes(bytes[pos + 8..pos + 12].try_into().unwrap());
+        assert_eq!(length_crc, masked_crc32c(length_bytes), "Length CRC");
+
+        let data = &bytes[pos + 12..pos + 12 + length];
+        let data_crc = u32::from_le_bytes(bytes[pos + 12 + length..pos + 16 + length].try_into().unwrap());
+        assert_eq!(data_crc, masked_crc32c(data), "Data CRC");
+
+        records.push(data.to_vec());
+        pos += 16 + length;
+    }
+    records
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+fn event_file(dir: &std::path::Path) -> Vec<u8> {
+    let entries: Vec<_> = fs::read_dir(dir).unwrap().map(|e| e.unwrap().path()).collect();
+    assert_eq!(entries.len(), 1, "One event file per writer");
+    let name = entries[0].file_name().unwrap().to_str().unwrap().to_string();
+    assert!(name.starts_with("events.out.tfevents."));
+    fs::read(&entries[0]).unwrap()
+}
+
+#[test]
+fn test_event_file_starts_with_version_record() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut writer = TensorBoardWriter::create(temp_dir.path()).unwrap();
+    writer.add_scalar("loss", 0.25, 1).unwrap();
+    writer.add_histogram("weights", &[0.1, -0.2, 0.3], 1).unwrap();
+    writer.flush().unwrap();
+
+    let records = read_records(&event_file(temp_dir.path()));
+    assert_eq!(records.len(), 3);
+    assert!(contains(&records[0], b"brain.Event:2"));
+    assert!(contains(&records[1], b"loss"));
+    assert!(contains(&records[1], &0.25f32.to_le_bytes()));
+    assert!(contains(&records[2], b"weights"));
+}
+
+#[test]
+fn test_callback_logs_scalars_and_histograms() {
+    let temp_dir = TempDir::new().unwrap();
+    let log_dir = temp_dir.path().join("run");
+    let config = TrainingConfig {
+        epochs: 4,
+        classification_threshold: Some(0.5),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![2, 3, 1], SIGMOID, 0.5), config);
+    let writer = TensorBoardWriter::create(&log_dir).unwrap().with_histogram_interval(2);
+    controller.add_callback(writer);
+    controller
+        .train(vec![vec![0.0, 1.0], vec![1.0, 1.0]], vec![vec![1.0], vec![0.0]])
+        .unwrap();
+
+    let records = read_records(&event_file(&log_dir));
+    let with_tag = |tag: &[u8]| records.iter().filter(|r| contains(r, tag)).count();
+
+    // loss, 4 metrics, learning rate and gradient norm every epoch
+    assert_eq!(with_tag(b"metrics/accuracy"), 4);
+    assert_eq!(with_tag(b"learning_rate"), 4);
+    assert_eq!(records.len(), 1 + 4 * 7 + 3 * 4, "Histograms at epochs 1, 2 and 4");
+    assert_eq!(with_tag(b"layer_1/weights"), 3);
+}
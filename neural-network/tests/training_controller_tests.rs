@@ -1,5 +1,7 @@
 // Integration tests for TrainingController
 use neural_network::activations::SIGMOID;
+use neural_network::callbacks::from_fn;
+use neural_network::matrix::assert_matrix_eq;
 use neural_network::network::Network;
 use std::sync::{Arc, Mutex};
 use tempfile::TempDir;
@@ -13,6 +15,7 @@ fn test_training_controller_basic() {
         checkpoint_path: None,
         verbose: false,
         example_name: None,
+        ..Default::default()
     };
 
     let mut controller = neural_network::training::TrainingController::new(network, config);
@@ -33,6 +36,7 @@ fn test_training_controller_with_callbacks() {
         checkpoint_path: None,
         verbose: false,
         example_name: None,
+        ..Default::default()
     };
 
     let mut controller = neural_network::training::TrainingController::new(network, config);
@@ -40,7 +44,7 @@ fn test_training_controller_with_callbacks() {
     let callback_invocations = Arc::new(Mutex::new(0));
     let invocations_clone = callback_invocations.clone();
 
-    controller.add_callback(Box::new(move |_epoch, _loss, _network| {
+    controller.add_callback(from_fn(move |_record, _network| {
         *invocations_clone.lock().unwrap() += 1;
     }));
 
@@ -61,6 +65,7 @@ fn test_training_controller_with_multiple_callbacks() {
         checkpoint_path: None,
         verbose: false,
         example_name: None,
+        ..Default::default()
     };
 
     let mut controller = neural_network::training::TrainingController::new(network, config);
@@ -70,10 +75,10 @@ fn test_training_controller_with_multiple_callbacks() {
     let c1 = counter1.clone();
     let c2 = counter2.clone();
 
-    controller.add_callback(Box::new(move |_e, _l, _n| {
+    controller.add_callback(from_fn(move |_record, _network| {
         *c1.lock().unwrap() += 1;
     }));
-    controller.add_callback(Box::new(move |_e, _l, _n| {
+    controller.add_callback(from_fn(move |_record, _network| {
         *c2.lock().unwrap() += 1;
     }));
 
@@ -95,6 +100,7 @@ fn test_training_controller_auto_checkpoint() {
         checkpoint_path: Some(checkpoint_path.clone()),
         verbose: false,
         example_name: None,
+        ..Default::default()
     };
 
     let mut controller = neural_network::training::TrainingController::new(network, config);
@@ -116,6 +122,7 @@ fn test_training_controller_verbose_mode() {
         checkpoint_path: None,
         verbose: true,
         example_name: None,
+        ..Default::default()
     };
 
     let mut controller = neural_network::training::TrainingController::new(network, config);
@@ -134,6 +141,7 @@ fn test_training_controller_returns_trained_network() {
         checkpoint_path: None,
         verbose: false,
         example_name: None,
+        ..Default::default()
     };
 
     let mut controller = neural_network::training::TrainingController::new(network, config);
@@ -161,6 +169,7 @@ fn test_training_config_defaults() {
         checkpoint_path: None,
         verbose: false,
         example_name: None,
+        ..Default::default()
     };
 
     assert_eq!(config.epochs, 1000);
@@ -181,6 +190,7 @@ fn test_training_controller_checkpoint_at_final_epoch() {
         checkpoint_path: Some(checkpoint_path.clone()),
         verbose: false,
         example_name: None,
+        ..Default::default()
     };
 
     let mut controller = neural_network::training::TrainingController::new(network, config);
@@ -190,3 +200,1071 @@ fn test_training_controller_checkpoint_at_final_epoch() {
     let (_, metadata) = Network::load_checkpoint(&checkpoint_path).unwrap();
     assert_eq!(metadata.epoch, 50);
 }
+
+#[test]
+fn test_training_controller_records_weight_norm_history() {
+    let network = Network::new(vec![2, 3, 1], SIGMOID, 0.5);
+    let config = neural_network::training::TrainingConfig {
+        epochs: 20,
+        checkpoint_interval: None,
+        checkpoint_path: None,
+        verbose: false,
+        example_name: None,
+        ..Default::default()
+    };
+
+    let mut controller = neural_network::training::TrainingController::new(network, config);
+    controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+
+    let history = controller.history();
+    assert_eq!(history.len(), 20, "One record per epoch");
+    assert_eq!(history.num_layers(), 2, "One norm per weight matrix");
+
+    let last = history.last().unwrap();
+    assert_eq!(last.epoch, 20);
+    assert_eq!(last.weight_norms, controller.network().weight_norms());
+}
+
+#[test]
+fn test_training_controller_checkpoint_includes_history() {
+    let temp_dir = TempDir::new().unwrap();
+    let checkpoint_path = temp_dir.path().join("history_checkpoint.json");
+
+    let network = Network::new(vec![2, 2, 1], SIGMOID, 0.5);
+    let config = neural_network::training::TrainingConfig {
+        epochs: 10,
+        checkpoint_interval: Some(5),
+        checkpoint_path: Some(checkpoint_path.clone()),
+        verbose: false,
+        example_name: None,
+        ..Default::default()
+    };
+
+    let mut controller = neural_network::training::TrainingController::new(network, config);
+    controller.train(vec![vec![0.0, 0.0]], vec![vec![0.0]]).unwrap();
+
+    let checkpoint = neural_network::checkpoint::Checkpoint::load(&checkpoint_path).unwrap();
+    assert_eq!(checkpoint.history.len(), 10);
+    assert_eq!(checkpoint.history.num_layers(), 2);
+    assert_eq!(checkpoint.history.last().unwrap().epoch, 10);
+    assert_eq!(checkpoint.history, *controller.history(), "Checkpoint carries the full records");
+}
+
+#[test]
+fn test_training_controller_returns_history() {
+    let network = Network::new(vec![2, 2, 1], SIGMOID, 0.5);
+    let config = neural_network::training::TrainingConfig {
+        epochs: 15,
+        checkpoint_interval: None,
+        checkpoint_path: None,
+        verbose: false,
+        example_name: None,
+        ..Default::default()
+    };
+
+    let mut controller = neural_network::training::TrainingController::new(network, config);
+    let history = controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+
+    assert_eq!(history.len(), 15);
+    assert!(history.gradient_norms().iter().all(|&g| g > 0.0 && g.is_finite()));
+    assert_eq!(&history, controller.history(), "History stays queryable after training");
+    assert_eq!(history.learning_rates(), vec![0.5; 15]);
+    assert!(history.epochs.windows(2).all(|w| w[0].elapsed_secs <= w[1].elapsed_secs));
+    assert!(history.elapsed_secs() > 0.0);
+}
+
+#[test]
+fn test_training_controller_detects_nan_divergence() {
+    let network = Network::new(vec![2, 2, 1], SIGMOID, 0.5);
+    let config = neural_network::training::TrainingConfig {
+        epochs: 10,
+        ..Default::default()
+    };
+
+    let mut controller = neural_network::training::TrainingController::new(network, config);
+    let err = controller
+        .train(vec![vec![f64::NAN, 0.0]], vec![vec![1.0]])
+        .expect_err("NaN input should abort training");
+
+    let divergence = err
+        .downcast_ref::<neural_network::training::DivergenceError>()
+        .expect("Should be a DivergenceError");
+    assert_eq!(divergence.epoch, 1, "Should stop at the first bad epoch");
+    assert_eq!(divergence.layer, Some(0), "NaN originates in the first layer");
+    assert!(divergence.rolled_back_to.is_none());
+    assert!(err.to_string().contains("epoch 1"));
+    assert!(controller.history().is_empty(), "Diverged epoch is not recorded");
+}
+
+#[test]
+fn test_training_controller_rolls_back_on_divergence() {
+    let temp_dir = TempDir::new().unwrap();
+    let checkpoint_path = temp_dir.path().join("good.json");
+
+    // Produce a good checkpoint at epoch 10
+    let network = Network::new(vec![2, 2, 1], SIGMOID, 0.5);
+    let mut controller = neural_network::training::TrainingController::new(
+        network,
+        neural_network::training::TrainingConfig {
+            epochs: 10,
+            checkpoint_interval: Some(10),
+            checkpoint_path: Some(checkpoint_path.clone()),
+            ..Default::default()
+        },
+    );
+    controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+    let good_weights = controller.network().weights.clone();
+
+    // Continue with data that diverges; the good checkpoint must be restored
+    let mut resumed = neural_network::training::TrainingController::from_checkpoint(
+        &checkpoint_path,
+        neural_network::training::TrainingConfig {
+            epochs: 10,
+            checkpoint_interval: Some(1),
+            checkpoint_path: Some(checkpoint_path.clone()),
+            rollback_on_divergence: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let err = resumed
+        .train(vec![vec![f64::INFINITY, 1.0]], vec![vec![1.0]])
+        .expect_err("Infinite input should diverge");
+
+    let divergence = err
+        .downcast_ref::<neural_network::training::DivergenceError>()
+        .unwrap();
+    assert_eq!(divergence.rolled_back_to, Some(10));
+    assert!(resumed.network().first_non_finite_layer().is_none());
+    for (restored, good) in resumed.network().weights.iter().zip(&good_weights) {
+        assert_matrix_eq!(restored, good, 1e-12);
+    }
+
+    // The checkpoint on disk was never overwritten with garbage
+    let (saved, metadata) = Network::load_checkpoint(&checkpoint_path).unwrap();
+    assert_eq!(metadata.epoch, 10);
+    assert!(saved.first_non_finite_layer().is_none());
+}
+
+#[test]
+fn test_training_controller_reports_per_output_loss() {
+    let network = Network::new(vec![2, 3, 2], SIGMOID, 0.5);
+    let config = neural_network::training::TrainingConfig {
+        epochs: 10,
+        ..Default::default()
+    };
+
+    let mut controller = neural_network::training::TrainingController::new(network, config);
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let seen_clone = seen.clone();
+    controller.add_callback(from_fn(move |record, _network| {
+        seen_clone.lock().unwrap().push(record.output_losses.clone());
+    }));
+
+    // The second output has a target the sigmoid can never reach
+    let inputs = vec![vec![0.0, 1.0], vec![1.0, 0.0]];
+    let targets = vec![vec![0.5, 5.0], vec![0.5, 5.0]];
+    let history = controller.train(inputs, targets).unwrap();
+
+    assert_eq!(seen.lock().unwrap().len(), 10, "Epoch callback runs every epoch");
+    assert_eq!(history.num_outputs(), 2);
+
+    let last = history.last().unwrap();
+    let sum: f64 = last.output_losses.iter().sum();
+    assert!((sum - last.loss).abs() < 1e-12, "Per-output losses add up to the total");
+    assert!(
+        last.output_losses[1] > last.output_losses[0],
+        "The unreachable output should lag behind"
+    );
+    assert_eq!(history.output_loss_series(0).len(), 10);
+}
+
+fn xor_data() -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+    let inputs = vec![vec![0.0, 0.0], vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]];
+    let targets = vec![vec![0.0], vec![1.0], vec![1.0], vec![0.0]];
+    (inputs, targets)
+}
+
+fn mse(network: &mut Network, inputs: &[Vec<f64>], targets: &[Vec<f64>]) -> f64 {
+    let total: f64 = inputs
+        .iter()
+        .zip(targets)
+        .map(|(input, target)| {
+            let output = network.feed_forward(neural_network::matrix::Matrix::from(input.clone()));
+            output.data.iter().zip(target).map(|(o, t)| (t - o) * (t - o)).sum::<f64>()
+        })
+        .sum();
+    total / inputs.len() as f64
+}
+
+#[test]
+fn test_validation_split_holds_out_last_samples() {
+    use neural_network::training::{TrainingConfig, TrainingController, Validation};
+
+    let (inputs, targets) = xor_data();
+    let network = Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 3);
+    let config = TrainingConfig {
+        epochs: 20,
+        validation: Validation::Split(0.25),
+        ..Default::default()
+    };
+
+    let mut controller = TrainingController::new(network, config);
+    let history = controller.train(inputs.clone(), targets.clone()).unwrap();
+
+    assert_eq!(history.validation_losses().len(), 20);
+    let last = history.last().unwrap();
+    let mut network = controller.into_network();
+    let expected_val = mse(&mut network, &inputs[3..], &targets[3..]);
+    let expected_train = mse(&mut network, &inputs[..3], &targets[..3]);
+    assert!((last.validation_loss.unwrap() - expected_val).abs() < 1e-12);
+    assert!((last.loss - expected_train).abs() < 1e-12, "Training loss excludes held-out samples");
+}
+
+#[test]
+fn test_validation_loss_reported_to_epoch_callback() {
+    use neural_network::training::{TrainingConfig, TrainingController, Validation};
+
+    let (inputs, targets) = xor_data();
+    let config = TrainingConfig {
+        epochs: 5,
+        validation: Validation::Set {
+            inputs: inputs.clone(),
+            targets: targets.clone(),
+        },
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![2, 3, 1], SIGMOID, 0.5), config);
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let seen_clone = seen.clone();
+    controller.add_callback(from_fn(move |record, _network| {
+        seen_clone.lock().unwrap().push(record.validation_loss);
+    }));
+    controller.train(inputs, targets).unwrap();
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 5);
+    assert!(seen.iter().all(|v| v.is_some_and(f64::is_finite)));
+}
+
+#[test]
+fn test_invalid_validation_split_is_an_error() {
+    use neural_network::training::{TrainingConfig, TrainingController, Validation};
+
+    for fraction in [0.0, 1.0, 0.05, f64::NAN] {
+        let (inputs, targets) = xor_data();
+        let config = TrainingConfig {
+            epochs: 1,
+            validation: Validation::Split(fraction),
+            ..Default::default()
+        };
+        let mut controller = TrainingController::new(Network::new(vec![2, 3, 1], SIGMOID, 0.5), config);
+        assert!(controller.train(inputs, targets).is_err(), "fraction {} should be rejected", fraction);
+    }
+}
+
+#[test]
+fn test_early_stopping_on_validation_loss() {
+    use neural_network::training::{TrainingConfig, TrainingController, Validation};
+
+    // A zero learning rate never improves the validation loss after epoch 1
+    let (inputs, targets) = xor_data();
+    let config = TrainingConfig {
+        epochs: 100,
+        validation: Validation::Split(0.25),
+        early_stopping_patience: Some(3),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![2, 3, 1], SIGMOID, 0.0), config);
+    let history = controller.train(inputs, targets).unwrap();
+
+    assert_eq!(history.len(), 4, "Stops after 3 epochs without improvement");
+    assert_eq!(controller.best_epoch(), Some(1));
+}
+
+#[test]
+fn test_restore_best_uses_lowest_validation_loss() {
+    use neural_network::training::{TrainingConfig, TrainingController, Validation};
+
+    // Validating against inverted targets makes the validation loss rise as
+    // training improves, so the best epoch is early
+    let (inputs, targets) = xor_data();
+    let inverted: Vec<Vec<f64>> = targets.iter().map(|t| vec![1.0 - t[0]]).collect();
+    let config = TrainingConfig {
+        epochs: 200,
+        validation: Validation::Set {
+            inputs: inputs.clone(),
+            targets: inverted.clone(),
+        },
+        restore_best: true,
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 9), config);
+    let history = controller.train(inputs.clone(), targets).unwrap();
+
+    let best_epoch = controller.best_epoch().unwrap();
+    let (_, best_loss) = history
+        .validation_losses()
+        .into_iter()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap();
+    assert_eq!(history.validation_losses()[best_epoch as usize - 1].1, best_loss);
+
+    let mut network = controller.into_network();
+    assert!((mse(&mut network, &inputs, &inverted) - best_loss).abs() < 1e-12);
+}
+
+fn train_shuffled(seed: Option<u64>, shuffle: bool) -> Network {
+    use neural_network::training::{TrainingConfig, TrainingController};
+
+    let (inputs, targets) = xor_data();
+    let config = TrainingConfig {
+        epochs: 50,
+        shuffle,
+        seed,
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 5), config);
+    controller.train(inputs, targets).unwrap();
+    controller.into_network()
+}
+
+#[test]
+fn test_shuffle_with_same_seed_is_reproducible() {
+    let a = train_shuffled(Some(11), true);
+    let b = train_shuffled(Some(11), true);
+    assert_eq!(a.weights, b.weights);
+    assert_eq!(a.biases, b.biases);
+}
+
+#[test]
+fn test_shuffle_changes_sample_order() {
+    let fixed = train_shuffled(Some(11), false);
+    let shuffled = train_shuffled(Some(11), true);
+    let other_seed = train_shuffled(Some(12), true);
+
+    assert_ne!(fixed.weights, shuffled.weights, "Shuffling visits samples in a different order");
+    assert_ne!(shuffled.weights, other_seed.weights, "Different seeds give different orders");
+}
+
+#[test]
+fn test_classification_metrics_recorded_per_epoch() {
+    use neural_network::metrics::ClassificationMetrics;
+    use neural_network::training::{TrainingConfig, TrainingController, Validation};
+
+    let (inputs, targets) = xor_data();
+    let config = TrainingConfig {
+        epochs: 10,
+        classification_threshold: Some(0.5),
+        validation: Validation::Set {
+            inputs: inputs.clone(),
+            targets: targets.clone(),
+        },
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 4), config);
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let seen_clone = seen.clone();
+    controller.add_callback(from_fn(move |record, _network| {
+        seen_clone.lock().unwrap().push(record.metrics);
+    }));
+    let history = controller.train(inputs.clone(), targets.clone()).unwrap();
+
+    assert!(seen.lock().unwrap().iter().all(Option::is_some));
+    let last = history.last().unwrap();
+    let mut network = controller.into_network();
+    let outputs: Vec<Vec<f64>> = inputs
+        .iter()
+        .map(|input| network.feed_forward(neural_network::matrix::Matrix::from(input.clone())).data)
+        .collect();
+    let expected = ClassificationMetrics::compute(&outputs, &targets, 0.5);
+    assert_eq!(last.metrics, Some(expected));
+    assert_eq!(last.validation_metrics, Some(expected));
+}
+
+#[test]
+fn test_classification_metrics_off_by_default() {
+    use neural_network::training::{TrainingConfig, TrainingController};
+
+    let (inputs, targets) = xor_data();
+    let config = TrainingConfig {
+        epochs: 3,
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![2, 3, 1], SIGMOID, 0.5), config);
+    let history = controller.train(inputs, targets).unwrap();
+    assert!(history.epochs.iter().all(|r| r.metrics.is_none()));
+}
+
+#[test]
+fn test_time_based_checkpointing() {
+    use neural_network::checkpoint::Checkpoint;
+    use neural_network::training::{TrainingConfig, TrainingController};
+    use std::time::Duration;
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("timed.json");
+
+    // A zero interval is always due, so every epoch overwrites the checkpoint
+    let config = TrainingConfig {
+        epochs: 7,
+        checkpoint_every: Some(Duration::ZERO),
+        checkpoint_path: Some(path.clone()),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![2, 2, 1], SIGMOID, 0.5), config);
+    controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+
+    let checkpoint = Checkpoint::load(&path).unwrap();
+    assert_eq!(checkpoint.metadata.epoch, 7);
+    assert_eq!(checkpoint.history.len(), 7);
+}
+
+#[test]
+fn test_time_based_checkpointing_waits_for_interval() {
+    use neural_network::training::{TrainingConfig, TrainingController};
+    use std::time::Duration;
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("timed.json");
+
+    let config = TrainingConfig {
+        epochs: 5,
+        checkpoint_every: Some(Duration::from_secs(3600)),
+        checkpoint_path: Some(path.clone()),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![2, 2, 1], SIGMOID, 0.5), config);
+    controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+
+    assert!(!path.exists(), "No checkpoint before the interval elapses");
+}
+
+#[test]
+fn test_cancellation_stops_after_current_epoch() {
+    use neural_network::training::{TrainingConfig, TrainingController};
+
+    let config = TrainingConfig {
+        epochs: 100,
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![2, 2, 1], SIGMOID, 0.5), config);
+    let token = controller.cancellation_token();
+    controller.add_callback(from_fn(move |record, _network| {
+        if record.epoch == 3 {
+            token.cancel();
+        }
+    }));
+
+    let history = controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+
+    assert!(controller.is_cancelled());
+    assert_eq!(history.len(), 3, "No epoch starts after cancellation");
+}
+
+#[test]
+fn test_cancellation_mid_epoch_skips_partial_epoch() {
+    use neural_network::callbacks::{BatchContext, TrainingCallback};
+    use neural_network::training::{CancellationToken, TrainingConfig, TrainingController};
+
+    struct CancelAtBatch(CancellationToken);
+    impl TrainingCallback for CancelAtBatch {
+        fn on_batch_end(&mut self, ctx: &BatchContext) {
+            if ctx.epoch == 2 && ctx.batch == 0 {
+                self.0.cancel();
+            }
+        }
+    }
+
+    let config = TrainingConfig {
+        epochs: 10,
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![2, 2, 1], SIGMOID, 0.5), config);
+    let token = CancellationToken::new();
+    controller.set_cancellation_token(token.clone());
+    controller.add_callback(CancelAtBatch(token));
+
+    let inputs = vec![vec![0.0, 0.0], vec![0.0, 1.0], vec![1.0, 1.0]];
+    let targets = vec![vec![0.0], vec![1.0], vec![0.0]];
+    let history = controller.train(inputs, targets).unwrap();
+
+    assert_eq!(history.len(), 1, "The interrupted epoch is not recorded");
+}
+
+#[test]
+fn test_cancelled_run_can_be_checkpointed() {
+    use neural_network::checkpoint::Checkpoint;
+    use neural_network::training::{TrainingConfig, TrainingController};
+
+    let config = TrainingConfig {
+        epochs: 50,
+        example_name: Some("and".to_string()),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![2, 2, 1], SIGMOID, 0.5), config);
+    let token = controller.cancellation_token();
+    controller.add_callback(from_fn(move |record, _network| {
+        if record.epoch == 5 {
+            token.cancel();
+        }
+    }));
+    controller.train(vec![vec![1.0, 1.0]], vec![vec![1.0]]).unwrap();
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("interrupted.json");
+    controller.save_checkpoint(&path).unwrap();
+
+    let checkpoint = Checkpoint::load(&path).unwrap();
+    assert_eq!(checkpoint.metadata.epoch, 5);
+    assert_eq!(checkpoint.metadata.total_epochs, 50);
+    assert_eq!(checkpoint.metadata.example, "and");
+    assert_eq!(checkpoint.history.len(), 5);
+}
+
+#[test]
+fn test_cancelled_token_prevents_training() {
+    use neural_network::training::{TrainingConfig, TrainingController};
+
+    let config = TrainingConfig {
+        epochs: 10,
+        ..Default::default()
+    };
+    let network = Network::new(vec![2, 2, 1], SIGMOID, 0.5);
+    let mut controller = TrainingController::new(network.clone(), config);
+    controller.cancellation_token().cancel();
+
+    let history = controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+
+    assert!(history.is_empty());
+    assert_eq!(controller.network().weights[0].data, network.weights[0].data, "No update was made");
+}
+
+#[test]
+fn test_train_epochs_in_steps_matches_single_train() {
+    use neural_network::training::{TrainingConfig, TrainingController};
+
+    let config = || TrainingConfig {
+        epochs: 25,
+        shuffle: true,
+        seed: Some(9),
+        restore_best: true,
+        ..Default::default()
+    };
+    let inputs = vec![vec![0.0, 0.0], vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]];
+    let targets = vec![vec![0.0], vec![1.0], vec![1.0], vec![0.0]];
+
+    let mut whole = TrainingController::new(Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 1), config());
+    let expected = whole.train(inputs.clone(), targets.clone()).unwrap();
+
+    let mut stepped = TrainingController::new(Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 1), config());
+    stepped.start(inputs, targets).unwrap();
+    let mut steps = Vec::new();
+    while !stepped.is_finished() {
+        steps.push(stepped.train_epochs(10).unwrap());
+    }
+    let history = stepped.finish().unwrap();
+
+    assert_eq!(steps, vec![10, 10, 5]);
+    assert_eq!(history.loss_series(), expected.loss_series());
+    assert_eq!(stepped.best_epoch(), whole.best_epoch());
+    assert_eq!(stepped.network().weights[0].data, whole.network().weights[0].data);
+}
+
+#[test]
+fn test_train_epochs_runs_start_and_end_hooks_once() {
+    use neural_network::callbacks::TrainingCallback;
+    use neural_network::history::TrainingHistory;
+    use neural_network::training::{TrainingConfig, TrainingController};
+
+    struct Counter(Arc<Mutex<(u32, u32)>>);
+    impl TrainingCallback for Counter {
+        fn on_train_start(&mut self, _config: &TrainingConfig, _network: &Network) -> anyhow::Result<()> {
+            self.0.lock().unwrap().0 += 1;
+            Ok(())
+        }
+        fn on_train_end(&mut self, _history: &TrainingHistory, _network: &Network) -> anyhow::Result<()> {
+            self.0.lock().unwrap().1 += 1;
+            Ok(())
+        }
+    }
+
+    let config = TrainingConfig {
+        epochs: 6,
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![2, 2, 1], SIGMOID, 0.5), config);
+    let counts = Arc::new(Mutex::new((0, 0)));
+    controller.add_callback(Counter(counts.clone()));
+
+    controller.start(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+    for _ in 0..4 {
+        controller.train_epochs(2).unwrap();
+    }
+    assert!(controller.is_finished());
+    assert_eq!(controller.train_epochs(2).unwrap(), 0, "No epochs past the configured total");
+    let history = controller.finish().unwrap();
+
+    assert_eq!(history.len(), 6);
+    assert_eq!(*counts.lock().unwrap(), (1, 1));
+    assert!(controller.finish().is_err(), "The run is over after finish");
+}
+
+#[test]
+fn test_second_run_continues_epoch_count() {
+    use neural_network::training::{TrainingConfig, TrainingController};
+
+    let config = TrainingConfig {
+        epochs: 5,
+        seed: Some(4),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new_seeded(vec![2, 2, 1], SIGMOID, 0.5, 4), config);
+    let inputs = vec![vec![0.0, 1.0], vec![1.0, 0.0]];
+    let targets = vec![vec![1.0], vec![0.0]];
+
+    controller.train(inputs.clone(), targets.clone()).unwrap();
+    let history = controller.train(inputs, targets).unwrap();
+
+    assert_eq!(history.len(), 5, "history() holds the latest run");
+    let epochs: Vec<u32> = controller.full_history().epochs.iter().map(|r| r.epoch).collect();
+    assert_eq!(epochs, (1..=10).collect::<Vec<_>>());
+    let checkpoint = controller.to_checkpoint();
+    assert_eq!(checkpoint.metadata.epoch, 10);
+    assert_eq!(checkpoint.history.len(), 10);
+}
+
+#[test]
+fn test_train_epochs_requires_start() {
+    use neural_network::training::{TrainingConfig, TrainingController};
+
+    let config = TrainingConfig {
+        epochs: 5,
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![2, 2, 1], SIGMOID, 0.5), config);
+
+    assert!(controller.train_epochs(1).is_err());
+}
+
+#[test]
+fn test_target_loss_stops_training_early() {
+    use neural_network::training::{TrainingConfig, TrainingController};
+
+    let inputs = vec![vec![0.0, 0.0], vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]];
+    let targets = vec![vec![0.0], vec![0.0], vec![0.0], vec![1.0]];
+    let config = TrainingConfig {
+        epochs: 5000,
+        target_loss: Some(0.05),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new_seeded(vec![2, 2, 1], SIGMOID, 1.0, 3), config);
+    let history = controller.train(inputs, targets).unwrap();
+
+    let used = history.len();
+    assert!(used < 5000, "AND should reach the target well before 5000 epochs");
+    assert!(history.last().unwrap().loss < 0.05);
+    assert!(history.epochs[..used - 1].iter().all(|r| r.loss >= 0.05), "Stops at the first epoch below target");
+}
+
+#[test]
+fn test_target_loss_unreachable_runs_all_epochs() {
+    use neural_network::training::{TrainingConfig, TrainingController};
+
+    let config = TrainingConfig {
+        epochs: 20,
+        target_loss: Some(0.0),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![2, 2, 1], SIGMOID, 0.5), config);
+    let history = controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+
+    assert_eq!(history.len(), 20);
+}
+
+#[test]
+fn test_epoch_timing_and_eta() {
+    use neural_network::history::EpochRecord;
+    use neural_network::training::{TrainingConfig, TrainingController};
+
+    let config = TrainingConfig {
+        epochs: 10,
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![2, 2, 1], SIGMOID, 0.5), config);
+    let etas = Arc::new(Mutex::new(Vec::new()));
+    let etas_clone = etas.clone();
+    controller.add_callback(from_fn(move |record, _network| {
+        etas_clone.lock().unwrap().push(record.eta_secs(10));
+    }));
+    let history = controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+
+    let total: f64 = history.epochs.iter().map(|r| r.epoch_secs).sum();
+    assert!(history.epochs.iter().all(|r| r.epoch_secs > 0.0));
+    assert!(total <= history.elapsed_secs() + 1e-9, "Epoch times add up to at most the elapsed time");
+    assert_eq!(*etas.lock().unwrap().last().unwrap(), 0.0);
+
+    // Four of ten epochs done in 2s leaves an estimated 3s
+    let record = EpochRecord {
+        epoch: 4,
+        elapsed_secs: 2.0,
+        ..Default::default()
+    };
+    assert_eq!(record.eta_secs(10), 3.0);
+    assert_eq!(record.eta_secs(2), 0.0, "Never negative");
+}
+
+#[test]
+fn test_batch_size_one_matches_per_sample_training() {
+    use neural_network::training::{TrainingConfig, TrainingController};
+
+    let inputs = vec![vec![0.0, 0.0], vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]];
+    let targets = vec![vec![0.0], vec![1.0], vec![1.0], vec![0.0]];
+    let train = |batch_size| {
+        let config = TrainingConfig {
+            epochs: 50,
+            shuffle: true,
+            seed: Some(5),
+            batch_size,
+            ..Default::default()
+        };
+        let mut controller = TrainingController::new(Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 8), config);
+        controller.train(inputs.clone(), targets.clone()).unwrap();
+        controller.into_network()
+    };
+
+    let per_sample = train(None);
+    let batched = train(Some(1));
+    assert_eq!(batched.weights, per_sample.weights);
+    assert_eq!(batched.biases, per_sample.biases);
+}
+
+#[test]
+fn test_full_batch_training_updates_once_per_epoch() {
+    use neural_network::callbacks::{BatchContext, TrainingCallback};
+    use neural_network::training::{TrainingConfig, TrainingController};
+
+    struct CountUpdates(Arc<Mutex<Vec<(u32, usize)>>>);
+    impl TrainingCallback for CountUpdates {
+        fn on_batch_end(&mut self, ctx: &BatchContext) {
+            self.0.lock().unwrap().push((ctx.epoch, ctx.batch));
+        }
+    }
+
+    let inputs = vec![vec![0.0, 0.0], vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]];
+    let targets = vec![vec![0.0], vec![0.0], vec![0.0], vec![1.0]];
+    let config = TrainingConfig {
+        epochs: 2000,
+        batch_size: Some(100),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new_seeded(vec![2, 2, 1], SIGMOID, 2.0, 3), config);
+    let updates = Arc::new(Mutex::new(Vec::new()));
+    controller.add_callback(CountUpdates(updates.clone()));
+    let history = controller.train(inputs, targets).unwrap();
+
+    let updates = updates.lock().unwrap();
+    assert_eq!(updates.len(), 2000);
+    assert!(updates.iter().all(|&(_, batch)| batch == 0));
+    assert!(history.last().unwrap().loss < history.epochs[0].loss / 2.0, "Full-batch descent learns AND");
+}
+
+#[test]
+fn test_mini_batches_split_the_epoch() {
+    use neural_network::callbacks::{BatchContext, TrainingCallback};
+    use neural_network::training::{TrainingConfig, TrainingController};
+
+    struct Count(Arc<Mutex<usize>>);
+    impl TrainingCallback for Count {
+        fn on_batch_end(&mut self, _ctx: &BatchContext) {
+            *self.0.lock().unwrap() += 1;
+        }
+    }
+
+    let config = TrainingConfig {
+        epochs: 3,
+        batch_size: Some(2),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![1, 2, 1], SIGMOID, 0.5), config);
+    let updates = Arc::new(Mutex::new(0));
+    controller.add_callback(Count(updates.clone()));
+    let inputs: Vec<Vec<f64>> = (0..5).map(|i| vec![i as f64 / 5.0]).collect();
+    let targets = inputs.clone();
+    controller.train(inputs, targets).unwrap();
+
+    // Batches of 2, 2 and 1 in each of 3 epochs
+    assert_eq!(*updates.lock().unwrap(), 9);
+}
+
+#[test]
+fn test_zero_batch_size_is_rejected() {
+    use neural_network::training::{TrainingConfig, TrainingController};
+
+    let config = TrainingConfig {
+        epochs: 1,
+        batch_size: Some(0),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![2, 2, 1], SIGMOID, 0.5), config);
+    let err = controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap_err();
+    assert!(err.to_string().contains("Batch size"));
+}
+
+#[test]
+fn test_uniform_sample_weights_scale_loss_and_step() {
+    use neural_network::training::{SampleWeights, TrainingConfig, TrainingController};
+
+    let inputs = vec![vec![0.0, 0.0], vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]];
+    let targets = vec![vec![0.0], vec![1.0], vec![1.0], vec![0.0]];
+    let train = |learning_rate, sample_weights| {
+        let config = TrainingConfig {
+            epochs: 20,
+            sample_weights,
+            ..Default::default()
+        };
+        let network = Network::new_seeded(vec![2, 3, 1], SIGMOID, learning_rate, 6);
+        let mut controller = TrainingController::new(network, config);
+        let history = controller.train(inputs.clone(), targets.clone()).unwrap();
+        (history, controller.into_network())
+    };
+
+    // Doubling every weight doubles the loss and each step, like doubling the learning rate
+    let (plain, plain_network) = train(0.5, SampleWeights::None);
+    let (weighted, weighted_network) = train(0.25, SampleWeights::PerSample(vec![2.0; 4]));
+    assert_eq!(weighted_network.weights, plain_network.weights);
+    assert_eq!(weighted.last().unwrap().loss, 2.0 * plain.last().unwrap().loss);
+}
+
+#[test]
+fn test_class_weights_favor_rare_class() {
+    use neural_network::training::{SampleWeights, TrainingConfig, TrainingController};
+
+    // AND has one positive sample and three negatives
+    let inputs = vec![vec![0.0, 0.0], vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]];
+    let targets = vec![vec![0.0], vec![0.0], vec![0.0], vec![1.0]];
+    let positive_output = |sample_weights| {
+        let config = TrainingConfig {
+            epochs: 30,
+            sample_weights,
+            ..Default::default()
+        };
+        let mut controller = TrainingController::new(Network::new_seeded(vec![2, 2, 1], SIGMOID, 0.5, 2), config);
+        controller.train(inputs.clone(), targets.clone()).unwrap();
+        controller.into_network().feed_forward(vec![1.0, 1.0].into()).data[0]
+    };
+
+    let plain = positive_output(SampleWeights::None);
+    let weighted = positive_output(SampleWeights::PerClass(vec![1.0, 5.0]));
+    assert!(weighted > plain, "Upweighting the positive class raises its output: {} vs {}", weighted, plain);
+}
+
+#[test]
+fn test_sample_weights_follow_validation_split() {
+    use neural_network::training::{SampleWeights, TrainingConfig, TrainingController, Validation};
+
+    let inputs: Vec<Vec<f64>> = (0..8).map(|i| vec![i as f64 / 8.0]).collect();
+    let targets = inputs.clone();
+    let config = TrainingConfig {
+        epochs: 2,
+        validation: Validation::Split(0.25),
+        sample_weights: SampleWeights::PerSample(vec![1.0; 8]),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![1, 2, 1], SIGMOID, 0.5), config);
+    let history = controller.train(inputs, targets).unwrap();
+    assert!(history.last().unwrap().validation_loss.is_some());
+}
+
+#[test]
+fn test_invalid_sample_weights_are_rejected() {
+    use neural_network::training::{SampleWeights, TrainingConfig, TrainingController};
+
+    let train = |sample_weights| {
+        let config = TrainingConfig {
+            epochs: 1,
+            sample_weights,
+            ..Default::default()
+        };
+        let mut controller = TrainingController::new(Network::new(vec![2, 2, 1], SIGMOID, 0.5), config);
+        controller
+            .train(vec![vec![0.0, 1.0], vec![1.0, 1.0]], vec![vec![0.0], vec![1.0]])
+            .unwrap_err()
+            .to_string()
+    };
+
+    assert!(train(SampleWeights::PerSample(vec![1.0])).contains("1 sample weights for 2 samples"));
+    assert!(train(SampleWeights::PerClass(vec![1.0])).contains("No class weight for class 1"));
+    assert!(train(SampleWeights::PerSample(vec![1.0, -1.0])).contains("non-negative"));
+}
+
+#[test]
+fn test_class_of_targets() {
+    use neural_network::training::class_of;
+
+    assert_eq!(class_of(&[0.2]), 0);
+    assert_eq!(class_of(&[0.5]), 1);
+    assert_eq!(class_of(&[0.0, 0.0, 1.0]), 2);
+    assert_eq!(class_of(&[0.7, 0.3]), 0);
+}
+
+#[test]
+fn test_gradient_noise_is_seeded_and_annealed() {
+    use neural_network::training::{GradientNoise, TrainingConfig, TrainingController};
+
+    let inputs = vec![vec![0.0, 0.0], vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]];
+    let targets = vec![vec![0.0], vec![1.0], vec![1.0], vec![0.0]];
+    let train = |gradient_noise, seed| {
+        let config = TrainingConfig {
+            epochs: 20,
+            seed: Some(seed),
+            gradient_noise,
+            ..Default::default()
+        };
+        let mut controller = TrainingController::new(Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 1), config);
+        controller.train(inputs.clone(), targets.clone()).unwrap();
+        controller.into_network().weights
+    };
+
+    let plain = train(None, 1);
+    let noisy = train(Some(GradientNoise::new(0.1)), 1);
+    assert_ne!(noisy, plain, "Noise changes the updates");
+    assert_eq!(train(Some(GradientNoise::new(0.1)), 1), noisy, "Same seed, same noise");
+    assert_ne!(train(Some(GradientNoise::new(0.1)), 2), noisy, "Other seed, other noise");
+    assert_eq!(train(Some(GradientNoise::new(0.0)), 1), plain, "Zero variance adds nothing");
+
+    let noise = GradientNoise { eta: 1.0, gamma: 1.0 };
+    assert_eq!(noise.std_dev(0), 1.0);
+    assert_eq!(noise.std_dev(3), 0.5);
+}
+
+#[test]
+fn test_negative_gradient_noise_is_rejected() {
+    use neural_network::training::{GradientNoise, TrainingConfig, TrainingController};
+
+    let config = TrainingConfig {
+        epochs: 1,
+        gradient_noise: Some(GradientNoise::new(-1.0)),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![2, 2, 1], SIGMOID, 0.5), config);
+    let err = controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap_err();
+    assert!(err.to_string().contains("Gradient noise"));
+}
+
+#[test]
+fn test_input_noise_is_seeded_and_loss_uses_clean_inputs() {
+    use neural_network::training::{TrainingConfig, TrainingController};
+
+    let inputs = vec![vec![0.0, 0.0], vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]];
+    let targets = vec![vec![0.0], vec![1.0], vec![1.0], vec![0.0]];
+    let train = |input_noise, seed| {
+        let config = TrainingConfig {
+            epochs: 20,
+            seed: Some(seed),
+            input_noise,
+            ..Default::default()
+        };
+        let mut controller = TrainingController::new(Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 1), config);
+        let history = controller.train(inputs.clone(), targets.clone()).unwrap();
+        (controller.into_network(), history.last().unwrap().loss)
+    };
+
+    let (plain, _) = train(None, 1);
+    let (noisy, noisy_loss) = train(Some(0.2), 1);
+    assert_ne!(noisy.weights, plain.weights, "Noise changes the updates");
+    assert_eq!(train(Some(0.2), 1).0.weights, noisy.weights, "Same seed, same noise");
+    assert_ne!(train(Some(0.2), 2).0.weights, noisy.weights, "Other seed, other noise");
+    assert_eq!(train(Some(0.0), 1).0.weights, plain.weights, "Zero noise adds nothing");
+
+    // The recorded loss is that of the clean samples
+    let mut workspace = noisy.workspace();
+    let clean_loss: f64 = inputs
+        .iter()
+        .zip(&targets)
+        .map(|(input, target)| {
+            noisy.feed_forward_with(input, &mut workspace);
+            workspace.squared_error(target)
+        })
+        .sum::<f64>()
+        / inputs.len() as f64;
+    assert!((noisy_loss - clean_loss).abs() < 1e-12, "{} != {}", noisy_loss, clean_loss);
+
+    let config = TrainingConfig {
+        epochs: 1,
+        input_noise: Some(f64::NAN),
+        ..Default::default()
+    };
+    let mut controller = TrainingController::new(Network::new(vec![2, 2, 1], SIGMOID, 0.5), config);
+    let err = controller.train(inputs.clone(), targets.clone()).unwrap_err();
+    assert!(err.to_string().contains("Input noise"));
+}
+
+#[test]
+fn test_training_controller_checkpoint_records_training_details() {
+    let temp_dir = TempDir::new().unwrap();
+    let first = temp_dir.path().join("first.json");
+    let second = temp_dir.path().join("second.json");
+    let (inputs, targets) = (vec![vec![0.0, 1.0], vec![1.0, 0.0]], vec![vec![1.0], vec![0.0]]);
+
+    let network = Network::new(vec![2, 2, 1], SIGMOID, 0.5);
+    let mut controller = neural_network::training::TrainingController::new(
+        network,
+        neural_network::training::TrainingConfig {
+            epochs: 10,
+            seed: Some(3),
+            optimizer: neural_network::optimizer::Optimizer::adam(),
+            ..Default::default()
+        },
+    );
+    controller.train(inputs.clone(), targets.clone()).unwrap();
+    controller.save_checkpoint(&first).unwrap();
+
+    let checkpoint = neural_network::checkpoint::Checkpoint::load(&first).unwrap();
+    let metadata = &checkpoint.metadata;
+    assert!(metadata.created_at.is_some());
+    assert_eq!(metadata.crate_version.as_deref(), Some(env!("CARGO_PKG_VERSION")));
+    assert_eq!(metadata.training_secs, Some(controller.history().elapsed_secs()));
+    assert_eq!(metadata.final_loss, Some(controller.history().last().unwrap().loss));
+    assert_eq!(metadata.seed, Some(3));
+    assert_eq!(metadata.optimizer.as_deref(), Some("adam"));
+    let fingerprint = neural_network::dataset::fingerprint(&inputs, &targets);
+    assert_eq!(metadata.dataset_fingerprint.as_deref(), Some(fingerprint.as_str()));
+
+    // A resumed run keeps the creation time and adds up the training time
+    let mut resumed = neural_network::training::TrainingController::from_checkpoint(
+        &first,
+        neural_network::training::TrainingConfig {
+            epochs: 5,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    resumed.train(inputs, vec![vec![0.0], vec![1.0]]).unwrap();
+    resumed.save_checkpoint(&second).unwrap();
+
+    let resumed_metadata = neural_network::checkpoint::Checkpoint::load(&second).unwrap().metadata;
+    assert_eq!(resumed_metadata.created_at, metadata.created_at);
+    assert!(resumed_metadata.training_secs.unwrap() >= metadata.training_secs.unwrap());
+    assert_eq!(resumed_metadata.optimizer.as_deref(), Some("sgd"));
+    assert_eq!(resumed_metadata.seed, Some(3), "The resumed run continues the shuffle seed");
+    assert_ne!(resumed_metadata.dataset_fingerprint, metadata.dataset_fingerprint, "Targets changed");
+}
+
+#[test]
+fn test_unseeded_checkpoint_records_drawn_seed() {
+    let mut controller = neural_network::training::TrainingController::new(
+        Network::new(vec![2, 2, 1], SIGMOID, 0.5),
+        neural_network::training::TrainingConfig {
+            epochs: 3,
+            ..Default::default()
+        },
+    );
+    controller.train(vec![vec![0.0, 1.0]], vec![vec![1.0]]).unwrap();
+
+    let seed = controller.training_state().unwrap().seed;
+    assert_eq!(controller.to_checkpoint().metadata.seed, Some(seed));
+}
@@ -0,0 +1,171 @@
+// Integration tests for the preallocated training Workspace
+use neural_network::activations::SIGMOID;
+use neural_network::matrix::Matrix;
+use neural_network::network::Network;
+use neural_network::workspace::{BatchWorkspace, GRADIENT_CHUNK, Workspace};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// Counts allocations made by the current thread so the test can check that a
+/// training step does not allocate
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+#[test]
+fn test_workspace_forward_and_gradients_match_allocating_path() {
+    let mut network = Network::new_seeded(vec![3, 5, 4, 2], SIGMOID, 0.5, 11);
+    let input = [0.2, -0.7, 0.9];
+    let target = [1.0, 0.0];
+
+    let output = network.feed_forward(Matrix::from(input.to_vec()));
+    let gradients = network.compute_gradients(&output, &Matrix::from(target.to_vec()));
+
+    let mut workspace = network.workspace();
+    assert_eq!(network.feed_forward_with(&input, &mut workspace), &output);
+    network.compute_gradients_with(&target, &mut workspace);
+
+    assert_eq!(workspace.gradients().weights, gradients.weights);
+    assert_eq!(workspace.gradients().biases, gradients.biases);
+}
+
+#[test]
+fn test_workspace_training_is_bit_identical() {
+    let inputs = [vec![0.0, 0.0], vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]];
+    let targets = [vec![0.0], vec![1.0], vec![1.0], vec![0.0]];
+
+    let mut reference = Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 42);
+    let mut pooled = reference.clone();
+    let mut workspace = pooled.workspace();
+
+    for _ in 0..50 {
+        for (input, target) in inputs.iter().zip(&targets) {
+            let output = reference.feed_forward(Matrix::from(input.clone()));
+            reference.back_propogate(output, Matrix::from(target.clone()));
+            pooled.train_sample_with(input, target, &mut workspace);
+        }
+    }
+
+    assert_eq!(pooled.weights, reference.weights);
+    assert_eq!(pooled.biases, reference.biases);
+}
+
+#[test]
+fn test_training_step_does_not_allocate() {
+    let mut network = Network::new_seeded(vec![4, 16, 8, 3], SIGMOID, 0.1, 5);
+    let mut workspace = network.workspace();
+    let input = [0.1, 0.2, 0.3, 0.4];
+    let target = [1.0, 0.0, 0.0];
+
+    // Warm up once so any lazy initialization is not counted
+    network.train_sample_with(&input, &target, &mut workspace);
+
+    let before = allocations();
+    for _ in 0..10 {
+        network.train_sample_with(&input, &target, &mut workspace);
+    }
+    assert_eq!(allocations(), before, "Training steps should reuse the workspace buffers");
+}
+
+#[test]
+#[should_panic(expected = "does not match the network architecture")]
+fn test_workspace_for_other_architecture_panics() {
+    let network = Network::new(vec![2, 3, 1], SIGMOID, 0.5);
+    let mut workspace = Workspace::new(&[2, 4, 1]);
+    network.feed_forward_with(&[0.0, 1.0], &mut workspace);
+}
+
+#[test]
+fn test_batch_gradients_are_mean_of_sample_gradients() {
+    let mut network = Network::new_seeded(vec![3, 4, 2], SIGMOID, 0.5, 9);
+    // Enough samples for several chunks, the last one partial
+    let count = 2 * GRADIENT_CHUNK + 5;
+    let inputs: Vec<Vec<f64>> = (0..count).map(|i| vec![(i % 7) as f64 / 7.0, (i % 3) as f64, -0.5]).collect();
+    let targets: Vec<Vec<f64>> = (0..count).map(|i| vec![(i % 2) as f64, 0.5]).collect();
+    let batch: Vec<usize> = (0..count).rev().collect();
+
+    let mut expected = Workspace::new(&network.layers).gradients().clone();
+    for (input, target) in inputs.iter().zip(&targets) {
+        let output = network.feed_forward(Matrix::from(input.clone()));
+        let gradients = network.compute_gradients(&output, &Matrix::from(target.clone()));
+        for (sum, g) in expected.weights.iter_mut().zip(&gradients.weights) {
+            *sum = sum.add(&g.clone().map(|v| v / count as f64));
+        }
+        for (sum, g) in expected.biases.iter_mut().zip(&gradients.biases) {
+            *sum = sum.add(&g.clone().map(|v| v / count as f64));
+        }
+    }
+
+    let mut workspace = BatchWorkspace::new(&network.layers);
+    let averaged = network.compute_batch_gradients_with(&inputs, &targets, &batch, None, &mut workspace);
+
+    let pairs = averaged.weights.iter().zip(&expected.weights).chain(averaged.biases.iter().zip(&expected.biases));
+    for (actual, expected) in pairs {
+        for (a, e) in actual.data.iter().zip(&expected.data) {
+            assert!((a - e).abs() < 1e-12, "{} != {}", a, e);
+        }
+    }
+}
+
+#[test]
+fn test_single_sample_batch_matches_workspace() {
+    let network = Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 4);
+    let inputs = vec![vec![0.3, 0.8], vec![1.0, 0.0]];
+    let targets = vec![vec![1.0], vec![0.0]];
+
+    let mut workspace = network.workspace();
+    network.feed_forward_with(&inputs[1], &mut workspace);
+    network.compute_gradients_with(&targets[1], &mut workspace);
+
+    let mut batch_workspace = BatchWorkspace::new(&network.layers);
+    let gradients = network.compute_batch_gradients_with(&inputs, &targets, &[1], None, &mut batch_workspace);
+
+    assert_eq!(gradients.weights, workspace.gradients().weights);
+    assert_eq!(gradients.biases, workspace.gradients().biases);
+}
+
+#[test]
+fn test_weighted_batch_gradients() {
+    let network = Network::new_seeded(vec![2, 3, 1], SIGMOID, 0.5, 4);
+    let inputs = vec![vec![0.3, 0.8], vec![1.0, 0.0]];
+    let targets = vec![vec![1.0], vec![0.0]];
+    let mut workspace = BatchWorkspace::new(&network.layers);
+
+    let plain = network
+        .compute_batch_gradients_with(&inputs, &targets, &[0, 1], None, &mut workspace)
+        .clone();
+    let doubled = network
+        .compute_batch_gradients_with(&inputs, &targets, &[0, 1], Some(&[2.0, 2.0]), &mut workspace)
+        .clone();
+    let pairs = doubled.weights.iter().chain(&doubled.biases).zip(plain.weights.iter().chain(&plain.biases));
+    for (d, p) in pairs {
+        assert_eq!(d, &p.clone().map(|v| 2.0 * v));
+    }
+
+    // A zero weight drops the sample from the sum but not from the count
+    let first = network
+        .compute_batch_gradients_with(&inputs, &targets, &[0], None, &mut workspace)
+        .clone();
+    let only_first = network.compute_batch_gradients_with(&inputs, &targets, &[0, 1], Some(&[2.0, 0.0]), &mut workspace);
+    assert_eq!(only_first.weights, first.weights);
+}
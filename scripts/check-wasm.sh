@@ -0,0 +1,20 @@
+#!/bin/bash
+# Build the WASM bindings for the browser target and run their tests under Node
+# Catches std APIs that compile for wasm32-unknown-unknown but panic at runtime
+#
+# Requires: rustup target add wasm32-unknown-unknown
+#           cargo install wasm-bindgen-cli (same version as the wasm-bindgen dependency)
+
+set -e
+
+SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+PROJECT_ROOT="$(cd "$SCRIPT_DIR/.." && pwd)"
+
+cd "$PROJECT_ROOT"
+
+echo "Building neural-net-wasm for wasm32-unknown-unknown..."
+cargo build -p neural-net-wasm --target wasm32-unknown-unknown
+
+echo "Running neural-net-wasm tests under Node..."
+CARGO_TARGET_WASM32_UNKNOWN_UNKNOWN_RUNNER=wasm-bindgen-test-runner \
+    cargo test -p neural-net-wasm --target wasm32-unknown-unknown